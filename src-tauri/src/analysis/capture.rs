@@ -0,0 +1,96 @@
+use serde::Serialize;
+
+use crate::i18n::t;
+use crate::types::{ChangeCapture, SchemaGraph};
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedTable {
+    pub table_id: String,
+    pub capture: ChangeCapture,
+    /// What the capture means for schema changes, one line per mechanism.
+    pub constraints: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeCaptureReport {
+    /// In graph order.
+    pub tables: Vec<CapturedTable>,
+    pub cdc_count: usize,
+    pub replicated_count: usize,
+    pub merge_published_count: usize,
+}
+
+/// Tables tracked by CDC or published for replication, for reviewing a
+/// schema change before it meets them.
+pub fn change_capture_report(graph: &SchemaGraph) -> ChangeCaptureReport {
+    let mut report = ChangeCaptureReport::default();
+    for table in &graph.tables {
+        let Some(capture) = table.change_capture else {
+            continue;
+        };
+        let mut constraints = Vec::new();
+        if capture.cdc {
+            report.cdc_count += 1;
+            constraints.push(t!("capture-cdc", table = table.id));
+        }
+        if capture.replicated {
+            report.replicated_count += 1;
+            constraints.push(t!("capture-replicated", table = table.id));
+        }
+        if capture.merge_published {
+            report.merge_published_count += 1;
+            constraints.push(t!("capture-merge-published", table = table.id));
+        }
+        report.tables.push(CapturedTable {
+            table_id: table.id.clone(),
+            capture,
+            constraints,
+        });
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TableNode;
+
+    fn table(name: &str, capture: Option<ChangeCapture>) -> TableNode {
+        TableNode {
+            id: format!("dbo.{}", name),
+            name: name.to_string(),
+            schema: "dbo".into(),
+            columns: Vec::new(),
+            source: None,
+            metadata: None,
+            change_capture: capture,
+        }
+    }
+
+    #[test]
+    fn only_captured_tables_are_reported() {
+        let graph = SchemaGraph {
+            tables: vec![
+                table("Orders", ChangeCapture::from_flags(true, true, false)),
+                table("Lookup", ChangeCapture::from_flags(false, false, false)),
+                table("Customers", ChangeCapture::from_flags(false, false, true)),
+            ],
+            ..Default::default()
+        };
+
+        let report = change_capture_report(&graph);
+        let ids: Vec<&str> = report.tables.iter().map(|t| t.table_id.as_str()).collect();
+        assert_eq!(ids, vec!["dbo.Orders", "dbo.Customers"]);
+        assert_eq!(report.tables[0].constraints.len(), 2);
+        assert_eq!(
+            (
+                report.cdc_count,
+                report.replicated_count,
+                report.merge_published_count
+            ),
+            (1, 1, 1)
+        );
+    }
+}
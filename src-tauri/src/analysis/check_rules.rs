@@ -0,0 +1,612 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::db::CheckConstraint;
+
+const IDENTIFIER: &str = r#"\[(?:[^\]]|\]\])+\]|[A-Za-z_@#][\w@#$]*"#;
+
+static IDENTIFIER_ONLY: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!("^(?:{})$", IDENTIFIER)).expect("identifier pattern"));
+static IN_LIST: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r#"(?is)^({})\s+IN\s*\((.*)\)$"#, IDENTIFIER)).expect("IN pattern")
+});
+static LIKE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r#"(?is)^({})\s+(NOT\s+)?LIKE\s+(N?'(?:[^']|'')*')$"#,
+        IDENTIFIER
+    ))
+    .expect("LIKE pattern")
+});
+static BETWEEN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r#"(?is)^({})\s+BETWEEN\s+(.+?)\s+AND\s+(.+)$"#,
+        IDENTIFIER
+    ))
+    .expect("BETWEEN pattern")
+});
+static LITERAL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?s)^N?'((?:[^']|'')*)'$|^-?[\d.]+$"#).expect("literal pattern"));
+
+/// One end of a range.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Bound {
+    pub value: String,
+    pub inclusive: bool,
+}
+
+/// A check constraint read as a rule about one column, or left as
+/// written when it's more than a list, range, pattern or comparison.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CheckRule {
+    /// `IN (...)`, or the chain of `=` comparisons SQL Server stores it as.
+    OneOf {
+        column: String,
+        values: Vec<String>,
+    },
+    /// Comparisons with constants; `BETWEEN` is stored as two of them.
+    Range {
+        column: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min: Option<Bound>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max: Option<Bound>,
+    },
+    Like {
+        column: String,
+        pattern: String,
+        negated: bool,
+    },
+    NotEqual {
+        column: String,
+        value: String,
+    },
+    /// Two columns of the row compared, e.g. an end date after a start date.
+    ColumnComparison {
+        left: String,
+        operator: String,
+        right: String,
+    },
+    Expression {
+        text: String,
+    },
+}
+
+impl CheckRule {
+    /// The rule for people: `Status ∈ {New, Paid, Shipped}`, `0 ≤ Qty ≤ 100`.
+    pub fn describe(&self) -> String {
+        match self {
+            CheckRule::OneOf { column, values } => {
+                format!("{} ∈ {{{}}}", column, values.join(", "))
+            }
+            CheckRule::Range { column, min, max } => {
+                let below = |b: &Bound| if b.inclusive { "≤" } else { "<" };
+                match (min, max) {
+                    (Some(min), Some(max)) => format!(
+                        "{} {} {} {} {}",
+                        min.value,
+                        below(min),
+                        column,
+                        below(max),
+                        max.value
+                    ),
+                    (Some(min), None) => {
+                        let above = if min.inclusive { "≥" } else { ">" };
+                        format!("{} {} {}", column, above, min.value)
+                    }
+                    (None, Some(max)) => format!("{} {} {}", column, below(max), max.value),
+                    (None, None) => column.clone(),
+                }
+            }
+            CheckRule::Like {
+                column,
+                pattern,
+                negated,
+            } => format!(
+                "{} {}LIKE '{}'",
+                column,
+                if *negated { "NOT " } else { "" },
+                pattern.replace('\'', "''")
+            ),
+            CheckRule::NotEqual { column, value } => format!("{} ≠ {}", column, value),
+            CheckRule::ColumnComparison {
+                left,
+                operator,
+                right,
+            } => format!("{} {} {}", left, operator, right),
+            CheckRule::Expression { text } => text.clone(),
+        }
+    }
+}
+
+/// A table's check constraint with its parsed rule.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckConstraintRule {
+    pub table_id: String,
+    pub name: String,
+    pub definition: String,
+    pub rule: CheckRule,
+    pub description: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Operator {
+    fn parse(text: &str) -> Option<Operator> {
+        Some(match text {
+            "=" => Operator::Eq,
+            "<>" | "!=" => Operator::Ne,
+            "<" => Operator::Lt,
+            "<=" => Operator::Le,
+            ">" => Operator::Gt,
+            ">=" => Operator::Ge,
+            _ => return None,
+        })
+    }
+
+    /// The same comparison with its operands swapped.
+    fn flipped(self) -> Operator {
+        match self {
+            Operator::Lt => Operator::Gt,
+            Operator::Le => Operator::Ge,
+            Operator::Gt => Operator::Lt,
+            Operator::Ge => Operator::Le,
+            other => other,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Operator::Eq => "=",
+            Operator::Ne => "≠",
+            Operator::Lt => "<",
+            Operator::Le => "≤",
+            Operator::Gt => ">",
+            Operator::Ge => "≥",
+        }
+    }
+}
+
+/// One comparison, list or pattern of a check.
+enum Term {
+    Compare {
+        column: String,
+        operator: Operator,
+        value: String,
+    },
+    Columns {
+        left: String,
+        operator: Operator,
+        right: String,
+    },
+    OneOf {
+        column: String,
+        values: Vec<String>,
+    },
+    Like {
+        column: String,
+        pattern: String,
+        negated: bool,
+    },
+    Between {
+        column: String,
+        low: String,
+        high: String,
+    },
+}
+
+/// `definition` without the parentheses wrapping all of it.
+pub(crate) fn strip_parens(mut definition: &str) -> &str {
+    loop {
+        definition = definition.trim();
+        let Some(inner) = definition
+            .strip_prefix('(')
+            .and_then(|d| d.strip_suffix(')'))
+        else {
+            return definition;
+        };
+        // `(a) OR (b)` starts and ends with parentheses that don't pair up
+        if split_top_level(inner, "").is_none() {
+            return definition;
+        }
+        definition = inner;
+    }
+}
+
+/// Split at top-level occurrences of the keyword `separator`, outside quotes
+/// and parentheses; `None` when the parentheses don't balance. An empty
+/// separator only checks the balance.
+fn split_top_level<'a>(text: &'a str, separator: &str) -> Option<Vec<&'a str>> {
+    let bytes = text.as_bytes();
+    let mut parts = Vec::new();
+    let (mut depth, mut in_string, mut start, mut i) = (0i32, false, 0, 0);
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\'' => in_string = !in_string,
+            b'(' if !in_string => depth += 1,
+            b')' if !in_string => {
+                depth -= 1;
+                if depth < 0 {
+                    return None;
+                }
+            }
+            _ if !in_string && depth == 0 && !separator.is_empty() => {
+                let end = i + separator.len();
+                let bounded = |at: usize| bytes.get(at).is_none_or(|b| b.is_ascii_whitespace());
+                if end <= bytes.len()
+                    && bytes[i..end].eq_ignore_ascii_case(separator.as_bytes())
+                    && i > 0
+                    && bounded(i - 1)
+                    && bounded(end)
+                {
+                    parts.push(&text[start..i]);
+                    start = end;
+                    i = end;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    (depth == 0 && !in_string).then(|| {
+        parts.push(&text[start..]);
+        parts
+    })
+}
+
+/// Items of a comma-separated list, splitting outside quotes and
+/// parentheses only.
+fn split_list(text: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let (mut depth, mut in_string, mut start) = (0, false, 0);
+    for (i, c) in text.char_indices() {
+        match c {
+            '\'' => in_string = !in_string,
+            '(' if !in_string => depth += 1,
+            ')' if !in_string => depth -= 1,
+            ',' if !in_string && depth == 0 => {
+                items.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&text[start..]);
+    items
+}
+
+/// Left operand, operator and right operand of a comparison, split at the
+/// first operator outside quotes, brackets and parentheses.
+fn split_comparison(text: &str) -> Option<(&str, Operator, &str)> {
+    let bytes = text.as_bytes();
+    let (mut depth, mut in_string, mut in_bracket) = (0, false, false);
+    for (i, &b) in bytes.iter().enumerate() {
+        match b {
+            b'\'' if !in_bracket => in_string = !in_string,
+            b'[' if !in_string => in_bracket = true,
+            b']' if !in_string => in_bracket = false,
+            b'(' if !in_string && !in_bracket => depth += 1,
+            b')' if !in_string && !in_bracket => depth -= 1,
+            b'<' | b'>' | b'=' | b'!' if !in_string && !in_bracket && depth == 0 => {
+                let width = match bytes.get(i + 1) {
+                    Some(b'=') => 2,
+                    Some(b'>') if b == b'<' => 2,
+                    _ => 1,
+                };
+                let operator = Operator::parse(&text[i..i + width])?;
+                return Some((&text[..i], operator, &text[i + width..]));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn unquote_identifier(name: &str) -> String {
+    match name.strip_prefix('[').and_then(|n| n.strip_suffix(']')) {
+        Some(inner) => inner.replace("]]", "]"),
+        None => name.to_string(),
+    }
+}
+
+fn column_name(text: &str) -> Option<String> {
+    let text = strip_parens(text);
+    IDENTIFIER_ONLY
+        .is_match(text)
+        .then(|| unquote_identifier(text))
+}
+
+/// The value of a T-SQL string or number literal, ignoring wrapping
+/// parentheses: `N'it''s'` is `it's`, `((0))` is `0`.
+pub fn literal_value(text: &str) -> Option<String> {
+    let text = strip_parens(text);
+    let captures = LITERAL.captures(text)?;
+    Some(match captures.get(1) {
+        Some(string) => string.as_str().replace("''", "'"),
+        None => text.to_string(),
+    })
+}
+
+fn parse_term(text: &str) -> Option<Term> {
+    let text = strip_parens(text);
+    if let Some(c) = IN_LIST.captures(text) {
+        let values = split_list(&c[2])
+            .into_iter()
+            .map(literal_value)
+            .collect::<Option<Vec<_>>>()?;
+        return Some(Term::OneOf {
+            column: unquote_identifier(&c[1]),
+            values,
+        });
+    }
+    if let Some(c) = LIKE.captures(text) {
+        return Some(Term::Like {
+            column: unquote_identifier(&c[1]),
+            pattern: literal_value(&c[3])?,
+            negated: c.get(2).is_some(),
+        });
+    }
+    if let Some(c) = BETWEEN.captures(text) {
+        return Some(Term::Between {
+            column: unquote_identifier(&c[1]),
+            low: literal_value(&c[2])?,
+            high: literal_value(&c[3])?,
+        });
+    }
+    let (left, operator, right) = split_comparison(text)?;
+    match (column_name(left), column_name(right)) {
+        (Some(left), Some(right)) => Some(Term::Columns {
+            left,
+            operator,
+            right,
+        }),
+        (Some(column), None) => Some(Term::Compare {
+            column,
+            operator,
+            value: literal_value(right)?,
+        }),
+        (None, Some(column)) => Some(Term::Compare {
+            column,
+            operator: operator.flipped(),
+            value: literal_value(left)?,
+        }),
+        (None, None) => None,
+    }
+}
+
+/// `IN` lists and `=` chains over one column.
+fn parse_one_of(disjuncts: &[&str]) -> Option<CheckRule> {
+    let mut column: Option<String> = None;
+    let mut values: Vec<String> = Vec::new();
+    for disjunct in disjuncts {
+        let (name, found) = match parse_term(disjunct)? {
+            Term::Compare {
+                column,
+                operator: Operator::Eq,
+                value,
+            } => (column, vec![value]),
+            Term::OneOf { column, values } => (column, values),
+            _ => return None,
+        };
+        match &column {
+            Some(existing) if !existing.eq_ignore_ascii_case(&name) => return None,
+            Some(_) => {}
+            None => column = Some(name),
+        }
+        for value in found {
+            if !values.contains(&value) {
+                values.push(value);
+            }
+        }
+    }
+    Some(CheckRule::OneOf {
+        column: column?,
+        values,
+    })
+}
+
+/// Bounds on one column from every conjunct, each end set once at most.
+fn parse_range(terms: Vec<Term>) -> Option<CheckRule> {
+    let mut column: Option<String> = None;
+    let (mut min, mut max): (Option<Bound>, Option<Bound>) = (None, None);
+    let set = |slot: &mut Option<Bound>, value: String, inclusive| {
+        let fresh = slot.is_none();
+        *slot = Some(Bound { value, inclusive });
+        fresh
+    };
+    for term in terms {
+        let (name, fresh) = match term {
+            Term::Compare {
+                column,
+                operator,
+                value,
+            } => {
+                let fresh = match operator {
+                    Operator::Gt => set(&mut min, value, false),
+                    Operator::Ge => set(&mut min, value, true),
+                    Operator::Lt => set(&mut max, value, false),
+                    Operator::Le => set(&mut max, value, true),
+                    Operator::Eq | Operator::Ne => return None,
+                };
+                (column, fresh)
+            }
+            Term::Between { column, low, high } => {
+                let fresh = set(&mut min, low, true) & set(&mut max, high, true);
+                (column, fresh)
+            }
+            _ => return None,
+        };
+        if !fresh {
+            return None;
+        }
+        match &column {
+            Some(existing) if !existing.eq_ignore_ascii_case(&name) => return None,
+            Some(_) => {}
+            None => column = Some(name),
+        }
+    }
+    Some(CheckRule::Range {
+        column: column?,
+        min,
+        max,
+    })
+}
+
+/// Read a check constraint's definition as a rule; anything that isn't a
+/// list, range, `LIKE` pattern or simple comparison comes back as an
+/// [`CheckRule::Expression`].
+pub fn parse_check(definition: &str) -> CheckRule {
+    let text = strip_parens(definition);
+    parse_structured(text).unwrap_or_else(|| CheckRule::Expression {
+        text: text.to_string(),
+    })
+}
+
+fn parse_structured(text: &str) -> Option<CheckRule> {
+    let disjuncts = split_top_level(text, "OR")?;
+    if disjuncts.len() > 1 {
+        return parse_one_of(&disjuncts);
+    }
+    // BETWEEN's own AND would otherwise split it
+    if let Some(term @ Term::Between { .. }) = parse_term(text) {
+        return parse_range(vec![term]);
+    }
+    let conjuncts = split_top_level(text, "AND")?;
+    if conjuncts.len() > 1 {
+        let terms = conjuncts
+            .into_iter()
+            .map(parse_term)
+            .collect::<Option<Vec<_>>>()?;
+        return parse_range(terms);
+    }
+    Some(match parse_term(text)? {
+        Term::Compare {
+            column,
+            operator: Operator::Ne,
+            value,
+        } => CheckRule::NotEqual { column, value },
+        Term::Compare {
+            column,
+            operator: Operator::Eq,
+            value,
+        } => CheckRule::OneOf {
+            column,
+            values: vec![value],
+        },
+        Term::OneOf { column, values } => CheckRule::OneOf { column, values },
+        term @ (Term::Compare { .. } | Term::Between { .. }) => parse_range(vec![term])?,
+        Term::Columns {
+            left,
+            operator,
+            right,
+        } => CheckRule::ColumnComparison {
+            left,
+            operator: operator.symbol().to_string(),
+            right,
+        },
+        Term::Like {
+            column,
+            pattern,
+            negated,
+        } => CheckRule::Like {
+            column,
+            pattern,
+            negated,
+        },
+    })
+}
+
+/// Every check constraint with its rule, in catalog order.
+pub fn check_rules(checks: &[CheckConstraint]) -> Vec<CheckConstraintRule> {
+    checks
+        .iter()
+        .map(|check| {
+            let rule = parse_check(&check.definition);
+            CheckConstraintRule {
+                table_id: check.table_id.clone(),
+                name: check.name.clone(),
+                definition: check.definition.clone(),
+                description: rule.describe(),
+                rule,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn described(definition: &str) -> String {
+        parse_check(definition).describe()
+    }
+
+    #[test]
+    fn lists_ranges_and_patterns_read_as_rules() {
+        assert_eq!(
+            parse_check("([Status]='Paid' OR [Status]='New' OR [Status]=N'On hold')"),
+            CheckRule::OneOf {
+                column: "Status".to_string(),
+                values: vec!["Paid".to_string(), "New".to_string(), "On hold".to_string()],
+            }
+        );
+        assert_eq!(
+            described("([priority] IN ((1), (2), (3)))"),
+            "priority ∈ {1, 2, 3}"
+        );
+        assert_eq!(
+            described("([Note]='it''s' OR [Note]='a, b')"),
+            "Note ∈ {it's, a, b}"
+        );
+
+        assert_eq!(described("([Qty]>=(0) AND [Qty]<=(100))"), "0 ≤ Qty ≤ 100");
+        assert_eq!(described("([Qty]>(0))"), "Qty > 0");
+        assert_eq!(described("((0)<[Price])"), "Price > 0");
+        assert_eq!(
+            described("([Discount] BETWEEN (0) AND (0.5))"),
+            "0 ≤ Discount ≤ 0.5"
+        );
+        assert_eq!(described("([Code] like 'A[0-9]%')"), "Code LIKE 'A[0-9]%'");
+        assert_eq!(
+            described("([Email] NOT LIKE N'%''%')"),
+            "Email NOT LIKE '%''%'"
+        );
+        assert_eq!(described("([Kind]<>'x')"), "Kind ≠ x");
+        assert_eq!(described("([EndDate]>=[StartDate])"), "EndDate ≥ StartDate");
+    }
+
+    #[test]
+    fn other_expressions_are_kept_as_written() {
+        // Two columns, a repeated bound, and a function call
+        for definition in [
+            "([A]='x' OR [B]='y')",
+            "([Qty]>(0) AND [Qty]>(1))",
+            "(len([Code])=(3))",
+            "([Qty]>(0) AND [Price]>(0))",
+        ] {
+            assert_eq!(
+                parse_check(definition),
+                CheckRule::Expression {
+                    text: strip_parens(definition).to_string()
+                },
+                "{}",
+                definition
+            );
+        }
+        assert_eq!(literal_value("((0))").as_deref(), Some("0"));
+        assert_eq!(literal_value("(N'Open')").as_deref(), Some("Open"));
+        assert_eq!(literal_value("(getdate())"), None);
+    }
+}
@@ -0,0 +1,214 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+use crate::analysis::dynamic_sql::{scanned, DynamicSqlObjectKind};
+use crate::types::{DefinitionAnalysis, SchemaGraph};
+
+/// Objects listed when the caller doesn't ask for a number.
+pub const DEFAULT_TOP_COMPLEX: usize = 20;
+
+/// Score weights: a branch costs more to follow than a straight statement,
+/// and dynamic SQL hides what the object does from every other analysis.
+const BRANCH_WEIGHT: u32 = 3;
+const TABLE_WEIGHT: u32 = 2;
+const LINES_PER_POINT: u32 = 20;
+const DYNAMIC_SQL_WEIGHT: u32 = 10;
+
+/// Comments and string literals, matched together so a quote inside a
+/// comment or `--` inside a string doesn't throw the other off.
+static NOISE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)--[^\n]*|/\*.*?\*/|N?'(?:[^']|'')*'").unwrap());
+
+/// `CREATE ... AS`, the header before the body.
+static HEADER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)^\s*(?:CREATE|ALTER)\b.*?\bAS\b").unwrap());
+
+static KEYWORD: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(INSERT|UPDATE|DELETE|MERGE|SELECT|EXEC|EXECUTE|SET|DECLARE|RETURN|THROW|RAISERROR|PRINT|TRUNCATE|IF|WHILE|WHEN|CATCH)\b",
+    )
+    .unwrap()
+});
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectComplexity {
+    pub object_id: String,
+    pub kind: DynamicSqlObjectKind,
+    /// Lines holding code, leaving out blank and comment-only ones.
+    pub lines: u32,
+    /// Statement keywords in the body; an `INSERT ... SELECT` counts twice.
+    pub statements: u32,
+    /// One plus each `IF`, `WHILE`, `CASE`/`MERGE` `WHEN` and `CATCH`.
+    pub branches: u32,
+    /// Tables read or written.
+    pub tables_touched: u32,
+    pub dynamic_sql: bool,
+    pub score: u32,
+}
+
+/// The body with comments removed and string literals emptied.
+fn code_only(definition: &str) -> String {
+    NOISE
+        .replace_all(
+            definition,
+            |c: &regex::Captures| {
+                if c[0].ends_with('\'') {
+                    "''"
+                } else {
+                    ""
+                }
+            },
+        )
+        .into_owned()
+}
+
+fn measure(
+    object_id: &str,
+    kind: DynamicSqlObjectKind,
+    definition: &str,
+    tables: BTreeSet<&str>,
+    analysis: &DefinitionAnalysis,
+) -> ObjectComplexity {
+    let code = code_only(definition);
+    let lines = code.lines().filter(|l| !l.trim().is_empty()).count() as u32;
+    let body_start = HEADER.find(&code).map_or(0, |m| m.end());
+    let body = &code[body_start..];
+
+    let (mut statements, mut branches) = (0, 1);
+    for m in KEYWORD.find_iter(body) {
+        let rest = body[m.end()..].trim_start();
+        match m.as_str().to_uppercase().as_str() {
+            "IF" | "WHILE" => {
+                statements += 1;
+                branches += 1;
+            }
+            "WHEN" | "CATCH" => branches += 1,
+            // `UPDATE(col)` in a trigger tests a column
+            "UPDATE" if rest.starts_with('(') => {}
+            // `UPDATE ... SET col =` is part of the update
+            "SET" if !rest.starts_with('@') => {}
+            _ => statements += 1,
+        }
+    }
+
+    let dynamic_sql = scanned(definition, analysis).dynamic_sql;
+    let tables_touched = tables.len() as u32;
+    let score = statements
+        + (branches - 1) * BRANCH_WEIGHT
+        + tables_touched * TABLE_WEIGHT
+        + lines / LINES_PER_POINT
+        + if dynamic_sql { DYNAMIC_SQL_WEIGHT } else { 0 };
+    ObjectComplexity {
+        object_id: object_id.to_string(),
+        kind,
+        lines,
+        statements,
+        branches,
+        tables_touched,
+        dynamic_sql,
+        score,
+    }
+}
+
+fn touched<'a>(referenced: &'a [String], affected: &'a [String]) -> BTreeSet<&'a str> {
+    referenced
+        .iter()
+        .chain(affected)
+        .map(String::as_str)
+        .collect()
+}
+
+/// The `top` most complex procedures and triggers, highest score first, as
+/// candidates for refactoring.
+pub fn complexity_report(graph: &SchemaGraph, top: usize) -> Vec<ObjectComplexity> {
+    let procedures = graph.stored_procedures.iter().map(|p| {
+        measure(
+            &p.id,
+            DynamicSqlObjectKind::StoredProcedure,
+            &p.definition,
+            touched(&p.referenced_tables, &p.affected_tables),
+            &p.analysis,
+        )
+    });
+    let triggers = graph.triggers.iter().map(|t| {
+        measure(
+            &t.id,
+            DynamicSqlObjectKind::Trigger,
+            &t.definition,
+            touched(&t.referenced_tables, &t.affected_tables),
+            &t.analysis,
+        )
+    });
+
+    let mut objects: Vec<ObjectComplexity> = procedures.chain(triggers).collect();
+    objects.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.object_id.cmp(&b.object_id))
+    });
+    objects.truncate(top);
+    objects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::StoredProcedure;
+
+    fn procedure(name: &str, definition: &str, tables: &[&str]) -> StoredProcedure {
+        StoredProcedure {
+            id: format!("dbo.{}", name),
+            name: name.to_string(),
+            schema: "dbo".into(),
+            procedure_type: "SQL_STORED_PROCEDURE".into(),
+            parameters: Vec::new(),
+            definition: definition.to_string(),
+            referenced_tables: tables.iter().map(|t| t.to_string()).collect(),
+            affected_tables: Vec::new(),
+            analysis: Default::default(),
+            source: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn branching_procedures_rank_first() {
+        let branching = "CREATE PROCEDURE dbo.Ship @Id int AS
+BEGIN
+    -- IF this were counted the branches would be off
+    SET NOCOUNT ON;
+    DECLARE @Status int;
+    SELECT @Status = Status FROM dbo.Orders WHERE Id = @Id;
+    IF @Status = 1
+        UPDATE dbo.Orders SET Status = CASE WHEN Paid = 1 THEN 2 ELSE 3 END WHERE Id = @Id;
+    ELSE
+        PRINT 'DELETE nothing';
+
+    WHILE @Status < 3 SET @Status += 1;
+END";
+        let graph = SchemaGraph {
+            stored_procedures: vec![
+                procedure("Simple", "CREATE PROCEDURE dbo.Simple AS SELECT 1", &[]),
+                procedure("Ship", branching, &["dbo.Orders"]),
+                procedure("Run", "EXEC ('SELECT 1')", &[]),
+            ],
+            ..Default::default()
+        };
+
+        let report = complexity_report(&graph, 2);
+        let ids: Vec<&str> = report.iter().map(|o| o.object_id.as_str()).collect();
+        assert_eq!(ids, vec!["dbo.Ship", "dbo.Run"]);
+
+        let ship = &report[0];
+        assert_eq!(ship.lines, 11);
+        // DECLARE, SELECT, IF, UPDATE, PRINT, WHILE, SET @Status
+        assert_eq!(ship.statements, 7);
+        assert_eq!(ship.branches, 4);
+        assert_eq!(ship.tables_touched, 1);
+        assert!(!ship.dynamic_sql);
+        assert!(report[1].dynamic_sql);
+    }
+}
@@ -0,0 +1,473 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::analysis::Severity;
+use crate::i18n::t;
+use crate::types::{Column, SchemaGraph};
+
+/// Rules read from a JSON or YAML policy file.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CompliancePolicy {
+    pub rules: Vec<PolicyRule>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyRule {
+    pub id: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+    /// Schemas the rule applies to; every schema when empty.
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(flatten)]
+    pub check: RuleCheck,
+}
+
+fn default_severity() -> Severity {
+    Severity::Medium
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum RuleCheck {
+    /// Every table has a primary key.
+    PrimaryKey,
+    /// Every table has all of `columns` (case-insensitive), e.g. audit
+    /// columns.
+    RequiredColumns { columns: Vec<String> },
+    /// The first column of every foreign key leads an index. Needs index
+    /// metadata from a live connection.
+    IndexedForeignKeys,
+    /// Names of `target` objects match the regex `pattern`.
+    Naming {
+        target: NamingTarget,
+        pattern: String,
+    },
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum NamingTarget {
+    Table,
+    View,
+    Column,
+    Procedure,
+    Function,
+}
+
+/// One object checked by a rule.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectResult {
+    pub object_id: String,
+    pub passed: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleResult {
+    pub rule_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub severity: Severity,
+    /// True when every object passed; also true for a skipped rule.
+    pub passed: bool,
+    /// Why the rule couldn't be evaluated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
+    pub objects: Vec<ObjectResult>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComplianceReport {
+    pub rules: Vec<RuleResult>,
+    /// Rules every object passed.
+    pub passed: usize,
+    pub failed: usize,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ComplianceReportFormat {
+    Markdown,
+    Csv,
+}
+
+/// Parse a policy; `.yaml` and `.yml` files are YAML, anything else JSON.
+pub fn load_policy(path: &Path) -> Result<CompliancePolicy, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let yaml = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("yaml") || e.eq_ignore_ascii_case("yml"));
+    let policy = if yaml {
+        serde_yaml::from_str(&content).map_err(|e| e.to_string())
+    } else {
+        serde_json::from_str(&content).map_err(|e| e.to_string())
+    };
+    policy.map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn in_scope(rule: &PolicyRule, schema: &str) -> bool {
+    rule.schemas.is_empty() || rule.schemas.iter().any(|s| s.eq_ignore_ascii_case(schema))
+}
+
+fn result(object_id: &str, violations: Vec<String>) -> ObjectResult {
+    ObjectResult {
+        object_id: object_id.to_string(),
+        passed: violations.is_empty(),
+        violations,
+    }
+}
+
+/// Objects of the rule's target kind in scope: ID, name and columns.
+fn named_objects<'a>(
+    graph: &'a SchemaGraph,
+    rule: &PolicyRule,
+    target: NamingTarget,
+) -> Vec<(&'a str, &'a str, &'a [Column])> {
+    let mut objects = Vec::new();
+    if matches!(target, NamingTarget::Table | NamingTarget::Column) {
+        objects.extend(
+            graph
+                .tables
+                .iter()
+                .filter(|t| in_scope(rule, &t.schema))
+                .map(|t| (t.id.as_str(), t.name.as_str(), t.columns.as_slice())),
+        );
+    }
+    if matches!(target, NamingTarget::View | NamingTarget::Column) {
+        objects.extend(
+            graph
+                .views
+                .iter()
+                .filter(|v| in_scope(rule, &v.schema))
+                .map(|v| (v.id.as_str(), v.name.as_str(), v.columns.as_slice())),
+        );
+    }
+    if target == NamingTarget::Procedure {
+        objects.extend(
+            graph
+                .stored_procedures
+                .iter()
+                .filter(|p| in_scope(rule, &p.schema))
+                .map(|p| (p.id.as_str(), p.name.as_str(), &[][..])),
+        );
+    }
+    if target == NamingTarget::Function {
+        objects.extend(
+            graph
+                .scalar_functions
+                .iter()
+                .filter(|f| in_scope(rule, &f.schema))
+                .map(|f| (f.id.as_str(), f.name.as_str(), &[][..])),
+        );
+    }
+    objects
+}
+
+fn evaluate(
+    graph: &SchemaGraph,
+    rule: &PolicyRule,
+    leading_index_columns: Option<&HashMap<String, HashSet<String>>>,
+) -> Result<RuleResult, String> {
+    let tables = || graph.tables.iter().filter(|t| in_scope(rule, &t.schema));
+    let mut skipped = None;
+    let objects: Vec<ObjectResult> = match &rule.check {
+        RuleCheck::PrimaryKey => tables()
+            .map(|table| {
+                let violations = if table.columns.iter().any(|c| c.is_primary_key) {
+                    Vec::new()
+                } else {
+                    vec![t!("compliance-missing-primary-key", object = table.id)]
+                };
+                result(&table.id, violations)
+            })
+            .collect(),
+        RuleCheck::RequiredColumns { columns } => tables()
+            .map(|table| {
+                let missing: Vec<&str> = columns
+                    .iter()
+                    .filter(|required| {
+                        !table
+                            .columns
+                            .iter()
+                            .any(|c| c.name.eq_ignore_ascii_case(required))
+                    })
+                    .map(String::as_str)
+                    .collect();
+                let violations = if missing.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![t!(
+                        "compliance-missing-columns",
+                        object = table.id,
+                        columns = missing.join(", ")
+                    )]
+                };
+                result(&table.id, violations)
+            })
+            .collect(),
+        RuleCheck::IndexedForeignKeys => match leading_index_columns {
+            None => {
+                skipped = Some(t!("compliance-needs-index-metadata"));
+                Vec::new()
+            }
+            Some(indexed) => {
+                // The first edge of each constraint carries its leading column
+                let mut seen = HashSet::new();
+                let mut violations: HashMap<&str, Vec<String>> = HashMap::new();
+                for edge in graph.relationships.iter().filter(|r| !r.is_virtual) {
+                    let Some(column) = &edge.from_column else {
+                        continue;
+                    };
+                    if !seen.insert((&edge.id, &edge.from)) {
+                        continue;
+                    }
+                    let covered = indexed
+                        .get(&edge.from)
+                        .is_some_and(|columns| columns.contains(column));
+                    if !covered {
+                        violations.entry(&edge.from).or_default().push(t!(
+                            "compliance-unindexed-foreign-key",
+                            object = edge.from,
+                            column = column,
+                            key = edge.id
+                        ));
+                    }
+                }
+                tables()
+                    .filter(|t| graph.relationships.iter().any(|r| r.from == t.id))
+                    .map(|t| result(&t.id, violations.remove(t.id.as_str()).unwrap_or_default()))
+                    .collect()
+            }
+        },
+        RuleCheck::Naming { target, pattern } => {
+            let regex = Regex::new(pattern).map_err(|e| {
+                t!(
+                    "error-invalid-pattern",
+                    pattern = pattern,
+                    reason = e.to_string()
+                )
+            })?;
+            named_objects(graph, rule, *target)
+                .into_iter()
+                .map(|(id, name, columns)| {
+                    let names: Vec<&str> = if *target == NamingTarget::Column {
+                        columns.iter().map(|c| c.name.as_str()).collect()
+                    } else {
+                        vec![name]
+                    };
+                    let violations = names
+                        .into_iter()
+                        .filter(|n| !regex.is_match(n))
+                        .map(|n| t!("compliance-naming", name = n, pattern = pattern))
+                        .collect();
+                    result(id, violations)
+                })
+                .collect()
+        }
+    };
+    Ok(RuleResult {
+        rule_id: rule.id.clone(),
+        description: rule.description.clone(),
+        severity: rule.severity,
+        passed: objects.iter().all(|o| o.passed),
+        skipped,
+        objects,
+    })
+}
+
+/// Evaluate every rule of `policy` against `graph`. Index rules are skipped
+/// when `leading_index_columns` is `None` (mock and file-based graphs).
+pub fn run_compliance(
+    graph: &SchemaGraph,
+    policy: &CompliancePolicy,
+    leading_index_columns: Option<&HashMap<String, HashSet<String>>>,
+) -> Result<ComplianceReport, String> {
+    let rules = policy
+        .rules
+        .iter()
+        .map(|rule| evaluate(graph, rule, leading_index_columns))
+        .collect::<Result<Vec<_>, _>>()?;
+    let passed = rules.iter().filter(|r| r.passed).count();
+    Ok(ComplianceReport {
+        failed: rules.len() - passed,
+        passed,
+        rules,
+    })
+}
+
+fn status(rule: &RuleResult) -> &'static str {
+    if rule.skipped.is_some() {
+        "skipped"
+    } else if rule.passed {
+        "pass"
+    } else {
+        "fail"
+    }
+}
+
+fn severity_label(severity: Severity) -> &'static str {
+    match severity {
+        Severity::High => "high",
+        Severity::Medium => "medium",
+        Severity::Low => "low",
+    }
+}
+
+fn markdown(report: &ComplianceReport) -> String {
+    let mut out = format!(
+        "# Compliance report\n\n{} rules passed, {} failed.\n",
+        report.passed, report.failed
+    );
+    for rule in &report.rules {
+        let _ = write!(
+            out,
+            "\n## {} ({}, {})\n\n",
+            rule.rule_id,
+            status(rule),
+            severity_label(rule.severity)
+        );
+        if let Some(description) = &rule.description {
+            let _ = writeln!(out, "{}\n", description);
+        }
+        if let Some(reason) = &rule.skipped {
+            let _ = writeln!(out, "Skipped: {}", reason);
+            continue;
+        }
+        let failures: Vec<&ObjectResult> = rule.objects.iter().filter(|o| !o.passed).collect();
+        let _ = writeln!(
+            out,
+            "{} of {} objects passed.",
+            rule.objects.len() - failures.len(),
+            rule.objects.len()
+        );
+        if !failures.is_empty() {
+            out.push('\n');
+        }
+        for object in failures {
+            for violation in &object.violations {
+                let _ = writeln!(out, "- {}", violation);
+            }
+        }
+    }
+    out
+}
+
+fn csv(report: &ComplianceReport) -> Result<String, String> {
+    let error = |e: csv::Error| format!("Failed to write compliance report: {}", e);
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(["Rule", "Severity", "Object", "Status", "Violations"])
+        .map_err(error)?;
+    for rule in &report.rules {
+        if let Some(reason) = &rule.skipped {
+            writer
+                .write_record([
+                    rule.rule_id.as_str(),
+                    severity_label(rule.severity),
+                    "",
+                    "skipped",
+                    reason,
+                ])
+                .map_err(error)?;
+        }
+        for object in &rule.objects {
+            writer
+                .write_record([
+                    rule.rule_id.as_str(),
+                    severity_label(rule.severity),
+                    &object.object_id,
+                    if object.passed { "pass" } else { "fail" },
+                    &object.violations.join("; "),
+                ])
+                .map_err(error)?;
+        }
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| format!("Failed to write compliance report: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+pub fn export_compliance_report(
+    report: &ComplianceReport,
+    format: ComplianceReportFormat,
+) -> Result<String, String> {
+    match format {
+        ComplianceReportFormat::Markdown => Ok(markdown(report)),
+        ComplianceReportFormat::Csv => csv(report),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+
+    const POLICY: &str = r#"
+rules:
+  - id: pk
+    kind: primaryKey
+    severity: high
+  - id: audit
+    kind: requiredColumns
+    columns: [CreatedAt]
+  - id: fk-indexed
+    kind: indexedForeignKeys
+  - id: table-names
+    kind: naming
+    target: table
+    pattern: "^[A-Z][A-Za-z0-9]*$"
+    schemas: [dbo]
+"#;
+
+    #[test]
+    fn rules_report_per_object_results() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("policy.yaml");
+        std::fs::write(&path, POLICY).unwrap();
+        let policy = load_policy(&path).unwrap();
+        assert_eq!(policy.rules[0].severity, Severity::High);
+        assert_eq!(policy.rules[1].severity, Severity::Medium);
+
+        let mut graph = generate_mock_schema("small");
+        graph.tables[0].columns.retain(|c| !c.is_primary_key);
+        let report = run_compliance(&graph, &policy, None).unwrap();
+        let by_id = |id: &str| report.rules.iter().find(|r| r.rule_id == id).unwrap();
+
+        let pk = by_id("pk");
+        assert!(!pk.passed);
+        assert_eq!(pk.objects.len(), graph.tables.len());
+        assert!(!pk.objects[0].passed);
+        assert!(!by_id("audit").passed);
+        assert!(by_id("fk-indexed").skipped.is_some());
+        assert_eq!(report.passed + report.failed, 4);
+
+        let csv = export_compliance_report(&report, ComplianceReportFormat::Csv).unwrap();
+        assert_eq!(
+            csv.lines().count(),
+            1 + 1 + report.rules.iter().map(|r| r.objects.len()).sum::<usize>()
+        );
+        let markdown = export_compliance_report(&report, ComplianceReportFormat::Markdown).unwrap();
+        assert!(markdown.contains("## pk (fail, high)"));
+
+        let invalid: CompliancePolicy = serde_json::from_str(
+            r#"{"rules": [{"id": "x", "kind": "naming", "target": "column", "pattern": "("}]}"#,
+        )
+        .unwrap();
+        assert!(run_compliance(&graph, &invalid, None).is_err());
+    }
+}
@@ -0,0 +1,275 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use super::check_rules::{literal_value, parse_check, CheckRule};
+use crate::db::{CheckConstraint, ColumnSample, DefaultConstraint};
+use crate::types::SchemaGraph;
+
+/// Columns with at most this many distinct values in the sample read as
+/// enums.
+pub const MAX_DOMAIN_VALUES: usize = 12;
+/// Rows read from each sampled column.
+pub const SAMPLE_ROWS: i64 = 10_000;
+/// Fewer sampled rows than this say nothing about a column's domain.
+const MIN_SAMPLE_ROWS: u64 = 20;
+/// Sampling costs a query per column, so one run samples at most this many.
+pub const MAX_SAMPLED_COLUMNS: usize = 200;
+/// Longest character type worth sampling; longer ones hold free text.
+const MAX_SAMPLED_LENGTH: u32 = 50;
+
+/// Where a column's allowed values were found.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DomainSource {
+    /// A check constraint lists them; nothing else can be stored.
+    CheckConstraint,
+    /// Every sampled row held one of a few values. Other values are
+    /// allowed and may exist outside the sample.
+    Sampled,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnDomain {
+    pub table_id: String,
+    pub column: String,
+    pub values: Vec<String>,
+    pub source: DomainSource,
+    /// The check constraint the values come from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constraint: Option<String>,
+    /// Sampled rows holding each value, in `values` order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub counts: Vec<u64>,
+    /// The column's default, when it's a literal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnDefault {
+    pub table_id: String,
+    pub column: String,
+    /// Name of the default constraint.
+    pub name: String,
+    /// As stored, e.g. `((0))` or `(getdate())`.
+    pub definition: String,
+    /// The value when the default is a literal rather than an expression.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainReport {
+    pub domains: Vec<ColumnDomain>,
+    pub defaults: Vec<ColumnDefault>,
+    /// Candidate columns left unsampled because of [`MAX_SAMPLED_COLUMNS`].
+    pub unsampled_columns: usize,
+}
+
+/// A column to sample: table ID, schema, table and column names.
+pub type SampleTarget = (String, String, String, String);
+
+/// Whether a declared type could hold a code: short strings and small
+/// integers. `bit` is left out since it only ever has two values.
+fn is_code_type(data_type: &str) -> bool {
+    let lower = data_type.to_lowercase();
+    let base = lower.split('(').next().unwrap_or_default().trim();
+    let length = lower
+        .split_once('(')
+        .and_then(|(_, rest)| rest.trim_end_matches(')').trim().parse::<u32>().ok());
+    match base {
+        "tinyint" | "smallint" => true,
+        "char" | "nchar" | "varchar" | "nvarchar" => {
+            length.is_some_and(|length| length <= MAX_SAMPLED_LENGTH)
+        }
+        _ => false,
+    }
+}
+
+/// Table columns worth sampling, in graph order: short strings and small
+/// integers that are neither keys nor foreign keys (whose lookup table is
+/// already their domain) nor covered by `known`. Only tables in `table_ids`
+/// when given.
+pub fn sample_targets(
+    graph: &SchemaGraph,
+    table_ids: Option<&[String]>,
+    known: &HashSet<(String, String)>,
+) -> Vec<SampleTarget> {
+    let foreign_keys: HashSet<(&str, String)> = graph
+        .relationships
+        .iter()
+        .filter_map(|r| Some((r.from.as_str(), r.from_column.as_ref()?.to_lowercase())))
+        .collect();
+    graph
+        .tables
+        .iter()
+        .filter(|t| table_ids.is_none_or(|ids| ids.contains(&t.id)))
+        .flat_map(|table| {
+            table
+                .columns
+                .iter()
+                .filter(|c| !c.is_primary_key && is_code_type(&c.data_type))
+                .filter(|c| !foreign_keys.contains(&(table.id.as_str(), c.name.to_lowercase())))
+                .filter(|c| !known.contains(&(table.id.clone(), c.name.to_lowercase())))
+                .map(|c| {
+                    (
+                        table.id.clone(),
+                        table.schema.to_string(),
+                        table.name.clone(),
+                        c.name.clone(),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Defaults as reported, with the value of those that are literals.
+pub fn column_defaults(constraints: Vec<DefaultConstraint>) -> Vec<ColumnDefault> {
+    constraints
+        .into_iter()
+        .map(|d| ColumnDefault {
+            value: literal_value(&d.definition),
+            table_id: d.table_id,
+            column: d.column,
+            name: d.name,
+            definition: d.definition,
+        })
+        .collect()
+}
+
+/// Domains from check constraints, and from samples when no check covers
+/// the column, each with the column's literal default.
+pub fn column_domains(
+    checks: &[CheckConstraint],
+    samples: &[ColumnSample],
+    defaults: &[ColumnDefault],
+) -> Vec<ColumnDomain> {
+    let default_of: HashMap<(&str, String), &ColumnDefault> = defaults
+        .iter()
+        .map(|d| ((d.table_id.as_str(), d.column.to_lowercase()), d))
+        .collect();
+    let default_value = |table_id: &str, column: &str| {
+        default_of
+            .get(&(table_id, column.to_lowercase()))
+            .and_then(|d| d.value.clone())
+    };
+
+    let mut domains: Vec<ColumnDomain> = Vec::new();
+    for check in checks {
+        let CheckRule::OneOf { column, values } = parse_check(&check.definition) else {
+            continue;
+        };
+        let existing = domains
+            .iter_mut()
+            .find(|d| d.table_id == check.table_id && d.column.eq_ignore_ascii_case(&column));
+        match existing {
+            // Two checks on one column: only values both allow can be stored
+            Some(domain) => domain.values.retain(|v| values.contains(v)),
+            None => domains.push(ColumnDomain {
+                default_value: default_value(&check.table_id, &column),
+                table_id: check.table_id.clone(),
+                column,
+                values,
+                source: DomainSource::CheckConstraint,
+                constraint: Some(check.name.clone()),
+                counts: Vec::new(),
+            }),
+        }
+    }
+
+    for sample in samples {
+        let rows: u64 = sample.values.iter().map(|(_, count)| count).sum();
+        let covered = domains.iter().any(|d| {
+            d.table_id == sample.table_id && d.column.eq_ignore_ascii_case(&sample.column)
+        });
+        if sample.truncated || rows < MIN_SAMPLE_ROWS || covered {
+            continue;
+        }
+        let (values, counts): (Vec<String>, Vec<u64>) = sample
+            .values
+            .iter()
+            .filter_map(|(value, count)| Some((value.clone()?, *count)))
+            .unzip();
+        // A single value is a constant, not a choice
+        if values.len() < 2 {
+            continue;
+        }
+        domains.push(ColumnDomain {
+            default_value: default_value(&sample.table_id, &sample.column),
+            table_id: sample.table_id.clone(),
+            column: sample.column.clone(),
+            values,
+            source: DomainSource::Sampled,
+            constraint: None,
+            counts,
+        });
+    }
+    domains
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+
+    #[test]
+    fn checks_win_over_samples_and_carry_defaults() {
+        let checks = vec![CheckConstraint {
+            table_id: "dbo.Orders".to_string(),
+            name: "CK_Orders_Status".to_string(),
+            definition: "([Status]='Open' OR [Status]='Closed')".to_string(),
+        }];
+        let sample = |column: &str, values: &[(Option<&str>, u64)], truncated| ColumnSample {
+            table_id: "dbo.Orders".to_string(),
+            column: column.to_string(),
+            values: values
+                .iter()
+                .map(|(v, n)| (v.map(str::to_string), *n))
+                .collect(),
+            truncated,
+        };
+        let samples = vec![
+            sample("status", &[(Some("Open"), 90), (Some("Closed"), 10)], false),
+            sample(
+                "Channel",
+                &[(Some("web"), 70), (None, 20), (Some("phone"), 10)],
+                false,
+            ),
+            sample("Region", &[(Some("EU"), 5)], false),
+            sample("Code", &[(Some("A"), 50), (Some("B"), 50)], true),
+        ];
+        let defaults = column_defaults(vec![DefaultConstraint {
+            table_id: "dbo.Orders".to_string(),
+            column: "STATUS".to_string(),
+            name: "DF_Orders_Status".to_string(),
+            definition: "('Open')".to_string(),
+        }]);
+
+        let domains = column_domains(&checks, &samples, &defaults);
+        assert_eq!(domains.len(), 2);
+        assert_eq!(domains[0].source, DomainSource::CheckConstraint);
+        assert_eq!(domains[0].default_value.as_deref(), Some("Open"));
+        assert_eq!(domains[1].column, "Channel");
+        assert_eq!(domains[1].values, vec!["web", "phone"]);
+        assert_eq!(domains[1].counts, vec![70, 10]);
+    }
+
+    #[test]
+    fn keys_and_free_text_are_not_sampled() {
+        let graph = generate_mock_schema("small");
+        let targets = sample_targets(&graph, None, &HashSet::new());
+        for (table_id, _, _, column) in &targets {
+            let table = graph.tables.iter().find(|t| &t.id == table_id).unwrap();
+            let column = table.columns.iter().find(|c| &c.name == column).unwrap();
+            assert!(!column.is_primary_key);
+            assert!(is_code_type(&column.data_type));
+        }
+        assert!(is_code_type("varchar(20)"));
+        assert!(!is_code_type("nvarchar(max)"));
+        assert!(!is_code_type("nvarchar(200)"));
+        assert!(!is_code_type("bit"));
+    }
+}
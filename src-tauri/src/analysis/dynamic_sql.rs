@@ -0,0 +1,133 @@
+use serde::Serialize;
+
+use crate::analysis::Severity;
+use crate::db::detect_dynamic_sql;
+use crate::i18n::t;
+use crate::types::{DefinitionAnalysis, SchemaGraph};
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DynamicSqlObjectKind {
+    StoredProcedure,
+    Trigger,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DynamicSqlFinding {
+    pub severity: Severity,
+    pub object_id: String,
+    pub kind: DynamicSqlObjectKind,
+    pub injection_risk: bool,
+    /// Variables concatenated into the executed text.
+    pub inputs: Vec<String>,
+    pub message: String,
+}
+
+/// Analysis stored by the loader, or a fresh scan for graphs cached or
+/// imported before dynamic SQL was tracked.
+pub(crate) fn scanned(definition: &str, stored: &DefinitionAnalysis) -> DefinitionAnalysis {
+    if stored.dynamic_sql {
+        return stored.clone();
+    }
+    let mut analysis = DefinitionAnalysis::default();
+    detect_dynamic_sql(definition, &mut analysis);
+    analysis
+}
+
+/// Procedures and triggers that run dynamic SQL, those concatenating
+/// variables into it first.
+pub fn dynamic_sql_report(graph: &SchemaGraph) -> Vec<DynamicSqlFinding> {
+    let procedures = graph.stored_procedures.iter().map(|p| {
+        (
+            &p.id,
+            DynamicSqlObjectKind::StoredProcedure,
+            scanned(&p.definition, &p.analysis),
+        )
+    });
+    let triggers = graph.triggers.iter().map(|t| {
+        (
+            &t.id,
+            DynamicSqlObjectKind::Trigger,
+            scanned(&t.definition, &t.analysis),
+        )
+    });
+
+    let mut findings: Vec<DynamicSqlFinding> = procedures
+        .chain(triggers)
+        .filter(|(_, _, analysis)| analysis.dynamic_sql)
+        .map(|(id, kind, analysis)| {
+            let (severity, message) = if analysis.injection_risk {
+                (
+                    Severity::High,
+                    t!(
+                        "dynamic-sql-injection-risk",
+                        object = id,
+                        inputs = analysis.concatenated_inputs.join(", ")
+                    ),
+                )
+            } else {
+                (Severity::Low, t!("dynamic-sql", object = id))
+            };
+            DynamicSqlFinding {
+                severity,
+                object_id: id.clone(),
+                kind,
+                injection_risk: analysis.injection_risk,
+                inputs: analysis.concatenated_inputs,
+                message,
+            }
+        })
+        .collect();
+    findings.sort_by(|a, b| {
+        a.severity
+            .cmp(&b.severity)
+            .then(a.object_id.cmp(&b.object_id))
+    });
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::StoredProcedure;
+
+    fn procedure(name: &str, definition: &str) -> StoredProcedure {
+        StoredProcedure {
+            id: format!("dbo.{}", name),
+            name: name.to_string(),
+            schema: "dbo".into(),
+            procedure_type: "SQL_STORED_PROCEDURE".into(),
+            parameters: Vec::new(),
+            definition: definition.to_string(),
+            referenced_tables: Vec::new(),
+            affected_tables: Vec::new(),
+            analysis: Default::default(),
+            source: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn injection_risks_come_first() {
+        let graph = SchemaGraph {
+            stored_procedures: vec![
+                procedure("A_Static", "SELECT 1 FROM dbo.Orders"),
+                procedure(
+                    "B_Params",
+                    "EXEC sp_executesql N'SELECT @x', N'@x int', @x = 1",
+                ),
+                procedure("C_Concat", "EXEC ('SELECT * FROM ' + @Table)"),
+            ],
+            ..Default::default()
+        };
+        let findings = dynamic_sql_report(&graph);
+        let ids: Vec<(&str, bool)> = findings
+            .iter()
+            .map(|f| (f.object_id.as_str(), f.injection_risk))
+            .collect();
+        assert_eq!(ids, vec![("dbo.C_Concat", true), ("dbo.B_Params", false)]);
+        assert_eq!(findings[0].inputs, vec!["@Table"]);
+        assert_eq!(findings[0].severity, Severity::High);
+    }
+}
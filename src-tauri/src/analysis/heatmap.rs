@@ -0,0 +1,240 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::types::SchemaGraph;
+
+/// Declared most recent first, so sorting puts the busiest objects on top.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeBucket {
+    LastWeek,
+    LastMonth,
+    LastQuarter,
+    Stale,
+    /// No modify date was loaded, e.g. for flat-file or mock sources.
+    Unknown,
+}
+
+impl ChangeBucket {
+    fn for_age(days: i64) -> Self {
+        match days {
+            ..=7 => ChangeBucket::LastWeek,
+            8..=30 => ChangeBucket::LastMonth,
+            31..=90 => ChangeBucket::LastQuarter,
+            _ => ChangeBucket::Stale,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectChange {
+    pub object_id: String,
+    pub object_type: String,
+    pub schema: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days_since_change: Option<i64>,
+    pub bucket: ChangeBucket,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketCounts {
+    pub last_week: usize,
+    pub last_month: usize,
+    pub last_quarter: usize,
+    pub stale: usize,
+    pub unknown: usize,
+}
+
+impl BucketCounts {
+    fn add(&mut self, bucket: ChangeBucket) {
+        match bucket {
+            ChangeBucket::LastWeek => self.last_week += 1,
+            ChangeBucket::LastMonth => self.last_month += 1,
+            ChangeBucket::LastQuarter => self.last_quarter += 1,
+            ChangeBucket::Stale => self.stale += 1,
+            ChangeBucket::Unknown => self.unknown += 1,
+        }
+    }
+
+    /// Recent changes weighted by how recent they are, from 0 (nothing
+    /// changed this quarter) to 1 (everything changed this week).
+    fn activity(&self) -> f64 {
+        let total =
+            self.last_week + self.last_month + self.last_quarter + self.stale + self.unknown;
+        if total == 0 {
+            return 0.0;
+        }
+        let weighted = self.last_week * 3 + self.last_month * 2 + self.last_quarter;
+        weighted as f64 / (total * 3) as f64
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaActivity {
+    pub schema: String,
+    pub counts: BucketCounts,
+    pub activity: f64,
+}
+
+/// Recency of change for every object, for colouring the diagram.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeHeatmap {
+    /// Most recently changed first.
+    pub objects: Vec<ObjectChange>,
+    pub totals: BucketCounts,
+    /// Most active schema first.
+    pub schemas: Vec<SchemaActivity>,
+}
+
+fn parse_modified(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").ok()
+}
+
+/// Bucket every object by how long ago its `modify_date` was, relative to
+/// `now` (UTC, like the dates themselves).
+pub fn change_heatmap(graph: &SchemaGraph, now: NaiveDateTime) -> ChangeHeatmap {
+    let nodes = graph
+        .tables
+        .iter()
+        .map(|t| ("table", &t.id, &*t.schema, &t.metadata))
+        .chain(
+            graph
+                .views
+                .iter()
+                .map(|v| ("view", &v.id, &*v.schema, &v.metadata)),
+        )
+        .chain(
+            graph
+                .triggers
+                .iter()
+                .map(|t| ("trigger", &t.id, &*t.schema, &t.metadata)),
+        )
+        .chain(
+            graph
+                .stored_procedures
+                .iter()
+                .map(|p| ("storedProcedure", &p.id, &*p.schema, &p.metadata)),
+        )
+        .chain(
+            graph
+                .scalar_functions
+                .iter()
+                .map(|f| ("scalarFunction", &f.id, &*f.schema, &f.metadata)),
+        );
+
+    let mut objects: Vec<ObjectChange> = nodes
+        .map(|(object_type, id, schema, metadata)| {
+            let modified_at = metadata.as_ref().and_then(|m| m.modified_at.clone());
+            let days = modified_at
+                .as_deref()
+                .and_then(parse_modified)
+                .map(|modified| (now - modified).num_days().max(0));
+            ObjectChange {
+                object_id: id.clone(),
+                object_type: object_type.to_string(),
+                schema: schema.to_string(),
+                modified_at,
+                days_since_change: days,
+                bucket: days.map_or(ChangeBucket::Unknown, ChangeBucket::for_age),
+            }
+        })
+        .collect();
+    objects.sort_by(|a, b| {
+        (a.bucket, a.days_since_change, &a.object_id).cmp(&(
+            b.bucket,
+            b.days_since_change,
+            &b.object_id,
+        ))
+    });
+
+    let mut totals = BucketCounts::default();
+    let mut per_schema: BTreeMap<&str, BucketCounts> = BTreeMap::new();
+    for object in &objects {
+        totals.add(object.bucket);
+        per_schema
+            .entry(object.schema.as_str())
+            .or_default()
+            .add(object.bucket);
+    }
+    let mut schemas: Vec<SchemaActivity> = per_schema
+        .into_iter()
+        .map(|(schema, counts)| SchemaActivity {
+            schema: schema.to_string(),
+            counts,
+            activity: counts.activity(),
+        })
+        .collect();
+    // Stable sort keeps schemas with equal activity in name order
+    schemas.sort_by(|a, b| b.activity.total_cmp(&a.activity));
+
+    ChangeHeatmap {
+        objects,
+        totals,
+        schemas,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ObjectMetadata, TableNode};
+
+    fn table(schema: &str, name: &str, modified_at: Option<&str>) -> TableNode {
+        TableNode {
+            id: format!("{}.{}", schema, name),
+            name: name.to_string(),
+            schema: schema.into(),
+            columns: Vec::new(),
+            source: None,
+            metadata: modified_at.map(|m| ObjectMetadata {
+                modified_at: Some(m.to_string()),
+                ..Default::default()
+            }),
+            change_capture: None,
+        }
+    }
+
+    #[test]
+    fn objects_are_bucketed_by_age_and_schemas_ranked_by_activity() {
+        let graph = SchemaGraph {
+            tables: vec![
+                table("archive", "Orders2019", Some("2023-01-15T08:00:00")),
+                table("sales", "Orders", Some("2024-06-28T10:15:00.250")),
+                table("sales", "Returns", Some("2024-06-10T00:00:00")),
+                table("sales", "Quotes", Some("2024-04-20T00:00:00")),
+                table("staging", "Load", None),
+            ],
+            ..Default::default()
+        };
+        let now = parse_modified("2024-07-01T12:00:00").unwrap();
+
+        let heatmap = change_heatmap(&graph, now);
+        let buckets: Vec<(&str, ChangeBucket, Option<i64>)> = heatmap
+            .objects
+            .iter()
+            .map(|o| (o.object_id.as_str(), o.bucket, o.days_since_change))
+            .collect();
+        assert_eq!(
+            buckets,
+            vec![
+                ("sales.Orders", ChangeBucket::LastWeek, Some(3)),
+                ("sales.Returns", ChangeBucket::LastMonth, Some(21)),
+                ("sales.Quotes", ChangeBucket::LastQuarter, Some(72)),
+                ("archive.Orders2019", ChangeBucket::Stale, Some(533)),
+                ("staging.Load", ChangeBucket::Unknown, None),
+            ]
+        );
+        assert_eq!(heatmap.totals.stale, 1);
+
+        let order: Vec<&str> = heatmap.schemas.iter().map(|s| s.schema.as_str()).collect();
+        assert_eq!(order, vec!["sales", "archive", "staging"]);
+        assert!((heatmap.schemas[0].activity - 6.0 / 9.0).abs() < 1e-9);
+    }
+}
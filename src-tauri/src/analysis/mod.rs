@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+pub mod capture;
+pub mod check_rules;
+pub mod complexity;
+pub mod compliance;
+pub mod domains;
+pub mod dynamic_sql;
+pub mod heatmap;
+pub mod portability;
+pub mod smells;
+pub mod stats;
+pub mod type_change;
+pub mod view_nesting;
+
+pub use capture::{change_capture_report, ChangeCaptureReport};
+pub use check_rules::{check_rules, CheckConstraintRule};
+pub use complexity::{complexity_report, ObjectComplexity, DEFAULT_TOP_COMPLEX};
+pub use compliance::{
+    export_compliance_report, load_policy, run_compliance, ComplianceReport, ComplianceReportFormat,
+};
+pub use domains::{
+    column_defaults, column_domains, sample_targets, DomainReport, MAX_DOMAIN_VALUES,
+    MAX_SAMPLED_COLUMNS, SAMPLE_ROWS,
+};
+pub use dynamic_sql::{dynamic_sql_report, DynamicSqlFinding};
+pub use heatmap::{change_heatmap, ChangeHeatmap};
+pub use portability::{check_portability, SeverityGroup, TargetDialect};
+pub use smells::{analyze_design, DesignFinding};
+pub use stats::{graph_stats, GraphStats};
+pub use type_change::{type_change_impact, TypeChangeImpact};
+pub use view_nesting::{view_nesting, ViewNestingReport, DEFAULT_MAX_VIEW_DEPTH};
+
+/// Shared by the analysis reports; variants are declared most severe first
+/// so sorting puts the urgent findings on top.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    High,
+    Medium,
+    Low,
+}
@@ -0,0 +1,302 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::analysis::Severity;
+use crate::i18n::t;
+use crate::types::{Column, SchemaGraph};
+
+/// PostgreSQL reserved key words, including those only allowed as function
+/// or type names. Lower case, space separated.
+const POSTGRES_RESERVED: &str = "\
+    all analyse analyze and any array as asc asymmetric authorization binary both case cast \
+    check collate collation column concurrently constraint create cross current_catalog \
+    current_date current_role current_schema current_time current_timestamp current_user \
+    default deferrable desc distinct do else end except false fetch for foreign freeze from \
+    full grant group having ilike in initially inner intersect into is isnull join lateral \
+    leading left like limit localtime localtimestamp natural not notnull null offset on only \
+    or order outer overlaps placing primary references returning right select session_user \
+    similar some symmetric system_user table tablesample then to trailing true union unique \
+    user using variadic verbose when where window with";
+
+/// MySQL 8.0 reserved words, space separated like the list above.
+const MYSQL_RESERVED: &str = "\
+    accessible add all alter analyze and as asc asensitive before between bigint binary blob \
+    both by call cascade case change char character check collate column condition \
+    constraint continue convert create cross cube cume_dist current_date current_time \
+    current_timestamp current_user cursor database databases day_hour day_microsecond \
+    day_minute day_second dec decimal declare default delayed delete dense_rank desc \
+    describe deterministic distinct distinctrow div double drop dual each else elseif empty \
+    enclosed escaped except exists exit explain false fetch first_value float float4 float8 \
+    for force foreign from fulltext function generated get grant group grouping groups \
+    having high_priority hour_microsecond hour_minute hour_second if ignore in index infile \
+    inner inout insensitive insert int int1 int2 int3 int4 int8 integer intersect interval \
+    into io_after_gtids io_before_gtids is iterate join json_table key keys kill lag \
+    last_value lateral lead leading leave left like limit linear lines load localtime \
+    localtimestamp lock long longblob longtext loop low_priority master_bind \
+    master_ssl_verify_server_cert match maxvalue mediumblob mediumint mediumtext middleint \
+    minute_microsecond minute_second mod modifies natural not no_write_to_binlog nth_value \
+    ntile null numeric of on optimize optimizer_costs option optionally or order out outer \
+    outfile over partition percent_rank precision primary procedure purge range rank read \
+    reads read_write real recursive references regexp release rename repeat replace require \
+    resignal restrict return revoke right rlike row rows row_number schema schemas \
+    second_microsecond select sensitive separator set show signal smallint spatial specific \
+    sql sqlexception sqlstate sqlwarning sql_big_result sql_calc_found_rows sql_small_result \
+    ssl starting stored straight_join system table terminated then tinyblob tinyint tinytext \
+    to trailing trigger true undo union unique unlock unsigned update usage use using \
+    utc_date utc_time utc_timestamp values varbinary varchar varcharacter varying virtual \
+    when where while window with write xor year_month zerofill";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TargetDialect {
+    Postgres,
+    MySql,
+}
+
+impl TargetDialect {
+    fn reserved(self) -> &'static str {
+        match self {
+            TargetDialect::Postgres => POSTGRES_RESERVED,
+            TargetDialect::MySql => MYSQL_RESERVED,
+        }
+    }
+
+    /// Postgres counts bytes (NAMEDATALEN - 1), MySQL counts characters.
+    fn name_too_long(self, identifier: &str) -> bool {
+        match self {
+            TargetDialect::Postgres => identifier.len() > 63,
+            TargetDialect::MySql => identifier.chars().count() > 64,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            TargetDialect::Postgres => "PostgreSQL",
+            TargetDialect::MySql => "MySQL",
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PortabilityIssueKind {
+    ReservedWord,
+    TooLong,
+    /// Needs quoting on every use: spaces, punctuation or a leading digit.
+    RequiresQuoting,
+    /// Postgres folds unquoted names to lower case, so `OrderDate` must be
+    /// quoted forever or renamed.
+    MixedCase,
+}
+
+impl PortabilityIssueKind {
+    fn severity(self, dialect: TargetDialect) -> Severity {
+        match (self, dialect) {
+            (PortabilityIssueKind::ReservedWord, _) => Severity::High,
+            // MySQL rejects long names; Postgres silently truncates them
+            (PortabilityIssueKind::TooLong, TargetDialect::MySql) => Severity::High,
+            (PortabilityIssueKind::TooLong, TargetDialect::Postgres) => Severity::Medium,
+            (PortabilityIssueKind::RequiresQuoting, _) => Severity::Medium,
+            (PortabilityIssueKind::MixedCase, _) => Severity::Low,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PortabilityIssue {
+    pub dialect: TargetDialect,
+    pub kind: PortabilityIssueKind,
+    /// Schema name for schemas, otherwise the object's graph ID.
+    pub object_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    pub identifier: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SeverityGroup {
+    pub severity: Severity,
+    pub issues: Vec<PortabilityIssue>,
+}
+
+fn is_plain_identifier(identifier: &str) -> bool {
+    let mut chars = identifier.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn identifier_issues(dialect: TargetDialect, identifier: &str) -> Vec<PortabilityIssueKind> {
+    let mut kinds = Vec::new();
+    let lower = identifier.to_lowercase();
+    if dialect.reserved().split_whitespace().any(|w| w == lower) {
+        kinds.push(PortabilityIssueKind::ReservedWord);
+    }
+    if dialect.name_too_long(identifier) {
+        kinds.push(PortabilityIssueKind::TooLong);
+    }
+    if !is_plain_identifier(identifier) {
+        kinds.push(PortabilityIssueKind::RequiresQuoting);
+    } else if dialect == TargetDialect::Postgres && identifier != lower {
+        kinds.push(PortabilityIssueKind::MixedCase);
+    }
+    kinds
+}
+
+fn message(dialect: TargetDialect, kind: PortabilityIssueKind, identifier: &str) -> String {
+    let key = match kind {
+        PortabilityIssueKind::ReservedWord => "portability-reserved-word",
+        PortabilityIssueKind::TooLong => "portability-too-long",
+        PortabilityIssueKind::RequiresQuoting => "portability-requires-quoting",
+        PortabilityIssueKind::MixedCase => "portability-mixed-case",
+    };
+    t!(key, identifier = identifier, dialect = dialect.label())
+}
+
+/// Check every schema, object and column name against each target dialect.
+/// Groups are ordered most severe first; empty groups are left out.
+pub fn check_portability(graph: &SchemaGraph, dialects: &[TargetDialect]) -> Vec<SeverityGroup> {
+    let schemas: BTreeSet<&str> = graph
+        .tables
+        .iter()
+        .map(|t| &*t.schema)
+        .chain(graph.views.iter().map(|v| &*v.schema))
+        .chain(graph.stored_procedures.iter().map(|p| &*p.schema))
+        .chain(graph.scalar_functions.iter().map(|f| &*f.schema))
+        .collect();
+
+    let no_columns: &[Column] = &[];
+    let names = schemas
+        .into_iter()
+        .map(|s| (s, s, no_columns))
+        .chain(
+            graph
+                .tables
+                .iter()
+                .map(|t| (t.id.as_str(), t.name.as_str(), t.columns.as_slice())),
+        )
+        .chain(
+            graph
+                .views
+                .iter()
+                .map(|v| (v.id.as_str(), v.name.as_str(), v.columns.as_slice())),
+        )
+        .chain(
+            graph
+                .stored_procedures
+                .iter()
+                .map(|p| (p.id.as_str(), p.name.as_str(), no_columns)),
+        )
+        .chain(
+            graph
+                .scalar_functions
+                .iter()
+                .map(|f| (f.id.as_str(), f.name.as_str(), no_columns)),
+        )
+        .chain(
+            graph
+                .triggers
+                .iter()
+                .map(|t| (t.id.as_str(), t.name.as_str(), no_columns)),
+        );
+
+    let mut issues = Vec::new();
+    for (object_id, name, columns) in names {
+        let identifiers = std::iter::once((None, name))
+            .chain(columns.iter().map(|c| (Some(&c.name), c.name.as_str())));
+        for (column, identifier) in identifiers {
+            for &dialect in dialects {
+                for kind in identifier_issues(dialect, identifier) {
+                    issues.push(PortabilityIssue {
+                        dialect,
+                        kind,
+                        object_id: object_id.to_string(),
+                        column: column.cloned(),
+                        identifier: identifier.to_string(),
+                        message: message(dialect, kind, identifier),
+                    });
+                }
+            }
+        }
+    }
+
+    [Severity::High, Severity::Medium, Severity::Low]
+        .into_iter()
+        .filter_map(|severity| {
+            let group: Vec<PortabilityIssue> = issues
+                .iter()
+                .filter(|i| i.kind.severity(i.dialect) == severity)
+                .cloned()
+                .collect();
+            (!group.is_empty()).then_some(SeverityGroup {
+                severity,
+                issues: group,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TableNode;
+
+    #[test]
+    fn reserved_and_unquotable_names_are_grouped_by_severity() {
+        let column = |name: &str| Column {
+            name: name.to_string(),
+            data_type: "int".into(),
+            ..Default::default()
+        };
+        let graph = SchemaGraph {
+            tables: vec![TableNode {
+                id: "dbo.user".to_string(),
+                name: "user".to_string(),
+                schema: "dbo".into(),
+                columns: vec![column("order_date"), column("Key"), column("Unit Price")],
+                source: None,
+                metadata: None,
+                change_capture: None,
+            }],
+            ..Default::default()
+        };
+
+        let groups = check_portability(&graph, &[TargetDialect::Postgres, TargetDialect::MySql]);
+        let summary: Vec<(Severity, Vec<(TargetDialect, &str)>)> = groups
+            .iter()
+            .map(|g| {
+                (
+                    g.severity,
+                    g.issues
+                        .iter()
+                        .map(|i| (i.dialect, i.identifier.as_str()))
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    Severity::High,
+                    vec![
+                        (TargetDialect::Postgres, "user"),
+                        (TargetDialect::MySql, "Key"),
+                    ]
+                ),
+                (
+                    Severity::Medium,
+                    vec![
+                        (TargetDialect::Postgres, "Unit Price"),
+                        (TargetDialect::MySql, "Unit Price"),
+                    ]
+                ),
+                (Severity::Low, vec![(TargetDialect::Postgres, "Key")]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::analysis::Severity;
+use crate::i18n::t;
+use crate::types::{SchemaGraph, TableNode};
+
+/// Column count at which a table is flagged as too wide.
+const WIDE_TABLE_COLUMNS: usize = 100;
+
+/// EAV tables are narrow; a wide table with a `Value` column is just a table.
+const EAV_MAX_COLUMNS: usize = 8;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SmellKind {
+    MissingPrimaryKey,
+    EntityAttributeValue,
+    UnindexedForeignKey,
+    WideTable,
+    RepeatedColumnGroup,
+}
+
+impl SmellKind {
+    fn severity(self) -> Severity {
+        match self {
+            SmellKind::MissingPrimaryKey | SmellKind::EntityAttributeValue => Severity::High,
+            SmellKind::UnindexedForeignKey | SmellKind::WideTable => Severity::Medium,
+            SmellKind::RepeatedColumnGroup => Severity::Low,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DesignFinding {
+    pub kind: SmellKind,
+    pub severity: Severity,
+    pub table_id: String,
+    /// Columns involved, when the smell is about specific columns.
+    pub columns: Vec<String>,
+    pub message: String,
+}
+
+fn finding(
+    kind: SmellKind,
+    table: &TableNode,
+    columns: Vec<String>,
+    message: String,
+) -> DesignFinding {
+    DesignFinding {
+        kind,
+        severity: kind.severity(),
+        table_id: table.id.clone(),
+        columns,
+        message,
+    }
+}
+
+/// `Address1`, `Address_2`, `address3` share the stem `address`.
+fn numbered_stem(column: &str) -> Option<String> {
+    let stem = column.trim_end_matches(|c: char| c.is_ascii_digit());
+    if stem.len() == column.len() {
+        return None;
+    }
+    let stem = stem.trim_end_matches('_');
+    (stem.len() >= 2).then(|| stem.to_lowercase())
+}
+
+fn repeated_groups(table: &TableNode) -> Vec<Vec<String>> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for column in &table.columns {
+        if let Some(stem) = numbered_stem(&column.name) {
+            groups.entry(stem).or_default().push(column.name.clone());
+        }
+    }
+    groups.into_values().filter(|g| g.len() >= 2).collect()
+}
+
+/// A narrow table holding attribute names next to a generic value column.
+fn is_entity_attribute_value(table: &TableNode) -> bool {
+    if table.columns.len() > EAV_MAX_COLUMNS {
+        return false;
+    }
+    let names: Vec<String> = table
+        .columns
+        .iter()
+        .map(|c| c.name.to_lowercase())
+        .collect();
+    let has_value = names.iter().any(|n| n.ends_with("value"));
+    let has_attribute = names.iter().any(|n| {
+        n.contains("attribute") || n.contains("property") || n.contains("field") || n == "key"
+    });
+    has_value && has_attribute
+}
+
+/// Flag design smells in `graph`, most severe first. The unindexed foreign
+/// key check needs index metadata and is skipped when `leading_index_columns`
+/// is `None` (mock and file-based graphs).
+pub fn analyze_design(
+    graph: &SchemaGraph,
+    leading_index_columns: Option<&HashMap<String, HashSet<String>>>,
+) -> Vec<DesignFinding> {
+    let mut findings = Vec::new();
+
+    for table in &graph.tables {
+        if !table.columns.iter().any(|c| c.is_primary_key) {
+            findings.push(finding(
+                SmellKind::MissingPrimaryKey,
+                table,
+                Vec::new(),
+                t!("smell-missing-primary-key", table = table.id),
+            ));
+        }
+        if is_entity_attribute_value(table) {
+            findings.push(finding(
+                SmellKind::EntityAttributeValue,
+                table,
+                Vec::new(),
+                t!("smell-entity-attribute-value", table = table.id),
+            ));
+        }
+        if table.columns.len() >= WIDE_TABLE_COLUMNS {
+            findings.push(finding(
+                SmellKind::WideTable,
+                table,
+                Vec::new(),
+                t!(
+                    "smell-wide-table",
+                    table = table.id,
+                    count = table.columns.len()
+                ),
+            ));
+        }
+        for group in repeated_groups(table) {
+            findings.push(finding(
+                SmellKind::RepeatedColumnGroup,
+                table,
+                group.clone(),
+                t!(
+                    "smell-repeated-column-group",
+                    table = table.id,
+                    columns = group.join(", ")
+                ),
+            ));
+        }
+    }
+
+    if let Some(indexed) = leading_index_columns {
+        let tables: HashMap<&str, &TableNode> =
+            graph.tables.iter().map(|t| (t.id.as_str(), t)).collect();
+        // Composite keys arrive as one edge per column pair, in key order
+        let mut keys: Vec<(&TableNode, &str, Vec<String>)> = Vec::new();
+        let mut positions: HashMap<(&str, &str), usize> = HashMap::new();
+        for edge in graph.relationships.iter().filter(|r| !r.is_virtual) {
+            let (Some(table), Some(column)) = (tables.get(edge.from.as_str()), &edge.from_column)
+            else {
+                continue;
+            };
+            let position = *positions
+                .entry((edge.from.as_str(), edge.id.as_str()))
+                .or_insert_with(|| {
+                    keys.push((table, edge.id.as_str(), Vec::new()));
+                    keys.len() - 1
+                });
+            keys[position].2.push(column.clone());
+        }
+
+        for (table, key, columns) in keys {
+            let nullable = table
+                .columns
+                .iter()
+                .any(|c| c.is_nullable && columns.contains(&c.name));
+            // An index supports the key when it leads with the key's first column
+            let covered = indexed
+                .get(&table.id)
+                .is_some_and(|leading| leading.contains(&columns[0]));
+            if nullable && !covered {
+                let message = t!(
+                    "smell-unindexed-foreign-key",
+                    table = table.id,
+                    columns = columns.join(", "),
+                    key = key
+                );
+                findings.push(finding(
+                    SmellKind::UnindexedForeignKey,
+                    table,
+                    columns,
+                    message,
+                ));
+            }
+        }
+    }
+
+    findings.sort_by(|a, b| {
+        a.severity
+            .cmp(&b.severity)
+            .then(a.table_id.cmp(&b.table_id))
+    });
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Column, RelationshipEdge};
+
+    fn table(name: &str, columns: &[&str]) -> TableNode {
+        TableNode {
+            id: format!("dbo.{}", name),
+            name: name.to_string(),
+            schema: "dbo".into(),
+            columns: columns
+                .iter()
+                .map(|c| Column {
+                    name: c.to_string(),
+                    data_type: "int".into(),
+                    is_primary_key: *c == "Id",
+                    is_nullable: c.ends_with("Id") && *c != "Id",
+                    ..Default::default()
+                })
+                .collect(),
+            source: None,
+            metadata: None,
+            change_capture: None,
+        }
+    }
+
+    fn key(id: &str, from: &str, column: &str) -> RelationshipEdge {
+        RelationshipEdge {
+            id: id.to_string(),
+            from: format!("dbo.{}", from),
+            to: "dbo.Customers".to_string(),
+            from_column: Some(column.to_string()),
+            to_column: Some("Id".to_string()),
+            source: None,
+            is_virtual: false,
+            participation: None,
+        }
+    }
+
+    #[test]
+    fn findings_are_prioritized_by_severity() {
+        let graph = SchemaGraph {
+            tables: vec![
+                table(
+                    "Customers",
+                    &["Id", "Address1", "Address2", "Address_3", "Sha256"],
+                ),
+                table("Orders", &["Id", "CustomerId", "SalesRepId"]),
+                table("Settings", &["EntityId", "AttributeName", "Value"]),
+                table("Shipments", &["Id", "WarehouseId", "BinId"]),
+            ],
+            relationships: vec![
+                key("FK_Orders_Customers", "Orders", "CustomerId"),
+                key("FK_Orders_Reps", "Orders", "SalesRepId"),
+                key("FK_Shipments_Bins", "Shipments", "WarehouseId"),
+                key("FK_Shipments_Bins", "Shipments", "BinId"),
+            ],
+            ..Default::default()
+        };
+        // The composite key is covered by an index on (WarehouseId, BinId)
+        let indexed = HashMap::from([
+            (
+                "dbo.Orders".to_string(),
+                HashSet::from(["CustomerId".to_string()]),
+            ),
+            (
+                "dbo.Shipments".to_string(),
+                HashSet::from(["WarehouseId".to_string()]),
+            ),
+        ]);
+
+        let findings = analyze_design(&graph, Some(&indexed));
+        let kinds: Vec<(SmellKind, &str)> = findings
+            .iter()
+            .map(|f| (f.kind, f.table_id.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (SmellKind::MissingPrimaryKey, "dbo.Settings"),
+                (SmellKind::EntityAttributeValue, "dbo.Settings"),
+                (SmellKind::UnindexedForeignKey, "dbo.Orders"),
+                (SmellKind::RepeatedColumnGroup, "dbo.Customers"),
+            ]
+        );
+        assert_eq!(findings[2].columns, vec!["SalesRepId"]);
+        assert_eq!(findings[3].columns.len(), 3);
+
+        // Without index metadata the FK check is skipped
+        assert_eq!(analyze_design(&graph, None).len(), 3);
+    }
+}
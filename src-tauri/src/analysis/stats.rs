@@ -0,0 +1,250 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::types::SchemaGraph;
+
+/// Entries kept in each "top N" list.
+const TOP_N: usize = 10;
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaCounts {
+    pub schema: String,
+    pub tables: usize,
+    pub views: usize,
+    pub procedures: usize,
+    pub functions: usize,
+    pub triggers: usize,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableMetric {
+    pub table_id: String,
+    pub value: usize,
+}
+
+/// At-a-glance health summary of a loaded graph.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphStats {
+    /// Object counts per schema, in schema order.
+    pub schemas: Vec<SchemaCounts>,
+    pub tables_without_primary_key: Vec<String>,
+    /// Tables with the most columns.
+    pub widest_tables: Vec<TableMetric>,
+    /// Tables read or written by the most views, procedures, functions and
+    /// triggers.
+    pub most_referenced_tables: Vec<TableMetric>,
+    /// Table referenced by the most foreign keys.
+    pub max_fan_in: Option<TableMetric>,
+    /// Table holding the most foreign keys.
+    pub max_fan_out: Option<TableMetric>,
+    pub relationship_count: usize,
+    /// Distinct related table pairs over all possible pairs, from 0 to 1.
+    pub relationship_density: f64,
+}
+
+/// Highest values first, ties broken by ID so the output is stable.
+fn top(counts: HashMap<&str, usize>, limit: usize) -> Vec<TableMetric> {
+    let mut metrics: Vec<TableMetric> = counts
+        .into_iter()
+        .filter(|(_, value)| *value > 0)
+        .map(|(id, value)| TableMetric {
+            table_id: id.to_string(),
+            value,
+        })
+        .collect();
+    metrics.sort_by(|a, b| b.value.cmp(&a.value).then(a.table_id.cmp(&b.table_id)));
+    metrics.truncate(limit);
+    metrics
+}
+
+fn counts<'a>(
+    schemas: &'a mut BTreeMap<String, SchemaCounts>,
+    schema: &str,
+) -> &'a mut SchemaCounts {
+    schemas
+        .entry(schema.to_string())
+        .or_insert_with(|| SchemaCounts {
+            schema: schema.to_string(),
+            ..Default::default()
+        })
+}
+
+pub fn graph_stats(graph: &SchemaGraph) -> GraphStats {
+    let mut schemas: BTreeMap<String, SchemaCounts> = BTreeMap::new();
+    for t in &graph.tables {
+        counts(&mut schemas, &t.schema).tables += 1;
+    }
+    for v in &graph.views {
+        counts(&mut schemas, &v.schema).views += 1;
+    }
+    for p in &graph.stored_procedures {
+        counts(&mut schemas, &p.schema).procedures += 1;
+    }
+    for f in &graph.scalar_functions {
+        counts(&mut schemas, &f.schema).functions += 1;
+    }
+    for t in &graph.triggers {
+        counts(&mut schemas, &t.schema).triggers += 1;
+    }
+
+    let table_ids: HashSet<&str> = graph.tables.iter().map(|t| t.id.as_str()).collect();
+    let tables_without_primary_key = graph
+        .tables
+        .iter()
+        .filter(|t| !t.columns.iter().any(|c| c.is_primary_key))
+        .map(|t| t.id.clone())
+        .collect();
+    let widest_tables = top(
+        graph
+            .tables
+            .iter()
+            .map(|t| (t.id.as_str(), t.columns.len()))
+            .collect(),
+        TOP_N,
+    );
+
+    let mut references: HashMap<&str, usize> = HashMap::new();
+    let modules = graph
+        .views
+        .iter()
+        .map(|v| v.referenced_tables.iter().collect::<HashSet<_>>())
+        .chain(graph.stored_procedures.iter().map(|p| {
+            p.referenced_tables
+                .iter()
+                .chain(&p.affected_tables)
+                .collect()
+        }))
+        .chain(graph.scalar_functions.iter().map(|f| {
+            f.referenced_tables
+                .iter()
+                .chain(&f.affected_tables)
+                .collect()
+        }))
+        .chain(graph.triggers.iter().map(|t| {
+            t.referenced_tables
+                .iter()
+                .chain(&t.affected_tables)
+                .collect()
+        }));
+    for tables in modules {
+        for id in tables {
+            if table_ids.contains(id.as_str()) {
+                *references.entry(id.as_str()).or_default() += 1;
+            }
+        }
+    }
+
+    // Composite keys produce one edge per column; count each constraint once
+    let keys: HashSet<(&str, &str, &str)> = graph
+        .relationships
+        .iter()
+        .map(|r| (r.id.as_str(), r.from.as_str(), r.to.as_str()))
+        .collect();
+    let mut fan_in: HashMap<&str, usize> = HashMap::new();
+    let mut fan_out: HashMap<&str, usize> = HashMap::new();
+    let mut pairs: HashSet<(&str, &str)> = HashSet::new();
+    for (_, from, to) in &keys {
+        *fan_in.entry(to).or_default() += 1;
+        *fan_out.entry(from).or_default() += 1;
+        if from != to {
+            pairs.insert(if from < to { (from, to) } else { (to, from) });
+        }
+    }
+
+    let nodes = graph.tables.len() + graph.views.len();
+    let possible_pairs = nodes * nodes.saturating_sub(1) / 2;
+    let relationship_density = match possible_pairs {
+        0 => 0.0,
+        possible => pairs.len() as f64 / possible as f64,
+    };
+
+    GraphStats {
+        schemas: schemas.into_values().collect(),
+        tables_without_primary_key,
+        widest_tables,
+        most_referenced_tables: top(references, TOP_N),
+        max_fan_in: top(fan_in, 1).pop(),
+        max_fan_out: top(fan_out, 1).pop(),
+        relationship_count: keys.len(),
+        relationship_density,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Column, RelationshipEdge, TableNode};
+
+    fn table(name: &str, columns: &[(&str, bool)]) -> TableNode {
+        TableNode {
+            id: format!("dbo.{}", name),
+            name: name.to_string(),
+            schema: "dbo".into(),
+            columns: columns
+                .iter()
+                .map(|(name, pk)| Column {
+                    name: name.to_string(),
+                    data_type: "int".into(),
+                    is_primary_key: *pk,
+                    ..Default::default()
+                })
+                .collect(),
+            source: None,
+            metadata: None,
+            change_capture: None,
+        }
+    }
+
+    fn fk(id: &str, from: &str, to: &str) -> RelationshipEdge {
+        RelationshipEdge {
+            id: id.to_string(),
+            from: format!("dbo.{}", from),
+            to: format!("dbo.{}", to),
+            from_column: None,
+            to_column: None,
+            source: None,
+            is_virtual: false,
+            participation: None,
+        }
+    }
+
+    #[test]
+    fn stats_count_constraints_not_columns() {
+        let graph = SchemaGraph {
+            tables: vec![
+                table("Customers", &[("Id", true), ("Name", false)]),
+                table(
+                    "Orders",
+                    &[("Id", true), ("CustomerId", false), ("ShipToId", false)],
+                ),
+                table("Staging", &[("Payload", false)]),
+            ],
+            relationships: vec![
+                // Composite key: two edges, one constraint
+                fk("FK_Orders_Customers", "Orders", "Customers"),
+                fk("FK_Orders_Customers", "Orders", "Customers"),
+                fk("FK_Orders_ShipTo", "Orders", "Customers"),
+            ],
+            ..Default::default()
+        };
+
+        let stats = graph_stats(&graph);
+        assert_eq!(stats.schemas.len(), 1);
+        assert_eq!(stats.schemas[0].tables, 3);
+        assert_eq!(stats.tables_without_primary_key, vec!["dbo.Staging"]);
+        assert_eq!(stats.widest_tables[0].table_id, "dbo.Orders");
+        assert_eq!(stats.relationship_count, 2);
+        assert_eq!(
+            stats.max_fan_in,
+            Some(TableMetric {
+                table_id: "dbo.Customers".to_string(),
+                value: 2
+            })
+        );
+        assert_eq!(stats.max_fan_out.unwrap().table_id, "dbo.Orders");
+        assert!((stats.relationship_density - 1.0 / 3.0).abs() < 1e-9);
+    }
+}
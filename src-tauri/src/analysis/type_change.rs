@@ -0,0 +1,759 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::db::{object_id, quote_name, split_multipart, ColumnIndex};
+use crate::i18n::t;
+use crate::types::{Column, SchemaGraph};
+
+static SCHEMABINDING: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\bschemabinding\b").unwrap());
+
+/// How values of the old type fit the new one.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Conversion {
+    Unchanged,
+    /// Every old value fits.
+    Widening,
+    /// Same kind of type, but longer or more precise values may not fit.
+    Narrowing,
+    /// A different kind of type; each value is converted and may fail.
+    Converting,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum DependentKind {
+    PrimaryKey,
+    ForeignKey,
+    Index,
+    View,
+    Function,
+    Procedure,
+    Trigger,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeChangeDependent {
+    pub object_id: String,
+    pub kind: DependentKind,
+    /// `ALTER COLUMN` fails until this object is dropped or changed; the
+    /// others keep working, at most needing a refresh or a review.
+    pub binding: bool,
+    pub reason: String,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistStep {
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sql: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeChangeImpact {
+    pub table_id: String,
+    pub column: String,
+    pub old_type: String,
+    pub new_type: String,
+    pub conversion: Conversion,
+    /// Binding dependents first.
+    pub dependents: Vec<TypeChangeDependent>,
+    /// In the order to carry them out.
+    pub checklist: Vec<ChecklistStep>,
+    /// Set when indexes couldn't be checked because no live connection was
+    /// given; the primary key is still reported from the graph.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+struct SqlType {
+    base: String,
+    /// Length, precision or scale; `None` for `max`.
+    args: Vec<Option<u32>>,
+}
+
+fn parse_type(text: &str) -> SqlType {
+    let text = text.trim().to_lowercase().replace(['[', ']'], "");
+    let (base, args) = match text.split_once('(') {
+        Some((base, rest)) => (base, rest.trim_end_matches(')')),
+        None => (text.as_str(), ""),
+    };
+    let args = args
+        .split(',')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(|a| a.parse().ok())
+        .collect();
+    SqlType {
+        base: base.trim().to_string(),
+        args,
+    }
+}
+
+impl SqlType {
+    fn arg(&self, index: usize, default: u32) -> Option<u32> {
+        self.args.get(index).copied().unwrap_or(Some(default))
+    }
+
+    /// Digits before and after the decimal point.
+    fn numeric(&self) -> Option<(u32, u32)> {
+        let digits = match self.base.as_str() {
+            "bit" => (1, 0),
+            "tinyint" => (3, 0),
+            "smallint" => (5, 0),
+            "int" => (10, 0),
+            "bigint" => (19, 0),
+            "smallmoney" => (6, 4),
+            "money" => (15, 4),
+            "decimal" | "numeric" => {
+                let precision = self.arg(0, 18)?;
+                let scale = self.arg(1, 0)?;
+                (precision.saturating_sub(scale), scale)
+            }
+            _ => return None,
+        };
+        Some(digits)
+    }
+
+    /// Binary or not, Unicode or not, and length (`u64::MAX` for `max`).
+    fn text(&self) -> Option<(bool, bool, u64)> {
+        let (binary, unicode) = match self.base.as_str() {
+            "char" | "varchar" => (false, false),
+            "nchar" | "nvarchar" => (false, true),
+            "binary" | "varbinary" => (true, false),
+            "text" | "image" => return Some((self.base == "image", false, u64::MAX)),
+            "ntext" => return Some((false, true, u64::MAX)),
+            _ => return None,
+        };
+        let length = self.arg(0, 1).map_or(u64::MAX, u64::from);
+        Some((binary, unicode, length))
+    }
+
+    /// Range rank and fractional-second digits.
+    fn temporal(&self) -> Option<(u32, u32)> {
+        match self.base.as_str() {
+            "smalldatetime" => Some((1, 0)),
+            "datetime" => Some((2, 3)),
+            "datetime2" => Some((3, self.arg(0, 7)?)),
+            "datetimeoffset" => Some((4, self.arg(0, 7)?)),
+            _ => None,
+        }
+    }
+}
+
+fn widening(fits: bool) -> Conversion {
+    match fits {
+        true => Conversion::Widening,
+        false => Conversion::Narrowing,
+    }
+}
+
+fn conversion(old: &SqlType, new: &SqlType) -> Conversion {
+    if old == new {
+        return Conversion::Unchanged;
+    }
+    if let (Some(a), Some(b)) = (old.numeric(), new.numeric()) {
+        return widening(b.0 >= a.0 && b.1 >= a.1);
+    }
+    if let (Some(a), Some(b)) = (old.text(), new.text()) {
+        if a.0 != b.0 {
+            return Conversion::Converting;
+        }
+        return widening(b.2 >= a.2 && (b.1 || !a.1));
+    }
+    if let (Some(a), Some(b)) = (old.temporal(), new.temporal()) {
+        return widening(b.0 >= a.0 && b.1 >= a.1);
+    }
+    match (old.base.as_str(), new.base.as_str()) {
+        ("date", "datetime2" | "datetimeoffset") => Conversion::Widening,
+        (_, "date") if old.temporal().is_some() => Conversion::Narrowing,
+        _ => Conversion::Converting,
+    }
+}
+
+/// SQL Server lets an indexed column grow in place only when it stays
+/// `varchar`, `nvarchar` or `varbinary` and the index isn't the primary key.
+fn index_allows(old: &SqlType, new: &SqlType, conversion: Conversion) -> bool {
+    old.base == new.base
+        && matches!(old.base.as_str(), "varchar" | "nvarchar" | "varbinary")
+        && matches!(conversion, Conversion::Unchanged | Conversion::Widening)
+}
+
+fn quoted(id: &str) -> String {
+    split_multipart(id)
+        .iter()
+        .map(|part| quote_name(part))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn alter_column(table_id: &str, column: &Column, new_type: &str) -> String {
+    format!(
+        "ALTER TABLE {} ALTER COLUMN {} {} {};",
+        quoted(table_id),
+        quote_name(&column.name),
+        new_type,
+        if column.is_nullable {
+            "NULL"
+        } else {
+            "NOT NULL"
+        }
+    )
+}
+
+/// The query counting rows whose value would not survive the change.
+fn verify_sql(
+    table_id: &str,
+    column: &str,
+    old: &SqlType,
+    new: &SqlType,
+    new_type: &str,
+) -> String {
+    let column = quote_name(column);
+    let condition = match (old.text(), new.text()) {
+        (Some(_), Some((binary, _, length))) if length != u64::MAX => {
+            let function = if binary { "DATALENGTH" } else { "LEN" };
+            format!("{}({}) > {}", function, column, length)
+        }
+        _ => format!(
+            "{} IS NOT NULL AND TRY_CONVERT({}, {}) IS NULL",
+            column, new_type, column
+        ),
+    };
+    format!(
+        "SELECT COUNT(*) FROM {} WHERE {};",
+        quoted(table_id),
+        condition
+    )
+}
+
+/// Every object that depends on `table_id`.`column_name`, classified by
+/// whether it blocks changing the column to `new_type`, and the steps to
+/// make the change. `indexes` are the indexes containing the column, from a
+/// live connection; without them only the primary key is known.
+pub fn type_change_impact(
+    graph: &SchemaGraph,
+    table_id: &str,
+    column_name: &str,
+    new_type: &str,
+    indexes: Option<&[ColumnIndex]>,
+) -> Result<TypeChangeImpact, String> {
+    let column = graph
+        .tables
+        .iter()
+        .find(|t| t.id == table_id)
+        .and_then(|t| {
+            t.columns
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(column_name))
+        })
+        .ok_or_else(|| {
+            t!(
+                "error-type-change-unknown-column",
+                table = table_id,
+                column = column_name
+            )
+        })?;
+    let new_type = new_type.trim();
+    let (old, new) = (parse_type(&column.data_type), parse_type(new_type));
+    let conversion = conversion(&old, &new);
+    let qualified = format!("{}.{}", table_id, column.name);
+    let mentions = Regex::new(&format!(
+        r"(?i)(?:^|[^\w@#$]){}(?:[^\w#$]|$)",
+        regex::escape(&column.name)
+    ))
+    .map_err(|e| e.to_string())?;
+    // A parameter or variable declared with the old type, e.g. `@id int`
+    let declares_old_type = Regex::new(&format!(
+        r"(?i)@\w+\s+(?:as\s+)?{}(?:[^\w(]|$)",
+        regex::escape(&column.data_type).replace(',', r"\s*,\s*")
+    ))
+    .map_err(|e| e.to_string())?;
+
+    let mut dependents = Vec::new();
+    let mut checklist = Vec::new();
+    let mut drops = Vec::new();
+    let mut recreates = Vec::new();
+    let mut follow_ups = Vec::new();
+    let mut skipped = None;
+
+    match indexes {
+        Some(indexes) => {
+            for index in indexes {
+                let kind = match index.is_primary_key {
+                    true => DependentKind::PrimaryKey,
+                    false => DependentKind::Index,
+                };
+                let binding = index.is_primary_key || !index_allows(&old, &new, conversion);
+                let mut parts = split_multipart(table_id);
+                parts.push(index.name.clone());
+                let id = object_id(&parts.iter().map(String::as_str).collect::<Vec<_>>());
+                let reason = match (kind, binding) {
+                    (DependentKind::PrimaryKey, _) => {
+                        t!("type-change-primary-key", column = qualified)
+                    }
+                    (_, true) => t!("type-change-index", index = index.name, column = qualified),
+                    (_, false) => t!(
+                        "type-change-index-resize",
+                        index = index.name,
+                        column = qualified
+                    ),
+                };
+                if binding {
+                    let sql = match index.is_primary_key || index.is_unique_constraint {
+                        true => format!(
+                            "ALTER TABLE {} DROP CONSTRAINT {};",
+                            quoted(table_id),
+                            quote_name(&index.name)
+                        ),
+                        false => format!(
+                            "DROP INDEX {} ON {};",
+                            quote_name(&index.name),
+                            quoted(table_id)
+                        ),
+                    };
+                    drops.push(ChecklistStep {
+                        description: t!("type-change-step-drop", object = id),
+                        sql: Some(sql),
+                    });
+                    recreates.push(ChecklistStep {
+                        description: t!("type-change-step-recreate", object = id),
+                        sql: None,
+                    });
+                }
+                dependents.push(TypeChangeDependent {
+                    object_id: id,
+                    kind,
+                    binding,
+                    reason,
+                });
+            }
+        }
+        None => {
+            skipped = Some(t!("type-change-needs-index-metadata"));
+            if column.is_primary_key {
+                dependents.push(TypeChangeDependent {
+                    object_id: table_id.to_string(),
+                    kind: DependentKind::PrimaryKey,
+                    binding: true,
+                    reason: t!("type-change-primary-key", column = qualified),
+                });
+                drops.push(ChecklistStep {
+                    description: t!(
+                        "type-change-step-drop",
+                        object = t!("type-change-primary-key-of", table = table_id)
+                    ),
+                    sql: None,
+                });
+                recreates.push(ChecklistStep {
+                    description: t!(
+                        "type-change-step-recreate",
+                        object = t!("type-change-primary-key-of", table = table_id)
+                    ),
+                    sql: None,
+                });
+            }
+        }
+    }
+
+    let mut keys = HashSet::new();
+    let mut other_columns = Vec::new();
+    for edge in graph.relationships.iter().filter(|r| !r.is_virtual) {
+        let (referencing, other, other_column) =
+            match (edge.from_column.as_deref(), edge.to_column.as_deref()) {
+                (Some(from), Some(to))
+                    if edge.from == table_id && from.eq_ignore_ascii_case(&column.name) =>
+                {
+                    (true, &edge.to, to)
+                }
+                (Some(from), Some(to))
+                    if edge.to == table_id && to.eq_ignore_ascii_case(&column.name) =>
+                {
+                    (false, &edge.from, from)
+                }
+                _ => continue,
+            };
+        if !keys.insert(edge.id.as_str()) {
+            continue;
+        }
+        let other_qualified = format!("{}.{}", other, other_column);
+        dependents.push(TypeChangeDependent {
+            object_id: edge.id.clone(),
+            kind: DependentKind::ForeignKey,
+            binding: true,
+            reason: t!(
+                "type-change-foreign-key",
+                key = edge.id,
+                other = other_qualified
+            ),
+        });
+        let owner = if referencing {
+            table_id
+        } else {
+            other.as_str()
+        };
+        drops.push(ChecklistStep {
+            description: t!("type-change-step-drop", object = edge.id),
+            sql: Some(format!(
+                "ALTER TABLE {} DROP CONSTRAINT {};",
+                quoted(owner),
+                quote_name(&edge.id)
+            )),
+        });
+        recreates.push(ChecklistStep {
+            description: t!("type-change-step-recreate", object = edge.id),
+            sql: None,
+        });
+        let other_column = graph.tables.iter().find(|t| &t.id == other).and_then(|t| {
+            t.columns
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(other_column))
+        });
+        if let Some(other_column) = other_column {
+            if parse_type(&other_column.data_type) != new {
+                other_columns.push(ChecklistStep {
+                    description: t!(
+                        "type-change-step-alter",
+                        column = other_qualified,
+                        sql_type = new_type
+                    ),
+                    sql: Some(alter_column(other, other_column, new_type)),
+                });
+            }
+        }
+    }
+
+    for view in &graph.views {
+        let selects = view.columns.iter().any(|c| {
+            c.source_columns
+                .iter()
+                .any(|s| s.table == table_id && s.column.eq_ignore_ascii_case(&column.name))
+        });
+        let mentions_column = view.referenced_tables.iter().any(|t| t == table_id)
+            && mentions.is_match(&view.definition);
+        if !selects && !mentions_column {
+            continue;
+        }
+        let binding = view.is_indexed || SCHEMABINDING.is_match(&view.definition);
+        let reason = match binding {
+            true => t!(
+                "type-change-schemabound",
+                object = view.id,
+                column = qualified
+            ),
+            false => t!("type-change-view", object = view.id, column = qualified),
+        };
+        if binding {
+            drops.push(ChecklistStep {
+                description: t!("type-change-step-unbind", object = view.id),
+                sql: None,
+            });
+            recreates.push(ChecklistStep {
+                description: t!("type-change-step-rebind", object = view.id),
+                sql: None,
+            });
+        } else {
+            follow_ups.push(ChecklistStep {
+                description: t!("type-change-step-refresh", object = view.id),
+                sql: Some(format!(
+                    "EXEC sp_refreshview N'{}';",
+                    quoted(&view.id).replace('\'', "''")
+                )),
+            });
+        }
+        dependents.push(TypeChangeDependent {
+            object_id: view.id.clone(),
+            kind: DependentKind::View,
+            binding,
+            reason,
+        });
+    }
+
+    let modules = graph
+        .scalar_functions
+        .iter()
+        .map(|f| {
+            let tables = f.referenced_tables.iter().chain(&f.affected_tables);
+            (
+                DependentKind::Function,
+                &f.id,
+                &f.definition,
+                tables.collect::<Vec<_>>(),
+            )
+        })
+        .chain(graph.stored_procedures.iter().map(|p| {
+            let tables = p.referenced_tables.iter().chain(&p.affected_tables);
+            (
+                DependentKind::Procedure,
+                &p.id,
+                &p.definition,
+                tables.collect(),
+            )
+        }))
+        .chain(graph.triggers.iter().map(|t| {
+            let tables = t.referenced_tables.iter().chain(&t.affected_tables);
+            let tables = tables.chain(std::iter::once(&t.table_id));
+            (
+                DependentKind::Trigger,
+                &t.id,
+                &t.definition,
+                tables.collect(),
+            )
+        }));
+    for (kind, id, definition, tables) in modules {
+        let on_table = tables.iter().any(|t| *t == table_id);
+        if !on_table || !mentions.is_match(definition) {
+            continue;
+        }
+        let binding = kind == DependentKind::Function && SCHEMABINDING.is_match(definition);
+        let reason = if binding {
+            t!("type-change-schemabound", object = id, column = qualified)
+        } else if conversion != Conversion::Unchanged && declares_old_type.is_match(definition) {
+            follow_ups.push(ChecklistStep {
+                description: t!(
+                    "type-change-step-review",
+                    object = id,
+                    sql_type = column.data_type
+                ),
+                sql: None,
+            });
+            t!(
+                "type-change-module-declares",
+                object = id,
+                column = qualified,
+                sql_type = column.data_type
+            )
+        } else {
+            t!("type-change-module", object = id, column = qualified)
+        };
+        if binding {
+            drops.push(ChecklistStep {
+                description: t!("type-change-step-unbind", object = id),
+                sql: None,
+            });
+            recreates.push(ChecklistStep {
+                description: t!("type-change-step-rebind", object = id),
+                sql: None,
+            });
+        }
+        dependents.push(TypeChangeDependent {
+            object_id: id.clone(),
+            kind,
+            binding,
+            reason,
+        });
+    }
+    dependents.sort_by(|a, b| {
+        b.binding
+            .cmp(&a.binding)
+            .then(a.kind.cmp(&b.kind))
+            .then(a.object_id.cmp(&b.object_id))
+    });
+
+    if matches!(conversion, Conversion::Narrowing | Conversion::Converting) {
+        checklist.push(ChecklistStep {
+            description: t!(
+                "type-change-step-verify",
+                column = qualified,
+                sql_type = new_type
+            ),
+            sql: Some(verify_sql(table_id, &column.name, &old, &new, new_type)),
+        });
+    }
+    checklist.extend(drops);
+    checklist.push(ChecklistStep {
+        description: t!(
+            "type-change-step-alter",
+            column = qualified,
+            sql_type = new_type
+        ),
+        sql: Some(alter_column(table_id, column, new_type)),
+    });
+    checklist.extend(other_columns);
+    // Recreate in reverse so objects come back after what they depend on
+    checklist.extend(recreates.into_iter().rev());
+    checklist.extend(follow_ups);
+
+    Ok(TypeChangeImpact {
+        table_id: table_id.to_string(),
+        column: column.name.clone(),
+        old_type: column.data_type.to_string(),
+        new_type: new_type.to_string(),
+        conversion,
+        dependents,
+        checklist,
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ColumnSource, RelationshipEdge, StoredProcedure, TableNode, ViewNode};
+
+    fn column(name: &str, data_type: &str, is_primary_key: bool) -> Column {
+        Column {
+            name: name.to_string(),
+            data_type: data_type.into(),
+            is_primary_key,
+            ..Default::default()
+        }
+    }
+
+    fn table(name: &str, columns: Vec<Column>) -> TableNode {
+        TableNode {
+            id: format!("dbo.{}", name),
+            name: name.to_string(),
+            schema: "dbo".into(),
+            columns,
+            source: None,
+            metadata: None,
+            change_capture: None,
+        }
+    }
+
+    fn graph() -> SchemaGraph {
+        SchemaGraph {
+            tables: vec![
+                table(
+                    "Orders",
+                    vec![
+                        column("Id", "int", true),
+                        column("CustomerId", "int", false),
+                        column("Code", "varchar(20)", false),
+                    ],
+                ),
+                table("Customers", vec![column("Id", "int", true)]),
+            ],
+            relationships: vec![RelationshipEdge {
+                id: "FK_Orders_Customers".to_string(),
+                from: "dbo.Orders".to_string(),
+                to: "dbo.Customers".to_string(),
+                from_column: Some("CustomerId".to_string()),
+                to_column: Some("Id".to_string()),
+                source: None,
+                is_virtual: false,
+                participation: None,
+            }],
+            views: vec![ViewNode {
+                id: "dbo.OrderTotals".to_string(),
+                name: "OrderTotals".to_string(),
+                schema: "dbo".into(),
+                columns: vec![Column {
+                    source_columns: vec![ColumnSource {
+                        table: "dbo.Orders".to_string(),
+                        column: "CustomerId".to_string(),
+                    }],
+                    ..column("CustomerId", "int", false)
+                }],
+                definition: "CREATE VIEW dbo.OrderTotals WITH SCHEMABINDING AS \
+                             SELECT CustomerId FROM dbo.Orders"
+                    .to_string(),
+                referenced_tables: vec!["dbo.Orders".to_string()],
+                source: None,
+                metadata: None,
+                is_indexed: false,
+            }],
+            stored_procedures: vec![StoredProcedure {
+                id: "dbo.GetOrders".to_string(),
+                name: "GetOrders".to_string(),
+                schema: "dbo".into(),
+                procedure_type: "SQL_STORED_PROCEDURE".into(),
+                parameters: Vec::new(),
+                definition: "CREATE PROCEDURE dbo.GetOrders @CustomerId int AS \
+                             SELECT * FROM dbo.Orders WHERE CustomerId = @CustomerId"
+                    .to_string(),
+                referenced_tables: vec!["dbo.Orders".to_string()],
+                affected_tables: Vec::new(),
+                analysis: Default::default(),
+                source: None,
+                metadata: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn dependents_are_classified_and_ordered_into_a_checklist() {
+        let impact =
+            type_change_impact(&graph(), "dbo.Orders", "customerid", "bigint", None).unwrap();
+        assert_eq!(impact.conversion, Conversion::Widening);
+        assert!(impact.skipped.is_some());
+        let dependents: Vec<(&str, bool)> = impact
+            .dependents
+            .iter()
+            .map(|d| (d.object_id.as_str(), d.binding))
+            .collect();
+        assert_eq!(
+            dependents,
+            vec![
+                ("FK_Orders_Customers", true),
+                ("dbo.OrderTotals", true),
+                ("dbo.GetOrders", false),
+            ]
+        );
+
+        let sql: Vec<&str> = impact
+            .checklist
+            .iter()
+            .filter_map(|s| s.sql.as_deref())
+            .collect();
+        assert_eq!(
+            sql,
+            vec![
+                "ALTER TABLE [dbo].[Orders] DROP CONSTRAINT [FK_Orders_Customers];",
+                "ALTER TABLE [dbo].[Orders] ALTER COLUMN [CustomerId] bigint NOT NULL;",
+                "ALTER TABLE [dbo].[Customers] ALTER COLUMN [Id] bigint NOT NULL;",
+            ]
+        );
+        // Unbind the view, alter both columns, restore in reverse, review the proc
+        assert_eq!(impact.checklist.len(), 7);
+    }
+
+    #[test]
+    fn indexed_columns_grow_in_place_but_not_shrink() {
+        let indexes = [ColumnIndex {
+            name: "IX_Orders_Code".to_string(),
+            is_primary_key: false,
+            is_unique_constraint: false,
+            is_included: false,
+        }];
+        let grow = type_change_impact(
+            &graph(),
+            "dbo.Orders",
+            "Code",
+            "varchar(40)",
+            Some(&indexes),
+        )
+        .unwrap();
+        assert_eq!(grow.conversion, Conversion::Widening);
+        assert!(!grow.dependents[0].binding);
+        assert_eq!(grow.checklist.len(), 1);
+
+        let shrink = type_change_impact(
+            &graph(),
+            "dbo.Orders",
+            "Code",
+            "varchar(10)",
+            Some(&indexes),
+        )
+        .unwrap();
+        assert_eq!(shrink.conversion, Conversion::Narrowing);
+        assert!(shrink.dependents[0].binding);
+        assert_eq!(
+            shrink.checklist[0].sql.as_deref(),
+            Some("SELECT COUNT(*) FROM [dbo].[Orders] WHERE LEN([Code]) > 10;")
+        );
+        assert_eq!(
+            conversion(&parse_type("int"), &parse_type("decimal(9,2)")),
+            Conversion::Narrowing
+        );
+        assert!(type_change_impact(&graph(), "dbo.Orders", "Missing", "int", None).is_err());
+    }
+}
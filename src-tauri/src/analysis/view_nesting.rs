@@ -0,0 +1,222 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::analysis::Severity;
+use crate::i18n::t;
+use crate::types::{SchemaGraph, ViewNode};
+
+/// Deepest view-on-view nesting accepted without a finding.
+pub const DEFAULT_MAX_VIEW_DEPTH: usize = 3;
+
+/// Nesting depth from which a chain is reported as high severity.
+const SEVERE_VIEW_DEPTH: usize = 5;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewDepth {
+    pub view_id: String,
+    /// Views in the longest chain starting here, counting this one; a view
+    /// over tables only has depth 1.
+    pub depth: usize,
+    /// That chain, outermost view first.
+    pub chain: Vec<String>,
+    pub is_indexed: bool,
+    /// Indexed views within `chain`, where expansion can stop.
+    pub indexed_in_chain: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NestedViewFinding {
+    pub severity: Severity,
+    pub view_id: String,
+    pub depth: usize,
+    pub chain: Vec<String>,
+    pub message: String,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewNestingReport {
+    /// Deepest first.
+    pub views: Vec<ViewDepth>,
+    pub findings: Vec<NestedViewFinding>,
+    pub indexed_view_count: usize,
+}
+
+/// Longest chain of views below `id`, memoized in `chains`. A reference
+/// back into the chain being walked ends it, so a cycle cannot recurse.
+/// The flag is set when that happened anywhere below `id`: such a chain
+/// depends on where the walk began, so it is not memoized.
+fn longest_chain<'a>(
+    id: &'a str,
+    views: &HashMap<&'a str, &'a ViewNode>,
+    chains: &mut HashMap<&'a str, Vec<&'a str>>,
+    walking: &mut Vec<&'a str>,
+) -> (Vec<&'a str>, bool) {
+    if let Some(chain) = chains.get(id) {
+        return (chain.clone(), false);
+    }
+    walking.push(id);
+    let mut deepest: Vec<&str> = Vec::new();
+    let mut cut = false;
+    for referenced in &views[id].referenced_tables {
+        let referenced = referenced.as_str();
+        if !views.contains_key(referenced) {
+            continue;
+        }
+        if walking.contains(&referenced) {
+            cut = true;
+            continue;
+        }
+        let (chain, chain_cut) = longest_chain(referenced, views, chains, walking);
+        cut |= chain_cut;
+        if chain.len() > deepest.len() {
+            deepest = chain;
+        }
+    }
+    walking.pop();
+
+    let mut chain = vec![id];
+    chain.extend(deepest);
+    if !cut {
+        chains.insert(id, chain.clone());
+    }
+    (chain, cut)
+}
+
+/// Nesting depth of every view, flagging chains deeper than `max_depth`.
+/// The optimizer expands nested views into one query, so deep chains tend
+/// to produce poor plans; indexed views in a chain are listed since reading
+/// them with `NOEXPAND` stops the expansion there.
+pub fn view_nesting(graph: &SchemaGraph, max_depth: usize) -> ViewNestingReport {
+    let views: HashMap<&str, &ViewNode> = graph.views.iter().map(|v| (v.id.as_str(), v)).collect();
+    let mut chains = HashMap::new();
+
+    let mut depths: Vec<ViewDepth> = graph
+        .views
+        .iter()
+        .map(|view| {
+            let (chain, _) = longest_chain(&view.id, &views, &mut chains, &mut Vec::new());
+            ViewDepth {
+                view_id: view.id.clone(),
+                depth: chain.len(),
+                indexed_in_chain: chain
+                    .iter()
+                    .filter(|id| views[*id].is_indexed)
+                    .map(|id| id.to_string())
+                    .collect(),
+                chain: chain.iter().map(|id| id.to_string()).collect(),
+                is_indexed: view.is_indexed,
+            }
+        })
+        .collect();
+    depths.sort_by(|a, b| {
+        b.depth
+            .cmp(&a.depth)
+            .then_with(|| a.view_id.cmp(&b.view_id))
+    });
+
+    let findings = depths
+        .iter()
+        .filter(|view| view.depth > max_depth)
+        .map(|view| {
+            let chain = view.chain.join(" → ");
+            NestedViewFinding {
+                severity: if view.depth >= SEVERE_VIEW_DEPTH {
+                    Severity::High
+                } else {
+                    Severity::Medium
+                },
+                view_id: view.view_id.clone(),
+                depth: view.depth,
+                chain: view.chain.clone(),
+                message: match view.indexed_in_chain.first() {
+                    Some(indexed) => t!(
+                        "view-nesting-deep-indexed",
+                        view = view.view_id,
+                        depth = view.depth,
+                        chain = chain,
+                        indexed = indexed
+                    ),
+                    None => t!(
+                        "view-nesting-deep",
+                        view = view.view_id,
+                        depth = view.depth,
+                        chain = chain
+                    ),
+                },
+            }
+        })
+        .collect();
+
+    ViewNestingReport {
+        indexed_view_count: graph.views.iter().filter(|v| v.is_indexed).count(),
+        views: depths,
+        findings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(name: &str, references: &[&str], is_indexed: bool) -> ViewNode {
+        ViewNode {
+            id: format!("dbo.{}", name),
+            name: name.to_string(),
+            schema: "dbo".into(),
+            columns: Vec::new(),
+            definition: String::new(),
+            referenced_tables: references.iter().map(|r| format!("dbo.{}", r)).collect(),
+            source: None,
+            metadata: None,
+            is_indexed,
+        }
+    }
+
+    #[test]
+    fn nested_chains_are_measured_and_flagged() {
+        let graph = SchemaGraph {
+            views: vec![
+                view("vBase", &["Orders"], true),
+                view("vMid", &["vBase", "Customers"], false),
+                view("vTop", &["vMid", "vBase"], false),
+                view("vReport", &["vTop"], false),
+                // A cycle must not recurse forever
+                view("vLoopA", &["vLoopB"], false),
+                view("vLoopB", &["vLoopA"], false),
+            ],
+            ..Default::default()
+        };
+
+        let report = view_nesting(&graph, 3);
+        let top = &report.views[0];
+        assert_eq!(top.view_id, "dbo.vReport");
+        assert_eq!(top.depth, 4);
+        assert_eq!(
+            top.chain,
+            vec!["dbo.vReport", "dbo.vTop", "dbo.vMid", "dbo.vBase"]
+        );
+        assert_eq!(top.indexed_in_chain, vec!["dbo.vBase"]);
+        assert_eq!(report.indexed_view_count, 1);
+
+        let loop_depth = report
+            .views
+            .iter()
+            .find(|v| v.view_id == "dbo.vLoopA")
+            .map(|v| v.depth);
+        assert_eq!(loop_depth, Some(2));
+        // vLoopB was first reached through vLoopA; its own walk is as deep
+        let loop_depth = report
+            .views
+            .iter()
+            .find(|v| v.view_id == "dbo.vLoopB")
+            .map(|v| v.depth);
+        assert_eq!(loop_depth, Some(2));
+
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].severity, Severity::Medium);
+        assert!(report.findings[0].message.contains("NOEXPAND"));
+    }
+}
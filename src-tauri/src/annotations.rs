@@ -0,0 +1,478 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use crate::db::{object_id, split_multipart};
+use crate::i18n::t;
+use crate::state::connection_file_stem;
+use crate::types::{Column, SchemaGraph};
+
+/// A local description of an object, or of one of its columns.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Annotation {
+    pub object_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    pub description: String,
+    pub updated_at: String,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+struct AnnotationFile {
+    annotations: Vec<Annotation>,
+}
+
+fn annotation_path(storage_path: &Path, server: &str, database: &str) -> PathBuf {
+    storage_path
+        .join("annotations")
+        .join(format!("{}.json", connection_file_stem(server, database)))
+}
+
+fn read_annotation_file(path: &Path) -> AnnotationFile {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_annotation_file(path: &Path, file: &AnnotationFile) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create annotation directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(file)
+        .map_err(|e| format!("Failed to serialize annotations: {}", e))?;
+    std::fs::write(path, content).map_err(|e| format!("Failed to write annotations: {}", e))
+}
+
+fn same_target(a: &Annotation, object_id: &str, column: Option<&str>) -> bool {
+    a.object_id == object_id
+        && match (a.column.as_deref(), column) {
+            (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+            (None, None) => true,
+            _ => false,
+        }
+}
+
+/// Insert or replace annotations, keeping existing ones in place.
+fn merge(file: &mut AnnotationFile, annotations: Vec<Annotation>) {
+    for annotation in annotations {
+        match file
+            .annotations
+            .iter_mut()
+            .find(|a| same_target(a, &annotation.object_id, annotation.column.as_deref()))
+        {
+            Some(existing) => *existing = annotation,
+            None => file.annotations.push(annotation),
+        }
+    }
+}
+
+pub fn list_annotations(storage_path: &Path, server: &str, database: &str) -> Vec<Annotation> {
+    read_annotation_file(&annotation_path(storage_path, server, database)).annotations
+}
+
+/// Describe an object or column. A blank description removes the annotation.
+pub fn set_annotation(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+    object_id: &str,
+    column: Option<String>,
+    description: &str,
+) -> Result<Vec<Annotation>, String> {
+    if object_id.trim().is_empty() {
+        return Err(t!("error-annotation-object-required"));
+    }
+    let column = column
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+    let description = description.trim();
+
+    let path = annotation_path(storage_path, server, database);
+    let mut file = read_annotation_file(&path);
+    if description.is_empty() {
+        file.annotations
+            .retain(|a| !same_target(a, object_id, column.as_deref()));
+    } else {
+        merge(
+            &mut file,
+            vec![Annotation {
+                object_id: object_id.to_string(),
+                column,
+                description: description.to_string(),
+                updated_at: Utc::now().to_rfc3339(),
+            }],
+        );
+    }
+    write_annotation_file(&path, &file)?;
+    Ok(file.annotations)
+}
+
+/// Which CSV columns (by header, case-insensitive) hold what.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationCsvMapping {
+    /// Object name, either qualified (`dbo.Orders`, `[dbo].[Orders]`) or
+    /// bare with the schema in `schema`.
+    pub object: String,
+    #[serde(default)]
+    pub schema: Option<String>,
+    /// Column name; rows with it blank describe the object itself.
+    #[serde(default)]
+    pub column: Option<String>,
+    pub description: String,
+    /// Report what would be imported without saving anything.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Also return a script writing the descriptions to the database as
+    /// `MS_Description` extended properties.
+    #[serde(default)]
+    pub extended_properties: bool,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum UnmatchedReason {
+    MissingObject,
+    MissingDescription,
+    UnknownObject,
+    /// A bare name found in several schemas.
+    AmbiguousObject,
+    UnknownColumn,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmatchedRow {
+    /// 1-based line in the file.
+    pub line: u64,
+    pub object: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    pub reason: UnmatchedReason,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationImportReport {
+    pub rows: usize,
+    pub matched: Vec<Annotation>,
+    pub unmatched: Vec<UnmatchedRow>,
+    /// False for a dry run.
+    pub saved: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extended_property_script: Option<String>,
+}
+
+/// An object annotations can be matched to.
+struct Target<'a> {
+    id: &'a str,
+    schema: &'a str,
+    name: &'a str,
+    /// `@level1type` for extended properties.
+    level1: &'static str,
+    columns: &'a [Column],
+}
+
+fn targets(graph: &SchemaGraph) -> Vec<Target<'_>> {
+    let mut targets: Vec<Target> = graph
+        .tables
+        .iter()
+        .map(|t| Target {
+            id: &t.id,
+            schema: &t.schema,
+            name: &t.name,
+            level1: "TABLE",
+            columns: &t.columns,
+        })
+        .collect();
+    targets.extend(graph.views.iter().map(|v| Target {
+        id: &v.id,
+        schema: &v.schema,
+        name: &v.name,
+        level1: "VIEW",
+        columns: &v.columns,
+    }));
+    targets.extend(graph.stored_procedures.iter().map(|p| Target {
+        id: &p.id,
+        schema: &p.schema,
+        name: &p.name,
+        level1: "PROCEDURE",
+        columns: &[],
+    }));
+    targets.extend(graph.scalar_functions.iter().map(|f| Target {
+        id: &f.id,
+        schema: &f.schema,
+        name: &f.name,
+        level1: "FUNCTION",
+        columns: &[],
+    }));
+    targets
+}
+
+fn resolve<'a, 'g>(
+    targets: &'a [Target<'g>],
+    schema: Option<&str>,
+    object: &str,
+) -> Result<&'a Target<'g>, UnmatchedReason> {
+    let mut parts = split_multipart(object);
+    if parts.len() == 1 {
+        if let Some(schema) = schema.filter(|s| !s.is_empty()) {
+            parts.insert(0, schema.to_string());
+        }
+    }
+    let found: Vec<&Target> = match parts.as_slice() {
+        [name] => targets
+            .iter()
+            .filter(|t| t.name.eq_ignore_ascii_case(name))
+            .collect(),
+        [.., schema, name] => {
+            let id = object_id(&[schema, name]);
+            targets
+                .iter()
+                .filter(|t| t.id.eq_ignore_ascii_case(&id))
+                .collect()
+        }
+        [] => Vec::new(),
+    };
+    match found.as_slice() {
+        [target] => Ok(target),
+        [] => Err(UnmatchedReason::UnknownObject),
+        _ => Err(UnmatchedReason::AmbiguousObject),
+    }
+}
+
+fn literal(value: &str) -> String {
+    format!("N'{}'", value.replace('\'', "''"))
+}
+
+/// Add or update `MS_Description` on the object or column.
+fn extended_property_statement(target: &Target, column: Option<&str>, description: &str) -> String {
+    let (level2_type, level2_name) = match column {
+        Some(column) => (literal("COLUMN"), literal(column)),
+        None => ("NULL".to_string(), "NULL".to_string()),
+    };
+    let levels = format!(
+        "N'SCHEMA', {}, {}, {}, {}, {}",
+        literal(target.schema),
+        literal(target.level1),
+        literal(target.name),
+        level2_type,
+        level2_name
+    );
+    let arguments = format!(
+        "@name = N'MS_Description', @value = {}, @level0type = N'SCHEMA', @level0name = {}, \
+         @level1type = {}, @level1name = {}, @level2type = {}, @level2name = {}",
+        literal(description),
+        literal(target.schema),
+        literal(target.level1),
+        literal(target.name),
+        level2_type,
+        level2_name
+    );
+    format!(
+        "IF EXISTS (SELECT 1 FROM sys.fn_listextendedproperty(N'MS_Description', {}))\n    \
+         EXEC sys.sp_updateextendedproperty {};\nELSE\n    EXEC sys.sp_addextendedproperty {};\n",
+        levels, arguments, arguments
+    )
+}
+
+fn header_index(headers: &csv::StringRecord, name: &str) -> Result<usize, String> {
+    headers
+        .iter()
+        .position(|h| h.trim().eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| t!("error-csv-missing-column", column = name))
+}
+
+/// Match the rows of a data dictionary CSV to the graph's objects and
+/// columns. Matched descriptions replace existing annotations unless
+/// `mapping.dry_run`; rows that match nothing are reported with the reason.
+pub fn import_annotations_csv(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+    graph: &SchemaGraph,
+    path: &Path,
+    mapping: &AnnotationCsvMapping,
+) -> Result<AnnotationImportReport, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_path(path)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("{}: {}", path.display(), e))?
+        .clone();
+    let object_index = header_index(&headers, &mapping.object)?;
+    let description_index = header_index(&headers, &mapping.description)?;
+    let schema_index = match &mapping.schema {
+        Some(name) => Some(header_index(&headers, name)?),
+        None => None,
+    };
+    let column_index = match &mapping.column {
+        Some(name) => Some(header_index(&headers, name)?),
+        None => None,
+    };
+
+    let targets = targets(graph);
+    let now = Utc::now().to_rfc3339();
+    let mut rows = 0;
+    let mut matched = Vec::new();
+    let mut script = String::new();
+    let mut unmatched = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("{}: {}", path.display(), e))?;
+        rows += 1;
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|i| record.get(i))
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        };
+        let object = field(Some(object_index));
+        let column = field(column_index);
+        let mut miss = |reason| {
+            unmatched.push(UnmatchedRow {
+                line: record.position().map(|p| p.line()).unwrap_or_default(),
+                object: object.unwrap_or_default().to_string(),
+                column: column.map(str::to_string),
+                reason,
+            })
+        };
+        let Some(object) = object else {
+            miss(UnmatchedReason::MissingObject);
+            continue;
+        };
+        let Some(description) = field(Some(description_index)) else {
+            miss(UnmatchedReason::MissingDescription);
+            continue;
+        };
+        let target = match resolve(&targets, field(schema_index), object) {
+            Ok(target) => target,
+            Err(reason) => {
+                miss(reason);
+                continue;
+            }
+        };
+        let column = match column {
+            Some(column) => match target
+                .columns
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(column))
+            {
+                Some(found) => Some(found.name.clone()),
+                None => {
+                    miss(UnmatchedReason::UnknownColumn);
+                    continue;
+                }
+            },
+            None => None,
+        };
+
+        if mapping.extended_properties {
+            let _ = writeln!(
+                script,
+                "{}",
+                extended_property_statement(target, column.as_deref(), description)
+            );
+        }
+        matched.push(Annotation {
+            object_id: target.id.to_string(),
+            column,
+            description: description.to_string(),
+            updated_at: now.clone(),
+        });
+    }
+
+    if !mapping.dry_run && !matched.is_empty() {
+        let store = annotation_path(storage_path, server, database);
+        let mut file = read_annotation_file(&store);
+        merge(&mut file, matched.clone());
+        write_annotation_file(&store, &file)?;
+    }
+
+    Ok(AnnotationImportReport {
+        rows,
+        matched,
+        unmatched,
+        saved: !mapping.dry_run,
+        extended_property_script: mapping.extended_properties.then_some(script),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+    use tempfile::tempdir;
+
+    #[test]
+    fn csv_rows_match_objects_and_columns() {
+        let dir = tempdir().expect("tempdir");
+        let root = dir.path();
+        let graph = generate_mock_schema("small");
+        let table = &graph.tables[0];
+        let column = &table.columns[0];
+
+        let csv = root.join("dictionary.csv");
+        std::fs::write(
+            &csv,
+            format!(
+                "Schema,Table,Column,Description\n\
+                 {schema},{name},,Main table\n\
+                 {schema},{name},{column},\"Key, it's unique\"\n\
+                 ,{schema}.{name},Missing,Nope\n\
+                 ,dbo.NoSuchTable,,Nope\n\
+                 {schema},{name},,\n",
+                schema = table.schema,
+                name = table.name.to_uppercase(),
+                column = column.name.to_lowercase()
+            ),
+        )
+        .unwrap();
+        let mut mapping = AnnotationCsvMapping {
+            object: "table".to_string(),
+            schema: Some("Schema".to_string()),
+            column: Some("Column".to_string()),
+            description: "Description".to_string(),
+            dry_run: true,
+            extended_properties: true,
+        };
+
+        let report = import_annotations_csv(root, "srv", "Sales", &graph, &csv, &mapping).unwrap();
+        assert_eq!(report.rows, 5);
+        assert_eq!(report.matched.len(), 2);
+        assert_eq!(report.matched[1].column.as_deref(), Some(&*column.name));
+        let reasons: Vec<(u64, UnmatchedReason)> = report
+            .unmatched
+            .iter()
+            .map(|u| (u.line, u.reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                (4, UnmatchedReason::UnknownColumn),
+                (5, UnmatchedReason::UnknownObject),
+                (6, UnmatchedReason::MissingDescription),
+            ]
+        );
+        assert!(report
+            .extended_property_script
+            .unwrap()
+            .contains("@value = N'Key, it''s unique'"));
+        assert!(list_annotations(root, "srv", "Sales").is_empty());
+
+        mapping.dry_run = false;
+        import_annotations_csv(root, "srv", "Sales", &graph, &csv, &mapping).unwrap();
+        let saved = set_annotation(root, "srv", "Sales", &table.id, None, "Replaced").unwrap();
+        assert_eq!(saved.len(), 2);
+        assert_eq!(saved[0].description, "Replaced");
+
+        mapping.description = "Notes".to_string();
+        assert!(import_annotations_csv(root, "srv", "Sales", &graph, &csv, &mapping).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::i18n::t;
+use crate::state::connection_file_stem;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Bookmark {
+    pub object_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+struct BookmarkFile {
+    bookmarks: Vec<Bookmark>,
+}
+
+fn bookmark_path(storage_path: &Path, server: &str, database: &str) -> PathBuf {
+    storage_path
+        .join("bookmarks")
+        .join(format!("{}.json", connection_file_stem(server, database)))
+}
+
+fn read_bookmark_file(path: &Path) -> BookmarkFile {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_bookmark_file(path: &Path, file: &BookmarkFile) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create bookmark directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(file)
+        .map_err(|e| format!("Failed to serialize bookmarks: {}", e))?;
+    std::fs::write(path, content).map_err(|e| format!("Failed to write bookmarks: {}", e))
+}
+
+/// Bookmarks for a connection in the order they were added.
+pub fn list_bookmarks(storage_path: &Path, server: &str, database: &str) -> Vec<Bookmark> {
+    read_bookmark_file(&bookmark_path(storage_path, server, database)).bookmarks
+}
+
+/// Bookmark an object, or relabel it if it is already bookmarked (keeping
+/// its place in the list). A blank label clears it.
+pub fn set_bookmark(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+    object_id: &str,
+    label: Option<String>,
+) -> Result<Vec<Bookmark>, String> {
+    if object_id.trim().is_empty() {
+        return Err(t!("error-bookmark-object-required"));
+    }
+    let label = label
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty());
+
+    let path = bookmark_path(storage_path, server, database);
+    let mut file = read_bookmark_file(&path);
+    match file.bookmarks.iter_mut().find(|b| b.object_id == object_id) {
+        Some(bookmark) => bookmark.label = label,
+        None => file.bookmarks.push(Bookmark {
+            object_id: object_id.to_string(),
+            label,
+            created_at: Utc::now().to_rfc3339(),
+        }),
+    }
+    write_bookmark_file(&path, &file)?;
+    Ok(file.bookmarks)
+}
+
+pub fn remove_bookmark(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+    object_id: &str,
+) -> Result<Vec<Bookmark>, String> {
+    let path = bookmark_path(storage_path, server, database);
+    let mut file = read_bookmark_file(&path);
+    let before = file.bookmarks.len();
+    file.bookmarks.retain(|b| b.object_id != object_id);
+    if file.bookmarks.len() == before {
+        return Err(t!("error-bookmark-not-found", id = object_id));
+    }
+    write_bookmark_file(&path, &file)?;
+    Ok(file.bookmarks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn bookmarks_keep_order_and_relabel_in_place() {
+        let dir = tempdir().expect("tempdir");
+        let root = dir.path();
+
+        set_bookmark(root, "srv", "Sales", "dbo.Orders", None).unwrap();
+        set_bookmark(root, "srv", "Sales", "dbo.Customers", Some("CRM".into())).unwrap();
+        let listed = set_bookmark(
+            root,
+            "srv",
+            "Sales",
+            "dbo.Orders",
+            Some(" Hot path ".into()),
+        )
+        .unwrap();
+
+        let ids: Vec<(&str, Option<&str>)> = listed
+            .iter()
+            .map(|b| (b.object_id.as_str(), b.label.as_deref()))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                ("dbo.Orders", Some("Hot path")),
+                ("dbo.Customers", Some("CRM"))
+            ]
+        );
+        assert!(list_bookmarks(root, "srv", "Other").is_empty());
+
+        let remaining = remove_bookmark(root, "SRV", "sales", "dbo.Orders").unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert!(remove_bookmark(root, "srv", "Sales", "dbo.Orders").is_err());
+        assert!(set_bookmark(root, "srv", "Sales", " ", None).is_err());
+    }
+}
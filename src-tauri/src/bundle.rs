@@ -0,0 +1,268 @@
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::annotations::Annotation;
+use crate::bookmarks::Bookmark;
+use crate::export_themes::ExportTheme;
+use crate::i18n::t;
+use crate::layouts::DiagramLayout;
+use crate::types::SchemaGraph;
+
+/// Bumped when the bundle layout changes incompatibly.
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const SCHEMA_ENTRY: &str = "schema.json";
+const LAYOUT_ENTRY: &str = "layout.json";
+const ANNOTATIONS_ENTRY: &str = "annotations.json";
+const DESCRIPTIONS_ENTRY: &str = "descriptions.json";
+const THEME_ENTRY: &str = "theme.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub title: String,
+    pub created_at: String,
+}
+
+/// Everything needed to view a diagram without a database: a `.monoclepkg`
+/// zip holding one JSON entry per part.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagramBundle {
+    pub manifest: BundleManifest,
+    pub graph: SchemaGraph,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<DiagramLayout>,
+    /// Bookmarked objects and their labels.
+    #[serde(default)]
+    pub annotations: Vec<Bookmark>,
+    /// Local object and column descriptions.
+    #[serde(default)]
+    pub descriptions: Vec<Annotation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<ExportTheme>,
+}
+
+/// Drop what identifies the connection rather than the schema: phase
+/// records carry driver errors, which name the server and login, the default
+/// schema and job and object owners are logins, and job step commands can
+/// hold connection strings and passwords.
+fn strip_connection_details(graph: &mut SchemaGraph) {
+    graph.load_phases.clear();
+    graph.default_schema = None;
+    for job in &mut graph.agent_jobs {
+        job.owner = None;
+        for step in &mut job.steps {
+            step.command.clear();
+        }
+    }
+    let metadata = graph
+        .tables
+        .iter_mut()
+        .map(|t| &mut t.metadata)
+        .chain(graph.views.iter_mut().map(|v| &mut v.metadata))
+        .chain(graph.triggers.iter_mut().map(|t| &mut t.metadata))
+        .chain(graph.stored_procedures.iter_mut().map(|p| &mut p.metadata))
+        .chain(graph.scalar_functions.iter_mut().map(|f| &mut f.metadata));
+    for metadata in metadata.flatten() {
+        metadata.owner = None;
+    }
+}
+
+pub fn build_bundle(
+    title: &str,
+    mut graph: SchemaGraph,
+    layout: Option<DiagramLayout>,
+    bookmarks: Vec<Bookmark>,
+    annotations: Vec<Annotation>,
+    theme: Option<ExportTheme>,
+) -> DiagramBundle {
+    strip_connection_details(&mut graph);
+    DiagramBundle {
+        manifest: BundleManifest {
+            format_version: BUNDLE_FORMAT_VERSION,
+            title: title.trim().to_string(),
+            created_at: Utc::now().to_rfc3339(),
+        },
+        graph,
+        layout,
+        annotations: bookmarks,
+        descriptions: annotations,
+        theme,
+    }
+}
+
+fn io_error(e: impl std::fmt::Display) -> String {
+    t!("error-bundle-write", reason = e)
+}
+
+fn write_entry<T: Serialize>(
+    zip: &mut ZipWriter<File>,
+    name: &str,
+    value: &T,
+) -> Result<(), String> {
+    let content =
+        serde_json::to_vec(value).map_err(|e| t!("error-bundle-serialize", reason = e))?;
+    zip.start_file(name, SimpleFileOptions::default())
+        .map_err(io_error)?;
+    zip.write_all(&content).map_err(io_error)
+}
+
+pub fn write_bundle(path: &Path, bundle: &DiagramBundle) -> Result<(), String> {
+    let mut zip = ZipWriter::new(File::create(path).map_err(io_error)?);
+    write_entry(&mut zip, MANIFEST_ENTRY, &bundle.manifest)?;
+    write_entry(&mut zip, SCHEMA_ENTRY, &bundle.graph)?;
+    write_entry(&mut zip, ANNOTATIONS_ENTRY, &bundle.annotations)?;
+    write_entry(&mut zip, DESCRIPTIONS_ENTRY, &bundle.descriptions)?;
+    if let Some(layout) = &bundle.layout {
+        write_entry(&mut zip, LAYOUT_ENTRY, layout)?;
+    }
+    if let Some(theme) = &bundle.theme {
+        write_entry(&mut zip, THEME_ENTRY, theme)?;
+    }
+    zip.finish().map_err(io_error)?.flush().map_err(io_error)
+}
+
+fn read_entry<T: DeserializeOwned>(
+    archive: &mut ZipArchive<File>,
+    name: &str,
+) -> Result<Option<T>, String> {
+    let mut file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(t!("error-bundle-invalid", reason = e)),
+    };
+    let mut content = Vec::new();
+    file.read_to_end(&mut content)
+        .map_err(|e| t!("error-bundle-read", reason = e))?;
+    serde_json::from_slice(&content)
+        .map(Some)
+        .map_err(|e| t!("error-bundle-invalid-entry", entry = name, reason = e))
+}
+
+fn missing_entry(name: &str) -> String {
+    t!("error-bundle-missing-entry", entry = name)
+}
+
+pub fn read_bundle(path: &Path) -> Result<DiagramBundle, String> {
+    let file = File::open(path).map_err(|e| t!("error-bundle-read", reason = e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| t!("error-bundle-invalid", reason = e))?;
+
+    let manifest: BundleManifest =
+        read_entry(&mut archive, MANIFEST_ENTRY)?.ok_or_else(|| missing_entry(MANIFEST_ENTRY))?;
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(t!(
+            "error-bundle-newer-version",
+            version = manifest.format_version
+        ));
+    }
+    let graph =
+        read_entry(&mut archive, SCHEMA_ENTRY)?.ok_or_else(|| missing_entry(SCHEMA_ENTRY))?;
+
+    Ok(DiagramBundle {
+        manifest,
+        graph,
+        layout: read_entry(&mut archive, LAYOUT_ENTRY)?,
+        annotations: read_entry(&mut archive, ANNOTATIONS_ENTRY)?.unwrap_or_default(),
+        descriptions: read_entry(&mut archive, DESCRIPTIONS_ENTRY)?.unwrap_or_default(),
+        theme: read_entry(&mut archive, THEME_ENTRY)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+    use crate::export_themes::builtin_export_themes;
+    use crate::types::{AgentJob, AgentJobStep, LoadPhase, LoadPhaseStatus, ObjectMetadata};
+    use tempfile::tempdir;
+
+    #[test]
+    fn bundle_round_trips_without_connection_details() {
+        let mut graph = generate_mock_schema("small");
+        graph.load_phases.push(LoadPhase {
+            phase: "tables".to_string(),
+            status: LoadPhaseStatus::Failed,
+            attempts: 1,
+            duration_ms: 1.0,
+            error: Some("Login failed for user 'sa' on sql01".to_string()),
+        });
+        graph.default_schema = Some("sales_reader".to_string());
+        graph.tables[0].metadata = Some(ObjectMetadata {
+            owner: Some("CORP\\dba".to_string()),
+            created_at: Some("2024-01-01T00:00:00".to_string()),
+            modified_at: None,
+        });
+        graph.agent_jobs.push(AgentJob {
+            id: "job:Nightly ETL".to_string(),
+            name: "Nightly ETL".to_string(),
+            enabled: true,
+            description: None,
+            owner: Some("CORP\\etl".to_string()),
+            schedules: Vec::new(),
+            steps: vec![AgentJobStep {
+                step_id: 1,
+                name: "Extract".to_string(),
+                subsystem: "CmdExec".to_string(),
+                database: None,
+                command: "sqlcmd -S sql01 -U etl -P s3cret".to_string(),
+                referenced_tables: Vec::new(),
+                affected_tables: Vec::new(),
+                executed_procedures: Vec::new(),
+            }],
+            referenced_tables: Vec::new(),
+            affected_tables: Vec::new(),
+            executed_procedures: Vec::new(),
+            source: None,
+        });
+        let annotations = vec![Bookmark {
+            object_id: graph.tables[0].id.clone(),
+            label: Some("Start here".to_string()),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+        }];
+        let descriptions = vec![Annotation {
+            object_id: graph.tables[0].id.clone(),
+            column: None,
+            description: "One row per order".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+        }];
+        let theme = builtin_export_themes().into_iter().next();
+        let bundle = build_bundle(
+            " Sales model ",
+            graph.clone(),
+            None,
+            annotations,
+            descriptions,
+            theme,
+        );
+
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("sales.monoclepkg");
+        write_bundle(&path, &bundle).unwrap();
+
+        let read = read_bundle(&path).unwrap();
+        assert_eq!(read.manifest.title, "Sales model");
+        assert_eq!(read.graph.tables.len(), graph.tables.len());
+        assert!(read.graph.load_phases.is_empty());
+        assert!(read.graph.default_schema.is_none());
+        assert!(read.graph.agent_jobs[0].owner.is_none());
+        let metadata = read.graph.tables[0].metadata.as_ref().unwrap();
+        assert!(metadata.owner.is_none());
+        assert!(metadata.created_at.is_some());
+        assert!(read.graph.agent_jobs[0].steps[0].command.is_empty());
+        assert_eq!(read.annotations, bundle.annotations);
+        assert_eq!(read.descriptions, bundle.descriptions);
+        assert_eq!(read.theme, bundle.theme);
+        assert!(read.layout.is_none());
+
+        assert!(read_bundle(&dir.path().join("missing.monoclepkg")).is_err());
+    }
+}
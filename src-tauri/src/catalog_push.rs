@@ -0,0 +1,751 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::Url;
+
+use crate::annotations::Annotation;
+use crate::credentials::store_catalog_token;
+use crate::i18n::t;
+use crate::state::connection_file_stem;
+use crate::types::{Classification, Column, DatabaseEngine, SchemaGraph};
+
+/// Tables per Purview bulk request; Atlas rejects very large payloads.
+const PURVIEW_BATCH: usize = 50;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Response text kept in an error message.
+const ERROR_BODY_CHARS: usize = 300;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum CatalogKind {
+    /// Microsoft Purview, through its Atlas API.
+    Purview,
+    /// DataHub's metadata service (GMS).
+    DataHub,
+}
+
+/// Where a connection's schema is published.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogTarget {
+    pub kind: CatalogKind,
+    /// `https://<account>.purview.azure.com`, or the DataHub GMS URL.
+    pub endpoint: String,
+    /// Sent as a bearer token: an Entra ID access token for Purview, a
+    /// personal access token for DataHub. Only set on its way in, where
+    /// saving moves it to the system keychain (empty forgets it, absent
+    /// keeps the stored one), and just before a push.
+    #[serde(default, skip_serializing)]
+    pub token: Option<String>,
+    /// Whether the keychain holds a token for this connection.
+    #[serde(default)]
+    pub has_token: bool,
+    /// DataHub's dataset environment; `PROD` when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+}
+
+/// One REST call of a push.
+#[derive(Debug, Clone)]
+pub struct CatalogRequest {
+    pub url: String,
+    pub body: Value,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogPushResult {
+    pub datasets: usize,
+    pub requests: usize,
+}
+
+fn target_path(storage_path: &Path, server: &str, database: &str) -> PathBuf {
+    storage_path
+        .join("catalogs")
+        .join(format!("{}.json", connection_file_stem(server, database)))
+}
+
+pub fn read_catalog_target(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+) -> Option<CatalogTarget> {
+    let content = std::fs::read_to_string(target_path(storage_path, server, database)).ok()?;
+    let mut target: CatalogTarget = serde_json::from_str(&content).ok()?;
+    // Files from before the keychain hold the token itself
+    target.has_token |= target.token.is_some();
+    Some(target)
+}
+
+/// Save the connection's catalog, or forget it when `target` is `None`. The
+/// token goes to the keychain; the file only records that there is one.
+pub fn save_catalog_target(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+    target: Option<CatalogTarget>,
+) -> Result<Option<CatalogTarget>, String> {
+    let path = target_path(storage_path, server, database);
+    let existing = read_catalog_target(storage_path, server, database);
+    let Some(mut target) = target else {
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove catalog settings: {}", e))?;
+        }
+        if let Err(e) = store_catalog_token(server, database, "") {
+            eprintln!("Failed to forget catalog token: {}", e);
+        }
+        return Ok(None);
+    };
+    target.endpoint = target.endpoint.trim().trim_end_matches('/').to_string();
+    if !(target.endpoint.starts_with("https://") || target.endpoint.starts_with("http://")) {
+        return Err(t!("error-catalog-endpoint", endpoint = target.endpoint));
+    }
+    // A token left in an old file moves to the keychain on the next save
+    let token = target
+        .token
+        .take()
+        .or_else(|| existing.as_ref().and_then(|e| e.token.clone()));
+    match token {
+        Some(token) => {
+            store_catalog_token(server, database, token.trim())?;
+            target.has_token = !token.trim().is_empty();
+        }
+        None => target.has_token = existing.is_some_and(|e| e.has_token),
+    }
+    target.environment = target
+        .environment
+        .map(|env| env.trim().to_uppercase())
+        .filter(|env| !env.is_empty());
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create catalog directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&target)
+        .map_err(|e| format!("Failed to serialize catalog settings: {}", e))?;
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Failed to write catalog settings: {}", e))?;
+    Ok(Some(target))
+}
+
+/// A table or view with what the catalog is told about it.
+struct Dataset<'a> {
+    id: &'a str,
+    schema: &'a str,
+    name: &'a str,
+    is_view: bool,
+    columns: &'a [Column],
+}
+
+type Key = (String, Option<String>);
+
+fn key(object_id: &str, column: Option<&str>) -> Key {
+    (object_id.to_string(), column.map(str::to_lowercase))
+}
+
+struct Context<'a> {
+    engine: DatabaseEngine,
+    server: &'a str,
+    database: &'a str,
+    descriptions: HashMap<Key, &'a str>,
+    classifications: HashMap<Key, &'a Classification>,
+}
+
+impl<'a> Context<'a> {
+    fn description(&self, object_id: &str, column: Option<&str>) -> Option<&'a str> {
+        self.descriptions.get(&key(object_id, column)).copied()
+    }
+
+    fn classification(&self, object_id: &str, column: Option<&str>) -> Option<&'a Classification> {
+        self.classifications.get(&key(object_id, column)).copied()
+    }
+}
+
+fn datasets(graph: &SchemaGraph) -> Vec<Dataset<'_>> {
+    let tables = graph.tables.iter().map(|t| Dataset {
+        id: &t.id,
+        schema: &t.schema,
+        name: &t.name,
+        is_view: false,
+        columns: &t.columns,
+    });
+    let views = graph.views.iter().map(|v| Dataset {
+        id: &v.id,
+        schema: &v.schema,
+        name: &v.name,
+        is_view: true,
+        columns: &v.columns,
+    });
+    tables.chain(views).collect()
+}
+
+/// Atlas labels allow letters, digits, `_` and `-` only.
+fn label(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn purview_labels(classification: Option<&Classification>) -> Vec<String> {
+    classification
+        .map(|c| c.tags.iter().chain(&c.terms).map(|n| label(n)).collect())
+        .unwrap_or_default()
+}
+
+/// Host and SQL Server instance of a server name, without port or
+/// protocol prefix.
+fn host_and_instance(server: &str) -> (String, String) {
+    let server = server.trim().trim_start_matches("tcp:");
+    let server = server.split(',').next().unwrap_or(server);
+    match server.split_once('\\') {
+        Some((host, instance)) => (host.to_string(), instance.to_string()),
+        None => {
+            let host = server.split(':').next().unwrap_or(server);
+            (host.to_string(), "MSSQLSERVER".to_string())
+        }
+    }
+}
+
+/// Entities in the shape of Purview's SQL Server and MySQL scanners, so a
+/// pushed table lines up with one a scan finds later.
+fn purview_requests(
+    target: &CatalogTarget,
+    ctx: &Context,
+    graph: &SchemaGraph,
+) -> Vec<CatalogRequest> {
+    let (host, instance) = host_and_instance(ctx.server);
+    let (prefix, root) = match ctx.engine {
+        DatabaseEngine::SqlServer => (
+            "mssql",
+            format!("mssql://{}/{}/{}", host, instance, ctx.database),
+        ),
+        DatabaseEngine::MySql => ("mysql", format!("mysql://{}/{}", host, ctx.database)),
+    };
+    let url = format!("{}/catalog/api/atlas/v2/entity/bulk", target.endpoint);
+
+    datasets(graph)
+        .chunks(PURVIEW_BATCH)
+        .map(|chunk| {
+            let mut entities = Vec::new();
+            for dataset in chunk {
+                let type_name = format!(
+                    "{}_{}",
+                    prefix,
+                    if dataset.is_view { "view" } else { "table" }
+                );
+                let qualified_name = match ctx.engine {
+                    DatabaseEngine::SqlServer => {
+                        format!("{}/{}/{}", root, dataset.schema, dataset.name)
+                    }
+                    DatabaseEngine::MySql => format!("{}/{}", root, dataset.name),
+                };
+                entities.push(json!({
+                    "typeName": type_name,
+                    "attributes": {
+                        "qualifiedName": qualified_name,
+                        "name": dataset.name,
+                        "userDescription": ctx.description(dataset.id, None),
+                    },
+                    "labels": purview_labels(ctx.classification(dataset.id, None)),
+                }));
+                let parent = if dataset.is_view { "view" } else { "table" };
+                for column in dataset.columns {
+                    let column_key = Some(column.name.as_str());
+                    entities.push(json!({
+                        "typeName": format!("{}_column", prefix),
+                        "attributes": {
+                            "qualifiedName": format!("{}#{}", qualified_name, column.name),
+                            "name": column.name,
+                            "data_type": &*column.data_type,
+                            "userDescription": ctx.description(dataset.id, column_key),
+                        },
+                        "relationshipAttributes": {
+                            parent: {
+                                "typeName": type_name,
+                                "uniqueAttributes": { "qualifiedName": qualified_name },
+                            },
+                        },
+                        "labels": purview_labels(ctx.classification(dataset.id, column_key)),
+                    }));
+                }
+            }
+            CatalogRequest {
+                url: url.clone(),
+                body: json!({ "entities": entities }),
+            }
+        })
+        .collect()
+}
+
+/// DataHub's field type for a native SQL type.
+fn datahub_type(data_type: &str) -> &'static str {
+    let base = data_type
+        .split(|c: char| c == '(' || c.is_whitespace())
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    match base.as_str() {
+        "bit" | "bool" | "boolean" => "BooleanType",
+        "tinyint" | "smallint" | "mediumint" | "int" | "integer" | "bigint" | "decimal"
+        | "numeric" | "float" | "real" | "double" | "money" | "smallmoney" => "NumberType",
+        "char" | "nchar" | "varchar" | "nvarchar" | "text" | "ntext" | "tinytext"
+        | "mediumtext" | "longtext" | "uniqueidentifier" | "xml" | "json" | "enum" | "set" => {
+            "StringType"
+        }
+        "date" | "datetime" | "datetime2" | "smalldatetime" | "datetimeoffset" | "timestamp" => {
+            "DateType"
+        }
+        "time" => "TimeType",
+        "binary" | "varbinary" | "image" | "blob" | "tinyblob" | "mediumblob" | "longblob"
+        | "rowversion" => "BytesType",
+        _ => "NullType",
+    }
+}
+
+fn datahub_tags(classification: Option<&Classification>) -> Option<Value> {
+    let tags: Vec<Value> = classification?
+        .tags
+        .iter()
+        .map(|tag| json!({ "tag": format!("urn:li:tag:{}", tag) }))
+        .collect();
+    (!tags.is_empty()).then(|| json!({ "tags": tags }))
+}
+
+fn datahub_terms(classification: Option<&Classification>) -> Option<Value> {
+    let terms: Vec<Value> = classification?
+        .terms
+        .iter()
+        .map(|term| json!({ "urn": format!("urn:li:glossaryTerm:{}", term) }))
+        .collect();
+    (!terms.is_empty()).then(|| {
+        json!({
+            "terms": terms,
+            "auditStamp": { "time": 0, "actor": "urn:li:corpuser:datahub" },
+        })
+    })
+}
+
+/// One `ingestProposal` call per aspect, the REST emitter's own layout.
+fn datahub_requests(
+    target: &CatalogTarget,
+    ctx: &Context,
+    graph: &SchemaGraph,
+) -> Vec<CatalogRequest> {
+    let platform = match ctx.engine {
+        DatabaseEngine::SqlServer => "urn:li:dataPlatform:mssql",
+        DatabaseEngine::MySql => "urn:li:dataPlatform:mysql",
+    };
+    let environment = target.environment.as_deref().unwrap_or("PROD");
+    let url = format!("{}/aspects?action=ingestProposal", target.endpoint);
+
+    let mut requests = Vec::new();
+    for dataset in datasets(graph) {
+        let name = match ctx.engine {
+            DatabaseEngine::SqlServer => {
+                format!("{}.{}.{}", ctx.database, dataset.schema, dataset.name)
+            }
+            DatabaseEngine::MySql => format!("{}.{}", ctx.database, dataset.name),
+        };
+        let urn = format!("urn:li:dataset:({},{},{})", platform, name, environment);
+        let fields: Vec<Value> = dataset
+            .columns
+            .iter()
+            .map(|column| {
+                let column_key = Some(column.name.as_str());
+                let classification = ctx.classification(dataset.id, column_key);
+                let mut field = json!({
+                    "fieldPath": column.name,
+                    "nativeDataType": &*column.data_type,
+                    "type": { "type": { format!("com.linkedin.schema.{}", datahub_type(&column.data_type)): {} } },
+                    "nullable": column.is_nullable,
+                    "isPartOfKey": column.is_primary_key,
+                });
+                if let Some(description) = ctx.description(dataset.id, column_key) {
+                    field["description"] = json!(description);
+                }
+                if let Some(tags) = datahub_tags(classification) {
+                    field["globalTags"] = tags;
+                }
+                if let Some(terms) = datahub_terms(classification) {
+                    field["glossaryTerms"] = terms;
+                }
+                field
+            })
+            .collect();
+
+        let mut properties = json!({ "name": dataset.name });
+        if let Some(description) = ctx.description(dataset.id, None) {
+            properties["description"] = json!(description);
+        }
+        let classification = ctx.classification(dataset.id, None);
+        let mut aspects = vec![
+            ("datasetProperties", properties),
+            (
+                "subTypes",
+                json!({ "typeNames": [if dataset.is_view { "View" } else { "Table" }] }),
+            ),
+            (
+                "schemaMetadata",
+                json!({
+                    "schemaName": name,
+                    "platform": platform,
+                    "version": 0,
+                    "hash": "",
+                    "platformSchema": { "com.linkedin.schema.MySqlDDL": { "tableSchema": "" } },
+                    "fields": fields,
+                }),
+            ),
+        ];
+        // Sent even when empty, so tags removed in Monocle are removed there too
+        aspects.push((
+            "globalTags",
+            datahub_tags(classification).unwrap_or_else(|| json!({ "tags": [] })),
+        ));
+        if let Some(terms) = datahub_terms(classification) {
+            aspects.push(("glossaryTerms", terms));
+        }
+
+        for (aspect, value) in aspects {
+            requests.push(CatalogRequest {
+                url: url.clone(),
+                body: json!({
+                    "proposal": {
+                        "entityType": "dataset",
+                        "entityUrn": urn,
+                        "changeType": "UPSERT",
+                        "aspectName": aspect,
+                        "aspect": { "contentType": "application/json", "value": value.to_string() },
+                    }
+                }),
+            });
+        }
+    }
+    requests
+}
+
+/// The calls that publish `graph`'s tables and views, with their columns,
+/// annotations, tags and glossary terms, to `target`.
+pub fn catalog_requests(
+    target: &CatalogTarget,
+    graph: &SchemaGraph,
+    annotations: &[Annotation],
+    engine: DatabaseEngine,
+    server: &str,
+    database: &str,
+) -> Vec<CatalogRequest> {
+    let ctx = Context {
+        engine,
+        server,
+        database,
+        descriptions: annotations
+            .iter()
+            .map(|a| {
+                (
+                    key(&a.object_id, a.column.as_deref()),
+                    a.description.as_str(),
+                )
+            })
+            .collect(),
+        classifications: graph
+            .classifications
+            .iter()
+            .map(|c| (key(&c.object_id, c.column.as_deref()), c))
+            .collect(),
+    };
+    match target.kind {
+        CatalogKind::Purview => purview_requests(target, &ctx, graph),
+        CatalogKind::DataHub => datahub_requests(target, &ctx, graph),
+    }
+}
+
+/// Send `requests` in order through `proxy`, if any, stopping at the first
+/// failure. Blocking.
+pub fn send_catalog_requests(
+    target: &CatalogTarget,
+    requests: &[CatalogRequest],
+    proxy: Option<&Url>,
+) -> Result<usize, String> {
+    let mut agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT);
+    if let Some(proxy) = proxy {
+        let proxy =
+            ureq::Proxy::new(proxy.as_str()).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+        agent = agent.proxy(proxy);
+    }
+    let agent = agent.build();
+    for (sent, request) in requests.iter().enumerate() {
+        let mut call = agent
+            .post(&request.url)
+            .set("X-RestLi-Protocol-Version", "2.0.0");
+        if let Some(token) = &target.token {
+            call = call.set("Authorization", &format!("Bearer {}", token));
+        }
+        if let Err(e) = call.send_json(&request.body) {
+            let reason = match e {
+                ureq::Error::Status(status, response) => {
+                    let body = response.into_string().unwrap_or_default();
+                    let body: String = body.chars().take(ERROR_BODY_CHARS).collect();
+                    format!("HTTP {}: {}", status, body.trim())
+                }
+                other => other.to_string(),
+            };
+            return Err(t!(
+                "error-catalog-push",
+                sent = sent,
+                total = requests.len(),
+                reason = reason
+            ));
+        }
+    }
+    Ok(requests.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+    use std::io::{Read, Write};
+    use tempfile::tempdir;
+
+    fn annotated() -> (SchemaGraph, Vec<Annotation>) {
+        let mut graph = generate_mock_schema("small");
+        let table = graph.tables[0].clone();
+        graph.classifications.push(Classification {
+            object_id: table.id.clone(),
+            column: Some(table.columns[0].name.to_uppercase()),
+            terms: vec!["Customer".to_string()],
+            tags: vec!["PII".to_string(), "Contact info".to_string()],
+        });
+        let annotations = vec![Annotation {
+            object_id: table.id.clone(),
+            column: None,
+            description: "Everyone who has bought something".to_string(),
+            updated_at: String::new(),
+        }];
+        (graph, annotations)
+    }
+
+    #[test]
+    fn purview_entities_follow_the_scanner_names() {
+        let (graph, annotations) = annotated();
+        let target = CatalogTarget {
+            kind: CatalogKind::Purview,
+            endpoint: "https://contoso.purview.azure.com".to_string(),
+            token: None,
+            has_token: false,
+            environment: None,
+        };
+        let requests = catalog_requests(
+            &target,
+            &graph,
+            &annotations,
+            DatabaseEngine::SqlServer,
+            "sql01\\REPORTING,1500",
+            "Sales",
+        );
+        let objects = graph.tables.len() + graph.views.len();
+        assert_eq!(requests.len(), objects.div_ceil(PURVIEW_BATCH));
+        assert!(requests[0]
+            .url
+            .ends_with("/catalog/api/atlas/v2/entity/bulk"));
+
+        let table = &graph.tables[0];
+        let entities = requests[0].body["entities"].as_array().unwrap();
+        let expected = format!(
+            "mssql://sql01/REPORTING/Sales/{}/{}",
+            table.schema, table.name
+        );
+        let entity = entities
+            .iter()
+            .find(|e| e["attributes"]["qualifiedName"] == expected)
+            .expect("the table entity");
+        assert_eq!(entity["typeName"], "mssql_table");
+        assert_eq!(
+            entity["attributes"]["userDescription"],
+            "Everyone who has bought something"
+        );
+        let column = entities
+            .iter()
+            .find(|e| {
+                e["attributes"]["qualifiedName"]
+                    == format!("{}#{}", expected, table.columns[0].name)
+            })
+            .expect("the column entity");
+        assert_eq!(
+            column["relationshipAttributes"]["table"]["uniqueAttributes"]["qualifiedName"],
+            expected
+        );
+        assert_eq!(column["labels"], json!(["PII", "Contact_info", "Customer"]));
+    }
+
+    #[test]
+    fn datahub_proposals_carry_schema_and_classifications() {
+        let (graph, annotations) = annotated();
+        let target = CatalogTarget {
+            kind: CatalogKind::DataHub,
+            endpoint: "http://datahub:8080".to_string(),
+            token: Some("pat".to_string()),
+            has_token: true,
+            environment: Some("DEV".to_string()),
+        };
+        let requests = catalog_requests(
+            &target,
+            &graph,
+            &annotations,
+            DatabaseEngine::MySql,
+            "db01:3306",
+            "shop",
+        );
+        let table = &graph.tables[0];
+        let urn = format!(
+            "urn:li:dataset:(urn:li:dataPlatform:mysql,shop.{},DEV)",
+            table.name
+        );
+        let aspect = |name: &str| -> Value {
+            let request = requests
+                .iter()
+                .find(|r| {
+                    r.body["proposal"]["entityUrn"] == urn
+                        && r.body["proposal"]["aspectName"] == name
+                })
+                .expect("the aspect");
+            serde_json::from_str(
+                request.body["proposal"]["aspect"]["value"]
+                    .as_str()
+                    .unwrap(),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            aspect("datasetProperties")["description"],
+            "Everyone who has bought something"
+        );
+        let field = &aspect("schemaMetadata")["fields"][0];
+        assert_eq!(field["fieldPath"], table.columns[0].name);
+        assert_eq!(field["globalTags"]["tags"][0]["tag"], "urn:li:tag:PII");
+        assert_eq!(
+            field["glossaryTerms"]["terms"][0]["urn"],
+            "urn:li:glossaryTerm:Customer"
+        );
+        assert_eq!(aspect("globalTags")["tags"], json!([]));
+        assert_eq!(datahub_type("nvarchar(50)"), "StringType");
+        assert_eq!(datahub_type("DECIMAL(10,2)"), "NumberType");
+    }
+
+    #[test]
+    fn requests_go_through_the_configured_proxy() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let proxy = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            let read = stream.read(&mut buf).unwrap_or(0);
+            let _ = tx.send(String::from_utf8_lossy(&buf[..read]).to_string());
+            let _ = stream.write_all(b"HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\n\r\n");
+        });
+
+        let target = CatalogTarget {
+            kind: CatalogKind::DataHub,
+            endpoint: "http://datahub.invalid:8080".to_string(),
+            token: None,
+            has_token: false,
+            environment: None,
+        };
+        let requests = vec![CatalogRequest {
+            url: "http://datahub.invalid:8080/aspects?action=ingestProposal".to_string(),
+            body: json!({}),
+        }];
+        assert!(send_catalog_requests(&target, &requests, Some(&proxy)).is_err());
+
+        let received = rx
+            .recv_timeout(Duration::from_secs(10))
+            .expect("request reached the proxy");
+        assert!(received.contains("datahub.invalid:8080"));
+    }
+
+    #[test]
+    fn targets_are_validated_and_stored_per_connection() {
+        keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+        let dir = tempdir().expect("tempdir");
+        let target = CatalogTarget {
+            kind: CatalogKind::DataHub,
+            endpoint: " http://datahub:8080/ ".to_string(),
+            token: Some("  ".to_string()),
+            has_token: true,
+            environment: Some("dev".to_string()),
+        };
+        let saved = save_catalog_target(dir.path(), "sql01", "Sales", Some(target.clone()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(saved.endpoint, "http://datahub:8080");
+        assert_eq!(saved.token, None);
+        assert!(!saved.has_token);
+        assert_eq!(saved.environment.as_deref(), Some("DEV"));
+        assert_eq!(
+            read_catalog_target(dir.path(), "sql01", "Sales"),
+            Some(saved)
+        );
+        assert_eq!(read_catalog_target(dir.path(), "sql01", "Other"), None);
+
+        let bad = CatalogTarget {
+            endpoint: "datahub:8080".to_string(),
+            ..target
+        };
+        assert!(save_catalog_target(dir.path(), "sql01", "Sales", Some(bad)).is_err());
+        save_catalog_target(dir.path(), "sql01", "Sales", None).unwrap();
+        assert_eq!(read_catalog_target(dir.path(), "sql01", "Sales"), None);
+    }
+
+    #[test]
+    fn tokens_stay_out_of_the_settings_file() {
+        keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+        let dir = tempdir().expect("tempdir");
+        let target = CatalogTarget {
+            kind: CatalogKind::Purview,
+            endpoint: "https://contoso.purview.azure.com".to_string(),
+            token: Some("secret".to_string()),
+            has_token: false,
+            environment: None,
+        };
+        let saved = save_catalog_target(dir.path(), "sql01", "Sales", Some(target))
+            .unwrap()
+            .unwrap();
+        assert!(saved.has_token);
+        let path = target_path(dir.path(), "sql01", "Sales");
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("secret"));
+        // Nor is it sent back out
+        assert!(!serde_json::to_string(&saved).unwrap().contains("secret"));
+
+        // Saving without a token keeps the stored one
+        let edited = CatalogTarget {
+            token: None,
+            has_token: false,
+            ..saved
+        };
+        let saved = save_catalog_target(dir.path(), "sql01", "Sales", Some(edited))
+            .unwrap()
+            .unwrap();
+        assert!(saved.has_token);
+
+        // A token written by an earlier version still counts
+        std::fs::write(
+            &path,
+            r#"{"kind":"dataHub","endpoint":"http://datahub:8080","token":"old"}"#,
+        )
+        .unwrap();
+        let legacy = read_catalog_target(dir.path(), "sql01", "Sales").unwrap();
+        assert!(legacy.has_token);
+        assert_eq!(legacy.token.as_deref(), Some("old"));
+    }
+}
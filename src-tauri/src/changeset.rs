@@ -0,0 +1,1080 @@
+//! Pre-deployment review of a folder of pending migration scripts: the
+//! statements are applied to a copy of the loaded graph, so the result can
+//! be diffed against the current schema before anything is deployed.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+use crate::db::schema_loader::build_name_lookup;
+use crate::db::working_tables::{closing_paren, split_top_level};
+use crate::db::{analyze_definition, constraint_participation, object_id, split_multipart};
+use crate::graph_patch::{diff_graphs, PatchKind, PatchObject, SchemaPatch};
+use crate::sandboxes::split_batches;
+use crate::types::{
+    Column, RelationshipEdge, ScalarFunction, SchemaGraph, StoredProcedure, TableNode, Trigger,
+    ViewNode,
+};
+
+const EXCERPT_LENGTH: usize = 200;
+
+/// A possibly qualified, possibly bracketed object name.
+const NAME: &str = r"((?:(?:\[[^\]]+\]|\w+)\s*\.\s*)*(?:\[[^\]]+\]|\w+))";
+
+/// A single identifier.
+const PART: &str = r"(\[[^\]]+\]|\w+)";
+
+static COMMENTS: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)--[^\n]*|/\*.*?\*/").unwrap());
+
+static MODULE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?is)^\s*(?:CREATE(?:\s+OR\s+ALTER)?|ALTER)\s+(VIEW|PROC(?:EDURE)?|FUNCTION|TRIGGER)\s+{}",
+        NAME
+    ))
+    .unwrap()
+});
+
+static CREATE_TABLE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r"(?is)^CREATE\s+TABLE\s+{}\s*\(", NAME)).unwrap());
+
+static DROP_OBJECT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?is)^DROP\s+(TABLE|VIEW|PROC(?:EDURE)?|FUNCTION|TRIGGER)\s+(?:IF\s+EXISTS\s+)?(.+)$",
+    )
+    .unwrap()
+});
+
+static ALTER_TABLE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?is)^ALTER\s+TABLE\s+{}\s+(?:WITH\s+(?:NO)?CHECK\s+)?(.+)$",
+        NAME
+    ))
+    .unwrap()
+});
+
+static FOREIGN_KEY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?is)^(?:CONSTRAINT\s+{}\s+)?FOREIGN\s+KEY\s*\(([^)]*)\)\s*REFERENCES\s+{}\s*\(([^)]*)\)",
+        PART, NAME
+    ))
+    .unwrap()
+});
+
+static PRIMARY_KEY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?is)^(?:CONSTRAINT\s+{}\s+)?PRIMARY\s+KEY(?:\s+(?:NON)?CLUSTERED)?\s*\(([^)]*)\)",
+        PART
+    ))
+    .unwrap()
+});
+
+static INLINE_REFERENCE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r"(?is)\bREFERENCES\s+{}\s*(?:\(([^)]*)\))?", NAME)).unwrap());
+
+static COLUMN_TYPE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?s)^\s*((?:\[[^\]]+\]|\w+)(?:\s*\.\s*(?:\[[^\]]+\]|\w+))?(?:\s*\([^)]*\))?)")
+        .unwrap()
+});
+
+static NOT_NULL: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\bNOT\s+NULL\b").unwrap());
+
+static INLINE_PRIMARY_KEY: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\bPRIMARY\s+KEY\b").unwrap());
+
+static SP_RENAME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?is)^EXEC(?:UTE)?\s+(?:sys\s*\.\s*)?sp_rename\s+(?:@objname\s*=\s*)?N?'([^']+)'\s*,\s*(?:@newname\s*=\s*)?N?'([^']+)'(?:\s*,\s*(?:@objtype\s*=\s*)?N?'(\w+)')?",
+    )
+    .unwrap()
+});
+
+static TRIGGER_TARGET: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r"(?is)\bON\s+{}", NAME)).unwrap());
+
+static TRIGGER_EVENTS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?is)\b(FOR|AFTER|INSTEAD\s+OF)\s+((?:INSERT|UPDATE|DELETE)(?:\s*,\s*(?:INSERT|UPDATE|DELETE))*)")
+        .unwrap()
+});
+
+static RETURNS: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)\bRETURNS\s+(@?\w+(?:\s*\([^)]*\))?)").unwrap());
+
+static STATEMENT_START: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^\s*(?:CREATE|ALTER|DROP|EXEC|EXECUTE)\b").unwrap());
+
+/// Table elements that aren't columns.
+const TABLE_CONSTRAINTS: &[&str] = &[
+    "CONSTRAINT",
+    "PRIMARY",
+    "UNIQUE",
+    "INDEX",
+    "CHECK",
+    "FOREIGN",
+    "PERIOD",
+];
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptSummary {
+    pub file: String,
+    pub statements: usize,
+    /// Statements that changed the projected graph.
+    pub recognized: usize,
+}
+
+/// A statement the review could not project; the projection may be missing
+/// its effect.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UnrecognizedStatement {
+    pub file: String,
+    pub statement: String,
+}
+
+/// An object the scripts don't touch that depends on one they change.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpactedObject {
+    pub object_id: String,
+    pub depends_on: Vec<String>,
+    /// One of `depends_on` no longer exists after the scripts run.
+    pub broken: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangesetReview {
+    pub scripts: Vec<ScriptSummary>,
+    /// From the current graph to `projected`.
+    pub patch: SchemaPatch,
+    pub projected: SchemaGraph,
+    pub impacted: Vec<ImpactedObject>,
+    pub unrecognized: Vec<UnrecognizedStatement>,
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum NameChunk {
+    Number(u64),
+    Text(String),
+}
+
+/// Orders `V2__x.sql` before `V10__y.sql`.
+fn natural_key(name: &str) -> Vec<NameChunk> {
+    let mut chunks = Vec::new();
+    let mut rest = name;
+    while let Some(first) = rest.chars().next() {
+        let digit = first.is_ascii_digit();
+        let end = rest
+            .find(|c: char| c.is_ascii_digit() != digit)
+            .unwrap_or(rest.len());
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(match chunk.parse() {
+            Ok(n) if digit => NameChunk::Number(n),
+            _ => NameChunk::Text(chunk.to_lowercase()),
+        });
+        rest = tail;
+    }
+    chunks
+}
+
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    natural_key(a).cmp(&natural_key(b))
+}
+
+fn excerpt(statement: &str) -> String {
+    match statement.char_indices().nth(EXCERPT_LENGTH) {
+        Some((end, _)) => format!("{}...", &statement[..end]),
+        None => statement.to_string(),
+    }
+}
+
+/// The statements of one batch. A module definition is a batch of its own,
+/// so it is kept whole; anything else splits on `;` and on lines starting a
+/// new DDL statement.
+fn split_statements(batch: &str) -> Vec<String> {
+    let batch = COMMENTS.replace_all(batch, "");
+    if MODULE.is_match(&batch) {
+        return vec![batch.trim().to_string()];
+    }
+
+    let mut statements = Vec::new();
+    let mut from = 0;
+    let mut depth = 0usize;
+    let mut in_string = false;
+    for (i, b) in batch.bytes().enumerate() {
+        match b {
+            b'\'' => in_string = !in_string,
+            _ if in_string => {}
+            b'(' => depth += 1,
+            b')' => depth = depth.saturating_sub(1),
+            b';' if depth == 0 => {
+                statements.push(&batch[from..i]);
+                from = i + 1;
+            }
+            b'\n' if depth == 0 && STATEMENT_START.is_match(&batch[i + 1..]) => {
+                statements.push(&batch[from..i]);
+                from = i + 1;
+            }
+            _ => {}
+        }
+    }
+    statements.push(&batch[from..]);
+    statements
+        .into_iter()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn unbracket(name: &str) -> String {
+    split_multipart(name.trim()).pop().unwrap_or_default()
+}
+
+fn column_list(list: &str) -> Vec<String> {
+    split_top_level(list)
+        .into_iter()
+        .map(|c| {
+            // Drop ASC/DESC from key column lists
+            let c = c.trim();
+            let name = c
+                .rsplit_once(char::is_whitespace)
+                .filter(|(_, order)| {
+                    order.eq_ignore_ascii_case("asc") || order.eq_ignore_ascii_case("desc")
+                })
+                .map_or(c, |(name, _)| name);
+            unbracket(name)
+        })
+        .collect()
+}
+
+/// Applies statements to the projected graph, resolving unqualified names
+/// against `default_schema`.
+struct Projection<'a> {
+    graph: SchemaGraph,
+    default_schema: &'a str,
+}
+
+impl Projection<'_> {
+    /// Schema, name and graph ID of a (possibly unqualified) name. An ID
+    /// that differs from an existing one only in case resolves to it.
+    fn resolve(&self, name: &str) -> (String, String, String) {
+        let parts = split_multipart(name);
+        let (schema, object) = match parts.as_slice() {
+            [.., schema, object] if !schema.is_empty() => (schema.clone(), object.clone()),
+            [.., object] => (self.default_schema.to_string(), object.clone()),
+            [] => (self.default_schema.to_string(), String::new()),
+        };
+        let id = object_id(&[&schema, &object]);
+        let graph = &self.graph;
+        let existing = graph
+            .tables
+            .iter()
+            .map(|o| (&o.id, &o.schema, &o.name))
+            .chain(graph.views.iter().map(|o| (&o.id, &o.schema, &o.name)))
+            .chain(graph.triggers.iter().map(|o| (&o.id, &o.schema, &o.name)))
+            .chain(
+                graph
+                    .stored_procedures
+                    .iter()
+                    .map(|o| (&o.id, &o.schema, &o.name)),
+            )
+            .chain(
+                graph
+                    .scalar_functions
+                    .iter()
+                    .map(|o| (&o.id, &o.schema, &o.name)),
+            )
+            .find(|(existing, _, _)| existing.eq_ignore_ascii_case(&id));
+        match existing {
+            Some((id, schema, name)) => (schema.to_string(), name.clone(), id.clone()),
+            None => (schema, object, id),
+        }
+    }
+
+    fn table_mut(&mut self, id: &str) -> Option<&mut TableNode> {
+        self.graph.tables.iter_mut().find(|t| t.id == id)
+    }
+
+    fn apply(&mut self, statement: &str) -> bool {
+        if let Some(cap) = MODULE.captures(statement) {
+            self.define_module(&cap[1], &cap[2], statement);
+            return true;
+        }
+        if let Some(cap) = CREATE_TABLE.captures(statement) {
+            let start = cap.get(0).map_or(0, |m| m.end());
+            let elements = &statement[start..closing_paren(statement, start)];
+            return self.create_table(&cap[1], elements);
+        }
+        if let Some(cap) = DROP_OBJECT.captures(statement) {
+            let kind = cap[1].to_uppercase();
+            let dropped: Vec<bool> = split_top_level(&cap[2])
+                .into_iter()
+                .map(|name| self.drop_object(&kind, name))
+                .collect();
+            return dropped.contains(&true);
+        }
+        if let Some(cap) = ALTER_TABLE.captures(statement) {
+            let (_, _, id) = self.resolve(&cap[1]);
+            if self.table_mut(&id).is_none() {
+                return false;
+            }
+            return self.alter_table(&id, cap[2].trim());
+        }
+        if let Some(cap) = SP_RENAME.captures(statement) {
+            let object_type = cap.get(3).map(|m| m.as_str().to_uppercase());
+            return match object_type.as_deref() {
+                Some("COLUMN") => self.rename_column(&cap[1], &cap[2]),
+                None | Some("OBJECT") => self.rename_object(&cap[1], &cap[2]),
+                _ => false,
+            };
+        }
+        false
+    }
+
+    fn create_table(&mut self, name: &str, elements: &str) -> bool {
+        let (schema, name, id) = self.resolve(name);
+        self.graph.tables.retain(|t| t.id != id);
+        self.graph.relationships.retain(|r| r.from != id);
+        self.graph.tables.push(TableNode {
+            id: id.clone(),
+            name,
+            schema: schema.into(),
+            columns: Vec::new(),
+            source: None,
+            metadata: None,
+            change_capture: None,
+        });
+        self.add_elements(&id, elements);
+        true
+    }
+
+    /// Columns and constraints of a `CREATE TABLE` or `ALTER TABLE ... ADD`.
+    fn add_elements(&mut self, table_id: &str, elements: &str) {
+        for element in split_top_level(elements) {
+            let element = element.trim();
+            let first = element
+                .split(|c: char| c.is_whitespace() || c == '(')
+                .next()
+                .unwrap_or_default();
+            if TABLE_CONSTRAINTS
+                .iter()
+                .any(|k| k.eq_ignore_ascii_case(first))
+            {
+                self.add_constraint(table_id, element);
+            } else {
+                self.add_column(table_id, element);
+            }
+        }
+    }
+
+    fn add_column(&mut self, table_id: &str, definition: &str) {
+        let Some(column) = parse_column(definition) else {
+            return;
+        };
+        let reference = INLINE_REFERENCE.captures(definition).map(|cap| {
+            let to_column = cap.get(2).map(|m| unbracket(m.as_str()));
+            (cap[1].to_string(), to_column)
+        });
+        let name = column.name.clone();
+        let Some(table) = self.table_mut(table_id) else {
+            return;
+        };
+        table
+            .columns
+            .retain(|c| !c.name.eq_ignore_ascii_case(&column.name));
+        let ordinal = table
+            .columns
+            .iter()
+            .map(|c| c.ordinal_position)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        table.columns.push(Column {
+            ordinal_position: ordinal,
+            ..column
+        });
+        let table_name = table.name.clone();
+        if let Some((to, to_column)) = reference {
+            let constraint = format!("FK_{}_{}", table_name, name);
+            self.add_foreign_key(table_id, &constraint, &[name], &to, to_column.as_deref());
+        }
+    }
+
+    fn add_constraint(&mut self, table_id: &str, element: &str) -> bool {
+        if let Some(cap) = FOREIGN_KEY.captures(element) {
+            let columns = column_list(&cap[2]);
+            let to_columns = column_list(&cap[4]);
+            let constraint = match cap.get(1) {
+                Some(name) => unbracket(name.as_str()),
+                None => format!("FK_{}", table_id.replace('.', "_")),
+            };
+            self.add_foreign_key(
+                table_id,
+                &constraint,
+                &columns,
+                &cap[3],
+                to_columns.first().map(String::as_str),
+            );
+            return true;
+        }
+        if let Some(cap) = PRIMARY_KEY.captures(element) {
+            let columns = column_list(&cap[2]);
+            if let Some(table) = self.table_mut(table_id) {
+                for column in &mut table.columns {
+                    column.is_primary_key =
+                        columns.iter().any(|c| c.eq_ignore_ascii_case(&column.name));
+                    if column.is_primary_key {
+                        column.is_nullable = false;
+                    }
+                }
+            }
+            return true;
+        }
+        false
+    }
+
+    fn add_foreign_key(
+        &mut self,
+        table_id: &str,
+        constraint: &str,
+        columns: &[String],
+        to: &str,
+        to_column: Option<&str>,
+    ) {
+        let (_, _, to_id) = self.resolve(to);
+        let column_names: Vec<&str> = columns.iter().map(String::as_str).collect();
+        let participation = self
+            .graph
+            .tables
+            .iter()
+            .find(|t| t.id == table_id)
+            .and_then(|t| constraint_participation(t, &column_names));
+        self.graph.relationships.retain(|r| r.id != constraint);
+        self.graph.relationships.push(RelationshipEdge {
+            id: constraint.to_string(),
+            from: table_id.to_string(),
+            to: to_id,
+            from_column: columns.first().cloned(),
+            to_column: to_column.map(str::to_string),
+            source: None,
+            is_virtual: false,
+            participation,
+        });
+    }
+
+    fn alter_table(&mut self, id: &str, action: &str) -> bool {
+        let Some((verb, rest)) = action.split_once(char::is_whitespace) else {
+            return false;
+        };
+        let rest = rest.trim();
+        match verb.to_uppercase().as_str() {
+            "ADD" => {
+                self.add_elements(id, rest);
+                true
+            }
+            "DROP" => self.drop_from_table(id, rest),
+            "ALTER" => {
+                let Some(definition) = strip_keyword(rest, "COLUMN") else {
+                    return false;
+                };
+                let Some(column) = parse_column(definition) else {
+                    return false;
+                };
+                let Some(existing) = self.table_mut(id).and_then(|t| {
+                    t.columns
+                        .iter_mut()
+                        .find(|c| c.name.eq_ignore_ascii_case(&column.name))
+                }) else {
+                    return false;
+                };
+                existing.data_type = column.data_type;
+                existing.is_nullable = column.is_nullable && !existing.is_primary_key;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// `DROP CONSTRAINT ...` or `DROP [COLUMN] ...`.
+    fn drop_from_table(&mut self, id: &str, list: &str) -> bool {
+        let strip_if_exists = |s: &'_ str| {
+            let s = s.trim();
+            strip_keyword(s, "IF")
+                .and_then(|s| strip_keyword(s, "EXISTS"))
+                .unwrap_or(s)
+                .to_string()
+        };
+        if let Some(constraints) = strip_keyword(list, "CONSTRAINT") {
+            for name in split_top_level(constraints) {
+                let name = unbracket(&strip_if_exists(name));
+                self.graph
+                    .relationships
+                    .retain(|r| !(r.from == id && r.id.eq_ignore_ascii_case(&name)));
+            }
+            return true;
+        }
+        let columns = strip_keyword(list, "COLUMN").unwrap_or(list);
+        let names: Vec<String> = split_top_level(columns)
+            .into_iter()
+            .map(|c| unbracket(&strip_if_exists(c)))
+            .collect();
+        let Some(table) = self.table_mut(id) else {
+            return false;
+        };
+        table
+            .columns
+            .retain(|c| !names.iter().any(|n| n.eq_ignore_ascii_case(&c.name)));
+        self.graph.relationships.retain(|r| {
+            r.from != id
+                || !r
+                    .from_column
+                    .as_deref()
+                    .is_some_and(|c| names.iter().any(|n| n.eq_ignore_ascii_case(c)))
+        });
+        true
+    }
+
+    fn drop_object(&mut self, kind: &str, name: &str) -> bool {
+        let (_, _, id) = self.resolve(name);
+        let graph = &mut self.graph;
+        let before = object_count(graph);
+        match kind {
+            "TABLE" => {
+                graph.tables.retain(|t| t.id != id);
+                graph.relationships.retain(|r| r.from != id);
+                graph.triggers.retain(|t| t.table_id != id);
+            }
+            "VIEW" => graph.views.retain(|v| v.id != id),
+            "TRIGGER" => graph.triggers.retain(|t| t.id != id),
+            "FUNCTION" => graph.scalar_functions.retain(|f| f.id != id),
+            _ => graph.stored_procedures.retain(|p| p.id != id),
+        }
+        object_count(graph) != before
+    }
+
+    fn define_module(&mut self, kind: &str, name: &str, definition: &str) {
+        let (schema, name, id) = self.resolve(name);
+        let name_to_id =
+            build_name_lookup(&self.graph.tables, &self.graph.views, self.default_schema);
+        let (reads, writes, analysis) = analyze_definition(definition, &name_to_id);
+        let body = &definition[MODULE.find(definition).map_or(0, |m| m.end())..];
+        let trigger_table = TRIGGER_TARGET
+            .captures(body)
+            .map(|cap| self.resolve(&cap[1]).2)
+            .unwrap_or_default();
+        let graph = &mut self.graph;
+        match kind.to_uppercase().as_str() {
+            "VIEW" => {
+                let existing = graph.views.iter().position(|v| v.id == id);
+                let previous = existing.map(|i| graph.views.remove(i));
+                graph.views.push(ViewNode {
+                    id,
+                    name,
+                    schema: schema.into(),
+                    columns: previous
+                        .as_ref()
+                        .map(|v| v.columns.clone())
+                        .unwrap_or_default(),
+                    definition: definition.to_string(),
+                    referenced_tables: reads,
+                    source: None,
+                    metadata: None,
+                    is_indexed: previous.is_some_and(|v| v.is_indexed),
+                });
+            }
+            "TRIGGER" => {
+                let events = TRIGGER_EVENTS.captures(definition);
+                let fires = |event: &str| {
+                    events
+                        .as_ref()
+                        .is_some_and(|cap| cap[2].to_uppercase().contains(event))
+                };
+                let trigger_type = match events.as_ref().map(|cap| cap[1].to_uppercase()) {
+                    Some(t) if t.starts_with("INSTEAD") => "INSTEAD OF",
+                    _ => "AFTER",
+                };
+                let is_disabled = graph
+                    .triggers
+                    .iter()
+                    .find(|t| t.id == id)
+                    .is_some_and(|t| t.is_disabled);
+                graph.triggers.retain(|t| t.id != id);
+                graph.triggers.push(Trigger {
+                    id,
+                    name,
+                    schema: schema.into(),
+                    table_id: trigger_table,
+                    trigger_type: trigger_type.into(),
+                    is_disabled,
+                    fires_on_insert: fires("INSERT"),
+                    fires_on_update: fires("UPDATE"),
+                    fires_on_delete: fires("DELETE"),
+                    definition: definition.to_string(),
+                    referenced_tables: reads,
+                    affected_tables: writes,
+                    watched_columns: Vec::new(),
+                    analysis,
+                    source: None,
+                    metadata: None,
+                });
+            }
+            "FUNCTION" => {
+                let existing = graph.scalar_functions.iter().position(|f| f.id == id);
+                let previous = existing.map(|i| graph.scalar_functions.remove(i));
+                let return_type = RETURNS
+                    .captures(definition)
+                    .map(|cap| normalize_type(&cap[1]))
+                    .unwrap_or_default();
+                let function_type =
+                    if return_type.eq_ignore_ascii_case("table") || return_type.starts_with('@') {
+                        "SQL_TABLE_VALUED_FUNCTION"
+                    } else {
+                        "SQL_SCALAR_FUNCTION"
+                    };
+                graph.scalar_functions.push(ScalarFunction {
+                    id,
+                    name,
+                    schema: schema.into(),
+                    function_type: function_type.into(),
+                    parameters: previous.map(|f| f.parameters).unwrap_or_default(),
+                    return_type: return_type.into(),
+                    definition: definition.to_string(),
+                    referenced_tables: reads,
+                    affected_tables: writes,
+                    source: None,
+                    metadata: None,
+                });
+            }
+            _ => {
+                let existing = graph.stored_procedures.iter().position(|p| p.id == id);
+                let previous = existing.map(|i| graph.stored_procedures.remove(i));
+                graph.stored_procedures.push(StoredProcedure {
+                    id,
+                    name,
+                    schema: schema.into(),
+                    procedure_type: "SQL_STORED_PROCEDURE".into(),
+                    parameters: previous.map(|p| p.parameters).unwrap_or_default(),
+                    definition: definition.to_string(),
+                    referenced_tables: reads,
+                    affected_tables: writes,
+                    analysis,
+                    source: None,
+                    metadata: None,
+                });
+            }
+        }
+    }
+
+    /// `sp_rename 'schema.Table.Column', 'NewName', 'COLUMN'`
+    fn rename_column(&mut self, old: &str, new: &str) -> bool {
+        let mut parts = split_multipart(old);
+        let Some(column) = parts.pop() else {
+            return false;
+        };
+        let (_, _, id) = self.resolve(&parts.join("."));
+        let new = unbracket(new);
+        let Some(existing) = self.table_mut(&id).and_then(|t| {
+            t.columns
+                .iter_mut()
+                .find(|c| c.name.eq_ignore_ascii_case(&column))
+        }) else {
+            return false;
+        };
+        existing.name = new.clone();
+        for relationship in &mut self.graph.relationships {
+            if relationship.from == id
+                && relationship
+                    .from_column
+                    .as_deref()
+                    .is_some_and(|c| c.eq_ignore_ascii_case(&column))
+            {
+                relationship.from_column = Some(new.clone());
+            }
+            if relationship.to == id
+                && relationship
+                    .to_column
+                    .as_deref()
+                    .is_some_and(|c| c.eq_ignore_ascii_case(&column))
+            {
+                relationship.to_column = Some(new.clone());
+            }
+        }
+        true
+    }
+
+    /// `sp_rename 'schema.Old', 'New'`; the object stays in its schema.
+    fn rename_object(&mut self, old: &str, new: &str) -> bool {
+        let (schema, _, id) = self.resolve(old);
+        let name = unbracket(new);
+        let new_id = object_id(&[&schema, &name]);
+        let graph = &mut self.graph;
+        if let Some(table) = graph.tables.iter_mut().find(|t| t.id == id) {
+            table.id = new_id.clone();
+            table.name = name;
+            for relationship in &mut graph.relationships {
+                if relationship.from == id {
+                    relationship.from = new_id.clone();
+                }
+                if relationship.to == id {
+                    relationship.to = new_id.clone();
+                }
+            }
+            for trigger in &mut graph.triggers {
+                if trigger.table_id == id {
+                    trigger.table_id = new_id.clone();
+                }
+            }
+            return true;
+        }
+        if let Some(view) = graph.views.iter_mut().find(|v| v.id == id) {
+            view.id = new_id;
+            view.name = name;
+            return true;
+        }
+        if let Some(procedure) = graph.stored_procedures.iter_mut().find(|p| p.id == id) {
+            procedure.id = new_id;
+            procedure.name = name;
+            return true;
+        }
+        if let Some(function) = graph.scalar_functions.iter_mut().find(|f| f.id == id) {
+            function.id = new_id;
+            function.name = name;
+            return true;
+        }
+        false
+    }
+}
+
+fn object_count(graph: &SchemaGraph) -> usize {
+    graph.tables.len()
+        + graph.views.len()
+        + graph.triggers.len()
+        + graph.stored_procedures.len()
+        + graph.scalar_functions.len()
+}
+
+/// `text` without a leading case-insensitive `keyword`, if it starts with one.
+fn strip_keyword<'t>(text: &'t str, keyword: &str) -> Option<&'t str> {
+    let text = text.trim_start();
+    let head = text.get(..keyword.len())?;
+    let rest = &text[keyword.len()..];
+    (head.eq_ignore_ascii_case(keyword) && rest.starts_with(char::is_whitespace))
+        .then(|| rest.trim_start())
+}
+
+/// `NVARCHAR (50)` as the loader formats it: `nvarchar(50)`.
+fn normalize_type(data_type: &str) -> String {
+    data_type
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '[' && *c != ']')
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// A column definition: name, type, nullability and an inline primary key.
+/// Computed columns get an empty type.
+fn parse_column(definition: &str) -> Option<Column> {
+    let definition = definition.trim();
+    let (name, rest) = match definition.strip_prefix('[') {
+        Some(quoted) => {
+            let end = quoted.find(']')?;
+            (quoted[..end].to_string(), &quoted[end + 1..])
+        }
+        None => {
+            let end = definition
+                .find(char::is_whitespace)
+                .unwrap_or(definition.len());
+            (definition[..end].to_string(), &definition[end..])
+        }
+    };
+    if name.is_empty() {
+        return None;
+    }
+    let data_type = match strip_keyword(rest, "AS") {
+        Some(_) => String::new(),
+        None => COLUMN_TYPE
+            .captures(rest)
+            .map(|cap| normalize_type(&cap[1]))
+            .unwrap_or_default(),
+    };
+    let is_primary_key = INLINE_PRIMARY_KEY.is_match(rest);
+    Some(Column {
+        name,
+        data_type: data_type.into(),
+        is_nullable: !is_primary_key && !NOT_NULL.is_match(rest),
+        is_primary_key,
+        ..Default::default()
+    })
+}
+
+fn patch_ids(patch: &SchemaPatch) -> (HashSet<String>, HashSet<String>) {
+    let mut changed = HashSet::new();
+    for object in patch.added.iter().chain(&patch.updated) {
+        let id = match object {
+            PatchObject::Table(o) => &o.id,
+            PatchObject::View(o) => &o.id,
+            PatchObject::Trigger(o) => &o.id,
+            PatchObject::StoredProcedure(o) => &o.id,
+            PatchObject::ScalarFunction(o) => &o.id,
+            PatchObject::Relationship(_) | PatchObject::AgentJob(_) => continue,
+        };
+        changed.insert(id.clone());
+    }
+    let removed: HashSet<String> = patch
+        .removed
+        .iter()
+        .filter(|r| !matches!(r.kind, PatchKind::Relationship | PatchKind::AgentJob))
+        .map(|r| r.id.clone())
+        .collect();
+    changed.extend(removed.iter().cloned());
+    (changed, removed)
+}
+
+/// Untouched objects of `projected` that read, write, fire on or reference
+/// by foreign key an object the changeset adds, alters or removes.
+fn impacted_objects(projected: &SchemaGraph, patch: &SchemaPatch) -> Vec<ImpactedObject> {
+    let (changed, removed) = patch_ids(patch);
+    let mut dependencies: Vec<(&String, BTreeSet<&String>)> = Vec::new();
+    for table in &projected.tables {
+        let targets = projected
+            .relationships
+            .iter()
+            .filter(|r| r.from == table.id && !r.is_virtual)
+            .map(|r| &r.to);
+        dependencies.push((&table.id, targets.collect()));
+    }
+    for view in &projected.views {
+        dependencies.push((&view.id, view.referenced_tables.iter().collect()));
+    }
+    for trigger in &projected.triggers {
+        let targets = trigger
+            .referenced_tables
+            .iter()
+            .chain(&trigger.affected_tables)
+            .chain([&trigger.table_id]);
+        dependencies.push((&trigger.id, targets.collect()));
+    }
+    for procedure in &projected.stored_procedures {
+        let targets = procedure
+            .referenced_tables
+            .iter()
+            .chain(&procedure.affected_tables);
+        dependencies.push((&procedure.id, targets.collect()));
+    }
+    for function in &projected.scalar_functions {
+        let targets = function
+            .referenced_tables
+            .iter()
+            .chain(&function.affected_tables);
+        dependencies.push((&function.id, targets.collect()));
+    }
+
+    let mut impacted: Vec<ImpactedObject> = dependencies
+        .into_iter()
+        .filter(|(id, _)| !changed.contains(*id))
+        .filter_map(|(id, targets)| {
+            let depends_on: Vec<String> = targets
+                .into_iter()
+                .filter(|t| *t != id && changed.contains(*t))
+                .cloned()
+                .collect();
+            (!depends_on.is_empty()).then(|| ImpactedObject {
+                object_id: id.clone(),
+                broken: depends_on.iter().any(|d| removed.contains(d)),
+                depends_on,
+            })
+        })
+        .collect();
+    impacted.sort_by(|a, b| b.broken.cmp(&a.broken).then(a.object_id.cmp(&b.object_id)));
+    impacted
+}
+
+/// Apply the `.sql` scripts in `folder`, in natural file name order, to a
+/// copy of `graph` and report the difference. Statements that aren't table
+/// or module DDL, or that can't be parsed, are listed rather than failing
+/// the review.
+pub fn review_changeset(graph: &SchemaGraph, folder: &Path) -> Result<ChangesetReview, String> {
+    let entries = std::fs::read_dir(folder)
+        .map_err(|e| format!("Failed to read {}: {}", folder.display(), e))?;
+    let mut files: Vec<(String, std::path::PathBuf)> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("sql"))
+        })
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().into_owned();
+            Some((name, path))
+        })
+        .collect();
+    files.sort_by(|a, b| natural_cmp(&a.0, &b.0));
+
+    let default_schema = graph.default_schema.as_deref().unwrap_or("dbo");
+    let mut projection = Projection {
+        graph: graph.clone(),
+        default_schema,
+    };
+    let mut scripts = Vec::new();
+    let mut unrecognized = Vec::new();
+    for (file, path) in files {
+        let script = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut summary = ScriptSummary {
+            file: file.clone(),
+            statements: 0,
+            recognized: 0,
+        };
+        for statement in split_batches(&script)
+            .iter()
+            .flat_map(|batch| split_statements(batch))
+        {
+            summary.statements += 1;
+            if projection.apply(&statement) {
+                summary.recognized += 1;
+            } else {
+                unrecognized.push(UnrecognizedStatement {
+                    file: file.clone(),
+                    statement: excerpt(&statement),
+                });
+            }
+        }
+        scripts.push(summary);
+    }
+
+    let projected = projection.graph;
+    let patch = diff_graphs(graph, &projected);
+    let impacted = impacted_objects(&projected, &patch);
+    Ok(ChangesetReview {
+        scripts,
+        patch,
+        projected,
+        impacted,
+        unrecognized,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+    use tempfile::tempdir;
+
+    #[test]
+    fn scripts_project_onto_the_graph_in_order() {
+        let graph = generate_mock_schema("small");
+        let table = graph.tables[0].clone();
+        let view = graph
+            .views
+            .iter()
+            .find(|v| v.referenced_tables.contains(&table.id))
+            .cloned();
+
+        let dir = tempdir().expect("tempdir");
+        std::fs::write(
+            dir.path().join("V10__drop.sql"),
+            format!(
+                "-- retire the old table\nDROP TABLE IF EXISTS [{}].[{}];\nGO\nSELECT 1;\n",
+                table.schema, table.name
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("V2__create.sql"),
+            format!(
+                "CREATE TABLE {schema}.Audit (\n  Id int NOT NULL PRIMARY KEY,\n  Note nvarchar (200) NULL,\n  SourceId int REFERENCES {schema}.[{table}](Id)\n)\nALTER TABLE {schema}.Audit ADD CreatedAt datetime2 NOT NULL\nGO\nCREATE VIEW {schema}.RecentAudit AS\nSELECT Id, Note FROM {schema}.Audit;\n",
+                schema = table.schema,
+                table = table.name
+            ),
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "DROP TABLE x").unwrap();
+
+        let review = review_changeset(&graph, dir.path()).unwrap();
+        let files: Vec<&str> = review.scripts.iter().map(|s| s.file.as_str()).collect();
+        assert_eq!(files, vec!["V2__create.sql", "V10__drop.sql"]);
+        assert_eq!(review.scripts[0].statements, 3);
+        assert_eq!(review.scripts[0].recognized, 3);
+        assert_eq!(review.unrecognized.len(), 1);
+        assert_eq!(review.unrecognized[0].statement, "SELECT 1");
+
+        let audit_id = object_id(&[&table.schema, "Audit"]);
+        let audit = review
+            .projected
+            .tables
+            .iter()
+            .find(|t| t.id == audit_id)
+            .expect("Audit table");
+        let columns: Vec<(&str, &str, bool, bool)> = audit
+            .columns
+            .iter()
+            .map(|c| {
+                (
+                    c.name.as_str(),
+                    &*c.data_type,
+                    c.is_nullable,
+                    c.is_primary_key,
+                )
+            })
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                ("Id", "int", false, true),
+                ("Note", "nvarchar(200)", true, false),
+                ("SourceId", "int", true, false),
+                ("CreatedAt", "datetime2", false, false),
+            ]
+        );
+        let recent = review
+            .projected
+            .views
+            .iter()
+            .find(|v| v.name == "RecentAudit")
+            .expect("RecentAudit view");
+        assert_eq!(recent.referenced_tables, vec![audit_id.clone()]);
+
+        assert!(review
+            .patch
+            .removed
+            .iter()
+            .any(|r| r.kind == PatchKind::Table && r.id == table.id));
+        assert!(!review.projected.tables.iter().any(|t| t.id == table.id));
+        // The new foreign key still points at the dropped table
+        let audit_impact = review.impacted.iter().find(|i| i.object_id == audit_id);
+        assert!(
+            audit_impact.is_none(),
+            "Audit is itself part of the changeset"
+        );
+        if let Some(view) = view {
+            let impact = review
+                .impacted
+                .iter()
+                .find(|i| i.object_id == view.id)
+                .expect("dependent view is impacted");
+            assert!(impact.broken);
+            assert!(impact.depends_on.contains(&table.id));
+        }
+    }
+
+    #[test]
+    fn statements_split_on_semicolons_and_new_ddl_lines() {
+        let statements = split_statements(
+            "ALTER TABLE t ADD c varchar(10) DEFAULT ';' /* ; */\nDROP VIEW v; EXEC sp_rename 't.c', 'd', 'COLUMN'",
+        );
+        assert_eq!(
+            statements,
+            vec![
+                "ALTER TABLE t ADD c varchar(10) DEFAULT ';'",
+                "DROP VIEW v",
+                "EXEC sp_rename 't.c', 'd', 'COLUMN'",
+            ]
+        );
+    }
+}
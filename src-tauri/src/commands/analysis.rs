@@ -0,0 +1,244 @@
+use crate::analysis::{
+    analyze_design, change_capture_report, change_heatmap, check_portability, check_rules,
+    column_defaults, column_domains, complexity_report, dynamic_sql_report,
+    export_compliance_report, graph_stats, load_policy, run_compliance, sample_targets,
+    type_change_impact, view_nesting, ChangeCaptureReport, ChangeHeatmap, CheckConstraintRule,
+    ComplianceReport, ComplianceReportFormat, DesignFinding, DomainReport, DynamicSqlFinding,
+    GraphStats, ObjectComplexity, SeverityGroup, TargetDialect, TypeChangeImpact,
+    ViewNestingReport, DEFAULT_MAX_VIEW_DEPTH, DEFAULT_TOP_COMPLEX, MAX_DOMAIN_VALUES,
+    MAX_SAMPLED_COLUMNS, SAMPLE_ROWS,
+};
+use crate::db::{
+    create_client, load_check_constraints, load_column_indexes, load_default_constraints,
+    load_index_leading_columns, load_server_utc_now, sample_column_values, SchemaError,
+};
+use crate::perf::CommandTimer;
+use crate::types::{ConnectionParams, SchemaGraph};
+use std::collections::HashSet;
+use std::path::Path;
+
+#[tauri::command]
+pub fn get_graph_stats_cmd(graph: SchemaGraph) -> GraphStats {
+    let timer = CommandTimer::start("get_graph_stats_cmd");
+    timer.finish_value(graph_stats(&graph))
+}
+
+/// Design smell report for `graph`. With `params` the database is also asked
+/// for its indexes, enabling the unindexed foreign key check.
+#[tauri::command]
+pub async fn analyze_design_cmd(
+    graph: SchemaGraph,
+    params: Option<ConnectionParams>,
+) -> Result<Vec<DesignFinding>, SchemaError> {
+    let timer = CommandTimer::start("analyze_design_cmd");
+    let result = async {
+        let indexed = match params {
+            Some(params) => {
+                let mut client = create_client(&params).await?;
+                Some(load_index_leading_columns(&mut client).await?)
+            }
+            None => None,
+        };
+        Ok(analyze_design(&graph, indexed.as_ref()))
+    }
+    .await;
+    timer.finish(result)
+}
+
+/// Identifiers that are reserved or awkward in each target dialect, grouped
+/// by severity.
+#[tauri::command]
+pub fn check_portability_cmd(
+    graph: SchemaGraph,
+    dialects: Vec<TargetDialect>,
+) -> Vec<SeverityGroup> {
+    let timer = CommandTimer::start("check_portability_cmd");
+    timer.finish_value(check_portability(&graph, &dialects))
+}
+
+/// Objects bucketed by how recently they changed, for the heatmap overlay.
+/// With `params` ages are measured against the server's clock.
+#[tauri::command]
+pub async fn get_change_heatmap_cmd(
+    graph: SchemaGraph,
+    params: Option<ConnectionParams>,
+) -> Result<ChangeHeatmap, SchemaError> {
+    let timer = CommandTimer::start("get_change_heatmap_cmd");
+    let result = async {
+        let server_now = match params {
+            Some(params) => {
+                let mut client = create_client(&params).await?;
+                load_server_utc_now(&mut client).await?
+            }
+            None => None,
+        };
+        let now = server_now.unwrap_or_else(|| chrono::Utc::now().naive_utc());
+        Ok(change_heatmap(&graph, now))
+    }
+    .await;
+    timer.finish(result)
+}
+
+/// View-on-view nesting depth and indexed views, flagging chains deeper than
+/// `max_depth` (3 by default).
+#[tauri::command]
+pub fn analyze_view_nesting_cmd(graph: SchemaGraph, max_depth: Option<usize>) -> ViewNestingReport {
+    let timer = CommandTimer::start("analyze_view_nesting_cmd");
+    timer.finish_value(view_nesting(
+        &graph,
+        max_depth.unwrap_or(DEFAULT_MAX_VIEW_DEPTH),
+    ))
+}
+
+/// Tables tracked by CDC or published for replication, with what each
+/// means for schema changes.
+#[tauri::command]
+pub fn get_change_capture_report_cmd(graph: SchemaGraph) -> ChangeCaptureReport {
+    let timer = CommandTimer::start("get_change_capture_report_cmd");
+    timer.finish_value(change_capture_report(&graph))
+}
+
+/// Procedures and triggers running dynamic SQL, with those that concatenate
+/// variables into it flagged as possible injection risks.
+#[tauri::command]
+pub fn get_dynamic_sql_report_cmd(graph: SchemaGraph) -> Vec<DynamicSqlFinding> {
+    let timer = CommandTimer::start("get_dynamic_sql_report_cmd");
+    timer.finish_value(dynamic_sql_report(&graph))
+}
+
+/// Procedures and triggers ranked by size, branching, tables touched and
+/// dynamic SQL, the `top` most complex first (20 when not given).
+#[tauri::command]
+pub fn get_complexity_report_cmd(graph: SchemaGraph, top: Option<usize>) -> Vec<ObjectComplexity> {
+    let timer = CommandTimer::start("get_complexity_report_cmd");
+    timer.finish_value(complexity_report(
+        &graph,
+        top.unwrap_or(DEFAULT_TOP_COMPLEX),
+    ))
+}
+
+/// Evaluate the JSON or YAML policy at `policy_path` against `graph`,
+/// returning pass/fail per rule and object. With `params` the database is
+/// also asked for its indexes, enabling foreign key index rules.
+#[tauri::command]
+pub async fn run_compliance_cmd(
+    graph: SchemaGraph,
+    policy_path: String,
+    params: Option<ConnectionParams>,
+) -> Result<ComplianceReport, SchemaError> {
+    let timer = CommandTimer::start("run_compliance_cmd");
+    let result = async {
+        let policy = load_policy(Path::new(&policy_path)).map_err(SchemaError::InvalidQuery)?;
+        let indexed = match params {
+            Some(params) => {
+                let mut client = create_client(&params).await?;
+                Some(load_index_leading_columns(&mut client).await?)
+            }
+            None => None,
+        };
+        run_compliance(&graph, &policy, indexed.as_ref()).map_err(SchemaError::InvalidQuery)
+    }
+    .await;
+    timer.finish(result)
+}
+
+/// A compliance report as Markdown or CSV.
+#[tauri::command]
+pub fn export_compliance_report_cmd(
+    report: ComplianceReport,
+    format: ComplianceReportFormat,
+) -> Result<String, String> {
+    let timer = CommandTimer::start("export_compliance_report_cmd");
+    timer.finish(export_compliance_report(&report, format))
+}
+
+/// Objects that depend on `table_id`.`column`, classified by whether they
+/// block changing it to `new_type`, with a checklist for the change. With
+/// `params` the database is also asked which indexes contain the column.
+#[tauri::command]
+pub async fn simulate_type_change_cmd(
+    graph: SchemaGraph,
+    table_id: String,
+    column: String,
+    new_type: String,
+    params: Option<ConnectionParams>,
+) -> Result<TypeChangeImpact, SchemaError> {
+    let timer = CommandTimer::start("simulate_type_change_cmd");
+    let result = async {
+        let indexes = match params {
+            Some(params) => {
+                let mut client = create_client(&params).await?;
+                Some(load_column_indexes(&mut client, &table_id, &column).await?)
+            }
+            None => None,
+        };
+        type_change_impact(&graph, &table_id, &column, &new_type, indexes.as_deref())
+            .map_err(SchemaError::InvalidQuery)
+    }
+    .await;
+    timer.finish(result)
+}
+
+/// Columns acting as enums: those a check constraint limits to a list, and
+/// those whose sampled rows hold only a few values. Every column default is
+/// reported too. Checks and defaults cover the database; sampling covers
+/// `table_ids` when given, else every table up to a column limit.
+#[tauri::command]
+pub async fn detect_column_domains_cmd(
+    params: ConnectionParams,
+    graph: SchemaGraph,
+    table_ids: Option<Vec<String>>,
+) -> Result<DomainReport, SchemaError> {
+    let timer = CommandTimer::start("detect_column_domains_cmd");
+    let result = async {
+        let mut client = create_client(&params).await?;
+        let checks = load_check_constraints(&mut client).await?;
+        let defaults = column_defaults(load_default_constraints(&mut client).await?);
+
+        let known: HashSet<(String, String)> = column_domains(&checks, &[], &defaults)
+            .into_iter()
+            .map(|d| (d.table_id, d.column.to_lowercase()))
+            .collect();
+        let mut targets = sample_targets(&graph, table_ids.as_deref(), &known);
+        let unsampled_columns = targets.len().saturating_sub(MAX_SAMPLED_COLUMNS);
+        targets.truncate(MAX_SAMPLED_COLUMNS);
+        let mut samples = Vec::new();
+        for (table_id, schema_name, table_name, column) in &targets {
+            let target = (
+                table_id.as_str(),
+                schema_name.as_str(),
+                table_name.as_str(),
+                column.as_str(),
+            );
+            // A table the login can't read is skipped rather than failing the run
+            if let Ok(sample) =
+                sample_column_values(&mut client, target, SAMPLE_ROWS, MAX_DOMAIN_VALUES).await
+            {
+                samples.push(sample);
+            }
+        }
+
+        Ok(DomainReport {
+            domains: column_domains(&checks, &samples, &defaults),
+            defaults,
+            unsampled_columns,
+        })
+    }
+    .await;
+    timer.finish(result)
+}
+
+/// Check constraints read as rules, e.g. `Status ∈ {New, Paid, Shipped}`.
+#[tauri::command]
+pub async fn get_check_rules_cmd(
+    params: ConnectionParams,
+) -> Result<Vec<CheckConstraintRule>, SchemaError> {
+    let timer = CommandTimer::start("get_check_rules_cmd");
+    let result = async {
+        let mut client = create_client(&params).await?;
+        let checks = load_check_constraints(&mut client).await?;
+        Ok(check_rules(&checks))
+    }
+    .await;
+    timer.finish(result)
+}
@@ -0,0 +1,81 @@
+use crate::annotations::{
+    import_annotations_csv, list_annotations, set_annotation, Annotation, AnnotationCsvMapping,
+    AnnotationImportReport,
+};
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use crate::types::SchemaGraph;
+use std::path::Path;
+use tauri::{State, Window};
+
+#[tauri::command]
+pub fn list_annotations_cmd(
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+) -> Result<Vec<Annotation>, String> {
+    let timer = CommandTimer::start("list_annotations_cmd");
+    timer.finish(Ok(list_annotations(
+        &state.storage_path,
+        &server,
+        &database,
+    )))
+}
+
+/// Describe an object or one of its columns; a blank description removes
+/// it. Returns the updated list.
+#[tauri::command]
+pub fn set_annotation_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    object_id: String,
+    column: Option<String>,
+    description: String,
+) -> Result<Vec<Annotation>, String> {
+    let timer = CommandTimer::start("set_annotation_cmd");
+    let result = state.ensure_writable(window.label()).and_then(|_| {
+        set_annotation(
+            &state.storage_path,
+            &server,
+            &database,
+            &object_id,
+            column,
+            &description,
+        )
+    });
+    timer.finish(result)
+}
+
+/// Import descriptions from a data dictionary CSV, matching rows to the
+/// loaded graph by name. With `mapping.dryRun` nothing is saved and the
+/// report shows what would be.
+#[tauri::command]
+pub fn import_annotations_csv_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    graph: SchemaGraph,
+    path: String,
+    mapping: AnnotationCsvMapping,
+) -> Result<AnnotationImportReport, String> {
+    let timer = CommandTimer::start("import_annotations_csv_cmd");
+    let writable = if mapping.dry_run {
+        Ok(())
+    } else {
+        state.ensure_writable(window.label())
+    };
+    let result = writable.and_then(|_| {
+        import_annotations_csv(
+            &state.storage_path,
+            &server,
+            &database,
+            &graph,
+            Path::new(&path),
+            &mapping,
+        )
+    });
+    timer.finish(result)
+}
@@ -0,0 +1,46 @@
+use crate::bookmarks::{list_bookmarks, remove_bookmark, set_bookmark, Bookmark};
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use tauri::{State, Window};
+
+#[tauri::command]
+pub fn list_bookmarks_cmd(
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+) -> Result<Vec<Bookmark>, String> {
+    let timer = CommandTimer::start("list_bookmarks_cmd");
+    timer.finish(Ok(list_bookmarks(&state.storage_path, &server, &database)))
+}
+
+/// Add a bookmark or change its label; returns the updated list.
+#[tauri::command]
+pub fn set_bookmark_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    object_id: String,
+    label: Option<String>,
+) -> Result<Vec<Bookmark>, String> {
+    let timer = CommandTimer::start("set_bookmark_cmd");
+    let result = state
+        .ensure_writable(window.label())
+        .and_then(|_| set_bookmark(&state.storage_path, &server, &database, &object_id, label));
+    timer.finish(result)
+}
+
+#[tauri::command]
+pub fn remove_bookmark_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    object_id: String,
+) -> Result<Vec<Bookmark>, String> {
+    let timer = CommandTimer::start("remove_bookmark_cmd");
+    let result = state
+        .ensure_writable(window.label())
+        .and_then(|_| remove_bookmark(&state.storage_path, &server, &database, &object_id));
+    timer.finish(result)
+}
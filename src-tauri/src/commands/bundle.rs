@@ -0,0 +1,54 @@
+use crate::annotations::Annotation;
+use crate::bookmarks::Bookmark;
+use crate::bundle::{build_bundle, read_bundle, write_bundle, DiagramBundle};
+use crate::export_themes::active_export_theme;
+use crate::layouts::DiagramLayout;
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use crate::types::SchemaGraph;
+use std::path::Path;
+use tauri::{State, Window};
+
+/// Write a `.monoclepkg` that opens without a connection. The theme is the
+/// active export theme; `bookmarks` and the local `annotations` are carried
+/// along.
+#[tauri::command]
+pub fn export_diagram_bundle_cmd(
+    state: State<'_, AppState>,
+    path: String,
+    title: String,
+    graph: SchemaGraph,
+    layout: Option<DiagramLayout>,
+    bookmarks: Option<Vec<Bookmark>>,
+    annotations: Option<Vec<Annotation>>,
+) -> Result<(), String> {
+    let timer = CommandTimer::start("export_diagram_bundle_cmd");
+    let result = state.get_settings().and_then(|settings| {
+        let bundle = build_bundle(
+            &title,
+            graph,
+            layout,
+            bookmarks.unwrap_or_default(),
+            annotations.unwrap_or_default(),
+            Some(active_export_theme(&settings)),
+        );
+        write_bundle(Path::new(&path), &bundle)
+    });
+    timer.finish(result)
+}
+
+/// Open a bundle for viewing. The calling window becomes read-only until a
+/// live schema or workspace load replaces the bundle.
+#[tauri::command]
+pub fn import_diagram_bundle_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<DiagramBundle, String> {
+    let timer = CommandTimer::start("import_diagram_bundle_cmd");
+    let result = read_bundle(Path::new(&path));
+    if result.is_ok() {
+        state.windows.set_read_only(window.label(), true);
+    }
+    timer.finish(result)
+}
@@ -0,0 +1,580 @@
+use crate::ddl::{refactor_design, CanvasRefactoring, RefactorResult};
+use crate::naming::{apply_naming, preview_naming, NameChange, NamingPreview, NamingRules};
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use crate::types::SchemaGraph;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{State, Window};
+
+/// Locks older than this are treated as abandoned (crashed app, unplugged laptop)
+/// and may be taken over without forcing.
+const LOCK_STALE_AFTER_HOURS: i64 = 12;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasLockInfo {
+    pub owner: String,
+    pub machine: String,
+    pub acquired_at: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasLockStatus {
+    pub acquired: bool,
+    pub holder: Option<CanvasLockInfo>,
+    pub stale: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasFileStamp {
+    pub modified_ms: u64,
+    pub size: u64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasConflictStatus {
+    pub changed: bool,
+    pub current: Option<CanvasFileStamp>,
+    pub lock: Option<CanvasLockInfo>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasMergeResult {
+    pub merged: Value,
+    pub conflicts: Vec<String>,
+}
+
+fn lock_path_for(path: &str) -> PathBuf {
+    PathBuf::from(format!("{}.lock", path))
+}
+
+fn current_identity() -> CanvasLockInfo {
+    let owner = std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_else(|_| "unknown".to_string());
+    let machine = std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_else(|_| "unknown".to_string());
+
+    CanvasLockInfo {
+        owner,
+        machine,
+        acquired_at: Utc::now().to_rfc3339(),
+    }
+}
+
+fn is_same_holder(a: &CanvasLockInfo, b: &CanvasLockInfo) -> bool {
+    a.owner.eq_ignore_ascii_case(&b.owner) && a.machine.eq_ignore_ascii_case(&b.machine)
+}
+
+fn is_stale(lock: &CanvasLockInfo, now: DateTime<Utc>) -> bool {
+    match DateTime::parse_from_rfc3339(&lock.acquired_at) {
+        Ok(acquired) => {
+            now.signed_duration_since(acquired.with_timezone(&Utc))
+                .num_hours()
+                >= LOCK_STALE_AFTER_HOURS
+        }
+        // An unreadable timestamp cannot be trusted to be recent
+        Err(_) => true,
+    }
+}
+
+fn read_lock(lock_path: &Path) -> Option<CanvasLockInfo> {
+    let content = std::fs::read_to_string(lock_path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Writes the lock beside the lock file first and then moves it into place,
+/// so other instances never read an empty or half-written lock. With
+/// `create_new` it is hard-linked into place, which fails with
+/// `AlreadyExists` when there is a lock already; otherwise it replaces it.
+/// File systems without hard links (FAT, exFAT, many SMB shares) get the lock
+/// created in place instead, where a reader may briefly find it empty; an
+/// unreadable lock counts as held, so that only delays the reader.
+fn write_lock(lock_path: &Path, info: &CanvasLockInfo, create_new: bool) -> std::io::Result<()> {
+    let content = serde_json::to_string_pretty(info)?;
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let temp_path = PathBuf::from(format!(
+        "{}.{}-{}.tmp",
+        lock_path.display(),
+        std::process::id(),
+        nanos
+    ));
+    std::fs::write(&temp_path, &content)?;
+    let placed = if create_new {
+        std::fs::hard_link(&temp_path, lock_path)
+    } else {
+        std::fs::rename(&temp_path, lock_path)
+    };
+    let _ = std::fs::remove_file(&temp_path);
+    match placed {
+        Err(e) if create_new && e.kind() != ErrorKind::AlreadyExists => {
+            let mut file = OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(lock_path)?;
+            file.write_all(content.as_bytes())
+        }
+        placed => placed,
+    }
+}
+
+/// Whether the lock file itself is old enough to count as abandoned; used
+/// when its contents can't be read.
+fn lock_file_is_stale(lock_path: &Path) -> bool {
+    std::fs::metadata(lock_path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_none_or(|age| age.as_secs() >= LOCK_STALE_AFTER_HOURS as u64 * 60 * 60)
+}
+
+fn acquire_lock(path: &str, me: &CanvasLockInfo, force: bool) -> Result<CanvasLockStatus, String> {
+    let lock_path = lock_path_for(path);
+    let acquired = || CanvasLockStatus {
+        acquired: true,
+        holder: Some(me.clone()),
+        stale: false,
+    };
+    let held_by = |holder: Option<CanvasLockInfo>| CanvasLockStatus {
+        acquired: false,
+        holder,
+        stale: false,
+    };
+
+    // Linking the lock in only when it is missing checks and takes it in one
+    // step, so two instances can't both see it free
+    match write_lock(&lock_path, me, true) {
+        Ok(()) => return Ok(acquired()),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+        Err(e) => return Err(format!("Failed to write lock file: {}", e)),
+    }
+
+    if !force {
+        match read_lock(&lock_path) {
+            Some(existing)
+                if !is_same_holder(&existing, me) && !is_stale(&existing, Utc::now()) =>
+            {
+                return Ok(held_by(Some(existing)));
+            }
+            Some(_) => {}
+            // Someone else's lock we can't read still counts until it goes stale
+            None if !lock_file_is_stale(&lock_path) => return Ok(held_by(None)),
+            None => {}
+        }
+    }
+
+    // Ours, stale or forced: take it over, then make sure another instance
+    // taking it over at the same time didn't win
+    write_lock(&lock_path, me, false).map_err(|e| format!("Failed to write lock file: {}", e))?;
+    match read_lock(&lock_path) {
+        Some(holder) if holder == *me => Ok(acquired()),
+        holder => Ok(held_by(holder)),
+    }
+}
+
+fn release_lock(path: &str, me: &CanvasLockInfo) -> Result<bool, String> {
+    let lock_path = lock_path_for(path);
+
+    match read_lock(&lock_path) {
+        Some(existing) if is_same_holder(&existing, me) => {
+            std::fs::remove_file(&lock_path)
+                .map_err(|e| format!("Failed to remove lock file: {}", e))?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+fn file_stamp(path: &str) -> Option<CanvasFileStamp> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified_ms = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis().min(u64::MAX as u128) as u64)
+        .unwrap_or(0);
+
+    Some(CanvasFileStamp {
+        modified_ms,
+        size: metadata.len(),
+    })
+}
+
+/// Three-way merge of canvas JSON documents.
+///
+/// Objects merge key by key, arrays of objects carrying an `id` merge by id,
+/// and anything else is taken from whichever side changed it. When both sides
+/// changed the same value differently, ours wins and the JSON pointer is
+/// recorded as a conflict.
+pub fn merge_canvas_values(base: &Value, ours: &Value, theirs: &Value) -> CanvasMergeResult {
+    let mut conflicts = Vec::new();
+    let merged = merge_value(Some(base), Some(ours), Some(theirs), "", &mut conflicts)
+        .unwrap_or(Value::Null);
+    CanvasMergeResult { merged, conflicts }
+}
+
+fn merge_value(
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+    pointer: &str,
+    conflicts: &mut Vec<String>,
+) -> Option<Value> {
+    if ours == theirs {
+        return ours.cloned();
+    }
+    if ours == base {
+        return theirs.cloned();
+    }
+    if theirs == base {
+        return ours.cloned();
+    }
+
+    match (ours, theirs) {
+        (Some(Value::Object(o)), Some(Value::Object(t))) => {
+            let empty = Map::new();
+            let b = base.and_then(Value::as_object).unwrap_or(&empty);
+            Some(Value::Object(merge_objects(b, o, t, pointer, conflicts)))
+        }
+        (Some(Value::Array(o)), Some(Value::Array(t))) if all_have_ids(o) && all_have_ids(t) => {
+            let empty = Vec::new();
+            let b = base
+                .and_then(Value::as_array)
+                .filter(|b| all_have_ids(b))
+                .unwrap_or(&empty);
+            Some(Value::Array(merge_id_arrays(b, o, t, pointer, conflicts)))
+        }
+        _ => {
+            conflicts.push(pointer.to_string());
+            ours.cloned()
+        }
+    }
+}
+
+fn merge_objects(
+    base: &Map<String, Value>,
+    ours: &Map<String, Value>,
+    theirs: &Map<String, Value>,
+    pointer: &str,
+    conflicts: &mut Vec<String>,
+) -> Map<String, Value> {
+    let mut merged = Map::new();
+    let mut keys: Vec<&String> = ours.keys().collect();
+    for key in theirs.keys().chain(base.keys()) {
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    for key in keys {
+        let child_pointer = format!("{}/{}", pointer, escape_pointer(key));
+        if let Some(value) = merge_value(
+            base.get(key),
+            ours.get(key),
+            theirs.get(key),
+            &child_pointer,
+            conflicts,
+        ) {
+            merged.insert(key.clone(), value);
+        }
+    }
+
+    merged
+}
+
+fn element_id(value: &Value) -> Option<&str> {
+    value.get("id").and_then(Value::as_str)
+}
+
+fn all_have_ids(items: &[Value]) -> bool {
+    items.iter().all(|item| element_id(item).is_some())
+}
+
+fn merge_id_arrays(
+    base: &[Value],
+    ours: &[Value],
+    theirs: &[Value],
+    pointer: &str,
+    conflicts: &mut Vec<String>,
+) -> Vec<Value> {
+    let find = |items: &'_ [Value], id: &str| -> Option<Value> {
+        items
+            .iter()
+            .find(|item| element_id(item) == Some(id))
+            .cloned()
+    };
+
+    let mut ids: Vec<String> = Vec::new();
+    for item in ours.iter().chain(theirs.iter()) {
+        if let Some(id) = element_id(item) {
+            if !ids.iter().any(|existing| existing == id) {
+                ids.push(id.to_string());
+            }
+        }
+    }
+
+    let mut merged = Vec::new();
+    for id in ids {
+        let child_pointer = format!("{}/{}", pointer, escape_pointer(&id));
+        let b = find(base, &id);
+        let o = find(ours, &id);
+        let t = find(theirs, &id);
+        if let Some(value) = merge_value(
+            b.as_ref(),
+            o.as_ref(),
+            t.as_ref(),
+            &child_pointer,
+            conflicts,
+        ) {
+            merged.push(value);
+        }
+    }
+
+    merged
+}
+
+fn escape_pointer(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+/// Taking the lock is the first step of every canvas edit, so it is refused
+/// during a read-only session.
+#[tauri::command]
+pub async fn acquire_canvas_lock_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    path: String,
+    force: bool,
+) -> Result<CanvasLockStatus, String> {
+    let timer = CommandTimer::start("acquire_canvas_lock_cmd");
+    if let Err(e) = state.ensure_writable(window.label()) {
+        return timer.finish(Err(e));
+    }
+    let result = tokio::task::spawn_blocking(move || {
+        let me = current_identity();
+        let lock_path = lock_path_for(&path);
+        let stale = read_lock(&lock_path)
+            .map(|existing| !is_same_holder(&existing, &me) && is_stale(&existing, Utc::now()))
+            .unwrap_or(false);
+
+        let mut status = acquire_lock(&path, &me, force)?;
+        status.stale = stale;
+        Ok(status)
+    })
+    .await
+    .map_err(|e| format!("Lock task failed: {}", e))?;
+    timer.finish(result)
+}
+
+#[tauri::command]
+pub async fn release_canvas_lock_cmd(path: String) -> Result<bool, String> {
+    let timer = CommandTimer::start("release_canvas_lock_cmd");
+    let result = tokio::task::spawn_blocking(move || release_lock(&path, &current_identity()))
+        .await
+        .map_err(|e| format!("Lock task failed: {}", e))?;
+    timer.finish(result)
+}
+
+#[tauri::command]
+pub async fn get_canvas_file_stamp_cmd(path: String) -> Result<Option<CanvasFileStamp>, String> {
+    let timer = CommandTimer::start("get_canvas_file_stamp_cmd");
+    let result = tokio::task::spawn_blocking(move || file_stamp(&path))
+        .await
+        .map_err(|e| format!("Task failed: {}", e));
+    timer.finish(result)
+}
+
+#[tauri::command]
+pub async fn check_canvas_conflict_cmd(
+    path: String,
+    expected: Option<CanvasFileStamp>,
+) -> Result<CanvasConflictStatus, String> {
+    let timer = CommandTimer::start("check_canvas_conflict_cmd");
+    let result = tokio::task::spawn_blocking(move || {
+        let current = file_stamp(&path);
+        let me = current_identity();
+        let lock = read_lock(&lock_path_for(&path)).filter(|l| !is_same_holder(l, &me));
+
+        CanvasConflictStatus {
+            changed: current != expected,
+            current,
+            lock,
+        }
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e));
+    timer.finish(result)
+}
+
+#[tauri::command]
+pub fn merge_canvas_cmd(base: Value, ours: Value, theirs: Value) -> CanvasMergeResult {
+    let timer = CommandTimer::start("merge_canvas_cmd");
+    timer.finish_value(merge_canvas_values(&base, &ours, &theirs))
+}
+
+/// Renames bringing a design in line with `rules`, with the names that
+/// would collide, for review before applying.
+#[tauri::command]
+pub fn preview_naming_cmd(graph: SchemaGraph, rules: NamingRules) -> NamingPreview {
+    let timer = CommandTimer::start("preview_naming_cmd");
+    timer.finish_value(preview_naming(&graph, &rules))
+}
+
+/// The design with the selected renames from `preview_naming_cmd` applied.
+#[tauri::command]
+pub fn apply_naming_cmd(
+    graph: SchemaGraph,
+    changes: Vec<NameChange>,
+) -> Result<SchemaGraph, String> {
+    let timer = CommandTimer::start("apply_naming_cmd");
+    timer.finish(apply_naming(&graph, &changes))
+}
+
+/// Splits, merges or reorders tables in the design, returning the updated
+/// design with the migration script for the same change.
+#[tauri::command]
+pub fn refactor_design_cmd(
+    graph: SchemaGraph,
+    refactoring: CanvasRefactoring,
+) -> Result<RefactorResult, String> {
+    let timer = CommandTimer::start("refactor_design_cmd");
+    timer.finish(refactor_design(&graph, &refactoring))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn identity(owner: &str) -> CanvasLockInfo {
+        CanvasLockInfo {
+            owner: owner.to_string(),
+            machine: "WS01".to_string(),
+            acquired_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    #[test]
+    fn lock_blocks_other_users_until_released() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("schema.monocle.json");
+        let path = path.to_string_lossy().to_string();
+
+        let alice = identity("alice");
+        let bob = identity("bob");
+
+        assert!(acquire_lock(&path, &alice, false).unwrap().acquired);
+
+        let status = acquire_lock(&path, &bob, false).unwrap();
+        assert!(!status.acquired);
+        assert_eq!(status.holder.unwrap().owner, "alice");
+
+        // Bob cannot release Alice's lock
+        assert!(!release_lock(&path, &bob).unwrap());
+        assert!(release_lock(&path, &alice).unwrap());
+        assert!(acquire_lock(&path, &bob, false).unwrap().acquired);
+    }
+
+    #[test]
+    fn stale_lock_can_be_taken_over() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("schema.monocle.json");
+        let path = path.to_string_lossy().to_string();
+
+        let mut alice = identity("alice");
+        alice.acquired_at =
+            (Utc::now() - chrono::Duration::hours(LOCK_STALE_AFTER_HOURS + 1)).to_rfc3339();
+        write_lock(&lock_path_for(&path), &alice, false).unwrap();
+
+        assert!(
+            acquire_lock(&path, &identity("bob"), false)
+                .unwrap()
+                .acquired
+        );
+    }
+
+    #[test]
+    fn unreadable_lock_counts_as_held() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("schema.monocle.json");
+        let path = path.to_string_lossy().to_string();
+        std::fs::write(lock_path_for(&path), "").unwrap();
+
+        let bob = identity("bob");
+        let status = acquire_lock(&path, &bob, false).unwrap();
+        assert!(!status.acquired);
+        assert!(status.holder.is_none());
+
+        assert!(acquire_lock(&path, &bob, true).unwrap().acquired);
+        assert_eq!(read_lock(&lock_path_for(&path)), Some(bob));
+    }
+
+    #[test]
+    fn new_lock_never_replaces_an_existing_one() {
+        let dir = tempdir().expect("tempdir");
+        let lock_path = dir.path().join("schema.monocle.json.lock");
+        let alice = identity("alice");
+
+        write_lock(&lock_path, &alice, true).unwrap();
+        let err = write_lock(&lock_path, &identity("bob"), true).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+        assert_eq!(read_lock(&lock_path), Some(alice));
+        // No temporary files are left behind
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn merge_takes_non_conflicting_changes_from_both_sides() {
+        let base = json!({
+            "nodePositions": { "dbo.A": { "x": 0, "y": 0 }, "dbo.B": { "x": 10, "y": 10 } },
+            "schema": { "tables": [{ "id": "dbo.A", "name": "A" }, { "id": "dbo.B", "name": "B" }] }
+        });
+        let ours = json!({
+            "nodePositions": { "dbo.A": { "x": 5, "y": 0 }, "dbo.B": { "x": 10, "y": 10 } },
+            "schema": { "tables": [{ "id": "dbo.A", "name": "A" }, { "id": "dbo.B", "name": "B" }, { "id": "dbo.C", "name": "C" }] }
+        });
+        let theirs = json!({
+            "nodePositions": { "dbo.A": { "x": 0, "y": 0 }, "dbo.B": { "x": 10, "y": 99 } },
+            "schema": { "tables": [{ "id": "dbo.A", "name": "A" }] }
+        });
+
+        let result = merge_canvas_values(&base, &ours, &theirs);
+        assert!(result.conflicts.is_empty());
+        assert_eq!(result.merged["nodePositions"]["dbo.A"]["x"], 5);
+        assert_eq!(result.merged["nodePositions"]["dbo.B"]["y"], 99);
+
+        let ids: Vec<&str> = result.merged["schema"]["tables"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(element_id)
+            .collect();
+        assert_eq!(ids, vec!["dbo.A", "dbo.C"]);
+    }
+
+    #[test]
+    fn merge_reports_conflicting_edits() {
+        let base = json!({ "nodePositions": { "dbo.A": { "x": 0 } } });
+        let ours = json!({ "nodePositions": { "dbo.A": { "x": 1 } } });
+        let theirs = json!({ "nodePositions": { "dbo.A": { "x": 2 } } });
+
+        let result = merge_canvas_values(&base, &ours, &theirs);
+        assert_eq!(result.conflicts, vec!["/nodePositions/dbo.A/x"]);
+        assert_eq!(result.merged["nodePositions"]["dbo.A"]["x"], 1);
+    }
+}
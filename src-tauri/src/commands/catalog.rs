@@ -0,0 +1,87 @@
+use crate::annotations::list_annotations;
+use crate::catalog_push::{
+    catalog_requests, read_catalog_target, save_catalog_target, send_catalog_requests,
+    CatalogPushResult, CatalogTarget,
+};
+use crate::credentials::catalog_token;
+use crate::diagnostics::record_action;
+use crate::i18n::t;
+use crate::network::resolve_proxy_url;
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use crate::types::{DatabaseEngine, SchemaGraph};
+use tauri::{State, Window};
+
+/// The connection's catalog. Its token stays in the backend; `hasToken`
+/// tells whether there is one.
+#[tauri::command]
+pub fn get_catalog_target_cmd(
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+) -> Option<CatalogTarget> {
+    let timer = CommandTimer::start("get_catalog_target_cmd");
+    let target =
+        read_catalog_target(&state.storage_path, &server, &database).map(|target| CatalogTarget {
+            token: None,
+            ..target
+        });
+    timer.finish_value(target)
+}
+
+/// Set the Purview or DataHub instance this connection's schema is pushed
+/// to; `None` removes it. A `token` is moved to the keychain.
+#[tauri::command]
+pub fn set_catalog_target_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    target: Option<CatalogTarget>,
+) -> Result<Option<CatalogTarget>, String> {
+    let timer = CommandTimer::start("set_catalog_target_cmd");
+    let result = state
+        .ensure_writable(window.label())
+        .and_then(|_| save_catalog_target(&state.storage_path, &server, &database, target));
+    timer.finish(result)
+}
+
+/// Publish the graph's tables and views, with annotations, tags and terms,
+/// to the connection's catalog.
+#[tauri::command]
+pub async fn push_catalog_cmd(
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    engine: Option<DatabaseEngine>,
+    graph: SchemaGraph,
+) -> Result<CatalogPushResult, String> {
+    let timer = CommandTimer::start("push_catalog_cmd");
+    let result = async {
+        let mut target = read_catalog_target(&state.storage_path, &server, &database)
+            .ok_or_else(|| t!("error-catalog-not-configured"))?;
+        if target.token.is_none() && target.has_token {
+            target.token = catalog_token(&server, &database);
+        }
+        record_action(format!("push_catalog {:?}", target.kind));
+        let annotations = list_annotations(&state.storage_path, &server, &database);
+        let requests = catalog_requests(
+            &target,
+            &graph,
+            &annotations,
+            engine.unwrap_or_default(),
+            &server,
+            &database,
+        );
+        let datasets = graph.tables.len() + graph.views.len();
+        let proxy = resolve_proxy_url(state.get_settings()?.proxy.as_ref())?;
+        let requests = tokio::task::spawn_blocking(move || {
+            send_catalog_requests(&target, &requests, proxy.as_ref())
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        Ok(CatalogPushResult { datasets, requests })
+    }
+    .await;
+    timer.finish(result)
+}
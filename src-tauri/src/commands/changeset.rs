@@ -0,0 +1,12 @@
+use crate::changeset::{review_changeset, ChangesetReview};
+use crate::perf::CommandTimer;
+use crate::types::SchemaGraph;
+use std::path::Path;
+
+/// Project the migration scripts in `folder` onto `graph` without running
+/// them anywhere.
+#[tauri::command]
+pub fn review_changeset_cmd(graph: SchemaGraph, folder: String) -> Result<ChangesetReview, String> {
+    let timer = CommandTimer::start("review_changeset_cmd");
+    timer.finish(review_changeset(&graph, Path::new(&folder)))
+}
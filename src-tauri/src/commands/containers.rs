@@ -0,0 +1,10 @@
+use crate::containers::{detect_local_sql_containers, SqlContainer};
+use crate::perf::CommandTimer;
+
+/// Running SQL Server containers on the local Docker daemon, with
+/// connection parameters to prefill for those publishing port 1433.
+#[tauri::command]
+pub async fn detect_local_sql_containers_cmd() -> Result<Vec<SqlContainer>, String> {
+    let timer = CommandTimer::start("detect_local_sql_containers_cmd");
+    timer.finish(detect_local_sql_containers().await)
+}
@@ -0,0 +1,74 @@
+use crate::credentials::{delete_credential, get_credential, has_credential, store_credential};
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use tauri::{State, Window};
+
+// Keychain calls can block on an unlock prompt, so they run off the async
+// runtime.
+async fn blocking<T: Send + 'static>(
+    task: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tokio::task::spawn_blocking(task)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Remember a SQL login's password in the system keychain.
+#[tauri::command]
+pub async fn store_credential_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    username: String,
+    password: String,
+) -> Result<(), String> {
+    let timer = CommandTimer::start("store_credential_cmd");
+    let result = match state.ensure_writable(window.label()) {
+        Ok(()) => {
+            blocking(move || store_credential(&server, &database, &username, &password)).await
+        }
+        Err(e) => Err(e),
+    };
+    timer.finish(result)
+}
+
+#[tauri::command]
+pub async fn get_credential_cmd(
+    server: String,
+    database: String,
+    username: String,
+) -> Result<Option<String>, String> {
+    let timer = CommandTimer::start("get_credential_cmd");
+    let result = blocking(move || get_credential(&server, &database, &username)).await;
+    timer.finish(result)
+}
+
+#[tauri::command]
+pub async fn delete_credential_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    username: String,
+) -> Result<(), String> {
+    let timer = CommandTimer::start("delete_credential_cmd");
+    let result = match state.ensure_writable(window.label()) {
+        Ok(()) => blocking(move || delete_credential(&server, &database, &username)).await,
+        Err(e) => Err(e),
+    };
+    timer.finish(result)
+}
+
+/// Whether the keychain holds a password for the login, for flagging saved
+/// connections without reading the secret.
+#[tauri::command]
+pub async fn has_credential_cmd(
+    server: String,
+    database: String,
+    username: String,
+) -> Result<bool, String> {
+    let timer = CommandTimer::start("has_credential_cmd");
+    let result = blocking(move || Ok(has_credential(&server, &database, &username))).await;
+    timer.finish(result)
+}
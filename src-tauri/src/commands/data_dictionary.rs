@@ -0,0 +1,23 @@
+use crate::annotations::list_annotations;
+use crate::data_dictionary::write_data_dictionary;
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use crate::types::SchemaGraph;
+use tauri::State;
+
+/// The graph's tables and views as a CSV data dictionary, with the
+/// connection's annotations and the graph's terms, tags and stewardship.
+#[tauri::command]
+pub fn export_data_dictionary_cmd(
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    graph: SchemaGraph,
+) -> Result<String, String> {
+    let timer = CommandTimer::start("export_data_dictionary_cmd");
+    let annotations = list_annotations(&state.storage_path, &server, &database);
+    let mut out = Vec::new();
+    let result = write_data_dictionary(&mut out, &graph, &annotations)
+        .and_then(|_| String::from_utf8(out).map_err(|e| e.to_string()));
+    timer.finish(result)
+}
@@ -1,20 +1,117 @@
 use futures_util::TryStreamExt;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
 
-use crate::db::{create_server_client, SchemaError, LIST_DATABASES_QUERY};
-use crate::types::ServerConnectionParams;
+use crate::db::{
+    create_client, create_server_client, like_pattern, load_availability_info, order_by_recent,
+    stream_databases, AvailabilityInfo, DatabaseCounts, DatabaseEntry, SchemaError,
+    DATABASE_BATCH_SIZE, LIST_DATABASES_MATCHING_QUERY, LIST_DATABASES_QUERY,
+};
+use crate::diagnostics::record_action;
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use crate::types::{ConnectionParams, ServerConnectionParams};
 
+/// Payload of the `database-batch` event.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseBatchPayload {
+    pub operation_id: String,
+    pub task_id: String,
+    pub databases: Vec<DatabaseEntry>,
+}
+
+/// Databases the login can open, recently used ones first. `filter` is
+/// matched on the server (`*` and `?` wildcards, otherwise a substring).
 #[tauri::command]
-pub async fn list_databases_cmd(params: ServerConnectionParams) -> Result<Vec<String>, SchemaError> {
-    let mut client = create_server_client(&params).await?;
+pub async fn list_databases_cmd(
+    state: State<'_, AppState>,
+    params: ServerConnectionParams,
+    filter: Option<String>,
+) -> Result<Vec<String>, SchemaError> {
+    let timer = CommandTimer::start("list_databases_cmd");
+    record_action(format!("list_databases {}", params.server));
+    let result = async {
+        let mut client = create_server_client(&params).await?;
 
-    let mut databases: Vec<String> = Vec::new();
-    let mut stream = client.query(LIST_DATABASES_QUERY, &[]).await?.into_row_stream();
+        let mut databases: Vec<String> = Vec::new();
+        let filter = filter.filter(|f| !f.trim().is_empty());
+        let mut stream = match filter {
+            Some(filter) => {
+                client
+                    .query(LIST_DATABASES_MATCHING_QUERY, &[&like_pattern(&filter)])
+                    .await?
+            }
+            None => client.query(LIST_DATABASES_QUERY, &[]).await?,
+        }
+        .into_row_stream();
 
-    while let Some(row) = stream.try_next().await? {
-        if let Some(name) = row.get::<&str, _>(0) {
-            databases.push(name.to_string());
+        while let Some(row) = stream.try_next().await? {
+            if let Some(name) = row.get::<&str, _>(0) {
+                databases.push(name.to_string());
+            }
         }
+
+        let recent = state.recent_databases_for(&params.server);
+        Ok::<_, SchemaError>(order_by_recent(databases, &recent))
     }
+    .await;
+    timer.finish(result)
+}
 
-    Ok(databases)
+/// List databases with their state and size, emitting them as
+/// `database-batch` events while rows arrive so servers with thousands of
+/// databases fill the picker progressively. `filter` narrows by name; the UI
+/// restarts the stream with a new `operation_id` when it changes and can
+/// stop the old one with `cancel_task_cmd`.
+#[tauri::command]
+pub async fn stream_databases_cmd(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    params: ServerConnectionParams,
+    operation_id: String,
+    filter: Option<String>,
+) -> Result<DatabaseCounts, SchemaError> {
+    let timer = CommandTimer::start("stream_databases_cmd");
+    record_action(format!("stream_databases {}", params.server));
+    let task = state.tasks.start("list_databases", params.server.clone());
+
+    let stream = async {
+        let mut client = create_server_client(&params).await?;
+        stream_databases(
+            &mut client,
+            filter.as_deref(),
+            DATABASE_BATCH_SIZE,
+            |databases| {
+                let payload = DatabaseBatchPayload {
+                    operation_id: operation_id.clone(),
+                    task_id: task.id.clone(),
+                    databases,
+                };
+                let _ = app.emit("database-batch", payload);
+            },
+        )
+        .await
+    };
+    let result = tokio::select! {
+        result = stream => result,
+        _ = task.cancelled() => Err(SchemaError::Cancelled),
+    };
+    state.tasks.finish(&task.id, &result);
+    timer.finish(result)
+}
+
+/// Availability group replicas and the role of the one this connection
+/// reached; `None` outside an availability group.
+#[tauri::command]
+pub async fn get_availability_info_cmd(
+    params: ConnectionParams,
+) -> Result<Option<AvailabilityInfo>, SchemaError> {
+    let timer = CommandTimer::start("get_availability_info_cmd");
+    let result = async {
+        let mut client = create_client(&params).await?;
+        load_availability_info(&mut client).await
+    }
+    .await;
+    timer.finish(result)
 }
@@ -0,0 +1,82 @@
+use crate::analysis::TargetDialect;
+use crate::ddl::{
+    default_type_mappings, generate_ddl, migration_kit, plan_rename, rebuild_script, DdlScript,
+    MigrationFormat, MigrationKit, RebuildScript, RenamePlan, TypeMapping,
+};
+use crate::export_annotations::ExportAnnotations;
+use crate::perf::CommandTimer;
+use crate::types::SchemaGraph;
+
+/// Built-in type-mapping table for a dialect, for the mapping editor.
+#[tauri::command]
+pub fn get_type_mappings_cmd(dialect: TargetDialect) -> Vec<TypeMapping> {
+    let timer = CommandTimer::start("get_type_mappings_cmd");
+    timer.finish_value(default_type_mappings(dialect))
+}
+
+/// DDL for `graph` in another dialect. `mappings` override built-in rows
+/// with the same source type. The title block and watermark of
+/// `annotations` are written as a comment header; DDL has no legend.
+#[tauri::command]
+pub fn export_ddl_cmd(
+    graph: SchemaGraph,
+    dialect: TargetDialect,
+    mappings: Option<Vec<TypeMapping>>,
+    annotations: Option<ExportAnnotations>,
+) -> DdlScript {
+    let timer = CommandTimer::start("export_ddl_cmd");
+    let mut ddl = generate_ddl(&graph, dialect, &mappings.unwrap_or_default());
+    let header = annotations
+        .unwrap_or_default()
+        .stamped()
+        .comment_block("--", &[]);
+    ddl.script.insert_str(0, &header);
+    timer.finish_value(ddl)
+}
+
+/// Topological load order for every table plus a script template that
+/// copies them in that order, headed by the title block and watermark of
+/// `annotations`.
+#[tauri::command]
+pub fn export_migration_kit_cmd(
+    graph: SchemaGraph,
+    format: MigrationFormat,
+    annotations: Option<ExportAnnotations>,
+) -> MigrationKit {
+    let timer = CommandTimer::start("export_migration_kit_cmd");
+    let mut kit = migration_kit(&graph, format);
+    let (marker, at) = match format {
+        MigrationFormat::InsertSelect => ("--", 0),
+        // After `@echo off`, so the header is not echoed
+        MigrationFormat::Bcp => ("rem", kit.script.find('\n').map_or(0, |i| i + 1)),
+    };
+    let header = annotations
+        .unwrap_or_default()
+        .stamped()
+        .comment_block(marker, &[]);
+    kit.script.insert_str(at, &header);
+    timer.finish_value(kit)
+}
+
+/// T-SQL that drops `object_ids` and recreates them in dependency order, for
+/// refreshing a set of reporting objects.
+#[tauri::command]
+pub fn export_rebuild_script_cmd(
+    graph: SchemaGraph,
+    object_ids: Vec<String>,
+) -> Result<RebuildScript, String> {
+    let timer = CommandTimer::start("export_rebuild_script_cmd");
+    timer.finish(rebuild_script(&graph, &object_ids))
+}
+
+/// `sp_rename` for an object or column (`schema.table.column`), followed by
+/// ALTERs for the views, functions, procedures and triggers that mention it.
+#[tauri::command]
+pub fn plan_rename_cmd(
+    graph: SchemaGraph,
+    object_or_column: String,
+    new_name: String,
+) -> Result<RenamePlan, String> {
+    let timer = CommandTimer::start("plan_rename_cmd");
+    timer.finish(plan_rename(&graph, &object_or_column, &new_name))
+}
@@ -0,0 +1,48 @@
+use crate::diagnostics::{clear_crash_reports, read_crash_reports, CrashReport};
+use crate::environment::{diagnose_environment, EnvironmentReport};
+use crate::perf::{
+    clear_performance_stats, performance_stats, set_payload_measurement, CommandTimer,
+    PerformanceStats,
+};
+use crate::state::AppState;
+use tauri::State;
+
+#[tauri::command]
+pub fn get_crash_reports_cmd(state: State<'_, AppState>) -> Result<Vec<CrashReport>, String> {
+    let timer = CommandTimer::start("get_crash_reports_cmd");
+    timer.finish(Ok(read_crash_reports(&state.storage_path)))
+}
+
+#[tauri::command]
+pub fn clear_crash_reports_cmd(state: State<'_, AppState>) -> Result<(), String> {
+    let timer = CommandTimer::start("clear_crash_reports_cmd");
+    timer.finish(clear_crash_reports(&state.storage_path))
+}
+
+#[tauri::command]
+pub fn get_performance_stats_cmd() -> PerformanceStats {
+    performance_stats()
+}
+
+#[tauri::command]
+pub fn clear_performance_stats_cmd() {
+    clear_performance_stats();
+}
+
+/// Record response sizes in the performance stats. Off by default, since
+/// it serializes every command result a second time.
+#[tauri::command]
+pub fn set_payload_measurement_cmd(enabled: bool) {
+    set_payload_measurement(enabled);
+}
+
+/// ODBC drivers, driver manager, TLS libraries and known problems with
+/// them, with a plain-text block to paste into a support request.
+#[tauri::command]
+pub async fn diagnose_environment_cmd() -> Result<EnvironmentReport, String> {
+    let timer = CommandTimer::start("diagnose_environment_cmd");
+    let result = tokio::task::spawn_blocking(diagnose_environment)
+        .await
+        .map_err(|e| e.to_string());
+    timer.finish(result)
+}
@@ -0,0 +1,23 @@
+use crate::er_export::{export_er_diagram, ErFormat};
+use crate::export_annotations::ExportAnnotations;
+use crate::perf::CommandTimer;
+use crate::types::SchemaGraph;
+
+/// Mermaid, DBML or PlantUML source for the tables of `graph`, with
+/// crow's-foot ends from foreign key participation, and an optional legend,
+/// title block and watermark.
+#[tauri::command]
+pub fn export_er_diagram_cmd(
+    graph: SchemaGraph,
+    format: ErFormat,
+    collapse_junctions: Option<bool>,
+    annotations: Option<ExportAnnotations>,
+) -> String {
+    let timer = CommandTimer::start("export_er_diagram_cmd");
+    timer.finish_value(export_er_diagram(
+        &graph,
+        format,
+        collapse_junctions.unwrap_or(false),
+        &annotations.unwrap_or_default().stamped(),
+    ))
+}
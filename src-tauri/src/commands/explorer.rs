@@ -1,3 +1,4 @@
+use crate::perf::CommandTimer;
 use crate::state::{AppSettings, AppState};
 use crate::validation::{detect_and_decode, validate_characters};
 use crate::validation::validator::ValidationProblem;
@@ -33,6 +34,7 @@ pub async fn list_directory_cmd(
     operation_id: String,
     explorer_state: State<'_, ExplorerState>,
 ) -> Result<Vec<DirEntry>, String> {
+    let timer = CommandTimer::start("list_directory_cmd");
     let cancel_token = CancellationToken::new();
     let token_clone = cancel_token.clone();
 
@@ -87,11 +89,11 @@ pub async fn list_directory_cmd(
         listings.remove(&op_id);
     }
 
-    match result {
+    timer.finish(match result {
         Ok(Ok(entries)) => entries,
         Ok(Err(e)) => Err(format!("Directory listing task failed: {}", e)),
         Err(_) => Err("Folder listing timed out after 15 seconds".to_string()),
-    }
+    })
 }
 
 #[tauri::command]
@@ -99,6 +101,7 @@ pub fn cancel_directory_cmd(
     operation_id: String,
     explorer_state: State<'_, ExplorerState>,
 ) -> Result<(), String> {
+    let timer = CommandTimer::start("cancel_directory_cmd");
     let listings = explorer_state
         .active_listings
         .lock()
@@ -108,7 +111,7 @@ pub fn cancel_directory_cmd(
         token.cancel();
     }
 
-    Ok(())
+    timer.finish(Ok(()))
 }
 
 #[derive(Serialize)]
@@ -123,7 +126,8 @@ pub struct FileContent {
 
 #[tauri::command]
 pub async fn read_file_cmd(path: String) -> Result<FileContent, String> {
-    tokio::time::timeout(
+    let timer = CommandTimer::start("read_file_cmd");
+    let result = tokio::time::timeout(
         Duration::from_secs(30),
         tokio::task::spawn_blocking(move || {
             let metadata = std::fs::metadata(&path)
@@ -151,16 +155,18 @@ pub async fn read_file_cmd(path: String) -> Result<FileContent, String> {
     )
     .await
     .map_err(|_| "File read timed out after 30 seconds".to_string())?
-    .map_err(|e| format!("File read task failed: {}", e))?
+    .map_err(|e| format!("File read task failed: {}", e))?;
+    timer.finish(result)
 }
 
 #[tauri::command]
 pub async fn check_path_reachable(path: String) -> Result<bool, String> {
+    let timer = CommandTimer::start("check_path_reachable");
     let result = tokio::task::spawn_blocking(move || std::fs::metadata(&path).is_ok())
         .await
         .map_err(|e| format!("Task failed: {}", e))?;
 
-    Ok(result)
+    timer.finish(Ok(result))
 }
 
 #[tauri::command]
@@ -169,7 +175,8 @@ pub fn toggle_favorite_cmd(
     client_name: String,
     state: State<'_, AppState>,
 ) -> Result<AppSettings, String> {
-    state.toggle_favorite(&source_id, &client_name)
+    let timer = CommandTimer::start("toggle_favorite_cmd");
+    timer.finish(state.toggle_favorite(&source_id, &client_name))
 }
 
 // -- Content search types and commands --
@@ -270,6 +277,7 @@ pub async fn content_search_cmd(
     operation_id: String,
     explorer_state: State<'_, ExplorerState>,
 ) -> Result<SearchSummaryResult, String> {
+    let timer = CommandTimer::start("content_search_cmd");
     let terms = parse_search_terms(&query);
     if terms.is_empty() {
         return Err("Search query is empty".to_string());
@@ -471,7 +479,7 @@ pub async fn content_search_cmd(
         listings.remove(&op_id);
     }
 
-    Ok(result)
+    timer.finish(Ok(result))
 }
 
 // -- Bulk scan types and commands --
@@ -527,6 +535,7 @@ pub async fn bulk_scan_cmd(
     operation_id: String,
     explorer_state: State<'_, ExplorerState>,
 ) -> Result<ScanSummary, String> {
+    let timer = CommandTimer::start("bulk_scan_cmd");
     let pattern = Pattern::new(&file_pattern)
         .map_err(|e| format!("Invalid file pattern '{}': {}", file_pattern, e))?;
 
@@ -699,7 +708,7 @@ pub async fn bulk_scan_cmd(
         listings.remove(&op_id_cleanup);
     }
 
-    Ok(result)
+    timer.finish(Ok(result))
 }
 
 #[tauri::command]
@@ -707,6 +716,7 @@ pub fn cancel_scan_cmd(
     operation_id: String,
     explorer_state: State<'_, ExplorerState>,
 ) -> Result<(), String> {
+    let timer = CommandTimer::start("cancel_scan_cmd");
     let listings = explorer_state
         .active_listings
         .lock()
@@ -716,7 +726,7 @@ pub fn cancel_scan_cmd(
         token.cancel();
     }
 
-    Ok(())
+    timer.finish(Ok(()))
 }
 
 #[cfg(test)]
@@ -0,0 +1,111 @@
+use crate::glossary::{
+    classify, list_classifications, read_glossary, remove_tag, remove_term, save_tag, save_term,
+    Glossary, GlossaryTerm, Tag,
+};
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use crate::types::Classification;
+use tauri::{State, Window};
+
+#[tauri::command]
+pub fn get_glossary_cmd(state: State<'_, AppState>) -> Result<Glossary, String> {
+    let timer = CommandTimer::start("get_glossary_cmd");
+    timer.finish(Ok(read_glossary(&state.storage_path)))
+}
+
+/// Add a glossary term or replace the one with the same name.
+#[tauri::command]
+pub fn save_glossary_term_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    term: GlossaryTerm,
+) -> Result<Glossary, String> {
+    let timer = CommandTimer::start("save_glossary_term_cmd");
+    let result = state
+        .ensure_writable(window.label())
+        .and_then(|_| save_term(&state.storage_path, term));
+    timer.finish(result)
+}
+
+#[tauri::command]
+pub fn remove_glossary_term_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<Glossary, String> {
+    let timer = CommandTimer::start("remove_glossary_term_cmd");
+    let result = state
+        .ensure_writable(window.label())
+        .and_then(|_| remove_term(&state.storage_path, &name));
+    timer.finish(result)
+}
+
+/// Add a tag to the taxonomy or move/describe an existing one.
+#[tauri::command]
+pub fn save_tag_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    tag: Tag,
+) -> Result<Glossary, String> {
+    let timer = CommandTimer::start("save_tag_cmd");
+    let result = state
+        .ensure_writable(window.label())
+        .and_then(|_| save_tag(&state.storage_path, tag));
+    timer.finish(result)
+}
+
+#[tauri::command]
+pub fn remove_tag_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<Glossary, String> {
+    let timer = CommandTimer::start("remove_tag_cmd");
+    let result = state
+        .ensure_writable(window.label())
+        .and_then(|_| remove_tag(&state.storage_path, &name));
+    timer.finish(result)
+}
+
+#[tauri::command]
+pub fn list_classifications_cmd(
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+) -> Result<Vec<Classification>, String> {
+    let timer = CommandTimer::start("list_classifications_cmd");
+    timer.finish(Ok(list_classifications(
+        &state.storage_path,
+        &server,
+        &database,
+    )))
+}
+
+/// Assign glossary terms and tags to an object or column, replacing its
+/// previous ones. Returns the connection's updated assignments.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn classify_object_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    object_id: String,
+    column: Option<String>,
+    terms: Vec<String>,
+    tags: Vec<String>,
+) -> Result<Vec<Classification>, String> {
+    let timer = CommandTimer::start("classify_object_cmd");
+    let result = state.ensure_writable(window.label()).and_then(|_| {
+        classify(
+            &state.storage_path,
+            &server,
+            &database,
+            &object_id,
+            column,
+            terms,
+            tags,
+        )
+    });
+    timer.finish(result)
+}
@@ -0,0 +1,19 @@
+use crate::glossary::read_glossary;
+use crate::join_paths::{suggest_join_path, JoinSuggestion};
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use crate::types::SchemaGraph;
+use tauri::State;
+
+/// Candidate tables, columns and joins for a question such as "orders per
+/// customer last month", from names and the glossary alone.
+#[tauri::command]
+pub fn suggest_join_path_cmd(
+    state: State<'_, AppState>,
+    graph: SchemaGraph,
+    question: String,
+) -> JoinSuggestion {
+    let timer = CommandTimer::start("suggest_join_path_cmd");
+    let glossary = read_glossary(&state.storage_path);
+    timer.finish_value(suggest_join_path(&graph, &glossary, &question))
+}
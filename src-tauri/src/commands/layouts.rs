@@ -0,0 +1,57 @@
+use crate::layouts::{
+    delete_layout, list_layouts, load_layout, save_layout, DiagramLayout, LayoutSummary,
+};
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use tauri::{State, Window};
+
+#[tauri::command]
+pub fn list_layouts_cmd(
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+) -> Result<Vec<LayoutSummary>, String> {
+    let timer = CommandTimer::start("list_layouts_cmd");
+    timer.finish(Ok(list_layouts(&state.storage_path, &server, &database)))
+}
+
+#[tauri::command]
+pub fn save_layout_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    layout: DiagramLayout,
+) -> Result<Vec<LayoutSummary>, String> {
+    let timer = CommandTimer::start("save_layout_cmd");
+    let result = state
+        .ensure_writable(window.label())
+        .and_then(|_| save_layout(&state.storage_path, &server, &database, layout));
+    timer.finish(result)
+}
+
+#[tauri::command]
+pub fn load_layout_cmd(
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    name: String,
+) -> Result<DiagramLayout, String> {
+    let timer = CommandTimer::start("load_layout_cmd");
+    timer.finish(load_layout(&state.storage_path, &server, &database, &name))
+}
+
+#[tauri::command]
+pub fn delete_layout_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    name: String,
+) -> Result<Vec<LayoutSummary>, String> {
+    let timer = CommandTimer::start("delete_layout_cmd");
+    let result = state
+        .ensure_writable(window.label())
+        .and_then(|_| delete_layout(&state.storage_path, &server, &database, &name));
+    timer.finish(result)
+}
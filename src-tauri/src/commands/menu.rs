@@ -1,23 +1,25 @@
-use serde::Deserialize;
-use tauri::AppHandle;
-
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct MenuUiState {
-    pub is_canvas_mode: bool,
-    pub has_focus: bool,
-    pub has_active_filters: bool,
-}
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use crate::windows::MenuUiState;
+use tauri::{AppHandle, State, Window};
 
+/// Record the calling window's menu state, and update the shared menu when
+/// that window is the focused one.
 #[tauri::command]
 pub fn set_menu_ui_state_cmd(
     app_handle: AppHandle,
+    window: Window,
+    app_state: State<'_, AppState>,
     state: MenuUiState,
 ) -> Result<(), String> {
-    crate::menu::set_menu_ui_state(
+    let timer = CommandTimer::start("set_menu_ui_state_cmd");
+    if !app_state.windows.set_menu_state(window.label(), state) {
+        return timer.finish(Ok(()));
+    }
+    timer.finish(crate::menu::set_menu_ui_state(
         &app_handle,
         state.is_canvas_mode,
         state.has_focus,
         state.has_active_filters,
-    )
+    ))
 }
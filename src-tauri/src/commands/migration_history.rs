@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use crate::db::{
+    create_client, load_migration_history, load_object_metadata, MigrationHistory, MigrationTool,
+    SchemaError,
+};
+use crate::ef_migrations::{correlate_migrations, scan_migrations, EfMigrationReport};
+use crate::perf::CommandTimer;
+use crate::types::{ConnectionParams, SchemaGraph};
+
+/// Applied Flyway, Liquibase or EF Core migrations of the database, with the
+/// objects each one created. `None` when the database has no history table.
+#[tauri::command]
+pub async fn get_migration_history_cmd(
+    params: ConnectionParams,
+) -> Result<Option<MigrationHistory>, SchemaError> {
+    let timer = CommandTimer::start("get_migration_history_cmd");
+    let result = async {
+        let mut client = create_client(&params).await?;
+        let metadata = load_object_metadata(&mut client).await?;
+        load_migration_history(&mut client, &metadata).await
+    }
+    .await;
+    timer.finish(result)
+}
+
+/// Correlate an EF Core `Migrations` source folder with the database's
+/// `__EFMigrationsHistory` and the loaded `graph`.
+#[tauri::command]
+pub async fn correlate_ef_migrations_cmd(
+    params: ConnectionParams,
+    graph: SchemaGraph,
+    folder: String,
+) -> Result<EfMigrationReport, SchemaError> {
+    let timer = CommandTimer::start("correlate_ef_migrations_cmd");
+    let result = async {
+        let default_schema = graph.default_schema.as_deref().unwrap_or("dbo");
+        let migrations = scan_migrations(Path::new(&folder), default_schema)
+            .map_err(SchemaError::InvalidQuery)?;
+        let mut client = create_client(&params).await?;
+        let history = load_migration_history(&mut client, &Default::default()).await?;
+        let applied = history
+            .filter(|h| h.tool == MigrationTool::EfCore)
+            .map(|h| h.migrations)
+            .unwrap_or_default();
+        Ok(correlate_migrations(&graph, &applied, migrations))
+    }
+    .await;
+    timer.finish(result)
+}
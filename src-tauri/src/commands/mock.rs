@@ -1,7 +1,9 @@
+use crate::perf::CommandTimer;
 use crate::types::{
     Column, ColumnSource, ProcedureParameter, RelationshipEdge, ScalarFunction, SchemaGraph,
     StoredProcedure, TableNode, Trigger, ViewNode,
 };
+use std::sync::Arc;
 
 struct MockConfig {
     tables: usize,
@@ -121,7 +123,7 @@ fn generate_tables(config: &MockConfig) -> Vec<TableNode> {
         let prefix_idx = simple_hash(i, 0) % TABLE_PREFIXES.len();
         let suffix_idx = simple_hash(i, 1) % TABLE_SUFFIXES.len();
 
-        let schema = SCHEMAS[schema_idx].to_string();
+        let schema: Arc<str> = SCHEMAS[schema_idx].into();
         let name = format!(
             "{}{}{}",
             TABLE_PREFIXES[prefix_idx], TABLE_SUFFIXES[suffix_idx], i
@@ -133,9 +135,10 @@ fn generate_tables(config: &MockConfig) -> Vec<TableNode> {
 
         columns.push(Column {
             name: "Id".to_string(),
-            data_type: "int".to_string(),
+            data_type: "int".into(),
             is_nullable: false,
             is_primary_key: true,
+            ordinal_position: 1,
             ..Default::default()
         });
 
@@ -145,9 +148,10 @@ fn generate_tables(config: &MockConfig) -> Vec<TableNode> {
 
             columns.push(Column {
                 name: format!("{}{}", COLUMN_NAMES[col_idx], c),
-                data_type: DATA_TYPES[type_idx].to_string(),
+                data_type: DATA_TYPES[type_idx].into(),
                 is_nullable: simple_hash(i * 100 + c, 5).is_multiple_of(2),
                 is_primary_key: false,
+                ordinal_position: c as u32 + 1,
                 ..Default::default()
             });
         }
@@ -157,6 +161,9 @@ fn generate_tables(config: &MockConfig) -> Vec<TableNode> {
             name,
             schema,
             columns,
+            source: None,
+            metadata: None,
+            change_capture: None,
         });
     }
 
@@ -190,6 +197,9 @@ fn generate_relationships(tables: &[TableNode], config: &MockConfig) -> Vec<Rela
             to: to_table.id.clone(),
             from_column: Some(fk_col_name),
             to_column: Some("Id".to_string()),
+            source: None,
+            is_virtual: false,
+            participation: None,
         });
     }
 
@@ -205,7 +215,7 @@ fn generate_views(tables: &[TableNode], config: &MockConfig) -> Vec<ViewNode> {
 
     for i in 0..config.views {
         let schema_idx = i % SCHEMAS.len();
-        let schema = SCHEMAS[schema_idx].to_string();
+        let schema: Arc<str> = SCHEMAS[schema_idx].into();
         let name = format!("vw_Report{}", i);
         let id = format!("{}.{}", schema, name);
 
@@ -236,12 +246,15 @@ fn generate_views(tables: &[TableNode], config: &MockConfig) -> Vec<ViewNode> {
                 data_type: source_column.data_type.clone(),
                 is_nullable: source_column.is_nullable,
                 is_primary_key: false,
+                ordinal_position: c as u32 + 1,
                 source_columns: vec![ColumnSource {
                     table: source_table.id.clone(),
                     column: source_column.name.clone(),
                 }],
                 source_table: Some(source_table.id.clone()),
                 source_column: Some(source_column.name.clone()),
+                is_identity: false,
+                is_computed: false,
             });
         }
 
@@ -258,6 +271,9 @@ fn generate_views(tables: &[TableNode], config: &MockConfig) -> Vec<ViewNode> {
             columns,
             definition,
             referenced_tables,
+            source: None,
+            metadata: None,
+            is_indexed: false,
         });
     }
 
@@ -277,7 +293,7 @@ fn generate_triggers(tables: &[TableNode], config: &MockConfig) -> Vec<Trigger>
         let table = &tables[table_idx];
 
         let name = format!("TR_{}_{}", table.name, i);
-        let trigger_type = trigger_types[simple_hash(i, 31) % trigger_types.len()].to_string();
+        let trigger_type = trigger_types[simple_hash(i, 31) % trigger_types.len()].into();
 
         let fires_on_insert = simple_hash(i, 32).is_multiple_of(2);
         let fires_on_update = simple_hash(i, 33).is_multiple_of(2) || !fires_on_insert;
@@ -305,6 +321,10 @@ fn generate_triggers(tables: &[TableNode], config: &MockConfig) -> Vec<Trigger>
             ),
             referenced_tables: vec![],
             affected_tables,
+            analysis: Default::default(),
+            watched_columns: Vec::new(),
+            source: None,
+            metadata: None,
         });
     }
 
@@ -317,7 +337,7 @@ fn generate_procedures(tables: &[TableNode], config: &MockConfig) -> Vec<StoredP
 
     for i in 0..config.procedures {
         let schema_idx = i % SCHEMAS.len();
-        let schema = SCHEMAS[schema_idx].to_string();
+        let schema: Arc<str> = SCHEMAS[schema_idx].into();
         let prefix = proc_prefixes[simple_hash(i, 40) % proc_prefixes.len()];
         let name = format!("{}Data{}", prefix, i);
         let id = format!("{}.{}", schema, name);
@@ -331,7 +351,7 @@ fn generate_procedures(tables: &[TableNode], config: &MockConfig) -> Vec<StoredP
 
             parameters.push(ProcedureParameter {
                 name: format!("@{}", COLUMN_NAMES[param_name_idx]),
-                data_type: DATA_TYPES[type_idx].to_string(),
+                data_type: DATA_TYPES[type_idx].into(),
                 is_output: p == num_params - 1 && simple_hash(i, 44).is_multiple_of(3),
             });
         }
@@ -365,11 +385,14 @@ fn generate_procedures(tables: &[TableNode], config: &MockConfig) -> Vec<StoredP
             id,
             name: name.clone(),
             schema,
-            procedure_type: "SQL_STORED_PROCEDURE".to_string(),
+            procedure_type: "SQL_STORED_PROCEDURE".into(),
             parameters,
             definition: format!("CREATE PROCEDURE {} -- Mock procedure {}", name, i),
             referenced_tables,
             affected_tables,
+            analysis: Default::default(),
+            source: None,
+            metadata: None,
         });
     }
 
@@ -389,7 +412,7 @@ fn generate_functions(tables: &[TableNode], config: &MockConfig) -> Vec<ScalarFu
 
     for i in 0..config.functions {
         let schema_idx = i % SCHEMAS.len();
-        let schema = SCHEMAS[schema_idx].to_string();
+        let schema: Arc<str> = SCHEMAS[schema_idx].into();
         let prefix = fn_prefixes[simple_hash(i, 50) % fn_prefixes.len()];
         let name = format!("{}Value{}", prefix, i);
         let id = format!("{}.{}", schema, name);
@@ -403,12 +426,12 @@ fn generate_functions(tables: &[TableNode], config: &MockConfig) -> Vec<ScalarFu
 
             parameters.push(ProcedureParameter {
                 name: format!("@{}", COLUMN_NAMES[param_name_idx]),
-                data_type: DATA_TYPES[type_idx].to_string(),
+                data_type: DATA_TYPES[type_idx].into(),
                 is_output: false,
             });
         }
 
-        let return_type = return_types[simple_hash(i, 54) % return_types.len()].to_string();
+        let return_type = return_types[simple_hash(i, 54) % return_types.len()].into();
 
         let mut referenced_tables = vec![];
         if !tables.is_empty() && simple_hash(i, 55).is_multiple_of(2) {
@@ -420,21 +443,22 @@ fn generate_functions(tables: &[TableNode], config: &MockConfig) -> Vec<ScalarFu
             id,
             name: name.clone(),
             schema,
-            function_type: "SQL_SCALAR_FUNCTION".to_string(),
+            function_type: "SQL_SCALAR_FUNCTION".into(),
             parameters,
             return_type,
             definition: format!("CREATE FUNCTION {} -- Mock function {}", name, i),
             referenced_tables,
             affected_tables: vec![],
+            source: None,
+            metadata: None,
         });
     }
 
     functions
 }
 
-#[tauri::command]
-pub fn load_schema_mock(size: String) -> Result<SchemaGraph, String> {
-    let config = MockConfig::from_size(&size);
+pub fn generate_mock_schema(size: &str) -> SchemaGraph {
+    let config = MockConfig::from_size(size);
 
     let tables = generate_tables(&config);
     let relationships = generate_relationships(&tables, &config);
@@ -443,14 +467,28 @@ pub fn load_schema_mock(size: String) -> Result<SchemaGraph, String> {
     let stored_procedures = generate_procedures(&tables, &config);
     let scalar_functions = generate_functions(&tables, &config);
 
-    Ok(SchemaGraph {
+    SchemaGraph {
         tables,
         views,
         relationships,
         triggers,
         stored_procedures,
         scalar_functions,
-    })
+        load_phases: Vec::new(),
+        warnings: Vec::new(),
+        links: Vec::new(),
+        agent_jobs: Vec::new(),
+        classifications: Vec::new(),
+        stewardship: Vec::new(),
+        default_schema: None,
+        version: 0,
+    }
+}
+
+#[tauri::command]
+pub fn load_schema_mock(size: String) -> Result<SchemaGraph, String> {
+    let timer = CommandTimer::start("load_schema_mock");
+    timer.finish(Ok(generate_mock_schema(&size)))
 }
 
 #[cfg(test)]
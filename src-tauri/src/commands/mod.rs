@@ -1,16 +1,134 @@
+pub mod analysis;
+pub mod annotations;
+pub mod bookmarks;
+pub mod bundle;
+pub mod canvas;
+pub mod catalog;
+pub mod changeset;
+pub mod containers;
+pub mod credentials;
+pub mod data_dictionary;
 pub mod databases;
+pub mod ddl;
+pub mod diagnostics;
+pub mod er_export;
 pub mod explorer;
+pub mod glossary;
+pub mod join_paths;
+pub mod layouts;
 pub mod menu;
+pub mod migration_history;
 pub mod mock;
+pub mod network_export;
+pub mod onboarding;
+pub mod openlineage;
+pub mod print;
+pub mod procedures;
+pub mod profiles;
+pub mod row_counts;
+pub mod sandboxes;
+pub mod saved_queries;
 pub mod schema;
+pub mod schema_summary;
+pub mod session;
 pub mod settings;
+pub mod sources;
+pub mod stewardship;
+pub mod tasks;
+pub mod updates;
+pub mod virtual_relationships;
+pub mod watch;
+pub mod windows;
+pub mod workspace;
 
-pub use databases::list_databases_cmd;
+pub use analysis::{
+    analyze_design_cmd, analyze_view_nesting_cmd, check_portability_cmd, detect_column_domains_cmd,
+    export_compliance_report_cmd, get_change_capture_report_cmd, get_change_heatmap_cmd,
+    get_check_rules_cmd, get_complexity_report_cmd, get_dynamic_sql_report_cmd,
+    get_graph_stats_cmd, run_compliance_cmd, simulate_type_change_cmd,
+};
+pub use annotations::{import_annotations_csv_cmd, list_annotations_cmd, set_annotation_cmd};
+pub use bookmarks::{list_bookmarks_cmd, remove_bookmark_cmd, set_bookmark_cmd};
+pub use bundle::{export_diagram_bundle_cmd, import_diagram_bundle_cmd};
+pub use canvas::{
+    acquire_canvas_lock_cmd, apply_naming_cmd, check_canvas_conflict_cmd,
+    get_canvas_file_stamp_cmd, merge_canvas_cmd, preview_naming_cmd, refactor_design_cmd,
+    release_canvas_lock_cmd,
+};
+pub use catalog::{get_catalog_target_cmd, push_catalog_cmd, set_catalog_target_cmd};
+pub use changeset::review_changeset_cmd;
+pub use containers::detect_local_sql_containers_cmd;
+pub use credentials::{
+    delete_credential_cmd, get_credential_cmd, has_credential_cmd, store_credential_cmd,
+};
+pub use data_dictionary::export_data_dictionary_cmd;
+pub use databases::{get_availability_info_cmd, list_databases_cmd, stream_databases_cmd};
+pub use ddl::{
+    export_ddl_cmd, export_migration_kit_cmd, export_rebuild_script_cmd, get_type_mappings_cmd,
+    plan_rename_cmd,
+};
+pub use diagnostics::{
+    clear_crash_reports_cmd, clear_performance_stats_cmd, diagnose_environment_cmd,
+    get_crash_reports_cmd, get_performance_stats_cmd, set_payload_measurement_cmd,
+};
+pub use er_export::export_er_diagram_cmd;
 pub use explorer::{
     bulk_scan_cmd, cancel_directory_cmd, cancel_scan_cmd, check_path_reachable,
     content_search_cmd, list_directory_cmd, read_file_cmd, toggle_favorite_cmd, ExplorerState,
 };
+pub use glossary::{
+    classify_object_cmd, get_glossary_cmd, list_classifications_cmd, remove_glossary_term_cmd,
+    remove_tag_cmd, save_glossary_term_cmd, save_tag_cmd,
+};
+pub use join_paths::suggest_join_path_cmd;
+pub use layouts::{delete_layout_cmd, list_layouts_cmd, load_layout_cmd, save_layout_cmd};
 pub use menu::set_menu_ui_state_cmd;
+pub use migration_history::{correlate_ef_migrations_cmd, get_migration_history_cmd};
 pub use mock::load_schema_mock;
-pub use schema::load_schema_cmd;
-pub use settings::{get_settings, save_settings};
+pub use network_export::export_network_graph_cmd;
+pub use onboarding::{
+    advance_onboarding_cmd, create_sample_workspace_cmd, get_onboarding_cmd, skip_onboarding_cmd,
+};
+pub use openlineage::export_openlineage_cmd;
+pub use print::print_diagram_cmd;
+pub use procedures::execute_procedure_cmd;
+pub use profiles::{
+    connect_profile_cmd, export_sanitized_profile_cmd, get_query_limits_cmd,
+    get_startup_action_cmd, import_profile_template_cmd, set_query_limits_cmd,
+    set_startup_action_cmd,
+};
+pub use row_counts::compare_row_counts_cmd;
+pub use sandboxes::{
+    create_sandbox_database_cmd, drop_sandbox_database_cmd, list_sandbox_databases_cmd,
+};
+pub use saved_queries::{
+    delete_saved_query_cmd, export_query_result_cmd, list_saved_queries_cmd, run_query_cmd,
+    run_saved_query_cmd, save_query_cmd,
+};
+pub use schema::{
+    diff_definitions_cmd, get_object_definition_cmd, get_object_history_cmd,
+    load_schema_binary_cmd, load_schema_cmd, load_schema_mock_binary, refresh_object_state_cmd,
+    refresh_schema_cmd, search_schema_index_cmd,
+};
+pub use schema_summary::summarize_schema_cmd;
+pub use session::{
+    is_read_only_session_cmd, is_safe_mode_cmd, restore_session_cmd, set_read_only_session_cmd,
+    update_session_cmd,
+};
+pub use settings::{
+    get_data_location_cmd, get_exclusions_cmd, get_export_theme_cmd, get_export_themes_cmd,
+    get_languages_cmd, get_schema_display_cmd, get_settings, save_settings, set_data_location_cmd,
+    set_exclusions_cmd, update_schema_display_cmd,
+};
+pub use sources::{
+    import_tabular_model_cmd, load_dbt_manifest_cmd, load_flat_files_cmd, scan_report_usage_cmd,
+};
+pub use stewardship::{list_stewardship_cmd, set_stewardship_cmd};
+pub use tasks::{cancel_task_cmd, list_tasks_cmd};
+pub use updates::{check_for_updates_cmd, defer_update_cmd, install_update_cmd};
+pub use virtual_relationships::{
+    add_virtual_relationship_cmd, list_virtual_relationships_cmd, remove_virtual_relationship_cmd,
+};
+pub use watch::start_watch_export_cmd;
+pub use windows::{get_window_context_cmd, open_new_window_cmd, take_launch_requests_cmd};
+pub use workspace::load_workspace_cmd;
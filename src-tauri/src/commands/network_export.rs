@@ -0,0 +1,11 @@
+use crate::network_export::{export_network, NetworkFormat};
+use crate::perf::CommandTimer;
+use crate::types::SchemaGraph;
+
+/// Tables, views and their relationships as GraphML or Cytoscape JSON, for
+/// network analysis in Gephi, Cytoscape and similar tools.
+#[tauri::command]
+pub fn export_network_graph_cmd(graph: SchemaGraph, format: NetworkFormat) -> String {
+    let timer = CommandTimer::start("export_network_graph_cmd");
+    timer.finish_value(export_network(&graph, format))
+}
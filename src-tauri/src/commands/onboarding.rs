@@ -0,0 +1,46 @@
+use crate::onboarding::{
+    create_sample_workspace, OnboardingState, OnboardingStatus, OnboardingStep, SampleWorkspace,
+};
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use tauri::{State, Window};
+
+#[tauri::command]
+pub fn get_onboarding_cmd(state: State<'_, AppState>) -> OnboardingStatus {
+    let timer = CommandTimer::start("get_onboarding_cmd");
+    timer.finish_value(OnboardingStatus {
+        first_run: state.is_first_run(),
+        state: state.onboarding_state(),
+    })
+}
+
+/// Finish tour step `from` and move to the next; a step already passed is
+/// left alone.
+#[tauri::command]
+pub fn advance_onboarding_cmd(
+    state: State<'_, AppState>,
+    from: OnboardingStep,
+) -> Result<OnboardingState, String> {
+    let timer = CommandTimer::start("advance_onboarding_cmd");
+    timer.finish(state.update_onboarding(|current| current.advance(from)))
+}
+
+#[tauri::command]
+pub fn skip_onboarding_cmd(state: State<'_, AppState>) -> Result<OnboardingState, String> {
+    let timer = CommandTimer::start("skip_onboarding_cmd");
+    timer.finish(state.update_onboarding(|_| OnboardingState::Skipped))
+}
+
+/// Store the demo schema, a layout and annotations under the sample
+/// connection so the tour has something to show.
+#[tauri::command]
+pub fn create_sample_workspace_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+) -> Result<SampleWorkspace, String> {
+    let timer = CommandTimer::start("create_sample_workspace_cmd");
+    let result = state
+        .ensure_writable(window.label())
+        .and_then(|_| create_sample_workspace(&state.storage_path));
+    timer.finish(result)
+}
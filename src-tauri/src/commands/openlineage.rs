@@ -0,0 +1,14 @@
+use chrono::Utc;
+
+use crate::openlineage::export_openlineage;
+use crate::perf::CommandTimer;
+use crate::types::SchemaGraph;
+
+/// View, procedure and foreign key lineage as newline-delimited
+/// OpenLineage events, for Marquez and other data catalogs.
+#[tauri::command]
+pub fn export_openlineage_cmd(server: String, database: String, graph: SchemaGraph) -> String {
+    let timer = CommandTimer::start("export_openlineage_cmd");
+    let event_time = Utc::now().to_rfc3339();
+    timer.finish_value(export_openlineage(&graph, &server, &database, &event_time))
+}
@@ -0,0 +1,61 @@
+use crate::export_annotations::ExportAnnotations;
+use crate::export_themes::active_export_theme;
+use crate::layouts::DiagramLayout;
+use crate::perf::CommandTimer;
+use crate::print::{render_print_document, PrintOptions, PrintPlan};
+use crate::state::AppState;
+use crate::types::SchemaGraph;
+use chrono::Utc;
+use tauri::{AppHandle, State, Url, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+/// Opens the system print dialog once the document has loaded.
+const PRINT_ON_LOAD: &str = "window.addEventListener('load', () => window.print());";
+
+/// Render the diagram as paginated pages in the active export theme and open
+/// it in a print window, which shows the OS print dialog. Returns the page
+/// plan so the caller can say how many sheets will print. `annotations` add a
+/// title block and watermark, and turn on the legend.
+#[tauri::command]
+pub fn print_diagram_cmd(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    title: String,
+    graph: SchemaGraph,
+    layout: DiagramLayout,
+    options: PrintOptions,
+    annotations: Option<ExportAnnotations>,
+) -> Result<PrintPlan, String> {
+    let timer = CommandTimer::start("print_diagram_cmd");
+    let result = state.get_settings().and_then(|settings| {
+        let theme = active_export_theme(&settings);
+        let annotations = annotations.unwrap_or_default().stamped();
+        let (plan, html) =
+            render_print_document(&title, &graph, &layout, &theme, &options, &annotations);
+
+        let stamp = Utc::now().format("%Y%m%d%H%M%S%3f");
+        let path = std::env::temp_dir().join(format!("monocle-print-{}.html", stamp));
+        let url = Url::from_file_path(&path)
+            .map_err(|_| format!("Invalid print document path: {}", path.display()))?;
+        std::fs::write(&path, html)
+            .map_err(|e| format!("Failed to write print document: {}", e))?;
+
+        let window =
+            WebviewWindowBuilder::new(&app, format!("print-{}", stamp), WebviewUrl::External(url))
+                .title(format!("Print - {}", title))
+                .inner_size(900.0, 700.0)
+                .initialization_script(PRINT_ON_LOAD)
+                .build()
+                .map_err(|e| {
+                    let _ = std::fs::remove_file(&path);
+                    format!("Failed to open print window: {}", e)
+                })?;
+        // The document is only needed while its window is open
+        window.on_window_event(move |event| {
+            if let WindowEvent::Destroyed = event {
+                let _ = std::fs::remove_file(&path);
+            }
+        });
+        Ok(plan)
+    });
+    timer.finish(result)
+}
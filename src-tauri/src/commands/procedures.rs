@@ -0,0 +1,47 @@
+use std::collections::BTreeMap;
+
+use crate::db::{create_client, execute_procedure, ProcedureRun, SchemaError};
+use crate::perf::CommandTimer;
+use crate::state::{connection_key, AppState};
+use crate::types::{ConnectionParams, StoredProcedure};
+use tauri::{State, Window};
+
+/// Test-run a stored procedure with `values` keyed by parameter name. Its
+/// changes are rolled back unless `commit` is set, which a read-only
+/// session or blocked writes refuse, or unless the procedure ends the
+/// transaction itself; see `execute_procedure`. The profile's preview row
+/// and timeout limits apply.
+#[tauri::command]
+pub async fn execute_procedure_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    params: ConnectionParams,
+    procedure: StoredProcedure,
+    values: Option<BTreeMap<String, Option<String>>>,
+    commit: Option<bool>,
+    max_rows: Option<usize>,
+) -> Result<ProcedureRun, SchemaError> {
+    let timer = CommandTimer::start("execute_procedure_cmd");
+    let result = async {
+        let commit = commit.unwrap_or(false);
+        if commit {
+            state
+                .ensure_writable(window.label())
+                .and_then(|_| state.ensure_writes_allowed())
+                .map_err(SchemaError::ReadOnly)?;
+        }
+        let limits = state.query_limits_for(&connection_key(&params.server, &params.database));
+        let values = values.unwrap_or_default();
+        let mut client = create_client(&params).await?;
+        let run = execute_procedure(
+            &mut client,
+            &procedure,
+            &values,
+            commit,
+            limits.preview_rows(max_rows),
+        );
+        limits.run(run).await?
+    }
+    .await;
+    timer.finish(result)
+}
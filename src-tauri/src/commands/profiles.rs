@@ -0,0 +1,188 @@
+use crate::commands::schema::{load_schema_task, prepare_cached_graph};
+use crate::db::QueryLimits;
+use crate::diagnostics::record_action;
+use crate::graph_cache::read_cached_graph;
+use crate::layouts::{load_layout, DiagramLayout};
+use crate::perf::CommandTimer;
+use crate::profiles::{
+    read_profile_template, sanitize_profile, write_profile_template, ProfileTemplate, StartupAction,
+};
+use crate::state::{connection_key, AppState};
+use crate::types::{ConnectionParams, SchemaGraph};
+use serde::Serialize;
+use std::path::Path;
+use tauri::{AppHandle, Emitter, State, Window};
+
+/// Write a credential-free copy of a connection profile, with the permissions
+/// the recipient's login needs, for handing to other developers.
+#[tauri::command]
+pub fn export_sanitized_profile_cmd(
+    state: State<'_, AppState>,
+    path: String,
+    name: String,
+    params: ConnectionParams,
+) -> Result<ProfileTemplate, String> {
+    let timer = CommandTimer::start("export_sanitized_profile_cmd");
+    let connection = connection_key(&params.server, &params.database);
+    let template = sanitize_profile(
+        &name,
+        &params,
+        state.startup_action_for(&connection),
+        state.query_limits_for(&connection),
+    );
+    timer.finish(write_profile_template(Path::new(&path), &template).map(|_| template))
+}
+
+/// Read a shared profile; the caller prompts for credentials before connecting
+/// and stores its startup action and query limits.
+#[tauri::command]
+pub fn import_profile_template_cmd(path: String) -> Result<ProfileTemplate, String> {
+    let timer = CommandTimer::start("import_profile_template_cmd");
+    timer.finish(read_profile_template(Path::new(&path)))
+}
+
+#[tauri::command]
+pub fn get_startup_action_cmd(
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+) -> StartupAction {
+    let timer = CommandTimer::start("get_startup_action_cmd");
+    timer.finish_value(state.startup_action_for(&connection_key(&server, &database)))
+}
+
+#[tauri::command]
+pub fn set_startup_action_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    action: StartupAction,
+) -> Result<(), String> {
+    let timer = CommandTimer::start("set_startup_action_cmd");
+    let result = state
+        .ensure_writable(window.label())
+        .and_then(|_| state.set_startup_action(&connection_key(&server, &database), action));
+    timer.finish(result)
+}
+
+#[tauri::command]
+pub fn get_query_limits_cmd(
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+) -> QueryLimits {
+    let timer = CommandTimer::start("get_query_limits_cmd");
+    timer.finish_value(state.query_limits_for(&connection_key(&server, &database)))
+}
+
+/// Caps on preview rows, query time and statements per batch for one
+/// profile, enforced by the query runner.
+#[tauri::command]
+pub fn set_query_limits_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    limits: QueryLimits,
+) -> Result<(), String> {
+    let timer = CommandTimer::start("set_query_limits_cmd");
+    let result = state
+        .ensure_writable(window.label())
+        .and_then(|_| state.set_query_limits(&connection_key(&server, &database), limits));
+    timer.finish(result)
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectStep {
+    ReadingCache,
+    LoadingSchema,
+    ApplyingLayout,
+    Done,
+}
+
+/// Payload of the `connect-progress` event.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectProgress {
+    pub server: String,
+    pub database: String,
+    pub step: ConnectStep,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectResult {
+    pub action: StartupAction,
+    pub graph: SchemaGraph,
+    /// When the graph came from the cache rather than the server.
+    pub cached_at: Option<String>,
+    /// The saved layout for `OpenLayout`; `None` if it no longer exists.
+    pub layout: Option<DiagramLayout>,
+}
+
+/// Connect with a profile and run its startup action, emitting
+/// `connect-progress` as each step starts.
+#[tauri::command]
+pub async fn connect_profile_cmd(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    params: ConnectionParams,
+) -> Result<ConnectResult, String> {
+    let timer = CommandTimer::start("connect_profile_cmd");
+    record_action(format!(
+        "connect_profile {} / {}",
+        params.server, params.database
+    ));
+    let action = state.startup_action_for(&connection_key(&params.server, &params.database));
+    let progress = |step| {
+        let _ = app.emit(
+            "connect-progress",
+            ConnectProgress {
+                server: params.server.clone(),
+                database: params.database.clone(),
+                step,
+            },
+        );
+    };
+
+    let result = async {
+        let cached = match action {
+            StartupAction::LoadCached if !state.is_safe_mode() => {
+                progress(ConnectStep::ReadingCache);
+                read_cached_graph(&state.storage_path, &params.server, &params.database)
+            }
+            _ => None,
+        };
+        let (graph, cached_at) = match cached {
+            Some(mut cached) => {
+                prepare_cached_graph(&state, &params.server, &params.database, &mut cached.graph);
+                (cached.graph, Some(cached.cached_at))
+            }
+            None => {
+                progress(ConnectStep::LoadingSchema);
+                let graph = load_schema_task(&state, &params)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                (graph, None)
+            }
+        };
+        let layout = match &action {
+            StartupAction::OpenLayout { name } => {
+                progress(ConnectStep::ApplyingLayout);
+                load_layout(&state.storage_path, &params.server, &params.database, name).ok()
+            }
+            _ => None,
+        };
+        progress(ConnectStep::Done);
+        Ok(ConnectResult {
+            action: action.clone(),
+            graph,
+            cached_at,
+            layout,
+        })
+    }
+    .await;
+    timer.finish(result)
+}
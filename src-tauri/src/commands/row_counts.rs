@@ -0,0 +1,64 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::db::{
+    compare_row_counts, create_client, key_checksum, load_primary_key_columns, load_row_counts,
+    RowCountDrift, SchemaError, DEFAULT_DRIFT_THRESHOLD_PERCENT,
+};
+use crate::perf::CommandTimer;
+use crate::types::ConnectionParams;
+
+/// Compare per-table row counts between two databases, e.g. a replica and
+/// its primary or a migration source and target. With `include_checksums`
+/// the primary key columns of tables present on both sides are checksummed
+/// too, which scans those tables.
+#[tauri::command]
+pub async fn compare_row_counts_cmd(
+    source: ConnectionParams,
+    target: ConnectionParams,
+    include_checksums: Option<bool>,
+    threshold_percent: Option<f64>,
+) -> Result<Vec<RowCountDrift>, SchemaError> {
+    let timer = CommandTimer::start("compare_row_counts_cmd");
+    let result = async {
+        let mut source_client = create_client(&source).await?;
+        let mut target_client = create_client(&target).await?;
+        let (source_counts, target_counts) = tokio::try_join!(
+            load_row_counts(&mut source_client),
+            load_row_counts(&mut target_client)
+        )?;
+
+        let mut checksums = HashMap::new();
+        if include_checksums.unwrap_or(false) {
+            let keys = load_primary_key_columns(&mut source_client).await?;
+            for (id, (schema_name, table_name, _)) in &source_counts {
+                let Some(columns) = keys.get(id).filter(|_| target_counts.contains_key(id)) else {
+                    continue;
+                };
+                // A key the target lacks fails that table's checksum, not the comparison
+                let (source_sum, target_sum) = tokio::join!(
+                    key_checksum(&mut source_client, schema_name, table_name, columns),
+                    key_checksum(&mut target_client, schema_name, table_name, columns)
+                );
+                checksums.insert(
+                    id.clone(),
+                    (source_sum.ok().flatten(), target_sum.ok().flatten()),
+                );
+            }
+        }
+
+        let rows = |counts: &BTreeMap<String, (String, String, i64)>| -> BTreeMap<String, i64> {
+            counts
+                .iter()
+                .map(|(id, (_, _, rows))| (id.clone(), *rows))
+                .collect()
+        };
+        Ok(compare_row_counts(
+            &rows(&source_counts),
+            &rows(&target_counts),
+            &checksums,
+            threshold_percent.unwrap_or(DEFAULT_DRIFT_THRESHOLD_PERCENT),
+        ))
+    }
+    .await;
+    timer.finish(result)
+}
@@ -0,0 +1,58 @@
+use tauri::{State, Window};
+
+use crate::db::SchemaError;
+use crate::diagnostics::record_action;
+use crate::perf::CommandTimer;
+use crate::sandboxes::{create_sandbox, drop_sandbox, SandboxDatabase};
+use crate::state::AppState;
+use crate::types::{SchemaGraph, ServerConnectionParams};
+
+/// Create a disposable database on `params.server` and build `graph` in it,
+/// for smoke-testing a canvas design. Rejected batches are reported in the
+/// result. Refused while writes are blocked or the window is read-only.
+#[tauri::command]
+pub async fn create_sandbox_database_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    params: ServerConnectionParams,
+    graph: SchemaGraph,
+) -> Result<SandboxDatabase, SchemaError> {
+    let timer = CommandTimer::start("create_sandbox_database_cmd");
+    record_action(format!("create_sandbox {}", params.server));
+    let result = match ensure_sandbox_writes(&state, &window) {
+        Ok(()) => create_sandbox(&state.sandboxes, &params, &graph).await,
+        Err(e) => Err(e),
+    };
+    timer.finish(result)
+}
+
+/// Drop a sandbox created by `create_sandbox_database_cmd` in this session.
+/// Refused while writes are blocked or the window is read-only.
+#[tauri::command]
+pub async fn drop_sandbox_database_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    database: String,
+) -> Result<(), SchemaError> {
+    let timer = CommandTimer::start("drop_sandbox_database_cmd");
+    record_action(format!("drop_sandbox {}", database));
+    let result = match ensure_sandbox_writes(&state, &window) {
+        Ok(()) => drop_sandbox(&state.sandboxes, &database).await,
+        Err(e) => Err(e),
+    };
+    timer.finish(result)
+}
+
+fn ensure_sandbox_writes(state: &AppState, window: &Window) -> Result<(), SchemaError> {
+    state
+        .ensure_writable(window.label())
+        .and_then(|_| state.ensure_writes_allowed())
+        .map_err(SchemaError::ReadOnly)
+}
+
+/// Sandboxes created in this session and not yet dropped.
+#[tauri::command]
+pub fn list_sandbox_databases_cmd(state: State<'_, AppState>) -> Vec<String> {
+    let timer = CommandTimer::start("list_sandbox_databases_cmd");
+    timer.finish_value(state.sandboxes.names())
+}
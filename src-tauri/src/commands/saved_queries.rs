@@ -0,0 +1,168 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::db::{
+    create_client, run_read_only_query, stream_read_only_query, QueryResult, RowsStreamed,
+    SchemaError,
+};
+use crate::perf::CommandTimer;
+use crate::query_export::{QueryExportFormat, QueryResultWriter};
+use crate::saved_queries::{
+    delete_saved_query, list_saved_queries, load_saved_query, save_query, SavedQuery,
+};
+use crate::state::{connection_key, AppState};
+use crate::types::ConnectionParams;
+use tauri::{State, Window};
+
+/// Saved queries, narrowed to one database when `server` and `database`
+/// are both given.
+#[tauri::command]
+pub fn list_saved_queries_cmd(
+    state: State<'_, AppState>,
+    server: Option<String>,
+    database: Option<String>,
+) -> Result<Vec<SavedQuery>, String> {
+    let timer = CommandTimer::start("list_saved_queries_cmd");
+    let connection = server.as_deref().zip(database.as_deref());
+    timer.finish(Ok(list_saved_queries(&state.storage_path, connection)))
+}
+
+#[tauri::command]
+pub fn save_query_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    query: SavedQuery,
+) -> Result<Vec<SavedQuery>, String> {
+    let timer = CommandTimer::start("save_query_cmd");
+    let result = state
+        .ensure_writable(window.label())
+        .and_then(|_| save_query(&state.storage_path, query));
+    timer.finish(result)
+}
+
+#[tauri::command]
+pub fn delete_saved_query_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<Vec<SavedQuery>, String> {
+    let timer = CommandTimer::start("delete_saved_query_cmd");
+    let result = state
+        .ensure_writable(window.label())
+        .and_then(|_| delete_saved_query(&state.storage_path, &name));
+    timer.finish(result)
+}
+
+/// Run a saved query on `params` through the read-only runner. Parameters
+/// missing from `values` fall back to their defaults.
+#[tauri::command]
+pub async fn run_saved_query_cmd(
+    state: State<'_, AppState>,
+    params: ConnectionParams,
+    name: String,
+    values: Option<BTreeMap<String, String>>,
+    max_rows: Option<usize>,
+) -> Result<QueryResult, SchemaError> {
+    let timer = CommandTimer::start("run_saved_query_cmd");
+    let result = async {
+        let query =
+            load_saved_query(&state.storage_path, &name).map_err(SchemaError::InvalidQuery)?;
+        let limits = state.query_limits_for(&connection_key(&params.server, &params.database));
+        let mut client = create_client(&params).await?;
+        run_read_only_query(
+            &mut client,
+            &query.sql,
+            &query.parameters,
+            &values.unwrap_or_default(),
+            max_rows,
+            &limits,
+        )
+        .await
+    }
+    .await;
+    timer.finish(result)
+}
+
+/// Run an unsaved query through the read-only runner.
+#[tauri::command]
+pub async fn run_query_cmd(
+    state: State<'_, AppState>,
+    params: ConnectionParams,
+    sql: String,
+    max_rows: Option<usize>,
+) -> Result<QueryResult, SchemaError> {
+    let timer = CommandTimer::start("run_query_cmd");
+    let result = async {
+        let limits = state.query_limits_for(&connection_key(&params.server, &params.database));
+        let mut client = create_client(&params).await?;
+        run_read_only_query(&mut client, &sql, &[], &BTreeMap::new(), max_rows, &limits).await
+    }
+    .await;
+    timer.finish(result)
+}
+
+/// What to run for an export: ad-hoc SQL or a saved query.
+#[derive(Deserialize, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum QuerySource {
+    Sql {
+        sql: String,
+    },
+    SavedQuery {
+        name: String,
+        #[serde(default)]
+        values: BTreeMap<String, String>,
+    },
+}
+
+/// Run a query through the read-only runner and stream every row to `path`
+/// as CSV, JSON or Excel. Rows are written as they arrive, so the result is
+/// never held in memory; only Excel's sheet size limit truncates it.
+#[tauri::command]
+pub async fn export_query_result_cmd(
+    state: State<'_, AppState>,
+    params: ConnectionParams,
+    source: QuerySource,
+    format: QueryExportFormat,
+    path: String,
+) -> Result<RowsStreamed, SchemaError> {
+    let timer = CommandTimer::start("export_query_result_cmd");
+    let result = async {
+        let (sql, parameters, values) = match source {
+            QuerySource::Sql { sql } => (sql, Vec::new(), BTreeMap::new()),
+            QuerySource::SavedQuery { name, values } => {
+                let query = load_saved_query(&state.storage_path, &name)
+                    .map_err(SchemaError::InvalidQuery)?;
+                (query.sql, query.parameters, values)
+            }
+        };
+        let limits = state.query_limits_for(&connection_key(&params.server, &params.database));
+        let mut client = create_client(&params).await?;
+        let path = Path::new(&path);
+        let mut writer = QueryResultWriter::create(path, format).map_err(SchemaError::Export)?;
+        let streamed = stream_read_only_query(
+            &mut client,
+            &sql,
+            &parameters,
+            &values,
+            format.max_rows(),
+            &limits,
+            &mut writer,
+        )
+        .await;
+        match streamed.and_then(|streamed| {
+            writer.finish().map_err(SchemaError::Export)?;
+            Ok(streamed)
+        }) {
+            Ok(streamed) => Ok(streamed),
+            Err(e) => {
+                // Don't leave a half-written file behind
+                let _ = std::fs::remove_file(path);
+                Err(e)
+            }
+        }
+    }
+    .await;
+    timer.finish(result)
+}
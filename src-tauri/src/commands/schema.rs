@@ -1,7 +1,450 @@
-use crate::db::{load_schema, SchemaError};
-use crate::types::{ConnectionParams, SchemaGraph};
+use crate::commands::mock::generate_mock_schema;
+use crate::db::{create_client, fetch_definition, load_object_states, load_schema, SchemaError};
+use crate::definition_diff::{diff_definitions, DefinitionDiff, DiffOptions};
+use crate::diagnostics::record_action;
+use crate::exclusions::{apply_exclusions, compile_saved_patterns};
+use crate::glossary::{apply_classifications, read_glossary};
+use crate::graph_cache::{patch_cached_graph, read_cached_graph, store_graph};
+use crate::graph_patch::{object_state_patch, SchemaPatch, SchemaPatchEvent};
+use crate::perf::CommandTimer;
+use crate::search_index::SearchHit;
+use crate::snapshots::{object_history, record_snapshot, HistoryEntry};
+use crate::state::{connection_key, AppState};
+use crate::stewardship::apply_stewardship;
+use crate::types::{ConnectionParams, ObjectState, SchemaGraph};
+use crate::virtual_relationships::apply_virtual_relationships;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use tauri::ipc::Response;
+use tauri::{AppHandle, Emitter, State, Window};
 
+/// Encode a graph as MessagePack with field names preserved, so the frontend
+/// decoder yields the same object shape as the JSON response.
+fn encode_msgpack(graph: &SchemaGraph) -> Result<Vec<u8>, SchemaError> {
+    rmp_serde::to_vec_named(graph).map_err(|e| SchemaError::Export(e.to_string()))
+}
+
+/// Run a schema load as a task that `cancel_task_cmd` can abort.
+pub(crate) async fn load_schema_task(
+    state: &AppState,
+    params: &ConnectionParams,
+) -> Result<SchemaGraph, SchemaError> {
+    load_schema_versioned(state, params)
+        .await
+        .map(|(graph, _)| graph)
+}
+
+/// Tell every window that a connection's graph moved to a new version.
+pub(crate) fn emit_schema_patch(app: &AppHandle, params: &ConnectionParams, patch: SchemaPatch) {
+    let _ = app.emit(
+        "schema-patch",
+        SchemaPatchEvent {
+            server: params.server.clone(),
+            database: params.database.clone(),
+            patch,
+        },
+    );
+}
+
+/// Like `load_schema_task`, also returning the patch from the previously
+/// cached version when the schema changed since. A load requested while an
+/// identical one is running waits for it and gets the same result.
+pub(crate) async fn load_schema_versioned(
+    state: &AppState,
+    params: &ConnectionParams,
+) -> Result<(SchemaGraph, Option<SchemaPatch>), SchemaError> {
+    state
+        .schema_loads
+        .run(
+            &schema_load_key(params),
+            run_schema_load(state, params),
+            SchemaError::Joined,
+        )
+        .await
+}
+
+/// Only a request that would connect the same way may join a running load,
+/// since it gets that load's graph or error: the same login and password
+/// (another login may see a different schema, a wrong password must fail),
+/// auth type and connection options. Integrated logins are all the user
+/// running the app. The password only goes into the key hashed.
+fn schema_load_key(params: &ConnectionParams) -> String {
+    let mut connection = DefaultHasher::new();
+    params.sql_login().hash(&mut connection);
+    (
+        params.trust_server_certificate,
+        params.multi_subnet_failover,
+        params.read_only_intent,
+    )
+        .hash(&mut connection);
+    format!(
+        "{}|{:?}|{:?}|{}|{:016x}",
+        connection_key(&params.server, &params.database),
+        params.engine,
+        params.auth_type,
+        params.sql_login().map_or("", |(username, _)| username),
+        connection.finish()
+    )
+}
+
+fn apply_saved_exclusions(state: &AppState, server: &str, database: &str, graph: &mut SchemaGraph) {
+    let patterns = state.exclusions_for(&connection_key(server, database));
+    apply_exclusions(graph, &compile_saved_patterns(&patterns));
+}
+
+/// Bring a graph read from the cache in line with a fresh load: virtual
+/// relationships are replaced with the saved ones, which may have changed
+/// since it was cached, and exclusions are applied.
+pub(crate) fn prepare_cached_graph(
+    state: &AppState,
+    server: &str,
+    database: &str,
+    graph: &mut SchemaGraph,
+) {
+    graph.relationships.retain(|r| !r.is_virtual);
+    apply_virtual_relationships(
+        &state.storage_path,
+        &connection_key(server, database),
+        graph,
+    );
+    apply_saved_exclusions(state, server, database, graph);
+}
+
+async fn run_schema_load(
+    state: &AppState,
+    params: &ConnectionParams,
+) -> Result<(SchemaGraph, Option<SchemaPatch>), SchemaError> {
+    let task = state.tasks.start(
+        "schema_load",
+        format!("{} / {}", params.server, params.database),
+    );
+    let mut result = tokio::select! {
+        result = load_schema(params, |progress, message| {
+            state
+                .tasks
+                .set_progress(&task.id, Some(progress), Some(message.to_string()))
+        }) => result,
+        _ = task.cancelled() => Err(SchemaError::Cancelled),
+    };
+    if let Ok(graph) = result.as_mut() {
+        apply_virtual_relationships(
+            &state.storage_path,
+            &connection_key(&params.server, &params.database),
+            graph,
+        );
+        apply_classifications(&state.storage_path, &params.server, &params.database, graph);
+        apply_stewardship(&state.storage_path, &params.server, &params.database, graph);
+    }
+    state.tasks.finish(&task.id, &result);
+    let mut patch = None;
+    if let Ok(graph) = result.as_mut() {
+        if let Err(e) = state.record_database_use(&params.server, &params.database) {
+            eprintln!("Failed to record recent database: {}", e);
+        }
+        match store_graph(&state.storage_path, &params.server, &params.database, graph) {
+            Ok(stored) => patch = stored,
+            Err(e) => eprintln!("Failed to cache schema: {}", e),
+        }
+        if let Err(e) = state.search_indexes.update(
+            &state.storage_path,
+            &params.server,
+            &params.database,
+            graph,
+        ) {
+            eprintln!("Failed to update search index: {}", e);
+        }
+        // History is best-effort; a failed write must not fail the load
+        if let Err(e) =
+            record_snapshot(&state.storage_path, &params.server, &params.database, graph)
+        {
+            eprintln!("Failed to record definition snapshot: {}", e);
+        }
+        // The cache and history above keep hidden objects, so editing the
+        // exclusion list never loses them; only what is served is filtered
+        apply_saved_exclusions(state, &params.server, &params.database, graph);
+        if let Some(patch) = patch.as_mut() {
+            patch.restrict_to(graph);
+        }
+    }
+    result.map(|graph| (graph, patch))
+}
+
+/// A live graph replaced whatever the window was viewing, so an imported
+/// bundle's read-only mode no longer applies.
+pub(crate) fn leave_read_only<T, E>(state: &AppState, window: &Window, result: &Result<T, E>) {
+    if result.is_ok() {
+        state.windows.set_read_only(window.label(), false);
+    }
+}
+
+#[tauri::command]
+pub async fn load_schema_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    params: ConnectionParams,
+) -> Result<SchemaGraph, SchemaError> {
+    let timer = CommandTimer::start("load_schema_cmd");
+    record_action(format!(
+        "load_schema {} / {}",
+        params.server, params.database
+    ));
+    let result = load_schema_task(&state, &params).await;
+    leave_read_only(&state, &window, &result);
+    timer.finish(result)
+}
+
+/// Same as `load_schema_cmd`, but returns the graph as a raw MessagePack
+/// response, which skips JSON stringification for very large schemas.
+#[tauri::command]
+pub async fn load_schema_binary_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    params: ConnectionParams,
+) -> Result<Response, SchemaError> {
+    let timer = CommandTimer::start("load_schema_binary_cmd");
+    record_action(format!(
+        "load_schema_binary {} / {}",
+        params.server, params.database
+    ));
+    let bytes = load_schema_task(&state, &params)
+        .await
+        .and_then(|graph| encode_msgpack(&graph));
+    leave_read_only(&state, &window, &bytes);
+    timer.finish_bytes(bytes).map(Response::new)
+}
+
+/// Fetch one module definition in full, independent of a schema load.
+#[tauri::command]
+pub async fn get_object_definition_cmd(
+    params: ConnectionParams,
+    schema: String,
+    name: String,
+) -> Result<String, SchemaError> {
+    let timer = CommandTimer::start("get_object_definition_cmd");
+    let mut client = create_client(&params).await?;
+    timer.finish(fetch_definition(&mut client, &schema, &name).await)
+}
+
+#[tauri::command]
+pub fn load_schema_mock_binary(size: String) -> Result<Response, SchemaError> {
+    let timer = CommandTimer::start("load_schema_mock_binary");
+    timer
+        .finish_bytes(encode_msgpack(&generate_mock_schema(&size)))
+        .map(Response::new)
+}
+
+/// Reload the schema and, if it changed since the cached version, emit and
+/// return the patch between them instead of the whole graph. `None` means
+/// the cached graph is still current.
+#[tauri::command]
+pub async fn refresh_schema_cmd(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    params: ConnectionParams,
+) -> Result<Option<SchemaPatch>, SchemaError> {
+    let timer = CommandTimer::start("refresh_schema_cmd");
+    let result = load_schema_versioned(&state, &params)
+        .await
+        .map(|(_, patch)| {
+            if let Some(patch) = &patch {
+                emit_schema_patch(&app, &params, patch.clone());
+            }
+            patch
+        });
+    timer.finish(result)
+}
+
+/// Re-read trigger, constraint and index enable/trust flags without a full
+/// schema load, so toggles made in other tools show up quickly. Trigger
+/// changes are also applied to the cached graph and emitted as a patch.
+#[tauri::command]
+pub async fn refresh_object_state_cmd(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    params: ConnectionParams,
+) -> Result<Vec<ObjectState>, SchemaError> {
+    let timer = CommandTimer::start("refresh_object_state_cmd");
+    let mut client = create_client(&params).await?;
+    let states = load_object_states(&mut client).await;
+    if let (Ok(states), Some(cached)) = (
+        &states,
+        read_cached_graph(&state.storage_path, &params.server, &params.database),
+    ) {
+        let patch = object_state_patch(&cached.graph, states);
+        if !patch.is_empty() {
+            match patch_cached_graph(
+                &state.storage_path,
+                &params.server,
+                &params.database,
+                &patch,
+            ) {
+                Ok(()) => emit_schema_patch(&app, &params, patch),
+                Err(e) => eprintln!("Failed to patch cached schema: {}", e),
+            }
+        }
+    }
+    timer.finish(states)
+}
+
+/// Recorded definition versions of one object, oldest first, with a line
+/// diff against the previous version. Versions are captured on each load.
+#[tauri::command]
+pub fn get_object_history_cmd(
+    state: State<'_, AppState>,
+    params: ConnectionParams,
+    object_id: String,
+) -> Result<Vec<HistoryEntry>, String> {
+    let timer = CommandTimer::start("get_object_history_cmd");
+    timer.finish(Ok(object_history(
+        &state.storage_path,
+        &params.server,
+        &params.database,
+        &object_id,
+    )))
+}
+
+/// Line and token diff of two definitions, ignoring whitespace, bracket
+/// style and casing unless `options` says otherwise. Shared by the snapshot
+/// history, environment comparison and changeset review views.
 #[tauri::command]
-pub async fn load_schema_cmd(params: ConnectionParams) -> Result<SchemaGraph, SchemaError> {
-    load_schema(&params).await
+pub fn diff_definitions_cmd(
+    text_a: String,
+    text_b: String,
+    options: Option<DiffOptions>,
+) -> Result<DefinitionDiff, String> {
+    let timer = CommandTimer::start("diff_definitions_cmd");
+    timer.finish(Ok(diff_definitions(
+        &text_a,
+        &text_b,
+        &options.unwrap_or_default(),
+    )))
+}
+
+/// Search the names, columns and definitions of the connection's last loaded
+/// graph through its persisted index, tolerating typos and expanding glossary
+/// synonyms, with character ranges to highlight.
+/// The index is rebuilt from the cached graph when missing, and is empty
+/// until the schema has been loaded once.
+#[tauri::command]
+pub fn search_schema_index_cmd(
+    state: State<'_, AppState>,
+    params: ConnectionParams,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<SearchHit>, String> {
+    let timer = CommandTimer::start("search_schema_index_cmd");
+    let indexes = &state.search_indexes;
+    let (storage, server, database) = (&state.storage_path, &params.server, &params.database);
+    let index = indexes.load(storage, server, database).or_else(|| {
+        let cached = read_cached_graph(storage, server, database)?;
+        indexes
+            .update(storage, server, database, &cached.graph)
+            .ok()?;
+        indexes.load(storage, server, database)
+    });
+    timer.finish(Ok(index
+        .map(|index| index.search(&query, limit, &read_glossary(storage)))
+        .unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AuthType;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn only_identical_credentials_share_a_schema_load() {
+        let params = |username: &str, password: &str, auth_type: AuthType| ConnectionParams {
+            server: "sql01".to_string(),
+            database: "Sales".to_string(),
+            engine: Default::default(),
+            auth_type,
+            username: Some(username.to_string()),
+            password: Some(password.to_string()),
+            trust_server_certificate: false,
+            multi_subnet_failover: false,
+            read_only_intent: false,
+        };
+        let key = schema_load_key(&params("ann", "right", AuthType::SqlServer));
+
+        assert_eq!(
+            key,
+            schema_load_key(&params("ann", "right", AuthType::SqlServer))
+        );
+        assert_ne!(
+            key,
+            schema_load_key(&params("ann", "wrong", AuthType::SqlServer))
+        );
+        assert_ne!(
+            key,
+            schema_load_key(&params("ann", "right", AuthType::Windows))
+        );
+        assert!(!key.contains("right"));
+        // Integrated logins ignore leftover SQL credentials
+        assert_eq!(
+            schema_load_key(&params("ann", "right", AuthType::Windows)),
+            schema_load_key(&params("bob", "other", AuthType::Windows))
+        );
+    }
+
+    #[test]
+    fn msgpack_round_trips_and_beats_json_on_large_graph() {
+        let graph = generate_mock_schema("large");
+        let json = serde_json::to_vec(&graph).unwrap();
+        let packed = encode_msgpack(&graph).unwrap();
+        assert!(packed.len() < json.len());
+
+        let decoded: SchemaGraph = rmp_serde::from_slice(&packed).unwrap();
+        assert_eq!(decoded.tables.len(), graph.tables.len());
+        assert_eq!(decoded.tables[0].schema, graph.tables[0].schema);
+        assert_eq!(
+            serde_json::to_vec(&decoded).unwrap(),
+            json,
+            "decoded graph should serialize identically"
+        );
+    }
+
+    /// Fastest of a few runs, so a cold cache or a scheduler hiccup doesn't
+    /// decide the result.
+    fn fastest<T>(mut run: impl FnMut() -> T) -> Duration {
+        (0..10)
+            .map(|_| {
+                let started = Instant::now();
+                std::hint::black_box(run());
+                started.elapsed()
+            })
+            .min()
+            .unwrap()
+    }
+
+    /// JSON against MessagePack for the largest mock graph: payload size and
+    /// the time to encode it in the backend and decode it on the other side.
+    /// Run with `cargo test --release payload_benchmark -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn payload_benchmark() {
+        let graph = generate_mock_schema("large");
+        let json = serde_json::to_vec(&graph).unwrap();
+        let packed = encode_msgpack(&graph).unwrap();
+
+        let json_encode = fastest(|| serde_json::to_vec(&graph).unwrap());
+        let json_decode = fastest(|| serde_json::from_slice::<serde_json::Value>(&json).unwrap());
+        let msgpack_encode = fastest(|| encode_msgpack(&graph).unwrap());
+        let msgpack_decode =
+            fastest(|| rmp_serde::from_slice::<serde_json::Value>(&packed).unwrap());
+
+        println!("format      bytes       encode      decode");
+        println!(
+            "json        {:<11} {:<11?} {:?}",
+            json.len(),
+            json_encode,
+            json_decode
+        );
+        println!(
+            "msgpack     {:<11} {:<11?} {:?}",
+            packed.len(),
+            msgpack_encode,
+            msgpack_decode
+        );
+    }
 }
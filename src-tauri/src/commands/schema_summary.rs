@@ -0,0 +1,26 @@
+use crate::annotations::list_annotations;
+use crate::perf::CommandTimer;
+use crate::schema_summary::{summarize_schema, SchemaSummary, SummaryOptions};
+use crate::state::AppState;
+use crate::types::SchemaGraph;
+use tauri::State;
+
+/// The schema as compact text for language model prompts, in chunks sized
+/// to a token budget, with the connection's annotations at full detail.
+#[tauri::command]
+pub fn summarize_schema_cmd(
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    graph: SchemaGraph,
+    options: Option<SummaryOptions>,
+) -> SchemaSummary {
+    let timer = CommandTimer::start("summarize_schema_cmd");
+    let annotations = list_annotations(&state.storage_path, &server, &database);
+    timer.finish_value(summarize_schema(
+        &graph,
+        &annotations,
+        &database,
+        &options.unwrap_or_default(),
+    ))
+}
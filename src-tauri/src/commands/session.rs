@@ -0,0 +1,63 @@
+use crate::commands::schema::prepare_cached_graph;
+use crate::perf::CommandTimer;
+use crate::session::{restore_session, RestoredConnection, SessionState};
+use crate::state::AppState;
+use tauri::{State, Window};
+
+/// Put the calling window in read-only mode (viewing a bundle or snapshot).
+/// One-way: only a live schema or workspace load takes the window out of it
+/// again, so the webview can't lift its own write guards. Returns the mode.
+#[tauri::command]
+pub fn set_read_only_session_cmd(window: Window, state: State<'_, AppState>) -> bool {
+    let timer = CommandTimer::start("set_read_only_session_cmd");
+    state.windows.set_read_only(window.label(), true);
+    timer.finish_value(state.windows.is_read_only(window.label()))
+}
+
+/// Whether the app was launched with `--safe-mode`.
+#[tauri::command]
+pub fn is_safe_mode_cmd(state: State<'_, AppState>) -> bool {
+    let timer = CommandTimer::start("is_safe_mode_cmd");
+    timer.finish_value(state.is_safe_mode())
+}
+
+#[tauri::command]
+pub fn is_read_only_session_cmd(window: Window, state: State<'_, AppState>) -> bool {
+    let timer = CommandTimer::start("is_read_only_session_cmd");
+    timer.finish_value(state.windows.is_read_only(window.label()))
+}
+
+/// Record what the calling window has open so it can be restored on the next
+/// launch; the file itself is written when a window closes.
+#[tauri::command]
+pub fn update_session_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    session: SessionState,
+) -> Result<(), String> {
+    let timer = CommandTimer::start("update_session_cmd");
+    state.windows.set_session(window.label(), session);
+    timer.finish(Ok(()))
+}
+
+/// The previous session's connections, each with its cached graph, when
+/// session restore is enabled; otherwise empty.
+#[tauri::command]
+pub fn restore_session_cmd(state: State<'_, AppState>) -> Result<Vec<RestoredConnection>, String> {
+    let timer = CommandTimer::start("restore_session_cmd");
+    let result = state.get_settings().map(|settings| {
+        if settings.restore_session.unwrap_or(false) && !state.is_safe_mode() {
+            let mut restored = restore_session(&state.storage_path);
+            for entry in &mut restored {
+                let connection = &entry.connection;
+                if let Some(graph) = entry.graph.as_mut() {
+                    prepare_cached_graph(&state, &connection.server, &connection.database, graph);
+                }
+            }
+            restored
+        } else {
+            Vec::new()
+        }
+    });
+    timer.finish(result)
+}
@@ -1,9 +1,18 @@
-use crate::state::{AppSettings, AppSettingsUpdate, AppState};
-use tauri::State;
+use crate::data_location::{exe_dir, save_data_location, DataLocation, DataLocationChoice};
+use crate::exclusions::compile_patterns;
+use crate::export_themes::{active_export_theme, available_export_themes, ExportTheme};
+use crate::i18n::{available_languages, t};
+use crate::perf::CommandTimer;
+use crate::state::{
+    connection_key, AppSettings, AppSettingsUpdate, AppState, SchemaDisplay, ALL_CONNECTIONS,
+};
+use std::collections::BTreeMap;
+use tauri::{State, Window};
 
 #[tauri::command]
 pub fn get_settings(state: State<'_, AppState>) -> Result<AppSettings, String> {
-    state.get_settings()
+    let timer = CommandTimer::start("get_settings");
+    timer.finish(state.get_settings())
 }
 
 #[tauri::command]
@@ -11,5 +20,153 @@ pub fn save_settings(
     state: State<'_, AppState>,
     settings: AppSettingsUpdate,
 ) -> Result<AppSettings, String> {
-    state.update_settings(settings)
+    let timer = CommandTimer::start("save_settings");
+    timer.finish(state.update_settings(settings))
+}
+
+#[tauri::command]
+pub fn get_schema_display_cmd(
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+) -> Result<BTreeMap<String, SchemaDisplay>, String> {
+    let timer = CommandTimer::start("get_schema_display_cmd");
+    let result = state.get_settings().map(|settings| {
+        settings
+            .schema_display
+            .get(&connection_key(&server, &database))
+            .cloned()
+            .unwrap_or_default()
+    });
+    timer.finish(result)
+}
+
+#[tauri::command]
+pub fn update_schema_display_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    schema: String,
+    display: SchemaDisplay,
+) -> Result<BTreeMap<String, SchemaDisplay>, String> {
+    let timer = CommandTimer::start("update_schema_display_cmd");
+    let result = state.ensure_writable(window.label()).and_then(|_| {
+        state.set_schema_display(&connection_key(&server, &database), &schema, display)
+    });
+    timer.finish(result)
+}
+
+/// Exclusion patterns for a connection, split into those saved for every
+/// connection and those saved for this one.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExclusionList {
+    pub global: Vec<String>,
+    pub connection: Vec<String>,
+}
+
+#[tauri::command]
+pub fn get_exclusions_cmd(
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+) -> Result<ExclusionList, String> {
+    let timer = CommandTimer::start("get_exclusions_cmd");
+    let result = state.get_settings().map(|settings| {
+        let patterns = |key: &str| {
+            settings
+                .excluded_objects
+                .get(key)
+                .cloned()
+                .unwrap_or_default()
+        };
+        ExclusionList {
+            global: patterns(ALL_CONNECTIONS),
+            connection: patterns(&connection_key(&server, &database)),
+        }
+    });
+    timer.finish(result)
+}
+
+/// Replace exclusion patterns. Without a server and database the patterns
+/// apply to every connection. Takes effect on the next schema load.
+#[tauri::command]
+pub fn set_exclusions_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    server: Option<String>,
+    database: Option<String>,
+    patterns: Vec<String>,
+) -> Result<(), String> {
+    let timer = CommandTimer::start("set_exclusions_cmd");
+    let result = (|| {
+        state.ensure_writable(window.label())?;
+        let patterns: Vec<String> = patterns
+            .into_iter()
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+        compile_patterns(&patterns)?;
+        let key = match (server, database) {
+            (Some(server), Some(database)) => connection_key(&server, &database),
+            (None, None) => ALL_CONNECTIONS.to_string(),
+            _ => return Err(t!("error-connection-required")),
+        };
+        state.set_exclusions(&key, patterns)
+    })();
+    timer.finish(result)
+}
+
+/// Language tags with a message catalog, for the settings picker.
+#[tauri::command]
+pub fn get_languages_cmd() -> Vec<&'static str> {
+    let timer = CommandTimer::start("get_languages_cmd");
+    timer.finish_value(available_languages())
+}
+
+/// Built-in and custom export themes, for the settings picker.
+#[tauri::command]
+pub fn get_export_themes_cmd(state: State<'_, AppState>) -> Result<Vec<ExportTheme>, String> {
+    let timer = CommandTimer::start("get_export_themes_cmd");
+    timer.finish(
+        state
+            .get_settings()
+            .map(|settings| available_export_themes(&settings)),
+    )
+}
+
+/// The theme exporters should draw with right now.
+#[tauri::command]
+pub fn get_export_theme_cmd(state: State<'_, AppState>) -> Result<ExportTheme, String> {
+    let timer = CommandTimer::start("get_export_theme_cmd");
+    timer.finish(
+        state
+            .get_settings()
+            .map(|settings| active_export_theme(&settings)),
+    )
+}
+
+/// Where this launch keeps its data, and what chose that directory.
+#[tauri::command]
+pub fn get_data_location_cmd(location: State<'_, DataLocation>) -> DataLocation {
+    let timer = CommandTimer::start("get_data_location_cmd");
+    timer.finish_value(location.inner().clone())
+}
+
+/// Choose where app data is kept from the next launch on; returns that
+/// directory. Launch flags still take precedence.
+#[tauri::command]
+pub fn set_data_location_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    location: State<'_, DataLocation>,
+    choice: DataLocationChoice,
+) -> Result<String, String> {
+    let timer = CommandTimer::start("set_data_location_cmd");
+    let result = state.ensure_writable(window.label()).and_then(|_| {
+        save_data_location(&choice, exe_dir().as_deref(), &location.default_path)
+            .map(|path| path.display().to_string())
+    });
+    timer.finish(result)
 }
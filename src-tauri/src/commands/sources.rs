@@ -0,0 +1,50 @@
+use crate::perf::CommandTimer;
+use crate::sources::{load_dbt_manifest, load_flat_files, load_tabular_model, scan_reports};
+use crate::types::{ModelConsumer, ReportScan, SchemaGraph};
+use std::path::Path;
+
+/// Introspect a folder of CSV/Parquet files as a schema of tables.
+#[tauri::command]
+pub async fn load_flat_files_cmd(path: String) -> Result<SchemaGraph, String> {
+    let timer = CommandTimer::start("load_flat_files_cmd");
+    let result = tokio::task::spawn_blocking(move || load_flat_files(Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+    timer.finish(result)
+}
+
+/// Import models, sources and `ref()` lineage from a dbt project's manifest.
+#[tauri::command]
+pub async fn load_dbt_manifest_cmd(path: String) -> Result<SchemaGraph, String> {
+    let timer = CommandTimer::start("load_dbt_manifest_cmd");
+    let result = tokio::task::spawn_blocking(move || load_dbt_manifest(Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+    timer.finish(result)
+}
+
+/// Read a Model.bim or TMSL script and report which database objects feed
+/// each model table, for "consumed by model" annotations.
+#[tauri::command]
+pub async fn import_tabular_model_cmd(path: String) -> Result<Vec<ModelConsumer>, String> {
+    let timer = CommandTimer::start("import_tabular_model_cmd");
+    let result = tokio::task::spawn_blocking(move || load_tabular_model(Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+    timer.finish(result)
+}
+
+/// Find the tables, views and procedures used by SSRS and Power BI reports in
+/// a folder, for impact analysis before a refactor.
+#[tauri::command]
+pub async fn scan_report_usage_cmd(path: String) -> Result<ReportScan, String> {
+    let timer = CommandTimer::start("scan_report_usage_cmd");
+    let result = tokio::task::spawn_blocking(move || scan_reports(Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+    timer.finish(result)
+}
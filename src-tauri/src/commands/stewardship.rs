@@ -0,0 +1,36 @@
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use crate::stewardship::{list_stewardship, set_stewardship};
+use crate::types::Stewardship;
+use tauri::{State, Window};
+
+#[tauri::command]
+pub fn list_stewardship_cmd(
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+) -> Result<Vec<Stewardship>, String> {
+    let timer = CommandTimer::start("list_stewardship_cmd");
+    timer.finish(Ok(list_stewardship(
+        &state.storage_path,
+        &server,
+        &database,
+    )))
+}
+
+/// Assign the owner and steward of a schema, or of a table when
+/// `assignment.objectId` is set. Returns the updated list.
+#[tauri::command]
+pub fn set_stewardship_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    server: String,
+    database: String,
+    assignment: Stewardship,
+) -> Result<Vec<Stewardship>, String> {
+    let timer = CommandTimer::start("set_stewardship_cmd");
+    let result = state
+        .ensure_writable(window.label())
+        .and_then(|_| set_stewardship(&state.storage_path, &server, &database, assignment));
+    timer.finish(result)
+}
@@ -0,0 +1,16 @@
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use crate::tasks::TaskInfo;
+use tauri::State;
+
+#[tauri::command]
+pub fn list_tasks_cmd(state: State<'_, AppState>) -> Vec<TaskInfo> {
+    let timer = CommandTimer::start("list_tasks_cmd");
+    timer.finish_value(state.tasks.list())
+}
+
+#[tauri::command]
+pub fn cancel_task_cmd(state: State<'_, AppState>, task_id: String) -> Result<TaskInfo, String> {
+    let timer = CommandTimer::start("cancel_task_cmd");
+    timer.finish(state.tasks.cancel(&task_id))
+}
@@ -0,0 +1,228 @@
+use crate::network::resolve_proxy_url;
+use crate::perf::CommandTimer;
+use crate::state::{AppSettings, AppState};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State, Url};
+use tauri_plugin_updater::{Updater, UpdaterExt};
+
+const STABLE_ENDPOINT: &str =
+    "https://github.com/elliotlayen/Monocle/releases/latest/download/latest.json";
+const BETA_ENDPOINT: &str =
+    "https://github.com/elliotlayen/Monocle/releases/download/beta/latest.json";
+const DEFAULT_CHECK_INTERVAL_HOURS: u32 = 24;
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCheckResult {
+    pub channel: String,
+    pub checked: bool,
+    pub deferred: bool,
+    pub available: bool,
+    pub current_version: String,
+    pub version: Option<String>,
+    pub release_notes: Option<String>,
+    pub release_date: Option<String>,
+}
+
+fn channel_endpoint(channel: &str) -> &'static str {
+    match channel {
+        "beta" => BETA_ENDPOINT,
+        _ => STABLE_ENDPOINT,
+    }
+}
+
+fn parse_timestamp(value: Option<&str>) -> Option<DateTime<Utc>> {
+    value
+        .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+        .map(|d| d.with_timezone(&Utc))
+}
+
+/// Decide whether an automatic check is due. Manual checks (`force`) always run;
+/// otherwise a pending "remind me later" or a recent check suppresses it.
+fn should_check(settings: &AppSettings, now: DateTime<Utc>, force: bool) -> bool {
+    if force {
+        return true;
+    }
+
+    if let Some(remind_after) = parse_timestamp(settings.update_remind_after.as_deref()) {
+        if now < remind_after {
+            return false;
+        }
+    }
+
+    let interval_hours = settings
+        .update_check_interval_hours
+        .unwrap_or(DEFAULT_CHECK_INTERVAL_HOURS);
+    if interval_hours == 0 {
+        // Zero disables automatic checks
+        return false;
+    }
+
+    match parse_timestamp(settings.last_update_check_at.as_deref()) {
+        Some(last) => now.signed_duration_since(last) >= Duration::hours(interval_hours as i64),
+        None => true,
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProgress {
+    pub downloaded: u64,
+    pub content_length: Option<u64>,
+}
+
+fn channel_of(settings: &AppSettings) -> String {
+    settings
+        .update_channel
+        .clone()
+        .unwrap_or_else(|| "stable".to_string())
+}
+
+/// Updater for the configured channel, going through the configured proxy.
+fn build_updater(app: &AppHandle, settings: &AppSettings) -> Result<Updater, String> {
+    let endpoint = Url::parse(channel_endpoint(&channel_of(settings)))
+        .map_err(|e| format!("Invalid update endpoint: {}", e))?;
+    let mut builder = app
+        .updater_builder()
+        .endpoints(vec![endpoint])
+        .map_err(|e| format!("Failed to configure updater: {}", e))?;
+    if let Some(proxy) = resolve_proxy_url(settings.proxy.as_ref())? {
+        builder = builder.proxy(proxy);
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to build updater: {}", e))
+}
+
+#[tauri::command]
+pub async fn check_for_updates_cmd(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    force: bool,
+) -> Result<UpdateCheckResult, String> {
+    let timer = CommandTimer::start("check_for_updates_cmd");
+    let settings = state.get_settings()?;
+    let channel = channel_of(&settings);
+    let now = Utc::now();
+
+    let mut result = UpdateCheckResult {
+        channel,
+        current_version: env!("CARGO_PKG_VERSION").to_string(),
+        ..Default::default()
+    };
+
+    // No automatic checks in safe mode
+    if !should_check(&settings, now, force) || (state.is_safe_mode() && !force) {
+        result.deferred = true;
+        return timer.finish(Ok(result));
+    }
+
+    let updater = build_updater(&app, &settings)?;
+    let update = updater
+        .check()
+        .await
+        .map_err(|e| format!("Update check failed: {}", e))?;
+
+    state.record_update_check(now.to_rfc3339())?;
+    result.checked = true;
+
+    if let Some(update) = update {
+        let skipped = settings.skipped_update_version.as_deref() == Some(update.version.as_str());
+        result.available = force || !skipped;
+        result.current_version = update.current_version;
+        result.version = Some(update.version);
+        result.release_notes = update.body;
+        result.release_date = update.date.map(|d| d.to_string());
+    }
+
+    timer.finish(Ok(result))
+}
+
+/// Postpone update prompts. `hours` sets a "remind me later" window; `skip_version`
+/// suppresses automatic prompts for that release until a newer one appears.
+#[tauri::command]
+pub fn defer_update_cmd(
+    state: State<'_, AppState>,
+    hours: Option<u32>,
+    skip_version: Option<String>,
+) -> Result<AppSettings, String> {
+    let timer = CommandTimer::start("defer_update_cmd");
+    let remind_after = hours.map(|h| (Utc::now() + Duration::hours(h as i64)).to_rfc3339());
+    timer.finish(state.defer_update(remind_after, skip_version))
+}
+
+/// Download and install the release on the configured channel, emitting
+/// `update-progress` events. The app is relaunched by the frontend.
+#[tauri::command]
+pub async fn install_update_cmd(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let timer = CommandTimer::start("install_update_cmd");
+    let settings = state.get_settings()?;
+    let update = build_updater(&app, &settings)?
+        .check()
+        .await
+        .map_err(|e| format!("Update check failed: {}", e))?
+        .ok_or_else(|| "No update is available".to_string())?;
+
+    let mut downloaded = 0u64;
+    let result = update
+        .download_and_install(
+            |chunk, content_length| {
+                downloaded += chunk as u64;
+                let _ = app.emit(
+                    "update-progress",
+                    UpdateProgress {
+                        downloaded,
+                        content_length,
+                    },
+                );
+            },
+            || {},
+        )
+        .await
+        .map_err(|e| format!("Failed to install update: {}", e));
+    timer.finish(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn beta_channel_uses_beta_endpoint() {
+        assert_eq!(channel_endpoint("beta"), BETA_ENDPOINT);
+        assert_eq!(channel_endpoint("stable"), STABLE_ENDPOINT);
+        assert_eq!(channel_endpoint("nightly"), STABLE_ENDPOINT);
+    }
+
+    #[test]
+    fn remind_later_suppresses_automatic_checks() {
+        let now = Utc::now();
+        let settings = AppSettings {
+            update_remind_after: Some((now + Duration::hours(2)).to_rfc3339()),
+            ..Default::default()
+        };
+
+        assert!(!should_check(&settings, now, false));
+        assert!(should_check(&settings, now, true));
+        assert!(should_check(&settings, now + Duration::hours(3), false));
+    }
+
+    #[test]
+    fn check_interval_is_respected() {
+        let now = Utc::now();
+        let settings = AppSettings {
+            update_check_interval_hours: Some(6),
+            last_update_check_at: Some((now - Duration::hours(1)).to_rfc3339()),
+            ..Default::default()
+        };
+        assert!(!should_check(&settings, now, false));
+        assert!(should_check(&settings, now + Duration::hours(6), false));
+
+        let disabled = AppSettings {
+            update_check_interval_hours: Some(0),
+            ..Default::default()
+        };
+        assert!(!should_check(&disabled, now, false));
+    }
+}
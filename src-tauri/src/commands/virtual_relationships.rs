@@ -0,0 +1,51 @@
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use crate::types::RelationshipEdge;
+use crate::virtual_relationships::{
+    add_virtual_relationship, list_virtual_relationships, remove_virtual_relationship,
+    VirtualRelationshipInput,
+};
+use tauri::{State, Window};
+
+#[tauri::command]
+pub fn list_virtual_relationships_cmd(
+    state: State<'_, AppState>,
+    workspace: String,
+) -> Result<Vec<RelationshipEdge>, String> {
+    let timer = CommandTimer::start("list_virtual_relationships_cmd");
+    timer.finish(Ok(list_virtual_relationships(
+        &state.storage_path,
+        &workspace,
+    )))
+}
+
+/// Save a user-defined relationship for a workspace (the connection key when
+/// a single database is open). It is merged into the graph (marked
+/// `isVirtual`) on every subsequent load of that workspace.
+#[tauri::command]
+pub fn add_virtual_relationship_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    workspace: String,
+    relationship: VirtualRelationshipInput,
+) -> Result<RelationshipEdge, String> {
+    let timer = CommandTimer::start("add_virtual_relationship_cmd");
+    let result = state
+        .ensure_writable(window.label())
+        .and_then(|_| add_virtual_relationship(&state.storage_path, &workspace, relationship));
+    timer.finish(result)
+}
+
+#[tauri::command]
+pub fn remove_virtual_relationship_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    workspace: String,
+    id: String,
+) -> Result<(), String> {
+    let timer = CommandTimer::start("remove_virtual_relationship_cmd");
+    let result = state
+        .ensure_writable(window.label())
+        .and_then(|_| remove_virtual_relationship(&state.storage_path, &workspace, &id));
+    timer.finish(result)
+}
@@ -0,0 +1,125 @@
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::commands::schema::{emit_schema_patch, load_schema_versioned};
+use crate::db::{create_client, load_schema_fingerprint};
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use crate::types::ConnectionParams;
+use crate::watch_export::{render_exports, write_exports, WatchExportConfig, WatchExportEvent};
+
+async fn fingerprint(params: &ConnectionParams) -> Result<String, String> {
+    let mut client = create_client(params).await.map_err(|e| e.to_string())?;
+    load_schema_fingerprint(&mut client)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn regenerate(
+    app: &AppHandle,
+    state: &AppState,
+    params: &ConnectionParams,
+    config: &WatchExportConfig,
+    output_dir: &Path,
+) -> Result<Vec<String>, String> {
+    let (graph, patch) = load_schema_versioned(state, params)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(patch) = patch {
+        emit_schema_patch(app, params, patch);
+    }
+    let files = render_exports(&graph, &params.database, &config.exports)?;
+    let output_dir = output_dir.to_path_buf();
+    tokio::task::spawn_blocking(move || write_exports(&output_dir, &files))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r)
+}
+
+/// Keep exports in `output_dir` current: poll the schema fingerprint and,
+/// whenever it changes, reload and rewrite every configured export, emitting
+/// `watch-export` and, for windows showing the database, `schema-patch`. Runs as a task until stopped with `cancel_task_cmd`;
+/// returns the task id.
+#[tauri::command]
+pub fn start_watch_export_cmd(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    profile: ConnectionParams,
+    export_config: WatchExportConfig,
+    output_dir: String,
+) -> Result<String, String> {
+    let timer = CommandTimer::start("start_watch_export_cmd");
+    if let Err(e) = state.ensure_not_safe_mode() {
+        return timer.finish(Err(e));
+    }
+    if export_config.exports.is_empty() {
+        return timer.finish(Err("Choose at least one export to keep updated".to_string()));
+    }
+
+    let task = state.tasks.start(
+        "watch_export",
+        format!(
+            "{} / {} -> {}",
+            profile.server, profile.database, output_dir
+        ),
+    );
+    let task_id = task.id.clone();
+    let output_dir = PathBuf::from(output_dir);
+    let interval = Duration::from_secs(export_config.interval_secs());
+
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let mut last_fingerprint: Option<String> = None;
+        loop {
+            match fingerprint(&profile).await {
+                Ok(current) if last_fingerprint.as_deref() != Some(current.as_str()) => {
+                    let result =
+                        regenerate(&app, &state, &profile, &export_config, &output_dir).await;
+                    let (files, error) = match result {
+                        Ok(files) => {
+                            // Only a successful export settles this fingerprint;
+                            // a failed one is retried on the next poll
+                            last_fingerprint = Some(current.clone());
+                            (files, None)
+                        }
+                        Err(e) => (Vec::new(), Some(e)),
+                    };
+                    state.tasks.set_progress(
+                        &task.id,
+                        None,
+                        Some(match &error {
+                            Some(e) => format!("Export failed: {}", e),
+                            None => format!("Exported {} file(s)", files.len()),
+                        }),
+                    );
+                    let _ = app.emit(
+                        "watch-export",
+                        WatchExportEvent {
+                            task_id: task.id.clone(),
+                            fingerprint: current,
+                            files,
+                            error,
+                            generated_at: Utc::now().to_rfc3339(),
+                        },
+                    );
+                }
+                Ok(_) => {}
+                // The server may be briefly unreachable; keep watching
+                Err(e) => {
+                    state
+                        .tasks
+                        .set_progress(&task.id, None, Some(format!("Check failed: {}", e)));
+                }
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = task.cancelled() => break,
+            }
+        }
+    });
+
+    timer.finish(Ok(task_id))
+}
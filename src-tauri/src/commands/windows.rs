@@ -0,0 +1,51 @@
+use crate::launch::{LaunchQueue, LaunchRequest};
+use crate::perf::CommandTimer;
+use crate::state::AppState;
+use crate::types::ConnectionParams;
+use tauri::{AppHandle, State, WebviewUrl, WebviewWindowBuilder, Window};
+
+/// Open another Monocle window, optionally bound to a connection, so two
+/// databases can be viewed side by side. Returns the new window's label.
+#[tauri::command]
+pub fn open_new_window_cmd(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    params: Option<ConnectionParams>,
+) -> Result<String, String> {
+    let timer = CommandTimer::start("open_new_window_cmd");
+    let title = match &params {
+        Some(params) => format!("Monocle - {} / {}", params.server, params.database),
+        None => "Monocle".to_string(),
+    };
+    let label = state.windows.open(params);
+    let result = WebviewWindowBuilder::new(&app, label.as_str(), WebviewUrl::default())
+        .title(title)
+        .inner_size(1280.0, 720.0)
+        .min_inner_size(1280.0, 720.0)
+        .build()
+        .map(|_| label.clone())
+        .map_err(|e| {
+            state.windows.close(&label);
+            format!("Failed to open window: {}", e)
+        });
+    timer.finish(result)
+}
+
+/// The connection the calling window was opened for; `None` for the main
+/// window and for windows opened without one.
+#[tauri::command]
+pub fn get_window_context_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+) -> Option<ConnectionParams> {
+    let timer = CommandTimer::start("get_window_context_cmd");
+    timer.finish_value(state.windows.context(window.label()))
+}
+
+/// Files and links this launch, or a later one forwarded to this instance,
+/// asked to open. Each request is returned once.
+#[tauri::command]
+pub fn take_launch_requests_cmd(queue: State<'_, LaunchQueue>) -> Vec<LaunchRequest> {
+    let timer = CommandTimer::start("take_launch_requests_cmd");
+    timer.finish_value(queue.take())
+}
@@ -0,0 +1,72 @@
+use crate::commands::mock::generate_mock_schema;
+use crate::commands::schema::{leave_read_only, load_schema_task};
+use crate::diagnostics::record_action;
+use crate::perf::CommandTimer;
+use crate::sources::{load_dbt_manifest, load_flat_files};
+use crate::state::AppState;
+use crate::types::{ManualLink, SchemaGraph};
+use crate::virtual_relationships::apply_virtual_relationships;
+use crate::workspace::{merge_sources, SourceConnection, WorkspaceSource};
+use std::path::Path;
+use tauri::{State, Window};
+
+/// Load every source of a workspace and merge them into one graph whose IDs
+/// are scoped by source. Sources load one after another so each shows up as
+/// its own cancellable task. Virtual relationships saved for `workspace` are
+/// drawn between the merged, source-scoped objects.
+#[tauri::command]
+pub async fn load_workspace_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    workspace: String,
+    sources: Vec<WorkspaceSource>,
+    links: Vec<ManualLink>,
+) -> Result<SchemaGraph, String> {
+    let timer = CommandTimer::start("load_workspace_cmd");
+    record_action(format!("load_workspace {} sources", sources.len()));
+
+    let mut graphs = Vec::with_capacity(sources.len());
+    for source in &sources {
+        let graph = match &source.connection {
+            SourceConnection::SqlServer { params } => load_schema_task(&state, params)
+                .await
+                .map_err(|e| format!("{}: {}", source.label, e)),
+            SourceConnection::Mock { size } => Ok(generate_mock_schema(size)),
+            SourceConnection::FlatFiles { path } => {
+                let path = path.clone();
+                read_folder(move || load_flat_files(Path::new(&path)))
+                    .await
+                    .map_err(|e| format!("{}: {}", source.label, e))
+            }
+            SourceConnection::Dbt { path } => {
+                let path = path.clone();
+                read_folder(move || load_dbt_manifest(Path::new(&path)))
+                    .await
+                    .map_err(|e| format!("{}: {}", source.label, e))
+            }
+        };
+        match graph {
+            Ok(graph) => graphs.push((source.id.clone(), graph)),
+            Err(e) => return timer.finish(Err(e)),
+        }
+    }
+
+    let result = merge_sources(graphs, links).map(|mut graph| {
+        apply_virtual_relationships(&state.storage_path, &workspace, &mut graph);
+        graph
+    });
+    leave_read_only(&state, &window, &result);
+    timer.finish(result)
+}
+
+/// Run a file-based source loader on the blocking pool so a large folder does
+/// not stall the async runtime.
+async fn read_folder<F>(load: F) -> Result<SchemaGraph, String>
+where
+    F: FnOnce() -> Result<SchemaGraph, String> + Send + 'static,
+{
+    tokio::task::spawn_blocking(load)
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r)
+}
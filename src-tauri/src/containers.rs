@@ -0,0 +1,227 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::types::{AuthType, ConnectionParams, DatabaseEngine};
+
+/// The Docker daemon answers locally or not at all; don't hang the picker.
+const DOCKER_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// SQL Server's default port inside the container.
+const SQL_SERVER_PORT: u16 = 1433;
+
+/// Image names of SQL Server on Linux and its relatives.
+const SQL_SERVER_IMAGES: &[&str] = &["mssql", "sql-server", "sqlserver", "azure-sql-edge"];
+
+/// A running container that looks like SQL Server.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlContainer {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    /// Docker's status line, e.g. "Up 2 hours (healthy)".
+    pub status: String,
+    /// Host port published for 1433; `None` when the port isn't published,
+    /// so the container can't be reached from the host.
+    pub host_port: Option<u16>,
+    /// Connection form prefill: `localhost,<port>` as `sa`, trusting the
+    /// container's self-signed certificate. The password is never read.
+    pub params: Option<ConnectionParams>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DockerPort {
+    #[serde(rename = "IP", default)]
+    ip: Option<String>,
+    private_port: u16,
+    #[serde(default)]
+    public_port: Option<u16>,
+    #[serde(rename = "Type", default)]
+    protocol: String,
+}
+
+/// An entry of `GET /containers/json`.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DockerContainer {
+    id: String,
+    #[serde(default)]
+    names: Vec<String>,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    ports: Vec<DockerPort>,
+    #[serde(default)]
+    labels: HashMap<String, String>,
+}
+
+impl DockerContainer {
+    fn is_sql_server(&self) -> bool {
+        let image = self.image.to_lowercase();
+        SQL_SERVER_IMAGES.iter().any(|name| image.contains(name))
+            || self.ports.iter().any(|p| p.private_port == SQL_SERVER_PORT)
+    }
+
+    /// Host port for 1433, preferring the IPv4 binding.
+    fn host_port(&self) -> Option<u16> {
+        let mut published: Vec<&DockerPort> = self
+            .ports
+            .iter()
+            .filter(|p| p.private_port == SQL_SERVER_PORT && p.protocol != "udp")
+            .filter(|p| p.public_port.is_some())
+            .collect();
+        published.sort_by_key(|p| p.ip.as_deref().is_some_and(|ip| ip.contains(':')));
+        published.first().and_then(|p| p.public_port)
+    }
+}
+
+/// SQL Server containers in a `GET /containers/json` response body.
+pub fn parse_containers(body: &str) -> Result<Vec<SqlContainer>, String> {
+    let containers: Vec<DockerContainer> =
+        serde_json::from_str(body).map_err(|e| format!("Unexpected Docker response: {}", e))?;
+    Ok(containers
+        .into_iter()
+        .filter(DockerContainer::is_sql_server)
+        .map(|c| {
+            let host_port = c.host_port();
+            let name = c
+                .names
+                .first()
+                .map(|n| n.trim_start_matches('/').to_string())
+                .or_else(|| c.labels.get("com.docker.compose.service").cloned())
+                .unwrap_or_else(|| c.id.chars().take(12).collect());
+            SqlContainer {
+                params: host_port.map(|port| ConnectionParams {
+                    server: format!("localhost,{}", port),
+                    database: "master".to_string(),
+                    engine: DatabaseEngine::SqlServer,
+                    auth_type: AuthType::SqlServer,
+                    username: Some("sa".to_string()),
+                    password: None,
+                    trust_server_certificate: true,
+                    multi_subnet_failover: false,
+                    read_only_intent: false,
+                }),
+                id: c.id,
+                name,
+                image: c.image,
+                status: c.status,
+                host_port,
+            }
+        })
+        .collect())
+}
+
+/// `GET path` over an already connected daemon stream. HTTP/1.0 keeps the
+/// daemon from chunking the body and closes the stream when done.
+async fn http_get<S: AsyncRead + AsyncWrite + Unpin>(
+    mut stream: S,
+    path: &str,
+) -> Result<String, String> {
+    let request = format!("GET {} HTTP/1.0\r\nHost: docker\r\n\r\n", path);
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| format!("Failed to query Docker: {}", e))?;
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .await
+        .map_err(|e| format!("Failed to read the Docker response: {}", e))?;
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| "Malformed Docker response".to_string())?;
+    let status = head.lines().next().unwrap_or_default();
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(format!("Docker answered '{}'", status));
+    }
+    Ok(body.to_string())
+}
+
+/// Unix socket paths to try: `DOCKER_HOST` when it names one, the system
+/// socket, then Docker Desktop's per-user socket.
+#[cfg(unix)]
+fn socket_paths() -> Vec<std::path::PathBuf> {
+    let mut paths = Vec::new();
+    if let Some(path) = std::env::var("DOCKER_HOST")
+        .ok()
+        .and_then(|host| host.strip_prefix("unix://").map(str::to_string))
+    {
+        paths.push(path.into());
+    }
+    paths.push("/var/run/docker.sock".into());
+    if let Some(home) = std::env::var_os("HOME") {
+        paths.push(std::path::Path::new(&home).join(".docker/run/docker.sock"));
+    }
+    paths
+}
+
+#[cfg(unix)]
+async fn docker_get(path: &str) -> Result<String, String> {
+    let mut last_error = "Docker is not running".to_string();
+    for socket in socket_paths() {
+        match tokio::net::UnixStream::connect(&socket).await {
+            Ok(stream) => return http_get(stream, path).await,
+            Err(e) if socket.exists() => {
+                last_error = format!("Cannot connect to {}: {}", socket.display(), e)
+            }
+            Err(_) => {}
+        }
+    }
+    Err(last_error)
+}
+
+#[cfg(windows)]
+async fn docker_get(path: &str) -> Result<String, String> {
+    let pipe = tokio::net::windows::named_pipe::ClientOptions::new()
+        .open(r"\\.\pipe\docker_engine")
+        .map_err(|_| "Docker is not running".to_string())?;
+    http_get(pipe, path).await
+}
+
+/// Running SQL Server containers on the local Docker daemon.
+pub async fn detect_local_sql_containers() -> Result<Vec<SqlContainer>, String> {
+    let body = tokio::time::timeout(DOCKER_TIMEOUT, docker_get("/containers/json"))
+        .await
+        .map_err(|_| "Docker did not answer in time".to_string())??;
+    parse_containers(&body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sql_server_containers_get_prefilled_params() {
+        let body = r#"[
+            {"Id": "a1b2c3d4e5f6a7b8", "Names": ["/sql2022"],
+             "Image": "mcr.microsoft.com/mssql/server:2022-latest", "Status": "Up 5 minutes",
+             "Ports": [
+                {"IP": "::", "PrivatePort": 1433, "PublicPort": 14330, "Type": "tcp"},
+                {"IP": "0.0.0.0", "PrivatePort": 1433, "PublicPort": 14330, "Type": "tcp"}
+             ]},
+            {"Id": "ffff", "Names": ["/unpublished"], "Image": "mcr.microsoft.com/azure-sql-edge",
+             "Status": "Up 1 hour", "Ports": [{"PrivatePort": 1433, "Type": "tcp"}]},
+            {"Id": "eeee", "Names": ["/web"], "Image": "nginx", "Status": "Up",
+             "Ports": [{"IP": "0.0.0.0", "PrivatePort": 80, "PublicPort": 8080, "Type": "tcp"}]}
+        ]"#;
+        let containers = parse_containers(body).unwrap();
+        assert_eq!(containers.len(), 2);
+        assert_eq!(containers[0].name, "sql2022");
+        assert_eq!(containers[0].host_port, Some(14330));
+        let params = containers[0].params.as_ref().unwrap();
+        assert_eq!(params.server, "localhost,14330");
+        assert_eq!(params.username.as_deref(), Some("sa"));
+        assert!(params.password.is_none());
+        assert!(containers[1].params.is_none());
+
+        assert!(parse_containers("not json").is_err());
+    }
+}
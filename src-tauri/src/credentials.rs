@@ -0,0 +1,145 @@
+use keyring::{Entry, Error as KeyringError};
+
+use crate::i18n::t;
+
+/// Service name the passwords are filed under in the keychain.
+const SERVICE: &str = "monocle";
+
+/// One keychain entry per login on a database. Server and database are
+/// case-insensitive in SQL Server, so they're folded; the user name is not.
+fn account(server: &str, database: &str, username: &str) -> String {
+    format!(
+        "{}@{}/{}",
+        username,
+        server.trim().to_lowercase(),
+        database.trim().to_lowercase()
+    )
+}
+
+fn entry(server: &str, database: &str, username: &str) -> Result<Entry, String> {
+    if username.trim().is_empty() {
+        return Err(t!("error-credential-username"));
+    }
+    Entry::new(SERVICE, &account(server, database, username))
+        .map_err(|e| t!("error-credential-store", reason = e))
+}
+
+/// Save the login's password in the platform keychain (Keychain on macOS,
+/// the Credential Manager on Windows, the Secret Service on Linux).
+pub fn store_credential(
+    server: &str,
+    database: &str,
+    username: &str,
+    password: &str,
+) -> Result<(), String> {
+    entry(server, database, username)?
+        .set_password(password)
+        .map_err(|e| t!("error-credential-store", reason = e))
+}
+
+/// The stored password, or `None` when the keychain has none for the login.
+pub fn get_credential(
+    server: &str,
+    database: &str,
+    username: &str,
+) -> Result<Option<String>, String> {
+    match entry(server, database, username)?.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(KeyringError::NoEntry) => Ok(None),
+        Err(e) => Err(t!("error-credential-store", reason = e)),
+    }
+}
+
+/// Forget the login's password; nothing stored is not an error.
+pub fn delete_credential(server: &str, database: &str, username: &str) -> Result<(), String> {
+    match entry(server, database, username)?.delete_credential() {
+        Ok(()) | Err(KeyringError::NoEntry) => Ok(()),
+        Err(e) => Err(t!("error-credential-store", reason = e)),
+    }
+}
+
+/// Whether a password is stored for the login. A locked or missing
+/// keychain counts as none.
+pub fn has_credential(server: &str, database: &str, username: &str) -> bool {
+    matches!(get_credential(server, database, username), Ok(Some(_)))
+}
+
+/// The proxy password is filed apart from SQL logins, whose accounts always
+/// carry a `/database` part.
+fn proxy_entry(username: &str) -> Result<Entry, String> {
+    if username.trim().is_empty() {
+        return Err(t!("error-credential-username"));
+    }
+    Entry::new(SERVICE, &format!("proxy:{}", username))
+        .map_err(|e| t!("error-credential-store", reason = e))
+}
+
+/// Save the proxy password in the keychain instead of settings.json; an
+/// empty password forgets it.
+pub fn store_proxy_password(username: &str, password: &str) -> Result<(), String> {
+    let entry = proxy_entry(username)?;
+    let result = if password.is_empty() {
+        match entry.delete_credential() {
+            Err(KeyringError::NoEntry) => Ok(()),
+            other => other,
+        }
+    } else {
+        entry.set_password(password)
+    };
+    result.map_err(|e| t!("error-credential-store", reason = e))
+}
+
+/// The stored proxy password. A locked or missing keychain counts as none.
+pub fn proxy_password(username: &str) -> Option<String> {
+    proxy_entry(username).ok()?.get_password().ok()
+}
+
+/// Catalog tokens are filed per connection, under a prefix no login has.
+fn catalog_entry(server: &str, database: &str) -> Result<Entry, String> {
+    Entry::new(
+        SERVICE,
+        &format!(
+            "catalog:{}/{}",
+            server.trim().to_lowercase(),
+            database.trim().to_lowercase()
+        ),
+    )
+    .map_err(|e| t!("error-credential-store", reason = e))
+}
+
+/// Save the connection's catalog token in the keychain instead of its
+/// catalog settings; an empty token forgets it.
+pub fn store_catalog_token(server: &str, database: &str, token: &str) -> Result<(), String> {
+    let entry = catalog_entry(server, database)?;
+    let result = if token.is_empty() {
+        match entry.delete_credential() {
+            Err(KeyringError::NoEntry) => Ok(()),
+            other => other,
+        }
+    } else {
+        entry.set_password(token)
+    };
+    result.map_err(|e| t!("error-credential-store", reason = e))
+}
+
+/// The stored catalog token. A locked or missing keychain counts as none.
+pub fn catalog_token(server: &str, database: &str) -> Option<String> {
+    catalog_entry(server, database).ok()?.get_password().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_credentials_are_not_errors() {
+        keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+        assert_eq!(account(" SQL01 ", "Sales", "Ann"), "Ann@sql01/sales");
+
+        // Mock entries don't share a store, so only the empty case is testable
+        assert_eq!(get_credential("sql01", "Sales", "ann").unwrap(), None);
+        assert!(!has_credential("sql01", "Sales", "ann"));
+        delete_credential("sql01", "Sales", "ann").unwrap();
+        assert!(store_credential("sql01", "Sales", " ", "secret").is_err());
+    }
+}
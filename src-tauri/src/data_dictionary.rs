@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::io::Write;
+
+use crate::annotations::Annotation;
+use crate::stewardship::effective_stewardship;
+use crate::types::{Classification, Column, Contact, SchemaGraph, Stewardship};
+
+const HEADERS: &[&str] = &[
+    "Schema",
+    "Object",
+    "Type",
+    "Column",
+    "Data Type",
+    "Nullable",
+    "Primary Key",
+    "Description",
+    "Terms",
+    "Tags",
+    "Owner",
+    "Owner Email",
+    "Owner Team",
+    "Steward",
+    "Steward Email",
+    "Steward Team",
+];
+
+fn key(object_id: &str, column: Option<&str>) -> (String, Option<String>) {
+    (object_id.to_string(), column.map(str::to_lowercase))
+}
+
+fn contact_fields(contact: Option<&Contact>) -> [String; 3] {
+    match contact {
+        Some(c) => [
+            c.name.clone(),
+            c.email.clone().unwrap_or_default(),
+            c.team.clone().unwrap_or_default(),
+        ],
+        None => Default::default(),
+    }
+}
+
+struct Lookup<'a> {
+    descriptions: HashMap<(String, Option<String>), &'a str>,
+    classifications: HashMap<(String, Option<String>), &'a Classification>,
+    stewardship: &'a [Stewardship],
+}
+
+impl Lookup<'_> {
+    /// Description, terms and tags of an object or column.
+    fn context(&self, object_id: &str, column: Option<&str>) -> [String; 3] {
+        let key = key(object_id, column);
+        let description = self.descriptions.get(&key).copied().unwrap_or_default();
+        let (terms, tags) = self
+            .classifications
+            .get(&key)
+            .map(|c| (c.terms.join("; "), c.tags.join("; ")))
+            .unwrap_or_default();
+        [description.to_string(), terms, tags]
+    }
+}
+
+fn write_object<W: Write>(
+    writer: &mut csv::Writer<W>,
+    lookup: &Lookup,
+    (id, schema, name, kind): (&str, &str, &str, &str),
+    columns: &[Column],
+) -> Result<usize, csv::Error> {
+    let stewardship = effective_stewardship(lookup.stewardship, schema, id);
+    let [owner, owner_email, owner_team] =
+        contact_fields(stewardship.and_then(|s| s.owner.as_ref()));
+    let [steward, steward_email, steward_team] =
+        contact_fields(stewardship.and_then(|s| s.steward.as_ref()));
+    let people = [
+        owner,
+        owner_email,
+        owner_team,
+        steward,
+        steward_email,
+        steward_team,
+    ];
+
+    let [description, terms, tags] = lookup.context(id, None);
+    let mut row = vec![
+        schema.to_string(),
+        name.to_string(),
+        kind.to_string(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        description,
+        terms,
+        tags,
+    ];
+    row.extend(people.iter().cloned());
+    writer.write_record(&row)?;
+
+    for column in columns {
+        let [description, terms, tags] = lookup.context(id, Some(&column.name));
+        let mut row = vec![
+            schema.to_string(),
+            name.to_string(),
+            kind.to_string(),
+            column.name.clone(),
+            column.data_type.to_string(),
+            if column.is_nullable { "yes" } else { "no" }.to_string(),
+            if column.is_primary_key { "yes" } else { "" }.to_string(),
+            description,
+            terms,
+            tags,
+        ];
+        row.extend(people.iter().cloned());
+        writer.write_record(&row)?;
+    }
+    Ok(columns.len() + 1)
+}
+
+/// A CSV data dictionary: one row per table and view, followed by a row
+/// per column, with annotations, glossary terms, tags and the effective
+/// owner and steward. Returns the number of rows written.
+pub fn write_data_dictionary<W: Write>(
+    out: W,
+    graph: &SchemaGraph,
+    annotations: &[Annotation],
+) -> Result<usize, String> {
+    let lookup = Lookup {
+        descriptions: annotations
+            .iter()
+            .map(|a| {
+                (
+                    key(&a.object_id, a.column.as_deref()),
+                    a.description.as_str(),
+                )
+            })
+            .collect(),
+        classifications: graph
+            .classifications
+            .iter()
+            .map(|c| (key(&c.object_id, c.column.as_deref()), c))
+            .collect(),
+        stewardship: &graph.stewardship,
+    };
+    let error = |e: csv::Error| format!("Failed to write data dictionary: {}", e);
+
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(HEADERS).map_err(error)?;
+    let mut rows = 0;
+    for table in &graph.tables {
+        rows += write_object(
+            &mut writer,
+            &lookup,
+            (&table.id, &table.schema, &table.name, "table"),
+            &table.columns,
+        )
+        .map_err(error)?;
+    }
+    for view in &graph.views {
+        rows += write_object(
+            &mut writer,
+            &lookup,
+            (&view.id, &view.schema, &view.name, "view"),
+            &view.columns,
+        )
+        .map_err(error)?;
+    }
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to write data dictionary: {}", e))?;
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+
+    #[test]
+    fn rows_carry_descriptions_and_inherited_stewardship() {
+        let mut graph = generate_mock_schema("small");
+        let table = graph.tables[0].clone();
+        let column = &table.columns[0];
+        graph.stewardship.push(Stewardship {
+            schema: table.schema.to_string(),
+            object_id: None,
+            owner: Some(Contact {
+                name: "Dana".to_string(),
+                email: None,
+                team: Some("Finance".to_string()),
+            }),
+            steward: None,
+        });
+        graph.classifications.push(Classification {
+            object_id: table.id.clone(),
+            column: Some(column.name.to_uppercase()),
+            terms: vec!["Customer".to_string()],
+            tags: vec!["PII".to_string(), "Email".to_string()],
+        });
+        let annotations = vec![Annotation {
+            object_id: table.id.clone(),
+            column: None,
+            description: "Main, \"primary\" table".to_string(),
+            updated_at: String::new(),
+        }];
+
+        let mut out = Vec::new();
+        let rows = write_data_dictionary(&mut out, &graph, &annotations).unwrap();
+        let expected: usize = graph
+            .tables
+            .iter()
+            .map(|t| t.columns.len() + 1)
+            .sum::<usize>()
+            + graph
+                .views
+                .iter()
+                .map(|v| v.columns.len() + 1)
+                .sum::<usize>();
+        assert_eq!(rows, expected);
+
+        let mut reader = csv::Reader::from_reader(out.as_slice());
+        let records: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(records.len(), rows);
+        assert_eq!(&records[0][7], "Main, \"primary\" table");
+        assert_eq!(&records[0][10], "Dana");
+        assert_eq!(&records[0][12], "Finance");
+        assert_eq!(&records[1][3], column.name);
+        assert_eq!(&records[1][8], "Customer");
+        assert_eq!(&records[1][9], "PII; Email");
+        assert_eq!(&records[1][10], "Dana");
+    }
+}
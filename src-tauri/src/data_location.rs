@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::i18n::t;
+
+/// Folder next to the executable that holds a portable install's data. Its
+/// presence alone turns portable mode on, so a copied install keeps working
+/// on a machine that has never run it.
+pub const PORTABLE_DIR: &str = "monocle-data";
+/// The choice made in the app, kept in the default data directory because
+/// it has to be read before the chosen one is known.
+const LOCATION_FILE: &str = "data-location.json";
+
+/// Where settings, caches, snapshots and crash reports are kept.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum DataLocationChoice {
+    /// The per-user application data directory.
+    Default,
+    /// [`PORTABLE_DIR`] next to the executable.
+    Portable,
+    Custom {
+        path: String,
+    },
+}
+
+/// What set the data directory for this launch.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DataLocationSource {
+    Default,
+    /// `--data-dir` or `--portable` on the command line.
+    LaunchFlag,
+    /// A choice saved with `set_data_location_cmd`.
+    Saved,
+    /// A [`PORTABLE_DIR`] folder was found next to the executable.
+    PortableFolder,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DataLocation {
+    pub path: PathBuf,
+    pub portable: bool,
+    pub source: DataLocationSource,
+    /// The per-user directory, which also holds the saved choice.
+    pub default_path: PathBuf,
+}
+
+/// The folder the running executable is in.
+pub fn exe_dir() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    exe.parent().map(Path::to_path_buf)
+}
+
+fn flag_value(args: &[String], flag: &str) -> Option<String> {
+    let prefix = format!("{}=", flag);
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == flag {
+            args.get(i + 1).cloned()
+        } else {
+            arg.strip_prefix(&prefix).map(str::to_string)
+        }
+    })
+}
+
+fn read_choice(default_dir: &Path) -> Option<DataLocationChoice> {
+    let content = std::fs::read_to_string(default_dir.join(LOCATION_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Pick the data directory for this launch. In order: `--data-dir <path>`,
+/// `--portable`, the choice saved in `default_dir`, a [`PORTABLE_DIR`]
+/// folder beside the executable, then `default_dir` itself.
+pub fn resolve_data_location(
+    args: &[String],
+    exe_dir: Option<&Path>,
+    default_dir: &Path,
+) -> DataLocation {
+    let portable_dir = exe_dir.map(|dir| dir.join(PORTABLE_DIR));
+    let located = |path: PathBuf, source| DataLocation {
+        portable: portable_dir.as_deref() == Some(path.as_path()),
+        path,
+        source,
+        default_path: default_dir.to_path_buf(),
+    };
+
+    if let Some(path) = flag_value(args, "--data-dir").filter(|p| !p.trim().is_empty()) {
+        return located(PathBuf::from(path), DataLocationSource::LaunchFlag);
+    }
+    if let Some(portable_dir) = &portable_dir {
+        if args.iter().any(|arg| arg == "--portable") {
+            return located(portable_dir.clone(), DataLocationSource::LaunchFlag);
+        }
+    }
+    match (read_choice(default_dir), &portable_dir) {
+        (Some(DataLocationChoice::Custom { path }), _) => {
+            return located(PathBuf::from(path), DataLocationSource::Saved);
+        }
+        (Some(DataLocationChoice::Portable), Some(portable_dir)) => {
+            return located(portable_dir.clone(), DataLocationSource::Saved);
+        }
+        (Some(_), _) => return located(default_dir.to_path_buf(), DataLocationSource::Saved),
+        (None, _) => {}
+    }
+    match &portable_dir {
+        Some(portable_dir) if portable_dir.is_dir() => {
+            located(portable_dir.clone(), DataLocationSource::PortableFolder)
+        }
+        _ => located(default_dir.to_path_buf(), DataLocationSource::Default),
+    }
+}
+
+/// Save where the next launch keeps its data. The target directory is
+/// created and checked for write access now so a bad choice fails here
+/// rather than at startup. Existing data is not moved.
+pub fn save_data_location(
+    choice: &DataLocationChoice,
+    exe_dir: Option<&Path>,
+    default_dir: &Path,
+) -> Result<PathBuf, String> {
+    let target = match choice {
+        DataLocationChoice::Default => default_dir.to_path_buf(),
+        DataLocationChoice::Portable => exe_dir
+            .map(|dir| dir.join(PORTABLE_DIR))
+            .ok_or_else(|| t!("error-data-location-no-exe"))?,
+        DataLocationChoice::Custom { path } if path.trim().is_empty() => {
+            return Err(t!("error-data-location-required"));
+        }
+        DataLocationChoice::Custom { path } => PathBuf::from(path),
+    };
+    let probe = target.join(".monocle-write-test");
+    std::fs::create_dir_all(&target)
+        .and_then(|_| std::fs::write(&probe, b""))
+        .and_then(|_| std::fs::remove_file(&probe))
+        .map_err(|e| {
+            t!(
+                "error-data-location-unwritable",
+                path = target.display().to_string(),
+                error = e.to_string()
+            )
+        })?;
+
+    std::fs::create_dir_all(default_dir)
+        .map_err(|e| format!("Failed to create storage directory: {}", e))?;
+    let content = serde_json::to_string_pretty(choice)
+        .map_err(|e| format!("Failed to serialize data location: {}", e))?;
+    std::fs::write(default_dir.join(LOCATION_FILE), content)
+        .map_err(|e| format!("Failed to write data location: {}", e))?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn flags_beat_saved_choice_which_beats_portable_folder() {
+        let root = tempdir().expect("tempdir");
+        let exe_dir = root.path().join("app");
+        let default_dir = root.path().join("roaming");
+        let custom = root.path().join("custom");
+        std::fs::create_dir_all(exe_dir.join(PORTABLE_DIR)).unwrap();
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let found = resolve_data_location(&[], Some(&exe_dir), &default_dir);
+        assert_eq!(found.source, DataLocationSource::PortableFolder);
+        assert!(found.portable);
+
+        let chosen = DataLocationChoice::Custom {
+            path: custom.to_string_lossy().into_owned(),
+        };
+        save_data_location(&chosen, Some(&exe_dir), &default_dir).unwrap();
+        let saved = resolve_data_location(&[], Some(&exe_dir), &default_dir);
+        assert_eq!(saved.path, custom);
+        assert_eq!(saved.source, DataLocationSource::Saved);
+        assert!(!saved.portable);
+
+        let flagged = resolve_data_location(&args(&["--portable"]), Some(&exe_dir), &default_dir);
+        assert!(flagged.portable);
+        let flagged = resolve_data_location(
+            &args(&["--safe-mode", "--data-dir", "/srv/monocle"]),
+            Some(&exe_dir),
+            &default_dir,
+        );
+        assert_eq!(flagged.path, PathBuf::from("/srv/monocle"));
+        assert_eq!(flagged.source, DataLocationSource::LaunchFlag);
+    }
+
+    #[test]
+    fn unusable_choices_are_rejected() {
+        let root = tempdir().expect("tempdir");
+        let blocker = root.path().join("file");
+        std::fs::write(&blocker, b"").unwrap();
+        let choice = DataLocationChoice::Custom {
+            path: blocker.join("data").to_string_lossy().into_owned(),
+        };
+        assert!(save_data_location(&choice, None, root.path()).is_err());
+        assert!(save_data_location(&DataLocationChoice::Portable, None, root.path()).is_err());
+        assert!(read_choice(root.path()).is_none());
+    }
+}
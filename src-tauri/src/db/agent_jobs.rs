@@ -0,0 +1,329 @@
+use futures_util::TryStreamExt;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap};
+use tiberius::Client;
+use tokio::net::TcpStream;
+use tokio_util::compat::Compat;
+
+use crate::db::schema_loader::{extract_table_references, reference_pattern, resolve_reference};
+use crate::db::{SchemaError, AGENT_JOB_SCHEDULES_QUERY, AGENT_JOB_STEPS_QUERY};
+use crate::types::{AgentJob, AgentJobStep, AgentSchedule, StoredProcedure};
+
+/// `EXEC dbo.usp_Load`, `EXECUTE @rc = Sales.dbo.usp_Load`.
+static EXEC_PATTERN: Lazy<Regex> = Lazy::new(|| reference_pattern(r"EXEC(?:UTE)?(?:\s+@\w+\s*=)?"));
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+struct StepRow {
+    job: String,
+    job_enabled: bool,
+    description: String,
+    owner: String,
+    step_id: i32,
+    name: String,
+    subsystem: String,
+    database: String,
+    command: String,
+}
+
+struct ScheduleRow {
+    job: String,
+    name: String,
+    enabled: bool,
+    freq_type: i32,
+    freq_interval: i32,
+    subday_type: i32,
+    subday_interval: i32,
+    start_time: i32,
+}
+
+/// Readable form of an `msdb.dbo.sysschedules` row.
+fn describe_schedule(row: &ScheduleRow) -> String {
+    let time = format!(
+        "{:02}:{:02}",
+        row.start_time / 10_000,
+        row.start_time / 100 % 100
+    );
+    let when = match row.subday_type {
+        2 => format!("every {} seconds from {}", row.subday_interval, time),
+        4 => format!("every {} minutes from {}", row.subday_interval, time),
+        8 => format!("every {} hours from {}", row.subday_interval, time),
+        _ => format!("at {}", time),
+    };
+    match row.freq_type {
+        1 => format!("Once {}", when),
+        4 if row.freq_interval <= 1 => format!("Daily {}", when),
+        4 => format!("Every {} days {}", row.freq_interval, when),
+        8 => {
+            let days: Vec<&str> = WEEKDAYS
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| row.freq_interval & (1 << i) != 0)
+                .map(|(_, day)| *day)
+                .collect();
+            format!("Weekly on {} {}", days.join(", "), when)
+        }
+        16 => format!("Monthly on day {} {}", row.freq_interval, when),
+        32 => format!("Monthly (relative) {}", when),
+        64 => "When SQL Server Agent starts".to_string(),
+        128 => "When the computer is idle".to_string(),
+        _ => format!("Unknown schedule {}", when),
+    }
+}
+
+/// Bare names, IDs and their lowercase forms, like the table lookup, with
+/// `dbo` preferred for bare names.
+fn procedure_lookup(procedures: &[StoredProcedure]) -> HashMap<String, String> {
+    let mut ordered: Vec<&StoredProcedure> = procedures.iter().collect();
+    ordered.sort_by_key(|p| &*p.schema != "dbo");
+
+    let mut lookup = HashMap::new();
+    for procedure in &ordered {
+        lookup.insert(procedure.id.clone(), procedure.id.clone());
+    }
+    for procedure in &ordered {
+        for key in [
+            procedure.name.clone(),
+            procedure.name.to_lowercase(),
+            procedure.id.to_lowercase(),
+        ] {
+            lookup.entry(key).or_insert_with(|| procedure.id.clone());
+        }
+    }
+    lookup
+}
+
+fn union(lists: impl Iterator<Item = Vec<String>>) -> Vec<String> {
+    lists
+        .flatten()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Group step rows into jobs, keeping only jobs with a T-SQL step that runs
+/// in `database`; other steps are listed but not parsed, since their names
+/// resolve against another database.
+fn build_jobs(
+    steps: Vec<StepRow>,
+    schedules: Vec<ScheduleRow>,
+    database: &str,
+    name_to_id: &HashMap<String, String>,
+    procedures: &[StoredProcedure],
+) -> Vec<AgentJob> {
+    let procedures = procedure_lookup(procedures);
+    let mut schedules_by_job: HashMap<String, Vec<AgentSchedule>> = HashMap::new();
+    for row in schedules {
+        schedules_by_job
+            .entry(row.job.clone())
+            .or_default()
+            .push(AgentSchedule {
+                description: describe_schedule(&row),
+                name: row.name,
+                enabled: row.enabled,
+            });
+    }
+
+    let mut jobs: Vec<(AgentJob, bool)> = Vec::new();
+    for row in steps {
+        let runs_here = row.subsystem.eq_ignore_ascii_case("TSQL")
+            && row.database.eq_ignore_ascii_case(database);
+        let (referenced_tables, affected_tables, executed_procedures) = if runs_here {
+            let (reads, writes) = extract_table_references(&row.command, name_to_id);
+            let executed: BTreeSet<String> = EXEC_PATTERN
+                .captures_iter(&row.command)
+                .filter_map(|cap| resolve_reference(cap.get(1)?.as_str(), &procedures).cloned())
+                .collect();
+            (reads, writes, executed.into_iter().collect())
+        } else {
+            Default::default()
+        };
+        let step = AgentJobStep {
+            step_id: row.step_id,
+            name: row.name,
+            subsystem: row.subsystem,
+            database: (!row.database.is_empty()).then_some(row.database),
+            command: row.command,
+            referenced_tables,
+            affected_tables,
+            executed_procedures,
+        };
+
+        match jobs.last_mut() {
+            Some((job, any_here)) if job.name == row.job => {
+                job.steps.push(step);
+                *any_here |= runs_here;
+            }
+            _ => jobs.push((
+                AgentJob {
+                    id: format!("job:{}", row.job),
+                    schedules: schedules_by_job.remove(&row.job).unwrap_or_default(),
+                    name: row.job,
+                    enabled: row.job_enabled,
+                    description: (!row.description.is_empty()).then_some(row.description),
+                    owner: (!row.owner.is_empty()).then_some(row.owner),
+                    steps: vec![step],
+                    referenced_tables: Vec::new(),
+                    affected_tables: Vec::new(),
+                    executed_procedures: Vec::new(),
+                    source: None,
+                },
+                runs_here,
+            )),
+        }
+    }
+
+    jobs.into_iter()
+        .filter(|(_, any_here)| *any_here)
+        .map(|(mut job, _)| {
+            job.referenced_tables = union(job.steps.iter().map(|s| s.referenced_tables.clone()));
+            job.affected_tables = union(job.steps.iter().map(|s| s.affected_tables.clone()));
+            job.executed_procedures =
+                union(job.steps.iter().map(|s| s.executed_procedures.clone()));
+            job
+        })
+        .collect()
+}
+
+/// SQL Server Agent jobs with steps in `database`, read from `msdb`. Fails
+/// without msdb access (Azure SQL Database, no `SQLAgentReaderRole`).
+pub async fn load_agent_jobs(
+    client: &mut Client<Compat<TcpStream>>,
+    database: &str,
+    name_to_id: &HashMap<String, String>,
+    procedures: &[StoredProcedure],
+) -> Result<Vec<AgentJob>, SchemaError> {
+    let mut steps = Vec::new();
+    let mut row_stream = client
+        .query(AGENT_JOB_STEPS_QUERY, &[])
+        .await?
+        .into_row_stream();
+    while let Some(row) = row_stream.try_next().await? {
+        let text = |index: usize| row.get::<&str, _>(index).unwrap_or_default().to_string();
+        steps.push(StepRow {
+            job: text(0),
+            job_enabled: row.get(1).unwrap_or_default(),
+            description: text(2),
+            owner: text(3),
+            step_id: row.get(4).unwrap_or_default(),
+            name: text(5),
+            subsystem: text(6),
+            database: text(7),
+            command: text(8),
+        });
+    }
+
+    drop(row_stream);
+
+    let mut schedules = Vec::new();
+    let stream = client.query(AGENT_JOB_SCHEDULES_QUERY, &[]).await?;
+    let mut row_stream = stream.into_row_stream();
+    while let Some(row) = row_stream.try_next().await? {
+        schedules.push(ScheduleRow {
+            job: row.get::<&str, _>(0).unwrap_or_default().to_string(),
+            name: row.get::<&str, _>(1).unwrap_or_default().to_string(),
+            enabled: row.get(2).unwrap_or_default(),
+            freq_type: row.get(3).unwrap_or_default(),
+            freq_interval: row.get(4).unwrap_or_default(),
+            subday_type: row.get(5).unwrap_or_default(),
+            subday_interval: row.get(6).unwrap_or_default(),
+            start_time: row.get(7).unwrap_or_default(),
+        });
+    }
+
+    Ok(build_jobs(
+        steps, schedules, database, name_to_id, procedures,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(job: &str, step_id: i32, database: &str, command: &str) -> StepRow {
+        StepRow {
+            job: job.to_string(),
+            job_enabled: true,
+            description: String::new(),
+            owner: "sa".to_string(),
+            step_id,
+            name: format!("Step {}", step_id),
+            subsystem: "TSQL".to_string(),
+            database: database.to_string(),
+            command: command.to_string(),
+        }
+    }
+
+    fn procedure(schema: &str, name: &str) -> StoredProcedure {
+        StoredProcedure {
+            id: format!("{}.{}", schema, name),
+            name: name.to_string(),
+            schema: schema.into(),
+            procedure_type: "SQL_STORED_PROCEDURE".into(),
+            parameters: Vec::new(),
+            definition: String::new(),
+            referenced_tables: Vec::new(),
+            affected_tables: Vec::new(),
+            analysis: Default::default(),
+            source: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn jobs_in_the_database_resolve_steps_and_schedules() {
+        let name_to_id: HashMap<String, String> = ["dbo.Orders", "dbo.OrderArchive"]
+            .iter()
+            .flat_map(|id| {
+                let name = id.trim_start_matches("dbo.");
+                [
+                    (id.to_string(), id.to_string()),
+                    (name.to_string(), id.to_string()),
+                ]
+            })
+            .collect();
+        let steps = vec![
+            step(
+                "Nightly archive",
+                1,
+                "Sales",
+                "INSERT INTO dbo.OrderArchive SELECT * FROM Orders WHERE Closed = 1",
+            ),
+            step(
+                "Nightly archive",
+                2,
+                "Sales",
+                "EXEC @rc = Sales.dbo.usp_Purge; EXEC sp_updatestats",
+            ),
+            step("Other database", 1, "Billing", "DELETE FROM dbo.Orders"),
+        ];
+        let schedules = vec![ScheduleRow {
+            job: "Nightly archive".to_string(),
+            name: "2am".to_string(),
+            enabled: true,
+            freq_type: 8,
+            freq_interval: 2 | 32,
+            subday_type: 1,
+            subday_interval: 0,
+            start_time: 20_000,
+        }];
+
+        let jobs = build_jobs(
+            steps,
+            schedules,
+            "sales",
+            &name_to_id,
+            &[procedure("dbo", "usp_Purge")],
+        );
+
+        assert_eq!(jobs.len(), 1);
+        let job = &jobs[0];
+        assert_eq!(job.id, "job:Nightly archive");
+        assert_eq!(job.steps.len(), 2);
+        assert_eq!(job.referenced_tables, vec!["dbo.Orders"]);
+        assert_eq!(job.affected_tables, vec!["dbo.OrderArchive"]);
+        assert_eq!(job.executed_procedures, vec!["dbo.usp_Purge"]);
+        assert_eq!(job.schedules[0].description, "Weekly on Mon, Fri at 02:00");
+    }
+}
@@ -0,0 +1,125 @@
+use futures_util::TryStreamExt;
+use serde::Serialize;
+use tiberius::Client;
+use tokio::net::TcpStream;
+use tokio_util::compat::Compat;
+
+use crate::db::{SchemaError, AVAILABILITY_REPLICAS_QUERY};
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailabilityReplica {
+    pub server: String,
+    /// `PRIMARY`, `SECONDARY` or `RESOLVING`; unknown for remote replicas when
+    /// connected to a secondary.
+    pub role: Option<String>,
+    pub is_local: bool,
+    pub synchronization_health: Option<String>,
+    pub availability_mode: String,
+    /// `NO`, `READ_ONLY` or `ALL`: whether the replica accepts connections
+    /// while it is a secondary.
+    pub secondary_allow_connections: String,
+}
+
+/// Availability group role of the connected replica, so the UI can show
+/// whether a read-intent connection actually landed on a readable secondary.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailabilityInfo {
+    pub group: String,
+    pub connected_server: String,
+    pub local_role: Option<String>,
+    pub database_updateable: bool,
+    pub replicas: Vec<AvailabilityReplica>,
+}
+
+struct ReplicaRow {
+    group: String,
+    connected_server: String,
+    database_updateable: bool,
+    replica: AvailabilityReplica,
+}
+
+fn build_info(rows: Vec<ReplicaRow>) -> Option<AvailabilityInfo> {
+    let first = rows.first()?;
+    let mut info = AvailabilityInfo {
+        group: first.group.clone(),
+        connected_server: first.connected_server.clone(),
+        local_role: None,
+        database_updateable: first.database_updateable,
+        replicas: Vec::with_capacity(rows.len()),
+    };
+    for row in rows {
+        if row.replica.is_local {
+            info.local_role = row.replica.role.clone();
+        }
+        info.replicas.push(row.replica);
+    }
+    Some(info)
+}
+
+/// `None` when the connected database is not part of an availability group.
+pub async fn load_availability_info(
+    client: &mut Client<Compat<TcpStream>>,
+) -> Result<Option<AvailabilityInfo>, SchemaError> {
+    let text = |row: &tiberius::Row, index: usize| row.get::<&str, _>(index).map(str::to_string);
+
+    let mut rows = Vec::new();
+    let mut stream = client
+        .query(AVAILABILITY_REPLICAS_QUERY, &[])
+        .await?
+        .into_row_stream();
+    while let Some(row) = stream.try_next().await? {
+        rows.push(ReplicaRow {
+            group: text(&row, 0).unwrap_or_default(),
+            connected_server: text(&row, 7).unwrap_or_default(),
+            database_updateable: row.get::<bool, _>(8).unwrap_or(false),
+            replica: AvailabilityReplica {
+                server: text(&row, 1).unwrap_or_default(),
+                role: text(&row, 2),
+                is_local: row.get::<bool, _>(3).unwrap_or(false),
+                synchronization_health: text(&row, 4),
+                availability_mode: text(&row, 5).unwrap_or_default(),
+                secondary_allow_connections: text(&row, 6).unwrap_or_default(),
+            },
+        });
+    }
+
+    Ok(build_info(rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(server: &str, role: Option<&str>, is_local: bool) -> ReplicaRow {
+        ReplicaRow {
+            group: "ag-sales".to_string(),
+            connected_server: "SQL02".to_string(),
+            database_updateable: false,
+            replica: AvailabilityReplica {
+                server: server.to_string(),
+                role: role.map(str::to_string),
+                is_local,
+                synchronization_health: None,
+                availability_mode: "SYNCHRONOUS_COMMIT".to_string(),
+                secondary_allow_connections: "READ_ONLY".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn local_replica_role_is_surfaced() {
+        assert_eq!(build_info(Vec::new()), None);
+
+        let info = build_info(vec![
+            row("SQL01", None, false),
+            row("SQL02", Some("SECONDARY"), true),
+        ])
+        .expect("in an availability group");
+        assert_eq!(info.group, "ag-sales");
+        assert_eq!(info.local_role.as_deref(), Some("SECONDARY"));
+        assert!(!info.database_updateable);
+        assert_eq!(info.replicas.len(), 2);
+    }
+}
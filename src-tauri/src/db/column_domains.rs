@@ -0,0 +1,123 @@
+use futures_util::TryStreamExt;
+use tiberius::Client;
+use tokio::net::TcpStream;
+use tokio_util::compat::Compat;
+
+use crate::db::{
+    object_id, quote_name, SchemaError, CHECK_CONSTRAINTS_QUERY, DEFAULT_CONSTRAINTS_QUERY,
+};
+
+/// A check constraint as read from the catalog.
+#[derive(Clone, Debug)]
+pub struct CheckConstraint {
+    pub table_id: String,
+    pub name: String,
+    pub definition: String,
+}
+
+/// A column's default constraint as read from the catalog.
+#[derive(Clone, Debug)]
+pub struct DefaultConstraint {
+    pub table_id: String,
+    pub column: String,
+    pub name: String,
+    pub definition: String,
+}
+
+/// Distinct values of one column in a sample, most frequent first. `None`
+/// counts NULLs.
+#[derive(Clone, Debug)]
+pub struct ColumnSample {
+    pub table_id: String,
+    pub column: String,
+    pub values: Vec<(Option<String>, u64)>,
+    /// The sample had more distinct values than were asked for.
+    pub truncated: bool,
+}
+
+pub async fn load_check_constraints(
+    client: &mut Client<Compat<TcpStream>>,
+) -> Result<Vec<CheckConstraint>, SchemaError> {
+    let mut checks = Vec::new();
+
+    let stream = client.query(CHECK_CONSTRAINTS_QUERY, &[]).await?;
+    let mut row_stream = stream.into_row_stream();
+
+    while let Some(row) = row_stream.try_next().await? {
+        let schema_name: &str = row.get(0).unwrap_or_default();
+        let table_name: &str = row.get(1).unwrap_or_default();
+        let name: &str = row.get(2).unwrap_or_default();
+        let definition: &str = row.get(3).unwrap_or_default();
+        checks.push(CheckConstraint {
+            table_id: object_id(&[schema_name, table_name]),
+            name: name.to_string(),
+            definition: definition.to_string(),
+        });
+    }
+
+    Ok(checks)
+}
+
+pub async fn load_default_constraints(
+    client: &mut Client<Compat<TcpStream>>,
+) -> Result<Vec<DefaultConstraint>, SchemaError> {
+    let mut defaults = Vec::new();
+
+    let stream = client.query(DEFAULT_CONSTRAINTS_QUERY, &[]).await?;
+    let mut row_stream = stream.into_row_stream();
+
+    while let Some(row) = row_stream.try_next().await? {
+        let schema_name: &str = row.get(0).unwrap_or_default();
+        let table_name: &str = row.get(1).unwrap_or_default();
+        let column: &str = row.get(2).unwrap_or_default();
+        let name: &str = row.get(3).unwrap_or_default();
+        let definition: &str = row.get(4).unwrap_or_default();
+        defaults.push(DefaultConstraint {
+            table_id: object_id(&[schema_name, table_name]),
+            column: column.to_string(),
+            name: name.to_string(),
+            definition: definition.to_string(),
+        });
+    }
+
+    Ok(defaults)
+}
+
+/// Up to `max_values` distinct values of a column among its first
+/// `sample_rows` rows, with how many rows hold each. Reads at most
+/// `sample_rows` rows, so it's safe on large tables.
+pub async fn sample_column_values(
+    client: &mut Client<Compat<TcpStream>>,
+    (table_id, schema_name, table_name, column): (&str, &str, &str, &str),
+    sample_rows: i64,
+    max_values: usize,
+) -> Result<ColumnSample, SchemaError> {
+    let sql = format!(
+        "SELECT TOP ({}) CAST(v AS nvarchar(400)), COUNT_BIG(*) AS n \
+         FROM (SELECT TOP ({}) {} AS v FROM {}.{}) s GROUP BY v ORDER BY n DESC",
+        max_values + 1,
+        sample_rows,
+        quote_name(column),
+        quote_name(schema_name),
+        quote_name(table_name)
+    );
+    let mut values = Vec::new();
+
+    let stream = client.query(sql, &[]).await?;
+    let mut row_stream = stream.into_row_stream();
+
+    while let Some(row) = row_stream.try_next().await? {
+        let value: Option<&str> = row.get(0);
+        let count: i64 = row.get(1).unwrap_or_default();
+        values.push((value.map(str::to_string), count.max(0) as u64));
+    }
+
+    let truncated = values.len() > max_values;
+    values.truncate(max_values);
+    Ok(ColumnSample {
+        table_id: table_id.to_string(),
+        column: column.to_string(),
+        values,
+        truncated,
+    })
+}
@@ -3,16 +3,22 @@ use tokio::net::TcpStream;
 use tokio_util::compat::TokioAsyncWriteCompatExt;
 
 use crate::db::ssrp::resolve_instance_port;
-use crate::types::{AuthType, ConnectionParams, ServerConnectionParams};
+use crate::types::{ConnectionParams, DatabaseEngine, ServerConnectionParams};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ConnectionError {
     #[error("Database error: {0}")]
     Tiberius(#[from] tiberius::error::Error),
+    #[error("Database error: {0}")]
+    MySql(#[from] mysql_async::Error),
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
     #[error("{0}")]
     Auth(String),
+    /// The profile is for another engine and this feature reads SQL Server
+    /// catalogs.
+    #[error("This needs a SQL Server connection")]
+    SqlServerOnly,
     #[error(
         "Could not resolve SQL Server instance `{server}\\{instance}` via SQL Server Browser (UDP 1434): {reason}. Verify SQL Server Browser is running and firewall allows UDP 1434, or connect using `server,port`."
     )]
@@ -24,6 +30,9 @@ pub enum ConnectionError {
 }
 
 pub async fn create_client(params: &ConnectionParams) -> Result<Client<tokio_util::compat::Compat<TcpStream>>, ConnectionError> {
+    if params.engine == DatabaseEngine::MySql {
+        return Err(ConnectionError::SqlServerOnly);
+    }
     let mut config = Config::new();
 
     // Parse server and port (format: "server", "server,port", "server:port", or "server\instance")
@@ -33,25 +42,7 @@ pub async fn create_client(params: &ConnectionParams) -> Result<Client<tokio_uti
     config.database(&params.database);
 
     // Configure authentication
-    match params.auth_type {
-        AuthType::Windows => {
-            #[cfg(windows)]
-            {
-                config.authentication(AuthMethod::Integrated);
-            }
-            #[cfg(not(windows))]
-            {
-                return Err(ConnectionError::Auth(
-                    "Windows Authentication is only supported on Windows".to_string(),
-                ));
-            }
-        }
-        AuthType::SqlServer => {
-            let username = params.username.as_deref().unwrap_or("");
-            let password = params.password.as_deref().unwrap_or("");
-            config.authentication(AuthMethod::sql_server(username, password));
-        }
-    }
+    config.authentication(authentication(params.sql_login())?);
 
     // Configure TLS
     if params.trust_server_certificate {
@@ -59,14 +50,11 @@ pub async fn create_client(params: &ConnectionParams) -> Result<Client<tokio_uti
     }
     config.encryption(EncryptionLevel::Required);
 
-    // Connect via TCP
-    let tcp = TcpStream::connect(config.get_addr()).await?;
-    tcp.set_nodelay(true)?;
-
-    // Create tiberius client
-    let client = Client::connect(config, tcp.compat_write()).await?;
+    // ApplicationIntent=ReadOnly; an AG listener answers with a routing
+    // redirect to a readable secondary
+    config.readonly(params.read_only_intent);
 
-    Ok(client)
+    connect(config, params.multi_subnet_failover).await
 }
 
 /// Create a client connected to the master database for listing databases
@@ -80,25 +68,7 @@ pub async fn create_server_client(params: &ServerConnectionParams) -> Result<Cli
     config.database("master"); // Connect to master database for listing databases
 
     // Configure authentication
-    match params.auth_type {
-        AuthType::Windows => {
-            #[cfg(windows)]
-            {
-                config.authentication(AuthMethod::Integrated);
-            }
-            #[cfg(not(windows))]
-            {
-                return Err(ConnectionError::Auth(
-                    "Windows Authentication is only supported on Windows".to_string(),
-                ));
-            }
-        }
-        AuthType::SqlServer => {
-            let username = params.username.as_deref().unwrap_or("");
-            let password = params.password.as_deref().unwrap_or("");
-            config.authentication(AuthMethod::sql_server(username, password));
-        }
-    }
+    config.authentication(authentication(params.sql_login())?);
 
     // Configure TLS
     if params.trust_server_certificate {
@@ -106,14 +76,70 @@ pub async fn create_server_client(params: &ServerConnectionParams) -> Result<Cli
     }
     config.encryption(EncryptionLevel::Required);
 
-    // Connect via TCP
-    let tcp = TcpStream::connect(config.get_addr()).await?;
-    tcp.set_nodelay(true)?;
+    connect(config, params.multi_subnet_failover).await
+}
 
-    // Create tiberius client
-    let client = Client::connect(config, tcp.compat_write()).await?;
+/// A SQL login, or integrated authentication (SSPI) when there is none.
+/// Integrated authentication needs Windows; the username and password
+/// fields play no part in it.
+fn authentication(login: Option<(&str, &str)>) -> Result<AuthMethod, ConnectionError> {
+    match login {
+        Some((username, password)) => Ok(AuthMethod::sql_server(username, password)),
+        #[cfg(windows)]
+        None => Ok(AuthMethod::Integrated),
+        #[cfg(not(windows))]
+        None => Err(ConnectionError::Auth(
+            "Windows Authentication is only supported on Windows".to_string(),
+        )),
+    }
+}
 
-    Ok(client)
+/// Open the TCP connection and log in, following at most one routing
+/// redirect (read-intent connections to an availability group listener are
+/// sent on to a readable secondary).
+async fn connect(
+    mut config: Config,
+    multi_subnet_failover: bool,
+) -> Result<Client<tokio_util::compat::Compat<TcpStream>>, ConnectionError> {
+    let tcp = connect_tcp(&config.get_addr(), multi_subnet_failover).await?;
+
+    match Client::connect(config.clone(), tcp.compat_write()).await {
+        Ok(client) => Ok(client),
+        Err(tiberius::error::Error::Routing { host, port }) => {
+            config.host(&host);
+            config.port(port);
+            let tcp = connect_tcp(&config.get_addr(), multi_subnet_failover).await?;
+            Ok(Client::connect(config, tcp.compat_write()).await?)
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// With `multi_subnet_failover`, every address the name resolves to is tried
+/// in parallel and the first to accept wins, so failing over to a listener IP
+/// in another subnet does not wait out a TCP timeout per stale address.
+async fn connect_tcp(
+    addr: &str,
+    multi_subnet_failover: bool,
+) -> Result<TcpStream, ConnectionError> {
+    let tcp = if multi_subnet_failover {
+        let attempts: Vec<_> = tokio::net::lookup_host(addr)
+            .await?
+            .map(|addr| Box::pin(TcpStream::connect(addr)))
+            .collect();
+        if attempts.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("{} did not resolve to any address", addr),
+            )
+            .into());
+        }
+        futures_util::future::select_ok(attempts).await?.0
+    } else {
+        TcpStream::connect(addr).await?
+    };
+    tcp.set_nodelay(true)?;
+    Ok(tcp)
 }
 
 /// Parse server string into host and port, resolving named instances via SSRP.
@@ -176,7 +202,9 @@ fn parse_server(server: &str) -> (String, u16) {
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_server, parse_server_async, ConnectionError};
+    use super::{authentication, parse_server, parse_server_async, ConnectionError};
+    use crate::types::{AuthType, ConnectionParams};
+    use tiberius::AuthMethod;
 
     #[test]
     fn parse_server_with_comma() {
@@ -199,6 +227,43 @@ mod tests {
         assert_eq!(port, 1433);
     }
 
+    #[test]
+    fn integrated_auth_ignores_sql_credentials() {
+        let params = ConnectionParams {
+            server: "sql01".to_string(),
+            database: "Sales".to_string(),
+            engine: Default::default(),
+            auth_type: AuthType::Windows,
+            username: Some("sa".to_string()),
+            password: Some("stale".to_string()),
+            trust_server_certificate: false,
+            multi_subnet_failover: false,
+            read_only_intent: false,
+        };
+        assert_eq!(params.sql_login(), None);
+        #[cfg(windows)]
+        assert_eq!(
+            authentication(params.sql_login()).unwrap(),
+            AuthMethod::Integrated
+        );
+        #[cfg(not(windows))]
+        assert!(matches!(
+            authentication(params.sql_login()),
+            Err(ConnectionError::Auth(_))
+        ));
+
+        let sql_login = ConnectionParams {
+            auth_type: AuthType::SqlServer,
+            password: None,
+            ..params
+        };
+        assert_eq!(sql_login.sql_login(), Some(("sa", "")));
+        assert_eq!(
+            authentication(sql_login.sql_login()).unwrap(),
+            AuthMethod::sql_server("sa", "")
+        );
+    }
+
     #[tokio::test]
     async fn parse_server_instance_resolution_failure_returns_explicit_error() {
         let result = parse_server_async("%%\\INSTANCE").await;
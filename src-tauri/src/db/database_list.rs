@@ -0,0 +1,148 @@
+use futures_util::TryStreamExt;
+use serde::Serialize;
+use tiberius::Client;
+use tokio::net::TcpStream;
+use tokio_util::compat::Compat;
+
+use crate::db::{SchemaError, DATABASE_DETAILS_QUERY};
+
+/// Databases sent per batch while streaming the list.
+pub const DATABASE_BATCH_SIZE: usize = 250;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseEntry {
+    pub name: String,
+    /// `sys.databases.state_desc`: `ONLINE`, `OFFLINE`, `RESTORING`, ...
+    pub state: String,
+    /// Online and openable by the current login.
+    pub accessible: bool,
+    /// Data and log file size; `None` when `sys.master_files` is not visible.
+    pub size_mb: Option<f64>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseCounts {
+    pub total: usize,
+    pub inaccessible: usize,
+}
+
+/// Turn a picker filter into a `LIKE` pattern: `*` and `?` are wildcards,
+/// everything else is literal, and a filter without wildcards matches
+/// anywhere in the name.
+pub fn like_pattern(filter: &str) -> String {
+    let filter = filter.trim();
+    let mut pattern = String::with_capacity(filter.len() + 2);
+    for c in filter.chars() {
+        match c {
+            '*' => pattern.push('%'),
+            '?' => pattern.push('_'),
+            '%' | '_' | '[' => {
+                pattern.push('[');
+                pattern.push(c);
+                pattern.push(']');
+            }
+            _ => pattern.push(c),
+        }
+    }
+    if filter.contains(['*', '?']) {
+        pattern
+    } else {
+        format!("%{}%", pattern)
+    }
+}
+
+/// Put recently used databases first, most recent first, and leave the rest
+/// in server order. Recent entries no longer on the server are dropped.
+pub fn order_by_recent(databases: Vec<String>, recent: &[String]) -> Vec<String> {
+    let rank = |name: &str| recent.iter().position(|r| r.eq_ignore_ascii_case(name));
+    let (mut used, rest): (Vec<String>, Vec<String>) =
+        databases.into_iter().partition(|d| rank(d).is_some());
+    used.sort_by_key(|d| rank(d));
+    used.extend(rest);
+    used
+}
+
+/// Case-insensitive substring filter; `filter` is already lowercased.
+fn matches_filter(name: &str, filter: &str) -> bool {
+    filter.is_empty() || name.to_lowercase().contains(filter)
+}
+
+/// Stream the server's databases, handing them to `on_batch` in groups of
+/// `batch_size` as rows arrive instead of collecting the whole list first.
+pub async fn stream_databases(
+    client: &mut Client<Compat<TcpStream>>,
+    filter: Option<&str>,
+    batch_size: usize,
+    mut on_batch: impl FnMut(Vec<DatabaseEntry>),
+) -> Result<DatabaseCounts, SchemaError> {
+    let filter = filter.map(|f| f.trim().to_lowercase()).unwrap_or_default();
+    let mut counts = DatabaseCounts::default();
+    let mut batch = Vec::with_capacity(batch_size);
+
+    let mut stream = client
+        .query(DATABASE_DETAILS_QUERY, &[])
+        .await?
+        .into_row_stream();
+    while let Some(row) = stream.try_next().await? {
+        let Some(name) = row.get::<&str, _>(0) else {
+            continue;
+        };
+        if !matches_filter(name, &filter) {
+            continue;
+        }
+        let entry = DatabaseEntry {
+            name: name.to_string(),
+            state: row.get::<&str, _>(1).unwrap_or_default().to_string(),
+            accessible: row.get::<bool, _>(2).unwrap_or(false),
+            size_mb: row.get::<f64, _>(3),
+        };
+        counts.total += 1;
+        if !entry.accessible {
+            counts.inaccessible += 1;
+        }
+        batch.push(entry);
+        if batch.len() >= batch_size {
+            on_batch(std::mem::replace(
+                &mut batch,
+                Vec::with_capacity(batch_size),
+            ));
+        }
+    }
+    if !batch.is_empty() {
+        on_batch(batch);
+    }
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn like_pattern_escapes_literals_and_maps_wildcards() {
+        assert_eq!(like_pattern("sales"), "%sales%");
+        assert_eq!(like_pattern(" Sales_2024 "), "%Sales[_]2024%");
+        assert_eq!(like_pattern("Sales*"), "Sales%");
+        assert_eq!(like_pattern("db?[1]"), "db_[[]1]");
+    }
+
+    #[test]
+    fn recent_databases_come_first() {
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let ordered = order_by_recent(
+            names(&["Archive", "Billing", "Sales", "Staging"]),
+            &names(&["sales", "Dropped", "Archive"]),
+        );
+        assert_eq!(ordered, names(&["Sales", "Archive", "Billing", "Staging"]));
+    }
+
+    #[test]
+    fn filter_is_case_insensitive_substring() {
+        assert!(matches_filter("Sales_2024", ""));
+        assert!(matches_filter("Sales_2024", "sales"));
+        assert!(matches_filter("Archive_Sales", "sales"));
+        assert!(!matches_filter("Billing", "sales"));
+    }
+}
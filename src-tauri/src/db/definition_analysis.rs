@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+
+use crate::db::schema_loader::extract_table_references;
+use crate::db::{detect_dynamic_sql, detect_working_tables};
+use crate::types::DefinitionAnalysis;
+
+#[cfg(feature = "deep-parse")]
+fn merge_ids(ids: &mut Vec<String>, more: Vec<String>) {
+    ids.extend(more);
+    ids.sort();
+    ids.dedup();
+}
+
+/// Tables a procedure or trigger body reads and writes, plus what else can
+/// be told about it. With the `deep-parse` feature the body goes through a
+/// T-SQL parser first; pattern matching covers bodies it can't make sense
+/// of, fills in the tables of statements it skipped, and reads every body
+/// when the feature is off. Dynamic SQL and working tables are picked up by
+/// pattern either way.
+pub fn analyze_definition(
+    definition: &str,
+    name_to_id: &HashMap<String, String>,
+) -> (Vec<String>, Vec<String>, DefinitionAnalysis) {
+    #[cfg(feature = "deep-parse")]
+    if let Some(mut parsed) = crate::db::tsql_parser::parse_definition(definition, name_to_id) {
+        // Statements the parser skipped can still name tables
+        if parsed.partial {
+            let (reads, writes) = extract_table_references(definition, name_to_id);
+            merge_ids(&mut parsed.reads, reads);
+            merge_ids(&mut parsed.writes, writes);
+        }
+        detect_dynamic_sql(definition, &mut parsed.analysis);
+        detect_working_tables(definition, &mut parsed.analysis);
+        return (parsed.reads, parsed.writes, parsed.analysis);
+    }
+
+    let (referenced_tables, affected_tables) = extract_table_references(definition, name_to_id);
+    let mut analysis = DefinitionAnalysis::default();
+    detect_dynamic_sql(definition, &mut analysis);
+    detect_working_tables(definition, &mut analysis);
+    (referenced_tables, affected_tables, analysis)
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use futures_util::TryStreamExt;
+use tiberius::Client;
+use tokio::net::TcpStream;
+use tokio_util::compat::Compat;
+
+use crate::db::{object_id, SchemaError, DEFINITION_CHUNK_QUERY, DEFINITION_LENGTHS_QUERY};
+
+/// Characters requested per `SUBSTRING` slice when streaming a definition.
+pub const DEFINITION_CHUNK_CHARS: i64 = 32 * 1024;
+
+/// Length of a definition in the units SQL Server reports (UTF-16 code units).
+pub fn definition_length(definition: &str) -> usize {
+    definition.encode_utf16().count()
+}
+
+/// Expected definition lengths keyed by `schema.name`.
+pub async fn load_definition_lengths(
+    client: &mut Client<Compat<TcpStream>>,
+) -> Result<HashMap<String, usize>, SchemaError> {
+    let mut lengths = HashMap::new();
+
+    let stream = client.query(DEFINITION_LENGTHS_QUERY, &[]).await?;
+    let mut row_stream = stream.into_row_stream();
+
+    while let Some(row) = row_stream.try_next().await? {
+        let schema_name: &str = row.get(0).unwrap_or_default();
+        let object_name: &str = row.get(1).unwrap_or_default();
+        let length: i64 = row.get(2).unwrap_or_default();
+        lengths.insert(
+            object_id(&[schema_name, object_name]),
+            length.max(0) as usize,
+        );
+    }
+
+    Ok(lengths)
+}
+
+/// Fetch a module definition slice by slice so its size never depends on the
+/// driver's text buffer. Returns an empty string for objects without a module.
+pub async fn fetch_definition(
+    client: &mut Client<Compat<TcpStream>>,
+    schema_name: &str,
+    object_name: &str,
+) -> Result<String, SchemaError> {
+    let mut definition = String::new();
+    let mut start: i64 = 1;
+
+    loop {
+        let row = client
+            .query(
+                DEFINITION_CHUNK_QUERY,
+                &[&schema_name, &object_name, &start, &DEFINITION_CHUNK_CHARS],
+            )
+            .await?
+            .into_row()
+            .await?;
+        let chunk: &str = row
+            .as_ref()
+            .and_then(|r| r.get::<&str, _>(0))
+            .unwrap_or_default();
+
+        definition.push_str(chunk);
+        let received = definition_length(chunk) as i64;
+        if received < DEFINITION_CHUNK_CHARS {
+            break;
+        }
+        start += received;
+    }
+
+    Ok(definition)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn definition_length_counts_utf16_units() {
+        assert_eq!(definition_length("SELECT 1"), 8);
+        // Astral characters occupy two nvarchar positions
+        assert_eq!(definition_length("-- 😀"), 5);
+        assert_eq!(definition_length("-- é"), 4);
+    }
+}
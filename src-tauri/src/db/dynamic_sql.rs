@@ -0,0 +1,205 @@
+use std::collections::{BTreeSet, HashMap};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::types::DefinitionAnalysis;
+
+static COMMENTS: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)--[^\n]*|/\*.*?\*/").unwrap());
+
+/// `EXEC (` / `EXECUTE (`, which runs a string rather than a module.
+static EXEC_STRING: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\bEXEC(?:UTE)?\s*\(").unwrap());
+
+/// `sp_executesql` up to its first argument, named or not.
+static SP_EXECUTESQL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\bsp_executesql\b\s*(?:@stmt\s*=\s*)?").unwrap());
+
+/// `SET @v =`, `SET @v +=`, `SELECT @v =` and `DECLARE @v type =`.
+static ASSIGNMENT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(?:SET|SELECT)\s+(@\w+)\s*\+?=\s*|\bDECLARE\s+(@\w+)\s+(?:AS\s+)?\w+(?:\s*\([^)]*\))?\s*=\s*",
+    )
+    .unwrap()
+});
+
+static QUOTENAME: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\bQUOTENAME\s*\(").unwrap());
+
+static VARIABLE: Lazy<Regex> = Lazy::new(|| Regex::new(r"@@?\w+").unwrap());
+
+/// End of the expression starting at `start`: a `;`, an unmatched `)`, a
+/// top-level `,` when `at_comma`, or a line break that isn't continued by
+/// `+` on either side.
+fn expression_end(text: &str, start: usize, at_comma: bool) -> usize {
+    let bytes = text.as_bytes();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut i = start;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if in_string {
+            if b == b'\'' {
+                if bytes.get(i + 1) == Some(&b'\'') {
+                    i += 1;
+                } else {
+                    in_string = false;
+                }
+            }
+        } else {
+            match b {
+                b'\'' => in_string = true,
+                b'(' => depth += 1,
+                b')' if depth == 0 => return i,
+                b')' => depth -= 1,
+                b';' if depth == 0 => return i,
+                b',' if depth == 0 && at_comma => return i,
+                b'\n' if depth == 0 => {
+                    let before = text[start..i].trim_end();
+                    let after = text[i..].trim_start();
+                    if !before.is_empty() && !before.ends_with('+') && !after.starts_with('+') {
+                        return i;
+                    }
+                }
+                _ => {}
+            }
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// `expr` with string literals and `QUOTENAME(...)` calls blanked out, so
+/// only variables that reach the SQL text unquoted remain.
+fn unquoted(expr: &str) -> String {
+    let mut out = String::with_capacity(expr.len());
+    let mut in_string = false;
+    let mut chars = expr.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\'' {
+            if in_string && chars.peek() == Some(&'\'') {
+                chars.next();
+            } else {
+                in_string = !in_string;
+            }
+            out.push(' ');
+        } else {
+            out.push(if in_string { ' ' } else { c });
+        }
+    }
+    while let Some(found) = QUOTENAME.find(&out) {
+        let end = expression_end(&out, found.end(), false);
+        let end = (end + 1).min(out.len());
+        out.replace_range(found.start()..end, &" ".repeat(end - found.start()));
+    }
+    out
+}
+
+fn variables(expr: &str) -> Vec<String> {
+    VARIABLE
+        .find_iter(&unquoted(expr))
+        .map(|m| m.as_str().to_string())
+        .filter(|v| !v.starts_with("@@"))
+        .collect()
+}
+
+/// Variables concatenated into the SQL text `expr` executes. A lone
+/// variable is followed through its assignments.
+fn concatenated_inputs(expr: &str, assignments: &HashMap<String, Vec<String>>) -> Vec<String> {
+    let expr = expr.trim();
+    let direct = variables(expr);
+    if direct.len() != 1 || direct[0].len() != expr.len() {
+        return direct;
+    }
+    let target = direct[0].to_lowercase();
+    assignments
+        .get(&target)
+        .into_iter()
+        .flatten()
+        .flat_map(|rhs| variables(rhs))
+        .filter(|v| v.to_lowercase() != target)
+        .collect()
+}
+
+/// Flag `EXEC (...)` and `sp_executesql` in a procedure or trigger body, and
+/// record variables concatenated into the executed text without `QUOTENAME`
+/// as a possible injection risk. Pattern based, so it runs with or without
+/// the T-SQL parser.
+pub fn detect_dynamic_sql(definition: &str, analysis: &mut DefinitionAnalysis) {
+    let text = COMMENTS.replace_all(definition, " ");
+
+    let mut assignments: HashMap<String, Vec<String>> = HashMap::new();
+    for cap in ASSIGNMENT.captures_iter(&text) {
+        let name = cap.get(1).or_else(|| cap.get(2)).unwrap().as_str();
+        let start = cap.get(0).unwrap().end();
+        let end = expression_end(&text, start, false);
+        assignments
+            .entry(name.to_lowercase())
+            .or_default()
+            .push(text[start..end].to_string());
+    }
+
+    let mut executed = Vec::new();
+    for found in EXEC_STRING.find_iter(&text) {
+        let end = expression_end(&text, found.end(), false);
+        executed.push(&text[found.end()..end]);
+    }
+    for found in SP_EXECUTESQL.find_iter(&text) {
+        let end = expression_end(&text, found.end(), true);
+        executed.push(&text[found.end()..end]);
+    }
+    if executed.is_empty() {
+        return;
+    }
+
+    analysis.dynamic_sql = true;
+    let mut seen: BTreeSet<String> = analysis
+        .concatenated_inputs
+        .iter()
+        .map(|v| v.to_lowercase())
+        .collect();
+    for expr in executed {
+        for input in concatenated_inputs(expr, &assignments) {
+            if seen.insert(input.to_lowercase()) {
+                analysis.concatenated_inputs.push(input);
+            }
+        }
+    }
+    analysis.injection_risk = !analysis.concatenated_inputs.is_empty();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(definition: &str) -> DefinitionAnalysis {
+        let mut analysis = DefinitionAnalysis::default();
+        detect_dynamic_sql(definition, &mut analysis);
+        analysis
+    }
+
+    #[test]
+    fn concatenated_input_is_an_injection_risk() {
+        let risky = scan(
+            "CREATE PROCEDURE dbo.Search @Table sysname, @Filter nvarchar(200) AS
+DECLARE @sql nvarchar(max) = N'SELECT * FROM ' + QUOTENAME(@Table)
+SET @sql = @sql
+    + N' WHERE Name LIKE ''' + @Filter + N''''
+EXEC (@sql)",
+        );
+        assert!(risky.dynamic_sql);
+        assert!(risky.injection_risk);
+        assert_eq!(risky.concatenated_inputs, vec!["@Filter"]);
+
+        let parameterized = scan(
+            "SET @sql = N'SELECT * FROM dbo.Orders WHERE Id = @id';
+EXEC sp_executesql @sql, N'@id int', @id = @OrderId;",
+        );
+        assert!(parameterized.dynamic_sql);
+        assert!(!parameterized.injection_risk);
+
+        let inline = scan("EXECUTE('DELETE FROM dbo.Log WHERE Day < ' + @Day)");
+        assert_eq!(inline.concatenated_inputs, vec!["@Day"]);
+
+        let commented = scan("-- EXEC (@sql)\nSELECT @@ROWCOUNT");
+        assert!(!commented.dynamic_sql);
+    }
+}
@@ -0,0 +1,90 @@
+use std::borrow::Cow;
+
+/// Bracket-quote an identifier the way T-SQL `QUOTENAME` does.
+pub fn quote_name(name: &str) -> String {
+    format!("[{}]", name.replace(']', "]]"))
+}
+
+/// Quote an identifier only when it would be ambiguous inside a dotted ID.
+/// Plain names (including ones with spaces) stay readable: `dbo.Order Details`,
+/// while `weird.name` becomes `[weird.name]`.
+pub fn id_part(name: &str) -> Cow<'_, str> {
+    if name.contains(['.', '[', ']']) {
+        Cow::Owned(quote_name(name))
+    } else {
+        Cow::Borrowed(name)
+    }
+}
+
+/// Build a graph object ID from its unquoted name parts (`schema`, `object`, ...).
+pub fn object_id(parts: &[&str]) -> String {
+    parts
+        .iter()
+        .map(|part| id_part(part))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Split a T-SQL multi-part name into unquoted parts. Understands `[..]` with
+/// `]]` escapes, `".."` with `""` escapes, and whitespace around the dots.
+pub fn split_multipart(name: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut chars = name.trim().chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '[' | '"' => {
+                let close = if c == '[' { ']' } else { '"' };
+                while let Some(inner) = chars.next() {
+                    if inner == close {
+                        if chars.peek() == Some(&close) {
+                            chars.next();
+                            current.push(close);
+                        } else {
+                            break;
+                        }
+                    } else {
+                        current.push(inner);
+                    }
+                }
+            }
+            '.' => parts.push(std::mem::take(&mut current).trim().to_string()),
+            _ => current.push(c),
+        }
+    }
+    parts.push(current.trim().to_string());
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_quote_only_ambiguous_parts() {
+        assert_eq!(object_id(&["dbo", "Orders"]), "dbo.Orders");
+        assert_eq!(object_id(&["dbo", "Order Details"]), "dbo.Order Details");
+        assert_eq!(object_id(&["dbo", "weird.name"]), "dbo.[weird.name]");
+        assert_eq!(object_id(&["dbo", "a]b"]), "dbo.[a]]b]");
+    }
+
+    #[test]
+    fn multipart_names_round_trip() {
+        assert_eq!(split_multipart("dbo.Orders"), vec!["dbo", "Orders"]);
+        assert_eq!(
+            split_multipart("[dbo] . [Order Details]"),
+            vec!["dbo", "Order Details"]
+        );
+        assert_eq!(
+            split_multipart("dbo.[weird.name]"),
+            vec!["dbo", "weird.name"]
+        );
+        assert_eq!(split_multipart("\"my \"\"x\"\"\".t"), vec!["my \"x\"", "t"]);
+        assert_eq!(split_multipart("[a]]b]"), vec!["a]b"]);
+
+        for parts in [vec!["dbo", "weird.name"], vec!["s p", "a]b"]] {
+            assert_eq!(split_multipart(&object_id(&parts)), parts);
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::types::{GraphWarning, GraphWarningKind, SchemaGraph};
+
+fn warning(
+    kind: GraphWarningKind,
+    object_id: &str,
+    missing: &str,
+    message: String,
+) -> GraphWarning {
+    GraphWarning {
+        kind,
+        object_id: object_id.to_string(),
+        missing: missing.to_string(),
+        message,
+    }
+}
+
+/// Drop reference IDs that do not resolve to a node, recording a warning for each.
+fn retain_references(
+    refs: &mut Vec<String>,
+    owner_id: &str,
+    nodes: &HashSet<String>,
+    warnings: &mut Vec<GraphWarning>,
+) {
+    refs.retain(|target| {
+        let exists = nodes.contains(target);
+        if !exists {
+            warnings.push(warning(
+                GraphWarningKind::DanglingReference,
+                owner_id,
+                target,
+                format!("{} references {}, which was not loaded", owner_id, target),
+            ));
+        }
+        exists
+    });
+}
+
+/// Post-load integrity pass. Removes relationships, triggers and references
+/// that point at nodes missing from the graph, so the frontend never renders a
+/// dangling edge, and reports each removal in `graph.warnings`.
+pub fn validate_graph(graph: &mut SchemaGraph) {
+    let mut warnings = Vec::new();
+
+    let columns_by_node: HashMap<String, HashSet<&str>> = graph
+        .tables
+        .iter()
+        .map(|t| (&t.id, &t.columns))
+        .chain(graph.views.iter().map(|v| (&v.id, &v.columns)))
+        .map(|(id, columns)| {
+            (
+                id.clone(),
+                columns.iter().map(|c| c.name.as_str()).collect(),
+            )
+        })
+        .collect();
+    let nodes: HashSet<String> = columns_by_node.keys().cloned().collect();
+    let table_ids: HashSet<&str> = graph.tables.iter().map(|t| t.id.as_str()).collect();
+
+    graph.relationships.retain(|edge| {
+        for (end, column) in [(&edge.from, &edge.from_column), (&edge.to, &edge.to_column)] {
+            let Some(columns) = columns_by_node.get(end) else {
+                warnings.push(warning(
+                    GraphWarningKind::DanglingRelationship,
+                    &edge.id,
+                    end,
+                    format!("Relationship {} points at missing object {}", edge.id, end),
+                ));
+                return false;
+            };
+            if let Some(column) = column.as_deref().filter(|c| !columns.contains(c)) {
+                warnings.push(warning(
+                    GraphWarningKind::MissingRelationshipColumn,
+                    &edge.id,
+                    &format!("{}.{}", end, column),
+                    format!(
+                        "Relationship {} uses column {} which {} does not have",
+                        edge.id, column, end
+                    ),
+                ));
+                return false;
+            }
+        }
+        true
+    });
+
+    graph.triggers.retain(|trigger| {
+        let exists = table_ids.contains(trigger.table_id.as_str());
+        if !exists {
+            warnings.push(warning(
+                GraphWarningKind::OrphanTrigger,
+                &trigger.id,
+                &trigger.table_id,
+                format!(
+                    "Trigger {} belongs to missing table {}",
+                    trigger.id, trigger.table_id
+                ),
+            ));
+        }
+        exists
+    });
+
+    for view in &mut graph.views {
+        retain_references(&mut view.referenced_tables, &view.id, &nodes, &mut warnings);
+    }
+    for trigger in &mut graph.triggers {
+        retain_references(
+            &mut trigger.referenced_tables,
+            &trigger.id,
+            &nodes,
+            &mut warnings,
+        );
+        retain_references(
+            &mut trigger.affected_tables,
+            &trigger.id,
+            &nodes,
+            &mut warnings,
+        );
+    }
+    for procedure in &mut graph.stored_procedures {
+        retain_references(
+            &mut procedure.referenced_tables,
+            &procedure.id,
+            &nodes,
+            &mut warnings,
+        );
+        retain_references(
+            &mut procedure.affected_tables,
+            &procedure.id,
+            &nodes,
+            &mut warnings,
+        );
+    }
+    for function in &mut graph.scalar_functions {
+        retain_references(
+            &mut function.referenced_tables,
+            &function.id,
+            &nodes,
+            &mut warnings,
+        );
+        retain_references(
+            &mut function.affected_tables,
+            &function.id,
+            &nodes,
+            &mut warnings,
+        );
+    }
+
+    graph.warnings.extend(warnings);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Column, RelationshipEdge, StoredProcedure, TableNode, Trigger};
+
+    fn table(id: &str, columns: &[&str]) -> TableNode {
+        TableNode {
+            id: id.to_string(),
+            name: id.rsplit('.').next().unwrap().to_string(),
+            schema: "dbo".into(),
+            columns: columns
+                .iter()
+                .map(|name| Column {
+                    name: name.to_string(),
+                    data_type: "int".into(),
+                    ..Default::default()
+                })
+                .collect(),
+            source: None,
+            metadata: None,
+            change_capture: None,
+        }
+    }
+
+    fn edge(id: &str, from: &str, to: &str, to_column: &str) -> RelationshipEdge {
+        RelationshipEdge {
+            id: id.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+            from_column: Some("CustomerId".to_string()),
+            to_column: Some(to_column.to_string()),
+            source: None,
+            is_virtual: false,
+            participation: None,
+        }
+    }
+
+    fn graph() -> SchemaGraph {
+        SchemaGraph {
+            tables: vec![
+                table("dbo.Orders", &["Id", "CustomerId"]),
+                table("dbo.Customers", &["Id"]),
+            ],
+            views: Vec::new(),
+            relationships: vec![
+                edge("FK_ok", "dbo.Orders", "dbo.Customers", "Id"),
+                edge("FK_missing_table", "dbo.Orders", "dbo.Gone", "Id"),
+                edge("FK_missing_column", "dbo.Orders", "dbo.Customers", "Code"),
+            ],
+            triggers: vec![Trigger {
+                id: "dbo.Gone.trg".to_string(),
+                name: "trg".to_string(),
+                schema: "dbo".into(),
+                table_id: "dbo.Gone".to_string(),
+                trigger_type: "SQL_TRIGGER".into(),
+                is_disabled: false,
+                fires_on_insert: true,
+                fires_on_update: false,
+                fires_on_delete: false,
+                definition: String::new(),
+                referenced_tables: Vec::new(),
+                affected_tables: Vec::new(),
+                analysis: Default::default(),
+                watched_columns: Vec::new(),
+                source: None,
+                metadata: None,
+            }],
+            stored_procedures: vec![StoredProcedure {
+                id: "dbo.usp".to_string(),
+                name: "usp".to_string(),
+                schema: "dbo".into(),
+                procedure_type: "SQL_STORED_PROCEDURE".into(),
+                parameters: Vec::new(),
+                definition: String::new(),
+                referenced_tables: vec!["dbo.Orders".to_string(), "dbo.Gone".to_string()],
+                affected_tables: Vec::new(),
+                analysis: Default::default(),
+                source: None,
+                metadata: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn dangling_items_are_removed_and_reported() {
+        let mut graph = graph();
+        validate_graph(&mut graph);
+
+        assert_eq!(graph.relationships.len(), 1);
+        assert_eq!(graph.relationships[0].id, "FK_ok");
+        assert!(graph.triggers.is_empty());
+        assert_eq!(
+            graph.stored_procedures[0].referenced_tables,
+            vec!["dbo.Orders"]
+        );
+
+        let kinds: Vec<GraphWarningKind> = graph.warnings.iter().map(|w| w.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                GraphWarningKind::DanglingRelationship,
+                GraphWarningKind::MissingRelationshipColumn,
+                GraphWarningKind::OrphanTrigger,
+                GraphWarningKind::DanglingReference,
+            ]
+        );
+        assert_eq!(graph.warnings[3].missing, "dbo.Gone");
+
+        // A second pass over the cleaned graph finds nothing new
+        let reported = graph.warnings.len();
+        validate_graph(&mut graph);
+        assert_eq!(graph.warnings.len(), reported);
+    }
+}
@@ -0,0 +1,255 @@
+use chrono::{Duration, NaiveDateTime};
+use futures_util::TryStreamExt;
+use serde::Serialize;
+use std::collections::HashMap;
+use tiberius::Client;
+use tokio::net::TcpStream;
+use tokio_util::compat::Compat;
+
+use crate::db::{object_id, quote_name, SchemaError, MIGRATION_TABLES_QUERY};
+use crate::types::ObjectMetadata;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MigrationTool {
+    Flyway,
+    Liquibase,
+    EfCore,
+}
+
+/// One row of the history table. Liquibase changesets map their ID to
+/// `version`, changelog file to `script` and author to `installed_by`; EF
+/// Core migrations their `MigrationId` to `version` and `script`.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedMigration {
+    /// Flyway `installed_rank`, Liquibase `ORDEREXECUTED`, or the position of
+    /// an EF Core migration ID.
+    pub rank: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub description: String,
+    /// Flyway `type` (`SQL`, `BASELINE`, ...), Liquibase `EXECTYPE`, or EF
+    /// Core `ProductVersion`.
+    pub kind: String,
+    pub script: String,
+    pub installed_by: String,
+    /// ISO 8601, server-local time; empty for EF Core, which doesn't record it.
+    pub installed_on: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_ms: Option<i32>,
+    pub success: bool,
+}
+
+/// The migration during which an object was created.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectMigration {
+    pub object_id: String,
+    pub rank: i32,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationHistory {
+    pub tool: MigrationTool,
+    /// Graph ID of the history table.
+    pub table: String,
+    /// In the order they were applied.
+    pub migrations: Vec<AppliedMigration>,
+    pub objects: Vec<ObjectMigration>,
+}
+
+fn flyway_query(table: &str) -> String {
+    format!(
+        "SELECT installed_rank, version, description, type, script, installed_by,
+                CONVERT(varchar(23), installed_on, 126), execution_time, CAST(success AS bit)
+         FROM {}
+         ORDER BY installed_rank",
+        table
+    )
+}
+
+fn liquibase_query(table: &str) -> String {
+    format!(
+        "SELECT ORDEREXECUTED, ID, ISNULL(DESCRIPTION, ''), ISNULL(EXECTYPE, ''), FILENAME, AUTHOR,
+                CONVERT(varchar(23), DATEEXECUTED, 126), CAST(NULL AS int),
+                CAST(CASE WHEN EXECTYPE = 'FAILED' THEN 0 ELSE 1 END AS bit)
+         FROM {}
+         ORDER BY ORDEREXECUTED",
+        table
+    )
+}
+
+/// EF Core migration IDs start with a UTC timestamp, so they sort in the
+/// order the migrations were written and applied.
+fn ef_core_query(table: &str) -> String {
+    format!(
+        "SELECT CAST(ROW_NUMBER() OVER (ORDER BY MigrationId) AS int), MigrationId,
+                CASE WHEN CHARINDEX('_', MigrationId) > 0
+                     THEN SUBSTRING(MigrationId, CHARINDEX('_', MigrationId) + 1, 300)
+                     ELSE MigrationId END,
+                ProductVersion, MigrationId, '', '', CAST(NULL AS int), CAST(1 AS bit)
+         FROM {}
+         ORDER BY MigrationId",
+        table
+    )
+}
+
+fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").ok()
+}
+
+/// The Flyway, Liquibase or EF Core history of the connected database, with
+/// each object attributed to the migration that created it where the tool
+/// records when migrations ran. `None` when no history table exists.
+pub async fn load_migration_history(
+    client: &mut Client<Compat<TcpStream>>,
+    metadata: &HashMap<String, ObjectMetadata>,
+) -> Result<Option<MigrationHistory>, SchemaError> {
+    let row = client
+        .query(MIGRATION_TABLES_QUERY, &[])
+        .await?
+        .into_row()
+        .await?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let schema_name: &str = row.get(0).unwrap_or_default();
+    let table_name: &str = row.get(1).unwrap_or_default();
+    let quoted = format!("{}.{}", quote_name(schema_name), quote_name(table_name));
+    let (tool, sql) = match table_name.to_lowercase().as_str() {
+        "databasechangelog" => (MigrationTool::Liquibase, liquibase_query(&quoted)),
+        "__efmigrationshistory" => (MigrationTool::EfCore, ef_core_query(&quoted)),
+        _ => (MigrationTool::Flyway, flyway_query(&quoted)),
+    };
+    let table = object_id(&[schema_name, table_name]);
+
+    let mut migrations = Vec::new();
+    let stream = client.query(sql, &[]).await?;
+    let mut row_stream = stream.into_row_stream();
+    while let Some(row) = row_stream.try_next().await? {
+        let text = |index: usize| row.get::<&str, _>(index).unwrap_or_default().to_string();
+        migrations.push(AppliedMigration {
+            rank: row.get(0).unwrap_or_default(),
+            version: row
+                .get::<&str, _>(1)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string),
+            description: text(2),
+            kind: text(3),
+            script: text(4),
+            installed_by: text(5),
+            installed_on: text(6),
+            execution_ms: row.get(7),
+            success: row.get(8).unwrap_or_default(),
+        });
+    }
+
+    let objects = attribute_objects(&migrations, metadata);
+    Ok(Some(MigrationHistory {
+        tool,
+        table,
+        migrations,
+        objects,
+    }))
+}
+
+/// Both tools record a migration when it finishes, so an object belongs to
+/// the first successful migration installed at or after its `create_date`
+/// and after the one before. The first migration only claims objects created
+/// while it ran, when its execution time is known; objects that predate the
+/// history (or a baseline) stay unattributed.
+pub fn attribute_objects(
+    migrations: &[AppliedMigration],
+    metadata: &HashMap<String, ObjectMetadata>,
+) -> Vec<ObjectMigration> {
+    let mut applied: Vec<(NaiveDateTime, &AppliedMigration)> = migrations
+        .iter()
+        .filter(|m| m.success)
+        .filter_map(|m| Some((parse_timestamp(&m.installed_on)?, m)))
+        .collect();
+    applied.sort_by_key(|(installed_on, m)| (*installed_on, m.rank));
+
+    let mut objects: Vec<ObjectMigration> = metadata
+        .iter()
+        .filter_map(|(id, meta)| {
+            let created = parse_timestamp(meta.created_at.as_deref()?)?;
+            let index = applied.partition_point(|(installed_on, _)| *installed_on < created);
+            let (installed_on, migration) = applied.get(index)?;
+            if index == 0 {
+                let started = *installed_on
+                    - Duration::milliseconds(i64::from(migration.execution_ms?))
+                    - Duration::seconds(1);
+                if created < started {
+                    return None;
+                }
+            }
+            Some(ObjectMigration {
+                object_id: id.clone(),
+                rank: migration.rank,
+            })
+        })
+        .collect();
+    objects.sort_by(|a, b| a.rank.cmp(&b.rank).then(a.object_id.cmp(&b.object_id)));
+    objects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migration(
+        rank: i32,
+        installed_on: &str,
+        execution_ms: i32,
+        success: bool,
+    ) -> AppliedMigration {
+        AppliedMigration {
+            rank,
+            version: Some(rank.to_string()),
+            description: format!("step {}", rank),
+            kind: "SQL".to_string(),
+            script: format!("V{}__step.sql", rank),
+            installed_by: "deploy".to_string(),
+            installed_on: installed_on.to_string(),
+            execution_ms: Some(execution_ms),
+            success,
+        }
+    }
+
+    fn created(at: &str) -> ObjectMetadata {
+        ObjectMetadata {
+            owner: None,
+            created_at: Some(at.to_string()),
+            modified_at: None,
+        }
+    }
+
+    #[test]
+    fn objects_belong_to_the_migration_that_was_running() {
+        let migrations = vec![
+            migration(1, "2024-03-01T10:00:05.000", 2_000, true),
+            migration(2, "2024-03-02T09:00:00.500", 300, false),
+            migration(3, "2024-03-02T09:05:00.000", 1_000, true),
+        ];
+        let metadata = HashMap::from([
+            ("dbo.Legacy".to_string(), created("2023-12-24T08:00:00")),
+            ("dbo.Orders".to_string(), created("2024-03-01T10:00:04.250")),
+            ("dbo.Lines".to_string(), created("2024-03-02T09:00:00.400")),
+            ("dbo.Audit".to_string(), created("2024-03-02T09:04:59.900")),
+            ("dbo.Later".to_string(), created("2024-05-01T00:00:00")),
+        ]);
+
+        let objects = attribute_objects(&migrations, &metadata);
+        let attributed: Vec<(&str, i32)> = objects
+            .iter()
+            .map(|o| (o.object_id.as_str(), o.rank))
+            .collect();
+        // The failed migration's object is credited to the retry that succeeded
+        assert_eq!(
+            attributed,
+            vec![("dbo.Orders", 1), ("dbo.Audit", 3), ("dbo.Lines", 3)]
+        );
+    }
+}
@@ -1,8 +1,63 @@
+pub mod agent_jobs;
+pub mod availability;
+pub mod column_domains;
 pub mod connection;
+pub mod database_list;
+pub mod definition_analysis;
+pub mod definitions;
+pub mod dynamic_sql;
+pub mod identifiers;
+pub mod integrity;
+pub mod migration_history;
+pub mod mysql;
+pub mod object_state;
+pub mod participation;
+pub mod procedure_runner;
 pub mod queries;
+pub mod query_runner;
+pub mod row_counts;
 pub mod schema_loader;
 pub mod ssrp;
+pub mod trigger_columns;
+#[cfg(feature = "deep-parse")]
+pub mod tsql_parser;
+pub mod working_tables;
 
+pub use agent_jobs::load_agent_jobs;
+pub use availability::{load_availability_info, AvailabilityInfo};
+pub use column_domains::{
+    load_check_constraints, load_default_constraints, sample_column_values, CheckConstraint,
+    ColumnSample, DefaultConstraint,
+};
 pub use connection::{create_client, create_server_client, ConnectionError};
+pub use database_list::{
+    like_pattern, order_by_recent, stream_databases, DatabaseCounts, DatabaseEntry,
+    DATABASE_BATCH_SIZE,
+};
+pub use definition_analysis::analyze_definition;
+pub use definitions::*;
+pub use dynamic_sql::detect_dynamic_sql;
+pub use identifiers::*;
+pub use integrity::validate_graph;
+pub use migration_history::{
+    load_migration_history, AppliedMigration, MigrationHistory, MigrationTool,
+};
+pub use mysql::load_mysql_schema;
+pub use object_state::{
+    apply_object_metadata, load_column_indexes, load_index_leading_columns, load_object_metadata,
+    load_object_states, load_schema_fingerprint, load_server_utc_now, ColumnIndex,
+};
+pub use participation::{annotate_participation, constraint_participation};
+pub use procedure_runner::{execute_procedure, ProcedureRun};
 pub use queries::*;
+pub use query_runner::{
+    run_read_only_query, stream_read_only_query, QueryLimits, QueryParameter, QueryResult, RowSink,
+    RowsStreamed,
+};
+pub use row_counts::{
+    compare_row_counts, key_checksum, load_primary_key_columns, load_row_counts, RowCountDrift,
+    DEFAULT_DRIFT_THRESHOLD_PERCENT,
+};
 pub use schema_loader::*;
+pub use trigger_columns::{annotate_watched_columns, triggers_watching};
+pub use working_tables::detect_working_tables;
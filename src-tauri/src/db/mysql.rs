@@ -0,0 +1,578 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Instant;
+
+use mysql_async::prelude::Queryable;
+use mysql_async::{Conn, OptsBuilder, SslOpts};
+
+use crate::db::schema_loader::{
+    build_name_lookup, extract_table_references, phase_record, sort_graph, CORE_PHASE_TIMEOUT,
+    OPTIONAL_PHASE_TIMEOUT, PHASE_ATTEMPTS,
+};
+use crate::db::{
+    analyze_definition, annotate_participation, annotate_watched_columns, object_id,
+    validate_graph, ConnectionError, SchemaError,
+};
+use crate::types::{
+    AuthType, Column, ConnectionParams, Interner, ProcedureParameter, RelationshipEdge,
+    ScalarFunction, SchemaGraph, StoredProcedure, TableNode, Trigger, ViewNode,
+};
+
+const DEFAULT_PORT: u16 = 3306;
+
+/// Every column of every table and view in the schema. MariaDB reports
+/// system-versioned tables as `SYSTEM VERSIONED`.
+const COLUMNS_QUERY: &str = r#"
+SELECT c.TABLE_NAME, c.COLUMN_NAME, c.COLUMN_TYPE, c.IS_NULLABLE, c.COLUMN_KEY,
+       c.ORDINAL_POSITION, t.TABLE_TYPE, c.EXTRA
+FROM information_schema.COLUMNS c
+JOIN information_schema.TABLES t
+  ON t.TABLE_SCHEMA = c.TABLE_SCHEMA AND t.TABLE_NAME = c.TABLE_NAME
+WHERE c.TABLE_SCHEMA = ?
+ORDER BY c.TABLE_NAME, c.ORDINAL_POSITION
+"#;
+
+/// Empty definitions come back for views the login lacks `SHOW VIEW` on.
+const VIEWS_QUERY: &str = r#"
+SELECT TABLE_NAME, VIEW_DEFINITION
+FROM information_schema.VIEWS
+WHERE TABLE_SCHEMA = ?
+"#;
+
+const FOREIGN_KEYS_QUERY: &str = r#"
+SELECT CONSTRAINT_NAME, TABLE_NAME, COLUMN_NAME,
+       REFERENCED_TABLE_SCHEMA, REFERENCED_TABLE_NAME, REFERENCED_COLUMN_NAME
+FROM information_schema.KEY_COLUMN_USAGE
+WHERE TABLE_SCHEMA = ? AND REFERENCED_TABLE_NAME IS NOT NULL
+ORDER BY TABLE_NAME, CONSTRAINT_NAME, ORDINAL_POSITION
+"#;
+
+const TRIGGERS_QUERY: &str = r#"
+SELECT TRIGGER_NAME, EVENT_OBJECT_TABLE, ACTION_TIMING, EVENT_MANIPULATION, ACTION_STATEMENT
+FROM information_schema.TRIGGERS
+WHERE TRIGGER_SCHEMA = ?
+"#;
+
+/// Procedures and functions with their parameters, one row per parameter.
+/// Position 0 is a function's return value, which `DTD_IDENTIFIER` on the
+/// routine already gives.
+const ROUTINES_QUERY: &str = r#"
+SELECT r.ROUTINE_NAME, r.ROUTINE_TYPE, r.DTD_IDENTIFIER, r.ROUTINE_DEFINITION,
+       p.PARAMETER_NAME, p.PARAMETER_MODE, p.DTD_IDENTIFIER
+FROM information_schema.ROUTINES r
+LEFT JOIN information_schema.PARAMETERS p
+  ON p.SPECIFIC_SCHEMA = r.ROUTINE_SCHEMA
+ AND p.SPECIFIC_NAME = r.SPECIFIC_NAME
+ AND p.ROUTINE_TYPE = r.ROUTINE_TYPE
+ AND p.ORDINAL_POSITION > 0
+WHERE r.ROUTINE_SCHEMA = ?
+ORDER BY r.ROUTINE_NAME, p.ORDINAL_POSITION
+"#;
+
+type ColumnRow = (String, String, String, String, String, u32, String, String);
+type ForeignKeyRow = (String, String, String, String, String, String);
+type TriggerRow = (String, String, String, String, String);
+type RoutineRow = (
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+/// Split `host`, `host:port` or `host,port` into host and port.
+fn parse_mysql_server(server: &str) -> (String, u16) {
+    let server = server.trim();
+    let split = server.split_once(',').or_else(|| server.rsplit_once(':'));
+    match split.and_then(|(host, port)| Some((host, port.trim().parse::<u16>().ok()?))) {
+        Some((host, port)) => (host.trim().to_string(), port),
+        None => (server.to_string(), DEFAULT_PORT),
+    }
+}
+
+/// Connect to a MySQL or MariaDB server with the profile's database
+/// selected. TLS is required, as it is for SQL Server.
+pub async fn create_mysql_conn(params: &ConnectionParams) -> Result<Conn, ConnectionError> {
+    if params.auth_type == AuthType::Windows {
+        return Err(ConnectionError::Auth(
+            "Windows Authentication is not available for MySQL".to_string(),
+        ));
+    }
+    let (host, port) = parse_mysql_server(&params.server);
+    let ssl = SslOpts::default().with_danger_accept_invalid_certs(params.trust_server_certificate);
+    let opts = OptsBuilder::default()
+        .ip_or_hostname(host)
+        .tcp_port(port)
+        .user(params.username.clone())
+        .pass(params.password.clone())
+        .db_name(Some(params.database.clone()))
+        .prefer_socket(false)
+        .ssl_opts(ssl);
+    Ok(Conn::new(opts).await?)
+}
+
+/// Rewrite MySQL's `` `quoted` `` identifiers as `[quoted]` so the T-SQL
+/// reference patterns used for SQL Server bodies read them too. String
+/// literals are left alone.
+fn bracket_identifiers(sql: &str) -> Cow<'_, str> {
+    if !sql.contains('`') {
+        return Cow::Borrowed(sql);
+    }
+    let mut out = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    let mut in_string = None;
+    while let Some(c) = chars.next() {
+        match (in_string, c) {
+            (Some(quote), _) if c == quote => {
+                in_string = None;
+                out.push(c);
+            }
+            (Some(_), _) => out.push(c),
+            (None, '\'' | '"') => {
+                in_string = Some(c);
+                out.push(c);
+            }
+            (None, '`') => {
+                out.push('[');
+                while let Some(c) = chars.next() {
+                    match c {
+                        '`' if chars.peek() == Some(&'`') => {
+                            chars.next();
+                            out.push('`');
+                        }
+                        '`' => break,
+                        ']' => out.push_str("]]"),
+                        _ => out.push(c),
+                    }
+                }
+                out.push(']');
+            }
+            (None, _) => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
+fn build_tables_and_views(
+    database: &str,
+    rows: Vec<ColumnRow>,
+    definitions: &HashMap<String, String>,
+    interner: &mut Interner,
+) -> (Vec<TableNode>, Vec<ViewNode>) {
+    let schema = interner.intern(database);
+    let mut tables: BTreeMap<String, TableNode> = BTreeMap::new();
+    let mut views: BTreeMap<String, ViewNode> = BTreeMap::new();
+    for (
+        table_name,
+        column_name,
+        column_type,
+        is_nullable,
+        column_key,
+        position,
+        table_type,
+        extra,
+    ) in rows
+    {
+        let extra = extra.to_ascii_lowercase();
+        let id = object_id(&[database, &table_name]);
+        let column = Column {
+            name: column_name,
+            data_type: interner.intern(&column_type),
+            is_nullable: is_nullable.eq_ignore_ascii_case("YES"),
+            is_primary_key: column_key == "PRI",
+            ordinal_position: position,
+            is_identity: extra.contains("auto_increment"),
+            // `VIRTUAL GENERATED` / `STORED GENERATED`; MariaDB says `PERSISTENT`
+            is_computed: extra.contains("generated")
+                || extra == "virtual"
+                || extra == "persistent"
+                || extra == "stored",
+            ..Default::default()
+        };
+        if table_type == "VIEW" {
+            views
+                .entry(id.clone())
+                .or_insert_with(|| ViewNode {
+                    definition: definitions.get(&table_name).cloned().unwrap_or_default(),
+                    id,
+                    name: table_name,
+                    schema: schema.clone(),
+                    columns: Vec::new(),
+                    referenced_tables: Vec::new(),
+                    source: None,
+                    metadata: None,
+                    is_indexed: false,
+                })
+                .columns
+                .push(column);
+        } else {
+            tables
+                .entry(id.clone())
+                .or_insert_with(|| TableNode {
+                    id,
+                    name: table_name,
+                    schema: schema.clone(),
+                    columns: Vec::new(),
+                    source: None,
+                    metadata: None,
+                    change_capture: None,
+                })
+                .columns
+                .push(column);
+        }
+    }
+    (
+        tables.into_values().collect(),
+        views.into_values().collect(),
+    )
+}
+
+fn build_foreign_keys(database: &str, rows: Vec<ForeignKeyRow>) -> Vec<RelationshipEdge> {
+    rows.into_iter()
+        .map(
+            |(name, table, column, ref_schema, ref_table, ref_column)| RelationshipEdge {
+                id: name,
+                from: object_id(&[database, &table]),
+                // Keys may point into another database on the same server
+                to: object_id(&[&ref_schema, &ref_table]),
+                from_column: Some(column),
+                to_column: Some(ref_column),
+                source: None,
+                is_virtual: false,
+                participation: None,
+            },
+        )
+        .collect()
+}
+
+fn build_triggers(
+    database: &str,
+    rows: Vec<TriggerRow>,
+    name_to_id: &HashMap<String, String>,
+    interner: &mut Interner,
+) -> Vec<Trigger> {
+    rows.into_iter()
+        .map(|(name, table, timing, event, statement)| {
+            let (referenced_tables, affected_tables, analysis) =
+                analyze_definition(&bracket_identifiers(&statement), name_to_id);
+            Trigger {
+                id: object_id(&[database, &table, &name]),
+                name,
+                schema: interner.intern(database),
+                table_id: object_id(&[database, &table]),
+                trigger_type: interner.intern(&timing),
+                is_disabled: false,
+                fires_on_insert: event.eq_ignore_ascii_case("INSERT"),
+                fires_on_update: event.eq_ignore_ascii_case("UPDATE"),
+                fires_on_delete: event.eq_ignore_ascii_case("DELETE"),
+                definition: statement,
+                referenced_tables,
+                affected_tables,
+                watched_columns: Vec::new(),
+                analysis,
+                source: None,
+                metadata: None,
+            }
+        })
+        .collect()
+}
+
+fn build_routines(
+    database: &str,
+    rows: Vec<RoutineRow>,
+    name_to_id: &HashMap<String, String>,
+    interner: &mut Interner,
+) -> (Vec<StoredProcedure>, Vec<ScalarFunction>) {
+    let mut procedures: BTreeMap<String, StoredProcedure> = BTreeMap::new();
+    let mut functions: BTreeMap<String, ScalarFunction> = BTreeMap::new();
+    for (name, routine_type, return_type, definition, parameter, mode, parameter_type) in rows {
+        let id = object_id(&[database, &name]);
+        let definition = definition.unwrap_or_default();
+        let parameter = parameter.map(|parameter| ProcedureParameter {
+            name: parameter,
+            data_type: interner.intern(parameter_type.as_deref().unwrap_or_default()),
+            is_output: matches!(mode.as_deref(), Some("OUT" | "INOUT")),
+        });
+        let parameters = if routine_type == "FUNCTION" {
+            &mut functions
+                .entry(id.clone())
+                .or_insert_with(|| {
+                    let (referenced_tables, affected_tables) =
+                        extract_table_references(&bracket_identifiers(&definition), name_to_id);
+                    ScalarFunction {
+                        id,
+                        name,
+                        schema: interner.intern(database),
+                        function_type: interner.intern("SQL_SCALAR_FUNCTION"),
+                        parameters: Vec::new(),
+                        return_type: interner.intern(return_type.as_deref().unwrap_or_default()),
+                        definition,
+                        referenced_tables,
+                        affected_tables,
+                        source: None,
+                        metadata: None,
+                    }
+                })
+                .parameters
+        } else {
+            &mut procedures
+                .entry(id.clone())
+                .or_insert_with(|| {
+                    let (referenced_tables, affected_tables, analysis) =
+                        analyze_definition(&bracket_identifiers(&definition), name_to_id);
+                    StoredProcedure {
+                        id,
+                        name,
+                        schema: interner.intern(database),
+                        procedure_type: interner.intern("SQL_STORED_PROCEDURE"),
+                        parameters: Vec::new(),
+                        definition,
+                        referenced_tables,
+                        affected_tables,
+                        analysis,
+                        source: None,
+                        metadata: None,
+                    }
+                })
+                .parameters
+        };
+        parameters.extend(parameter);
+    }
+    (
+        procedures.into_values().collect(),
+        functions.into_values().collect(),
+    )
+}
+
+/// Like the SQL Server loader's `run_phase!`: run one phase under a
+/// timeout, retrying once on a fresh connection, and record the outcome.
+/// The connection is replaced after the final failure too.
+macro_rules! run_phase {
+    ($phases:ident, $conn:ident, $params:expr, $name:expr, $timeout:expr, $call:expr) => {{
+        let started = Instant::now();
+        let mut attempts = 0;
+        let result: Result<_, SchemaError> = loop {
+            attempts += 1;
+            let error = match tokio::time::timeout($timeout, $call).await {
+                Ok(Ok(value)) => break Ok(value),
+                Ok(Err(e)) => SchemaError::from(e),
+                Err(_) => SchemaError::PhaseTimeout {
+                    phase: $name.to_string(),
+                    seconds: $timeout.as_secs(),
+                },
+            };
+            match create_mysql_conn($params).await {
+                Ok(fresh) => $conn = fresh,
+                Err(_) => break Err(error),
+            }
+            if attempts >= PHASE_ATTEMPTS {
+                break Err(error);
+            }
+        };
+        $phases.push(phase_record($name, started, attempts, &result));
+        result
+    }};
+}
+
+/// Load a MySQL or MariaDB database into the same graph the SQL Server
+/// loader builds. The database is the graph's only schema, so object IDs
+/// read `database.object`.
+pub async fn load_mysql_schema(
+    params: &ConnectionParams,
+    on_progress: impl Fn(f64, &str),
+) -> Result<SchemaGraph, SchemaError> {
+    on_progress(0.0, "Connecting");
+    let mut conn = create_mysql_conn(params).await?;
+    let database = params.database.as_str();
+    let mut interner = Interner::default();
+    let mut phases = Vec::new();
+
+    on_progress(0.1, "Loading tables");
+    let columns: Vec<ColumnRow> = run_phase!(
+        phases,
+        conn,
+        params,
+        "tables",
+        CORE_PHASE_TIMEOUT,
+        conn.exec(COLUMNS_QUERY, (database,))
+    )?;
+    on_progress(0.35, "Loading views");
+    let definitions: Vec<(String, Option<String>)> = run_phase!(
+        phases,
+        conn,
+        params,
+        "views",
+        CORE_PHASE_TIMEOUT,
+        conn.exec(VIEWS_QUERY, (database,))
+    )?;
+    let definitions: HashMap<String, String> = definitions
+        .into_iter()
+        .map(|(name, definition)| (name, definition.unwrap_or_default()))
+        .collect();
+    let (tables, mut views) =
+        build_tables_and_views(database, columns, &definitions, &mut interner);
+    let name_to_id = build_name_lookup(&tables, &views, database);
+    for view in views.iter_mut() {
+        let definition = bracket_identifiers(&view.definition);
+        view.referenced_tables = extract_table_references(&definition, &name_to_id).0;
+    }
+
+    on_progress(0.6, "Loading relationships");
+    let relationships = run_phase!(
+        phases,
+        conn,
+        params,
+        "relationships",
+        OPTIONAL_PHASE_TIMEOUT,
+        conn.exec(FOREIGN_KEYS_QUERY, (database,))
+    )
+    .map(|rows| build_foreign_keys(database, rows))
+    .unwrap_or_default();
+    on_progress(0.7, "Loading triggers");
+    let triggers = run_phase!(
+        phases,
+        conn,
+        params,
+        "triggers",
+        OPTIONAL_PHASE_TIMEOUT,
+        conn.exec(TRIGGERS_QUERY, (database,))
+    )
+    .map(|rows| build_triggers(database, rows, &name_to_id, &mut interner))
+    .unwrap_or_default();
+    on_progress(0.8, "Loading stored procedures");
+    let (stored_procedures, scalar_functions) = run_phase!(
+        phases,
+        conn,
+        params,
+        "routines",
+        OPTIONAL_PHASE_TIMEOUT,
+        conn.exec(ROUTINES_QUERY, (database,))
+    )
+    .map(|rows| build_routines(database, rows, &name_to_id, &mut interner))
+    .unwrap_or_default();
+    let _ = conn.disconnect().await;
+
+    let mut graph = SchemaGraph {
+        tables,
+        views,
+        relationships,
+        triggers,
+        stored_procedures,
+        scalar_functions,
+        load_phases: phases,
+        default_schema: Some(database.to_string()),
+        ..Default::default()
+    };
+    validate_graph(&mut graph);
+    annotate_participation(&mut graph);
+    annotate_watched_columns(&mut graph);
+    sort_graph(&mut graph);
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings<const N: usize>(values: [&str; N]) -> [String; N] {
+        values.map(str::to_string)
+    }
+
+    #[test]
+    fn server_names_and_quoted_identifiers() {
+        assert_eq!(parse_mysql_server("db01"), ("db01".to_string(), 3306));
+        assert_eq!(parse_mysql_server("db01:3307"), ("db01".to_string(), 3307));
+        assert_eq!(parse_mysql_server("db01, 3308"), ("db01".to_string(), 3308));
+
+        assert_eq!(
+            bracket_identifiers("select `a``b`.`c]` from `shop`.`orders` where x = '`lit`'"),
+            "select [a`b].[c]]] from [shop].[orders] where x = '`lit`'"
+        );
+    }
+
+    #[test]
+    fn catalog_rows_become_a_graph() {
+        let mut interner = Interner::default();
+        let column = |table: &str, name: &str, key: &str, position: u32, kind: &str| {
+            let extra = if key == "PRI" { "auto_increment" } else { "" };
+            let [table, name, column_type, nullable, key, kind, extra] =
+                strings([table, name, "int(11)", "NO", key, kind, extra]);
+            (
+                table,
+                name,
+                column_type,
+                nullable,
+                key,
+                position,
+                kind,
+                extra,
+            )
+        };
+        let rows = vec![
+            column("customers", "id", "PRI", 1, "BASE TABLE"),
+            column("orders", "id", "PRI", 1, "BASE TABLE"),
+            column("orders", "customer_id", "MUL", 2, "BASE TABLE"),
+            column("order_totals", "customer_id", "", 1, "VIEW"),
+        ];
+        let definitions = HashMap::from([(
+            "order_totals".to_string(),
+            "select `o`.`customer_id` from `shop`.`orders` `o`".to_string(),
+        )]);
+        let (tables, views) = build_tables_and_views("shop", rows, &definitions, &mut interner);
+        assert_eq!(tables.len(), 2);
+        assert_eq!(views[0].id, "shop.order_totals");
+        assert!(tables[1].columns[0].is_primary_key);
+        assert!(tables[1].columns[0].is_identity);
+        assert!(!tables[1].columns[1].is_identity);
+
+        let name_to_id = build_name_lookup(&tables, &views, "shop");
+        let definition = bracket_identifiers(&views[0].definition);
+        let (reads, _) = extract_table_references(&definition, &name_to_id);
+        assert_eq!(reads, vec!["shop.orders".to_string()]);
+
+        let keys = build_foreign_keys(
+            "shop",
+            vec![strings([
+                "fk_orders_customer",
+                "orders",
+                "customer_id",
+                "shop",
+                "customers",
+                "id",
+            ])
+            .into()],
+        );
+        assert_eq!(keys[0].from, "shop.orders");
+        assert_eq!(keys[0].to, "shop.customers");
+
+        let routine = |parameter: Option<&str>, mode: Option<&str>| {
+            (
+                "close_order".to_string(),
+                "PROCEDURE".to_string(),
+                None,
+                Some("UPDATE `orders` SET id = id WHERE id = p_id".to_string()),
+                parameter.map(str::to_string),
+                mode.map(str::to_string),
+                Some("int".to_string()),
+            )
+        };
+        let (procedures, functions) = build_routines(
+            "shop",
+            vec![
+                routine(Some("p_id"), Some("IN")),
+                routine(Some("p_done"), Some("OUT")),
+            ],
+            &name_to_id,
+            &mut interner,
+        );
+        assert!(functions.is_empty());
+        assert_eq!(procedures[0].parameters.len(), 2);
+        assert!(procedures[0].parameters[1].is_output);
+        assert_eq!(
+            procedures[0].affected_tables,
+            vec!["shop.orders".to_string()]
+        );
+    }
+}
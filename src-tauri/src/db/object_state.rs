@@ -0,0 +1,265 @@
+use chrono::NaiveDateTime;
+use futures_util::TryStreamExt;
+use std::collections::{HashMap, HashSet};
+use tiberius::Client;
+use tokio::net::TcpStream;
+use tokio_util::compat::Compat;
+
+use crate::db::{
+    object_id, split_multipart, SchemaError, COLUMN_INDEXES_QUERY, INDEX_LEADING_COLUMNS_QUERY,
+    OBJECT_METADATA_QUERY, OBJECT_STATE_QUERY, SCHEMA_FINGERPRINT_QUERY, SERVER_UTC_NOW_QUERY,
+};
+use crate::types::{ObjectMetadata, ObjectState, ObjectStateKind, SchemaGraph};
+
+fn parse_kind(kind: &str) -> Option<ObjectStateKind> {
+    match kind {
+        "trigger" => Some(ObjectStateKind::Trigger),
+        "foreignKey" => Some(ObjectStateKind::ForeignKey),
+        "checkConstraint" => Some(ObjectStateKind::CheckConstraint),
+        "index" => Some(ObjectStateKind::Index),
+        _ => None,
+    }
+}
+
+/// ID under which the schema loader reports the object, so the frontend can
+/// patch its graph in place.
+fn state_id(kind: ObjectStateKind, schema_name: &str, table_name: &str, name: &str) -> String {
+    match kind {
+        // Relationship edges are keyed by constraint name alone
+        ObjectStateKind::ForeignKey => name.to_string(),
+        _ => object_id(&[schema_name, table_name, name]),
+    }
+}
+
+/// Re-read trigger, constraint and index flags without reloading the schema.
+pub async fn load_object_states(
+    client: &mut Client<Compat<TcpStream>>,
+) -> Result<Vec<ObjectState>, SchemaError> {
+    let mut states = Vec::new();
+
+    let stream = client.query(OBJECT_STATE_QUERY, &[]).await?;
+    let mut row_stream = stream.into_row_stream();
+
+    while let Some(row) = row_stream.try_next().await? {
+        let Some(kind) = parse_kind(row.get(0).unwrap_or_default()) else {
+            continue;
+        };
+        let schema_name: &str = row.get(1).unwrap_or_default();
+        let table_name: &str = row.get(2).unwrap_or_default();
+        let name: &str = row.get(3).unwrap_or_default();
+
+        states.push(ObjectState {
+            kind,
+            id: state_id(kind, schema_name, table_name, name),
+            table_id: object_id(&[schema_name, table_name]),
+            name: name.to_string(),
+            is_disabled: row.get(4).unwrap_or_default(),
+            is_not_trusted: row.get(5).unwrap_or_default(),
+        });
+    }
+
+    Ok(states)
+}
+
+/// Leading index key columns, keyed by table ID.
+pub async fn load_index_leading_columns(
+    client: &mut Client<Compat<TcpStream>>,
+) -> Result<HashMap<String, HashSet<String>>, SchemaError> {
+    let mut columns: HashMap<String, HashSet<String>> = HashMap::new();
+
+    let stream = client.query(INDEX_LEADING_COLUMNS_QUERY, &[]).await?;
+    let mut row_stream = stream.into_row_stream();
+
+    while let Some(row) = row_stream.try_next().await? {
+        let schema_name: &str = row.get(0).unwrap_or_default();
+        let table_name: &str = row.get(1).unwrap_or_default();
+        let column_name: &str = row.get(2).unwrap_or_default();
+        columns
+            .entry(object_id(&[schema_name, table_name]))
+            .or_default()
+            .insert(column_name.to_string());
+    }
+
+    Ok(columns)
+}
+
+/// An index containing a given column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnIndex {
+    pub name: String,
+    pub is_primary_key: bool,
+    pub is_unique_constraint: bool,
+    /// In the `INCLUDE` list rather than the key.
+    pub is_included: bool,
+}
+
+/// Indexes, including the primary key, that contain `column` of `table_id`.
+pub async fn load_column_indexes(
+    client: &mut Client<Compat<TcpStream>>,
+    table_id: &str,
+    column: &str,
+) -> Result<Vec<ColumnIndex>, SchemaError> {
+    let parts = split_multipart(table_id);
+    let (schema_name, table_name) = match parts.as_slice() {
+        [schema_name, table_name] => (schema_name.as_str(), table_name.as_str()),
+        _ => ("dbo", table_id),
+    };
+    let mut indexes = Vec::new();
+
+    let stream = client
+        .query(COLUMN_INDEXES_QUERY, &[&schema_name, &table_name, &column])
+        .await?;
+    let mut row_stream = stream.into_row_stream();
+
+    while let Some(row) = row_stream.try_next().await? {
+        let name: &str = row.get(0).unwrap_or_default();
+        indexes.push(ColumnIndex {
+            name: name.to_string(),
+            is_primary_key: row.get(1).unwrap_or_default(),
+            is_unique_constraint: row.get(2).unwrap_or_default(),
+            is_included: row.get(3).unwrap_or_default(),
+        });
+    }
+
+    Ok(indexes)
+}
+
+/// Opaque value that changes when any user object is created, altered or
+/// dropped.
+pub async fn load_schema_fingerprint(
+    client: &mut Client<Compat<TcpStream>>,
+) -> Result<String, SchemaError> {
+    let row = client
+        .query(SCHEMA_FINGERPRINT_QUERY, &[])
+        .await?
+        .into_row()
+        .await?;
+    Ok(row
+        .as_ref()
+        .and_then(|r| r.get::<&str, _>(0))
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// Current server time in UTC.
+pub async fn load_server_utc_now(
+    client: &mut Client<Compat<TcpStream>>,
+) -> Result<Option<NaiveDateTime>, SchemaError> {
+    let row = client
+        .query(SERVER_UTC_NOW_QUERY, &[])
+        .await?
+        .into_row()
+        .await?;
+    Ok(row
+        .as_ref()
+        .and_then(|r| r.get::<&str, _>(0))
+        .and_then(|now| NaiveDateTime::parse_from_str(now, "%Y-%m-%dT%H:%M:%S%.f").ok()))
+}
+
+/// Owner and create/modify dates keyed by graph ID.
+pub async fn load_object_metadata(
+    client: &mut Client<Compat<TcpStream>>,
+) -> Result<HashMap<String, ObjectMetadata>, SchemaError> {
+    let mut metadata = HashMap::new();
+
+    let stream = client.query(OBJECT_METADATA_QUERY, &[]).await?;
+    let mut row_stream = stream.into_row_stream();
+
+    while let Some(row) = row_stream.try_next().await? {
+        let schema_name: &str = row.get(0).unwrap_or_default();
+        let parent_name: &str = row.get(1).unwrap_or_default();
+        let object_name: &str = row.get(2).unwrap_or_default();
+        let text = |index: usize| {
+            row.get::<&str, _>(index)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        let id = if parent_name.is_empty() {
+            object_id(&[schema_name, object_name])
+        } else {
+            object_id(&[schema_name, parent_name, object_name])
+        };
+        metadata.insert(
+            id,
+            ObjectMetadata {
+                owner: text(3),
+                created_at: text(4),
+                modified_at: text(5),
+            },
+        );
+    }
+
+    Ok(metadata)
+}
+
+/// Attach loaded metadata to the matching nodes of `graph`.
+pub fn apply_object_metadata(graph: &mut SchemaGraph, metadata: &HashMap<String, ObjectMetadata>) {
+    let nodes = graph
+        .tables
+        .iter_mut()
+        .map(|t| (&t.id, &mut t.metadata))
+        .chain(graph.views.iter_mut().map(|v| (&v.id, &mut v.metadata)))
+        .chain(graph.triggers.iter_mut().map(|t| (&t.id, &mut t.metadata)))
+        .chain(
+            graph
+                .stored_procedures
+                .iter_mut()
+                .map(|p| (&p.id, &mut p.metadata)),
+        )
+        .chain(
+            graph
+                .scalar_functions
+                .iter_mut()
+                .map(|f| (&f.id, &mut f.metadata)),
+        );
+    for (id, slot) in nodes {
+        if let Some(found) = metadata.get(id) {
+            *slot = Some(found.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_ids_match_loaded_graph_ids() {
+        assert_eq!(
+            state_id(ObjectStateKind::Trigger, "dbo", "Orders", "trg_audit"),
+            "dbo.Orders.trg_audit"
+        );
+        assert_eq!(
+            state_id(
+                ObjectStateKind::ForeignKey,
+                "dbo",
+                "Orders",
+                "FK_Orders_Customers"
+            ),
+            "FK_Orders_Customers"
+        );
+        assert_eq!(
+            parse_kind("checkConstraint"),
+            Some(ObjectStateKind::CheckConstraint)
+        );
+        assert_eq!(parse_kind("statistic"), None);
+    }
+
+    #[test]
+    fn metadata_is_attached_by_graph_id() {
+        let mut graph = crate::commands::mock::generate_mock_schema("small");
+        let table_id = graph.tables[0].id.clone();
+        let metadata = HashMap::from([(
+            table_id.clone(),
+            ObjectMetadata {
+                owner: Some("etl_owner".to_string()),
+                created_at: Some("2021-04-01T09:30:00".to_string()),
+                modified_at: Some("2024-11-12T17:05:41.123".to_string()),
+            },
+        )]);
+
+        apply_object_metadata(&mut graph, &metadata);
+        assert_eq!(graph.tables[0].metadata.as_ref(), metadata.get(&table_id));
+        assert!(graph.views.iter().all(|v| v.metadata.is_none()));
+    }
+}
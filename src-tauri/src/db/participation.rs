@@ -0,0 +1,147 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::types::{Optionality, Participation, SchemaGraph, TableNode};
+
+/// Participation of a foreign key from `child` over `columns`, or `None` if
+/// a column is unknown.
+pub fn constraint_participation(child: &TableNode, columns: &[&str]) -> Option<Participation> {
+    if columns.is_empty() {
+        return None;
+    }
+    let mut nullable = false;
+    for name in columns {
+        let column = child.columns.iter().find(|c| c.name == *name)?;
+        nullable |= column.is_nullable;
+    }
+    let primary_key: HashSet<&str> = child
+        .columns
+        .iter()
+        .filter(|c| c.is_primary_key)
+        .map(|c| c.name.as_str())
+        .collect();
+    let key_columns: HashSet<&str> = columns.iter().copied().collect();
+    Some(Participation {
+        parent: if nullable {
+            Optionality::Optional
+        } else {
+            Optionality::Mandatory
+        },
+        one_to_one: key_columns == primary_key,
+        identifying: key_columns.iter().any(|c| primary_key.contains(c)),
+    })
+}
+
+/// Set `participation` on every relationship edge whose columns are known.
+/// Edges of one multi-column constraint share the result.
+pub fn annotate_participation(graph: &mut SchemaGraph) {
+    let mut constraints: HashMap<(&str, &str, &str), Vec<&str>> = HashMap::new();
+    for edge in &graph.relationships {
+        let columns = constraints
+            .entry((&edge.id, &edge.from, &edge.to))
+            .or_default();
+        if let Some(column) = edge.from_column.as_deref() {
+            columns.push(column);
+        }
+    }
+
+    let tables: HashMap<&str, &TableNode> =
+        graph.tables.iter().map(|t| (t.id.as_str(), t)).collect();
+    let participation: HashMap<(String, String, String), Participation> = constraints
+        .into_iter()
+        .filter_map(|((id, from, to), columns)| {
+            let child = tables.get(from)?;
+            let participation = constraint_participation(child, &columns)?;
+            Some((
+                (id.to_string(), from.to_string(), to.to_string()),
+                participation,
+            ))
+        })
+        .collect();
+
+    for edge in &mut graph.relationships {
+        edge.participation = participation
+            .get(&(edge.id.clone(), edge.from.clone(), edge.to.clone()))
+            .copied();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Column, RelationshipEdge};
+
+    fn column(name: &str, nullable: bool, pk: bool) -> Column {
+        Column {
+            name: name.to_string(),
+            data_type: "int".into(),
+            is_nullable: nullable,
+            is_primary_key: pk,
+            ..Default::default()
+        }
+    }
+
+    fn edge(id: &str, from: &str, column: &str) -> RelationshipEdge {
+        RelationshipEdge {
+            id: id.to_string(),
+            from: from.to_string(),
+            to: "dbo.Parent".to_string(),
+            from_column: Some(column.to_string()),
+            to_column: Some("Id".to_string()),
+            source: None,
+            is_virtual: false,
+            participation: None,
+        }
+    }
+
+    #[test]
+    fn participation_follows_nullability_and_keys() {
+        let table = |name: &str, columns: Vec<Column>| TableNode {
+            id: format!("dbo.{}", name),
+            name: name.to_string(),
+            schema: "dbo".into(),
+            columns,
+            source: None,
+            metadata: None,
+            change_capture: None,
+        };
+        let mut graph = SchemaGraph {
+            tables: vec![
+                table(
+                    "Child",
+                    vec![
+                        column("Id", false, true),
+                        column("ParentId", false, false),
+                        column("BackupParentId", true, false),
+                    ],
+                ),
+                table("Profile", vec![column("ParentId", false, true)]),
+            ],
+            relationships: vec![
+                edge("FK_Child_Parent", "dbo.Child", "ParentId"),
+                edge("FK_Child_Backup", "dbo.Child", "BackupParentId"),
+                edge("FK_Profile_Parent", "dbo.Profile", "ParentId"),
+                edge("FK_Child_Gone", "dbo.Child", "Missing"),
+            ],
+            ..Default::default()
+        };
+
+        annotate_participation(&mut graph);
+        let participation: Vec<Option<(Optionality, bool, bool)>> = graph
+            .relationships
+            .iter()
+            .map(|e| {
+                e.participation
+                    .map(|p| (p.parent, p.one_to_one, p.identifying))
+            })
+            .collect();
+        assert_eq!(
+            participation,
+            vec![
+                Some((Optionality::Mandatory, false, false)),
+                Some((Optionality::Optional, false, false)),
+                Some((Optionality::Mandatory, true, true)),
+                None,
+            ]
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use futures_util::TryStreamExt;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tiberius::{Client, QueryItem, ToSql};
+use tokio::net::TcpStream;
+use tokio_util::compat::Compat;
+
+use crate::db::query_runner::{cell_value, check_parameter};
+use crate::db::{quote_name, QueryResult, SchemaError};
+use crate::i18n::t;
+use crate::types::StoredProcedure;
+
+/// Column holding the procedure's return code in the trailing output row.
+const RETURN_VALUE_COLUMN: &str = "@RETURN_VALUE";
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcedureRun {
+    /// Result sets the procedure returned, each cut off at `max_rows`.
+    pub result_sets: Vec<QueryResult>,
+    /// Final values of `OUTPUT` parameters, keyed by parameter name.
+    pub output_parameters: BTreeMap<String, Value>,
+    pub return_value: Value,
+    pub committed: bool,
+    /// The run's changes were rolled back. False when committed, and when the
+    /// procedure ended the run's transaction with its own `COMMIT` or
+    /// `ROLLBACK`, after which what it did was no longer undone.
+    pub rolled_back: bool,
+}
+
+/// Type for the variable holding a parameter's value. Catalog metadata has
+/// no lengths, and the type's default length would truncate, so variable
+/// length types get their widest form.
+fn variable_type(data_type: &str) -> String {
+    match data_type.to_ascii_lowercase().as_str() {
+        "nvarchar" | "nchar" => "nvarchar(max)".to_string(),
+        "varchar" | "char" => "varchar(max)".to_string(),
+        "varbinary" | "binary" => "varbinary(max)".to_string(),
+        "decimal" | "numeric" => "decimal(38, 10)".to_string(),
+        _ => data_type.to_string(),
+    }
+}
+
+/// The batch calling `procedure`, and the text values for its positional
+/// arguments. Inputs without a value are left out so their defaults apply;
+/// outputs are always passed and read back in a trailing row. `WITH
+/// RECOMPILE` keeps test values from being sniffed into the cached plan.
+fn exec_batch(
+    procedure: &StoredProcedure,
+    values: &BTreeMap<String, Option<String>>,
+) -> Result<(String, Vec<Option<String>>), String> {
+    let bare = |name: &str| name.trim_start_matches('@').to_string();
+    let values: BTreeMap<String, &Option<String>> = values
+        .iter()
+        .map(|(name, value)| (bare(name), value))
+        .collect();
+    if let Some(unknown) = values
+        .keys()
+        .find(|name| !procedure.parameters.iter().any(|p| &bare(&p.name) == *name))
+    {
+        return Err(t!("error-procedure-unknown-parameter", name = unknown));
+    }
+
+    let mut batch = String::from("DECLARE @__return int;\n");
+    let mut arguments = Vec::new();
+    let mut args = Vec::new();
+    let mut outputs = vec![format!("@__return AS {}", quote_name(RETURN_VALUE_COLUMN))];
+
+    for (i, parameter) in procedure.parameters.iter().enumerate() {
+        let name = bare(&parameter.name);
+        let value = values.get(&name);
+        if value.is_none() && !parameter.is_output {
+            continue;
+        }
+        let sql_type = variable_type(&parameter.data_type);
+        check_parameter(&name, &sql_type)?;
+
+        let variable = format!("@p{}", i + 1);
+        args.push(value.and_then(|v| (*v).clone()));
+        let argument = format!("@P{}", args.len());
+        // Text does not convert implicitly to binary; style 1 reads `0x...`
+        let initial = if sql_type == "varbinary(max)" {
+            format!("CONVERT(varbinary(max), {}, 1)", argument)
+        } else {
+            argument
+        };
+        batch.push_str(&format!(
+            "DECLARE {} {} = {};\n",
+            variable, sql_type, initial
+        ));
+
+        if parameter.is_output {
+            arguments.push(format!("@{} = {} OUTPUT", name, variable));
+            outputs.push(format!("{} AS {}", variable, quote_name(&name)));
+        } else {
+            arguments.push(format!("@{} = {}", name, variable));
+        }
+    }
+
+    batch.push_str(&format!(
+        "EXEC @__return = {}.{}",
+        quote_name(&procedure.schema),
+        quote_name(&procedure.name)
+    ));
+    if !arguments.is_empty() {
+        batch.push(' ');
+        batch.push_str(&arguments.join(", "));
+    }
+    batch.push_str(" WITH RECOMPILE;\n");
+    batch.push_str(&format!("SELECT {};\n", outputs.join(", ")));
+    Ok((batch, args))
+}
+
+async fn transaction_count(client: &mut Client<Compat<TcpStream>>) -> Result<i32, SchemaError> {
+    let row = client
+        .simple_query("SELECT @@TRANCOUNT;")
+        .await?
+        .into_row()
+        .await?;
+    Ok(row.and_then(|r| r.get::<i32, _>(0)).unwrap_or(0))
+}
+
+/// Execute `procedure` inside a transaction that is rolled back unless
+/// `commit` is set, returning its result sets, output parameters and
+/// return code. A procedure that commits or rolls back on its own ends that
+/// transaction early; the run reports it as not rolled back, or names it in
+/// the error. Side effects outside the transaction persist either way:
+/// sequence and identity values, linked server calls, `xp_*` procedures.
+pub async fn execute_procedure(
+    client: &mut Client<Compat<TcpStream>>,
+    procedure: &StoredProcedure,
+    values: &BTreeMap<String, Option<String>>,
+    commit: bool,
+    max_rows: usize,
+) -> Result<ProcedureRun, SchemaError> {
+    let (batch, args) = exec_batch(procedure, values).map_err(SchemaError::InvalidQuery)?;
+
+    client
+        .simple_query("BEGIN TRANSACTION;")
+        .await?
+        .into_results()
+        .await?;
+    let opened = transaction_count(client).await?;
+
+    let result = async {
+        let args: Vec<&dyn ToSql> = args.iter().map(|a| a as &dyn ToSql).collect();
+        let mut stream = client.query(batch, &args).await?;
+        let mut sets: Vec<QueryResult> = Vec::new();
+        while let Some(item) = stream.try_next().await? {
+            match item {
+                QueryItem::Metadata(meta) => sets.push(QueryResult {
+                    columns: meta
+                        .columns()
+                        .iter()
+                        .map(|c| c.name().to_string())
+                        .collect(),
+                    ..Default::default()
+                }),
+                QueryItem::Row(row) => {
+                    let Some(set) = sets.last_mut() else { continue };
+                    if set.rows.len() == max_rows {
+                        set.truncated = true;
+                    } else {
+                        set.rows.push(row.into_iter().map(cell_value).collect());
+                    }
+                }
+            }
+        }
+        Ok::<_, SchemaError>(sets)
+    }
+    .await;
+
+    // Below the count the run opened, the procedure ended its transaction
+    let ended_early = transaction_count(client).await? < opened;
+
+    // Roll back on failure even when asked to commit
+    let committed = commit && result.is_ok();
+    let end = if committed {
+        "IF @@TRANCOUNT > 0 COMMIT TRANSACTION;"
+    } else {
+        "IF @@TRANCOUNT > 0 ROLLBACK TRANSACTION;"
+    };
+    client.simple_query(end).await?.into_results().await?;
+
+    let mut result_sets = match result {
+        Err(e) if ended_early => {
+            return Err(SchemaError::InvalidQuery(t!(
+                "error-procedure-ended-transaction",
+                error = e.to_string()
+            )))
+        }
+        result => result?,
+    };
+    let mut run = ProcedureRun {
+        committed,
+        rolled_back: !committed && !ended_early,
+        ..Default::default()
+    };
+    // The trailing row written by `exec_batch`
+    if let Some(outputs) = result_sets.pop() {
+        let row = outputs.rows.into_iter().next().unwrap_or_default();
+        for (column, value) in outputs.columns.into_iter().zip(row) {
+            if column == RETURN_VALUE_COLUMN {
+                run.return_value = value;
+            } else {
+                run.output_parameters.insert(format!("@{}", column), value);
+            }
+        }
+    }
+    run.result_sets = result_sets;
+    Ok(run)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ProcedureParameter;
+
+    fn parameter(name: &str, data_type: &str, is_output: bool) -> ProcedureParameter {
+        ProcedureParameter {
+            name: name.to_string(),
+            data_type: data_type.into(),
+            is_output,
+        }
+    }
+
+    #[test]
+    fn exec_batch_binds_inputs_and_reads_back_outputs() {
+        let procedure = StoredProcedure {
+            id: "sales.usp_PlaceOrder".to_string(),
+            name: "usp_PlaceOrder".to_string(),
+            schema: "sales".into(),
+            procedure_type: "SQL_STORED_PROCEDURE".into(),
+            parameters: vec![
+                parameter("@CustomerId", "int", false),
+                parameter("@Note", "nvarchar", false),
+                parameter("@Payload", "varbinary", false),
+                parameter("@OrderId", "int", true),
+            ],
+            definition: String::new(),
+            referenced_tables: Vec::new(),
+            affected_tables: Vec::new(),
+            analysis: Default::default(),
+            source: None,
+            metadata: None,
+        };
+        let values = BTreeMap::from([
+            ("@CustomerId".to_string(), Some("42".to_string())),
+            ("Payload".to_string(), Some("0x0A0B".to_string())),
+        ]);
+
+        let (batch, args) = exec_batch(&procedure, &values).unwrap();
+        assert_eq!(
+            batch,
+            "DECLARE @__return int;\n\
+             DECLARE @p1 int = @P1;\n\
+             DECLARE @p3 varbinary(max) = CONVERT(varbinary(max), @P2, 1);\n\
+             DECLARE @p4 int = @P3;\n\
+             EXEC @__return = [sales].[usp_PlaceOrder] @CustomerId = @p1, @Payload = @p3, @OrderId = @p4 OUTPUT WITH RECOMPILE;\n\
+             SELECT @__return AS [@RETURN_VALUE], @p4 AS [OrderId];\n"
+        );
+        assert_eq!(
+            args,
+            vec![Some("42".to_string()), Some("0x0A0B".to_string()), None]
+        );
+
+        let typo = BTreeMap::from([("@CustomerID2".to_string(), None)]);
+        assert!(exec_batch(&procedure, &typo).is_err());
+    }
+}
@@ -7,6 +7,36 @@ WHERE state_desc = 'ONLINE'
 ORDER BY name
 "#;
 
+/// `LIST_DATABASES_QUERY` narrowed by a `LIKE` pattern (`@P1`).
+pub const LIST_DATABASES_MATCHING_QUERY: &str = r#"
+SELECT name
+FROM sys.databases
+WHERE state_desc = 'ONLINE'
+  AND database_id > 4
+  AND HAS_DBACCESS(name) = 1
+  AND name LIKE @P1
+ORDER BY name
+"#;
+
+/// Every user database with its state and file size, including ones that are
+/// offline or that the login cannot open, so the picker can mark them.
+pub const DATABASE_DETAILS_QUERY: &str = r#"
+SELECT
+    d.name,
+    d.state_desc,
+    CAST(CASE WHEN d.state_desc = 'ONLINE' AND HAS_DBACCESS(d.name) = 1
+        THEN 1 ELSE 0 END AS bit) AS accessible,
+    CAST(f.size_pages * 8 / 1024.0 AS float) AS size_mb
+FROM sys.databases d
+LEFT JOIN (
+    SELECT database_id, SUM(CAST(size AS bigint)) AS size_pages
+    FROM sys.master_files
+    GROUP BY database_id
+) f ON f.database_id = d.database_id
+WHERE d.database_id > 4
+ORDER BY d.name
+"#;
+
 pub const TABLES_AND_COLUMNS_QUERY: &str = r#"
 SELECT
     s.name AS schema_name,
@@ -17,7 +47,13 @@ SELECT
     c.precision,
     c.scale,
     c.is_nullable,
-    CASE WHEN pk.column_id IS NOT NULL THEN 1 ELSE 0 END AS is_primary_key
+    CASE WHEN pk.column_id IS NOT NULL THEN 1 ELSE 0 END AS is_primary_key,
+    c.column_id,
+    t.is_tracked_by_cdc,
+    t.is_replicated,
+    t.is_merge_published,
+    c.is_identity,
+    c.is_computed
 FROM sys.tables t
 JOIN sys.schemas s ON t.schema_id = s.schema_id
 JOIN sys.columns c ON t.object_id = c.object_id
@@ -59,6 +95,7 @@ JOIN sys.schemas sch_ref
 JOIN sys.columns c_ref
   ON fkc.referenced_object_id = c_ref.object_id
  AND fkc.referenced_column_id = c_ref.column_id
+ORDER BY sch_src.name, t_src.name, fk.name, fkc.constraint_column_id
 "#;
 
 pub const TRIGGERS_QUERY: &str = r#"
@@ -106,7 +143,11 @@ SELECT
     c.precision,
     c.scale,
     c.is_nullable,
-    ISNULL(OBJECT_DEFINITION(v.object_id), '') AS view_definition
+    ISNULL(OBJECT_DEFINITION(v.object_id), '') AS view_definition,
+    c.column_id,
+    CAST(CASE WHEN EXISTS (
+        SELECT 1 FROM sys.indexes i WHERE i.object_id = v.object_id AND i.type = 1
+    ) THEN 1 ELSE 0 END AS bit) AS is_indexed
 FROM sys.views v
 JOIN sys.schemas s ON v.schema_id = s.schema_id
 JOIN sys.columns c ON v.object_id = c.object_id
@@ -161,6 +202,243 @@ WHERE o.type = 'FN'
 ORDER BY s.name, o.name, p.parameter_id
 "#;
 
+/// Full definition length per module, used to detect row-set truncation.
+/// `definition` is nvarchar, so bytes / 2 is the length in UTF-16 code units.
+pub const DEFINITION_LENGTHS_QUERY: &str = r#"
+SELECT
+    s.name AS schema_name,
+    o.name AS object_name,
+    CAST(DATALENGTH(sm.definition) / 2 AS BIGINT) AS definition_length
+FROM sys.sql_modules sm
+JOIN sys.objects o ON sm.object_id = o.object_id
+JOIN sys.schemas s ON o.schema_id = s.schema_id
+WHERE o.is_ms_shipped = 0
+  AND sm.definition IS NOT NULL
+"#;
+
+/// One slice of a module definition. Parameters: schema, object name,
+/// 1-based start offset and slice length (in characters).
+pub const DEFINITION_CHUNK_QUERY: &str = r#"
+SELECT ISNULL(SUBSTRING(sm.definition, @P3, @P4), '') AS chunk
+FROM sys.sql_modules sm
+WHERE sm.object_id = OBJECT_ID(QUOTENAME(@P1) + N'.' + QUOTENAME(@P2))
+"#;
+
+pub const DEFAULT_SCHEMA_QUERY: &str = "SELECT SCHEMA_NAME() AS default_schema";
+
+/// Volatile enable/trust flags for triggers, constraints and indexes, cheap
+/// enough to poll so that toggles made elsewhere show up without a reload.
+pub const OBJECT_STATE_QUERY: &str = r#"
+SELECT 'trigger' AS kind, s.name AS schema_name, t.name AS table_name, tr.name AS object_name,
+       tr.is_disabled, CAST(0 AS bit) AS is_not_trusted
+FROM sys.triggers tr
+JOIN sys.tables t ON tr.parent_id = t.object_id
+JOIN sys.schemas s ON t.schema_id = s.schema_id
+WHERE t.is_ms_shipped = 0
+UNION ALL
+SELECT 'foreignKey', s.name, t.name, fk.name, fk.is_disabled, fk.is_not_trusted
+FROM sys.foreign_keys fk
+JOIN sys.tables t ON fk.parent_object_id = t.object_id
+JOIN sys.schemas s ON t.schema_id = s.schema_id
+WHERE t.is_ms_shipped = 0
+UNION ALL
+SELECT 'checkConstraint', s.name, t.name, cc.name, cc.is_disabled, cc.is_not_trusted
+FROM sys.check_constraints cc
+JOIN sys.tables t ON cc.parent_object_id = t.object_id
+JOIN sys.schemas s ON t.schema_id = s.schema_id
+WHERE t.is_ms_shipped = 0
+UNION ALL
+SELECT 'index', s.name, t.name, i.name, i.is_disabled, CAST(0 AS bit)
+FROM sys.indexes i
+JOIN sys.tables t ON i.object_id = t.object_id
+JOIN sys.schemas s ON t.schema_id = s.schema_id
+WHERE t.is_ms_shipped = 0
+  AND i.name IS NOT NULL
+ORDER BY schema_name, table_name, object_name
+"#;
+
+/// First key column of every enabled index, i.e. the columns a lookup can
+/// seek on.
+pub const INDEX_LEADING_COLUMNS_QUERY: &str = r#"
+SELECT s.name AS schema_name, t.name AS table_name, c.name AS column_name
+FROM sys.index_columns ic
+JOIN sys.indexes i ON ic.object_id = i.object_id AND ic.index_id = i.index_id
+JOIN sys.columns c ON ic.object_id = c.object_id AND ic.column_id = c.column_id
+JOIN sys.tables t ON ic.object_id = t.object_id
+JOIN sys.schemas s ON t.schema_id = s.schema_id
+WHERE ic.key_ordinal = 1
+  AND i.is_disabled = 0
+  AND t.is_ms_shipped = 0
+"#;
+
+/// Indexes with `@P3` of `@P1`.`@P2` as a key or included column.
+pub const COLUMN_INDEXES_QUERY: &str = r#"
+SELECT i.name AS index_name, i.is_primary_key, i.is_unique_constraint, ic.is_included_column
+FROM sys.index_columns ic
+JOIN sys.indexes i ON ic.object_id = i.object_id AND ic.index_id = i.index_id
+JOIN sys.columns c ON ic.object_id = c.object_id AND ic.column_id = c.column_id
+JOIN sys.tables t ON ic.object_id = t.object_id
+JOIN sys.schemas s ON t.schema_id = s.schema_id
+WHERE s.name = @P1 AND t.name = @P2 AND c.name = @P3
+  AND i.name IS NOT NULL
+ORDER BY i.index_id
+"#;
+
+/// Enabled check constraints of user tables.
+pub const CHECK_CONSTRAINTS_QUERY: &str = r#"
+SELECT s.name AS schema_name, t.name AS table_name, cc.name AS constraint_name, cc.definition
+FROM sys.check_constraints cc
+JOIN sys.tables t ON cc.parent_object_id = t.object_id
+JOIN sys.schemas s ON t.schema_id = s.schema_id
+WHERE t.is_ms_shipped = 0
+  AND cc.is_disabled = 0
+"#;
+
+/// Column defaults of user tables.
+pub const DEFAULT_CONSTRAINTS_QUERY: &str = r#"
+SELECT s.name AS schema_name, t.name AS table_name, c.name AS column_name,
+       dc.name AS constraint_name, dc.definition
+FROM sys.default_constraints dc
+JOIN sys.columns c ON dc.parent_object_id = c.object_id AND dc.parent_column_id = c.column_id
+JOIN sys.tables t ON dc.parent_object_id = t.object_id
+JOIN sys.schemas s ON t.schema_id = s.schema_id
+WHERE t.is_ms_shipped = 0
+"#;
+
+/// Per-table row counts from partition metadata: instant, and exact enough to
+/// compare environments without scanning any table.
+pub const ROW_COUNTS_QUERY: &str = r#"
+SELECT s.name AS schema_name, t.name AS table_name, SUM(p.rows) AS row_count
+FROM sys.tables t
+JOIN sys.schemas s ON t.schema_id = s.schema_id
+JOIN sys.partitions p ON t.object_id = p.object_id AND p.index_id IN (0, 1)
+WHERE t.is_ms_shipped = 0
+GROUP BY s.name, t.name
+"#;
+
+pub const PRIMARY_KEY_COLUMNS_QUERY: &str = r#"
+SELECT s.name AS schema_name, t.name AS table_name, c.name AS column_name
+FROM sys.indexes i
+JOIN sys.index_columns ic ON i.object_id = ic.object_id AND i.index_id = ic.index_id
+JOIN sys.columns c ON ic.object_id = c.object_id AND ic.column_id = c.column_id
+JOIN sys.tables t ON i.object_id = t.object_id
+JOIN sys.schemas s ON t.schema_id = s.schema_id
+WHERE i.is_primary_key = 1
+  AND t.is_ms_shipped = 0
+ORDER BY s.name, t.name, ic.key_ordinal
+"#;
+
+/// Cheap summary that changes whenever an object is created, altered or
+/// dropped; polled by watch mode instead of reloading the whole schema.
+pub const SCHEMA_FINGERPRINT_QUERY: &str = r#"
+SELECT CAST(COUNT(*) AS varchar(12)) + '|' +
+       ISNULL(CONVERT(varchar(33), MAX(modify_date), 126), '') AS fingerprint
+FROM sys.objects
+WHERE is_ms_shipped = 0
+"#;
+
+/// The server's clock in UTC, so ages are measured against the server
+/// rather than the machine running the app.
+pub const SERVER_UTC_NOW_QUERY: &str = "SELECT CONVERT(varchar(27), SYSUTCDATETIME(), 126)";
+
+/// Owner and create/modify dates of every user object. Objects without an
+/// explicit owner belong to their schema's owner. Triggers are reported
+/// under their table, matching trigger IDs in the graph. Modify dates are
+/// shifted from server-local time to UTC by the server's current offset.
+pub const OBJECT_METADATA_QUERY: &str = r#"
+SELECT
+    s.name AS schema_name,
+    ISNULL(parent.name, '') AS parent_name,
+    o.name AS object_name,
+    ISNULL(USER_NAME(COALESCE(o.principal_id, s.principal_id)), '') AS owner_name,
+    CONVERT(varchar(23), o.create_date, 126) AS create_date,
+    CONVERT(
+        varchar(23),
+        DATEADD(minute, DATEDIFF(minute, SYSDATETIME(), SYSUTCDATETIME()), o.modify_date),
+        126
+    ) AS modify_date
+FROM sys.objects o
+JOIN sys.schemas s ON o.schema_id = s.schema_id
+LEFT JOIN sys.objects parent ON o.type = 'TR' AND o.parent_object_id = parent.object_id
+WHERE o.is_ms_shipped = 0
+  AND o.type IN ('U', 'V', 'P', 'FN', 'TR')
+"#;
+
+/// Flyway (`flyway_schema_history`, or `schema_version` before Flyway 5),
+/// Liquibase (`DATABASECHANGELOG`) and EF Core (`__EFMigrationsHistory`)
+/// history tables, in that order of preference.
+pub const MIGRATION_TABLES_QUERY: &str = r#"
+SELECT s.name AS schema_name, t.name AS table_name
+FROM sys.tables t
+JOIN sys.schemas s ON t.schema_id = s.schema_id
+WHERE t.name IN ('flyway_schema_history', 'schema_version', 'DATABASECHANGELOG', '__EFMigrationsHistory')
+  AND (t.name <> 'schema_version'
+       OR COL_LENGTH(QUOTENAME(s.name) + '.' + QUOTENAME(t.name), 'installed_rank') IS NOT NULL)
+ORDER BY CASE t.name
+             WHEN 'flyway_schema_history' THEN 0
+             WHEN 'schema_version' THEN 1
+             WHEN 'DATABASECHANGELOG' THEN 2
+             ELSE 3
+         END,
+         s.name
+"#;
+
+pub const AGENT_JOB_STEPS_QUERY: &str = r#"
+SELECT
+    j.name AS job_name,
+    CAST(j.enabled AS bit) AS job_enabled,
+    ISNULL(j.description, '') AS job_description,
+    ISNULL(SUSER_SNAME(j.owner_sid), '') AS owner_name,
+    s.step_id,
+    s.step_name,
+    s.subsystem,
+    ISNULL(s.database_name, '') AS database_name,
+    ISNULL(s.command, '') AS command
+FROM msdb.dbo.sysjobs j
+JOIN msdb.dbo.sysjobsteps s ON s.job_id = j.job_id
+ORDER BY j.name, s.step_id
+"#;
+
+pub const AGENT_JOB_SCHEDULES_QUERY: &str = r#"
+SELECT
+    j.name AS job_name,
+    sc.name AS schedule_name,
+    CAST(sc.enabled AS bit) AS schedule_enabled,
+    sc.freq_type,
+    sc.freq_interval,
+    sc.freq_subday_type,
+    sc.freq_subday_interval,
+    sc.active_start_time
+FROM msdb.dbo.sysjobs j
+JOIN msdb.dbo.sysjobschedules js ON js.job_id = j.job_id
+JOIN msdb.dbo.sysschedules sc ON sc.schedule_id = js.schedule_id
+ORDER BY j.name, sc.name
+"#;
+
+/// One row per replica of the availability group the current database
+/// belongs to; no rows when it is not in one. Replica state is only visible
+/// for the local replica when connected to a secondary.
+pub const AVAILABILITY_REPLICAS_QUERY: &str = r#"
+SELECT
+    ag.name AS group_name,
+    ar.replica_server_name,
+    rs.role_desc,
+    CAST(ISNULL(rs.is_local, 0) AS bit) AS is_local,
+    rs.synchronization_health_desc,
+    ar.availability_mode_desc,
+    ar.secondary_role_allow_connections_desc,
+    CAST(@@SERVERNAME AS nvarchar(256)) AS connected_server,
+    CAST(CASE WHEN CAST(DATABASEPROPERTYEX(DB_NAME(), 'Updateability') AS nvarchar(16)) = 'READ_WRITE'
+        THEN 1 ELSE 0 END AS bit) AS database_updateable
+FROM sys.dm_hadr_database_replica_states drs
+JOIN sys.availability_groups ag ON ag.group_id = drs.group_id
+JOIN sys.availability_replicas ar ON ar.group_id = ag.group_id
+LEFT JOIN sys.dm_hadr_availability_replica_states rs ON rs.replica_id = ar.replica_id
+WHERE drs.database_id = DB_ID()
+  AND drs.is_local = 1
+ORDER BY ar.replica_server_name
+"#;
+
 pub fn format_data_type(
     type_name: &str,
     max_length: i16,
@@ -193,8 +471,9 @@ pub fn format_data_type(
                 "float".to_string()
             }
         }
+        // Scale 0 is meaningful here; only the default of 7 is left implicit
         "datetime2" | "datetimeoffset" | "time" => {
-            if scale > 0 && scale != 7 {
+            if scale != 7 {
                 format!("{}({})", type_name, scale)
             } else {
                 type_name.to_string()
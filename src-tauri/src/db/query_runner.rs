@@ -0,0 +1,527 @@
+use chrono::{Duration, NaiveDate};
+use futures_util::TryStreamExt;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::future::Future;
+use tiberius::{Client, ColumnData, QueryItem, ToSql};
+use tokio::net::TcpStream;
+use tokio_util::compat::Compat;
+
+use crate::db::SchemaError;
+use crate::i18n::t;
+
+/// Rows returned before a result is cut off and marked truncated.
+pub const DEFAULT_MAX_QUERY_ROWS: usize = 5_000;
+
+const DEFAULT_PARAMETER_TYPE: &str = "nvarchar(4000)";
+
+/// Keywords that can change data, schema, permissions or session state, or
+/// reach outside the database. Any of them outside a string, comment or
+/// quoted identifier rejects the query.
+const FORBIDDEN_KEYWORDS: &str =
+    "ALTER BACKUP BEGIN BULK COMMIT CREATE DBCC DELETE DENY DROP EXEC \
+     EXECUTE GRANT INSERT INTO KILL MERGE OPENDATASOURCE OPENQUERY OPENROWSET RECONFIGURE RESTORE \
+     REVOKE ROLLBACK SAVE SET SHUTDOWN TRUNCATE UPDATE USE WAITFOR";
+
+static PARAMETER_NAME: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").expect("valid regex"));
+static PARAMETER_TYPE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?i)[a-z][a-z0-9]*(\s*\(\s*(\d+|max)\s*(,\s*\d+\s*)?\))?$").expect("valid regex")
+});
+
+/// A named value the query refers to as `@name`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryParameter {
+    pub name: String,
+    /// SQL Server type the value is converted to; `nvarchar(4000)` if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sql_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<String>,
+}
+
+/// Per-connection caps on ad-hoc and saved queries, set by whoever hands
+/// out the profile. Unset fields leave the caller's own limits in place.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryLimits {
+    /// Upper bound on rows returned to a preview; exports are not affected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_preview_rows: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_seconds: Option<u64>,
+    /// Most statements one batch may contain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_statements: Option<usize>,
+}
+
+impl QueryLimits {
+    /// Rows to return for a preview that asked for `requested`.
+    pub fn preview_rows(&self, requested: Option<usize>) -> usize {
+        let requested = requested.unwrap_or(DEFAULT_MAX_QUERY_ROWS);
+        self.max_preview_rows
+            .map_or(requested, |max| requested.min(max))
+    }
+
+    /// Await `query`, giving up after the timeout. The abandoned request
+    /// leaves the connection unusable, and closing it rolls back any open
+    /// transaction.
+    pub async fn run<T>(
+        &self,
+        query: impl Future<Output = Result<T, SchemaError>>,
+    ) -> Result<Result<T, SchemaError>, SchemaError> {
+        match self.timeout_seconds {
+            Some(seconds) => tokio::time::timeout(std::time::Duration::from_secs(seconds), query)
+                .await
+                .map_err(|_| {
+                    SchemaError::InvalidQuery(t!("error-query-timeout", seconds = seconds))
+                }),
+            None => Ok(query.await),
+        }
+    }
+}
+
+/// First result set of a query, with every cell as JSON.
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    pub truncated: bool,
+}
+
+/// Upper-cased keywords of `sql`, skipping comments, string literals,
+/// quoted identifiers and variables.
+fn keywords(sql: &str) -> Vec<String> {
+    tokens(sql)
+        .into_iter()
+        .filter(|t| !matches!(t.as_str(), "(" | ")" | ";"))
+        .collect()
+}
+
+/// Keywords as [`keywords`] finds them, plus the parentheses and semicolons
+/// that delimit statements.
+fn tokens(sql: &str) -> Vec<String> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut words = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            '-' if next == Some('-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if next == Some('*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            '\'' | '"' | '[' => {
+                let close = if c == '[' { ']' } else { c };
+                i += 1;
+                while i < chars.len() {
+                    if chars[i] == close {
+                        // A doubled delimiter is an escaped one
+                        if chars.get(i + 1) == Some(&close) {
+                            i += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            c if c.is_alphabetic() || c == '_' || c == '@' || c == '#' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '@' | '#' | '$'))
+                {
+                    i += 1;
+                }
+                if c != '@' {
+                    words.push(chars[start..i].iter().collect::<String>().to_uppercase());
+                }
+            }
+            '(' | ')' | ';' => {
+                words.push(c.to_string());
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    words
+}
+
+/// Accept only queries that read: they start with `SELECT` or `WITH` and use
+/// none of the forbidden keywords. `NEXT VALUE FOR` is refused too: it
+/// advances a sequence, which rolling back doesn't undo.
+pub fn check_read_only(sql: &str) -> Result<(), String> {
+    let words = keywords(sql);
+    if !matches!(words.first().map(String::as_str), Some("SELECT" | "WITH")) {
+        return Err(t!("error-query-not-select"));
+    }
+    if words.windows(3).any(|w| w == ["NEXT", "VALUE", "FOR"]) {
+        return Err(t!(
+            "error-query-forbidden-keyword",
+            keyword = "NEXT VALUE FOR"
+        ));
+    }
+    match words.iter().find(|w| {
+        FORBIDDEN_KEYWORDS
+            .split_whitespace()
+            .any(|k| k == w.as_str())
+    }) {
+        Some(keyword) => Err(t!("error-query-forbidden-keyword", keyword = keyword)),
+        None => Ok(()),
+    }
+}
+
+/// Statements in a read-only batch. T-SQL doesn't need semicolons, so a new
+/// statement also starts at each top-level `SELECT` or `WITH` that doesn't
+/// continue a set operation, a common table expression, or a table hint.
+pub fn statement_count(sql: &str) -> usize {
+    let tokens = tokens(sql);
+    let mut count = 0;
+    let mut depth = 0usize;
+    let mut previous: Option<&str> = None;
+    // A `WITH` statement's main query is still to come
+    let mut open_cte = false;
+    for (i, token) in tokens.iter().enumerate() {
+        match token.as_str() {
+            "(" => depth += 1,
+            ")" => depth = depth.saturating_sub(1),
+            ";" if depth == 0 => {
+                previous = None;
+                open_cte = false;
+            }
+            _ if depth > 0 => {}
+            word => {
+                let continues = matches!(previous, Some("UNION" | "ALL" | "EXCEPT" | "INTERSECT"));
+                match word {
+                    "SELECT" if open_cte => open_cte = false,
+                    "SELECT" if !continues => count += 1,
+                    "WITH" if !continues => {
+                        let next = tokens.get(i + 1).map(String::as_str);
+                        if !matches!(next, Some("(" | "TIES")) {
+                            count += 1;
+                            open_cte = true;
+                        }
+                    }
+                    _ => {}
+                }
+                previous = Some(word);
+            }
+        }
+    }
+    count
+}
+
+/// Parameter names and types are spliced into `DECLARE` statements, so
+/// they must be plain identifiers and type names.
+pub(crate) fn check_parameter(name: &str, sql_type: &str) -> Result<(), String> {
+    if !PARAMETER_NAME.is_match(name) {
+        return Err(t!("error-query-parameter-name", name = name));
+    }
+    if !PARAMETER_TYPE.is_match(sql_type) {
+        return Err(t!("error-query-parameter-type", name = name));
+    }
+    Ok(())
+}
+
+/// `DECLARE` lines binding each parameter to a positional argument, and the
+/// argument values: supplied, else the parameter default, else `NULL`.
+fn bind_parameters(
+    parameters: &[QueryParameter],
+    values: &BTreeMap<String, String>,
+) -> Result<(String, Vec<Option<String>>), String> {
+    let mut preamble = String::new();
+    let mut args = Vec::new();
+    for (i, parameter) in parameters.iter().enumerate() {
+        let sql_type = parameter
+            .sql_type
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .unwrap_or(DEFAULT_PARAMETER_TYPE);
+        check_parameter(&parameter.name, sql_type)?;
+        preamble.push_str(&format!(
+            "DECLARE @{} {} = @P{};\n",
+            parameter.name,
+            sql_type,
+            i + 1
+        ));
+        args.push(
+            values
+                .get(&parameter.name)
+                .or(parameter.default_value.as_ref())
+                .cloned(),
+        );
+    }
+    Ok((preamble, args))
+}
+
+/// `datetime` and `smalldatetime` count days from 1900-01-01.
+fn datetime_text(days: i64, milliseconds: i64) -> Value {
+    let base = NaiveDate::from_ymd_opt(1900, 1, 1)
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .expect("valid date");
+    let value = base + Duration::days(days) + Duration::milliseconds(milliseconds);
+    Value::String(value.format("%Y-%m-%dT%H:%M:%S%.3f").to_string())
+}
+
+pub(crate) fn cell_value(data: ColumnData<'_>) -> Value {
+    match data {
+        ColumnData::U8(v) => v.into(),
+        ColumnData::I16(v) => v.into(),
+        ColumnData::I32(v) => v.into(),
+        ColumnData::I64(v) => v.into(),
+        ColumnData::F32(v) => v.into(),
+        ColumnData::F64(v) => v.into(),
+        ColumnData::Bit(v) => v.into(),
+        ColumnData::String(v) => v.map(|s| s.into_owned()).into(),
+        ColumnData::Guid(v) => v.map(|g| g.to_string()).into(),
+        ColumnData::Binary(v) => v
+            .map(|bytes| {
+                let hex: String = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+                format!("0x{}", hex)
+            })
+            .into(),
+        // Kept as text so no precision is lost in JavaScript
+        ColumnData::Numeric(v) => v.map(|n| n.to_string()).into(),
+        ColumnData::Xml(v) => v.map(|x| x.to_string()).into(),
+        ColumnData::DateTime(v) => v.map_or(Value::Null, |d| {
+            // Fractions are 1/300 of a second
+            datetime_text(d.days() as i64, d.seconds_fragments() as i64 * 10 / 3)
+        }),
+        ColumnData::SmallDateTime(v) => v.map_or(Value::Null, |d| {
+            datetime_text(d.days() as i64, d.seconds_fragments() as i64 * 60_000)
+        }),
+    }
+}
+
+/// Receives a query's columns, then its rows one at a time, so large
+/// results can go straight to a file.
+pub trait RowSink: Send {
+    fn columns(&mut self, columns: Vec<String>) -> Result<(), String>;
+    fn row(&mut self, row: Vec<Value>) -> Result<(), String>;
+}
+
+impl RowSink for QueryResult {
+    fn columns(&mut self, columns: Vec<String>) -> Result<(), String> {
+        self.columns = columns;
+        Ok(())
+    }
+
+    fn row(&mut self, row: Vec<Value>) -> Result<(), String> {
+        self.rows.push(row);
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RowsStreamed {
+    pub rows: usize,
+    /// More rows were available than `max_rows`.
+    pub truncated: bool,
+}
+
+/// Run `sql` after checking it only reads, passing the first result set to
+/// `sink`. It runs inside a transaction that is always rolled back, and with
+/// a `max_rows` limit `SET ROWCOUNT` bounds how much the server sends.
+/// `limits` caps the statement count and how long the query may run.
+pub async fn stream_read_only_query(
+    client: &mut Client<Compat<TcpStream>>,
+    sql: &str,
+    parameters: &[QueryParameter],
+    values: &BTreeMap<String, String>,
+    max_rows: Option<usize>,
+    limits: &QueryLimits,
+    sink: &mut dyn RowSink,
+) -> Result<RowsStreamed, SchemaError> {
+    check_read_only(sql).map_err(SchemaError::InvalidQuery)?;
+    if let Some(max) = limits.max_statements {
+        let count = statement_count(sql);
+        if count > max {
+            return Err(SchemaError::InvalidQuery(t!(
+                "error-query-statement-cap",
+                count = count,
+                max = max
+            )));
+        }
+    }
+    let (preamble, args) =
+        bind_parameters(parameters, values).map_err(SchemaError::InvalidQuery)?;
+
+    // One extra row tells a full result from a truncated one
+    let rowcount = max_rows.map_or(0, |max| max + 1);
+    client
+        .simple_query(format!("SET ROWCOUNT {}; BEGIN TRANSACTION;", rowcount))
+        .await?
+        .into_results()
+        .await?;
+
+    let run = async {
+        let args: Vec<&dyn ToSql> = args.iter().map(|a| a as &dyn ToSql).collect();
+        let mut stream = client.query(format!("{}{}", preamble, sql), &args).await?;
+        let mut streamed = RowsStreamed::default();
+        while let Some(item) = stream.try_next().await? {
+            match item {
+                QueryItem::Metadata(meta) if meta.result_index() == 0 => {
+                    let columns = meta
+                        .columns()
+                        .iter()
+                        .map(|c| c.name().to_string())
+                        .collect();
+                    sink.columns(columns).map_err(SchemaError::Export)?;
+                }
+                QueryItem::Row(row) if row.result_index() == 0 => {
+                    if max_rows == Some(streamed.rows) {
+                        streamed.truncated = true;
+                    } else {
+                        sink.row(row.into_iter().map(cell_value).collect())
+                            .map_err(SchemaError::Export)?;
+                        streamed.rows += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(streamed)
+    };
+    let result = limits.run(run).await?;
+
+    client
+        .simple_query("IF @@TRANCOUNT > 0 ROLLBACK TRANSACTION; SET ROWCOUNT 0;")
+        .await?
+        .into_results()
+        .await?;
+    result
+}
+
+/// Run a read-only query and collect up to `max_rows` rows in memory, or
+/// fewer when `limits` caps previews.
+pub async fn run_read_only_query(
+    client: &mut Client<Compat<TcpStream>>,
+    sql: &str,
+    parameters: &[QueryParameter],
+    values: &BTreeMap<String, String>,
+    max_rows: Option<usize>,
+    limits: &QueryLimits,
+) -> Result<QueryResult, SchemaError> {
+    let mut result = QueryResult::default();
+    let max_rows = Some(limits.preview_rows(max_rows));
+    let streamed = stream_read_only_query(
+        client,
+        sql,
+        parameters,
+        values,
+        max_rows,
+        limits,
+        &mut result,
+    )
+    .await?;
+    result.truncated = streamed.truncated;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_reading_queries_pass() {
+        for sql in [
+            "SELECT * FROM dbo.Orders",
+            "  with recent AS (SELECT 1 AS x) SELECT x FROM recent;",
+            "SELECT [Update], 'DROP TABLE x' AS note FROM dbo.[Insert] -- delete later",
+            "SELECT Next, Value FROM dbo.Steps",
+            "/* INTO */ SELECT @region AS \"Set\"",
+        ] {
+            assert!(check_read_only(sql).is_ok(), "{}", sql);
+        }
+        for sql in [
+            "UPDATE dbo.Orders SET Total = 0",
+            "SELECT * INTO #copy FROM dbo.Orders",
+            "SELECT 1; DROP TABLE dbo.Orders",
+            "WITH x AS (SELECT 1 AS a) DELETE FROM x",
+            "EXEC sp_who",
+            "SELECT NEXT VALUE FOR dbo.OrderNumbers",
+            "SELECT next  value\n for [dbo].[OrderNumbers] AS n",
+            "-- SELECT\n",
+        ] {
+            assert!(check_read_only(sql).is_err(), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn parameters_are_declared_and_bound_in_order() {
+        let parameters = vec![
+            QueryParameter {
+                name: "region".into(),
+                sql_type: None,
+                default_value: Some("EU".into()),
+            },
+            QueryParameter {
+                name: "since".into(),
+                sql_type: Some("datetime2(3)".into()),
+                default_value: None,
+            },
+        ];
+        let values = BTreeMap::from([("since".to_string(), "2024-01-01".to_string())]);
+        let (preamble, args) = bind_parameters(&parameters, &values).unwrap();
+        assert_eq!(
+            preamble,
+            "DECLARE @region nvarchar(4000) = @P1;\nDECLARE @since datetime2(3) = @P2;\n"
+        );
+        assert_eq!(args, vec![Some("EU".into()), Some("2024-01-01".into())]);
+
+        let bad_type = QueryParameter {
+            name: "x".into(),
+            sql_type: Some("int; DROP TABLE t".into()),
+            default_value: None,
+        };
+        assert!(bind_parameters(&[bad_type], &BTreeMap::new()).is_err());
+        assert_eq!(
+            datetime_text(45_000, 3_723_500),
+            Value::String("2023-03-17T01:02:03.500".into())
+        );
+    }
+
+    #[test]
+    fn statements_are_counted_without_semicolons() {
+        for (sql, count) in [
+            ("SELECT 1", 1),
+            ("SELECT 1; SELECT 2;", 2),
+            ("SELECT 1 SELECT 2 SELECT 3", 3),
+            ("SELECT a FROM x UNION ALL SELECT a FROM y EXCEPT SELECT a FROM z", 1),
+            ("WITH a AS (SELECT 1 AS x), b AS (SELECT x FROM a) SELECT x FROM b", 1),
+            ("SELECT * FROM t WITH (NOLOCK) WHERE id IN (SELECT id FROM u)", 1),
+            ("SELECT TOP 5 WITH TIES * FROM t ORDER BY x; WITH a AS (SELECT 1 AS x) SELECT x FROM a", 2),
+            ("SELECT 'SELECT' AS word -- SELECT\n", 1),
+        ] {
+            assert_eq!(statement_count(sql), count, "{}", sql);
+        }
+
+        let limits = QueryLimits {
+            max_preview_rows: Some(100),
+            ..Default::default()
+        };
+        assert_eq!(limits.preview_rows(None), 100);
+        assert_eq!(limits.preview_rows(Some(20)), 20);
+        assert_eq!(
+            QueryLimits::default().preview_rows(None),
+            DEFAULT_MAX_QUERY_ROWS
+        );
+    }
+}
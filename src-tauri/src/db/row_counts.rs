@@ -0,0 +1,188 @@
+use futures_util::TryStreamExt;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use tiberius::Client;
+use tokio::net::TcpStream;
+use tokio_util::compat::Compat;
+
+use crate::db::{object_id, quote_name, SchemaError, PRIMARY_KEY_COLUMNS_QUERY, ROW_COUNTS_QUERY};
+
+/// Relative difference, in percent, above which a table is reported as drifted.
+pub const DEFAULT_DRIFT_THRESHOLD_PERCENT: f64 = 1.0;
+
+/// Row count of every user table, keyed by table ID, with the schema and
+/// name kept for building per-table queries.
+pub async fn load_row_counts(
+    client: &mut Client<Compat<TcpStream>>,
+) -> Result<BTreeMap<String, (String, String, i64)>, SchemaError> {
+    let mut counts = BTreeMap::new();
+
+    let stream = client.query(ROW_COUNTS_QUERY, &[]).await?;
+    let mut row_stream = stream.into_row_stream();
+
+    while let Some(row) = row_stream.try_next().await? {
+        let schema_name: &str = row.get(0).unwrap_or_default();
+        let table_name: &str = row.get(1).unwrap_or_default();
+        let rows: i64 = row.get(2).unwrap_or_default();
+        counts.insert(
+            object_id(&[schema_name, table_name]),
+            (schema_name.to_string(), table_name.to_string(), rows),
+        );
+    }
+
+    Ok(counts)
+}
+
+/// Primary key columns in key order, keyed by table ID.
+pub async fn load_primary_key_columns(
+    client: &mut Client<Compat<TcpStream>>,
+) -> Result<HashMap<String, Vec<String>>, SchemaError> {
+    let mut keys: HashMap<String, Vec<String>> = HashMap::new();
+
+    let stream = client.query(PRIMARY_KEY_COLUMNS_QUERY, &[]).await?;
+    let mut row_stream = stream.into_row_stream();
+
+    while let Some(row) = row_stream.try_next().await? {
+        let schema_name: &str = row.get(0).unwrap_or_default();
+        let table_name: &str = row.get(1).unwrap_or_default();
+        let column_name: &str = row.get(2).unwrap_or_default();
+        keys.entry(object_id(&[schema_name, table_name]))
+            .or_default()
+            .push(column_name.to_string());
+    }
+
+    Ok(keys)
+}
+
+/// Order-independent checksum of the key columns of one table. This scans
+/// the table, so it is only run on request.
+pub async fn key_checksum(
+    client: &mut Client<Compat<TcpStream>>,
+    schema_name: &str,
+    table_name: &str,
+    key_columns: &[String],
+) -> Result<Option<i32>, SchemaError> {
+    let columns = key_columns
+        .iter()
+        .map(|c| quote_name(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!(
+        "SELECT CHECKSUM_AGG(BINARY_CHECKSUM({})) FROM {}.{}",
+        columns,
+        quote_name(schema_name),
+        quote_name(table_name)
+    );
+    let row = client.query(sql, &[]).await?.into_row().await?;
+    Ok(row.and_then(|r| r.get::<i32, _>(0)))
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RowCountDrift {
+    pub table_id: String,
+    /// `None` when the table only exists on the other side.
+    pub source_rows: Option<i64>,
+    pub target_rows: Option<i64>,
+    /// Target minus source.
+    pub difference: i64,
+    /// Difference relative to the source count; 100 when the source is empty.
+    pub percent: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_checksum: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_checksum: Option<i32>,
+    /// Missing on one side, over the threshold, or key checksums differ.
+    pub significant: bool,
+}
+
+/// Compare two environments table by table. Checksums, keyed by table ID,
+/// hold `(source, target)` for the tables that were checksummed. Significant
+/// drift is listed first, largest differences first.
+pub fn compare_row_counts(
+    source: &BTreeMap<String, i64>,
+    target: &BTreeMap<String, i64>,
+    checksums: &HashMap<String, (Option<i32>, Option<i32>)>,
+    threshold_percent: f64,
+) -> Vec<RowCountDrift> {
+    let tables: BTreeSet<&String> = source.keys().chain(target.keys()).collect();
+    let mut drift: Vec<RowCountDrift> = tables
+        .into_iter()
+        .map(|id| {
+            let source_rows = source.get(id).copied();
+            let target_rows = target.get(id).copied();
+            let difference = target_rows.unwrap_or(0) - source_rows.unwrap_or(0);
+            let percent = match source_rows.unwrap_or(0) {
+                0 if difference == 0 => 0.0,
+                0 => 100.0,
+                rows => difference as f64 / rows as f64 * 100.0,
+            };
+            let (source_checksum, target_checksum) =
+                checksums.get(id).copied().unwrap_or((None, None));
+            let checksum_differs = checksums.contains_key(id) && source_checksum != target_checksum;
+            RowCountDrift {
+                table_id: id.clone(),
+                source_rows,
+                target_rows,
+                difference,
+                percent,
+                source_checksum,
+                target_checksum,
+                significant: source_rows.is_none()
+                    || target_rows.is_none()
+                    || percent.abs() > threshold_percent
+                    || checksum_differs,
+            }
+        })
+        .collect();
+
+    drift.sort_by(|a, b| {
+        b.significant
+            .cmp(&a.significant)
+            .then(b.difference.abs().cmp(&a.difference.abs()))
+            .then(a.table_id.cmp(&b.table_id))
+    });
+    drift
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drift_flags_missing_tables_and_large_differences() {
+        let counts = |pairs: &[(&str, i64)]| -> BTreeMap<String, i64> {
+            pairs.iter().map(|(id, n)| (id.to_string(), *n)).collect()
+        };
+        let source = counts(&[
+            ("dbo.Orders", 10_000),
+            ("dbo.Lines", 50_000),
+            ("dbo.Audit", 5),
+        ]);
+        let target = counts(&[
+            ("dbo.Orders", 10_050),
+            ("dbo.Lines", 40_000),
+            ("dbo.New", 0),
+        ]);
+        let checksums = HashMap::from([("dbo.Orders".to_string(), (Some(1), Some(2)))]);
+
+        let drift = compare_row_counts(&source, &target, &checksums, 1.0);
+        let summary: Vec<(&str, i64, bool)> = drift
+            .iter()
+            .map(|d| (d.table_id.as_str(), d.difference, d.significant))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("dbo.Lines", -10_000, true),
+                ("dbo.Orders", 50, true),
+                ("dbo.Audit", -5, true),
+                ("dbo.New", 0, true),
+            ]
+        );
+        assert_eq!(drift[1].percent, 0.5);
+
+        let drift = compare_row_counts(&source, &source, &HashMap::new(), 1.0);
+        assert!(drift.iter().all(|d| !d.significant));
+    }
+}
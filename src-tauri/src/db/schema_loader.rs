@@ -1,4 +1,5 @@
 use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 use futures_util::TryStreamExt;
 use once_cell::sync::Lazy;
@@ -8,21 +9,50 @@ use tokio::net::TcpStream;
 use tokio_util::compat::Compat;
 
 use crate::db::{
-    create_client, format_data_type, ConnectionError, FOREIGN_KEYS_QUERY, SCALAR_FUNCTIONS_QUERY,
-    STORED_PROCEDURES_QUERY, TABLES_AND_COLUMNS_QUERY, TRIGGERS_QUERY, VIEWS_AND_COLUMNS_QUERY,
-    VIEW_COLUMN_SOURCES_QUERY,
+    analyze_definition, annotate_participation, annotate_watched_columns, apply_object_metadata,
+    create_client, definition_length, fetch_definition, format_data_type, load_agent_jobs,
+    load_definition_lengths, load_mysql_schema, load_object_metadata, object_id, split_multipart,
+    validate_graph, ConnectionError, DEFAULT_SCHEMA_QUERY, FOREIGN_KEYS_QUERY,
+    SCALAR_FUNCTIONS_QUERY, STORED_PROCEDURES_QUERY, TABLES_AND_COLUMNS_QUERY, TRIGGERS_QUERY,
+    VIEWS_AND_COLUMNS_QUERY, VIEW_COLUMN_SOURCES_QUERY,
 };
 use crate::types::{
-    Column, ColumnSource, ConnectionParams, ProcedureParameter, RelationshipEdge, ScalarFunction,
-    SchemaGraph, StoredProcedure, TableNode, Trigger, ViewNode,
+    ChangeCapture, Column, ColumnSource, ConnectionParams, DatabaseEngine, Interner, LoadPhase,
+    LoadPhaseStatus, ProcedureParameter, RelationshipEdge, ScalarFunction, SchemaGraph,
+    StoredProcedure, TableNode, Trigger, ViewNode,
 };
 
+/// Core phases stream every column in the database, so they get more headroom.
+pub(crate) const CORE_PHASE_TIMEOUT: Duration = Duration::from_secs(180);
+pub(crate) const OPTIONAL_PHASE_TIMEOUT: Duration = Duration::from_secs(60);
+/// Each phase is tried once and retried once before giving up.
+pub(crate) const PHASE_ATTEMPTS: u32 = 2;
+
 #[derive(Debug, thiserror::Error)]
 pub enum SchemaError {
     #[error("Connection error: {0}")]
     Connection(#[from] ConnectionError),
     #[error("Database error: {0}")]
     Tiberius(#[from] tiberius::error::Error),
+    #[error("Database error: {0}")]
+    MySql(#[from] mysql_async::Error),
+    #[error("Schema load was cancelled")]
+    Cancelled,
+    #[error("Loading {phase} timed out after {seconds}s")]
+    PhaseTimeout { phase: String, seconds: u64 },
+    /// A phase skipped because an earlier one couldn't reconnect.
+    #[error("Skipped after the connection was lost: {0}")]
+    ConnectionLost(String),
+    #[error("{0}")]
+    InvalidQuery(String),
+    #[error("Export failed: {0}")]
+    Export(String),
+    /// A change was attempted during a read-only session.
+    #[error("{0}")]
+    ReadOnly(String),
+    /// The failure of a concurrent identical request this one waited on.
+    #[error("{0}")]
+    Joined(String),
 }
 
 impl serde::Serialize for SchemaError {
@@ -34,45 +64,296 @@ impl serde::Serialize for SchemaError {
     }
 }
 
-pub async fn load_schema(params: &ConnectionParams) -> Result<SchemaGraph, SchemaError> {
+pub(crate) fn phase_record<T>(
+    phase: &str,
+    started: Instant,
+    attempts: u32,
+    result: &Result<T, SchemaError>,
+) -> LoadPhase {
+    let (status, error) = match result {
+        Ok(_) => (LoadPhaseStatus::Ok, None),
+        Err(e @ SchemaError::PhaseTimeout { .. }) => {
+            (LoadPhaseStatus::TimedOut, Some(e.to_string()))
+        }
+        Err(e) => (LoadPhaseStatus::Failed, Some(e.to_string())),
+    };
+    LoadPhase {
+        phase: phase.to_string(),
+        status,
+        attempts,
+        duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+        error,
+    }
+}
+
+/// Run one loader phase under a timeout, retrying once on a fresh connection.
+/// A timed-out query leaves the TDS stream mid-response, so a failed attempt
+/// always replaces the client. When that reconnect fails `$lost` records why,
+/// and later phases are skipped rather than read the abandoned stream. The
+/// outcome is appended to `$phases`.
+macro_rules! run_phase {
+    ($phases:ident, $client:ident, $lost:ident, $params:expr, $name:expr, $timeout:expr, $call:expr) => {{
+        let started = Instant::now();
+        let mut attempts = 0;
+        let result = loop {
+            if let Some(reason) = &$lost {
+                break Err(SchemaError::ConnectionLost(reason.clone()));
+            }
+            attempts += 1;
+            let error = match tokio::time::timeout($timeout, $call).await {
+                Ok(Ok(value)) => break Ok(value),
+                Ok(Err(e)) => e,
+                Err(_) => SchemaError::PhaseTimeout {
+                    phase: $name.to_string(),
+                    seconds: $timeout.as_secs(),
+                },
+            };
+            // Reconnect after every failure, the last one included, so neither
+            // the retry nor the next phase reads from an abandoned stream
+            match create_client($params).await {
+                Ok(fresh) => $client = fresh,
+                Err(e) => {
+                    // Nothing reads it after the last phase
+                    #[allow(unused_assignments)]
+                    {
+                        $lost = Some(e.to_string());
+                    }
+                    break Err(error);
+                }
+            }
+            if attempts >= PHASE_ATTEMPTS {
+                break Err(error);
+            }
+        };
+        $phases.push(phase_record($name, started, attempts, &result));
+        result
+    }};
+}
+
+/// Load the full schema graph. `on_progress` receives a completed fraction and a
+/// short description as each metadata phase starts.
+pub async fn load_schema(
+    params: &ConnectionParams,
+    on_progress: impl Fn(f64, &str),
+) -> Result<SchemaGraph, SchemaError> {
+    if params.engine == DatabaseEngine::MySql {
+        return load_mysql_schema(params, on_progress).await;
+    }
+    on_progress(0.0, "Connecting");
     let mut client = create_client(params).await?;
+    // Schema names and data types repeat across thousands of objects; share them
+    let mut interner = Interner::default();
+    let mut phases = Vec::new();
+    let mut lost: Option<String> = None;
 
     // Core data - must succeed
-    let tables = load_tables_and_columns(&mut client).await?;
-    let mut views = load_views_and_columns(&mut client).await?;
+    on_progress(0.1, "Loading tables");
+    let tables = run_phase!(
+        phases,
+        client,
+        lost,
+        params,
+        "tables",
+        CORE_PHASE_TIMEOUT,
+        load_tables_and_columns(&mut client, &mut interner)
+    )?;
+    on_progress(0.35, "Loading views");
+    let mut views = run_phase!(
+        phases,
+        client,
+        lost,
+        params,
+        "views",
+        CORE_PHASE_TIMEOUT,
+        load_views_and_columns(&mut client, &mut interner)
+    )?;
 
     // Optional enrichment - continue if fails (DMV queries can fail on broken references)
-    load_view_column_sources(&mut client, &mut views).await;
-
-    let name_to_id = build_name_lookup(&tables, &views);
+    on_progress(0.5, "Resolving view columns");
+    let _ = run_phase!(
+        phases,
+        client,
+        lost,
+        params,
+        "viewColumnSources",
+        OPTIONAL_PHASE_TIMEOUT,
+        async {
+            load_view_column_sources(&mut client, &mut views).await;
+            Ok::<(), SchemaError>(())
+        }
+    );
+
+    // Without it, unqualified names fall back to the usual `dbo` resolution
+    let default_schema = run_phase!(
+        phases,
+        client,
+        lost,
+        params,
+        "defaultSchema",
+        OPTIONAL_PHASE_TIMEOUT,
+        load_default_schema(&mut client)
+    )
+    .unwrap_or_else(|_| "dbo".to_string());
+    let name_to_id = build_name_lookup(&tables, &views, &default_schema);
 
     // Populate view references (needs tables to be loaded first)
     load_views_with_references(&mut views, &name_to_id);
 
     // Optional data - continue with empty if fails
-    let relationships = load_foreign_keys(&mut client).await.unwrap_or_default();
-    let triggers = load_triggers(&mut client, &name_to_id)
-        .await
-        .unwrap_or_default();
-    let stored_procedures = load_stored_procedures(&mut client, &name_to_id)
-        .await
-        .unwrap_or_default();
-    let scalar_functions = load_scalar_functions(&mut client, &name_to_id)
-        .await
-        .unwrap_or_default();
-
-    Ok(SchemaGraph {
+    on_progress(0.6, "Loading relationships");
+    let relationships = run_phase!(
+        phases,
+        client,
+        lost,
+        params,
+        "relationships",
+        OPTIONAL_PHASE_TIMEOUT,
+        load_foreign_keys(&mut client)
+    )
+    .unwrap_or_default();
+    on_progress(0.7, "Loading triggers");
+    let mut triggers = run_phase!(
+        phases,
+        client,
+        lost,
+        params,
+        "triggers",
+        OPTIONAL_PHASE_TIMEOUT,
+        load_triggers(&mut client, &name_to_id, &mut interner)
+    )
+    .unwrap_or_default();
+    on_progress(0.8, "Loading stored procedures");
+    let mut stored_procedures = run_phase!(
+        phases,
+        client,
+        lost,
+        params,
+        "storedProcedures",
+        OPTIONAL_PHASE_TIMEOUT,
+        load_stored_procedures(&mut client, &name_to_id, &mut interner)
+    )
+    .unwrap_or_default();
+    on_progress(0.9, "Loading functions");
+    let mut scalar_functions = run_phase!(
+        phases,
+        client,
+        lost,
+        params,
+        "scalarFunctions",
+        OPTIONAL_PHASE_TIMEOUT,
+        load_scalar_functions(&mut client, &name_to_id, &mut interner)
+    )
+    .unwrap_or_default();
+
+    // A failure here is reported through the phase record rather than failing the load
+    on_progress(0.95, "Verifying definitions");
+    let _ = run_phase!(
+        phases,
+        client,
+        lost,
+        params,
+        "definitions",
+        OPTIONAL_PHASE_TIMEOUT,
+        complete_definitions(
+            &mut client,
+            &name_to_id,
+            &mut views,
+            &mut triggers,
+            &mut stored_procedures,
+            &mut scalar_functions,
+        )
+    );
+
+    // Ownership and dates are informational; a failure leaves them unset
+    let metadata = run_phase!(
+        phases,
+        client,
+        lost,
+        params,
+        "metadata",
+        OPTIONAL_PHASE_TIMEOUT,
+        load_object_metadata(&mut client)
+    )
+    .unwrap_or_default();
+
+    // Needs msdb access, which many logins and Azure SQL Database lack
+    on_progress(0.97, "Loading Agent jobs");
+    let agent_jobs = run_phase!(
+        phases,
+        client,
+        lost,
+        params,
+        "agentJobs",
+        OPTIONAL_PHASE_TIMEOUT,
+        load_agent_jobs(
+            &mut client,
+            &params.database,
+            &name_to_id,
+            &stored_procedures
+        )
+    )
+    .unwrap_or_default();
+
+    let mut graph = SchemaGraph {
         tables,
         views,
         relationships,
         triggers,
         stored_procedures,
         scalar_functions,
-    })
+        load_phases: phases,
+        warnings: Vec::new(),
+        links: Vec::new(),
+        agent_jobs,
+        classifications: Vec::new(),
+        stewardship: Vec::new(),
+        default_schema: Some(default_schema),
+        version: 0,
+    };
+    apply_object_metadata(&mut graph, &metadata);
+    validate_graph(&mut graph);
+    annotate_participation(&mut graph);
+    annotate_watched_columns(&mut graph);
+    sort_graph(&mut graph);
+    Ok(graph)
+}
+
+/// Give every collection a stable order (schema, then name; columns by ordinal)
+/// so repeated loads of the same database produce identical graphs.
+pub(crate) fn sort_graph(graph: &mut SchemaGraph) {
+    graph
+        .tables
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    graph
+        .views
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    for columns in graph
+        .tables
+        .iter_mut()
+        .map(|t| &mut t.columns)
+        .chain(graph.views.iter_mut().map(|v| &mut v.columns))
+    {
+        columns.sort_by_key(|c| c.ordinal_position);
+    }
+
+    // Stable sort keeps multi-column foreign keys in constraint column order
+    graph
+        .relationships
+        .sort_by(|a, b| (&a.from, &a.id).cmp(&(&b.from, &b.id)));
+    graph
+        .triggers
+        .sort_by(|a, b| (&a.schema, &a.table_id, &a.name).cmp(&(&b.schema, &b.table_id, &b.name)));
+    graph
+        .stored_procedures
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    graph
+        .scalar_functions
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
 }
 
 async fn load_tables_and_columns(
     client: &mut Client<Compat<TcpStream>>,
+    interner: &mut Interner,
 ) -> Result<Vec<TableNode>, SchemaError> {
     let mut tables: HashMap<String, TableNode> = HashMap::new();
 
@@ -89,18 +370,29 @@ async fn load_tables_and_columns(
         let scale: u8 = row.get(6).unwrap_or_default();
         let is_nullable: bool = row.get(7).unwrap_or_default();
         let is_primary_key: i32 = row.get(8).unwrap_or_default();
-
-        let table_id = format!("{}.{}", schema_name, table_name);
+        let column_id: i32 = row.get(9).unwrap_or_default();
+        let change_capture = ChangeCapture::from_flags(
+            row.get(10).unwrap_or_default(),
+            row.get(11).unwrap_or_default(),
+            row.get(12).unwrap_or_default(),
+        );
+        let is_identity: bool = row.get(13).unwrap_or_default();
+        let is_computed: bool = row.get(14).unwrap_or_default();
+
+        let table_id = object_id(&[schema_name, table_name]);
         let formatted_type = format_data_type(data_type, max_length, precision, scale);
 
         let column = Column {
             name: column_name.to_string(),
-            data_type: formatted_type,
+            data_type: interner.intern(&formatted_type),
             is_nullable,
             is_primary_key: is_primary_key != 0,
+            ordinal_position: column_id.max(0) as u32,
             source_columns: Vec::new(),
             source_table: None,
             source_column: None,
+            is_identity,
+            is_computed,
         };
 
         tables
@@ -108,8 +400,11 @@ async fn load_tables_and_columns(
             .or_insert_with(|| TableNode {
                 id: table_id,
                 name: table_name.to_string(),
-                schema: schema_name.to_string(),
+                schema: interner.intern(schema_name),
                 columns: Vec::new(),
+                source: None,
+                metadata: None,
+                change_capture,
             })
             .columns
             .push(column);
@@ -120,6 +415,7 @@ async fn load_tables_and_columns(
 
 async fn load_views_and_columns(
     client: &mut Client<Compat<TcpStream>>,
+    interner: &mut Interner,
 ) -> Result<Vec<ViewNode>, SchemaError> {
     let mut views: HashMap<String, (ViewNode, String)> = HashMap::new();
 
@@ -136,18 +432,23 @@ async fn load_views_and_columns(
         let scale: u8 = row.get(6).unwrap_or_default();
         let is_nullable: bool = row.get(7).unwrap_or_default();
         let definition: &str = row.get(8).unwrap_or_default();
+        let column_id: i32 = row.get(9).unwrap_or_default();
+        let is_indexed: bool = row.get(10).unwrap_or_default();
 
-        let view_id = format!("{}.{}", schema_name, view_name);
+        let view_id = object_id(&[schema_name, view_name]);
         let formatted_type = format_data_type(data_type, max_length, precision, scale);
 
         let column = Column {
             name: column_name.to_string(),
-            data_type: formatted_type,
+            data_type: interner.intern(&formatted_type),
             is_nullable,
             is_primary_key: false,
+            ordinal_position: column_id.max(0) as u32,
             source_columns: Vec::new(),
             source_table: None,
             source_column: None,
+            is_identity: false,
+            is_computed: false,
         };
 
         let entry = views.entry(view_id.clone()).or_insert_with(|| {
@@ -155,10 +456,13 @@ async fn load_views_and_columns(
                 ViewNode {
                     id: view_id,
                     name: view_name.to_string(),
-                    schema: schema_name.to_string(),
+                    schema: interner.intern(schema_name),
                     columns: Vec::new(),
                     definition: definition.to_string(),
                     referenced_tables: Vec::new(),
+                    source: None,
+                    metadata: None,
+                    is_indexed,
                 },
                 definition.to_string(),
             )
@@ -197,13 +501,13 @@ async fn load_view_column_sources(
                 let source_table: &str = row.get(4).unwrap_or_default();
                 let source_column: &str = row.get(5).unwrap_or_default();
 
-                let view_id = format!("{}.{}", view_schema, view_name);
+                let view_id = object_id(&[view_schema, view_name]);
 
                 if !source_table.is_empty() && !source_column.is_empty() {
                     let source_table_name = if source_schema.is_empty() {
                         source_table.to_string()
                     } else {
-                        format!("{}.{}", source_schema, source_table)
+                        object_id(&[source_schema, source_table])
                     };
                     let entry = column_sources
                         .entry(view_id)
@@ -244,6 +548,69 @@ async fn load_view_column_sources(
     }
 }
 
+/// Re-fetch, slice by slice, every definition that came back shorter than
+/// `sys.sql_modules` reports, and refresh the references parsed from it.
+/// Returns the number of definitions that had to be completed.
+async fn complete_definitions(
+    client: &mut Client<Compat<TcpStream>>,
+    name_to_id: &HashMap<String, String>,
+    views: &mut [ViewNode],
+    triggers: &mut [Trigger],
+    procedures: &mut [StoredProcedure],
+    functions: &mut [ScalarFunction],
+) -> Result<usize, SchemaError> {
+    let lengths = load_definition_lengths(client).await?;
+    let is_truncated = |schema: &str, name: &str, definition: &str| {
+        lengths
+            .get(&object_id(&[schema, name]))
+            .is_some_and(|&expected| definition_length(definition) < expected)
+    };
+    let mut completed = 0;
+
+    for view in views.iter_mut() {
+        if is_truncated(&view.schema, &view.name, &view.definition) {
+            view.definition = fetch_definition(client, &view.schema, &view.name).await?;
+            let (read_refs, _) = extract_table_references(&view.definition, name_to_id);
+            view.referenced_tables = read_refs;
+            completed += 1;
+        }
+    }
+    for trigger in triggers.iter_mut() {
+        if is_truncated(&trigger.schema, &trigger.name, &trigger.definition) {
+            trigger.definition = fetch_definition(client, &trigger.schema, &trigger.name).await?;
+            (
+                trigger.referenced_tables,
+                trigger.affected_tables,
+                trigger.analysis,
+            ) = analyze_definition(&trigger.definition, name_to_id);
+            completed += 1;
+        }
+    }
+    for procedure in procedures.iter_mut() {
+        if is_truncated(&procedure.schema, &procedure.name, &procedure.definition) {
+            procedure.definition =
+                fetch_definition(client, &procedure.schema, &procedure.name).await?;
+            (
+                procedure.referenced_tables,
+                procedure.affected_tables,
+                procedure.analysis,
+            ) = analyze_definition(&procedure.definition, name_to_id);
+            completed += 1;
+        }
+    }
+    for function in functions.iter_mut() {
+        if is_truncated(&function.schema, &function.name, &function.definition) {
+            function.definition =
+                fetch_definition(client, &function.schema, &function.name).await?;
+            (function.referenced_tables, function.affected_tables) =
+                extract_table_references(&function.definition, name_to_id);
+            completed += 1;
+        }
+    }
+
+    Ok(completed)
+}
+
 fn load_views_with_references(views: &mut [ViewNode], name_to_id: &HashMap<String, String>) {
     for view in views.iter_mut() {
         let (read_refs, _) = extract_table_references(&view.definition, name_to_id);
@@ -268,8 +635,8 @@ async fn load_foreign_keys(
         let ref_table: &str = row.get(5).unwrap_or_default();
         let ref_column: &str = row.get(6).unwrap_or_default();
 
-        let from_id = format!("{}.{}", src_schema, src_table);
-        let to_id = format!("{}.{}", ref_schema, ref_table);
+        let from_id = object_id(&[src_schema, src_table]);
+        let to_id = object_id(&[ref_schema, ref_table]);
 
         relationships.push(RelationshipEdge {
             id: fk_name.to_string(),
@@ -277,6 +644,9 @@ async fn load_foreign_keys(
             to: to_id,
             from_column: Some(src_column.to_string()),
             to_column: Some(ref_column.to_string()),
+            source: None,
+            is_virtual: false,
+            participation: None,
         });
     }
 
@@ -286,6 +656,7 @@ async fn load_foreign_keys(
 async fn load_triggers(
     client: &mut Client<Compat<TcpStream>>,
     name_to_id: &HashMap<String, String>,
+    interner: &mut Interner,
 ) -> Result<Vec<Trigger>, SchemaError> {
     let mut triggers = Vec::new();
 
@@ -303,17 +674,18 @@ async fn load_triggers(
         let fires_on_delete: i32 = row.get(7).unwrap_or_default();
         let definition: &str = row.get(8).unwrap_or_default();
 
-        let table_id = format!("{}.{}", schema_name, table_name);
-        let trigger_id = format!("{}.{}.{}", schema_name, table_name, trigger_name);
+        let table_id = object_id(&[schema_name, table_name]);
+        let trigger_id = object_id(&[schema_name, table_name, trigger_name]);
 
-        let (referenced_tables, affected_tables) = extract_table_references(definition, name_to_id);
+        let (referenced_tables, affected_tables, analysis) =
+            analyze_definition(definition, name_to_id);
 
         triggers.push(Trigger {
             id: trigger_id,
             name: trigger_name.to_string(),
-            schema: schema_name.to_string(),
+            schema: interner.intern(schema_name),
             table_id,
-            trigger_type: trigger_type.to_string(),
+            trigger_type: interner.intern(trigger_type),
             is_disabled,
             fires_on_insert: fires_on_insert != 0,
             fires_on_update: fires_on_update != 0,
@@ -321,6 +693,10 @@ async fn load_triggers(
             definition: definition.to_string(),
             referenced_tables,
             affected_tables,
+            watched_columns: Vec::new(),
+            analysis,
+            source: None,
+            metadata: None,
         });
     }
 
@@ -330,6 +706,7 @@ async fn load_triggers(
 async fn load_stored_procedures(
     client: &mut Client<Compat<TcpStream>>,
     name_to_id: &HashMap<String, String>,
+    interner: &mut Interner,
 ) -> Result<Vec<StoredProcedure>, SchemaError> {
     let mut procedures: HashMap<String, StoredProcedure> = HashMap::new();
 
@@ -345,26 +722,30 @@ async fn load_stored_procedures(
         let is_output: bool = row.get(5).unwrap_or_default();
         let definition: &str = row.get(6).unwrap_or_default();
 
-        let procedure_id = format!("{}.{}", schema_name, procedure_name);
+        let procedure_id = object_id(&[schema_name, procedure_name]);
 
         let procedure = procedures.entry(procedure_id.clone()).or_insert_with(|| {
-            let (referenced_tables, affected_tables) = extract_table_references(definition, name_to_id);
+            let (referenced_tables, affected_tables, analysis) =
+                analyze_definition(definition, name_to_id);
             StoredProcedure {
                 id: procedure_id,
                 name: procedure_name.to_string(),
-                schema: schema_name.to_string(),
-                procedure_type: procedure_type.to_string(),
+                schema: interner.intern(schema_name),
+                procedure_type: interner.intern(procedure_type),
                 parameters: Vec::new(),
                 definition: definition.to_string(),
                 referenced_tables,
                 affected_tables,
+                analysis,
+                source: None,
+                metadata: None,
             }
         });
 
         if !parameter_name.is_empty() {
             procedure.parameters.push(ProcedureParameter {
                 name: parameter_name.to_string(),
-                data_type: parameter_type.to_string(),
+                data_type: interner.intern(parameter_type),
                 is_output,
             });
         }
@@ -376,6 +757,7 @@ async fn load_stored_procedures(
 async fn load_scalar_functions(
     client: &mut Client<Compat<TcpStream>>,
     name_to_id: &HashMap<String, String>,
+    interner: &mut Interner,
 ) -> Result<Vec<ScalarFunction>, SchemaError> {
     let mut functions: HashMap<String, ScalarFunction> = HashMap::new();
 
@@ -392,27 +774,29 @@ async fn load_scalar_functions(
         let return_type: &str = row.get(6).unwrap_or_default();
         let definition: &str = row.get(7).unwrap_or_default();
 
-        let function_id = format!("{}.{}", schema_name, function_name);
+        let function_id = object_id(&[schema_name, function_name]);
 
         let function = functions.entry(function_id.clone()).or_insert_with(|| {
             let (referenced_tables, affected_tables) = extract_table_references(definition, name_to_id);
             ScalarFunction {
                 id: function_id,
                 name: function_name.to_string(),
-                schema: schema_name.to_string(),
-                function_type: function_type.to_string(),
+                schema: interner.intern(schema_name),
+                function_type: interner.intern(function_type),
                 parameters: Vec::new(),
-                return_type: return_type.to_string(),
+                return_type: interner.intern(return_type),
                 definition: definition.to_string(),
                 referenced_tables,
                 affected_tables,
+                source: None,
+                metadata: None,
             }
         });
 
         if !parameter_name.is_empty() {
             function.parameters.push(ProcedureParameter {
                 name: parameter_name.to_string(),
-                data_type: parameter_type.to_string(),
+                data_type: interner.intern(parameter_type),
                 is_output,
             });
         }
@@ -421,80 +805,269 @@ async fn load_scalar_functions(
     Ok(functions.into_values().collect())
 }
 
-static READ_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
-    vec![
-        Regex::new(r"(?i)\bFROM\s+(?:\[?(\w+)\]?\.)?\[?(\w+)\]?").unwrap(),
-        Regex::new(r"(?i)\bJOIN\s+(?:\[?(\w+)\]?\.)?\[?(\w+)\]?").unwrap(),
-    ]
-});
+/// A T-SQL multi-part name: up to four parts, each bare, `[bracketed]` (with
+/// `]]` escapes) or `"quoted"`, separated by dots with optional whitespace.
+const MULTIPART_NAME: &str = r#"((?:\[(?:[^\]]|\]\])+\]|"(?:[^"]|"")+"|[\w@#$]+)(?:\s*\.\s*(?:\[(?:[^\]]|\]\])+\]|"(?:[^"]|"")+"|[\w@#$]*)){0,3})"#;
+
+pub(crate) fn reference_pattern(keyword: &str) -> Regex {
+    Regex::new(&format!(r"(?i)\b{}\s+{}", keyword, MULTIPART_NAME)).unwrap()
+}
+
+pub(crate) static READ_PATTERNS: Lazy<Vec<Regex>> =
+    Lazy::new(|| vec![reference_pattern("FROM"), reference_pattern("JOIN")]);
 
 static WRITE_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
     vec![
-        Regex::new(r"(?i)\bINSERT\s+INTO\s+(?:\[?(\w+)\]?\.)?\[?(\w+)\]?").unwrap(),
-        Regex::new(r"(?i)\bUPDATE\s+(?:\[?(\w+)\]?\.)?\[?(\w+)\]?").unwrap(),
-        Regex::new(r"(?i)\bDELETE\s+FROM\s+(?:\[?(\w+)\]?\.)?\[?(\w+)\]?").unwrap(),
+        reference_pattern(r"INSERT\s+INTO"),
+        reference_pattern("UPDATE"),
+        reference_pattern(r"DELETE\s+FROM"),
     ]
 });
 
-fn extract_table_references(
+/// Resolve a referenced name to a graph ID. Exact-case matches win so that
+/// objects differing only by case (case-sensitive collations) stay distinct.
+pub(crate) fn resolve_reference<'a>(
+    name: &str,
+    name_to_id: &'a HashMap<String, String>,
+) -> Option<&'a String> {
+    let parts = split_multipart(name);
+    let key = match parts.as_slice() {
+        // `db..table` leaves the schema empty; fall back to the bare name
+        [.., schema, object] if !schema.is_empty() => {
+            object_id(&[schema.as_str(), object.as_str()])
+        }
+        [.., object] => object.clone(),
+        [] => return None,
+    };
+    name_to_id
+        .get(&key)
+        .or_else(|| name_to_id.get(&key.to_lowercase()))
+}
+
+fn collect_references(
+    patterns: &[Regex],
     definition: &str,
     name_to_id: &HashMap<String, String>,
-) -> (Vec<String>, Vec<String>) {
-    let mut read_refs: HashSet<String> = HashSet::new();
-    let mut write_refs: HashSet<String> = HashSet::new();
+) -> HashSet<String> {
+    let mut refs = HashSet::new();
+    for pattern in patterns {
+        for cap in pattern.captures_iter(definition) {
+            if let Some(id) = cap
+                .get(1)
+                .and_then(|m| resolve_reference(m.as_str(), name_to_id))
+            {
+                refs.insert(id.clone());
+            }
+        }
+    }
+    refs
+}
 
+pub(crate) fn extract_table_references(
+    definition: &str,
+    name_to_id: &HashMap<String, String>,
+) -> (Vec<String>, Vec<String>) {
     if definition.is_empty() {
         return (Vec::new(), Vec::new());
     }
 
-    for pattern in READ_PATTERNS.iter() {
-        for cap in pattern.captures_iter(definition) {
-            let schema = cap.get(1).map(|m| m.as_str());
-            if let Some(table) = cap.get(2).map(|m| m.as_str()) {
-                let lookup_key = if let Some(s) = schema {
-                    format!("{}.{}", s, table).to_lowercase()
-                } else {
-                    table.to_lowercase()
-                };
-
-                if let Some(id) = name_to_id.get(&lookup_key) {
-                    read_refs.insert(id.clone());
-                }
-            }
-        }
+    let mut read_refs: Vec<String> = collect_references(&READ_PATTERNS, definition, name_to_id)
+        .into_iter()
+        .collect();
+    let mut write_refs: Vec<String> = collect_references(&WRITE_PATTERNS, definition, name_to_id)
+        .into_iter()
+        .collect();
+    read_refs.sort();
+    write_refs.sort();
+
+    (read_refs, write_refs)
+}
+
+/// Map bare names, `schema.name` IDs and their lowercase forms to object IDs.
+/// A bare name resolves the way SQL Server does for the connecting user: the
+/// user's default schema first, then `dbo`, then any other schema.
+pub(crate) fn build_name_lookup(
+    tables: &[TableNode],
+    views: &[ViewNode],
+    default_schema: &str,
+) -> HashMap<String, String> {
+    let mut name_to_id: HashMap<String, String> = HashMap::new();
+    let mut objects: Vec<(&String, &String, &str)> = tables
+        .iter()
+        .map(|t| (&t.name, &t.id, &*t.schema))
+        .chain(views.iter().map(|v| (&v.name, &v.id, &*v.schema)))
+        .collect();
+    objects.sort_by_key(|(_, _, schema)| match *schema {
+        s if s == default_schema => 0,
+        "dbo" => 1,
+        _ => 2,
+    });
+
+    // Exact keys first so a case-insensitive alias never shadows a real object
+    for (_, id, _) in &objects {
+        name_to_id.insert((*id).clone(), (*id).clone());
+    }
+    for (name, id, _) in &objects {
+        name_to_id
+            .entry((*name).clone())
+            .or_insert_with(|| (*id).clone());
+    }
+    for (name, id, _) in &objects {
+        name_to_id
+            .entry(name.to_lowercase())
+            .or_insert_with(|| (*id).clone());
+        name_to_id
+            .entry(id.to_lowercase())
+            .or_insert_with(|| (*id).clone());
     }
 
-    for pattern in WRITE_PATTERNS.iter() {
-        for cap in pattern.captures_iter(definition) {
-            let schema = cap.get(1).map(|m| m.as_str());
-            if let Some(table) = cap.get(2).map(|m| m.as_str()) {
-                let lookup_key = if let Some(s) = schema {
-                    format!("{}.{}", s, table).to_lowercase()
-                } else {
-                    table.to_lowercase()
-                };
-
-                if let Some(id) = name_to_id.get(&lookup_key) {
-                    write_refs.insert(id.clone());
-                }
-            }
+    name_to_id
+}
+
+/// Default schema of the connecting user (`SCHEMA_NAME()`), which decides how
+/// unqualified names resolve. Contained database users often have one other
+/// than `dbo`.
+async fn load_default_schema(
+    client: &mut Client<Compat<TcpStream>>,
+) -> Result<String, SchemaError> {
+    let row = client
+        .query(DEFAULT_SCHEMA_QUERY, &[])
+        .await?
+        .into_row()
+        .await?;
+    Ok(row
+        .as_ref()
+        .and_then(|r| r.get::<&str, _>(0))
+        .unwrap_or("dbo")
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(ids: &[&str]) -> HashMap<String, String> {
+        let mut name_to_id = HashMap::new();
+        for id in ids {
+            let name = id.rsplit('.').next().unwrap();
+            name_to_id.insert(name.to_lowercase(), id.to_string());
+            name_to_id.insert(id.to_lowercase(), id.to_string());
         }
+        name_to_id
     }
 
-    (read_refs.into_iter().collect(), write_refs.into_iter().collect())
-}
+    #[test]
+    fn references_resolve_non_ascii_identifiers() {
+        let name_to_id = lookup(&["販売.注文", "dbo.Ürünler"]);
+        let definition = "INSERT INTO [販売].[注文] SELECT * FROM dbo.ürünler";
 
-fn build_name_lookup(tables: &[TableNode], views: &[ViewNode]) -> HashMap<String, String> {
-    let mut name_to_id: HashMap<String, String> = HashMap::new();
+        let (reads, writes) = extract_table_references(definition, &name_to_id);
+        assert_eq!(reads, vec!["dbo.Ürünler".to_string()]);
+        assert_eq!(writes, vec!["販売.注文".to_string()]);
+    }
 
-    for table in tables {
-        name_to_id.insert(table.name.to_lowercase(), table.id.clone());
-        name_to_id.insert(table.id.to_lowercase(), table.id.clone());
+    #[test]
+    fn references_resolve_bracketed_and_dotted_identifiers() {
+        let name_to_id = lookup(&["dbo.Order Details", "dbo.[weird.name]", "sales.Orders"]);
+        let definition = r#"
+            SELECT * FROM [dbo].[Order Details] od
+            JOIN dbo . [weird.name] w ON w.id = od.id
+            JOIN "sales"."orders" o ON o.id = od.order_id
+        "#;
+
+        let (mut reads, _) = extract_table_references(definition, &name_to_id);
+        reads.sort();
+        assert_eq!(
+            reads,
+            vec!["dbo.Order Details", "dbo.[weird.name]", "sales.Orders"]
+        );
     }
-    for view in views {
-        name_to_id.insert(view.name.to_lowercase(), view.id.clone());
-        name_to_id.insert(view.id.to_lowercase(), view.id.clone());
+
+    #[test]
+    fn exact_case_match_wins_under_case_sensitive_collation() {
+        let table = |name: &str| TableNode {
+            id: object_id(&["dbo", name]),
+            name: name.to_string(),
+            schema: "dbo".into(),
+            columns: Vec::new(),
+            source: None,
+            metadata: None,
+            change_capture: None,
+        };
+        let name_to_id = build_name_lookup(&[table("Audit"), table("audit")], &[], "dbo");
+
+        let (reads, _) = extract_table_references("SELECT 1 FROM dbo.audit", &name_to_id);
+        assert_eq!(reads, vec!["dbo.audit".to_string()]);
+        let (reads, _) = extract_table_references("SELECT 1 FROM Audit", &name_to_id);
+        assert_eq!(reads, vec!["dbo.Audit".to_string()]);
+        let (reads, _) = extract_table_references("SELECT 1 FROM AUDIT", &name_to_id);
+        assert_eq!(reads.len(), 1);
     }
 
-    name_to_id
+    #[test]
+    fn bare_names_prefer_default_schema_then_dbo() {
+        let table = |schema: &str, name: &str| TableNode {
+            id: object_id(&[schema, name]),
+            name: name.to_string(),
+            schema: schema.into(),
+            columns: Vec::new(),
+            source: None,
+            metadata: None,
+            change_capture: None,
+        };
+        let tables = [
+            table("archive", "Orders"),
+            table("dbo", "Orders"),
+            table("sales", "Orders"),
+            table("archive", "Customers"),
+        ];
+
+        let as_sales = build_name_lookup(&tables, &[], "sales");
+        let (reads, _) = extract_table_references("SELECT * FROM Orders", &as_sales);
+        assert_eq!(reads, vec!["sales.Orders".to_string()]);
+        let (reads, _) = extract_table_references("SELECT * FROM orders", &as_sales);
+        assert_eq!(reads, vec!["sales.Orders".to_string()]);
+
+        let as_dbo = build_name_lookup(&tables, &[], "dbo");
+        let (reads, _) = extract_table_references("SELECT * FROM Orders", &as_dbo);
+        assert_eq!(reads, vec!["dbo.Orders".to_string()]);
+        let (reads, _) = extract_table_references("SELECT * FROM Customers", &as_dbo);
+        assert_eq!(reads, vec!["archive.Customers".to_string()]);
+    }
+
+    #[test]
+    fn sort_graph_orders_by_schema_name_and_ordinal() {
+        let column = |name: &str, ordinal: u32| Column {
+            name: name.to_string(),
+            data_type: "int".into(),
+            ordinal_position: ordinal,
+            ..Default::default()
+        };
+        let table = |schema: &str, name: &str| TableNode {
+            id: object_id(&[schema, name]),
+            name: name.to_string(),
+            schema: schema.into(),
+            columns: vec![column("c3", 3), column("c1", 1), column("c2", 2)],
+            source: None,
+            metadata: None,
+            change_capture: None,
+        };
+        let mut graph = crate::commands::mock::generate_mock_schema("small");
+        graph.tables = vec![table("sales", "B"), table("dbo", "Z"), table("sales", "A")];
+
+        sort_graph(&mut graph);
+
+        let ids: Vec<&str> = graph.tables.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["dbo.Z", "sales.A", "sales.B"]);
+        let columns: Vec<&str> = graph.tables[0]
+            .columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(columns, vec!["c1", "c2", "c3"]);
+        assert!(graph
+            .stored_procedures
+            .windows(2)
+            .all(|w| (&w[0].schema, &w[0].name) <= (&w[1].schema, &w[1].name)));
+    }
 }
@@ -3,6 +3,8 @@ use std::time::Duration;
 use tokio::net::UdpSocket;
 use tokio::time::timeout;
 
+use crate::validation::detect_and_decode;
+
 const SSRP_PORT: u16 = 1434;
 const SSRP_TIMEOUT: Duration = Duration::from_secs(2);
 
@@ -123,8 +125,10 @@ fn parse_ssrp_response(data: &[u8], instance: &str) -> Result<u16, SsrpError> {
         return Err(SsrpError::InvalidResponse);
     }
 
-    // Skip header (3 bytes) and parse the response string
-    let response_str = String::from_utf8_lossy(&data[3..]);
+    // Skip header (3 bytes) and parse the response string. SQL Server Browser
+    // answers in the server's ANSI code page, not UTF-8, so detect it rather
+    // than mangling non-ASCII server and instance names.
+    let response_str = detect_and_decode(&data[3..]).content;
 
     // Response is semicolon-delimited key-value pairs:
     // ServerName;HOSTNAME;InstanceName;INSTANCE;IsClustered;No;Version;X.X.X.X;tcp;PORT;np;...;;
@@ -157,6 +161,19 @@ mod tests {
         assert_eq!(port, 1444);
     }
 
+    #[test]
+    fn parse_ssrp_response_handles_ansi_code_page() {
+        let (body, _, _) = encoding_rs::SHIFT_JIS.encode(
+            "ServerName;開発サーバー;InstanceName;販売;IsClustered;No;Version;16.0.1000.6;tcp;1500;;",
+        );
+        let mut response = vec![0x05, body.len() as u8, 0x00];
+        response.extend_from_slice(&body);
+
+        let port = parse_ssrp_response(&response, "販売")
+            .expect("expected SSRP parser to handle Shift_JIS response");
+        assert_eq!(port, 1500);
+    }
+
     #[test]
     fn parse_ssrp_response_handles_invalid() {
         // Invalid response (wrong header)
@@ -0,0 +1,186 @@
+use std::collections::{BTreeSet, HashMap};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::db::split_multipart;
+use crate::types::{Column, SchemaGraph, TableNode};
+
+/// `UPDATE(Column)`, with the column bare, `[bracketed]` or `"quoted"`.
+static UPDATE_COLUMN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)\bUPDATE\s*\(\s*(\[(?:[^\]]|\]\])+\]|"(?:[^"]|"")+"|[\w@#$]+)\s*\)"#).unwrap()
+});
+
+static COLUMNS_UPDATED: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\bCOLUMNS_UPDATED\s*\(\s*\)").unwrap());
+
+/// `COLUMNS_UPDATED() & mask`, where the mask covers column IDs 1 to 8 upward.
+static WHOLE_MASK: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\bCOLUMNS_UPDATED\s*\(\s*\)\s*\)?\s*&\s*(0x[0-9a-f]+|\d+)").unwrap()
+});
+
+/// `SUBSTRING(COLUMNS_UPDATED(), byte, 1) & mask`, where byte `n` covers
+/// column IDs `8(n-1)+1` to `8n`.
+static BYTE_MASK: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\bSUBSTRING\s*\(\s*COLUMNS_UPDATED\s*\(\s*\)\s*,\s*(\d+)\s*,\s*1\s*\)\s*\)?\s*&\s*(0x[0-9a-f]+|\d+)",
+    )
+    .unwrap()
+});
+
+fn parse_mask(text: &str) -> Option<u64> {
+    match text.get(..2) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("0x") => {
+            u64::from_str_radix(&text[2..], 16).ok()
+        }
+        _ => text.parse().ok(),
+    }
+}
+
+/// Column IDs (1-based) whose bits are set in `mask`, starting at `first`.
+fn mask_ordinals(mask: u64, first: u32) -> impl Iterator<Item = u32> {
+    (0..64)
+        .filter(move |bit| mask & (1 << bit) != 0)
+        .filter_map(move |bit| first.checked_add(bit))
+}
+
+/// Columns of `table` whose update the trigger tests for, in column order.
+/// A `COLUMNS_UPDATED()` use without a literal mask could test any column,
+/// so it counts as watching them all.
+pub fn watched_columns(definition: &str, table: &TableNode) -> Vec<String> {
+    let by_name: HashMap<String, &str> = table
+        .columns
+        .iter()
+        .map(|c| (c.name.to_lowercase(), c.name.as_str()))
+        .collect();
+    let mut names: BTreeSet<&str> = UPDATE_COLUMN
+        .captures_iter(definition)
+        .filter_map(|cap| split_multipart(&cap[1]).pop())
+        .filter_map(|name| by_name.get(&name.to_lowercase()).copied())
+        .collect();
+
+    let mut ordinals = BTreeSet::new();
+    let mut decoded = 0;
+    for cap in BYTE_MASK.captures_iter(definition) {
+        if let (Ok(byte), Some(mask)) = (cap[1].parse::<u32>(), parse_mask(&cap[2])) {
+            // Byte 0 and bytes past any column ID select nothing
+            let first = byte
+                .checked_sub(1)
+                .and_then(|b| b.checked_mul(8)?.checked_add(1));
+            if let Some(first) = first {
+                ordinals.extend(mask_ordinals(mask & 0xff, first));
+            }
+            decoded += 1;
+        }
+    }
+    for cap in WHOLE_MASK.captures_iter(definition) {
+        if let Some(mask) = parse_mask(&cap[1]) {
+            ordinals.extend(mask_ordinals(mask, 1));
+            decoded += 1;
+        }
+    }
+    let all = COLUMNS_UPDATED.find_iter(definition).count() > decoded;
+    names.extend(
+        table
+            .columns
+            .iter()
+            .filter(|c| all || ordinals.contains(&c.ordinal_position))
+            .map(|c| c.name.as_str()),
+    );
+
+    table
+        .columns
+        .iter()
+        .filter(|c| names.contains(c.name.as_str()))
+        .map(|c| c.name.clone())
+        .collect()
+}
+
+/// Set `watched_columns` on every trigger whose table is in the graph.
+pub fn annotate_watched_columns(graph: &mut SchemaGraph) {
+    let tables: HashMap<&str, &TableNode> =
+        graph.tables.iter().map(|t| (t.id.as_str(), t)).collect();
+    for trigger in &mut graph.triggers {
+        if let Some(table) = tables.get(trigger.table_id.as_str()) {
+            trigger.watched_columns = watched_columns(&trigger.definition, table);
+        }
+    }
+}
+
+/// Triggers on `table_id` whose `watched_columns` include any of `columns`,
+/// each with the ones it watches.
+pub fn triggers_watching<'a>(
+    graph: &'a SchemaGraph,
+    table_id: &str,
+    columns: &[Column],
+) -> Vec<(&'a str, Vec<&'a str>)> {
+    graph
+        .triggers
+        .iter()
+        .filter(|t| t.table_id == table_id)
+        .filter_map(|t| {
+            let watched: Vec<&str> = t
+                .watched_columns
+                .iter()
+                .filter(|w| columns.iter().any(|c| c.name.eq_ignore_ascii_case(w)))
+                .map(String::as_str)
+                .collect();
+            (!watched.is_empty()).then_some((t.id.as_str(), watched))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> TableNode {
+        TableNode {
+            id: "dbo.Orders".to_string(),
+            name: "Orders".to_string(),
+            schema: "dbo".into(),
+            columns: ["Id", "Status", "Total", "Note"]
+                .iter()
+                .enumerate()
+                .map(|(i, name)| Column {
+                    name: name.to_string(),
+                    data_type: "int".into(),
+                    ordinal_position: i as u32 + 1,
+                    ..Default::default()
+                })
+                .collect(),
+            source: None,
+            metadata: None,
+            change_capture: None,
+        }
+    }
+
+    #[test]
+    fn update_and_columns_updated_tests_map_to_columns() {
+        let table = table();
+        assert_eq!(
+            watched_columns("IF UPDATE([status]) OR UPDATE(Missing) RETURN;", &table),
+            vec!["Status"]
+        );
+        // 0x0A marks the second and fourth columns
+        assert_eq!(
+            watched_columns("IF (COLUMNS_UPDATED() & 0x0A) > 0", &table),
+            vec!["Status", "Note"]
+        );
+        assert_eq!(
+            watched_columns("IF SUBSTRING(COLUMNS_UPDATED(), 1, 1) & 4 = 4", &table),
+            vec!["Total"]
+        );
+        assert_eq!(
+            watched_columns("SET @mask = COLUMNS_UPDATED();", &table).len(),
+            4
+        );
+        assert!(watched_columns("UPDATE dbo.Orders SET Note = NULL", &table).is_empty());
+        // Out-of-range bytes match no column rather than overflowing
+        assert!(watched_columns(
+            "IF SUBSTRING(COLUMNS_UPDATED(), 4294967295, 1) & 1 = 1",
+            &table
+        )
+        .is_empty());
+    }
+}
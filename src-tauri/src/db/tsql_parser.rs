@@ -0,0 +1,337 @@
+//! Procedure and trigger bodies read with sqlparser's MS SQL dialect, built
+//! with the `deep-parse` feature.
+//!
+//! sqlparser doesn't cover all of T-SQL (control flow, `BEGIN TRY`, most
+//! `SET` options), so the body is read one statement at a time: anything it
+//! can't parse is skipped up to the next token that starts a statement,
+//! which also walks into `IF`/`WHILE`/`BEGIN ... END` blocks.
+
+use std::collections::{BTreeSet, HashMap};
+use std::ops::ControlFlow;
+
+use sqlparser::ast::{Expr, ObjectName, Statement, TableFactor, Visit, Visitor};
+use sqlparser::dialect::MsSqlDialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::Token;
+
+use crate::db::schema_loader::resolve_reference;
+use crate::db::split_multipart;
+use crate::types::{ColumnSource, DefinitionAnalysis, WorkingTable, WorkingTableKind};
+
+/// Words that can begin a statement sqlparser understands; parsing is only
+/// attempted from one of these.
+const STATEMENT_STARTS: &[&str] = &[
+    "SELECT", "INSERT", "UPDATE", "DELETE", "MERGE", "WITH", "DECLARE", "SET", "CREATE", "DROP",
+    "TRUNCATE", "EXEC", "EXECUTE",
+];
+
+/// Offset just past the `AS` that ends the `CREATE PROCEDURE`/`CREATE
+/// TRIGGER` header, skipping `EXECUTE AS` clauses.
+fn body_start(definition: &str) -> usize {
+    let upper = definition.to_ascii_uppercase();
+    let bytes = upper.as_bytes();
+    let is_word = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'@' || b == b'#';
+    let mut from = 0;
+    while let Some(found) = upper[from..].find("AS") {
+        let start = from + found;
+        let end = start + 2;
+        from = end;
+        let bounded = (start == 0 || !is_word(bytes[start - 1]))
+            && (end == bytes.len() || !is_word(bytes[end]));
+        if !bounded {
+            continue;
+        }
+        let before = upper[..start].trim_end();
+        if before.ends_with("EXECUTE") || before.ends_with("EXEC") {
+            continue;
+        }
+        return end;
+    }
+    0
+}
+
+fn last_part(name: &ObjectName) -> String {
+    split_multipart(&name.to_string()).pop().unwrap_or_default()
+}
+
+/// Collects references from one statement at a time.
+struct Collector<'a> {
+    name_to_id: &'a HashMap<String, String>,
+    reads: BTreeSet<String>,
+    writes: BTreeSet<String>,
+    temp_tables: BTreeSet<String>,
+    columns: BTreeSet<(String, String)>,
+    /// Per statement: the next relation is the DML target.
+    expect_target: bool,
+    /// Per statement: a target named by alias (`UPDATE o ... FROM dbo.Orders o`).
+    target_alias: Option<String>,
+    /// Per statement: lowercase alias or bare name to table ID.
+    aliases: HashMap<String, String>,
+    /// Per statement: column references as (qualifier, column).
+    pending: Vec<(Option<String>, String)>,
+}
+
+impl Collector<'_> {
+    fn begin(&mut self, statement: &Statement) {
+        self.expect_target = matches!(
+            statement,
+            Statement::Insert(..)
+                | Statement::Update { .. }
+                | Statement::Delete(..)
+                | Statement::Merge { .. }
+        );
+        self.target_alias = None;
+        self.aliases.clear();
+        self.pending.clear();
+    }
+
+    fn finish(&mut self) {
+        if let Some(id) = self
+            .target_alias
+            .take()
+            .and_then(|alias| self.aliases.get(&alias))
+        {
+            self.writes.insert(id.clone());
+        }
+        let single: BTreeSet<&String> = self.aliases.values().collect();
+        for (qualifier, column) in self.pending.drain(..) {
+            let table = match qualifier {
+                Some(qualifier) => self.aliases.get(&qualifier),
+                None if single.len() == 1 => single.first().copied(),
+                None => None,
+            };
+            if let Some(table) = table {
+                self.columns.insert((table.clone(), column));
+            }
+        }
+    }
+}
+
+impl Visitor for Collector<'_> {
+    type Break = ();
+
+    fn pre_visit_relation(&mut self, relation: &ObjectName) -> ControlFlow<()> {
+        let name = last_part(relation);
+        let target = std::mem::take(&mut self.expect_target);
+        if name.starts_with('#') {
+            self.temp_tables.insert(name);
+            return ControlFlow::Continue(());
+        }
+        match resolve_reference(&relation.to_string(), self.name_to_id) {
+            Some(id) if target => {
+                self.writes.insert(id.clone());
+            }
+            Some(id) => {
+                self.reads.insert(id.clone());
+            }
+            None if target => self.target_alias = Some(name.to_lowercase()),
+            None => {}
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_table_factor(&mut self, factor: &TableFactor) -> ControlFlow<()> {
+        if let TableFactor::Table { name, alias, .. } = factor {
+            if let Some(id) = resolve_reference(&name.to_string(), self.name_to_id) {
+                self.aliases
+                    .insert(last_part(name).to_lowercase(), id.clone());
+                if let Some(alias) = alias {
+                    self.aliases
+                        .insert(alias.name.value.to_lowercase(), id.clone());
+                }
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<()> {
+        match expr {
+            Expr::CompoundIdentifier(parts) if parts.len() >= 2 => {
+                let qualifier = parts[parts.len() - 2].value.to_lowercase();
+                let column = parts[parts.len() - 1].value.clone();
+                self.pending.push((Some(qualifier), column));
+            }
+            Expr::Identifier(ident) if !ident.value.starts_with('@') => {
+                self.pending.push((None, ident.value.clone()));
+            }
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// What the parser made of a procedure or trigger body.
+pub struct ParsedDefinition {
+    pub reads: Vec<String>,
+    pub writes: Vec<String>,
+    pub analysis: DefinitionAnalysis,
+    /// Some statements could not be parsed and were skipped, so tables they
+    /// use are missing from `reads` and `writes`.
+    pub partial: bool,
+}
+
+/// Read a procedure or trigger body. `None` when not a single statement
+/// could be parsed, so the caller falls back to pattern matching.
+pub fn parse_definition(
+    definition: &str,
+    name_to_id: &HashMap<String, String>,
+) -> Option<ParsedDefinition> {
+    let dialect = MsSqlDialect {};
+    let mut parser = Parser::new(&dialect)
+        .try_with_sql(&definition[body_start(definition)..])
+        .ok()?;
+    let mut collector = Collector {
+        name_to_id,
+        reads: BTreeSet::new(),
+        writes: BTreeSet::new(),
+        temp_tables: BTreeSet::new(),
+        columns: BTreeSet::new(),
+        expect_target: false,
+        target_alias: None,
+        aliases: HashMap::new(),
+        pending: Vec::new(),
+    };
+    let mut parsed = 0;
+    let mut skipped = 0;
+    let mut dynamic_sql = false;
+
+    loop {
+        let word = match parser.peek_token().token {
+            Token::EOF => break,
+            Token::Word(word) => word.value.to_ascii_uppercase(),
+            _ => {
+                parser.next_token();
+                continue;
+            }
+        };
+        if !STATEMENT_STARTS.contains(&word.as_str()) {
+            parser.next_token();
+            continue;
+        }
+        // `EXEC (@sql)` runs a string; sqlparser only reads `EXEC name ...`
+        if (word == "EXEC" || word == "EXECUTE") && parser.peek_nth_token(1).token == Token::LParen
+        {
+            dynamic_sql = true;
+            parser.next_token();
+            continue;
+        }
+        match parser.parse_statement() {
+            Ok(statement) => {
+                parsed += 1;
+                if matches!(statement, Statement::Execute { .. })
+                    && statement
+                        .to_string()
+                        .to_ascii_lowercase()
+                        .contains("sp_executesql")
+                {
+                    dynamic_sql = true;
+                }
+                collector.begin(&statement);
+                let _ = statement.visit(&mut collector);
+                collector.finish();
+            }
+            Err(_) => {
+                skipped += 1;
+                parser.next_token();
+            }
+        }
+    }
+
+    if parsed == 0 {
+        return None;
+    }
+    let analysis = DefinitionAnalysis {
+        parsed: true,
+        referenced_columns: collector
+            .columns
+            .into_iter()
+            .map(|(table, column)| ColumnSource { table, column })
+            .collect(),
+        working_tables: collector
+            .temp_tables
+            .into_iter()
+            .map(|name| WorkingTable {
+                kind: WorkingTableKind::of(&name),
+                name,
+                columns: Vec::new(),
+            })
+            .collect(),
+        dynamic_sql,
+        ..Default::default()
+    };
+    Some(ParsedDefinition {
+        reads: collector.reads.into_iter().collect(),
+        writes: collector.writes.into_iter().collect(),
+        analysis,
+        partial: skipped > 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup() -> HashMap<String, String> {
+        ["dbo.Orders", "dbo.Customers", "dbo.OrderArchive"]
+            .iter()
+            .flat_map(|id| {
+                let name = id.trim_start_matches("dbo.");
+                [
+                    (id.to_string(), id.to_string()),
+                    (name.to_string(), id.to_string()),
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn procedure_body_yields_tables_columns_and_flags() {
+        let definition = "CREATE PROCEDURE dbo.usp_Archive @Days int
+WITH EXECUTE AS OWNER
+AS
+BEGIN
+    SET NOCOUNT ON;
+    IF EXISTS (SELECT 1 FROM dbo.Orders o WHERE o.CreatedAt < GETDATE() - @Days)
+    BEGIN
+        CREATE TABLE #old (Id int);
+        INSERT INTO #old (Id) SELECT o.Id FROM dbo.Orders o JOIN Customers c ON c.Id = o.CustomerId;
+        INSERT INTO dbo.OrderArchive (Id) SELECT Id FROM #old;
+        UPDATE o SET o.Archived = 1 FROM dbo.Orders o JOIN #old t ON t.Id = o.Id;
+    END
+    EXEC (@sql);
+END";
+        let ParsedDefinition {
+            reads,
+            writes,
+            analysis,
+            ..
+        } = parse_definition(definition, &lookup()).expect("parsed");
+
+        assert_eq!(reads, vec!["dbo.Customers", "dbo.Orders"]);
+        assert_eq!(writes, vec!["dbo.OrderArchive", "dbo.Orders"]);
+        assert_eq!(analysis.working_tables.len(), 1);
+        assert_eq!(analysis.working_tables[0].name, "#old");
+        assert!(analysis.dynamic_sql);
+        assert!(analysis
+            .referenced_columns
+            .iter()
+            .any(|c| c.table == "dbo.Orders" && c.column == "CustomerId"));
+    }
+
+    #[test]
+    fn skipped_statements_fall_back_to_patterns() {
+        let definition = "CREATE PROCEDURE dbo.usp_Sync AS
+BEGIN
+    SELECT Id FROM dbo.Orders;
+    INSERT INTO dbo.OrderArchive WITH (TABLOCK) (Id) VALUES (1);
+END";
+        let parsed = parse_definition(definition, &lookup()).expect("parsed");
+        assert!(parsed.partial);
+        assert_eq!(parsed.reads, vec!["dbo.Orders"]);
+
+        let (reads, writes, analysis) = crate::db::analyze_definition(definition, &lookup());
+        assert!(analysis.parsed);
+        assert!(reads.contains(&"dbo.Orders".to_string()));
+        assert_eq!(writes, vec!["dbo.OrderArchive"]);
+    }
+}
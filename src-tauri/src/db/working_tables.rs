@@ -0,0 +1,287 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::db::split_multipart;
+use crate::types::{DefinitionAnalysis, WorkingColumn, WorkingTable, WorkingTableKind};
+
+static COMMENTS: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)--[^\n]*|/\*.*?\*/").unwrap());
+
+static STRINGS: Lazy<Regex> = Lazy::new(|| Regex::new(r"N?'(?:[^']|'')*'").unwrap());
+
+static CREATE_TEMP: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\bCREATE\s+TABLE\s+(##?\w+)\s*\(").unwrap());
+
+static DECLARE_TABLE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\bDECLARE\s+(@\w+)\s+(?:AS\s+)?TABLE\s*\(").unwrap());
+
+static SELECT_INTO: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\bINTO\s+(##?\w+)\s+FROM\b").unwrap());
+
+static INSERT_COLUMNS: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\bINSERT\s+(?:INTO\s+)?(##?\w+|@\w+)\s*\(").unwrap());
+
+static SELECT_KEYWORD: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\bSELECT\b").unwrap());
+
+/// `DISTINCT`, `TOP (n)` and `TOP n PERCENT` ahead of a select list.
+static SELECT_PREFIX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^\s*(?:ALL\s+|DISTINCT\s+)?(?:TOP\s*(?:\(\s*[^)]*\)|\d+)(?:\s+PERCENT)?(?:\s+WITH\s+TIES)?\s+)?")
+        .unwrap()
+});
+
+static TEMP_REFERENCE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:^|[^\w@#$])(##?[A-Za-z_]\w*)").unwrap());
+
+/// `alias = expr` in a select list.
+static LEADING_ALIAS: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*(\[[^\]]+\]|\w+)\s*=[^=]").unwrap());
+
+/// Trailing alias, with or without `AS`, after a select list expression.
+static TRAILING_ALIAS: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)(?:\bAS\s+|[\s\)])(\[[^\]]+\]|\w+)\s*$").unwrap());
+
+static PLAIN_COLUMN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*((?:(?:\[[^\]]+\]|\w+)\s*\.\s*)*(?:\[[^\]]+\]|\w+))\s*$").unwrap()
+});
+
+/// Table elements that aren't columns.
+const TABLE_CONSTRAINTS: &[&str] = &[
+    "CONSTRAINT",
+    "PRIMARY",
+    "UNIQUE",
+    "INDEX",
+    "CHECK",
+    "FOREIGN",
+    "PERIOD",
+];
+
+/// Offset of the `)` closing a parenthesis opened just before `start`.
+pub(crate) fn closing_paren(text: &str, start: usize) -> usize {
+    let mut depth = 0usize;
+    for (i, b) in text.bytes().enumerate().skip(start) {
+        match b {
+            b'(' => depth += 1,
+            b')' if depth == 0 => return i,
+            b')' => depth -= 1,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+pub(crate) fn split_top_level(list: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut from = 0;
+    for (i, b) in list.bytes().enumerate() {
+        match b {
+            b'(' => depth += 1,
+            b')' => depth = depth.saturating_sub(1),
+            b',' if depth == 0 => {
+                parts.push(&list[from..i]);
+                from = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&list[from..]);
+    parts.into_iter().filter(|p| !p.trim().is_empty()).collect()
+}
+
+fn unbracket(name: &str) -> String {
+    split_multipart(name.trim()).pop().unwrap_or_default()
+}
+
+/// Columns of a `CREATE TABLE` or `DECLARE @t TABLE` element list.
+fn defined_columns(elements: &str) -> Vec<WorkingColumn> {
+    split_top_level(elements)
+        .into_iter()
+        .filter_map(|element| {
+            let element = element.trim();
+            let (name, rest) = match element.strip_prefix('[') {
+                Some(quoted) => {
+                    let end = quoted.find(']')?;
+                    (&quoted[..end], &quoted[end + 1..])
+                }
+                None => element.split_at(element.find(char::is_whitespace)?),
+            };
+            if TABLE_CONSTRAINTS.contains(&name.to_ascii_uppercase().as_str()) {
+                return None;
+            }
+            let rest = rest.trim_start();
+            let type_end = rest
+                .find(|c: char| c.is_whitespace() || c == '(')
+                .unwrap_or(rest.len());
+            let type_name = &rest[..type_end];
+            let data_type = if type_name.is_empty() || type_name.eq_ignore_ascii_case("AS") {
+                // Computed column
+                None
+            } else {
+                let after = rest[type_end..].trim_start();
+                let size = if after.starts_with('(') {
+                    &after[..=closing_paren(after, 1).min(after.len() - 1)]
+                } else {
+                    ""
+                };
+                Some(format!(
+                    "{}{}",
+                    type_name.to_lowercase(),
+                    size.replace(' ', "")
+                ))
+            };
+            Some(WorkingColumn {
+                name: name.to_string(),
+                data_type,
+            })
+        })
+        .collect()
+}
+
+/// Output names of a select list; `*` and unnamed expressions make the
+/// list unknown.
+fn select_list_columns(list: &str) -> Option<Vec<WorkingColumn>> {
+    let list = SELECT_PREFIX.replace(list, "");
+    split_top_level(&list)
+        .into_iter()
+        .map(|item| {
+            let name = if let Some(cap) = LEADING_ALIAS.captures(item) {
+                cap[1].to_string()
+            } else if let Some(cap) = PLAIN_COLUMN.captures(item) {
+                cap[1].to_string()
+            } else {
+                TRAILING_ALIAS.captures(item)?[1].to_string()
+            };
+            let name = unbracket(&name);
+            (name != "*").then_some(WorkingColumn {
+                name,
+                data_type: None,
+            })
+        })
+        .collect()
+}
+
+/// Add `name` to `tables` unless present, filling in `columns` when the
+/// existing entry has none.
+fn record(tables: &mut Vec<WorkingTable>, name: &str, columns: Vec<WorkingColumn>) {
+    match tables
+        .iter_mut()
+        .find(|t| t.name.eq_ignore_ascii_case(name))
+    {
+        Some(table) if table.columns.is_empty() => table.columns = columns,
+        Some(_) => {}
+        None => tables.push(WorkingTable {
+            name: name.to_string(),
+            kind: WorkingTableKind::of(name),
+            columns,
+        }),
+    }
+}
+
+/// Record the `#temp` tables and `@table` variables a body works with,
+/// with columns from `CREATE TABLE`, `DECLARE ... TABLE`, `SELECT ... INTO`
+/// or an `INSERT` column list, in that order of preference.
+pub fn detect_working_tables(definition: &str, analysis: &mut DefinitionAnalysis) {
+    let text = COMMENTS.replace_all(definition, " ");
+    let text = STRINGS.replace_all(&text, "''");
+    let tables = &mut analysis.working_tables;
+
+    for regex in [&*CREATE_TEMP, &*DECLARE_TABLE] {
+        for cap in regex.captures_iter(&text) {
+            let start = cap.get(0).unwrap().end();
+            let end = closing_paren(&text, start);
+            record(tables, &cap[1], defined_columns(&text[start..end]));
+        }
+    }
+    for cap in SELECT_INTO.captures_iter(&text) {
+        let into = cap.get(0).unwrap().start();
+        let Some(select) = SELECT_KEYWORD.find_iter(&text[..into]).last() else {
+            continue;
+        };
+        let columns = select_list_columns(&text[select.end()..into]).unwrap_or_default();
+        record(tables, &cap[1], columns);
+    }
+    for cap in INSERT_COLUMNS.captures_iter(&text) {
+        let name = &cap[1];
+        // `@t` is only a table when declared as one
+        if name.starts_with('@') && !tables.iter().any(|t| t.name.eq_ignore_ascii_case(name)) {
+            continue;
+        }
+        let start = cap.get(0).unwrap().end();
+        let end = closing_paren(&text, start);
+        let columns = split_top_level(&text[start..end])
+            .into_iter()
+            .map(|c| WorkingColumn {
+                name: unbracket(c),
+                data_type: None,
+            })
+            .collect();
+        record(tables, name, columns);
+    }
+    for cap in TEMP_REFERENCE.captures_iter(&text) {
+        record(tables, &cap[1], Vec::new());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(definition: &str) -> Vec<WorkingTable> {
+        let mut analysis = DefinitionAnalysis::default();
+        detect_working_tables(definition, &mut analysis);
+        analysis.working_tables
+    }
+
+    fn columns(table: &WorkingTable) -> Vec<(&str, Option<&str>)> {
+        table
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.data_type.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn temp_tables_and_table_variables_with_columns() {
+        let tables = scan(
+            "CREATE PROCEDURE dbo.Report AS
+DECLARE @ids TABLE (Id int PRIMARY KEY, [Total Due] decimal(10, 2), Label AS 'x');
+CREATE TABLE #stage (Id int NOT NULL, Name nvarchar(50), CONSTRAINT PK_stage PRIMARY KEY (Id));
+SELECT TOP (10) o.Id, Total = o.Amount * 2, c.Name AS CustomerName INTO #top FROM dbo.Orders o;
+SELECT * INTO ##shared FROM dbo.Orders;
+INSERT INTO #later (A, [B]) SELECT 1, 2;
+INSERT INTO @missing (X) VALUES (1);
+-- #commented
+SELECT '#literal' FROM #stage JOIN #other ON 1 = 1;",
+        );
+        let summary: Vec<(&str, WorkingTableKind)> =
+            tables.iter().map(|t| (t.name.as_str(), t.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("#stage", WorkingTableKind::TempTable),
+                ("@ids", WorkingTableKind::TableVariable),
+                ("#top", WorkingTableKind::TempTable),
+                ("##shared", WorkingTableKind::GlobalTempTable),
+                ("#later", WorkingTableKind::TempTable),
+                ("#other", WorkingTableKind::TempTable),
+            ]
+        );
+        assert_eq!(
+            columns(&tables[0]),
+            vec![("Id", Some("int")), ("Name", Some("nvarchar(50)"))]
+        );
+        assert_eq!(
+            columns(&tables[1]),
+            vec![
+                ("Id", Some("int")),
+                ("Total Due", Some("decimal(10,2)")),
+                ("Label", None)
+            ]
+        );
+        assert_eq!(
+            columns(&tables[2]),
+            vec![("Id", None), ("Total", None), ("CustomerName", None)]
+        );
+        assert!(tables[3].columns.is_empty());
+        assert_eq!(columns(&tables[4]), vec![("A", None), ("B", None)]);
+    }
+}
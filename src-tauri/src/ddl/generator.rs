@@ -0,0 +1,248 @@
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+use crate::analysis::TargetDialect;
+use crate::ddl::type_mapping::{TypeMapper, TypeMapping};
+use crate::i18n::t;
+use crate::types::{RelationshipEdge, SchemaGraph};
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeMappingWarning {
+    pub table_id: String,
+    pub column: String,
+    pub source_type: String,
+    pub target_type: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DdlScript {
+    pub script: String,
+    pub warnings: Vec<TypeMappingWarning>,
+}
+
+pub(crate) fn quote(dialect: TargetDialect, name: &str) -> String {
+    match dialect {
+        TargetDialect::Postgres => format!("\"{}\"", name.replace('"', "\"\"")),
+        TargetDialect::MySql => format!("`{}`", name.replace('`', "``")),
+    }
+}
+
+fn column_list(dialect: TargetDialect, columns: &[&str]) -> String {
+    columns
+        .iter()
+        .map(|c| quote(dialect, c))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Foreign keys with one edge per column, regrouped into constraints.
+pub(crate) fn foreign_keys(graph: &SchemaGraph) -> Vec<(&str, &str, &str, Vec<&RelationshipEdge>)> {
+    let mut keys: Vec<(&str, &str, &str, Vec<&RelationshipEdge>)> = Vec::new();
+    for edge in graph.relationships.iter().filter(|r| !r.is_virtual) {
+        match keys
+            .iter_mut()
+            .find(|(id, from, to, _)| *id == edge.id && *from == edge.from && *to == edge.to)
+        {
+            Some((_, _, _, edges)) => edges.push(edge),
+            None => keys.push((&edge.id, &edge.from, &edge.to, vec![edge])),
+        }
+    }
+    keys
+}
+
+/// `CREATE TABLE` script for every table in `graph`, translated to `dialect`
+/// through the type-mapping table, followed by the foreign keys. Module code
+/// is T-SQL and is not translated.
+pub fn generate_ddl(
+    graph: &SchemaGraph,
+    dialect: TargetDialect,
+    overrides: &[TypeMapping],
+) -> DdlScript {
+    let mapper = TypeMapper::new(dialect, overrides);
+    let mut warnings = Vec::new();
+    let mut script = String::new();
+
+    let schemas: BTreeSet<&str> = graph.tables.iter().map(|t| &*t.schema).collect();
+    for schema in &schemas {
+        let _ = writeln!(
+            script,
+            "CREATE SCHEMA IF NOT EXISTS {};",
+            quote(dialect, schema)
+        );
+    }
+
+    let table_name =
+        |schema: &str, name: &str| format!("{}.{}", quote(dialect, schema), quote(dialect, name));
+    for table in &graph.tables {
+        let _ = writeln!(
+            script,
+            "\nCREATE TABLE {} (",
+            table_name(&table.schema, &table.name)
+        );
+        let mut lines = Vec::new();
+        for column in &table.columns {
+            let mapped = mapper.map(&column.data_type);
+            if let Some(message) = &mapped.warning {
+                warnings.push(TypeMappingWarning {
+                    table_id: table.id.clone(),
+                    column: column.name.clone(),
+                    source_type: column.data_type.to_string(),
+                    target_type: mapped.target.clone(),
+                    message: message.clone(),
+                });
+            }
+            lines.push(format!(
+                "    {} {}{}",
+                quote(dialect, &column.name),
+                mapped.target,
+                if column.is_nullable { "" } else { " NOT NULL" }
+            ));
+        }
+        let primary_key: Vec<&str> = table
+            .columns
+            .iter()
+            .filter(|c| c.is_primary_key)
+            .map(|c| c.name.as_str())
+            .collect();
+        if !primary_key.is_empty() {
+            lines.push(format!(
+                "    PRIMARY KEY ({})",
+                column_list(dialect, &primary_key)
+            ));
+        }
+        script.push_str(&lines.join(",\n"));
+        script.push_str("\n);\n");
+    }
+
+    let tables: HashMap<&str, (&str, &str)> = graph
+        .tables
+        .iter()
+        .map(|t| (t.id.as_str(), (&*t.schema, t.name.as_str())))
+        .collect();
+    let mut wrote_header = false;
+    for (id, from, to, edges) in foreign_keys(graph) {
+        let (Some(from_table), Some(to_table)) = (tables.get(from), tables.get(to)) else {
+            continue;
+        };
+        let from_columns: Vec<&str> = edges
+            .iter()
+            .filter_map(|e| e.from_column.as_deref())
+            .collect();
+        let to_columns: Vec<&str> = edges
+            .iter()
+            .filter_map(|e| e.to_column.as_deref())
+            .collect();
+        if from_columns.is_empty() || from_columns.len() != to_columns.len() {
+            continue;
+        }
+        if !wrote_header {
+            script.push('\n');
+            wrote_header = true;
+        }
+        let _ = writeln!(
+            script,
+            "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({});",
+            table_name(from_table.0, from_table.1),
+            quote(dialect, id),
+            column_list(dialect, &from_columns),
+            table_name(to_table.0, to_table.1),
+            column_list(dialect, &to_columns)
+        );
+    }
+
+    let modules = graph.views.len()
+        + graph.stored_procedures.len()
+        + graph.scalar_functions.len()
+        + graph.triggers.len();
+    if modules > 0 {
+        let _ = writeln!(
+            script,
+            "\n-- {}",
+            t!("ddl-modules-not-translated", count = modules)
+        );
+    }
+
+    DdlScript { script, warnings }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Column, TableNode};
+
+    fn column(name: &str, data_type: &str, pk: bool) -> Column {
+        Column {
+            name: name.to_string(),
+            data_type: data_type.into(),
+            is_primary_key: pk,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn composite_foreign_keys_become_one_constraint() {
+        let table = |name: &str, columns: Vec<Column>| TableNode {
+            id: format!("sales.{}", name),
+            name: name.to_string(),
+            schema: "sales".into(),
+            columns,
+            source: None,
+            metadata: None,
+            change_capture: None,
+        };
+        let edge = |column: &str| RelationshipEdge {
+            id: "FK_Lines_Orders".to_string(),
+            from: "sales.Lines".to_string(),
+            to: "sales.Orders".to_string(),
+            from_column: Some(column.to_string()),
+            to_column: Some(column.to_string()),
+            source: None,
+            is_virtual: false,
+            participation: None,
+        };
+        let graph = SchemaGraph {
+            tables: vec![
+                table(
+                    "Orders",
+                    vec![
+                        column("Region", "nchar(2)", true),
+                        column("OrderId", "int", true),
+                        column("PlacedAt", "datetimeoffset", false),
+                    ],
+                ),
+                table(
+                    "Lines",
+                    vec![
+                        column("Region", "nchar(2)", false),
+                        column("OrderId", "int", false),
+                    ],
+                ),
+            ],
+            relationships: vec![edge("Region"), edge("OrderId")],
+            ..Default::default()
+        };
+
+        let ddl = generate_ddl(&graph, TargetDialect::Postgres, &[]);
+        assert!(ddl
+            .script
+            .contains("CREATE SCHEMA IF NOT EXISTS \"sales\";"));
+        assert!(ddl
+            .script
+            .contains("    \"PlacedAt\" timestamptz(6) NOT NULL,"));
+        assert!(ddl
+            .script
+            .contains("    PRIMARY KEY (\"Region\", \"OrderId\")"));
+        assert!(ddl.script.contains(
+            "ALTER TABLE \"sales\".\"Lines\" ADD CONSTRAINT \"FK_Lines_Orders\" FOREIGN KEY (\"Region\", \"OrderId\") REFERENCES \"sales\".\"Orders\" (\"Region\", \"OrderId\");"
+        ));
+        assert_eq!(ddl.warnings.len(), 1);
+        assert_eq!(ddl.warnings[0].column, "PlacedAt");
+
+        let mysql = generate_ddl(&graph, TargetDialect::MySql, &[]);
+        assert!(mysql.script.contains("CREATE TABLE `sales`.`Orders` ("));
+    }
+}
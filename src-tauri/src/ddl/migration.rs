@@ -0,0 +1,321 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+
+use crate::db::quote_name;
+use crate::types::{SchemaGraph, TableNode};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MigrationFormat {
+    /// sqlcmd script copying between databases with `INSERT ... SELECT`.
+    InsertSelect,
+    /// Windows batch file exporting and importing native-format BCP files.
+    Bcp,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadStep {
+    pub table_id: String,
+    /// 0 for tables with no parents; otherwise one more than the deepest parent.
+    pub level: usize,
+    /// Part of a foreign key cycle; constraints are disabled around its load.
+    pub in_cycle: bool,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationKit {
+    pub load_order: Vec<LoadStep>,
+    pub script: String,
+}
+
+/// Parents before children (Kahn's algorithm, ties in ID order so the output
+/// is stable). Self-references are ignored; tables left over in a cycle are
+/// appended at the end and flagged.
+pub fn load_order(graph: &SchemaGraph) -> Vec<LoadStep> {
+    let mut parents: BTreeMap<&str, BTreeSet<&str>> = graph
+        .tables
+        .iter()
+        .map(|t| (t.id.as_str(), BTreeSet::new()))
+        .collect();
+    for edge in &graph.relationships {
+        if edge.is_virtual || edge.from == edge.to || !parents.contains_key(edge.to.as_str()) {
+            continue;
+        }
+        if let Some(set) = parents.get_mut(edge.from.as_str()) {
+            set.insert(edge.to.as_str());
+        }
+    }
+
+    let mut levels: HashMap<&str, usize> = HashMap::new();
+    let mut order = Vec::new();
+    loop {
+        let ready: Vec<&str> = parents
+            .iter()
+            .filter(|(_, p)| p.iter().all(|p| levels.contains_key(p)))
+            .map(|(id, _)| *id)
+            .collect();
+        if ready.is_empty() {
+            break;
+        }
+        for id in ready {
+            let level = parents[id].iter().map(|p| levels[p] + 1).max().unwrap_or(0);
+            levels.insert(id, level);
+            parents.remove(id);
+            order.push(LoadStep {
+                table_id: id.to_string(),
+                level,
+                in_cycle: false,
+            });
+        }
+    }
+
+    let max_level = order.iter().map(|s| s.level + 1).max().unwrap_or(0);
+    order.extend(parents.keys().map(|id| LoadStep {
+        table_id: id.to_string(),
+        level: max_level,
+        in_cycle: true,
+    }));
+    order
+}
+
+/// Columns that can be written. Computed and `rowversion` values are
+/// generated by the server and rejected on insert.
+fn insertable_columns(table: &TableNode) -> Vec<String> {
+    table
+        .columns
+        .iter()
+        .filter(|c| !c.is_computed && !matches!(&*c.data_type, "timestamp" | "rowversion"))
+        .map(|c| quote_name(&c.name))
+        .collect()
+}
+
+fn insert_select_script(order: &[LoadStep], tables: &HashMap<&str, &TableNode>) -> String {
+    let mut script = String::from(
+        ":setvar SourceDatabase \"SourceDb\"\n:setvar TargetDatabase \"TargetDb\"\n\nSET XACT_ABORT ON;\n",
+    );
+    let target = |table: &TableNode| {
+        format!(
+            "[$(TargetDatabase)].{}.{}",
+            quote_name(&table.schema),
+            quote_name(&table.name)
+        )
+    };
+    // Tables in a cycle reference each other, so their constraints stay off
+    // until every one of them is loaded
+    let cycle: Vec<&TableNode> = order
+        .iter()
+        .filter(|s| s.in_cycle)
+        .map(|s| tables[s.table_id.as_str()])
+        .collect();
+
+    for (i, step) in order.iter().enumerate() {
+        let table = tables[step.table_id.as_str()];
+        let name = target(table);
+        let columns = insertable_columns(table).join(", ");
+        let identity = table.columns.iter().any(|c| c.is_identity);
+
+        if step.in_cycle && i == order.len() - cycle.len() {
+            script.push_str(
+                "\n-- Foreign key cycle: constraints are off until all of it is loaded\n",
+            );
+            for table in &cycle {
+                let _ = writeln!(
+                    script,
+                    "ALTER TABLE {} NOCHECK CONSTRAINT ALL;",
+                    target(table)
+                );
+            }
+        }
+        let _ = writeln!(
+            script,
+            "\n-- {}. {} (level {})",
+            i + 1,
+            table.id,
+            step.level
+        );
+        if identity {
+            let _ = writeln!(script, "SET IDENTITY_INSERT {} ON;", name);
+        }
+        let _ = writeln!(
+            script,
+            "INSERT INTO {} ({})\nSELECT {}\nFROM [$(SourceDatabase)].{}.{};",
+            name,
+            columns,
+            columns,
+            quote_name(&table.schema),
+            quote_name(&table.name)
+        );
+        if identity {
+            let _ = writeln!(script, "SET IDENTITY_INSERT {} OFF;", name);
+        }
+    }
+    if !cycle.is_empty() {
+        script.push('\n');
+        for table in &cycle {
+            let _ = writeln!(
+                script,
+                "ALTER TABLE {} WITH CHECK CHECK CONSTRAINT ALL;",
+                target(table)
+            );
+        }
+    }
+    script
+}
+
+fn bcp_script(order: &[LoadStep], tables: &HashMap<&str, &TableNode>) -> String {
+    let mut script = String::from(
+        "@echo off\nset SOURCE_SERVER=source-server\nset SOURCE_DB=SourceDb\nset TARGET_SERVER=target-server\nset TARGET_DB=TargetDb\n\nrem Export (any order)\n",
+    );
+    let target = |table: &TableNode| format!("{}.{}", table.schema, table.name);
+    for step in order {
+        let table = tables[step.table_id.as_str()];
+        let _ = writeln!(
+            script,
+            "bcp \"%SOURCE_DB%.{}.{}\" out \"{}.dat\" -S %SOURCE_SERVER% -T -n",
+            quote_name(&table.schema),
+            quote_name(&table.name),
+            target(table)
+        );
+    }
+    script.push_str("\nrem Import (dependency order; -E keeps identity values)\n");
+    for step in order {
+        let table = tables[step.table_id.as_str()];
+        // bcp checks no constraints unless asked, so cycles load as-is
+        let _ = writeln!(
+            script,
+            "bcp \"%TARGET_DB%.{}.{}\" in \"{}.dat\" -S %TARGET_SERVER% -T -n -E{}",
+            quote_name(&table.schema),
+            quote_name(&table.name),
+            target(table),
+            if step.in_cycle {
+                ""
+            } else {
+                " -h \"CHECK_CONSTRAINTS\""
+            }
+        );
+    }
+    script
+}
+
+/// Load order plus a script template that copies every table in that order.
+pub fn migration_kit(graph: &SchemaGraph, format: MigrationFormat) -> MigrationKit {
+    let load_order = load_order(graph);
+    let tables: HashMap<&str, &TableNode> =
+        graph.tables.iter().map(|t| (t.id.as_str(), t)).collect();
+    let script = match format {
+        MigrationFormat::InsertSelect => insert_select_script(&load_order, &tables),
+        MigrationFormat::Bcp => bcp_script(&load_order, &tables),
+    };
+    MigrationKit { load_order, script }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Column, RelationshipEdge};
+
+    fn graph(tables: &[&str], edges: &[(&str, &str)]) -> SchemaGraph {
+        SchemaGraph {
+            tables: tables
+                .iter()
+                .map(|name| TableNode {
+                    id: format!("dbo.{}", name),
+                    name: name.to_string(),
+                    schema: "dbo".into(),
+                    columns: vec![
+                        Column {
+                            name: "Id".to_string(),
+                            data_type: "int".into(),
+                            ..Default::default()
+                        },
+                        Column {
+                            name: "Version".to_string(),
+                            data_type: "timestamp".into(),
+                            ..Default::default()
+                        },
+                        Column {
+                            name: "Label".to_string(),
+                            data_type: "nvarchar(20)".into(),
+                            is_computed: true,
+                            ..Default::default()
+                        },
+                    ],
+                    source: None,
+                    metadata: None,
+                    change_capture: None,
+                })
+                .collect(),
+            relationships: edges
+                .iter()
+                .map(|(from, to)| RelationshipEdge {
+                    id: format!("FK_{}_{}", from, to),
+                    from: format!("dbo.{}", from),
+                    to: format!("dbo.{}", to),
+                    from_column: None,
+                    to_column: None,
+                    source: None,
+                    is_virtual: false,
+                    participation: None,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parents_load_before_children_and_cycles_go_last() {
+        let graph = graph(
+            &["Lines", "Orders", "Customers", "Employees", "Departments"],
+            &[
+                ("Lines", "Orders"),
+                ("Orders", "Customers"),
+                ("Customers", "Customers"),
+                ("Employees", "Departments"),
+                ("Departments", "Employees"),
+            ],
+        );
+        let order: Vec<(String, usize, bool)> = load_order(&graph)
+            .into_iter()
+            .map(|s| (s.table_id, s.level, s.in_cycle))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("dbo.Customers".to_string(), 0, false),
+                ("dbo.Orders".to_string(), 1, false),
+                ("dbo.Lines".to_string(), 2, false),
+                ("dbo.Departments".to_string(), 3, true),
+                ("dbo.Employees".to_string(), 3, true),
+            ]
+        );
+
+        let mut graph = graph;
+        graph.tables[0].columns[0].is_identity = true;
+        let kit = migration_kit(&graph, MigrationFormat::InsertSelect);
+        assert!(kit.script.contains(
+            "INSERT INTO [$(TargetDatabase)].[dbo].[Customers] ([Id])\nSELECT [Id]\nFROM [$(SourceDatabase)].[dbo].[Customers];"
+        ));
+        assert!(kit.script.contains(
+            "SET IDENTITY_INSERT [$(TargetDatabase)].[dbo].[Lines] ON;\nINSERT INTO [$(TargetDatabase)].[dbo].[Lines]"
+        ));
+        assert!(kit
+            .script
+            .contains("SET IDENTITY_INSERT [$(TargetDatabase)].[dbo].[Lines] OFF;"));
+        assert!(!kit
+            .script
+            .contains("IDENTITY_INSERT [$(TargetDatabase)].[dbo].[Orders]"));
+
+        // Both cycle tables are loaded before either is checked again
+        let position = |text: &str| kit.script.find(text).unwrap();
+        let employees_off =
+            position("ALTER TABLE [$(TargetDatabase)].[dbo].[Employees] NOCHECK CONSTRAINT ALL;");
+        let departments_on = position(
+            "ALTER TABLE [$(TargetDatabase)].[dbo].[Departments] WITH CHECK CHECK CONSTRAINT ALL;",
+        );
+        assert!(employees_off < position("INSERT INTO [$(TargetDatabase)].[dbo].[Departments]"));
+        assert!(position("INSERT INTO [$(TargetDatabase)].[dbo].[Employees]") < departments_on);
+    }
+}
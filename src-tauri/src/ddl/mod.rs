@@ -0,0 +1,13 @@
+pub mod generator;
+pub mod migration;
+pub mod rebuild;
+pub mod refactor;
+pub mod rename;
+pub mod type_mapping;
+
+pub use generator::{generate_ddl, DdlScript};
+pub use migration::{migration_kit, MigrationFormat, MigrationKit};
+pub use rebuild::{rebuild_script, RebuildScript};
+pub use refactor::{refactor_design, CanvasRefactoring, RefactorResult};
+pub use rename::{plan_rename, RenamePlan};
+pub use type_mapping::{default_type_mappings, TypeMapping};
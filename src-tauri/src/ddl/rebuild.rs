@@ -0,0 +1,421 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write;
+
+use crate::db::quote_name;
+use crate::ddl::generator::foreign_keys;
+use crate::i18n::t;
+use crate::types::{Column, RelationshipEdge, SchemaGraph};
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RebuildScript {
+    pub script: String,
+    /// Object IDs in the order they are created; drops run in reverse.
+    pub create_order: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Declared in creation order, which also breaks ties between objects at
+/// the same dependency depth.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum ObjectKind {
+    Table,
+    View,
+    Function,
+    Procedure,
+    Trigger,
+}
+
+impl ObjectKind {
+    fn keyword(self) -> &'static str {
+        match self {
+            ObjectKind::Table => "TABLE",
+            ObjectKind::View => "VIEW",
+            ObjectKind::Function => "FUNCTION",
+            ObjectKind::Procedure => "PROCEDURE",
+            ObjectKind::Trigger => "TRIGGER",
+        }
+    }
+}
+
+struct GraphObject<'a> {
+    kind: ObjectKind,
+    schema: &'a str,
+    name: &'a str,
+    /// Module text; tables are scripted from their columns instead.
+    definition: &'a str,
+    columns: &'a [Column],
+    depends_on: Vec<&'a str>,
+}
+
+fn graph_objects(graph: &SchemaGraph) -> BTreeMap<&str, GraphObject<'_>> {
+    let mut objects = BTreeMap::new();
+    let module = |kind, schema, name, definition, depends_on| GraphObject {
+        kind,
+        schema,
+        name,
+        definition,
+        columns: &[],
+        depends_on,
+    };
+    for table in &graph.tables {
+        objects.insert(
+            table.id.as_str(),
+            GraphObject {
+                kind: ObjectKind::Table,
+                schema: &table.schema,
+                name: &table.name,
+                definition: "",
+                columns: &table.columns,
+                depends_on: Vec::new(),
+            },
+        );
+    }
+    for view in &graph.views {
+        let depends_on = view.referenced_tables.iter().map(String::as_str).collect();
+        objects.insert(
+            view.id.as_str(),
+            module(
+                ObjectKind::View,
+                &view.schema,
+                &view.name,
+                &view.definition,
+                depends_on,
+            ),
+        );
+    }
+    for function in &graph.scalar_functions {
+        let depends_on = function
+            .referenced_tables
+            .iter()
+            .chain(&function.affected_tables)
+            .map(String::as_str)
+            .collect();
+        objects.insert(
+            function.id.as_str(),
+            module(
+                ObjectKind::Function,
+                &function.schema,
+                &function.name,
+                &function.definition,
+                depends_on,
+            ),
+        );
+    }
+    for procedure in &graph.stored_procedures {
+        let depends_on = procedure
+            .referenced_tables
+            .iter()
+            .chain(&procedure.affected_tables)
+            .map(String::as_str)
+            .collect();
+        objects.insert(
+            procedure.id.as_str(),
+            module(
+                ObjectKind::Procedure,
+                &procedure.schema,
+                &procedure.name,
+                &procedure.definition,
+                depends_on,
+            ),
+        );
+    }
+    for trigger in &graph.triggers {
+        let depends_on = std::iter::once(&trigger.table_id)
+            .chain(&trigger.referenced_tables)
+            .chain(&trigger.affected_tables)
+            .map(String::as_str)
+            .collect();
+        objects.insert(
+            trigger.id.as_str(),
+            module(
+                ObjectKind::Trigger,
+                &trigger.schema,
+                &trigger.name,
+                &trigger.definition,
+                depends_on,
+            ),
+        );
+    }
+    objects
+}
+
+/// Dependencies first. Objects at the same depth are created tables first,
+/// then views, functions, procedures and triggers, each in ID order; any
+/// left in a cycle come last.
+fn create_order<'a>(
+    objects: &BTreeMap<&'a str, GraphObject<'a>>,
+    selected: &BTreeSet<&'a str>,
+) -> Vec<&'a str> {
+    let mut pending: BTreeMap<&str, BTreeSet<&str>> = selected
+        .iter()
+        .map(|id| {
+            let depends_on = objects[id]
+                .depends_on
+                .iter()
+                .copied()
+                .filter(|d| d != id && selected.contains(d))
+                .collect();
+            (*id, depends_on)
+        })
+        .collect();
+
+    let mut order: Vec<&str> = Vec::new();
+    loop {
+        let mut ready: Vec<&str> = pending
+            .iter()
+            .filter(|(_, deps)| deps.iter().all(|d| order.contains(d)))
+            .map(|(id, _)| *id)
+            .collect();
+        if ready.is_empty() {
+            break;
+        }
+        ready.sort_by_key(|id| (objects[id].kind, *id));
+        for id in ready {
+            pending.remove(id);
+            order.push(id);
+        }
+    }
+    order.extend(pending.keys());
+    order
+}
+
+pub(super) fn create_table(columns: &[Column], name: &str) -> String {
+    let mut lines: Vec<String> = columns
+        .iter()
+        .map(|c| {
+            format!(
+                "    {} {}{}",
+                quote_name(&c.name),
+                c.data_type,
+                if c.is_nullable { " NULL" } else { " NOT NULL" }
+            )
+        })
+        .collect();
+    let primary_key: Vec<String> = columns
+        .iter()
+        .filter(|c| c.is_primary_key)
+        .map(|c| quote_name(&c.name))
+        .collect();
+    if !primary_key.is_empty() {
+        lines.push(format!("    PRIMARY KEY ({})", primary_key.join(", ")));
+    }
+    format!("CREATE TABLE {} (\n{}\n);", name, lines.join(",\n"))
+}
+
+/// T-SQL that drops the selected objects and creates them again: dependents
+/// are dropped before what they depend on and created after it. Triggers on
+/// selected tables are included, since dropping a table drops them. Foreign
+/// keys that reference a selected table are dropped up front and added back
+/// at the end. Tables come back empty, with only their columns, primary key
+/// and foreign keys.
+pub fn rebuild_script(graph: &SchemaGraph, object_ids: &[String]) -> Result<RebuildScript, String> {
+    let objects = graph_objects(graph);
+    let mut selected = BTreeSet::new();
+    for id in object_ids {
+        let Some((id, _)) = objects.get_key_value(id.as_str()) else {
+            return Err(t!("error-rebuild-unknown-object", id = id));
+        };
+        selected.insert(*id);
+    }
+    for trigger in &graph.triggers {
+        if selected.contains(trigger.table_id.as_str()) {
+            selected.insert(trigger.id.as_str());
+        }
+    }
+
+    let order = create_order(&objects, &selected);
+    let qualified = |id: &str| {
+        let object = &objects[id];
+        format!("{}.{}", quote_name(object.schema), quote_name(object.name))
+    };
+
+    let mut warnings = Vec::new();
+    for (id, object) in &objects {
+        if selected.contains(id) {
+            continue;
+        }
+        if let Some(dependency) = object.depends_on.iter().find(|d| selected.contains(*d)) {
+            warnings.push(t!(
+                "rebuild-unselected-dependent",
+                id = *id,
+                dependency = *dependency
+            ));
+        }
+    }
+
+    let tables: HashSet<&str> = order
+        .iter()
+        .copied()
+        .filter(|id| objects[id].kind == ObjectKind::Table)
+        .collect();
+    let keys: Vec<_> = foreign_keys(graph)
+        .into_iter()
+        .filter(|(_, from, to, _)| {
+            (tables.contains(from) || tables.contains(to))
+                && objects.contains_key(from)
+                && objects.contains_key(to)
+        })
+        .collect();
+
+    let mut script = String::new();
+    let _ = writeln!(script, "-- {}", t!("rebuild-header", count = order.len()));
+    if !tables.is_empty() {
+        let _ = writeln!(script, "-- {}", t!("rebuild-tables-recreated-empty"));
+    }
+
+    script.push('\n');
+    for (name, from, to, _) in &keys {
+        if tables.contains(to) {
+            let _ = writeln!(
+                script,
+                "ALTER TABLE {} DROP CONSTRAINT IF EXISTS {};",
+                qualified(from),
+                quote_name(name)
+            );
+        }
+    }
+    for id in order.iter().rev() {
+        let _ = writeln!(
+            script,
+            "DROP {} IF EXISTS {};",
+            objects[id].kind.keyword(),
+            qualified(id)
+        );
+    }
+    script.push_str("GO\n");
+
+    for id in &order {
+        let object = &objects[id];
+        script.push('\n');
+        if object.kind == ObjectKind::Table {
+            script.push_str(&create_table(object.columns, &qualified(id)));
+        } else if object.definition.trim().is_empty() {
+            warnings.push(t!("rebuild-missing-definition", id = *id));
+            let _ = write!(script, "-- {}", t!("rebuild-missing-definition", id = *id));
+        } else {
+            script.push_str(object.definition.trim());
+        }
+        script.push_str("\nGO\n");
+    }
+
+    if !keys.is_empty() {
+        script.push('\n');
+    }
+    for (name, from, to, edges) in &keys {
+        let columns = |column: fn(&RelationshipEdge) -> Option<&str>| {
+            edges
+                .iter()
+                .filter_map(|e| column(e).map(quote_name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let _ = writeln!(
+            script,
+            "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({});",
+            qualified(from),
+            quote_name(name),
+            columns(|e| e.from_column.as_deref()),
+            qualified(to),
+            columns(|e| e.to_column.as_deref())
+        );
+    }
+
+    Ok(RebuildScript {
+        script,
+        create_order: order.iter().map(|id| id.to_string()).collect(),
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+    use crate::types::{TableNode, ViewNode};
+
+    fn table(name: &str) -> TableNode {
+        TableNode {
+            id: format!("rpt.{}", name),
+            name: name.to_string(),
+            schema: "rpt".into(),
+            columns: vec![Column {
+                name: "Id".to_string(),
+                data_type: "int".into(),
+                is_primary_key: true,
+                ..Default::default()
+            }],
+            source: None,
+            metadata: None,
+            change_capture: None,
+        }
+    }
+
+    fn view(name: &str, references: &[&str]) -> ViewNode {
+        ViewNode {
+            id: format!("rpt.{}", name),
+            name: name.to_string(),
+            schema: "rpt".into(),
+            columns: Vec::new(),
+            definition: format!("CREATE VIEW rpt.{} AS SELECT 1 AS x", name),
+            referenced_tables: references.iter().map(|r| r.to_string()).collect(),
+            source: None,
+            metadata: None,
+            is_indexed: false,
+        }
+    }
+
+    #[test]
+    fn views_are_dropped_before_and_created_after_their_tables() {
+        let graph = SchemaGraph {
+            tables: vec![table("Sales")],
+            views: vec![
+                view("vMonthly", &["rpt.vDaily"]),
+                view("vDaily", &["rpt.Sales"]),
+                view("vUnselected", &["rpt.Sales"]),
+            ],
+            ..Default::default()
+        };
+        let ids: Vec<String> = ["rpt.vMonthly", "rpt.Sales", "rpt.vDaily"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let rebuild = rebuild_script(&graph, &ids).unwrap();
+        assert_eq!(
+            rebuild.create_order,
+            vec!["rpt.Sales", "rpt.vDaily", "rpt.vMonthly"]
+        );
+        let position = |text: &str| rebuild.script.find(text).expect(text);
+        assert!(
+            position("DROP VIEW IF EXISTS [rpt].[vMonthly]")
+                < position("DROP VIEW IF EXISTS [rpt].[vDaily]")
+        );
+        assert!(
+            position("DROP VIEW IF EXISTS [rpt].[vDaily]")
+                < position("DROP TABLE IF EXISTS [rpt].[Sales]")
+        );
+        assert!(position("CREATE TABLE [rpt].[Sales]") < position("CREATE VIEW rpt.vDaily"));
+        assert!(position("CREATE VIEW rpt.vDaily") < position("CREATE VIEW rpt.vMonthly"));
+        assert_eq!(rebuild.warnings.len(), 1);
+        assert!(rebuild.warnings[0].contains("rpt.vUnselected"));
+
+        assert!(rebuild_script(&graph, &["rpt.Missing".to_string()]).is_err());
+    }
+
+    #[test]
+    fn foreign_keys_into_selected_tables_are_dropped_and_restored() {
+        let graph = generate_mock_schema("small");
+        let edge = graph
+            .relationships
+            .iter()
+            .find(|r| r.from != r.to)
+            .expect("mock schema has a foreign key");
+
+        let rebuild = rebuild_script(&graph, std::slice::from_ref(&edge.to)).unwrap();
+        let drop = format!("DROP CONSTRAINT IF EXISTS {}", quote_name(&edge.id));
+        let add = format!("ADD CONSTRAINT {}", quote_name(&edge.id));
+        assert!(rebuild.script.find(&drop).unwrap() < rebuild.script.find(&add).unwrap());
+    }
+}
@@ -0,0 +1,1091 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use crate::db::{object_id, quote_name, triggers_watching};
+use crate::ddl::generator::foreign_keys;
+use crate::ddl::rebuild::create_table;
+use crate::i18n::t;
+use crate::types::{Column, RelationshipEdge, SchemaGraph, TableNode};
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CanvasRefactoring {
+    /// Put the table's columns in this order; every column listed once.
+    #[serde(rename_all = "camelCase")]
+    ReorderColumns {
+        table_id: String,
+        columns: Vec<String>,
+    },
+    /// Move `columns` to a new table in the same schema that shares the
+    /// primary key and references the original through a new foreign key.
+    #[serde(rename_all = "camelCase")]
+    SplitTable {
+        table_id: String,
+        new_table: String,
+        columns: Vec<String>,
+    },
+    /// Move `columns` to a table joined to `from` by a foreign key.
+    #[serde(rename_all = "camelCase")]
+    MoveColumns {
+        from: String,
+        to: String,
+        columns: Vec<String>,
+    },
+    /// Fold the columns of `from` into `into` and drop `from`.
+    #[serde(rename_all = "camelCase")]
+    MergeTables { into: String, from: String },
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RefactorResult {
+    /// The design with the refactoring applied.
+    pub graph: SchemaGraph,
+    /// T-SQL that makes the same change to a live database, keeping data.
+    pub script: String,
+    pub warnings: Vec<String>,
+}
+
+/// Column pairs of the foreign key joining two tables, oriented as
+/// (`from` column, `to` column).
+struct Join {
+    pairs: Vec<(String, String)>,
+    /// `from` holds the foreign key, so several of its rows can share one
+    /// row of `to`.
+    from_is_child: bool,
+}
+
+fn join_between(graph: &SchemaGraph, from: &str, to: &str) -> Option<Join> {
+    let edge = graph.relationships.iter().find(|r| {
+        r.from_column.is_some()
+            && r.to_column.is_some()
+            && ((r.from == from && r.to == to) || (r.from == to && r.to == from))
+    })?;
+    let from_is_child = edge.from == from;
+    let pairs = graph
+        .relationships
+        .iter()
+        .filter(|r| r.id == edge.id && r.from == edge.from && r.to == edge.to)
+        .filter_map(|r| {
+            let (child, parent) = (r.from_column.clone()?, r.to_column.clone()?);
+            Some(if from_is_child {
+                (child, parent)
+            } else {
+                (parent, child)
+            })
+        })
+        .collect();
+    Some(Join {
+        pairs,
+        from_is_child,
+    })
+}
+
+fn table_index(graph: &SchemaGraph, id: &str) -> Result<usize, String> {
+    graph
+        .tables
+        .iter()
+        .position(|t| t.id == id)
+        .ok_or_else(|| t!("error-refactor-unknown-table", id = id))
+}
+
+fn qualified(table: &TableNode) -> String {
+    format!("{}.{}", quote_name(&table.schema), quote_name(&table.name))
+}
+
+fn qualified_id(graph: &SchemaGraph, id: &str) -> String {
+    graph
+        .tables
+        .iter()
+        .find(|t| t.id == id)
+        .map_or_else(|| quote_name(id), qualified)
+}
+
+fn quoted_list<'a>(names: impl IntoIterator<Item = &'a str>) -> String {
+    names
+        .into_iter()
+        .map(quote_name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn column_definition(column: &Column, nullable: bool) -> String {
+    format!(
+        "{} {}{}",
+        quote_name(&column.name),
+        column.data_type,
+        if nullable { " NULL" } else { " NOT NULL" }
+    )
+}
+
+/// Opens the transaction a data-moving script runs in. With XACT_ABORT, a
+/// failed copy rolls everything back and ends the batch before any drop.
+const BEGIN_COPY: &str = "SET XACT_ABORT ON;\nBEGIN TRANSACTION;\n";
+
+/// Opens a later batch of the same transaction: after a failed batch the
+/// transaction is gone, and the next one must not drop anything on its own.
+const IN_COPY: &str =
+    "IF @@TRANCOUNT = 0 THROW 50000, N'The copy failed and was rolled back', 1;\n";
+
+const COMMIT_COPY: &str = "COMMIT TRANSACTION;\nGO\n";
+
+/// `rowversion` values are generated by the server and can't be inserted.
+fn is_rowversion(column: &Column) -> bool {
+    let data_type = column.data_type.to_ascii_lowercase();
+    data_type == "timestamp" || data_type == "rowversion"
+}
+
+fn has_column(columns: &[Column], name: &str) -> bool {
+    columns.iter().any(|c| c.name.eq_ignore_ascii_case(name))
+}
+
+/// The named columns of `table` in the order given, each once.
+fn pick_columns(table: &TableNode, names: &[String]) -> Result<Vec<Column>, String> {
+    if names.is_empty() {
+        return Err(t!("error-refactor-no-columns"));
+    }
+    let mut picked: Vec<Column> = Vec::new();
+    for name in names {
+        let Some(column) = table
+            .columns
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+        else {
+            return Err(t!(
+                "error-refactor-unknown-column",
+                table = table.id.as_str(),
+                column = name.as_str()
+            ));
+        };
+        if !has_column(&picked, &column.name) {
+            picked.push(column.clone());
+        }
+    }
+    Ok(picked)
+}
+
+fn renumber(columns: &mut [Column]) {
+    for (i, column) in columns.iter_mut().enumerate() {
+        column.ordinal_position = i as u32 + 1;
+    }
+}
+
+/// Names of the foreign key constraints with an edge matching `touches`.
+fn keys_touching(
+    graph: &SchemaGraph,
+    touches: impl Fn(&RelationshipEdge) -> bool,
+) -> BTreeSet<String> {
+    graph
+        .relationships
+        .iter()
+        .filter(|r| !r.is_virtual && touches(r))
+        .map(|r| r.id.clone())
+        .collect()
+}
+
+fn drop_keys(script: &mut String, graph: &SchemaGraph, names: &BTreeSet<String>) {
+    for (name, from, _, _) in foreign_keys(graph) {
+        if names.contains(name) {
+            let _ = writeln!(
+                script,
+                "ALTER TABLE {} DROP CONSTRAINT {};",
+                qualified_id(graph, from),
+                quote_name(name)
+            );
+        }
+    }
+}
+
+/// Adds back the named keys as they stand in `graph`; keys the refactoring
+/// removed are skipped.
+fn add_keys(script: &mut String, graph: &SchemaGraph, names: &BTreeSet<String>) {
+    for (name, from, to, edges) in foreign_keys(graph) {
+        if !names.contains(name) {
+            continue;
+        }
+        let columns = |column: fn(&RelationshipEdge) -> Option<&str>| {
+            quoted_list(edges.iter().filter_map(|e| column(e)))
+        };
+        let _ = writeln!(
+            script,
+            "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({});",
+            qualified_id(graph, from),
+            quote_name(name),
+            columns(|e| e.from_column.as_deref()),
+            qualified_id(graph, to),
+            columns(|e| e.to_column.as_deref())
+        );
+    }
+}
+
+/// A warning naming the views, routines and triggers that use `table_id`,
+/// whose code the refactoring doesn't rewrite.
+fn dependents_warning(graph: &SchemaGraph, table_id: &str) -> Option<String> {
+    let uses = |tables: &[String]| tables.iter().any(|t| t == table_id);
+    let mut objects: Vec<&str> = graph
+        .views
+        .iter()
+        .filter(|v| uses(&v.referenced_tables))
+        .map(|v| v.id.as_str())
+        .chain(
+            graph
+                .stored_procedures
+                .iter()
+                .filter(|p| uses(&p.referenced_tables) || uses(&p.affected_tables))
+                .map(|p| p.id.as_str()),
+        )
+        .chain(
+            graph
+                .scalar_functions
+                .iter()
+                .filter(|f| uses(&f.referenced_tables) || uses(&f.affected_tables))
+                .map(|f| f.id.as_str()),
+        )
+        .chain(
+            graph
+                .triggers
+                .iter()
+                .filter(|t| {
+                    t.table_id == table_id || uses(&t.referenced_tables) || uses(&t.affected_tables)
+                })
+                .map(|t| t.id.as_str()),
+        )
+        .collect();
+    objects.sort_unstable();
+    objects.dedup();
+    (!objects.is_empty()).then(|| {
+        t!(
+            "refactor-dependent-modules",
+            table = table_id,
+            objects = objects.join(", ")
+        )
+    })
+}
+
+/// Warnings for the triggers on `table_id` that test for updates to `moved`
+/// columns, which stop firing those tests once the columns leave the table.
+fn watching_triggers_warnings(
+    graph: &SchemaGraph,
+    table_id: &str,
+    moved: &[Column],
+) -> Vec<String> {
+    triggers_watching(graph, table_id, moved)
+        .into_iter()
+        .map(|(trigger, columns)| {
+            t!(
+                "refactor-trigger-watches-moved",
+                trigger = trigger,
+                columns = columns.join(", "),
+                table = table_id
+            )
+        })
+        .collect()
+}
+
+/// Points relationships, links and classifications on `moved` columns of
+/// `from` at the table the columns now live in.
+fn follow_columns(graph: &mut SchemaGraph, from: &str, to: &str, moved: &[Column]) {
+    let is_moved = |table: &str, column: Option<&str>| {
+        table == from && column.is_some_and(|c| has_column(moved, c))
+    };
+    for r in &mut graph.relationships {
+        if is_moved(&r.from, r.from_column.as_deref()) {
+            r.from = to.to_string();
+        }
+        if is_moved(&r.to, r.to_column.as_deref()) {
+            r.to = to.to_string();
+        }
+    }
+    for link in &mut graph.links {
+        if is_moved(&link.from, link.from_column.as_deref()) {
+            link.from = to.to_string();
+        }
+        if is_moved(&link.to, link.to_column.as_deref()) {
+            link.to = to.to_string();
+        }
+    }
+    for c in &mut graph.classifications {
+        if is_moved(&c.object_id, c.column.as_deref()) {
+            c.object_id = to.to_string();
+        }
+    }
+}
+
+/// Fails when a moved column is a key of its table or already exists in `to`.
+fn check_movable(to: &TableNode, moved: &[Column], join: &Join) -> Result<(), String> {
+    for column in moved {
+        if column.is_primary_key || join.pairs.iter().any(|(f, _)| *f == column.name) {
+            return Err(t!(
+                "error-refactor-key-column",
+                column = column.name.as_str()
+            ));
+        }
+        if has_column(&to.columns, &column.name) {
+            return Err(t!(
+                "error-refactor-column-exists",
+                table = to.id.as_str(),
+                column = column.name.as_str()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Adds `moved` to `to` in both the design and the script, copying values
+/// across the join. Columns are added nullable and tightened after the copy,
+/// since existing rows have nothing to put in them until then. The new
+/// columns need a batch of their own, so the copy continues in the next.
+fn copy_columns(
+    script: &mut String,
+    result: &mut SchemaGraph,
+    from: &TableNode,
+    to: &TableNode,
+    moved: &[Column],
+    join: &Join,
+) {
+    for column in moved {
+        let _ = writeln!(
+            script,
+            "ALTER TABLE {} ADD {};",
+            qualified(to),
+            column_definition(column, true)
+        );
+    }
+    script.push_str("GO\n");
+    script.push_str(IN_COPY);
+    let assignments: Vec<String> = moved
+        .iter()
+        .map(|c| format!("target.{0} = source.{0}", quote_name(&c.name)))
+        .collect();
+    let on: Vec<String> = join
+        .pairs
+        .iter()
+        .map(|(f, t)| format!("target.{} = source.{}", quote_name(t), quote_name(f)))
+        .collect();
+    let _ = writeln!(
+        script,
+        "UPDATE target SET {} FROM {} AS target JOIN {} AS source ON {};",
+        assignments.join(", "),
+        qualified(to),
+        qualified(from),
+        on.join(" AND ")
+    );
+    for column in moved.iter().filter(|c| !c.is_nullable) {
+        let _ = writeln!(
+            script,
+            "ALTER TABLE {} ALTER COLUMN {};",
+            qualified(to),
+            column_definition(column, false)
+        );
+    }
+
+    follow_columns(result, &from.id, &to.id, moved);
+    if let Some(target) = result.tables.iter_mut().find(|t| t.id == to.id) {
+        let next = target
+            .columns
+            .iter()
+            .map(|c| c.ordinal_position)
+            .max()
+            .unwrap_or(0);
+        target
+            .columns
+            .extend(moved.iter().enumerate().map(|(i, c)| Column {
+                is_primary_key: false,
+                ordinal_position: next + i as u32 + 1,
+                ..c.clone()
+            }));
+    }
+}
+
+fn reorder_columns(
+    graph: &SchemaGraph,
+    table_id: &str,
+    order: &[String],
+) -> Result<RefactorResult, String> {
+    let index = table_index(graph, table_id)?;
+    let table = &graph.tables[index];
+    let out_of_order = || t!("error-refactor-column-order", table = table_id);
+    let mut columns: Vec<Column> = Vec::new();
+    for name in order {
+        match table
+            .columns
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+        {
+            Some(column) if !has_column(&columns, &column.name) => columns.push(column.clone()),
+            _ => return Err(out_of_order()),
+        }
+    }
+    if columns.len() != table.columns.len() {
+        return Err(out_of_order());
+    }
+    renumber(&mut columns);
+
+    // SQL Server only appends columns, so the table is copied into a new one
+    let keys = keys_touching(graph, |r| r.from == table_id || r.to == table_id);
+    let staging = format!(
+        "{}.{}",
+        quote_name(&table.schema),
+        quote_name(&format!("{}_reordered", table.name))
+    );
+    let names = quoted_list(
+        columns
+            .iter()
+            .filter(|c| !is_rowversion(c))
+            .map(|c| c.name.as_str()),
+    );
+    let mut script = String::from(BEGIN_COPY);
+    drop_keys(&mut script, graph, &keys);
+    script.push_str(&create_table(&columns, &staging));
+    script.push('\n');
+    let _ = writeln!(
+        script,
+        "INSERT INTO {} ({}) SELECT {} FROM {};",
+        staging,
+        names,
+        names,
+        qualified(table)
+    );
+    let _ = writeln!(script, "DROP TABLE {};", qualified(table));
+    let _ = writeln!(
+        script,
+        "EXEC sp_rename N'{}', N'{}';",
+        staging.replace('\'', "''"),
+        table.name.replace('\'', "''")
+    );
+
+    let mut result = graph.clone();
+    result.tables[index].columns = columns;
+    add_keys(&mut script, &result, &keys);
+    script.push_str(COMMIT_COPY);
+
+    let mut warnings = vec![t!("refactor-table-recreated", table = table_id)];
+    // Dropping the table dropped its triggers, so they are created again
+    for trigger in graph.triggers.iter().filter(|t| t.table_id == table_id) {
+        if trigger.definition.trim().is_empty() {
+            warnings.push(t!(
+                "refactor-trigger-not-recreated",
+                trigger = trigger.id.as_str(),
+                table = table_id
+            ));
+            continue;
+        }
+        let _ = writeln!(script, "{}\nGO", trigger.definition.trim());
+        if trigger.is_disabled {
+            let _ = writeln!(
+                script,
+                "ALTER TABLE {} DISABLE TRIGGER {};\nGO",
+                qualified(table),
+                quote_name(&trigger.name)
+            );
+        }
+    }
+    warnings.extend(dependents_warning(graph, table_id));
+    Ok(RefactorResult {
+        graph: result,
+        script,
+        warnings,
+    })
+}
+
+fn split_table(
+    graph: &SchemaGraph,
+    table_id: &str,
+    new_name: &str,
+    names: &[String],
+) -> Result<RefactorResult, String> {
+    let index = table_index(graph, table_id)?;
+    let table = &graph.tables[index];
+    let moved = pick_columns(table, names)?;
+    if let Some(column) = moved.iter().find(|c| c.is_primary_key) {
+        return Err(t!(
+            "error-refactor-key-column",
+            column = column.name.as_str()
+        ));
+    }
+    let key: Vec<Column> = table
+        .columns
+        .iter()
+        .filter(|c| c.is_primary_key)
+        .cloned()
+        .collect();
+    if key.is_empty() {
+        return Err(t!("error-refactor-no-primary-key", table = table_id));
+    }
+    let new_id = object_id(&[&table.schema, new_name]);
+    let taken = |id: &str| id.eq_ignore_ascii_case(&new_id);
+    if graph.tables.iter().any(|t| taken(&t.id)) || graph.views.iter().any(|v| taken(&v.id)) {
+        return Err(t!("error-refactor-table-exists", id = new_id.as_str()));
+    }
+
+    let mut columns: Vec<Column> = key
+        .iter()
+        .cloned()
+        .chain(moved.iter().map(|c| Column {
+            is_primary_key: false,
+            ..c.clone()
+        }))
+        .collect();
+    renumber(&mut columns);
+    let new_table = TableNode {
+        id: new_id.clone(),
+        name: new_name.to_string(),
+        schema: table.schema.clone(),
+        columns,
+        source: table.source.clone(),
+        metadata: None,
+        change_capture: None,
+    };
+
+    let is_moved = |table: &str, column: Option<&String>| {
+        table == table_id && column.is_some_and(|c| has_column(&moved, c))
+    };
+    let mut keys = keys_touching(graph, |r| {
+        is_moved(&r.from, r.from_column.as_ref()) || is_moved(&r.to, r.to_column.as_ref())
+    });
+
+    let mut result = graph.clone();
+    follow_columns(&mut result, table_id, &new_id, &moved);
+    result.tables[index]
+        .columns
+        .retain(|c| !has_column(&moved, &c.name));
+    let split_key = format!("FK_{}_{}", new_name, table.name);
+    result
+        .relationships
+        .extend(key.iter().map(|c| RelationshipEdge {
+            id: split_key.clone(),
+            from: new_id.clone(),
+            to: table_id.to_string(),
+            from_column: Some(c.name.clone()),
+            to_column: Some(c.name.clone()),
+            source: None,
+            is_virtual: false,
+            participation: None,
+        }));
+    result.tables.insert(index + 1, new_table.clone());
+
+    let copied = quoted_list(
+        new_table
+            .columns
+            .iter()
+            .filter(|c| !is_rowversion(c))
+            .map(|c| c.name.as_str()),
+    );
+    let mut script = String::from(BEGIN_COPY);
+    script.push_str(&create_table(&new_table.columns, &qualified(&new_table)));
+    script.push('\n');
+    let _ = writeln!(
+        script,
+        "INSERT INTO {} ({}) SELECT {} FROM {};",
+        qualified(&new_table),
+        copied,
+        copied,
+        qualified(table)
+    );
+    drop_keys(&mut script, graph, &keys);
+    let _ = writeln!(
+        script,
+        "ALTER TABLE {} DROP COLUMN {};",
+        qualified(table),
+        quoted_list(moved.iter().map(|c| c.name.as_str()))
+    );
+    keys.insert(split_key);
+    add_keys(&mut script, &result, &keys);
+    script.push_str(COMMIT_COPY);
+
+    let mut warnings = watching_triggers_warnings(graph, table_id, &moved);
+    warnings.extend(dependents_warning(graph, table_id));
+    Ok(RefactorResult {
+        graph: result,
+        script,
+        warnings,
+    })
+}
+
+fn move_columns(
+    graph: &SchemaGraph,
+    from_id: &str,
+    to_id: &str,
+    names: &[String],
+) -> Result<RefactorResult, String> {
+    let from_index = table_index(graph, from_id)?;
+    let to_index = table_index(graph, to_id)?;
+    let (from, to) = (&graph.tables[from_index], &graph.tables[to_index]);
+    let moved = pick_columns(from, names)?;
+    let join = join_between(graph, from_id, to_id)
+        .filter(|_| from_id != to_id)
+        .ok_or_else(|| t!("error-refactor-not-related", from = from_id, to = to_id))?;
+    check_movable(to, &moved, &join)?;
+
+    let mut warnings = Vec::new();
+    if join.from_is_child {
+        warnings.push(t!("refactor-move-to-parent", from = from_id, to = to_id));
+    }
+    warnings.extend(watching_triggers_warnings(graph, from_id, &moved));
+    let is_moved = |table: &str, column: Option<&String>| {
+        table == from_id && column.is_some_and(|c| has_column(&moved, c))
+    };
+    let keys = keys_touching(graph, |r| {
+        is_moved(&r.from, r.from_column.as_ref()) || is_moved(&r.to, r.to_column.as_ref())
+    });
+
+    let mut result = graph.clone();
+    let mut script = String::from(BEGIN_COPY);
+    copy_columns(&mut script, &mut result, from, to, &moved, &join);
+    drop_keys(&mut script, graph, &keys);
+    let _ = writeln!(
+        script,
+        "ALTER TABLE {} DROP COLUMN {};",
+        qualified(from),
+        quoted_list(moved.iter().map(|c| c.name.as_str()))
+    );
+    result.tables[from_index]
+        .columns
+        .retain(|c| !has_column(&moved, &c.name));
+    add_keys(&mut script, &result, &keys);
+    script.push_str(COMMIT_COPY);
+
+    warnings.extend(dependents_warning(graph, from_id));
+    Ok(RefactorResult {
+        graph: result,
+        script,
+        warnings,
+    })
+}
+
+fn merge_tables(
+    graph: &SchemaGraph,
+    into_id: &str,
+    from_id: &str,
+) -> Result<RefactorResult, String> {
+    let from_index = table_index(graph, from_id)?;
+    let into_index = table_index(graph, into_id)?;
+    let (from, into) = (&graph.tables[from_index], &graph.tables[into_index]);
+    let join = join_between(graph, from_id, into_id)
+        .filter(|_| from_id != into_id)
+        .ok_or_else(|| t!("error-refactor-not-related", from = from_id, to = into_id))?;
+    // The join columns already have their values in `into`
+    let moved: Vec<Column> = from
+        .columns
+        .iter()
+        .filter(|c| !join.pairs.iter().any(|(f, _)| *f == c.name))
+        .map(|c| Column {
+            is_primary_key: false,
+            ..c.clone()
+        })
+        .collect();
+    check_movable(into, &moved, &join)?;
+
+    let mut warnings = Vec::new();
+    if join.from_is_child {
+        warnings.push(t!("refactor-move-to-parent", from = from_id, to = into_id));
+    }
+    let keys = keys_touching(graph, |r| r.from == from_id || r.to == from_id);
+    let dropped_triggers: Vec<&str> = graph
+        .triggers
+        .iter()
+        .filter(|t| t.table_id == from_id)
+        .map(|t| t.id.as_str())
+        .collect();
+    if !dropped_triggers.is_empty() {
+        warnings.push(t!(
+            "refactor-triggers-dropped",
+            triggers = dropped_triggers.join(", "),
+            table = from_id
+        ));
+    }
+
+    let mut result = graph.clone();
+    let mut script = String::from(BEGIN_COPY);
+    if !moved.is_empty() {
+        copy_columns(&mut script, &mut result, from, into, &moved, &join);
+    }
+    drop_keys(&mut script, graph, &keys);
+    let _ = writeln!(script, "DROP TABLE {};", qualified(from));
+
+    // What is left on `from` are its join columns, which map onto `into`'s
+    let mapped = |column: &mut Option<String>| {
+        if let Some(name) = column.as_mut() {
+            if let Some((_, t)) = join.pairs.iter().find(|(f, _)| f == name) {
+                *name = t.clone();
+            }
+        }
+    };
+    for r in &mut result.relationships {
+        if r.from == from_id {
+            r.from = into_id.to_string();
+            mapped(&mut r.from_column);
+        }
+        if r.to == from_id {
+            r.to = into_id.to_string();
+            mapped(&mut r.to_column);
+        }
+    }
+    result
+        .relationships
+        .retain(|r| !(r.from == into_id && r.to == into_id && r.from_column == r.to_column));
+    for link in &mut result.links {
+        if link.from == from_id {
+            link.from = into_id.to_string();
+            mapped(&mut link.from_column);
+        }
+        if link.to == from_id {
+            link.to = into_id.to_string();
+            mapped(&mut link.to_column);
+        }
+    }
+    result
+        .links
+        .retain(|l| !(l.from == into_id && l.to == into_id && l.from_column == l.to_column));
+    for c in &mut result.classifications {
+        if c.object_id == from_id {
+            c.object_id = into_id.to_string();
+            mapped(&mut c.column);
+        }
+    }
+    // Dropping the table drops its triggers
+    result.triggers.retain(|t| t.table_id != from_id);
+    result
+        .stewardship
+        .retain(|s| s.object_id.as_deref() != Some(from_id));
+    result.tables.retain(|t| t.id != from_id);
+    add_keys(&mut script, &result, &keys);
+    script.push_str(COMMIT_COPY);
+
+    warnings.extend(dependents_warning(graph, from_id));
+    Ok(RefactorResult {
+        graph: result,
+        script,
+        warnings,
+    })
+}
+
+/// Applies a table refactoring to a canvas design and scripts the same
+/// change as a data-preserving migration against the design's tables.
+/// Foreign keys on the columns involved are dropped before the change and
+/// added back where they end up, all in one transaction that a failed copy
+/// rolls back before anything is dropped. Module code is not rewritten; objects that
+/// use the affected tables are listed in the warnings.
+pub fn refactor_design(
+    graph: &SchemaGraph,
+    refactoring: &CanvasRefactoring,
+) -> Result<RefactorResult, String> {
+    match refactoring {
+        CanvasRefactoring::ReorderColumns { table_id, columns } => {
+            reorder_columns(graph, table_id, columns)
+        }
+        CanvasRefactoring::SplitTable {
+            table_id,
+            new_table,
+            columns,
+        } => split_table(graph, table_id, new_table, columns),
+        CanvasRefactoring::MoveColumns { from, to, columns } => {
+            move_columns(graph, from, to, columns)
+        }
+        CanvasRefactoring::MergeTables { into, from } => merge_tables(graph, into, from),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Trigger;
+
+    fn table(name: &str, columns: &[(&str, bool)]) -> TableNode {
+        TableNode {
+            id: format!("dbo.{}", name),
+            name: name.to_string(),
+            schema: "dbo".into(),
+            columns: columns
+                .iter()
+                .enumerate()
+                .map(|(i, (name, key))| Column {
+                    name: name.to_string(),
+                    data_type: "int".into(),
+                    is_nullable: false,
+                    is_primary_key: *key,
+                    ordinal_position: i as u32 + 1,
+                    source_columns: Vec::new(),
+                    source_table: None,
+                    source_column: None,
+                    is_identity: false,
+                    is_computed: false,
+                })
+                .collect(),
+            source: None,
+            metadata: None,
+            change_capture: None,
+        }
+    }
+
+    fn edge(
+        id: &str,
+        from: &str,
+        to: &str,
+        from_column: &str,
+        to_column: &str,
+    ) -> RelationshipEdge {
+        RelationshipEdge {
+            id: id.to_string(),
+            from: format!("dbo.{}", from),
+            to: format!("dbo.{}", to),
+            from_column: Some(from_column.to_string()),
+            to_column: Some(to_column.to_string()),
+            source: None,
+            is_virtual: false,
+            participation: None,
+        }
+    }
+
+    fn shop() -> SchemaGraph {
+        SchemaGraph {
+            tables: vec![
+                table(
+                    "Customer",
+                    &[
+                        ("Id", true),
+                        ("Name", false),
+                        ("RegionId", false),
+                        ("Vip", false),
+                    ],
+                ),
+                table("Region", &[("Id", true)]),
+                table("Order", &[("Id", true), ("CustomerId", false)]),
+            ],
+            relationships: vec![
+                edge("FK_Customer_Region", "Customer", "Region", "RegionId", "Id"),
+                edge("FK_Order_Customer", "Order", "Customer", "CustomerId", "Id"),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn column_names(graph: &SchemaGraph, id: &str) -> Vec<String> {
+        let table = graph.tables.iter().find(|t| t.id == id).unwrap();
+        table.columns.iter().map(|c| c.name.clone()).collect()
+    }
+
+    #[test]
+    fn split_moves_columns_and_their_keys_to_a_new_table() {
+        let refactoring = CanvasRefactoring::SplitTable {
+            table_id: "dbo.Customer".into(),
+            new_table: "CustomerProfile".into(),
+            columns: vec!["regionid".into(), "Vip".into()],
+        };
+        let mut graph = shop();
+        graph.triggers.push(Trigger {
+            id: "dbo.trCustomerVip".to_string(),
+            name: "trCustomerVip".to_string(),
+            schema: "dbo".into(),
+            table_id: "dbo.Customer".to_string(),
+            trigger_type: "AFTER".into(),
+            is_disabled: false,
+            fires_on_insert: false,
+            fires_on_update: true,
+            fires_on_delete: false,
+            definition: "IF UPDATE(Vip) RETURN;".to_string(),
+            referenced_tables: Vec::new(),
+            affected_tables: Vec::new(),
+            watched_columns: vec!["Name".to_string(), "Vip".to_string()],
+            analysis: Default::default(),
+            source: None,
+            metadata: None,
+        });
+        let result = refactor_design(&graph, &refactoring).unwrap();
+        assert_eq!(
+            result.warnings[0],
+            t!(
+                "refactor-trigger-watches-moved",
+                trigger = "dbo.trCustomerVip",
+                columns = "Vip",
+                table = "dbo.Customer"
+            )
+        );
+
+        assert_eq!(column_names(&result.graph, "dbo.Customer"), ["Id", "Name"]);
+        assert_eq!(
+            column_names(&result.graph, "dbo.CustomerProfile"),
+            ["Id", "RegionId", "Vip"]
+        );
+        let region = &result.graph.relationships[0];
+        assert_eq!(region.from, "dbo.CustomerProfile");
+        assert!(result
+            .graph
+            .relationships
+            .iter()
+            .any(|r| r.id == "FK_CustomerProfile_Customer"
+                && r.from == "dbo.CustomerProfile"
+                && r.to == "dbo.Customer"));
+
+        let script = &result.script;
+        assert!(script.contains("CREATE TABLE [dbo].[CustomerProfile] ("));
+        assert!(script.contains(
+            "INSERT INTO [dbo].[CustomerProfile] ([Id], [RegionId], [Vip]) \
+             SELECT [Id], [RegionId], [Vip] FROM [dbo].[Customer];"
+        ));
+        let drop_key = script
+            .find("ALTER TABLE [dbo].[Customer] DROP CONSTRAINT [FK_Customer_Region];")
+            .unwrap();
+        let drop_columns = script
+            .find("ALTER TABLE [dbo].[Customer] DROP COLUMN [RegionId], [Vip];")
+            .unwrap();
+        assert!(drop_key < drop_columns);
+        assert!(script.contains(
+            "ALTER TABLE [dbo].[CustomerProfile] ADD CONSTRAINT [FK_Customer_Region] \
+             FOREIGN KEY ([RegionId]) REFERENCES [dbo].[Region] ([Id]);"
+        ));
+
+        let key = CanvasRefactoring::SplitTable {
+            table_id: "dbo.Customer".into(),
+            new_table: "Other".into(),
+            columns: vec!["Id".into()],
+        };
+        assert!(refactor_design(&shop(), &key).is_err());
+    }
+
+    #[test]
+    fn moving_and_merging_copy_values_across_the_join() {
+        let moved = CanvasRefactoring::MoveColumns {
+            from: "dbo.Customer".into(),
+            to: "dbo.Order".into(),
+            columns: vec!["Vip".into()],
+        };
+        let result = refactor_design(&shop(), &moved).unwrap();
+        assert_eq!(
+            column_names(&result.graph, "dbo.Order"),
+            ["Id", "CustomerId", "Vip"]
+        );
+        assert!(result.script.contains(
+            "UPDATE target SET target.[Vip] = source.[Vip] FROM [dbo].[Order] AS target \
+             JOIN [dbo].[Customer] AS source ON target.[CustomerId] = source.[Id];"
+        ));
+        assert!(result
+            .script
+            .contains("ALTER TABLE [dbo].[Order] ALTER COLUMN [Vip] int NOT NULL;"));
+        // Customer is the parent, so each order gets exactly one value
+        assert!(result.warnings.is_empty());
+
+        let merged = CanvasRefactoring::MergeTables {
+            into: "dbo.Order".into(),
+            from: "dbo.Customer".into(),
+        };
+        let result = refactor_design(&shop(), &merged).unwrap();
+        assert!(result.graph.tables.iter().all(|t| t.id != "dbo.Customer"));
+        assert_eq!(
+            column_names(&result.graph, "dbo.Order"),
+            ["Id", "CustomerId", "Name", "RegionId", "Vip"]
+        );
+        let ids: Vec<&str> = result
+            .graph
+            .relationships
+            .iter()
+            .map(|r| r.id.as_str())
+            .collect();
+        assert_eq!(ids, ["FK_Customer_Region"]);
+        assert_eq!(result.graph.relationships[0].from, "dbo.Order");
+        assert!(result.script.contains("DROP TABLE [dbo].[Customer];"));
+        // The copy runs past the batch adding the columns, and a later batch
+        // stops rather than drop the table outside a rolled-back transaction
+        let guard = result.script.find("IF @@TRANCOUNT = 0 THROW").unwrap();
+        assert!(guard < result.script.find("UPDATE target").unwrap());
+        assert!(result.script.ends_with("COMMIT TRANSACTION;\nGO\n"));
+        assert!(result.script.contains(
+            "ALTER TABLE [dbo].[Order] ADD CONSTRAINT [FK_Customer_Region] \
+             FOREIGN KEY ([RegionId]) REFERENCES [dbo].[Region] ([Id]);"
+        ));
+
+        let unrelated = CanvasRefactoring::MoveColumns {
+            from: "dbo.Region".into(),
+            to: "dbo.Order".into(),
+            columns: vec!["Id".into()],
+        };
+        assert!(refactor_design(&shop(), &unrelated).is_err());
+    }
+
+    #[test]
+    fn reorder_rebuilds_the_table_in_the_new_order() {
+        let reorder = CanvasRefactoring::ReorderColumns {
+            table_id: "dbo.Customer".into(),
+            columns: vec!["Id".into(), "Vip".into(), "Name".into(), "RegionId".into()],
+        };
+        let result = refactor_design(&shop(), &reorder).unwrap();
+        let table = &result.graph.tables[0];
+        assert_eq!(
+            column_names(&result.graph, "dbo.Customer"),
+            ["Id", "Vip", "Name", "RegionId"]
+        );
+        assert_eq!(table.columns[1].ordinal_position, 2);
+        assert!(result
+            .script
+            .contains("EXEC sp_rename N'[dbo].[Customer_reordered]', N'Customer';"));
+        // Both keys touching the table are dropped first and added back last
+        assert!(result.script.starts_with(
+            "SET XACT_ABORT ON;\nBEGIN TRANSACTION;\nALTER TABLE [dbo].[Customer] DROP CONSTRAINT"
+        ));
+        assert_eq!(result.script.matches("ADD CONSTRAINT").count(), 2);
+        assert!(result.script.ends_with("COMMIT TRANSACTION;\nGO\n"));
+
+        let partial = CanvasRefactoring::ReorderColumns {
+            table_id: "dbo.Customer".into(),
+            columns: vec!["Id".into(), "Id".into(), "Name".into(), "Vip".into()],
+        };
+        assert!(refactor_design(&shop(), &partial).is_err());
+    }
+
+    #[test]
+    fn reorder_skips_rowversion_and_recreates_triggers() {
+        let mut graph = shop();
+        graph.tables[0].columns[3].data_type = "timestamp".into();
+        graph.triggers.push(Trigger {
+            id: "dbo.trCustomerAudit".to_string(),
+            name: "trCustomerAudit".to_string(),
+            schema: "dbo".into(),
+            table_id: "dbo.Customer".to_string(),
+            trigger_type: "AFTER".into(),
+            is_disabled: true,
+            fires_on_insert: true,
+            fires_on_update: false,
+            fires_on_delete: false,
+            definition:
+                "CREATE TRIGGER dbo.trCustomerAudit ON dbo.Customer AFTER INSERT AS RETURN;"
+                    .to_string(),
+            referenced_tables: Vec::new(),
+            affected_tables: Vec::new(),
+            watched_columns: Vec::new(),
+            analysis: Default::default(),
+            source: None,
+            metadata: None,
+        });
+        let reorder = CanvasRefactoring::ReorderColumns {
+            table_id: "dbo.Customer".into(),
+            columns: vec!["Vip".into(), "Id".into(), "Name".into(), "RegionId".into()],
+        };
+        let result = refactor_design(&graph, &reorder).unwrap();
+
+        assert!(result.script.contains(
+            "INSERT INTO [dbo].[Customer_reordered] ([Id], [Name], [RegionId]) \
+             SELECT [Id], [Name], [RegionId] FROM [dbo].[Customer];"
+        ));
+        let commit = result.script.find("COMMIT TRANSACTION;").unwrap();
+        let trigger = result
+            .script
+            .find("CREATE TRIGGER dbo.trCustomerAudit")
+            .unwrap();
+        assert!(commit < trigger);
+        assert!(result
+            .script
+            .contains("ALTER TABLE [dbo].[Customer] DISABLE TRIGGER [trCustomerAudit];\nGO"));
+
+        let merged = CanvasRefactoring::MergeTables {
+            into: "dbo.Order".into(),
+            from: "dbo.Customer".into(),
+        };
+        let result = refactor_design(&graph, &merged).unwrap();
+        assert!(result.warnings.contains(&t!(
+            "refactor-triggers-dropped",
+            triggers = "dbo.trCustomerAudit",
+            table = "dbo.Customer"
+        )));
+    }
+}
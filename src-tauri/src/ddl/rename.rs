@@ -0,0 +1,720 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::db::{quote_name, split_multipart};
+use crate::i18n::t;
+use crate::types::SchemaGraph;
+
+/// Bracketed identifiers and bare words, including `@variables` and
+/// `#temp` names so those can be told apart from real references.
+static IDENTIFIER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[(?:[^\]]|\]\])+\]|[\w@#$]+").unwrap());
+
+static CREATE_MODULE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(\s*)CREATE(?:\s+OR\s+ALTER)?(\s+(?:VIEW|PROC|PROCEDURE|FUNCTION|TRIGGER)\b)")
+        .unwrap()
+});
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RenameKind {
+    Object,
+    Column,
+}
+
+/// How sure the plan is that a match refers to the renamed object or column.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum Confidence {
+    /// Schema-qualified; a column whose alias or table qualifier resolves to
+    /// its table, or of the only referenced table that has it.
+    High,
+    /// The module references the target, but the match is unqualified, or is
+    /// a column whose table can't be told apart: another referenced table has
+    /// a column of the same name, or the qualifier doesn't resolve. Object
+    /// matches are rewritten, column matches left for review.
+    Medium,
+    /// Only the text matches: inside a comment or string, or in a module the
+    /// graph doesn't link to the target. Reported but not rewritten.
+    Low,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameReference {
+    pub object_id: String,
+    /// 1-based line in the definition.
+    pub line: usize,
+    /// That line, trimmed.
+    pub text: String,
+    pub confidence: Confidence,
+    /// Rewritten in the object's ALTER; low-confidence matches and
+    /// ambiguous column matches are left for review.
+    pub rewritten: bool,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamePlan {
+    pub target: String,
+    pub new_name: String,
+    pub kind: RenameKind,
+    /// `sp_rename`, then an ALTER for each module with rewritten references.
+    pub script: String,
+    pub references: Vec<RenameReference>,
+    /// Modules the script alters, in script order.
+    pub altered: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// What is being renamed, resolved against the graph.
+struct Target {
+    kind: RenameKind,
+    /// Object ID, or the ID of the column's table.
+    object_id: String,
+    schema: String,
+    /// Object or column name.
+    name: String,
+}
+
+fn resolve(graph: &SchemaGraph, target: &str) -> Option<Target> {
+    let objects = graph
+        .tables
+        .iter()
+        .map(|t| (&t.id, &t.schema, &t.name))
+        .chain(graph.views.iter().map(|v| (&v.id, &v.schema, &v.name)))
+        .chain(
+            graph
+                .stored_procedures
+                .iter()
+                .map(|p| (&p.id, &p.schema, &p.name)),
+        )
+        .chain(
+            graph
+                .scalar_functions
+                .iter()
+                .map(|f| (&f.id, &f.schema, &f.name)),
+        )
+        .chain(graph.triggers.iter().map(|t| (&t.id, &t.schema, &t.name)));
+    for (id, schema, name) in objects {
+        if id.eq_ignore_ascii_case(target) {
+            return Some(Target {
+                kind: RenameKind::Object,
+                object_id: id.clone(),
+                schema: schema.to_string(),
+                name: name.clone(),
+            });
+        }
+    }
+
+    let (table_id, column) = target.rsplit_once('.')?;
+    let column = split_multipart(column).pop()?;
+    let table = graph
+        .tables
+        .iter()
+        .find(|t| t.id.eq_ignore_ascii_case(table_id))?;
+    let column = table
+        .columns
+        .iter()
+        .find(|c| c.name.eq_ignore_ascii_case(&column))?;
+    Some(Target {
+        kind: RenameKind::Column,
+        object_id: table.id.clone(),
+        schema: table.schema.to_string(),
+        name: column.name.clone(),
+    })
+}
+
+/// Byte ranges of comments and string literals, where a match is only text.
+fn inert_spans(definition: &str) -> Vec<Range<usize>> {
+    let bytes = definition.as_bytes();
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let end = match (bytes[i], bytes.get(i + 1)) {
+            (b'-', Some(b'-')) => definition[i..].find('\n').map_or(bytes.len(), |n| i + n),
+            (b'/', Some(b'*')) => definition[i + 2..]
+                .find("*/")
+                .map_or(bytes.len(), |n| i + n + 4),
+            (b'\'', _) => {
+                let mut j = i + 1;
+                loop {
+                    match definition[j..].find('\'') {
+                        Some(n) if bytes.get(j + n + 1) == Some(&b'\'') => j += n + 2,
+                        Some(n) => break j + n + 1,
+                        None => break bytes.len(),
+                    }
+                }
+            }
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        spans.push(start..end);
+        i = end;
+    }
+    spans
+}
+
+fn unbracket(token: &str) -> String {
+    match token.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        Some(inner) => inner.replace("]]", "]"),
+        None => token.to_string(),
+    }
+}
+
+/// `new_name` written the way the matched token was: bracketed when it was,
+/// or when the name needs it.
+fn replacement(token: &str, new_name: &str) -> String {
+    static PLAIN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[A-Za-z_][\w]*$").unwrap());
+    match token.starts_with('[') || !PLAIN.is_match(new_name) {
+        true => quote_name(new_name),
+        false => new_name.to_string(),
+    }
+}
+
+struct Module<'a> {
+    id: &'a str,
+    definition: &'a str,
+    /// Tables and views the module reads or writes, plus a trigger's table.
+    tables: Vec<&'a str>,
+    /// A trigger's table, which `inserted` and `deleted` stand for.
+    trigger_table: Option<&'a str>,
+}
+
+fn modules(graph: &SchemaGraph) -> Vec<Module<'_>> {
+    fn module<'a>(id: &'a str, definition: &'a str, tables: &[&'a [String]]) -> Module<'a> {
+        Module {
+            id,
+            definition,
+            tables: tables
+                .iter()
+                .flat_map(|t| t.iter().map(String::as_str))
+                .collect(),
+            trigger_table: None,
+        }
+    }
+    graph
+        .views
+        .iter()
+        .map(|v| module(&v.id, &v.definition, &[&v.referenced_tables]))
+        .chain(graph.scalar_functions.iter().map(|f| {
+            module(
+                &f.id,
+                &f.definition,
+                &[&f.referenced_tables, &f.affected_tables],
+            )
+        }))
+        .chain(graph.stored_procedures.iter().map(|p| {
+            module(
+                &p.id,
+                &p.definition,
+                &[&p.referenced_tables, &p.affected_tables],
+            )
+        }))
+        .chain(graph.triggers.iter().map(|t| {
+            let mut module = module(
+                &t.id,
+                &t.definition,
+                &[&t.referenced_tables, &t.affected_tables],
+            );
+            module.tables.push(&t.table_id);
+            module.trigger_table = Some(&t.table_id);
+            module
+        }))
+        .collect()
+}
+
+/// Words that end a table source rather than alias it.
+const SOURCE_STOP_WORDS: &str =
+    "APPLY BEGIN CROSS DECLARE DELETE ELSE END EXCEPT EXEC EXECUTE FOR FULL GO GROUP HAVING IF \
+     INNER INSERT INTERSECT JOIN LEFT MERGE ON OPTION ORDER OUTER OUTPUT PIVOT RETURN RIGHT \
+     SELECT SET UNION UNPIVOT UPDATE USING VALUES WHEN WHERE WHILE WITH";
+
+/// The tables a module's FROM, JOIN, UPDATE and INTO clauses name, keyed by
+/// lowercase alias, or by table name when there is no alias. An alias bound
+/// to different tables in different statements maps to `None`.
+fn table_sources(
+    module: &Module,
+    tokens: &[regex::Match],
+    inert: &[Range<usize>],
+) -> HashMap<String, Option<String>> {
+    let definition = module.definition;
+    let gap = |a: &regex::Match, b: &regex::Match| definition[a.end()..b.start()].to_string();
+    let mut sources: HashMap<String, Option<String>> = module
+        .trigger_table
+        .map(|t| {
+            [
+                ("inserted".into(), Some(t.into())),
+                ("deleted".into(), Some(t.into())),
+            ]
+        })
+        .into_iter()
+        .flatten()
+        .collect();
+    let mut bind = |key: String, id: String| {
+        sources
+            .entry(key)
+            .and_modify(|bound| {
+                if bound.as_ref().is_some_and(|b| !b.eq_ignore_ascii_case(&id)) {
+                    *bound = None;
+                }
+            })
+            .or_insert(Some(id));
+    };
+
+    for (i, keyword) in tokens.iter().enumerate() {
+        let is_source = ["from", "join", "update", "into"]
+            .iter()
+            .any(|k| keyword.as_str().eq_ignore_ascii_case(k));
+        if !is_source || inert.iter().any(|s| s.contains(&keyword.start())) {
+            continue;
+        }
+        // A dotted name right after the keyword; derived tables start with `(`
+        let mut end = i + 1;
+        match tokens.get(end) {
+            Some(first) if gap(keyword, first).trim().is_empty() => {}
+            _ => continue,
+        }
+        while tokens
+            .get(end + 1)
+            .is_some_and(|n| gap(&tokens[end], n).trim() == ".")
+        {
+            end += 1;
+        }
+        let mut parts: Vec<String> = tokens[i + 1..=end]
+            .iter()
+            .map(|t| unbracket(t.as_str()))
+            .collect();
+        let name = parts.pop().unwrap_or_default();
+        if name.starts_with(['@', '#']) {
+            continue;
+        }
+        let id = match parts.pop() {
+            Some(schema) => format!("{}.{}", schema, name),
+            None => module
+                .tables
+                .iter()
+                .find(|t| {
+                    split_multipart(t)
+                        .last()
+                        .is_some_and(|n| n.eq_ignore_ascii_case(&name))
+                })
+                .map_or_else(|| name.clone(), |t| t.to_string()),
+        };
+
+        let mut next = end + 1;
+        if tokens
+            .get(next)
+            .is_some_and(|t| t.as_str().eq_ignore_ascii_case("as"))
+        {
+            next += 1;
+        }
+        let alias = tokens
+            .get(next)
+            .filter(|a| gap(&tokens[next - 1], a).trim().is_empty())
+            .map(|a| unbracket(a.as_str()))
+            .filter(|a| {
+                !SOURCE_STOP_WORDS
+                    .split_whitespace()
+                    .any(|w| w.eq_ignore_ascii_case(a))
+            });
+        bind(alias.unwrap_or(name).to_lowercase(), id);
+    }
+    sources
+}
+
+/// Matches of the target in one module, with their byte ranges and whether
+/// to rewrite them.
+fn find_references(
+    graph: &SchemaGraph,
+    target: &Target,
+    module: &Module,
+) -> Vec<(Range<usize>, Confidence, bool)> {
+    let definition = module.definition;
+    let inert = inert_spans(definition);
+    let links_target = module
+        .tables
+        .iter()
+        .any(|t| t.eq_ignore_ascii_case(&target.object_id));
+    // Another referenced table with a column of the same name makes an
+    // unqualified column match ambiguous
+    let ambiguous = target.kind == RenameKind::Column
+        && graph.tables.iter().any(|t| {
+            t.id != target.object_id
+                && module.tables.contains(&t.id.as_str())
+                && t.columns
+                    .iter()
+                    .any(|c| c.name.eq_ignore_ascii_case(&target.name))
+        });
+
+    let tokens: Vec<_> = IDENTIFIER.find_iter(definition).collect();
+    let sources = match target.kind {
+        RenameKind::Column => table_sources(module, &tokens, &inert),
+        RenameKind::Object => HashMap::new(),
+    };
+    let mut matches = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let text = token.as_str();
+        if text.starts_with(['@', '#']) || !unbracket(text).eq_ignore_ascii_case(&target.name) {
+            continue;
+        }
+        let dotted = |a: usize, b: usize| definition[a..b].trim() == ".";
+        let qualifier = i
+            .checked_sub(1)
+            .map(|p| &tokens[p])
+            .filter(|p| dotted(p.end(), token.start()))
+            .map(|p| unbracket(p.as_str()));
+        let qualifier_schema = i
+            .checked_sub(2)
+            .filter(|&p| qualifier.is_some() && dotted(tokens[p].end(), tokens[p + 1].start()))
+            .map(|p| unbracket(tokens[p].as_str()));
+        let qualifies_next = tokens
+            .get(i + 1)
+            .is_some_and(|n| dotted(token.end(), n.start()));
+
+        let confidence = if inert.iter().any(|s| s.contains(&token.start())) {
+            Confidence::Low
+        } else {
+            match target.kind {
+                RenameKind::Object => match qualifier {
+                    Some(schema) if schema.eq_ignore_ascii_case(&target.schema) => Confidence::High,
+                    // Same name in another schema
+                    Some(_) if !qualifies_next => continue,
+                    _ if links_target => Confidence::Medium,
+                    _ => Confidence::Low,
+                },
+                RenameKind::Column => {
+                    // `Orders.Id` names a table or schema, not this column
+                    if qualifies_next {
+                        continue;
+                    }
+                    // `o.Total`, `Orders.Total` or `dbo.Orders.Total`: the
+                    // column of whichever table the qualifier stands for
+                    let table = match (&qualifier_schema, &qualifier) {
+                        (Some(schema), Some(table)) => Some(Some(format!("{}.{}", schema, table))),
+                        (None, Some(alias)) => {
+                            Some(sources.get(&alias.to_lowercase()).cloned().flatten())
+                        }
+                        _ => None,
+                    };
+                    match (table, links_target) {
+                        (Some(Some(id)), _) if !id.eq_ignore_ascii_case(&target.object_id) => {
+                            continue
+                        }
+                        (Some(Some(_)), _) => Confidence::High,
+                        (_, false) => Confidence::Low,
+                        (Some(None), true) => Confidence::Medium,
+                        (None, true) if ambiguous => Confidence::Medium,
+                        (None, true) => Confidence::High,
+                    }
+                }
+            }
+        };
+        let rewritten = match confidence {
+            Confidence::High => true,
+            Confidence::Medium => target.kind == RenameKind::Object,
+            Confidence::Low => false,
+        };
+        matches.push((token.range(), confidence, rewritten));
+    }
+    matches
+}
+
+fn line_of(definition: &str, offset: usize) -> (usize, &str) {
+    let number = definition[..offset].matches('\n').count() + 1;
+    let start = definition[..offset].rfind('\n').map_or(0, |n| n + 1);
+    let end = definition[offset..]
+        .find('\n')
+        .map_or(definition.len(), |n| offset + n);
+    (number, definition[start..end].trim())
+}
+
+fn sp_rename(target: &Target, new_name: &str) -> String {
+    let literal = |s: &str| format!("N'{}'", s.replace('\'', "''"));
+    let quoted = |id: &str| {
+        split_multipart(id)
+            .iter()
+            .map(|p| quote_name(p))
+            .collect::<Vec<_>>()
+            .join(".")
+    };
+    match target.kind {
+        RenameKind::Object => format!(
+            "EXEC sp_rename {}, {};",
+            literal(&quoted(&target.object_id)),
+            literal(new_name)
+        ),
+        RenameKind::Column => format!(
+            "EXEC sp_rename {}, {}, N'COLUMN';",
+            literal(&format!(
+                "{}.{}",
+                quoted(&target.object_id),
+                quote_name(&target.name)
+            )),
+            literal(new_name)
+        ),
+    }
+}
+
+/// A script renaming `target` (an object ID, or a table ID followed by a
+/// column name) to `new_name` with `sp_rename`, then altering every view,
+/// function, procedure and trigger whose definition refers to it. Matches
+/// are found textually and graded: high confidence ones and unqualified
+/// object names are rewritten, the rest are listed for review. Column
+/// matches qualified by another table's alias are not references at all.
+pub fn plan_rename(
+    graph: &SchemaGraph,
+    target: &str,
+    new_name: &str,
+) -> Result<RenamePlan, String> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        return Err(t!("error-rename-name-required"));
+    }
+    let target_spec = resolve(graph, target.trim())
+        .ok_or_else(|| t!("error-rename-unknown-target", target = target))?;
+
+    let mut references = Vec::new();
+    let mut altered = Vec::new();
+    let mut alters = String::new();
+    let mut warnings = Vec::new();
+    for module in modules(graph) {
+        let renamed_itself =
+            target_spec.kind == RenameKind::Object && module.id == target_spec.object_id;
+        let matches = find_references(graph, &target_spec, &module);
+        if renamed_itself {
+            if !matches.is_empty() {
+                warnings.push(t!("rename-own-definition", object = module.id));
+            }
+            continue;
+        }
+
+        let mut definition = module.definition.to_string();
+        let mut rewrites = 0;
+        // Back to front, so earlier ranges stay valid
+        for &(ref range, confidence, rewritten) in matches.iter().rev() {
+            if rewritten {
+                let token = &module.definition[range.clone()];
+                definition.replace_range(range.clone(), &replacement(token, new_name));
+                rewrites += 1;
+            }
+            let (line, text) = line_of(module.definition, range.start);
+            references.push(RenameReference {
+                object_id: module.id.to_string(),
+                line,
+                text: text.to_string(),
+                confidence,
+                rewritten,
+            });
+        }
+        if rewrites == 0 {
+            continue;
+        }
+        if !CREATE_MODULE.is_match(&definition) {
+            warnings.push(t!("rename-not-alterable", object = module.id));
+            continue;
+        }
+        let definition = CREATE_MODULE.replace(&definition, "${1}ALTER${2}");
+        let _ = writeln!(alters, "\n{}\nGO", definition.trim());
+        altered.push(module.id.to_string());
+    }
+    references.sort_by(|a, b| a.object_id.cmp(&b.object_id).then(a.line.cmp(&b.line)));
+
+    let mut script = String::new();
+    let _ = writeln!(
+        script,
+        "-- {}",
+        t!(
+            "rename-header",
+            target = target_spec.object_id,
+            name = new_name,
+            count = altered.len()
+        )
+    );
+    for reference in references.iter().filter(|r| !r.rewritten) {
+        let _ = writeln!(
+            script,
+            "-- {}",
+            t!(
+                "rename-review",
+                object = reference.object_id,
+                line = reference.line
+            )
+        );
+    }
+    let _ = writeln!(script, "\n{}\nGO", sp_rename(&target_spec, new_name));
+    script.push_str(&alters);
+
+    Ok(RenamePlan {
+        target: match target_spec.kind {
+            RenameKind::Object => target_spec.object_id,
+            RenameKind::Column => format!("{}.{}", target_spec.object_id, target_spec.name),
+        },
+        new_name: new_name.to_string(),
+        kind: target_spec.kind,
+        script,
+        references,
+        altered,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Column, TableNode, ViewNode};
+
+    fn table(name: &str, columns: &[&str]) -> TableNode {
+        TableNode {
+            id: format!("dbo.{}", name),
+            name: name.to_string(),
+            schema: "dbo".into(),
+            columns: columns
+                .iter()
+                .map(|c| Column {
+                    name: c.to_string(),
+                    data_type: "int".into(),
+                    ..Default::default()
+                })
+                .collect(),
+            source: None,
+            metadata: None,
+            change_capture: None,
+        }
+    }
+
+    fn view(name: &str, references: &[&str], definition: &str) -> ViewNode {
+        ViewNode {
+            id: format!("dbo.{}", name),
+            name: name.to_string(),
+            schema: "dbo".into(),
+            columns: Vec::new(),
+            definition: definition.to_string(),
+            referenced_tables: references.iter().map(|r| r.to_string()).collect(),
+            source: None,
+            metadata: None,
+            is_indexed: false,
+        }
+    }
+
+    fn graph() -> SchemaGraph {
+        SchemaGraph {
+            tables: vec![
+                table("Invoice", &["InvoiceId", "Total"]),
+                table("Refund", &["RefundId", "Total"]),
+            ],
+            views: vec![
+                view(
+                    "vInvoiceTotals",
+                    &["dbo.Invoice"],
+                    "CREATE VIEW dbo.vInvoiceTotals AS\n\
+                     -- sums Invoice.Total\n\
+                     SELECT [InvoiceId], Total FROM [dbo].[Invoice]",
+                ),
+                view(
+                    "vNet",
+                    &["dbo.Invoice", "dbo.Refund"],
+                    "CREATE OR ALTER VIEW dbo.vNet AS\n\
+                     SELECT i.Total - r.Total AS Net\n\
+                     FROM Invoice i JOIN audit.Invoice a ON 1 = 1 JOIN Refund r ON 1 = 1",
+                ),
+                view(
+                    "vLegacy",
+                    &[],
+                    "CREATE VIEW dbo.vLegacy AS SELECT 'Invoice' AS Source, Total FROM x",
+                ),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn confidences(plan: &RenamePlan, object_id: &str) -> Vec<Confidence> {
+        plan.references
+            .iter()
+            .filter(|r| r.object_id == object_id)
+            .map(|r| r.confidence)
+            .collect()
+    }
+
+    #[test]
+    fn object_rename_rewrites_linked_modules_and_lists_the_rest() {
+        let plan = plan_rename(&graph(), "dbo.Invoice", "SalesInvoice").unwrap();
+
+        assert_eq!(plan.kind, RenameKind::Object);
+        assert!(plan
+            .script
+            .contains("EXEC sp_rename N'[dbo].[Invoice]', N'SalesInvoice';\nGO"));
+        assert_eq!(plan.altered, vec!["dbo.vInvoiceTotals", "dbo.vNet"]);
+        assert!(plan.script.contains(
+            "ALTER VIEW dbo.vInvoiceTotals AS\n-- sums Invoice.Total\n\
+             SELECT [InvoiceId], Total FROM [dbo].[SalesInvoice]\nGO"
+        ));
+        // audit.Invoice is another table and stays as it is
+        assert!(plan
+            .script
+            .contains("FROM SalesInvoice i JOIN audit.Invoice a"));
+        assert_eq!(
+            confidences(&plan, "dbo.vInvoiceTotals"),
+            vec![Confidence::Low, Confidence::High]
+        );
+        assert_eq!(confidences(&plan, "dbo.vLegacy"), vec![Confidence::Low]);
+        assert!(plan.script.contains("-- dbo.vLegacy line 1"));
+    }
+
+    #[test]
+    fn column_rename_grades_ambiguous_and_unlinked_references() {
+        let plan = plan_rename(&graph(), "dbo.Invoice.Total", "GrossTotal").unwrap();
+
+        assert_eq!(plan.target, "dbo.Invoice.Total");
+        assert!(plan
+            .script
+            .contains("EXEC sp_rename N'[dbo].[Invoice].[Total]', N'GrossTotal', N'COLUMN';"));
+        assert_eq!(
+            confidences(&plan, "dbo.vInvoiceTotals"),
+            vec![Confidence::Low, Confidence::High]
+        );
+        // r is Refund, whose Total column keeps its name
+        assert_eq!(confidences(&plan, "dbo.vNet"), vec![Confidence::High]);
+        assert_eq!(confidences(&plan, "dbo.vLegacy"), vec![Confidence::Low]);
+        assert_eq!(plan.altered, vec!["dbo.vInvoiceTotals", "dbo.vNet"]);
+        assert!(plan.script.contains("SELECT [InvoiceId], GrossTotal FROM"));
+        assert!(plan.script.contains("SELECT i.GrossTotal - r.Total AS Net"));
+
+        assert!(plan_rename(&graph(), "dbo.Invoice.Missing", "X").is_err());
+        assert!(plan_rename(&graph(), "dbo.Invoice", " ").is_err());
+    }
+
+    #[test]
+    fn ambiguous_column_references_are_left_for_review() {
+        let mut graph = graph();
+        graph.views.push(view(
+            "vBoth",
+            &["dbo.Invoice", "dbo.Refund"],
+            "CREATE VIEW dbo.vBoth AS\n\
+             SELECT Total, x.Total AS Other, Invoice.InvoiceId\n\
+             FROM Invoice JOIN Refund ON 1 = 1",
+        ));
+
+        let plan = plan_rename(&graph, "dbo.Invoice.Total", "GrossTotal").unwrap();
+
+        let both: Vec<_> = plan
+            .references
+            .iter()
+            .filter(|r| r.object_id == "dbo.vBoth")
+            .map(|r| (r.confidence, r.rewritten))
+            .collect();
+        // Refund also has a Total column, and x is no source of this view
+        assert_eq!(
+            both,
+            vec![(Confidence::Medium, false), (Confidence::Medium, false)]
+        );
+        assert!(!plan.altered.contains(&"dbo.vBoth".to_string()));
+        assert!(plan.script.contains("-- dbo.vBoth line 2"));
+    }
+}
@@ -0,0 +1,255 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::analysis::TargetDialect;
+
+/// One row of the type-mapping table.
+///
+/// `source` is a SQL Server type: a bare name (`nvarchar`) matches every
+/// length, a full type (`nvarchar(max)`, `datetime2(7)`) matches only that
+/// and takes precedence. In `target`, `{0}` and `{1}` stand for the source
+/// type's arguments, so `decimal` → `numeric({0},{1})` keeps precision and scale.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeMapping {
+    pub source: String,
+    pub target: String,
+    /// Why the mapping can lose data or behaviour; reported as a warning for
+    /// every column that uses it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lossy_note: Option<String>,
+}
+
+fn mapping(source: &str, target: &str, lossy_note: Option<&str>) -> TypeMapping {
+    TypeMapping {
+        source: source.to_string(),
+        target: target.to_string(),
+        lossy_note: lossy_note.map(str::to_string),
+    }
+}
+
+const FRACTION_7: &str = "SQL Server keeps 7 fractional second digits; the target keeps 6";
+const OFFSET_LOST: &str = "The original time zone offset is not preserved";
+const ROWVERSION: &str = "No longer maintained automatically on update";
+const VARIANT: &str = "Values of every type are stored as text";
+
+pub fn default_type_mappings(dialect: TargetDialect) -> Vec<TypeMapping> {
+    match dialect {
+        TargetDialect::Postgres => vec![
+            mapping("bigint", "bigint", None),
+            mapping("int", "integer", None),
+            mapping("smallint", "smallint", None),
+            mapping("tinyint", "smallint", None),
+            mapping("bit", "boolean", None),
+            mapping("decimal", "numeric({0},{1})", None),
+            mapping("numeric", "numeric({0},{1})", None),
+            mapping("money", "numeric(19,4)", None),
+            mapping("smallmoney", "numeric(10,4)", None),
+            mapping("float", "double precision", None),
+            mapping("real", "real", None),
+            mapping("date", "date", None),
+            mapping("datetime", "timestamp(3)", None),
+            mapping("datetime2", "timestamp({0})", None),
+            mapping("datetime2(7)", "timestamp(6)", Some(FRACTION_7)),
+            mapping("smalldatetime", "timestamp(0)", None),
+            mapping("datetimeoffset", "timestamptz({0})", Some(OFFSET_LOST)),
+            mapping("datetimeoffset(7)", "timestamptz(6)", Some(OFFSET_LOST)),
+            mapping("time", "time({0})", None),
+            mapping("time(7)", "time(6)", Some(FRACTION_7)),
+            mapping("char", "char({0})", None),
+            mapping("nchar", "char({0})", None),
+            mapping("varchar", "varchar({0})", None),
+            mapping("nvarchar", "varchar({0})", None),
+            mapping("varchar(max)", "text", None),
+            mapping("nvarchar(max)", "text", None),
+            mapping("text", "text", None),
+            mapping("ntext", "text", None),
+            mapping("binary", "bytea", None),
+            mapping("varbinary", "bytea", None),
+            mapping("image", "bytea", None),
+            mapping("uniqueidentifier", "uuid", None),
+            mapping("xml", "xml", None),
+            mapping("sql_variant", "text", Some(VARIANT)),
+            mapping(
+                "hierarchyid",
+                "text",
+                Some("Stored as its string path; hierarchy methods are lost"),
+            ),
+            mapping("rowversion", "bytea", Some(ROWVERSION)),
+            mapping("timestamp", "bytea", Some(ROWVERSION)),
+        ],
+        TargetDialect::MySql => vec![
+            mapping("bigint", "bigint", None),
+            mapping("int", "int", None),
+            mapping("smallint", "smallint", None),
+            mapping("tinyint", "tinyint unsigned", None),
+            mapping("bit", "tinyint(1)", None),
+            mapping("decimal", "decimal({0},{1})", None),
+            mapping("numeric", "decimal({0},{1})", None),
+            mapping("money", "decimal(19,4)", None),
+            mapping("smallmoney", "decimal(10,4)", None),
+            mapping("float", "double", None),
+            mapping("real", "float", None),
+            mapping("date", "date", None),
+            mapping("datetime", "datetime(3)", None),
+            mapping("datetime2", "datetime({0})", None),
+            mapping("datetime2(7)", "datetime(6)", Some(FRACTION_7)),
+            mapping("smalldatetime", "datetime", None),
+            mapping("datetimeoffset", "datetime({0})", Some(OFFSET_LOST)),
+            mapping("datetimeoffset(7)", "datetime(6)", Some(OFFSET_LOST)),
+            mapping("time", "time({0})", None),
+            mapping("time(7)", "time(6)", Some(FRACTION_7)),
+            mapping("char", "char({0})", None),
+            mapping("nchar", "char({0})", None),
+            mapping("varchar", "varchar({0})", None),
+            mapping("nvarchar", "varchar({0})", None),
+            mapping("varchar(max)", "longtext", None),
+            mapping("nvarchar(max)", "longtext", None),
+            mapping("text", "longtext", None),
+            mapping("ntext", "longtext", None),
+            mapping("binary", "binary({0})", None),
+            mapping("varbinary", "varbinary({0})", None),
+            mapping("varbinary(max)", "longblob", None),
+            mapping("image", "longblob", None),
+            mapping(
+                "uniqueidentifier",
+                "char(36)",
+                Some("Stored as text; no native UUID type"),
+            ),
+            mapping(
+                "xml",
+                "longtext",
+                Some("XML validation and query methods are lost"),
+            ),
+            mapping("sql_variant", "longtext", Some(VARIANT)),
+            mapping(
+                "hierarchyid",
+                "varchar(4000)",
+                Some("Stored as its string path; hierarchy methods are lost"),
+            ),
+            mapping("rowversion", "binary(8)", Some(ROWVERSION)),
+            mapping("timestamp", "binary(8)", Some(ROWVERSION)),
+        ],
+    }
+}
+
+/// Result of mapping one column type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MappedType {
+    pub target: String,
+    /// Set for lossy mappings and for types with no mapping at all.
+    pub warning: Option<String>,
+}
+
+/// Split `decimal(18, 2)` into `("decimal", ["18", "2"])`. Types whose
+/// default scale SQL Server omits (`datetime2` = `datetime2(7)`) get it back,
+/// so the full-type rows in the table can match them. `float(1)` through
+/// `float(24)` are single precision, which SQL Server itself stores as `real`.
+fn parse_type(data_type: &str) -> (String, Vec<String>) {
+    let data_type = data_type.trim().to_lowercase();
+    let (base, mut args) = match data_type.split_once('(') {
+        Some((base, rest)) => (
+            base.trim().to_string(),
+            rest.trim_end_matches(')')
+                .split(',')
+                .map(|a| a.trim().to_string())
+                .collect(),
+        ),
+        None => (data_type.clone(), Vec::new()),
+    };
+    if args.is_empty() && matches!(base.as_str(), "datetime2" | "datetimeoffset" | "time") {
+        args.push("7".to_string());
+    }
+    if base == "float" {
+        let single = matches!(args.as_slice(), [n] if n.parse::<u8>().is_ok_and(|n| n <= 24));
+        let base = if single { "real" } else { "float" };
+        return (base.to_string(), Vec::new());
+    }
+    (base, args)
+}
+
+/// Default table for a dialect with caller-supplied rows layered on top.
+pub struct TypeMapper {
+    mappings: HashMap<String, TypeMapping>,
+}
+
+impl TypeMapper {
+    pub fn new(dialect: TargetDialect, overrides: &[TypeMapping]) -> Self {
+        let mappings = default_type_mappings(dialect)
+            .into_iter()
+            .chain(overrides.iter().cloned())
+            .map(|m| (m.source.trim().to_lowercase().replace(' ', ""), m))
+            .collect();
+        TypeMapper { mappings }
+    }
+
+    pub fn map(&self, data_type: &str) -> MappedType {
+        let (base, args) = parse_type(data_type);
+        let full = match args.is_empty() {
+            true => base.clone(),
+            false => format!("{}({})", base, args.join(",")),
+        };
+        let Some(mapping) = self
+            .mappings
+            .get(&full)
+            .or_else(|| self.mappings.get(&base))
+        else {
+            return MappedType {
+                target: data_type.to_string(),
+                warning: Some(format!("No mapping for {}; copied unchanged", data_type)),
+            };
+        };
+
+        let mut target = mapping.target.clone();
+        for (i, arg) in args.iter().enumerate() {
+            target = target.replace(&format!("{{{}}}", i), arg);
+        }
+        // A placeholder without a matching argument drops its parentheses
+        if target.contains('{') {
+            if let Some(open) = target.find('(') {
+                target.truncate(open);
+            }
+        }
+
+        MappedType {
+            target,
+            warning: mapping.lossy_note.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_types_take_precedence_over_bare_names() {
+        let mapper = TypeMapper::new(
+            TargetDialect::Postgres,
+            &[mapping("money", "numeric(12,2)", Some("Rounded to cents"))],
+        );
+        let target = |t: &str| mapper.map(t).target;
+
+        assert_eq!(target("nvarchar(50)"), "varchar(50)");
+        assert_eq!(target("nvarchar(max)"), "text");
+        assert_eq!(target("decimal(18,2)"), "numeric(18,2)");
+        assert_eq!(target("datetime2(3)"), "timestamp(3)");
+        assert_eq!(target("money"), "numeric(12,2)");
+
+        let mapped = mapper.map("datetime2");
+        assert_eq!(mapped.target, "timestamp(6)");
+        assert!(mapped.warning.is_some());
+        assert!(mapper.map("datetime2(3)").warning.is_none());
+        let whole_seconds = mapper.map("datetime2(0)");
+        assert_eq!(whole_seconds.target, "timestamp(0)");
+        assert!(whole_seconds.warning.is_none());
+
+        assert_eq!(target("float(24)"), "real");
+        assert_eq!(target("float(53)"), "double precision");
+        assert_eq!(target("float"), "double precision");
+
+        let unknown = mapper.map("geography");
+        assert_eq!(unknown.target, "geography");
+        assert!(unknown.warning.unwrap().contains("No mapping"));
+    }
+}
@@ -0,0 +1,360 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::snapshots::DiffOp;
+
+/// Above this many LCS cells the diff falls back to "all removed, all added"
+/// rather than allocating a huge table for a rewritten module.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Comments, strings, bracketed and quoted identifiers, words, numbers,
+/// whitespace runs, and any other single character.
+static TOKEN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?s)--.*|/\*.*?(?:\*/|$)|N?'(?:[^']|'')*'?|\[(?:[^\]]|\]\])*\]?|"(?:[^"]|"")*"?|[@#]*[A-Za-z_][\w@#$]*|\d+(?:\.\d+)?|\s+|."#,
+    )
+    .unwrap()
+});
+
+/// One step of an edit script, by index into the old and new sequences.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Edit {
+    Unchanged(usize, usize),
+    Removed(usize),
+    Added(usize),
+}
+
+/// Longest-common-subsequence edit script turning `old` into `new`, after
+/// trimming the shared prefix and suffix (typically most of a module).
+pub(crate) fn diff_sequences<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Edit> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a, b) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    let mut edits: Vec<Edit> = (0..prefix).map(|i| Edit::Unchanged(i, i)).collect();
+
+    if (a.len() + 1) * (b.len() + 1) > MAX_DIFF_CELLS {
+        edits.extend((0..a.len()).map(|i| Edit::Removed(prefix + i)));
+        edits.extend((0..b.len()).map(|j| Edit::Added(prefix + j)));
+    } else {
+        // lcs[i][j] = LCS length of a[i..] and b[j..]
+        let width = b.len() + 1;
+        let mut lcs = vec![0u32; (a.len() + 1) * width];
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                lcs[i * width + j] = if a[i] == b[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() || j < b.len() {
+            if i < a.len() && j < b.len() && a[i] == b[j] {
+                edits.push(Edit::Unchanged(prefix + i, prefix + j));
+                i += 1;
+                j += 1;
+            } else if i < a.len()
+                && (j == b.len() || lcs[(i + 1) * width + j] >= lcs[i * width + j + 1])
+            {
+                edits.push(Edit::Removed(prefix + i));
+                i += 1;
+            } else {
+                edits.push(Edit::Added(prefix + j));
+                j += 1;
+            }
+        }
+    }
+
+    let (old_tail, new_tail) = (old.len() - suffix, new.len() - suffix);
+    edits.extend((0..suffix).map(|k| Edit::Unchanged(old_tail + k, new_tail + k)));
+    edits
+}
+
+/// What counts as a difference. Everything is ignored by default, so only
+/// changes that alter the SQL show up.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DiffOptions {
+    pub ignore_whitespace: bool,
+    /// `[Orders]` and `"Orders"` compare equal to `Orders`.
+    pub ignore_brackets: bool,
+    /// Keywords and identifiers compare case-insensitively; string literals
+    /// never do.
+    pub ignore_case: bool,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        DiffOptions {
+            ignore_whitespace: true,
+            ignore_brackets: true,
+            ignore_case: true,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffToken {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DefinitionDiffLine {
+    pub op: DiffOp,
+    /// 1-based line numbers; unchanged lines have both.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_line: Option<usize>,
+    /// The old text for removed lines, the new text otherwise.
+    pub text: String,
+    /// Token diff of a changed line against the line it replaced; empty for
+    /// unchanged lines and for lines with no counterpart.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tokens: Vec<DiffToken>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DefinitionDiff {
+    /// No differences once normalized.
+    pub identical: bool,
+    pub added: usize,
+    pub removed: usize,
+    pub lines: Vec<DefinitionDiffLine>,
+}
+
+/// Original text and comparison key of each token of `line`. Whitespace
+/// keys are empty when whitespace is ignored, and are skipped.
+fn tokens<'t>(line: &'t str, options: &DiffOptions) -> Vec<(&'t str, String)> {
+    TOKEN
+        .find_iter(line)
+        .map(|m| {
+            let text = m.as_str();
+            let first = text.chars().next().unwrap_or_default();
+            let key = if first.is_whitespace() {
+                if options.ignore_whitespace {
+                    String::new()
+                } else {
+                    text.to_string()
+                }
+            } else if text.starts_with('\'') || text.starts_with("N'") || text.starts_with("--") {
+                text.to_string()
+            } else {
+                let unquoted = match first {
+                    '[' if options.ignore_brackets => text
+                        .trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .replace("]]", "]"),
+                    '"' if options.ignore_brackets => text.trim_matches('"').replace("\"\"", "\""),
+                    _ => text.to_string(),
+                };
+                if options.ignore_case {
+                    unquoted.to_lowercase()
+                } else {
+                    unquoted
+                }
+            };
+            (text, key)
+        })
+        .collect()
+}
+
+fn line_key(line: &str, options: &DiffOptions) -> Vec<String> {
+    tokens(line, options)
+        .into_iter()
+        .map(|(_, key)| key)
+        .filter(|key| !key.is_empty())
+        .collect()
+}
+
+/// Token diffs of a removed line and the added line that replaced it.
+fn token_diff(old: &str, new: &str, options: &DiffOptions) -> (Vec<DiffToken>, Vec<DiffToken>) {
+    let old_tokens = tokens(old, options);
+    let new_tokens = tokens(new, options);
+    let old_keys: Vec<&str> = old_tokens.iter().map(|(_, k)| k.as_str()).collect();
+    let new_keys: Vec<&str> = new_tokens.iter().map(|(_, k)| k.as_str()).collect();
+    let token = |op, text: &str| DiffToken {
+        op,
+        text: text.to_string(),
+    };
+
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    for edit in diff_sequences(&old_keys, &new_keys) {
+        match edit {
+            Edit::Unchanged(i, j) => {
+                removed.push(token(DiffOp::Unchanged, old_tokens[i].0));
+                added.push(token(DiffOp::Unchanged, new_tokens[j].0));
+            }
+            // Ignored whitespace never counts as a change
+            Edit::Removed(i) if old_keys[i].is_empty() => {
+                removed.push(token(DiffOp::Unchanged, old_tokens[i].0))
+            }
+            Edit::Added(j) if new_keys[j].is_empty() => {
+                added.push(token(DiffOp::Unchanged, new_tokens[j].0))
+            }
+            Edit::Removed(i) => removed.push(token(DiffOp::Removed, old_tokens[i].0)),
+            Edit::Added(j) => added.push(token(DiffOp::Added, new_tokens[j].0)),
+        }
+    }
+    (removed, added)
+}
+
+/// Line diff of two definitions under `options`, with a token diff for each
+/// pair of lines that replaced one another. Lines that differ only in ways
+/// the options ignore are unchanged.
+pub fn diff_definitions(old: &str, new: &str, options: &DiffOptions) -> DefinitionDiff {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let old_keys: Vec<Vec<String>> = old_lines.iter().map(|l| line_key(l, options)).collect();
+    let new_keys: Vec<Vec<String>> = new_lines.iter().map(|l| line_key(l, options)).collect();
+    let edits = diff_sequences(&old_keys, &new_keys);
+
+    let mut lines = Vec::with_capacity(edits.len());
+    let mut k = 0;
+    while k < edits.len() {
+        if let Edit::Unchanged(i, j) = edits[k] {
+            lines.push(DefinitionDiffLine {
+                op: DiffOp::Unchanged,
+                old_line: Some(i + 1),
+                new_line: Some(j + 1),
+                text: new_lines[j].to_string(),
+                tokens: Vec::new(),
+            });
+            k += 1;
+            continue;
+        }
+
+        // A hunk: the removed lines, then the added ones, paired in order
+        let end = edits[k..]
+            .iter()
+            .position(|e| matches!(e, Edit::Unchanged(..)))
+            .map_or(edits.len(), |p| k + p);
+        let removed: Vec<usize> = edits[k..end]
+            .iter()
+            .filter_map(|e| match e {
+                Edit::Removed(i) => Some(*i),
+                _ => None,
+            })
+            .collect();
+        let added: Vec<usize> = edits[k..end]
+            .iter()
+            .filter_map(|e| match e {
+                Edit::Added(j) => Some(*j),
+                _ => None,
+            })
+            .collect();
+        let mut added_tokens = vec![Vec::new(); added.len()];
+        for (n, &i) in removed.iter().enumerate() {
+            let tokens = match added.get(n) {
+                Some(&j) => {
+                    let (old_tokens, new_tokens) = token_diff(old_lines[i], new_lines[j], options);
+                    added_tokens[n] = new_tokens;
+                    old_tokens
+                }
+                None => Vec::new(),
+            };
+            lines.push(DefinitionDiffLine {
+                op: DiffOp::Removed,
+                old_line: Some(i + 1),
+                new_line: None,
+                text: old_lines[i].to_string(),
+                tokens,
+            });
+        }
+        for (&j, tokens) in added.iter().zip(added_tokens) {
+            lines.push(DefinitionDiffLine {
+                op: DiffOp::Added,
+                old_line: None,
+                new_line: Some(j + 1),
+                text: new_lines[j].to_string(),
+                tokens,
+            });
+        }
+        k = end;
+    }
+
+    let count = |op| lines.iter().filter(|l| l.op == op).count();
+    let (added, removed) = (count(DiffOp::Added), count(DiffOp::Removed));
+    DefinitionDiff {
+        identical: added == 0 && removed == 0,
+        added,
+        removed,
+        lines,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalization_hides_cosmetic_changes() {
+        let old = "CREATE VIEW [dbo].[Active] AS\nSELECT  Id, Name\nFROM dbo.Customers\nWHERE Status = 'A'";
+        let new = "create view dbo.Active as\nselect Id, [Name], Email\nFROM \"dbo\".Customers\nWHERE Status = 'a'";
+
+        let diff = diff_definitions(old, new, &DiffOptions::default());
+        let ops: Vec<(DiffOp, Option<usize>, Option<usize>)> = diff
+            .lines
+            .iter()
+            .map(|l| (l.op, l.old_line, l.new_line))
+            .collect();
+        assert_eq!(
+            ops,
+            vec![
+                (DiffOp::Unchanged, Some(1), Some(1)),
+                (DiffOp::Removed, Some(2), None),
+                (DiffOp::Added, None, Some(2)),
+                (DiffOp::Unchanged, Some(3), Some(3)),
+                (DiffOp::Removed, Some(4), None),
+                (DiffOp::Added, None, Some(4)),
+            ]
+        );
+        assert_eq!((diff.added, diff.removed), (2, 2));
+
+        // Only the new column is highlighted; the respaced and bracketed
+        // names are not
+        let changed: Vec<(DiffOp, &str)> = diff.lines[2]
+            .tokens
+            .iter()
+            .filter(|t| t.op != DiffOp::Unchanged)
+            .map(|t| (t.op, t.text.as_str()))
+            .collect();
+        assert_eq!(
+            changed,
+            vec![(DiffOp::Added, ","), (DiffOp::Added, "Email")]
+        );
+        let literal: Vec<&str> = diff.lines[5]
+            .tokens
+            .iter()
+            .filter(|t| t.op == DiffOp::Added)
+            .map(|t| t.text.as_str())
+            .collect();
+        assert_eq!(literal, vec!["'a'"]);
+
+        let strict = DiffOptions {
+            ignore_whitespace: false,
+            ignore_brackets: false,
+            ignore_case: false,
+        };
+        assert!(!diff_definitions(old, new, &strict).lines[0]
+            .tokens
+            .is_empty());
+        assert!(diff_definitions(old, old, &strict).identical);
+    }
+}
@@ -0,0 +1,219 @@
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Number of recent user/backend actions kept for crash reports.
+const ACTION_LOG_CAPACITY: usize = 50;
+/// Oldest reports beyond this count are pruned when a new one is written.
+const MAX_CRASH_REPORTS: usize = 20;
+const CRASH_REPORTS_DIR: &str = "crash-reports";
+
+static ACTION_LOG: Lazy<Mutex<VecDeque<String>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(ACTION_LOG_CAPACITY)));
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub id: String,
+    pub timestamp: String,
+    pub app_version: String,
+    pub os: String,
+    pub thread: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+    pub recent_actions: Vec<String>,
+}
+
+/// Append an entry to the in-memory action ring buffer.
+pub fn record_action(action: impl Into<String>) {
+    if let Ok(mut log) = ACTION_LOG.lock() {
+        if log.len() == ACTION_LOG_CAPACITY {
+            log.pop_front();
+        }
+        log.push_back(format!("{} {}", Utc::now().to_rfc3339(), action.into()));
+    }
+}
+
+pub fn recent_actions() -> Vec<String> {
+    ACTION_LOG
+        .lock()
+        .map(|log| log.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn crash_dir(storage_path: &Path) -> PathBuf {
+    storage_path.join(CRASH_REPORTS_DIR)
+}
+
+fn panic_message(info: &PanicHookInfo<'_>) -> String {
+    if let Some(s) = info.payload().downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = info.payload().downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "Unknown panic payload".to_string()
+    }
+}
+
+fn build_report(info: &PanicHookInfo<'_>) -> CrashReport {
+    let now = Utc::now();
+    CrashReport {
+        id: format!("crash-{}", now.format("%Y%m%dT%H%M%S%.3fZ")),
+        timestamp: now.to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        thread: std::thread::current()
+            .name()
+            .unwrap_or("unnamed")
+            .to_string(),
+        message: panic_message(info),
+        location: info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+        backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+        recent_actions: recent_actions(),
+    }
+}
+
+pub fn write_crash_report(storage_path: &Path, report: &CrashReport) -> Result<PathBuf, String> {
+    let dir = crash_dir(storage_path);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create crash report directory: {}", e))?;
+
+    let file = dir.join(format!("{}.json", report.id));
+    let content = serde_json::to_string_pretty(report)
+        .map_err(|e| format!("Failed to serialize crash report: {}", e))?;
+    std::fs::write(&file, content).map_err(|e| format!("Failed to write crash report: {}", e))?;
+
+    prune_crash_reports(&dir);
+    Ok(file)
+}
+
+fn prune_crash_reports(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+
+    if files.len() <= MAX_CRASH_REPORTS {
+        return;
+    }
+
+    // Report ids embed a sortable timestamp, so name order is age order
+    files.sort();
+    for file in &files[..files.len() - MAX_CRASH_REPORTS] {
+        let _ = std::fs::remove_file(file);
+    }
+}
+
+/// Read stored crash reports, newest first. Unreadable files are skipped.
+pub fn read_crash_reports(storage_path: &Path) -> Vec<CrashReport> {
+    let Ok(entries) = std::fs::read_dir(crash_dir(storage_path)) else {
+        return Vec::new();
+    };
+
+    let mut reports: Vec<CrashReport> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    reports.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    reports
+}
+
+pub fn clear_crash_reports(storage_path: &Path) -> Result<(), String> {
+    let dir = crash_dir(storage_path);
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)
+            .map_err(|e| format!("Failed to remove crash reports: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Install a panic hook that persists a crash report before delegating to the
+/// previously installed hook (which prints to stderr).
+pub fn install_panic_hook(storage_path: PathBuf) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let report = build_report(info);
+        if let Err(e) = write_crash_report(&storage_path, &report) {
+            eprintln!("Failed to write crash report: {}", e);
+        }
+        previous(info);
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn report(id: &str, timestamp: &str) -> CrashReport {
+        CrashReport {
+            id: id.to_string(),
+            timestamp: timestamp.to_string(),
+            app_version: "0.0.0".to_string(),
+            os: "test".to_string(),
+            thread: "main".to_string(),
+            message: "boom".to_string(),
+            location: None,
+            backtrace: String::new(),
+            recent_actions: vec!["load_schema".to_string()],
+        }
+    }
+
+    #[test]
+    fn action_log_is_bounded() {
+        for i in 0..(ACTION_LOG_CAPACITY + 10) {
+            record_action(format!("action {}", i));
+        }
+        let actions = recent_actions();
+        assert_eq!(actions.len(), ACTION_LOG_CAPACITY);
+        assert!(actions
+            .last()
+            .unwrap()
+            .ends_with(&format!("action {}", ACTION_LOG_CAPACITY + 9)));
+    }
+
+    #[test]
+    fn crash_reports_round_trip_newest_first() {
+        let dir = tempdir().expect("tempdir");
+        write_crash_report(
+            dir.path(),
+            &report("crash-20240101T000000.000Z", "2024-01-01T00:00:00+00:00"),
+        )
+        .unwrap();
+        write_crash_report(
+            dir.path(),
+            &report("crash-20240201T000000.000Z", "2024-02-01T00:00:00+00:00"),
+        )
+        .unwrap();
+
+        let reports = read_crash_reports(dir.path());
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].id, "crash-20240201T000000.000Z");
+        assert_eq!(reports[0].recent_actions, vec!["load_schema"]);
+
+        clear_crash_reports(dir.path()).unwrap();
+        assert!(read_crash_reports(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn old_crash_reports_are_pruned() {
+        let dir = tempdir().expect("tempdir");
+        for i in 0..(MAX_CRASH_REPORTS + 3) {
+            let id = format!("crash-2024010{}T{:06}.000Z", i / 10, i);
+            write_crash_report(dir.path(), &report(&id, "2024-01-01T00:00:00+00:00")).unwrap();
+        }
+        assert_eq!(read_crash_reports(dir.path()).len(), MAX_CRASH_REPORTS);
+    }
+}
@@ -0,0 +1,580 @@
+//! Correlation of an EF Core `Migrations` folder with the live schema. The
+//! `Up` method of each migration is scanned for table and column operations,
+//! which are replayed to build the schema the applied migrations describe.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use crate::db::working_tables::closing_paren;
+use crate::db::{object_id, AppliedMigration};
+use crate::types::SchemaGraph;
+
+const HISTORY_TABLE: &str = "__EFMigrationsHistory";
+
+static MIGRATION_FILE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\d{14}_\w+)\.cs$").unwrap());
+
+static UP_METHOD: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\bvoid\s+Up\s*\(\s*MigrationBuilder\s+(\w+)\s*\)\s*\{").unwrap());
+
+static OPERATION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(\w+)\s*\.\s*(CreateTable|DropTable|RenameTable|AddColumn|DropColumn|RenameColumn)\s*(?:<[^>]*>)?\s*\(")
+        .unwrap()
+});
+
+static NAMED_ARGUMENT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\b(\w+)\s*:\s*"([^"]*)""#).unwrap());
+
+static TABLE_COLUMN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b(\w+)\s*=\s*\w+\s*\.\s*Column\s*<[^>]*>\s*\(").unwrap());
+
+/// A table or column operation from a migration's `Up` method.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum EfOperation {
+    #[serde(rename_all = "camelCase")]
+    CreateTable {
+        table_id: String,
+        columns: Vec<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    DropTable { table_id: String },
+    #[serde(rename_all = "camelCase")]
+    RenameTable { table_id: String, new_id: String },
+    #[serde(rename_all = "camelCase")]
+    AddColumn { table_id: String, column: String },
+    #[serde(rename_all = "camelCase")]
+    DropColumn { table_id: String, column: String },
+    #[serde(rename_all = "camelCase")]
+    RenameColumn {
+        table_id: String,
+        column: String,
+        new_name: String,
+    },
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EfMigration {
+    /// `20240301120000_AddOrders`, the file name and history row ID.
+    pub id: String,
+    pub file: String,
+    /// Listed in `__EFMigrationsHistory`.
+    pub applied: bool,
+    pub operations: Vec<EfOperation>,
+}
+
+/// The migration that created a table, or added one of its columns.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EfObjectMigration {
+    pub object_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    pub migration_id: String,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum EfDriftKind {
+    /// A migration file that hasn't been applied.
+    PendingMigration,
+    /// An applied migration with no file in the folder.
+    MissingMigrationFile,
+    /// The applied migrations create a table the database doesn't have.
+    MissingTable,
+    MissingColumn,
+    /// A table no migration creates.
+    UnmanagedTable,
+    /// A column of a migrated table that no migration adds.
+    UnmanagedColumn,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EfDrift {
+    pub kind: EfDriftKind,
+    /// Table ID, or migration ID for the migration kinds.
+    pub object_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EfMigrationReport {
+    pub migrations: Vec<EfMigration>,
+    pub objects: Vec<EfObjectMigration>,
+    pub drift: Vec<EfDrift>,
+}
+
+/// Body of the `Up` method, and the name of its `MigrationBuilder`.
+fn up_method(source: &str) -> Option<(&str, &str)> {
+    let cap = UP_METHOD.captures(source)?;
+    let start = cap.get(0)?.end();
+    let mut depth = 0usize;
+    for (i, b) in source.bytes().enumerate().skip(start) {
+        match b {
+            b'{' => depth += 1,
+            b'}' if depth == 0 => return Some((cap.get(1)?.as_str(), &source[start..i])),
+            b'}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The string value of `name: "..."` among a call's top-level arguments.
+/// Nested calls, like the column builders of `CreateTable`, are skipped.
+fn argument(arguments: &str, name: &str) -> Option<String> {
+    let mut depth = 0usize;
+    let mut top_level = String::new();
+    for c in arguments.chars() {
+        match c {
+            '(' | '{' => depth += 1,
+            ')' | '}' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => top_level.push(c),
+            _ => {}
+        }
+    }
+    NAMED_ARGUMENT
+        .captures_iter(&top_level)
+        .find(|cap| &cap[1] == name)
+        .map(|cap| cap[2].to_string())
+}
+
+/// Operations of a migration, in source order. Tables without a `schema`
+/// argument are in `default_schema`.
+pub fn parse_migration(source: &str, default_schema: &str) -> Vec<EfOperation> {
+    let Some((builder, body)) = up_method(source) else {
+        return Vec::new();
+    };
+    let mut operations = Vec::new();
+    for cap in OPERATION.captures_iter(body) {
+        if &cap[1] != builder {
+            continue;
+        }
+        let start = cap.get(0).map_or(0, |m| m.end());
+        let arguments = &body[start..closing_paren(body, start)];
+        let schema = argument(arguments, "schema").unwrap_or_else(|| default_schema.to_string());
+        let table_name = match &cap[2] {
+            "CreateTable" | "DropTable" | "RenameTable" => argument(arguments, "name"),
+            _ => argument(arguments, "table"),
+        };
+        let Some(table_name) = table_name else {
+            continue;
+        };
+        let table_id = object_id(&[&schema, &table_name]);
+        let column = || argument(arguments, "name");
+        let operation = match &cap[2] {
+            "CreateTable" => EfOperation::CreateTable {
+                table_id,
+                columns: create_table_columns(arguments),
+            },
+            "DropTable" => EfOperation::DropTable { table_id },
+            "RenameTable" => {
+                let new_schema = argument(arguments, "newSchema").unwrap_or(schema);
+                let new_name = argument(arguments, "newName").unwrap_or(table_name);
+                EfOperation::RenameTable {
+                    table_id,
+                    new_id: object_id(&[&new_schema, &new_name]),
+                }
+            }
+            "AddColumn" => match column() {
+                Some(column) => EfOperation::AddColumn { table_id, column },
+                None => continue,
+            },
+            "DropColumn" => match column() {
+                Some(column) => EfOperation::DropColumn { table_id, column },
+                None => continue,
+            },
+            _ => match (column(), argument(arguments, "newName")) {
+                (Some(column), Some(new_name)) => EfOperation::RenameColumn {
+                    table_id,
+                    column,
+                    new_name,
+                },
+                _ => continue,
+            },
+        };
+        operations.push(operation);
+    }
+    operations
+}
+
+/// `Id = table.Column<int>(...)` entries of `columns: table => new { ... }`;
+/// a `name:` argument overrides the property name.
+fn create_table_columns(arguments: &str) -> Vec<String> {
+    TABLE_COLUMN
+        .captures_iter(arguments)
+        .map(|cap| {
+            let start = cap.get(0).map_or(0, |m| m.end());
+            let column_arguments = &arguments[start..closing_paren(arguments, start)];
+            argument(column_arguments, "name").unwrap_or_else(|| cap[1].to_string())
+        })
+        .collect()
+}
+
+/// Migration files of `folder`, in ID order. Designer and model snapshot
+/// files don't match the `<timestamp>_<Name>.cs` pattern and are skipped.
+pub fn scan_migrations(folder: &Path, default_schema: &str) -> Result<Vec<EfMigration>, String> {
+    let entries = std::fs::read_dir(folder)
+        .map_err(|e| format!("Failed to read {}: {}", folder.display(), e))?;
+    let mut migrations = Vec::new();
+    for path in entries.filter_map(Result::ok).map(|e| e.path()) {
+        let file = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let Some(cap) = MIGRATION_FILE.captures(&file) else {
+            continue;
+        };
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        migrations.push(EfMigration {
+            id: cap[1].to_string(),
+            file: file.clone(),
+            applied: false,
+            operations: parse_migration(&source, default_schema),
+        });
+    }
+    migrations.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(migrations)
+}
+
+/// A table of the migrated model, with the migration that created it and
+/// each column with the migration that added it.
+struct ModelTable {
+    id: String,
+    created_by: String,
+    columns: Vec<(String, String)>,
+}
+
+/// Tables keyed by lowercase ID, since SQL Server names are case-insensitive.
+type Model = BTreeMap<String, ModelTable>;
+
+fn columns<'m>(model: &'m mut Model, table_id: &str) -> Option<&'m mut Vec<(String, String)>> {
+    model
+        .get_mut(&table_id.to_lowercase())
+        .map(|t| &mut t.columns)
+}
+
+fn replay(model: &mut Model, migration: &EfMigration) {
+    for operation in &migration.operations {
+        match operation {
+            EfOperation::CreateTable { table_id, columns } => {
+                let columns = columns
+                    .iter()
+                    .map(|c| (c.clone(), migration.id.clone()))
+                    .collect();
+                model.insert(
+                    table_id.to_lowercase(),
+                    ModelTable {
+                        id: table_id.clone(),
+                        created_by: migration.id.clone(),
+                        columns,
+                    },
+                );
+            }
+            EfOperation::DropTable { table_id } => {
+                model.remove(&table_id.to_lowercase());
+            }
+            EfOperation::RenameTable { table_id, new_id } => {
+                if let Some(table) = model.remove(&table_id.to_lowercase()) {
+                    model.insert(
+                        new_id.to_lowercase(),
+                        ModelTable {
+                            id: new_id.clone(),
+                            ..table
+                        },
+                    );
+                }
+            }
+            EfOperation::AddColumn { table_id, column } => {
+                if let Some(columns) = columns(model, table_id) {
+                    columns.push((column.clone(), migration.id.clone()));
+                }
+            }
+            EfOperation::DropColumn { table_id, column } => {
+                if let Some(columns) = columns(model, table_id) {
+                    columns.retain(|(c, _)| !c.eq_ignore_ascii_case(column));
+                }
+            }
+            EfOperation::RenameColumn {
+                table_id,
+                column,
+                new_name,
+            } => {
+                for (c, _) in columns(model, table_id).into_iter().flatten() {
+                    if c.eq_ignore_ascii_case(column) {
+                        *c = new_name.clone();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Replay the applied migrations of `migrations` and compare the result with
+/// `graph`: which migration introduced each table and column, and where the
+/// folder, the history table and the database disagree.
+pub fn correlate_migrations(
+    graph: &SchemaGraph,
+    applied: &[AppliedMigration],
+    mut migrations: Vec<EfMigration>,
+) -> EfMigrationReport {
+    let applied_ids: HashSet<&str> = applied
+        .iter()
+        .filter_map(|m| m.version.as_deref())
+        .collect();
+    let mut drift = Vec::new();
+    for migration in &mut migrations {
+        migration.applied = applied_ids.contains(migration.id.as_str());
+        if !migration.applied {
+            drift.push(EfDrift {
+                kind: EfDriftKind::PendingMigration,
+                object_id: migration.id.clone(),
+                column: None,
+            });
+        }
+    }
+    let files: HashSet<&str> = migrations.iter().map(|m| m.id.as_str()).collect();
+    for id in &applied_ids {
+        if !files.contains(id) {
+            drift.push(EfDrift {
+                kind: EfDriftKind::MissingMigrationFile,
+                object_id: id.to_string(),
+                column: None,
+            });
+        }
+    }
+
+    let mut model = Model::new();
+    for migration in migrations.iter().filter(|m| m.applied) {
+        replay(&mut model, migration);
+    }
+
+    let mut objects = Vec::new();
+    for migrated in model.values() {
+        let Some(table) = graph
+            .tables
+            .iter()
+            .find(|t| t.id.eq_ignore_ascii_case(&migrated.id))
+        else {
+            drift.push(EfDrift {
+                kind: EfDriftKind::MissingTable,
+                object_id: migrated.id.clone(),
+                column: None,
+            });
+            continue;
+        };
+        objects.push(EfObjectMigration {
+            object_id: table.id.clone(),
+            column: None,
+            migration_id: migrated.created_by.clone(),
+        });
+        let columns = &migrated.columns;
+        for (column, migration_id) in columns {
+            if table
+                .columns
+                .iter()
+                .any(|c| c.name.eq_ignore_ascii_case(column))
+            {
+                objects.push(EfObjectMigration {
+                    object_id: table.id.clone(),
+                    column: Some(column.clone()),
+                    migration_id: migration_id.clone(),
+                });
+            } else {
+                drift.push(EfDrift {
+                    kind: EfDriftKind::MissingColumn,
+                    object_id: table.id.clone(),
+                    column: Some(column.clone()),
+                });
+            }
+        }
+        for column in &table.columns {
+            if !columns
+                .iter()
+                .any(|(c, _)| c.eq_ignore_ascii_case(&column.name))
+            {
+                drift.push(EfDrift {
+                    kind: EfDriftKind::UnmanagedColumn,
+                    object_id: table.id.clone(),
+                    column: Some(column.name.clone()),
+                });
+            }
+        }
+    }
+    for table in &graph.tables {
+        if !model.contains_key(&table.id.to_lowercase())
+            && !table.name.eq_ignore_ascii_case(HISTORY_TABLE)
+        {
+            drift.push(EfDrift {
+                kind: EfDriftKind::UnmanagedTable,
+                object_id: table.id.clone(),
+                column: None,
+            });
+        }
+    }
+    drift.sort_by(|a, b| (a.kind, &a.object_id).cmp(&(b.kind, &b.object_id)));
+
+    EfMigrationReport {
+        migrations,
+        objects,
+        drift,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Column, TableNode};
+    use tempfile::tempdir;
+
+    const CREATE_ORDERS: &str = r#"
+public partial class CreateOrders : Migration
+{
+    protected override void Up(MigrationBuilder migrationBuilder)
+    {
+        migrationBuilder.CreateTable(
+            name: "Orders",
+            columns: table => new
+            {
+                Id = table.Column<int>(type: "int", nullable: false)
+                    .Annotation("SqlServer:Identity", "1, 1"),
+                Total = table.Column<decimal>(name: "OrderTotal", type: "decimal(18,2)", nullable: false),
+                Note = table.Column<string>(maxLength: 200, nullable: true)
+            },
+            constraints: table =>
+            {
+                table.PrimaryKey("PK_Orders", x => x.Id);
+            });
+    }
+
+    protected override void Down(MigrationBuilder migrationBuilder)
+    {
+        migrationBuilder.DropTable(name: "Orders");
+    }
+}
+"#;
+
+    const ADD_STATUS: &str = r#"
+public partial class AddStatus : Migration
+{
+    protected override void Up(MigrationBuilder mb)
+    {
+        mb.AddColumn<string>(name: "Status", table: "Orders", nullable: true);
+        mb.DropColumn(name: "Note", table: "Orders");
+    }
+}
+"#;
+
+    fn table(name: &str, columns: &[&str]) -> TableNode {
+        TableNode {
+            id: object_id(&["dbo", name]),
+            name: name.to_string(),
+            schema: "dbo".into(),
+            columns: columns
+                .iter()
+                .map(|c| Column {
+                    name: c.to_string(),
+                    data_type: "int".into(),
+                    ..Default::default()
+                })
+                .collect(),
+            source: None,
+            metadata: None,
+            change_capture: None,
+        }
+    }
+
+    fn applied(id: &str) -> AppliedMigration {
+        AppliedMigration {
+            rank: 1,
+            version: Some(id.to_string()),
+            description: String::new(),
+            kind: "8.0.1".to_string(),
+            script: id.to_string(),
+            installed_by: String::new(),
+            installed_on: String::new(),
+            execution_ms: None,
+            success: true,
+        }
+    }
+
+    #[test]
+    fn applied_migrations_are_replayed_against_the_database() {
+        let dir = tempdir().expect("tempdir");
+        let write = |file: &str, source: &str| std::fs::write(dir.path().join(file), source);
+        write("20240301120000_CreateOrders.cs", CREATE_ORDERS).unwrap();
+        write("20240301120000_CreateOrders.Designer.cs", CREATE_ORDERS).unwrap();
+        write("20240410080000_AddStatus.cs", ADD_STATUS).unwrap();
+        write(
+            "20240501090000_AddCustomers.cs",
+            "void Up(MigrationBuilder b) { b.CreateTable(name: \"Customers\", schema: \"sales\", columns: t => new { Id = t.Column<int>() }); }",
+        )
+        .unwrap();
+        write("AppDbContextModelSnapshot.cs", "").unwrap();
+
+        let migrations = scan_migrations(dir.path(), "dbo").unwrap();
+        assert_eq!(migrations.len(), 3);
+        assert_eq!(
+            migrations[0].operations,
+            vec![EfOperation::CreateTable {
+                table_id: "dbo.Orders".to_string(),
+                columns: vec!["Id".into(), "OrderTotal".into(), "Note".into()],
+            }]
+        );
+
+        let graph = SchemaGraph {
+            tables: vec![
+                table("Orders", &["Id", "OrderTotal", "Legacy"]),
+                table("Audit", &["Id"]),
+                table(HISTORY_TABLE, &["MigrationId"]),
+            ],
+            ..Default::default()
+        };
+        let history = [
+            applied("20240301120000_CreateOrders"),
+            applied("20240410080000_AddStatus"),
+            applied("20231101000000_Initial"),
+        ];
+        let report = correlate_migrations(&graph, &history, migrations);
+
+        let drift: Vec<(EfDriftKind, &str, Option<&str>)> = report
+            .drift
+            .iter()
+            .map(|d| (d.kind, d.object_id.as_str(), d.column.as_deref()))
+            .collect();
+        assert_eq!(
+            drift,
+            vec![
+                (
+                    EfDriftKind::PendingMigration,
+                    "20240501090000_AddCustomers",
+                    None
+                ),
+                (
+                    EfDriftKind::MissingMigrationFile,
+                    "20231101000000_Initial",
+                    None
+                ),
+                (EfDriftKind::MissingColumn, "dbo.Orders", Some("Status")),
+                (EfDriftKind::UnmanagedTable, "dbo.Audit", None),
+                (EfDriftKind::UnmanagedColumn, "dbo.Orders", Some("Legacy")),
+            ]
+        );
+        assert_eq!(
+            report.objects[0],
+            EfObjectMigration {
+                object_id: "dbo.Orders".to_string(),
+                column: None,
+                migration_id: "20240301120000_CreateOrders".to_string(),
+            }
+        );
+        assert_eq!(report.objects.len(), 3);
+    }
+}
@@ -0,0 +1,497 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::fmt::Write;
+use std::path::Path;
+use std::process::Command;
+
+static MICROSOFT_DRIVER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^ODBC Driver (\d+) for SQL Server$").unwrap());
+static LEADING_VERSION: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+(?:\.\d+)+").unwrap());
+
+/// Where unixODBC installs look for drivers when `odbcinst -j` can't say.
+#[cfg(not(windows))]
+const ODBCINST_FALLBACKS: &[&str] = &[
+    "/etc/odbcinst.ini",
+    "/usr/local/etc/odbcinst.ini",
+    "/opt/homebrew/etc/odbcinst.ini",
+];
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OdbcDriver {
+    pub name: String,
+    pub version: Option<String>,
+    /// The driver library, as registered.
+    pub path: Option<String>,
+}
+
+impl OdbcDriver {
+    /// 17 for "ODBC Driver 17 for SQL Server"; `None` for other drivers.
+    fn microsoft_major(&self) -> Option<u32> {
+        MICROSOFT_DRIVER
+            .captures(&self.name)
+            .and_then(|c| c[1].parse().ok())
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DriverManager {
+    pub name: String,
+    pub version: Option<String>,
+    /// The file drivers are registered in.
+    pub drivers_file: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsLibrary {
+    pub name: String,
+    pub version: Option<String>,
+    /// Who uses it.
+    pub used_by: String,
+}
+
+/// What support needs to know about the machine's SQL Server client stack.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentReport {
+    pub app_version: String,
+    pub os: String,
+    pub os_version: Option<String>,
+    pub driver_manager: Option<DriverManager>,
+    pub drivers: Vec<OdbcDriver>,
+    pub has_driver_17: bool,
+    pub has_driver_18: bool,
+    pub tls: Vec<TlsLibrary>,
+    /// Known problems with this combination, worded for the user.
+    pub quirks: Vec<String>,
+    /// The whole report as plain text, for pasting into a support request.
+    pub text: String,
+}
+
+/// Output of a program, or `None` when it isn't installed or fails.
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Numeric parts of a library file name: `libmsodbcsql-18.3.so.2.1` is
+/// 18.3.2.1, `msodbcsql17.dll` is 17.
+fn version_from_path(path: &str) -> Option<String> {
+    let file = Path::new(path).file_name()?.to_string_lossy().into_owned();
+    let parts: Vec<String> = file
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("."))
+}
+
+/// `odbcinst -j`: the unixODBC version line, then `DRIVERS....: <path>`.
+#[cfg(any(not(windows), test))]
+fn parse_odbcinst_info(output: &str) -> DriverManager {
+    let version = output
+        .lines()
+        .find(|l| l.starts_with("unixODBC"))
+        .and_then(|l| LEADING_VERSION.find(l))
+        .map(|m| m.as_str().to_string());
+    let drivers_file = output
+        .lines()
+        .find(|l| l.starts_with("DRIVERS"))
+        .and_then(|l| l.split_once(':'))
+        .map(|(_, path)| path.trim().to_string());
+    DriverManager {
+        name: "unixODBC".to_string(),
+        version,
+        drivers_file,
+    }
+}
+
+/// Driver sections of an `odbcinst.ini`.
+#[cfg(any(not(windows), test))]
+fn parse_odbcinst_ini(content: &str) -> Vec<OdbcDriver> {
+    let mut drivers: Vec<OdbcDriver> = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if line.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            // `[ODBC]` holds driver manager options, not a driver
+            if !name.eq_ignore_ascii_case("ODBC") {
+                drivers.push(OdbcDriver {
+                    name: name.to_string(),
+                    version: None,
+                    path: None,
+                });
+            }
+            continue;
+        }
+        let (Some(driver), Some((key, value))) = (drivers.last_mut(), line.split_once('=')) else {
+            continue;
+        };
+        if key.trim().eq_ignore_ascii_case("Driver") {
+            let path = value.trim().to_string();
+            driver.version = version_from_path(&path);
+            driver.path = Some(path);
+        }
+    }
+    drivers
+}
+
+/// Values of a `reg query` listing: `    <name>    REG_SZ    <value>`.
+#[cfg(any(windows, test))]
+fn parse_reg_values(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|l| l.split_once("    REG_SZ    "))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+#[cfg(windows)]
+fn probe_odbc() -> (Option<DriverManager>, Vec<OdbcDriver>) {
+    const ODBCINST: &str = r"HKLM\SOFTWARE\ODBC\ODBCINST.INI";
+    let listed = run("reg", &["query", &format!(r"{}\ODBC Drivers", ODBCINST)]);
+    let drivers = parse_reg_values(listed.as_deref().unwrap_or_default())
+        .into_iter()
+        .map(|(name, _)| {
+            let key = format!(r"{}\{}", ODBCINST, name);
+            let path = run("reg", &["query", &key, "/v", "Driver"])
+                .and_then(|o| parse_reg_values(&o).into_iter().next())
+                .map(|(_, path)| path);
+            // The DLL's own version beats the digits in its name
+            let version = path
+                .as_deref()
+                .and_then(|p| {
+                    let script = format!("(Get-Item '{}').VersionInfo.ProductVersion", p);
+                    run("powershell", &["-NoProfile", "-Command", &script])
+                })
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .or_else(|| path.as_deref().and_then(version_from_path));
+            OdbcDriver {
+                name,
+                version,
+                path,
+            }
+        })
+        .collect();
+    let manager = DriverManager {
+        name: "Windows ODBC Driver Manager (odbc32.dll)".to_string(),
+        version: None,
+        drivers_file: Some(format!(r"{}\ODBC Drivers", ODBCINST)),
+    };
+    (Some(manager), drivers)
+}
+
+#[cfg(not(windows))]
+fn probe_odbc() -> (Option<DriverManager>, Vec<OdbcDriver>) {
+    let manager = run("odbcinst", &["-j"]).map(|o| parse_odbcinst_info(&o));
+    let drivers_file = manager
+        .as_ref()
+        .and_then(|m| m.drivers_file.clone())
+        .into_iter()
+        .chain(ODBCINST_FALLBACKS.iter().map(|p| p.to_string()))
+        .find(|p| Path::new(p).exists());
+    let drivers = drivers_file
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .map(|content| parse_odbcinst_ini(&content))
+        .unwrap_or_default();
+    (manager, drivers)
+}
+
+fn os_version() -> Option<String> {
+    match std::env::consts::OS {
+        "linux" => std::fs::read_to_string("/etc/os-release")
+            .ok()?
+            .lines()
+            .find_map(|l| l.strip_prefix("PRETTY_NAME="))
+            .map(|v| v.trim_matches('"').to_string()),
+        "macos" => run("sw_vers", &["-productVersion"]).map(|v| format!("macOS {}", v.trim())),
+        "windows" => run("cmd", &["/C", "ver"]).map(|v| v.trim().to_string()),
+        _ => None,
+    }
+}
+
+fn tls_libraries() -> Vec<TlsLibrary> {
+    let mut libraries = vec![TlsLibrary {
+        name: "rustls".to_string(),
+        version: None,
+        used_by: "Monocle's own connections (built in)".to_string(),
+    }];
+    if cfg!(windows) {
+        libraries.push(TlsLibrary {
+            name: "Schannel".to_string(),
+            version: None,
+            used_by: "ODBC drivers".to_string(),
+        });
+    } else if let Some(output) = run("openssl", &["version"]) {
+        libraries.push(TlsLibrary {
+            name: "OpenSSL".to_string(),
+            version: LEADING_VERSION
+                .find(&output)
+                .map(|m| m.as_str().to_string()),
+            used_by: "ODBC drivers".to_string(),
+        });
+    }
+    libraries
+}
+
+fn major_minor(version: &str) -> (u32, u32) {
+    let mut parts = version.split('.').map(|p| p.parse().unwrap_or(0));
+    (parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+}
+
+/// Known trouble with this driver manager, driver and TLS combination.
+fn quirks(
+    os: &str,
+    manager: Option<&DriverManager>,
+    drivers: &[OdbcDriver],
+    tls: &[TlsLibrary],
+) -> Vec<String> {
+    let mut quirks = Vec::new();
+    if os != "windows" && manager.is_none() {
+        quirks.push(
+            "unixODBC is not installed (odbcinst was not found); the Microsoft ODBC driver \
+             needs it"
+                .to_string(),
+        );
+    }
+    let microsoft: Vec<(u32, &OdbcDriver)> = drivers
+        .iter()
+        .filter_map(|d| d.microsoft_major().map(|major| (major, d)))
+        .collect();
+    if !microsoft.iter().any(|(major, _)| *major >= 17) {
+        let legacy = drivers.iter().any(|d| d.name == "SQL Server");
+        quirks.push(match legacy {
+            true => "Only the legacy \"SQL Server\" driver is installed; install ODBC Driver 18 \
+                     for SQL Server for current TLS and authentication support"
+                .to_string(),
+            false => "Neither ODBC Driver 17 nor 18 for SQL Server is installed".to_string(),
+        });
+    }
+    if microsoft.iter().any(|(major, _)| *major == 18) {
+        quirks.push(
+            "ODBC Driver 18 encrypts by default; servers with self-signed certificates need \
+             TrustServerCertificate=yes"
+                .to_string(),
+        );
+    }
+    let openssl_3 = tls
+        .iter()
+        .any(|t| t.name == "OpenSSL" && t.version.as_deref().is_some_and(|v| v.starts_with('3')));
+    for (major, driver) in &microsoft {
+        let old = driver
+            .version
+            .as_deref()
+            .is_some_and(|v| *major == 17 && major_minor(v) < (17, 10));
+        if openssl_3 && old {
+            quirks.push(format!(
+                "{} {} predates OpenSSL 3 support; upgrade to 17.10 or later",
+                driver.name,
+                driver.version.as_deref().unwrap_or_default()
+            ));
+        }
+    }
+    for driver in drivers {
+        if let Some(path) = &driver.path {
+            // Windows paths may use %SystemRoot% and similar
+            if !path.contains('%') && !Path::new(path).exists() {
+                quirks.push(format!(
+                    "{} is registered but its library {} is missing",
+                    driver.name, path
+                ));
+            }
+        }
+    }
+    quirks
+}
+
+fn yes_no(present: bool) -> &'static str {
+    match present {
+        true => "yes",
+        false => "no",
+    }
+}
+
+fn render(report: &EnvironmentReport) -> String {
+    let mut text = String::new();
+    let _ = writeln!(text, "Monocle {} environment", report.app_version);
+    let _ = writeln!(
+        text,
+        "OS: {}{}",
+        report.os,
+        report
+            .os_version
+            .as_deref()
+            .map(|v| format!(" ({})", v))
+            .unwrap_or_default()
+    );
+    let manager = match &report.driver_manager {
+        Some(m) => format!(
+            "{}{}{}",
+            m.name,
+            m.version
+                .as_deref()
+                .map(|v| format!(" {}", v))
+                .unwrap_or_default(),
+            m.drivers_file
+                .as_deref()
+                .map(|f| format!(", drivers in {}", f))
+                .unwrap_or_default()
+        ),
+        None => "not found".to_string(),
+    };
+    let _ = writeln!(text, "Driver manager: {}", manager);
+    let _ = writeln!(
+        text,
+        "ODBC Driver 17 for SQL Server: {}",
+        yes_no(report.has_driver_17)
+    );
+    let _ = writeln!(
+        text,
+        "ODBC Driver 18 for SQL Server: {}",
+        yes_no(report.has_driver_18)
+    );
+    let _ = writeln!(text, "Drivers:");
+    if report.drivers.is_empty() {
+        let _ = writeln!(text, "  (none)");
+    }
+    for driver in &report.drivers {
+        let _ = writeln!(
+            text,
+            "  {} | {} | {}",
+            driver.name,
+            driver.version.as_deref().unwrap_or("?"),
+            driver.path.as_deref().unwrap_or("?")
+        );
+    }
+    let _ = writeln!(text, "TLS:");
+    for library in &report.tls {
+        let _ = writeln!(
+            text,
+            "  {} {} ({})",
+            library.name,
+            library.version.as_deref().unwrap_or("?"),
+            library.used_by
+        );
+    }
+    if !report.quirks.is_empty() {
+        let _ = writeln!(text, "Notes:");
+        for quirk in &report.quirks {
+            let _ = writeln!(text, "  - {}", quirk);
+        }
+    }
+    text
+}
+
+fn build_report(
+    os_version: Option<String>,
+    driver_manager: Option<DriverManager>,
+    drivers: Vec<OdbcDriver>,
+    tls: Vec<TlsLibrary>,
+) -> EnvironmentReport {
+    let os = std::env::consts::OS;
+    let has_major = |major| drivers.iter().any(|d| d.microsoft_major() == Some(major));
+    let mut report = EnvironmentReport {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: format!("{} {}", os, std::env::consts::ARCH),
+        os_version,
+        has_driver_17: has_major(17),
+        has_driver_18: has_major(18),
+        quirks: quirks(os, driver_manager.as_ref(), &drivers, &tls),
+        driver_manager,
+        drivers,
+        tls,
+        text: String::new(),
+    };
+    report.text = render(&report);
+    report
+}
+
+/// Inspect the installed ODBC drivers, driver manager and TLS libraries.
+/// Runs system tools (`odbcinst`, `reg`, `openssl`), so call it off the
+/// async runtime.
+pub fn diagnose_environment() -> EnvironmentReport {
+    let (manager, drivers) = probe_odbc();
+    build_report(os_version(), manager, drivers, tls_libraries())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unix_odbc_configuration_is_parsed() {
+        let manager = parse_odbcinst_info(
+            "unixODBC 2.3.11\nDRIVERS............: /etc/odbcinst.ini\n\
+             SYSTEM DATA SOURCES: /etc/odbc.ini\nSQLULEN Size.......: 8\n",
+        );
+        assert_eq!(manager.version.as_deref(), Some("2.3.11"));
+        assert_eq!(manager.drivers_file.as_deref(), Some("/etc/odbcinst.ini"));
+
+        let drivers = parse_odbcinst_ini(
+            "[ODBC]\nTrace=No\n\n[ODBC Driver 17 for SQL Server]\n\
+             Description=Microsoft ODBC Driver 17 for SQL Server\n\
+             Driver=/opt/microsoft/msodbcsql17/lib64/libmsodbcsql-17.6.so.1.1\n\
+             # Driver=/old/path.so\n[FreeTDS]\nDriver = /usr/lib/libtdsodbc.so\n",
+        );
+        assert_eq!(
+            drivers,
+            vec![
+                OdbcDriver {
+                    name: "ODBC Driver 17 for SQL Server".into(),
+                    version: Some("17.6.1.1".into()),
+                    path: Some("/opt/microsoft/msodbcsql17/lib64/libmsodbcsql-17.6.so.1.1".into()),
+                },
+                OdbcDriver {
+                    name: "FreeTDS".into(),
+                    version: None,
+                    path: Some("/usr/lib/libtdsodbc.so".into()),
+                },
+            ]
+        );
+        assert_eq!(
+            parse_reg_values("\nHKEY_LOCAL_MACHINE\\...\n    SQL Server    REG_SZ    Installed\n"),
+            vec![("SQL Server".to_string(), "Installed".to_string())]
+        );
+    }
+
+    #[test]
+    fn report_flags_known_quirks() {
+        let driver = |name: &str, version: &str| OdbcDriver {
+            name: name.to_string(),
+            version: Some(version.to_string()),
+            path: None,
+        };
+        let tls = vec![TlsLibrary {
+            name: "OpenSSL".into(),
+            version: Some("3.0.2".into()),
+            used_by: "ODBC drivers".into(),
+        }];
+        let report = build_report(
+            None,
+            None,
+            vec![driver("ODBC Driver 17 for SQL Server", "17.6.1.1")],
+            tls.clone(),
+        );
+        assert!(report.has_driver_17 && !report.has_driver_18);
+        assert!(report.text.contains("Driver manager: not found"));
+        assert!(report
+            .text
+            .contains("ODBC Driver 17 for SQL Server | 17.6.1.1 | ?"));
+        assert!(report.quirks.iter().any(|q| q.contains("OpenSSL 3")));
+        if std::env::consts::OS != "windows" {
+            assert!(report.quirks[0].contains("unixODBC is not installed"));
+        }
+
+        let quirks = quirks("windows", None, &[driver("SQL Server", "10.0")], &tls);
+        assert_eq!(quirks.len(), 1);
+        assert!(quirks[0].contains("legacy"));
+    }
+}
@@ -0,0 +1,568 @@
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+use crate::db::constraint_participation;
+use crate::ddl::generator::foreign_keys;
+use crate::export_annotations::{one_line, ExportAnnotations};
+use crate::types::{Optionality, Participation, RelationshipEdge, SchemaGraph, TableNode};
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ErFormat {
+    Mermaid,
+    Dbml,
+    PlantUml,
+}
+
+/// One foreign key constraint, with its edges regrouped.
+struct Constraint<'a> {
+    name: &'a str,
+    child: &'a TableNode,
+    parent: &'a TableNode,
+    from_columns: Vec<&'a str>,
+    to_columns: Vec<&'a str>,
+    participation: Option<Participation>,
+}
+
+fn constraints(graph: &SchemaGraph) -> Vec<Constraint<'_>> {
+    let tables: HashMap<&str, &TableNode> =
+        graph.tables.iter().map(|t| (t.id.as_str(), t)).collect();
+    foreign_keys(graph)
+        .into_iter()
+        .filter_map(|(name, from, to, edges)| {
+            let (child, parent) = (*tables.get(from)?, *tables.get(to)?);
+            let from_columns: Vec<&str> = edges
+                .iter()
+                .filter_map(|e| e.from_column.as_deref())
+                .collect();
+            let to_columns = edges
+                .iter()
+                .filter_map(|e: &&RelationshipEdge| e.to_column.as_deref())
+                .collect();
+            let participation = edges
+                .first()
+                .and_then(|e| e.participation)
+                .or_else(|| constraint_participation(child, &from_columns));
+            Some(Constraint {
+                name,
+                child,
+                parent,
+                from_columns,
+                to_columns,
+                participation,
+            })
+        })
+        .collect()
+}
+
+/// Tables that only link two others: exactly two foreign keys whose columns
+/// together make up the primary key. Maps the junction to its two parents,
+/// in ID order.
+fn junction_tables<'a>(constraints: &[Constraint<'a>]) -> HashMap<&'a str, (&'a str, &'a str)> {
+    let mut by_child: HashMap<&str, Vec<&Constraint>> = HashMap::new();
+    for constraint in constraints {
+        by_child
+            .entry(constraint.child.id.as_str())
+            .or_default()
+            .push(constraint);
+    }
+    by_child
+        .into_iter()
+        .filter_map(|(id, keys)| {
+            let [left, right] = keys.as_slice() else {
+                return None;
+            };
+            let primary_key: BTreeSet<&str> = left
+                .child
+                .columns
+                .iter()
+                .filter(|c| c.is_primary_key)
+                .map(|c| c.name.as_str())
+                .collect();
+            let key_columns: BTreeSet<&str> = left
+                .from_columns
+                .iter()
+                .chain(&right.from_columns)
+                .copied()
+                .collect();
+            let (a, b) = (left.parent.id.as_str(), right.parent.id.as_str());
+            (!primary_key.is_empty() && key_columns == primary_key)
+                .then_some((id, (a.min(b), a.max(b))))
+        })
+        .collect()
+}
+
+/// Crow's-foot ends and line style shared by Mermaid and PlantUML: parent
+/// end, line, child end. Unknown participation is drawn as the loosest
+/// reading, optional and non-identifying.
+fn crows_foot(participation: Option<Participation>) -> (&'static str, &'static str, &'static str) {
+    let Some(p) = participation else {
+        return ("|o", "..", "o{");
+    };
+    (
+        match p.parent {
+            Optionality::Mandatory => "||",
+            Optionality::Optional => "|o",
+        },
+        if p.identifying { "--" } else { ".." },
+        if p.one_to_one { "o|" } else { "o{" },
+    )
+}
+
+/// Legend lines for the ends and lines drawn by [`crows_foot`].
+const CROWS_FOOT_LEGEND: &[&str] = &[
+    "||  exactly one parent",
+    "|o  zero or one parent",
+    "o{  zero or more children",
+    "o|  zero or one child",
+    "--  identifying, the key is part of the child's primary key",
+    "..  non-identifying",
+];
+
+fn legend(format: ErFormat, collapse_junctions: bool) -> Vec<&'static str> {
+    let mut lines = match format {
+        ErFormat::Dbml => {
+            return vec![
+                ">  many-to-one, child > parent",
+                "-  one-to-one",
+                "[pk]  primary key",
+                "[not null]  required column",
+            ]
+        }
+        ErFormat::Mermaid => vec!["PK, FK  primary and foreign key columns"],
+        ErFormat::PlantUml => vec![
+            "*  required column",
+            "<<PK>>, <<FK>>  primary and foreign key columns",
+        ],
+    };
+    lines.extend(CROWS_FOOT_LEGEND);
+    if collapse_junctions {
+        lines.push("}o..o{  many-to-many through a junction table");
+    }
+    lines
+}
+
+fn alias(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn mermaid_token(text: &str, keep: &[char]) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || keep.contains(&c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn foreign_key_columns<'a>(constraints: &[Constraint<'a>]) -> HashMap<&'a str, BTreeSet<&'a str>> {
+    let mut columns: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    for constraint in constraints {
+        columns
+            .entry(constraint.child.id.as_str())
+            .or_default()
+            .extend(&constraint.from_columns);
+    }
+    columns
+}
+
+fn mermaid(
+    graph: &SchemaGraph,
+    constraints: &[Constraint<'_>],
+    junctions: &HashMap<&str, (&str, &str)>,
+) -> String {
+    let fk_columns = foreign_key_columns(constraints);
+    let mut out = String::from("erDiagram\n");
+    for table in graph
+        .tables
+        .iter()
+        .filter(|t| !junctions.contains_key(t.id.as_str()))
+    {
+        let _ = writeln!(
+            out,
+            "    {}[\"{}\"] {{",
+            alias(&table.id),
+            table.id.replace('"', "'")
+        );
+        for column in &table.columns {
+            let is_fk = fk_columns
+                .get(table.id.as_str())
+                .is_some_and(|c| c.contains(column.name.as_str()));
+            let keys = match (column.is_primary_key, is_fk) {
+                (true, true) => " PK, FK",
+                (true, false) => " PK",
+                (false, true) => " FK",
+                (false, false) => "",
+            };
+            let _ = writeln!(
+                out,
+                "        {} {}{}",
+                mermaid_token(&column.data_type, &['(', ')', '-']),
+                mermaid_token(&column.name, &['-']),
+                keys
+            );
+        }
+        out.push_str("    }\n");
+    }
+    for constraint in constraints {
+        if junctions.contains_key(constraint.child.id.as_str()) {
+            continue;
+        }
+        let (parent_end, line, child_end) = crows_foot(constraint.participation);
+        let _ = writeln!(
+            out,
+            "    {} {}{}{} {} : \"{}\"",
+            alias(&constraint.parent.id),
+            parent_end,
+            line,
+            child_end,
+            alias(&constraint.child.id),
+            constraint.name.replace('"', "'")
+        );
+    }
+    for (junction, (left, right)) in sorted(junctions) {
+        let _ = writeln!(
+            out,
+            "    {} }}o..o{{ {} : \"{}\"",
+            alias(left),
+            alias(right),
+            junction.replace('"', "'")
+        );
+    }
+    out
+}
+
+/// PlantUML renders its own title, caption and legend blocks, so the
+/// annotations are drawn on the diagram rather than left as comments.
+fn plant_uml(
+    graph: &SchemaGraph,
+    constraints: &[Constraint<'_>],
+    junctions: &HashMap<&str, (&str, &str)>,
+    annotations: &ExportAnnotations,
+) -> String {
+    let fk_columns = foreign_key_columns(constraints);
+    let mut out = String::from("@startuml\nhide circle\nskinparam linetype ortho\n");
+    let title = annotations.title_lines();
+    if !title.is_empty() {
+        out.push_str("title\n");
+        for (label, value) in title {
+            let _ = writeln!(out, "  {}: {}", label, one_line(value));
+        }
+        out.push_str("end title\n");
+    }
+    if let Some(watermark) = annotations.watermark() {
+        let _ = writeln!(out, "caption {}", one_line(watermark));
+    }
+    for table in graph
+        .tables
+        .iter()
+        .filter(|t| !junctions.contains_key(t.id.as_str()))
+    {
+        let _ = writeln!(out, "\nentity \"{}\" as {} {{", table.id, alias(&table.id));
+        let fks = fk_columns.get(table.id.as_str());
+        let line = |column: &crate::types::Column| {
+            let is_fk = fks.is_some_and(|c| c.contains(column.name.as_str()));
+            format!(
+                "  {}{} : {}{}{}\n",
+                if column.is_nullable { "" } else { "* " },
+                column.name,
+                column.data_type,
+                if column.is_primary_key { " <<PK>>" } else { "" },
+                if is_fk { " <<FK>>" } else { "" }
+            )
+        };
+        let (key, rest): (Vec<_>, Vec<_>) = table.columns.iter().partition(|c| c.is_primary_key);
+        key.iter().for_each(|c| out.push_str(&line(c)));
+        if !key.is_empty() {
+            out.push_str("  --\n");
+        }
+        rest.iter().for_each(|c| out.push_str(&line(c)));
+        out.push_str("}\n");
+    }
+    out.push('\n');
+    for constraint in constraints {
+        if junctions.contains_key(constraint.child.id.as_str()) {
+            continue;
+        }
+        let (parent_end, line, child_end) = crows_foot(constraint.participation);
+        let _ = writeln!(
+            out,
+            "{} {}{}{} {} : {}",
+            alias(&constraint.parent.id),
+            parent_end,
+            line,
+            child_end,
+            alias(&constraint.child.id),
+            constraint.name
+        );
+    }
+    for (junction, (left, right)) in sorted(junctions) {
+        let _ = writeln!(
+            out,
+            "{} }}o..o{{ {} : {}",
+            alias(left),
+            alias(right),
+            junction
+        );
+    }
+    if annotations.legend {
+        out.push_str("\nlegend right\n");
+        for line in legend(ErFormat::PlantUml, !junctions.is_empty()) {
+            let _ = writeln!(out, "  {}", line);
+        }
+        out.push_str("endlegend\n");
+    }
+    out.push_str("@enduml\n");
+    out
+}
+
+fn dbml_name(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\\\""))
+}
+
+fn dbml_table(table: &TableNode) -> String {
+    format!("{}.{}", dbml_name(&table.schema), dbml_name(&table.name))
+}
+
+fn dbml_columns(table: &TableNode, columns: &[&str]) -> String {
+    match columns {
+        [column] => format!("{}.{}", dbml_table(table), dbml_name(column)),
+        _ => format!(
+            "{}.({})",
+            dbml_table(table),
+            columns
+                .iter()
+                .map(|c| dbml_name(c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// DBML marks optionality on the column (`not null`) and cardinality on
+/// the reference, and junction tables are kept since references are made
+/// column to column.
+fn dbml(graph: &SchemaGraph, constraints: &[Constraint<'_>]) -> String {
+    let mut out = String::new();
+    for table in &graph.tables {
+        let _ = writeln!(out, "Table {} {{", dbml_table(table));
+        let primary_key: Vec<&str> = table
+            .columns
+            .iter()
+            .filter(|c| c.is_primary_key)
+            .map(|c| c.name.as_str())
+            .collect();
+        for column in &table.columns {
+            let setting = if column.is_primary_key && primary_key.len() == 1 {
+                " [pk]"
+            } else if !column.is_nullable {
+                " [not null]"
+            } else {
+                ""
+            };
+            let data_type = if column.data_type.contains(' ') {
+                dbml_name(&column.data_type)
+            } else {
+                column.data_type.to_string()
+            };
+            let _ = writeln!(
+                out,
+                "  {} {}{}",
+                dbml_name(&column.name),
+                data_type,
+                setting
+            );
+        }
+        if primary_key.len() > 1 {
+            let columns: Vec<String> = primary_key.iter().map(|c| dbml_name(c)).collect();
+            let _ = writeln!(
+                out,
+                "\n  indexes {{\n    ({}) [pk]\n  }}",
+                columns.join(", ")
+            );
+        }
+        out.push_str("}\n\n");
+    }
+    for constraint in constraints {
+        if constraint.from_columns.is_empty()
+            || constraint.from_columns.len() != constraint.to_columns.len()
+        {
+            continue;
+        }
+        let cardinality = match constraint.participation {
+            Some(p) if p.one_to_one => "-",
+            _ => ">",
+        };
+        let _ = writeln!(
+            out,
+            "Ref {}: {} {} {}",
+            dbml_name(constraint.name),
+            dbml_columns(constraint.child, &constraint.from_columns),
+            cardinality,
+            dbml_columns(constraint.parent, &constraint.to_columns)
+        );
+    }
+    out
+}
+
+fn sorted<'a>(
+    junctions: &HashMap<&'a str, (&'a str, &'a str)>,
+) -> Vec<(&'a str, (&'a str, &'a str))> {
+    let mut junctions: Vec<_> = junctions.iter().map(|(k, v)| (*k, *v)).collect();
+    junctions.sort();
+    junctions
+}
+
+/// ER diagram source for the tables of `graph` with crow's-foot ends taken
+/// from each foreign key's participation. With `collapse_junctions`, Mermaid
+/// and PlantUML draw junction tables as a many-to-many line between the two
+/// tables they link. `annotations` become comments at the top, except in
+/// PlantUML, which draws them.
+pub fn export_er_diagram(
+    graph: &SchemaGraph,
+    format: ErFormat,
+    collapse_junctions: bool,
+    annotations: &ExportAnnotations,
+) -> String {
+    let constraints = constraints(graph);
+    let junctions = if collapse_junctions {
+        junction_tables(&constraints)
+    } else {
+        HashMap::new()
+    };
+    let legend = legend(format, !junctions.is_empty());
+    match format {
+        ErFormat::Mermaid => {
+            annotations.comment_block("%%", &legend) + &mermaid(graph, &constraints, &junctions)
+        }
+        ErFormat::PlantUml => plant_uml(graph, &constraints, &junctions, annotations),
+        ErFormat::Dbml => annotations.comment_block("//", &legend) + &dbml(graph, &constraints),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::annotate_participation;
+    use crate::types::Column;
+
+    fn column(name: &str, nullable: bool, pk: bool) -> Column {
+        Column {
+            name: name.to_string(),
+            data_type: "int".into(),
+            is_nullable: nullable,
+            is_primary_key: pk,
+            ..Default::default()
+        }
+    }
+
+    fn table(name: &str, columns: Vec<Column>) -> TableNode {
+        TableNode {
+            id: format!("dbo.{}", name),
+            name: name.to_string(),
+            schema: "dbo".into(),
+            columns,
+            source: None,
+            metadata: None,
+            change_capture: None,
+        }
+    }
+
+    fn edge(id: &str, from: &str, column: &str, to: &str) -> RelationshipEdge {
+        RelationshipEdge {
+            id: id.to_string(),
+            from: format!("dbo.{}", from),
+            to: format!("dbo.{}", to),
+            from_column: Some(column.to_string()),
+            to_column: Some("Id".to_string()),
+            source: None,
+            is_virtual: false,
+            participation: None,
+        }
+    }
+
+    fn graph() -> SchemaGraph {
+        let mut graph = SchemaGraph {
+            tables: vec![
+                table("Students", vec![column("Id", false, true)]),
+                table("Courses", vec![column("Id", false, true)]),
+                table(
+                    "Enrollments",
+                    vec![
+                        column("StudentId", false, true),
+                        column("CourseId", false, true),
+                    ],
+                ),
+                table(
+                    "Lockers",
+                    vec![column("Id", false, true), column("StudentId", true, false)],
+                ),
+            ],
+            relationships: vec![
+                edge(
+                    "FK_Enrollments_Students",
+                    "Enrollments",
+                    "StudentId",
+                    "Students",
+                ),
+                edge(
+                    "FK_Enrollments_Courses",
+                    "Enrollments",
+                    "CourseId",
+                    "Courses",
+                ),
+                edge("FK_Lockers_Students", "Lockers", "StudentId", "Students"),
+            ],
+            ..Default::default()
+        };
+        annotate_participation(&mut graph);
+        graph
+    }
+
+    #[test]
+    fn crows_foot_ends_follow_participation() {
+        let graph = graph();
+
+        let mermaid = export_er_diagram(
+            &graph,
+            ErFormat::Mermaid,
+            false,
+            &ExportAnnotations::default(),
+        );
+        assert!(
+            mermaid.contains("dbo_Students ||--o{ dbo_Enrollments : \"FK_Enrollments_Students\"")
+        );
+        assert!(mermaid.contains("dbo_Students |o..o{ dbo_Lockers : \"FK_Lockers_Students\""));
+        assert!(mermaid.contains("int StudentId PK, FK"));
+
+        let annotations = ExportAnnotations {
+            legend: true,
+            title_block: Some(crate::export_annotations::TitleBlock {
+                database: Some("School".to_string()),
+                ..Default::default()
+            }),
+            watermark: Some("DRAFT".to_string()),
+        };
+        let collapsed = export_er_diagram(&graph, ErFormat::PlantUml, true, &annotations);
+        assert!(collapsed.contains("title\n  Database: School\nend title\ncaption DRAFT\n"));
+        assert!(collapsed.contains("legend right\n  *  required column\n"));
+        assert!(
+            collapsed.contains("}o..o{  many-to-many through a junction table\nendlegend\n@enduml")
+        );
+        assert!(!collapsed.contains("as dbo_Enrollments"));
+        assert!(collapsed.contains("dbo_Courses }o..o{ dbo_Students : dbo.Enrollments"));
+        assert!(collapsed.contains("dbo_Students |o..o{ dbo_Lockers : FK_Lockers_Students"));
+
+        let dbml = export_er_diagram(&graph, ErFormat::Dbml, true, &ExportAnnotations::default());
+        assert!(dbml.contains("(\"StudentId\", \"CourseId\") [pk]"));
+        assert!(dbml.contains(
+            "Ref \"FK_Lockers_Students\": \"dbo\".\"Lockers\".\"StudentId\" > \"dbo\".\"Students\".\"Id\""
+        ));
+    }
+}
@@ -0,0 +1,150 @@
+use glob::{MatchOptions, Pattern};
+use std::collections::HashSet;
+
+use crate::i18n::t;
+use crate::types::SchemaGraph;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+
+/// Compile exclusion patterns, rejecting the whole list if any is invalid.
+pub fn compile_patterns(patterns: &[String]) -> Result<Vec<Pattern>, String> {
+    patterns
+        .iter()
+        .map(|p| {
+            Pattern::new(p.trim()).map_err(|e| t!("error-invalid-pattern", pattern = p, reason = e))
+        })
+        .collect()
+}
+
+/// Patterns are validated when saved, so one that no longer compiles (a
+/// hand-edited settings file) is skipped rather than failing the load.
+pub fn compile_saved_patterns(patterns: &[String]) -> Vec<Pattern> {
+    patterns
+        .iter()
+        .filter_map(|p| Pattern::new(p.trim()).ok())
+        .collect()
+}
+
+/// A pattern matches an object by its full ID (`audit.*`) or by bare name
+/// (`__EFMigrationsHistory`), case-insensitively.
+fn is_excluded(patterns: &[Pattern], id: &str, name: &str) -> bool {
+    patterns
+        .iter()
+        .any(|p| p.matches_with(id, MATCH_OPTIONS) || p.matches_with(name, MATCH_OPTIONS))
+}
+
+/// Remove excluded objects along with every edge and reference that touches
+/// them. Unlike the integrity pass this is silent: hiding is intentional.
+/// Returns how many objects were removed.
+pub fn apply_exclusions(graph: &mut SchemaGraph, patterns: &[Pattern]) -> usize {
+    if patterns.is_empty() {
+        return 0;
+    }
+
+    let mut removed: HashSet<String> = HashSet::new();
+    macro_rules! exclude {
+        ($items:expr) => {
+            $items.retain(|item| {
+                let excluded = is_excluded(patterns, &item.id, &item.name);
+                if excluded {
+                    removed.insert(item.id.clone());
+                }
+                !excluded
+            });
+        };
+    }
+    exclude!(graph.tables);
+    exclude!(graph.views);
+    exclude!(graph.stored_procedures);
+    exclude!(graph.scalar_functions);
+    exclude!(graph.triggers);
+
+    // Triggers go with their table
+    let orphaned: Vec<String> = graph
+        .triggers
+        .iter()
+        .filter(|t| removed.contains(&t.table_id))
+        .map(|t| t.id.clone())
+        .collect();
+    graph.triggers.retain(|t| !removed.contains(&t.table_id));
+    removed.extend(orphaned);
+
+    graph
+        .relationships
+        .retain(|r| !removed.contains(&r.from) && !removed.contains(&r.to));
+    graph
+        .links
+        .retain(|l| !removed.contains(&l.from) && !removed.contains(&l.to));
+
+    let keep = |refs: &mut Vec<String>| refs.retain(|id| !removed.contains(id));
+    for view in &mut graph.views {
+        keep(&mut view.referenced_tables);
+    }
+    for trigger in &mut graph.triggers {
+        keep(&mut trigger.referenced_tables);
+        keep(&mut trigger.affected_tables);
+    }
+    for procedure in &mut graph.stored_procedures {
+        keep(&mut procedure.referenced_tables);
+        keep(&mut procedure.affected_tables);
+    }
+    for function in &mut graph.scalar_functions {
+        keep(&mut function.referenced_tables);
+        keep(&mut function.affected_tables);
+    }
+    for job in &mut graph.agent_jobs {
+        keep(&mut job.referenced_tables);
+        keep(&mut job.affected_tables);
+        keep(&mut job.executed_procedures);
+        for step in &mut job.steps {
+            keep(&mut step.referenced_tables);
+            keep(&mut step.affected_tables);
+            keep(&mut step.executed_procedures);
+        }
+    }
+
+    removed.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+
+    #[test]
+    fn excluded_tables_take_their_edges_with_them() {
+        let mut graph = generate_mock_schema("small");
+        let target = graph.relationships[0].to.clone();
+        let name = graph
+            .tables
+            .iter()
+            .find(|t| t.id == target)
+            .map(|t| t.name.to_uppercase())
+            .unwrap();
+        let tables = graph.tables.len();
+
+        let patterns = compile_patterns(&[name, "nothing.*".to_string()]).unwrap();
+        let removed = apply_exclusions(&mut graph, &patterns);
+
+        assert!(removed >= 1);
+        assert_eq!(graph.tables.len(), tables - 1);
+        assert!(graph
+            .relationships
+            .iter()
+            .all(|r| r.from != target && r.to != target));
+        assert!(graph
+            .triggers
+            .iter()
+            .all(|t| t.table_id != target && !t.affected_tables.contains(&target)));
+        assert!(graph
+            .stored_procedures
+            .iter()
+            .all(|p| !p.referenced_tables.contains(&target)));
+
+        assert!(compile_patterns(&["[oops".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use chrono::Utc;
+use serde::Deserialize;
+use std::fmt::Write;
+
+/// Where an exported artifact came from.
+#[derive(Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TitleBlock {
+    #[serde(default)]
+    pub database: Option<String>,
+    #[serde(default)]
+    pub server: Option<String>,
+    /// Filled with the export time by [`ExportAnnotations::stamped`] when empty.
+    #[serde(default)]
+    pub generated_at: Option<String>,
+    /// Filters that shaped the exported graph, e.g. "schema sales, 42 of 180 tables".
+    #[serde(default)]
+    pub filter_summary: Option<String>,
+}
+
+/// Optional blocks added to every exporter's output so an artifact still
+/// makes sense away from the app.
+#[derive(Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportAnnotations {
+    /// Explain the format's node and edge notation.
+    #[serde(default)]
+    pub legend: bool,
+    #[serde(default)]
+    pub title_block: Option<TitleBlock>,
+    /// Text such as "DRAFT" or "Confidential".
+    #[serde(default)]
+    pub watermark: Option<String>,
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// A value kept on one line, so a line break in it cannot end a comment or
+/// block and leave the rest of the value to run as code.
+pub(crate) fn one_line(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+impl ExportAnnotations {
+    /// Set the title block's generation time to now unless the caller gave one.
+    pub fn stamped(mut self) -> Self {
+        if let Some(block) = self.title_block.as_mut() {
+            if non_empty(&block.generated_at).is_none() {
+                block.generated_at = Some(Utc::now().format("%Y-%m-%d %H:%M UTC").to_string());
+            }
+        }
+        self
+    }
+
+    /// Title block as label and value pairs, skipping empty fields.
+    pub fn title_lines(&self) -> Vec<(&'static str, &str)> {
+        let Some(block) = &self.title_block else {
+            return Vec::new();
+        };
+        [
+            ("Database", &block.database),
+            ("Server", &block.server),
+            ("Generated", &block.generated_at),
+            ("Filter", &block.filter_summary),
+        ]
+        .into_iter()
+        .filter_map(|(label, value)| non_empty(value).map(|v| (label, v)))
+        .collect()
+    }
+
+    pub fn watermark(&self) -> Option<&str> {
+        non_empty(&self.watermark)
+    }
+
+    /// The annotations as line comments for text formats, each line starting
+    /// with `marker`. `legend` is the format's notation, one entry per line.
+    /// Empty when nothing is enabled.
+    pub fn comment_block(&self, marker: &str, legend: &[&str]) -> String {
+        let mut out = String::new();
+        if let Some(watermark) = self.watermark() {
+            let _ = writeln!(out, "{} *** {} ***", marker, one_line(watermark));
+        }
+        for (label, value) in self.title_lines() {
+            let _ = writeln!(out, "{} {}: {}", marker, label, one_line(value));
+        }
+        if self.legend && !legend.is_empty() {
+            let _ = writeln!(out, "{} Legend:", marker);
+            for line in legend {
+                let _ = writeln!(out, "{}   {}", marker, line);
+            }
+        }
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comment_block_skips_empty_fields() {
+        let annotations = ExportAnnotations {
+            legend: true,
+            title_block: Some(TitleBlock {
+                database: Some("Sales".to_string()),
+                server: Some("  ".to_string()),
+                generated_at: None,
+                filter_summary: Some("schema dbo".to_string()),
+            }),
+            watermark: Some("DRAFT".to_string()),
+        };
+        assert_eq!(
+            annotations.comment_block("--", &["PK primary key"]),
+            "-- *** DRAFT ***\n-- Database: Sales\n-- Filter: schema dbo\n-- Legend:\n--   PK primary key\n\n"
+        );
+
+        let injected = ExportAnnotations {
+            watermark: Some("DRAFT\r\ndel /q *.*\nDROP TABLE dbo.Orders".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            injected.comment_block("rem", &[]),
+            "rem *** DRAFT  del /q *.* DROP TABLE dbo.Orders ***\n\n"
+        );
+
+        let stamped = annotations.stamped();
+        assert_eq!(stamped.title_lines()[1].0, "Generated");
+        assert!(ExportAnnotations::default()
+            .comment_block("--", &["PK primary key"])
+            .is_empty());
+    }
+}
@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+
+use crate::state::AppSettings;
+
+pub const DEFAULT_EXPORT_THEME: &str = "default";
+
+/// Smallest and largest font scale accepted from settings.
+const FONT_SCALE_RANGE: (f64, f64) = (0.5, 3.0);
+
+/// Colours and sizing the diagram and report exporters draw with. Colours
+/// are `#rrggbb`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportTheme {
+    pub id: String,
+    pub name: String,
+    pub background: String,
+    pub text: String,
+    pub muted_text: String,
+    pub table_accent: String,
+    pub view_accent: String,
+    pub edge: String,
+    pub virtual_edge: String,
+    /// Multiplier for edge and border strokes.
+    pub line_width: f64,
+    /// Multiplier for every font size.
+    pub font_scale: f64,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn theme(
+    id: &str,
+    name: &str,
+    background: &str,
+    text: &str,
+    muted_text: &str,
+    [table_accent, view_accent, edge, virtual_edge]: [&str; 4],
+    line_width: f64,
+    font_scale: f64,
+) -> ExportTheme {
+    ExportTheme {
+        id: id.to_string(),
+        name: name.to_string(),
+        background: background.to_string(),
+        text: text.to_string(),
+        muted_text: muted_text.to_string(),
+        table_accent: table_accent.to_string(),
+        view_accent: view_accent.to_string(),
+        edge: edge.to_string(),
+        virtual_edge: virtual_edge.to_string(),
+        line_width,
+        font_scale,
+    }
+}
+
+pub fn builtin_export_themes() -> Vec<ExportTheme> {
+    vec![
+        // Matches the canvas, so exports look like the app
+        theme(
+            DEFAULT_EXPORT_THEME,
+            "Default",
+            "#09090b",
+            "#fafafa",
+            "#a1a1aa",
+            ["#3b82f6", "#22c55e", "#71717a", "#a855f7"],
+            1.0,
+            1.0,
+        ),
+        theme(
+            "light",
+            "Light (print)",
+            "#ffffff",
+            "#18181b",
+            "#52525b",
+            ["#2563eb", "#16a34a", "#52525b", "#9333ea"],
+            1.0,
+            1.0,
+        ),
+        // Every foreground meets WCAG AAA (7:1) on white
+        theme(
+            "high-contrast",
+            "High contrast",
+            "#ffffff",
+            "#000000",
+            "#000000",
+            ["#00307a", "#004d2a", "#000000", "#5a0080"],
+            2.0,
+            1.25,
+        ),
+        // Okabe-Ito palette, distinguishable with the common colour vision deficiencies
+        theme(
+            "color-blind-safe",
+            "Color-blind safe",
+            "#ffffff",
+            "#000000",
+            "#4d4d4d",
+            ["#0072b2", "#e69f00", "#000000", "#cc79a7"],
+            1.5,
+            1.0,
+        ),
+    ]
+}
+
+fn is_hex_color(value: &str) -> bool {
+    value.len() == 7 && value.starts_with('#') && value[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Reject custom themes the exporters could not draw with.
+pub fn validate_export_theme(theme: &ExportTheme) -> Result<(), String> {
+    if theme.id.trim().is_empty() {
+        return Err("Export theme id is required".to_string());
+    }
+    let colors = [
+        &theme.background,
+        &theme.text,
+        &theme.muted_text,
+        &theme.table_accent,
+        &theme.view_accent,
+        &theme.edge,
+        &theme.virtual_edge,
+    ];
+    if let Some(bad) = colors.into_iter().find(|c| !is_hex_color(c)) {
+        return Err(format!(
+            "Export theme '{}' has an invalid colour '{}'; expected #rrggbb",
+            theme.id, bad
+        ));
+    }
+    if !(theme.line_width > 0.0 && theme.font_scale > 0.0) {
+        return Err(format!(
+            "Export theme '{}' needs a positive line width and font scale",
+            theme.id
+        ));
+    }
+    Ok(())
+}
+
+/// Built-in themes followed by custom ones; a custom theme with a built-in
+/// id replaces it.
+pub fn available_export_themes(settings: &AppSettings) -> Vec<ExportTheme> {
+    let mut themes: Vec<ExportTheme> = builtin_export_themes()
+        .into_iter()
+        .filter(|t| !settings.custom_export_themes.iter().any(|c| c.id == t.id))
+        .collect();
+    themes.extend(settings.custom_export_themes.iter().cloned());
+    themes
+}
+
+/// The selected theme, with the font scale setting applied on top.
+pub fn active_export_theme(settings: &AppSettings) -> ExportTheme {
+    let selected = settings
+        .export_theme
+        .as_deref()
+        .unwrap_or(DEFAULT_EXPORT_THEME);
+    let mut themes = available_export_themes(settings);
+    let index = themes.iter().position(|t| t.id == selected).unwrap_or(0);
+    let mut theme = themes.swap_remove(index);
+    if let Some(scale) = settings.export_font_scale {
+        theme.font_scale = scale.clamp(FONT_SCALE_RANGE.0, FONT_SCALE_RANGE.1);
+    }
+    theme
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// WCAG 2 contrast ratio between two `#rrggbb` colours.
+    fn contrast_ratio(a: &str, b: &str) -> f64 {
+        let luminance = |hex: &str| {
+            let channel = |i: usize| {
+                let c = u8::from_str_radix(&hex[i..i + 2], 16).unwrap() as f64 / 255.0;
+                if c <= 0.03928 {
+                    c / 12.92
+                } else {
+                    ((c + 0.055) / 1.055).powf(2.4)
+                }
+            };
+            0.2126 * channel(1) + 0.7152 * channel(3) + 0.0722 * channel(5)
+        };
+        let (la, lb) = (luminance(a), luminance(b));
+        (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+    }
+
+    #[test]
+    fn high_contrast_theme_meets_wcag_aaa() {
+        let themes = builtin_export_themes();
+        let theme = themes.iter().find(|t| t.id == "high-contrast").unwrap();
+        for color in [
+            &theme.text,
+            &theme.muted_text,
+            &theme.table_accent,
+            &theme.view_accent,
+            &theme.edge,
+            &theme.virtual_edge,
+        ] {
+            assert!(contrast_ratio(color, &theme.background) >= 7.0, "{}", color);
+        }
+        assert!(themes.iter().all(|t| validate_export_theme(t).is_ok()));
+    }
+
+    #[test]
+    fn active_theme_applies_selection_and_font_scale() {
+        let mut custom = builtin_export_themes().remove(1);
+        custom.name = "Company print".to_string();
+        let settings = AppSettings {
+            export_theme: Some("light".to_string()),
+            export_font_scale: Some(10.0),
+            custom_export_themes: vec![custom],
+            ..Default::default()
+        };
+
+        let theme = active_export_theme(&settings);
+        assert_eq!(theme.name, "Company print");
+        assert_eq!(theme.font_scale, 3.0);
+        assert_eq!(available_export_themes(&settings).len(), 4);
+
+        let mut bad = theme.clone();
+        bad.edge = "red".to_string();
+        assert!(validate_export_theme(&bad).is_err());
+    }
+}
@@ -0,0 +1,379 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::i18n::t;
+use crate::state::connection_file_stem;
+use crate::types::{Classification, SchemaGraph};
+
+/// A business term and what it means.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GlossaryTerm {
+    pub name: String,
+    pub definition: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub synonyms: Vec<String>,
+}
+
+/// A node of the tag taxonomy, e.g. `PII` under `Sensitivity`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Terms and tags shared by every connection. Names are unique,
+/// case-insensitively.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Glossary {
+    #[serde(default)]
+    pub terms: Vec<GlossaryTerm>,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+}
+
+impl Glossary {
+    fn term(&self, name: &str) -> Option<&GlossaryTerm> {
+        self.terms
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case(name))
+    }
+
+    fn tag(&self, name: &str) -> Option<&Tag> {
+        self.tags.iter().find(|t| t.name.eq_ignore_ascii_case(name))
+    }
+
+    /// `name` and its ancestors, root last.
+    pub fn tag_path(&self, name: &str) -> Vec<&str> {
+        let mut path = Vec::new();
+        let mut current = self.tag(name);
+        while let Some(tag) = current {
+            // Guards against a hand-edited file with a cycle
+            if path.contains(&tag.name.as_str()) {
+                break;
+            }
+            path.push(tag.name.as_str());
+            current = tag.parent.as_deref().and_then(|p| self.tag(p));
+        }
+        path
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ClassificationFile {
+    classifications: Vec<Classification>,
+}
+
+fn glossary_path(storage_path: &Path) -> PathBuf {
+    storage_path.join("glossary.json")
+}
+
+fn classification_path(storage_path: &Path, server: &str, database: &str) -> PathBuf {
+    storage_path
+        .join("classifications")
+        .join(format!("{}.json", connection_file_stem(server, database)))
+}
+
+fn read_json<T: serde::de::DeserializeOwned + Default>(path: &Path) -> T {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create glossary directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize glossary: {}", e))?;
+    std::fs::write(path, content).map_err(|e| format!("Failed to write glossary: {}", e))
+}
+
+fn required_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(t!("error-glossary-name-required"));
+    }
+    Ok(name.to_string())
+}
+
+pub fn read_glossary(storage_path: &Path) -> Glossary {
+    read_json(&glossary_path(storage_path))
+}
+
+/// Add a term or replace the one with the same name.
+pub fn save_term(storage_path: &Path, term: GlossaryTerm) -> Result<Glossary, String> {
+    let term = GlossaryTerm {
+        name: required_name(&term.name)?,
+        definition: term.definition.trim().to_string(),
+        synonyms: term
+            .synonyms
+            .into_iter()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+    };
+    let mut glossary = read_glossary(storage_path);
+    match glossary
+        .terms
+        .iter_mut()
+        .find(|t| t.name.eq_ignore_ascii_case(&term.name))
+    {
+        Some(existing) => *existing = term,
+        None => glossary.terms.push(term),
+    }
+    write_json(&glossary_path(storage_path), &glossary)?;
+    Ok(glossary)
+}
+
+/// Remove a term. Assignments naming it are left in place, so adding the
+/// term again restores them.
+pub fn remove_term(storage_path: &Path, name: &str) -> Result<Glossary, String> {
+    let mut glossary = read_glossary(storage_path);
+    let before = glossary.terms.len();
+    glossary
+        .terms
+        .retain(|t| !t.name.eq_ignore_ascii_case(name));
+    if glossary.terms.len() == before {
+        return Err(t!("error-glossary-term-not-found", name = name));
+    }
+    write_json(&glossary_path(storage_path), &glossary)?;
+    Ok(glossary)
+}
+
+/// Add a tag or replace the one with the same name. Its parent must exist
+/// and may not be the tag itself or one of its descendants.
+pub fn save_tag(storage_path: &Path, tag: Tag) -> Result<Glossary, String> {
+    let name = required_name(&tag.name)?;
+    let mut glossary = read_glossary(storage_path);
+    let parent = match tag
+        .parent
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        Some(parent) => {
+            let parent = glossary
+                .tag(parent)
+                .ok_or_else(|| t!("error-tag-not-found", name = parent))?;
+            if glossary
+                .tag_path(&parent.name)
+                .iter()
+                .any(|ancestor| ancestor.eq_ignore_ascii_case(&name))
+            {
+                return Err(t!("error-tag-parent-cycle", name = name));
+            }
+            Some(parent.name.clone())
+        }
+        None => None,
+    };
+    let tag = Tag {
+        name,
+        parent,
+        description: tag
+            .description
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty()),
+    };
+    match glossary
+        .tags
+        .iter_mut()
+        .find(|t| t.name.eq_ignore_ascii_case(&tag.name))
+    {
+        Some(existing) => *existing = tag,
+        None => glossary.tags.push(tag),
+    }
+    write_json(&glossary_path(storage_path), &glossary)?;
+    Ok(glossary)
+}
+
+/// Remove a tag that has no children.
+pub fn remove_tag(storage_path: &Path, name: &str) -> Result<Glossary, String> {
+    let mut glossary = read_glossary(storage_path);
+    if glossary.tag(name).is_none() {
+        return Err(t!("error-tag-not-found", name = name));
+    }
+    if glossary.tags.iter().any(|t| {
+        t.parent
+            .as_deref()
+            .is_some_and(|p| p.eq_ignore_ascii_case(name))
+    }) {
+        return Err(t!("error-tag-has-children", name = name));
+    }
+    glossary.tags.retain(|t| !t.name.eq_ignore_ascii_case(name));
+    write_json(&glossary_path(storage_path), &glossary)?;
+    Ok(glossary)
+}
+
+pub fn list_classifications(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+) -> Vec<Classification> {
+    read_json::<ClassificationFile>(&classification_path(storage_path, server, database))
+        .classifications
+}
+
+/// Set the terms and tags of an object or column, replacing earlier ones.
+/// Every name must be in the glossary; empty lists clear the assignment.
+pub fn classify(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+    object_id: &str,
+    column: Option<String>,
+    terms: Vec<String>,
+    tags: Vec<String>,
+) -> Result<Vec<Classification>, String> {
+    if object_id.trim().is_empty() {
+        return Err(t!("error-annotation-object-required"));
+    }
+    let glossary = read_glossary(storage_path);
+    let mut canonical_terms = Vec::new();
+    for name in &terms {
+        let term = glossary
+            .term(name.trim())
+            .ok_or_else(|| t!("error-glossary-term-not-found", name = name))?;
+        if !canonical_terms.contains(&term.name) {
+            canonical_terms.push(term.name.clone());
+        }
+    }
+    let mut canonical_tags = Vec::new();
+    for name in &tags {
+        let tag = glossary
+            .tag(name.trim())
+            .ok_or_else(|| t!("error-tag-not-found", name = name))?;
+        if !canonical_tags.contains(&tag.name) {
+            canonical_tags.push(tag.name.clone());
+        }
+    }
+    let column = column
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+
+    let path = classification_path(storage_path, server, database);
+    let mut file: ClassificationFile = read_json(&path);
+    let position = file.classifications.iter().position(|c| {
+        c.object_id == object_id
+            && c.column.as_deref().map(str::to_lowercase)
+                == column.as_deref().map(str::to_lowercase)
+    });
+    let classification = Classification {
+        object_id: object_id.to_string(),
+        column,
+        terms: canonical_terms,
+        tags: canonical_tags,
+    };
+    match position {
+        Some(i) if classification.terms.is_empty() && classification.tags.is_empty() => {
+            file.classifications.remove(i);
+        }
+        Some(i) => file.classifications[i] = classification,
+        None if classification.terms.is_empty() && classification.tags.is_empty() => {}
+        None => file.classifications.push(classification),
+    }
+    write_json(&path, &file)?;
+    Ok(file.classifications)
+}
+
+/// Attach the connection's classifications to a freshly loaded graph, so
+/// search and every export that writes the graph carry them.
+pub fn apply_classifications(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+    graph: &mut SchemaGraph,
+) {
+    graph.classifications = list_classifications(storage_path, server, database);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn term(name: &str) -> GlossaryTerm {
+        GlossaryTerm {
+            name: name.to_string(),
+            definition: format!("What {} means", name),
+            synonyms: vec![" ".to_string()],
+        }
+    }
+
+    fn tag(name: &str, parent: Option<&str>) -> Tag {
+        Tag {
+            name: name.to_string(),
+            parent: parent.map(str::to_string),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn taxonomy_and_assignments() {
+        let dir = tempdir().expect("tempdir");
+        let root = dir.path();
+
+        save_term(root, term("Customer")).unwrap();
+        let glossary = save_term(root, term("customer")).unwrap();
+        assert_eq!(glossary.terms.len(), 1);
+        assert!(glossary.terms[0].synonyms.is_empty());
+
+        save_tag(root, tag("Sensitivity", None)).unwrap();
+        save_tag(root, tag("PII", Some("sensitivity"))).unwrap();
+        let glossary = save_tag(root, tag("Email", Some("PII"))).unwrap();
+        assert_eq!(
+            glossary.tag_path("email"),
+            vec!["Email", "PII", "Sensitivity"]
+        );
+        assert!(save_tag(root, tag("Sensitivity", Some("Email"))).is_err());
+        assert!(save_tag(root, tag("Orphan", Some("Missing"))).is_err());
+        assert!(remove_tag(root, "PII").is_err());
+
+        let classified = classify(
+            root,
+            "srv",
+            "Sales",
+            "dbo.Customers",
+            Some("Email".to_string()),
+            vec!["CUSTOMER".to_string()],
+            vec!["pii".to_string(), "PII".to_string()],
+        )
+        .unwrap();
+        assert_eq!(classified[0].terms, vec!["customer"]);
+        assert_eq!(classified[0].tags, vec!["PII"]);
+        assert!(classify(
+            root,
+            "srv",
+            "Sales",
+            "dbo.Customers",
+            None,
+            vec!["Unknown".to_string()],
+            Vec::new()
+        )
+        .is_err());
+
+        let mut graph = SchemaGraph::default();
+        apply_classifications(root, "srv", "Sales", &mut graph);
+        assert_eq!(graph.classifications.len(), 1);
+
+        let cleared = classify(
+            root,
+            "srv",
+            "Sales",
+            "dbo.Customers",
+            Some("email".to_string()),
+            Vec::new(),
+            Vec::new(),
+        )
+        .unwrap();
+        assert!(cleared.is_empty());
+    }
+}
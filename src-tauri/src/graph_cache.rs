@@ -0,0 +1,134 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::graph_patch::{apply_patch, diff_graphs, SchemaPatch};
+use crate::state::connection_file_stem;
+use crate::types::SchemaGraph;
+
+/// The last graph loaded for a connection, so it can be reopened without
+/// querying the server.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedGraph {
+    pub cached_at: String,
+    pub graph: SchemaGraph,
+}
+
+fn cache_path(storage_path: &Path, server: &str, database: &str) -> PathBuf {
+    storage_path
+        .join("graph-cache")
+        .join(format!("{}.json", connection_file_stem(server, database)))
+}
+
+pub fn write_cached_graph(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+    graph: &SchemaGraph,
+) -> Result<(), String> {
+    let path = cache_path(storage_path, server, database);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+    }
+    let cached = CachedGraph {
+        cached_at: Utc::now().to_rfc3339(),
+        graph: graph.clone(),
+    };
+    let content =
+        serde_json::to_vec(&cached).map_err(|e| format!("Failed to serialize cache: {}", e))?;
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write cache: {}", e))
+}
+
+/// `None` when nothing is cached or the file can't be read, in which case
+/// the caller falls back to a live load.
+pub fn read_cached_graph(storage_path: &Path, server: &str, database: &str) -> Option<CachedGraph> {
+    let content = std::fs::read(cache_path(storage_path, server, database)).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+/// Cache a freshly loaded `graph` and number it: one past the cached
+/// version when it differs, returning the patch between them, or the cached
+/// version when nothing changed. The first graph cached is version 1.
+pub fn store_graph(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+    graph: &mut SchemaGraph,
+) -> Result<Option<SchemaPatch>, String> {
+    let patch = match read_cached_graph(storage_path, server, database) {
+        Some(cached) => {
+            let patch = diff_graphs(&cached.graph, graph);
+            graph.version = match patch.is_empty() {
+                true => cached.graph.version,
+                false => patch.version,
+            };
+            (!patch.is_empty()).then_some(patch)
+        }
+        None => {
+            graph.version = 1;
+            None
+        }
+    };
+    write_cached_graph(storage_path, server, database, graph)?;
+    Ok(patch)
+}
+
+/// Apply a patch built from a partial refresh to the cached graph. Fails
+/// when nothing is cached or the cache is at another version.
+pub fn patch_cached_graph(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+    patch: &SchemaPatch,
+) -> Result<(), String> {
+    let mut cached = read_cached_graph(storage_path, server, database)
+        .ok_or_else(|| "No cached schema to patch".to_string())?;
+    apply_patch(&mut cached.graph, patch)?;
+    write_cached_graph(storage_path, server, database, &cached.graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+    use tempfile::tempdir;
+
+    #[test]
+    fn cached_graph_round_trips_per_connection() {
+        let dir = tempdir().expect("tempdir");
+        let graph = generate_mock_schema("small");
+
+        assert!(read_cached_graph(dir.path(), "srv", "Sales").is_none());
+        write_cached_graph(dir.path(), "srv", "Sales", &graph).unwrap();
+
+        let cached = read_cached_graph(dir.path(), "SRV", "sales").expect("cached");
+        assert_eq!(cached.graph.tables.len(), graph.tables.len());
+        assert!(!cached.cached_at.is_empty());
+        assert!(read_cached_graph(dir.path(), "srv", "Billing").is_none());
+    }
+
+    #[test]
+    fn stored_graphs_are_versioned_by_change() {
+        let dir = tempdir().expect("tempdir");
+        let mut graph = generate_mock_schema("small");
+
+        assert!(store_graph(dir.path(), "srv", "Sales", &mut graph)
+            .unwrap()
+            .is_none());
+        assert_eq!(graph.version, 1);
+        assert!(store_graph(dir.path(), "srv", "Sales", &mut graph)
+            .unwrap()
+            .is_none());
+        assert_eq!(graph.version, 1);
+
+        graph.tables.pop();
+        let patch = store_graph(dir.path(), "srv", "Sales", &mut graph)
+            .unwrap()
+            .expect("patch");
+        assert_eq!((patch.base_version, patch.version), (1, 2));
+        assert_eq!(graph.version, 2);
+        assert!(patch_cached_graph(dir.path(), "srv", "Sales", &patch).is_err());
+    }
+}
@@ -0,0 +1,331 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::types::{
+    AgentJob, GraphWarning, ObjectState, ObjectStateKind, RelationshipEdge, ScalarFunction,
+    SchemaGraph, StoredProcedure, TableNode, Trigger, ViewNode,
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PatchKind {
+    Table,
+    View,
+    Relationship,
+    Trigger,
+    StoredProcedure,
+    ScalarFunction,
+    AgentJob,
+}
+
+/// An added or changed object, in full.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "kind", content = "object", rename_all = "camelCase")]
+pub enum PatchObject {
+    Table(TableNode),
+    View(ViewNode),
+    Relationship(RelationshipEdge),
+    Trigger(Trigger),
+    StoredProcedure(StoredProcedure),
+    ScalarFunction(ScalarFunction),
+    AgentJob(AgentJob),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchRemoval {
+    pub kind: PatchKind,
+    pub id: String,
+}
+
+/// The difference between two versions of a connection's graph. Applies
+/// only on top of `base_version`; a consumer holding any other version
+/// reloads instead.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaPatch {
+    pub base_version: u64,
+    pub version: u64,
+    pub added: Vec<PatchObject>,
+    pub updated: Vec<PatchObject>,
+    pub removed: Vec<PatchRemoval>,
+    /// Integrity warnings are replaced as a whole rather than diffed.
+    pub warnings: Vec<GraphWarning>,
+}
+
+/// Payload of the `schema-patch` event, so each window can ignore patches
+/// for connections it doesn't show.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaPatchEvent {
+    pub server: String,
+    pub database: String,
+    pub patch: SchemaPatch,
+}
+
+impl SchemaPatch {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+
+    /// Keep only the added and updated objects still present in `graph`,
+    /// taking each one's copy from it. Used once exclusions have filtered a
+    /// freshly loaded graph, so a patch never brings hidden objects back.
+    pub fn restrict_to(&mut self, graph: &SchemaGraph) {
+        let current = |object: &PatchObject| match object {
+            PatchObject::Table(o) => find(&graph.tables, &o.id, |o| &o.id).map(PatchObject::Table),
+            PatchObject::View(o) => find(&graph.views, &o.id, |o| &o.id).map(PatchObject::View),
+            PatchObject::Relationship(o) => {
+                find(&graph.relationships, &o.id, |o| &o.id).map(PatchObject::Relationship)
+            }
+            PatchObject::Trigger(o) => {
+                find(&graph.triggers, &o.id, |o| &o.id).map(PatchObject::Trigger)
+            }
+            PatchObject::StoredProcedure(o) => {
+                find(&graph.stored_procedures, &o.id, |o| &o.id).map(PatchObject::StoredProcedure)
+            }
+            PatchObject::ScalarFunction(o) => {
+                find(&graph.scalar_functions, &o.id, |o| &o.id).map(PatchObject::ScalarFunction)
+            }
+            PatchObject::AgentJob(o) => {
+                find(&graph.agent_jobs, &o.id, |o| &o.id).map(PatchObject::AgentJob)
+            }
+        };
+        self.added = self.added.iter().filter_map(current).collect();
+        self.updated = self.updated.iter().filter_map(current).collect();
+        self.warnings = graph.warnings.clone();
+    }
+}
+
+fn find<T: Clone>(objects: &[T], id: &str, key: fn(&T) -> &str) -> Option<T> {
+    objects.iter().find(|o| key(o) == id).cloned()
+}
+
+/// Node types compare by their serialized form, since they don't derive
+/// `PartialEq`.
+fn same<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+fn diff_objects<T: Serialize + Clone>(
+    old: &[T],
+    new: &[T],
+    id: fn(&T) -> &str,
+    kind: PatchKind,
+    wrap: fn(T) -> PatchObject,
+    patch: &mut SchemaPatch,
+) {
+    let before: HashMap<&str, &T> = old.iter().map(|o| (id(o), o)).collect();
+    let after: HashMap<&str, &T> = new.iter().map(|o| (id(o), o)).collect();
+    for object in new {
+        match before.get(id(object)) {
+            None => patch.added.push(wrap(object.clone())),
+            Some(previous) if !same(*previous, object) => patch.updated.push(wrap(object.clone())),
+            Some(_) => {}
+        }
+    }
+    patch.removed.extend(
+        old.iter()
+            .filter(|o| !after.contains_key(id(o)))
+            .map(|o| PatchRemoval {
+                kind,
+                id: id(o).to_string(),
+            }),
+    );
+}
+
+/// Patch that turns `old` into `new`, numbered one past `old.version`.
+pub fn diff_graphs(old: &SchemaGraph, new: &SchemaGraph) -> SchemaPatch {
+    let mut patch = SchemaPatch {
+        base_version: old.version,
+        version: old.version + 1,
+        added: Vec::new(),
+        updated: Vec::new(),
+        removed: Vec::new(),
+        warnings: new.warnings.clone(),
+    };
+    diff_objects(
+        &old.tables,
+        &new.tables,
+        |o| &o.id,
+        PatchKind::Table,
+        PatchObject::Table,
+        &mut patch,
+    );
+    diff_objects(
+        &old.views,
+        &new.views,
+        |o| &o.id,
+        PatchKind::View,
+        PatchObject::View,
+        &mut patch,
+    );
+    diff_objects(
+        &old.relationships,
+        &new.relationships,
+        |o| &o.id,
+        PatchKind::Relationship,
+        PatchObject::Relationship,
+        &mut patch,
+    );
+    diff_objects(
+        &old.triggers,
+        &new.triggers,
+        |o| &o.id,
+        PatchKind::Trigger,
+        PatchObject::Trigger,
+        &mut patch,
+    );
+    diff_objects(
+        &old.stored_procedures,
+        &new.stored_procedures,
+        |o| &o.id,
+        PatchKind::StoredProcedure,
+        PatchObject::StoredProcedure,
+        &mut patch,
+    );
+    diff_objects(
+        &old.scalar_functions,
+        &new.scalar_functions,
+        |o| &o.id,
+        PatchKind::ScalarFunction,
+        PatchObject::ScalarFunction,
+        &mut patch,
+    );
+    diff_objects(
+        &old.agent_jobs,
+        &new.agent_jobs,
+        |o| &o.id,
+        PatchKind::AgentJob,
+        PatchObject::AgentJob,
+        &mut patch,
+    );
+    patch
+}
+
+/// Patch from a flags-only refresh: the triggers of `graph` whose enabled
+/// state differs from `states`. Other kinds have no flags in the graph.
+pub fn object_state_patch(graph: &SchemaGraph, states: &[ObjectState]) -> SchemaPatch {
+    let disabled: HashMap<&str, bool> = states
+        .iter()
+        .filter(|s| s.kind == ObjectStateKind::Trigger)
+        .map(|s| (s.id.as_str(), s.is_disabled))
+        .collect();
+    let updated = graph
+        .triggers
+        .iter()
+        .filter(|t| {
+            disabled
+                .get(t.id.as_str())
+                .is_some_and(|d| *d != t.is_disabled)
+        })
+        .map(|t| {
+            PatchObject::Trigger(Trigger {
+                is_disabled: !t.is_disabled,
+                ..t.clone()
+            })
+        })
+        .collect();
+    SchemaPatch {
+        base_version: graph.version,
+        version: graph.version + 1,
+        added: Vec::new(),
+        updated,
+        removed: Vec::new(),
+        warnings: graph.warnings.clone(),
+    }
+}
+
+fn upsert<T>(objects: &mut Vec<T>, object: T, id: fn(&T) -> &str) {
+    match objects.iter().position(|o| id(o) == id(&object)) {
+        Some(index) => objects[index] = object,
+        None => objects.push(object),
+    }
+}
+
+/// Apply `patch` in place. Fails without changing `graph` when the graph
+/// is not at the patch's base version.
+pub fn apply_patch(graph: &mut SchemaGraph, patch: &SchemaPatch) -> Result<(), String> {
+    if graph.version != patch.base_version {
+        return Err(format!(
+            "Schema patch {} applies to version {}, but the graph is at version {}",
+            patch.version, patch.base_version, graph.version
+        ));
+    }
+    for removal in &patch.removed {
+        let id = removal.id.as_str();
+        match removal.kind {
+            PatchKind::Table => graph.tables.retain(|o| o.id != id),
+            PatchKind::View => graph.views.retain(|o| o.id != id),
+            PatchKind::Relationship => graph.relationships.retain(|o| o.id != id),
+            PatchKind::Trigger => graph.triggers.retain(|o| o.id != id),
+            PatchKind::StoredProcedure => graph.stored_procedures.retain(|o| o.id != id),
+            PatchKind::ScalarFunction => graph.scalar_functions.retain(|o| o.id != id),
+            PatchKind::AgentJob => graph.agent_jobs.retain(|o| o.id != id),
+        }
+    }
+    for object in patch.added.iter().chain(&patch.updated).cloned() {
+        match object {
+            PatchObject::Table(o) => upsert(&mut graph.tables, o, |o| &o.id),
+            PatchObject::View(o) => upsert(&mut graph.views, o, |o| &o.id),
+            PatchObject::Relationship(o) => upsert(&mut graph.relationships, o, |o| &o.id),
+            PatchObject::Trigger(o) => upsert(&mut graph.triggers, o, |o| &o.id),
+            PatchObject::StoredProcedure(o) => upsert(&mut graph.stored_procedures, o, |o| &o.id),
+            PatchObject::ScalarFunction(o) => upsert(&mut graph.scalar_functions, o, |o| &o.id),
+            PatchObject::AgentJob(o) => upsert(&mut graph.agent_jobs, o, |o| &o.id),
+        }
+    }
+    graph.warnings = patch.warnings.clone();
+    graph.version = patch.version;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+
+    #[test]
+    fn applying_a_diff_reproduces_the_new_graph() {
+        let old = SchemaGraph {
+            version: 4,
+            ..generate_mock_schema("small")
+        };
+        let mut new = old.clone();
+        let removed = new.tables.remove(0).id;
+        new.views[0].definition.push_str(" -- changed");
+        new.relationships
+            .retain(|r| r.from != removed && r.to != removed);
+        let mut added = new.tables[0].clone();
+        added.id = "dbo.Added".to_string();
+        new.tables.push(added);
+
+        let patch = diff_graphs(&old, &new);
+        assert_eq!((patch.base_version, patch.version), (4, 5));
+        assert_eq!(patch.added.len(), 1);
+        assert_eq!(patch.updated.len(), 1);
+        assert!(patch.removed.contains(&PatchRemoval {
+            kind: PatchKind::Table,
+            id: removed.clone(),
+        }));
+
+        let mut patched = old.clone();
+        apply_patch(&mut patched, &patch).unwrap();
+        assert_eq!(patched.version, 5);
+        assert!(patched.tables.iter().all(|t| t.id != removed));
+        assert!(patched.tables.iter().any(|t| t.id == "dbo.Added"));
+        assert!(diff_graphs(&patched, &new).is_empty());
+
+        // Already at version 5, so the same patch no longer applies
+        assert!(apply_patch(&mut patched, &patch).is_err());
+        assert!(diff_graphs(&old, &old).is_empty());
+
+        // Once the new graph is filtered, the added table stays hidden
+        let mut restricted = patch.clone();
+        new.tables.retain(|t| t.id != "dbo.Added");
+        restricted.restrict_to(&new);
+        assert!(restricted.added.is_empty());
+        assert_eq!(restricted.updated.len(), 1);
+        assert_eq!(restricted.removed, patch.removed);
+    }
+}
@@ -0,0 +1,140 @@
+# Deutsche Meldungen. Fehlende Schlüssel fallen auf Englisch zurück.
+
+## Design smells
+smell-missing-primary-key = { $table } hat keinen Primärschlüssel
+smell-entity-attribute-value = { $table } sieht nach einer Entity-Attribute-Value-Tabelle aus; typisierte Spalten lassen sich leichter abfragen und einschränken
+smell-wide-table = { $table } hat { $count } Spalten
+smell-repeated-column-group = { $table } wiederholt eine Spaltengruppe ({ $columns }); eine Detailtabelle wäre besser
+smell-unindexed-foreign-key = Der nullbare Fremdschlüssel { $key } auf { $table } ({ $columns }) hat keinen passenden Index
+
+## Portability
+portability-reserved-word = '{ $identifier }' ist in { $dialect } ein reserviertes Wort
+portability-too-long = '{ $identifier }' überschreitet die maximale Namenslänge von { $dialect }
+portability-requires-quoting = '{ $identifier }' muss in { $dialect } überall in Anführungszeichen stehen
+portability-mixed-case = '{ $identifier }' enthält Groß- und Kleinbuchstaben; { $dialect } wandelt Namen ohne Anführungszeichen in Kleinbuchstaben um
+
+## Change capture
+capture-cdc = { $table } wird von CDC erfasst; die Erfassungsinstanz behält die alte Spaltenliste, bis sie nach einer Spaltenänderung neu erstellt wird
+capture-replicated = { $table } ist für die Replikation veröffentlicht; die Tabelle kann nicht gelöscht werden, und Spaltenänderungen gehen an alle Abonnenten
+capture-merge-published = { $table } ist für die Mergereplikation veröffentlicht; die Tabelle kann nicht gelöscht werden, und Spaltenänderungen gehen an alle Abonnenten
+
+## View nesting
+view-nesting-deep = { $view } ist { $depth } Sichten tief verschachtelt ({ $chain }); der Optimierer expandiert jede Ebene in eine Abfrage
+view-nesting-deep-indexed = { $view } ist { $depth } Sichten tief verschachtelt ({ $chain }); { $indexed } ist indiziert, ein Lesen mit WITH (NOEXPAND) beendet die Expansion dort
+
+## Dynamic SQL
+dynamic-sql-injection-risk = { $object } verkettet { $inputs } zu dynamischem SQL; Werte besser als sp_executesql-Parameter übergeben oder Namen mit QUOTENAME einschließen
+dynamic-sql = { $object } führt dynamisches SQL aus; seine Tabellenverweise sind für die Analyse nicht sichtbar
+
+## Compliance
+compliance-missing-primary-key = { $object } hat keinen Primärschlüssel
+compliance-missing-columns = { $object } fehlt { $columns }
+compliance-unindexed-foreign-key = Die Fremdschlüsselspalte { $object }.{ $column } ({ $key }) führt keinen Index an
+compliance-naming = '{ $name }' entspricht nicht { $pattern }
+compliance-needs-index-metadata = Benötigt Indexinformationen aus einer aktiven Verbindung
+
+## Type changes
+type-change-primary-key = Der Primärschlüssel auf { $column } blockiert ALTER COLUMN
+type-change-primary-key-of = den Primärschlüssel von { $table }
+type-change-index = Der Index { $index } auf { $column } blockiert ALTER COLUMN
+type-change-index-resize = Der Index { $index } erlaubt es, { $column } direkt zu vergrößern
+type-change-foreign-key = { $key } verlangt für { $other } denselben Typ
+type-change-schemabound = { $object } ist schemagebunden an { $column }
+type-change-view = { $object } wählt { $column } aus; die Spaltenmetadaten müssen aktualisiert werden
+type-change-module = { $object } verweist auf { $column }; es wird bei der nächsten Ausführung neu kompiliert
+type-change-module-declares = { $object } verweist auf { $column } und deklariert { $sql_type }-Werte, die den neuen Typ brauchen könnten
+type-change-needs-index-metadata = Benötigt Indexinformationen aus einer aktiven Verbindung
+type-change-step-verify = Prüfen, dass jeder Wert von { $column } in { $sql_type } konvertiert werden kann
+type-change-step-drop = { $object } löschen
+type-change-step-unbind = SCHEMABINDING von { $object } entfernen oder das Objekt löschen
+type-change-step-alter = { $column } in { $sql_type } ändern
+type-change-step-recreate = { $object } neu erstellen
+type-change-step-rebind = SCHEMABINDING für { $object } wiederherstellen
+type-change-step-refresh = { $object } aktualisieren
+type-change-step-review = Parameter und Variablen vom Typ { $sql_type } in { $object } prüfen
+
+## Exports
+ddl-modules-not-translated = { $count } Sichten, Prozeduren, Funktionen und Trigger sind T-SQL und wurden nicht übersetzt
+
+## Errors
+error-invalid-pattern = Ungültiges Muster '{ $pattern }': { $reason }
+error-connection-required = Server und Datenbank sind beide erforderlich
+error-layout-name-required = Ein Layoutname ist erforderlich
+error-layout-not-found = Layout nicht gefunden: { $name }
+error-relationship-ends-required = Beide Enden einer Beziehung sind erforderlich
+error-relationship-not-found = Virtuelle Beziehung nicht gefunden: { $id }
+error-bookmark-object-required = Für ein Lesezeichen ist ein Objekt erforderlich
+error-bookmark-not-found = Kein Lesezeichen für { $id }
+error-annotation-object-required = Für eine Anmerkung ist ein Objekt erforderlich
+error-csv-missing-column = Die CSV-Datei hat keine Spalte "{ $column }"
+error-glossary-name-required = Ein Name ist erforderlich
+error-glossary-term-not-found = Kein Glossarbegriff namens "{ $name }"
+error-tag-not-found = Kein Tag namens "{ $name }"
+error-tag-parent-cycle = "{ $name }" kann nicht unter sich selbst oder einem seiner Untertags eingeordnet werden
+error-tag-has-children = Unter "{ $name }" sind noch Tags eingeordnet
+error-contact-name-required = Ein Kontakt braucht einen Namen
+error-stewardship-schema-required = Ein Schema ist erforderlich
+error-query-not-select = Nur Abfragen, die mit SELECT oder WITH beginnen, können ausgeführt werden
+error-query-forbidden-keyword = { $keyword } ist in einer schreibgeschützten Abfrage nicht erlaubt
+error-query-parameter-name = Ungültiger Parametername „{ $name }“
+error-query-parameter-type = Ungültiger Typ für Parameter „{ $name }“
+error-saved-query-name-required = Ein Name für die gespeicherte Abfrage ist erforderlich
+error-saved-query-sql-required = Die gespeicherte Abfrage enthält kein SQL
+error-saved-query-not-found = Keine gespeicherte Abfrage namens „{ $name }“
+error-procedure-unknown-parameter = Die Prozedur hat keinen Parameter namens { $name }
+error-procedure-ended-transaction = Die Prozedur hat die Transaktion des Testlaufs selbst beendet; Änderungen davor wurden nicht zurückgesetzt: { $error }
+error-rebuild-unknown-object = { $id } ist nicht im geladenen Schema enthalten
+error-read-only-session = Diese Sitzung ist schreibgeschützt; öffnen Sie die Quellverbindung, um Änderungen vorzunehmen
+error-safe-mode = Im abgesicherten Modus nicht verfügbar; starten Sie Monocle normal neu, um es zu verwenden
+error-type-change-unknown-column = { $table } hat keine Spalte { $column }
+error-rename-name-required = Ein neuer Name ist erforderlich
+error-rename-unknown-target = { $target } ist kein Objekt und keine Spalte im geladenen Schema
+error-query-statement-cap = Die Abfrage enthält { $count } Anweisungen; diese Verbindung erlaubt { $max }
+error-query-timeout = Die Abfrage wurde nach { $seconds } s abgebrochen, dem Limit dieser Verbindung
+error-writes-blocked = Änderungen an Servern sind in den Einstellungen gesperrt
+error-data-location-required = Wählen Sie einen Ordner für die App-Daten
+error-data-location-no-exe = Der Ordner, aus dem Monocle läuft, konnte nicht ermittelt werden
+error-data-location-unwritable = App-Daten können nicht in { $path } gespeichert werden: { $error }
+error-catalog-endpoint = { $endpoint } ist keine http://- oder https://-Adresse
+error-catalog-not-configured = Für diese Verbindung ist kein Datenkatalog eingerichtet
+error-catalog-push = Die Übertragung an den Katalog wurde nach { $sent } von { $total } Anfragen abgebrochen: { $reason }
+error-credential-username = Zum Speichern eines Passworts wird ein Benutzername benötigt
+error-credential-store = Der Schlüsselbund des Systems konnte nicht verwendet werden: { $reason }
+error-naming-stale = { $name } ist nicht mehr im Entwurf; bitte die Umbenennungen erneut prüfen
+error-naming-conflict = Nach den Umbenennungen hätte { $scope } zwei Objekte namens { $name }
+error-refactor-unknown-table = Tabelle { $id } ist nicht im Entwurf
+error-refactor-unknown-column = { $table } hat keine Spalte { $column }
+error-refactor-no-columns = Mindestens eine Spalte zum Verschieben auswählen
+error-refactor-column-order = Die neue Spaltenreihenfolge muss jede Spalte von { $table } genau einmal enthalten
+error-refactor-key-column = { $column } ist eine Schlüsselspalte und kann nicht verschoben werden
+error-refactor-no-primary-key = { $table } braucht einen Primärschlüssel, um aufgeteilt zu werden
+error-refactor-table-exists = { $id } existiert bereits
+error-refactor-column-exists = { $table } hat bereits eine Spalte { $column }
+error-refactor-not-related = Kein Fremdschlüssel verbindet { $from } und { $to }
+error-export-serialize = Das Schema konnte nicht in JSON umgewandelt werden: { $reason }
+error-export-folder = Der Exportordner { $path } konnte nicht angelegt werden: { $reason }
+error-export-write = { $path } konnte nicht geschrieben werden: { $reason }
+error-bundle-write = Das Paket konnte nicht geschrieben werden: { $reason }
+error-bundle-serialize = Das Paket konnte nicht in JSON umgewandelt werden: { $reason }
+error-bundle-read = Das Paket konnte nicht gelesen werden: { $reason }
+error-bundle-invalid = Dies ist kein gültiges Monocle-Paket: { $reason }
+error-bundle-invalid-entry = Der Paketeintrag { $entry } ist ungültig: { $reason }
+error-bundle-missing-entry = Dem Paket fehlt der Eintrag { $entry }
+error-bundle-newer-version = Das Paket wurde mit einer neueren Monocle-Version erstellt (Format { $version })
+rebuild-header = Löscht { $count } Objekte und erstellt sie in Abhängigkeitsreihenfolge neu
+rebuild-tables-recreated-empty = Tabellen werden leer neu erstellt, nur mit Spalten, Primärschlüssel und Fremdschlüsseln
+rebuild-unselected-dependent = { $id } hängt von { $dependency } ab, ist aber nicht Teil der Neuerstellung
+rebuild-missing-definition = Für { $id } ist keine Definition verfügbar; es wird gelöscht, aber nicht neu erstellt
+refactor-table-recreated = { $table } wird in eine neue Tabelle mit nur Spalten, Primärschlüssel, Fremdschlüsseln und Triggern kopiert; Standardwerte, Indizes und Identity-Einstellungen müssen neu angelegt werden
+refactor-move-to-parent = Mehrere Zeilen von { $from } können zu einer Zeile von { $to } gehören; die verschobenen Werte stammen aus einer davon
+refactor-dependent-modules = { $objects } verwenden { $table } und müssen eventuell angepasst werden
+refactor-trigger-watches-moved = { $trigger } prüft Änderungen an { $columns }, die aus { $table } entfernt werden
+refactor-trigger-not-recreated = { $trigger } auf { $table } hat keine Definition im Entwurf und wird nicht neu angelegt
+refactor-triggers-dropped = { $triggers } werden zusammen mit { $table } gelöscht
+rename-header = Benennt { $target } in { $name } um und ändert { $count } Objekte, die darauf verweisen
+rename-review = { $object } Zeile { $line } erwähnt den alten Namen; bitte manuell prüfen
+rename-own-definition = Die Definition von { $object } verwendet noch den alten Namen; skripten Sie sie nach der Umbenennung erneut
+rename-not-alterable = { $object } verweist auf den alten Namen, aber die Definition hat keinen CREATE-Kopf, der in ALTER umgewandelt werden kann
+onboarding-sample-table = Beispielanmerkung: { $count } Tabellen verweisen auf diese. Wählen Sie sie aus, um ihre Beziehungen zu sehen
+onboarding-sample-key = Primärschlüssel; Fremdschlüssel anderer Tabellen verweisen hierher
+onboarding-sample-view = Eine Sicht über { $count } Tabellen; öffnen Sie sie, um zu sehen, woher ihre Spalten stammen
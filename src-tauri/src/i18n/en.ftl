@@ -0,0 +1,140 @@
+# English messages; the fallback for every other language.
+
+## Design smells
+smell-missing-primary-key = { $table } has no primary key
+smell-entity-attribute-value = { $table } looks like an entity-attribute-value table; typed columns are easier to query and constrain
+smell-wide-table = { $table } has { $count } columns
+smell-repeated-column-group = { $table } repeats a column group ({ $columns }); consider a child table
+smell-unindexed-foreign-key = Nullable foreign key { $key } on { $table } ({ $columns }) has no supporting index
+
+## Portability
+portability-reserved-word = '{ $identifier }' is a reserved word in { $dialect }
+portability-too-long = '{ $identifier }' exceeds the { $dialect } name length limit
+portability-requires-quoting = '{ $identifier }' must be quoted everywhere in { $dialect }
+portability-mixed-case = '{ $identifier }' is mixed case; { $dialect } folds unquoted names to lower case
+
+## Change capture
+capture-cdc = { $table } is tracked by CDC; its capture instance keeps the old column list until it is recreated after a column change
+capture-replicated = { $table } is published for replication; it cannot be dropped, and column changes are sent to every subscriber
+capture-merge-published = { $table } is published for merge replication; it cannot be dropped, and column changes are sent to every subscriber
+
+## View nesting
+view-nesting-deep = { $view } nests { $depth } views deep ({ $chain }); the optimizer expands every level into one query
+view-nesting-deep-indexed = { $view } nests { $depth } views deep ({ $chain }); { $indexed } is indexed, so reading it WITH (NOEXPAND) stops the expansion there
+
+## Dynamic SQL
+dynamic-sql-injection-risk = { $object } concatenates { $inputs } into dynamic SQL; pass values as sp_executesql parameters or wrap names in QUOTENAME
+dynamic-sql = { $object } runs dynamic SQL; its table references are not visible to analysis
+
+## Compliance
+compliance-missing-primary-key = { $object } has no primary key
+compliance-missing-columns = { $object } is missing { $columns }
+compliance-unindexed-foreign-key = Foreign key column { $object }.{ $column } ({ $key }) does not lead an index
+compliance-naming = '{ $name }' does not match { $pattern }
+compliance-needs-index-metadata = Needs index metadata from a live connection
+
+## Type changes
+type-change-primary-key = The primary key on { $column } blocks ALTER COLUMN
+type-change-primary-key-of = the primary key of { $table }
+type-change-index = Index { $index } on { $column } blocks ALTER COLUMN
+type-change-index-resize = Index { $index } lets { $column } grow in place
+type-change-foreign-key = { $key } requires { $other } to have the same type
+type-change-schemabound = { $object } is schema-bound to { $column }
+type-change-view = { $object } selects { $column }; its column metadata needs a refresh
+type-change-module = { $object } references { $column }; it recompiles on its next run
+type-change-module-declares = { $object } references { $column } and declares { $sql_type } values that may need the new type
+type-change-needs-index-metadata = Needs index metadata from a live connection
+type-change-step-verify = Check that every { $column } value converts to { $sql_type }
+type-change-step-drop = Drop { $object }
+type-change-step-unbind = Remove SCHEMABINDING from { $object }, or drop it
+type-change-step-alter = Change { $column } to { $sql_type }
+type-change-step-recreate = Recreate { $object }
+type-change-step-rebind = Restore SCHEMABINDING on { $object }
+type-change-step-refresh = Refresh { $object }
+type-change-step-review = Review the { $sql_type } parameters and variables of { $object }
+
+## Exports
+ddl-modules-not-translated = { $count } views, procedures, functions and triggers are T-SQL and were not translated
+
+## Errors
+error-invalid-pattern = Invalid pattern '{ $pattern }': { $reason }
+error-connection-required = Both server and database are required
+error-layout-name-required = Layout name is required
+error-layout-not-found = Layout not found: { $name }
+error-relationship-ends-required = Both ends of a relationship are required
+error-relationship-not-found = Virtual relationship not found: { $id }
+error-bookmark-object-required = An object is required to bookmark
+error-bookmark-not-found = No bookmark for { $id }
+error-annotation-object-required = An object is required to annotate
+error-csv-missing-column = The CSV file has no "{ $column }" column
+error-glossary-name-required = A name is required
+error-glossary-term-not-found = No glossary term named "{ $name }"
+error-tag-not-found = No tag named "{ $name }"
+error-tag-parent-cycle = "{ $name }" can't be placed under itself or one of its own tags
+error-tag-has-children = "{ $name }" still has tags under it
+error-contact-name-required = A contact needs a name
+error-stewardship-schema-required = A schema is required
+error-query-not-select = Only queries starting with SELECT or WITH can be run
+error-query-forbidden-keyword = { $keyword } is not allowed in a read-only query
+error-query-parameter-name = Invalid parameter name "{ $name }"
+error-query-parameter-type = Invalid type for parameter "{ $name }"
+error-saved-query-name-required = Saved query name is required
+error-saved-query-sql-required = Saved query has no SQL
+error-saved-query-not-found = No saved query named "{ $name }"
+error-procedure-unknown-parameter = The procedure has no parameter named { $name }
+error-procedure-ended-transaction = The procedure ended the test run's transaction itself, so changes it made before that were not rolled back: { $error }
+error-rebuild-unknown-object = { $id } is not in the loaded schema
+error-read-only-session = This session is read-only; open the source connection to make changes
+error-safe-mode = Not available in safe mode; restart Monocle normally to use it
+error-type-change-unknown-column = { $table } has no column { $column }
+error-rename-name-required = A new name is required
+error-rename-unknown-target = { $target } is not an object or column in the loaded schema
+error-query-statement-cap = The query has { $count } statements; this connection allows { $max }
+error-query-timeout = The query was stopped after { $seconds }s, the limit for this connection
+error-writes-blocked = Changes to servers are blocked in settings
+error-data-location-required = Choose a folder for app data
+error-data-location-no-exe = The folder Monocle runs from could not be determined
+error-data-location-unwritable = App data can't be stored in { $path }: { $error }
+error-catalog-endpoint = { $endpoint } is not an http:// or https:// address
+error-catalog-not-configured = No data catalog is set up for this connection
+error-catalog-push = The catalog push stopped after { $sent } of { $total } requests: { $reason }
+error-credential-username = A user name is needed to store a password
+error-credential-store = The system keychain could not be used: { $reason }
+error-naming-stale = { $name } is no longer in the design; preview the renames again
+error-naming-conflict = The renames would give { $scope } two objects named { $name }
+error-refactor-unknown-table = Table { $id } is not in the design
+error-refactor-unknown-column = { $table } has no column { $column }
+error-refactor-no-columns = Choose at least one column to move
+error-refactor-column-order = The new column order must list every column of { $table } once
+error-refactor-key-column = { $column } is a key column and can't be moved
+error-refactor-no-primary-key = { $table } needs a primary key to be split
+error-refactor-table-exists = { $id } already exists
+error-refactor-column-exists = { $table } already has a column { $column }
+error-refactor-not-related = No foreign key joins { $from } and { $to }
+error-export-serialize = The schema could not be converted to JSON: { $reason }
+error-export-folder = The export folder { $path } could not be created: { $reason }
+error-export-write = { $path } could not be written: { $reason }
+error-bundle-write = The bundle could not be written: { $reason }
+error-bundle-serialize = The bundle could not be converted to JSON: { $reason }
+error-bundle-read = The bundle could not be read: { $reason }
+error-bundle-invalid = This is not a valid Monocle bundle: { $reason }
+error-bundle-invalid-entry = The bundle entry { $entry } is invalid: { $reason }
+error-bundle-missing-entry = The bundle has no { $entry } entry
+error-bundle-newer-version = The bundle was created by a newer version of Monocle (format { $version })
+rebuild-header = Drops and recreates { $count } objects in dependency order
+rebuild-tables-recreated-empty = Tables are recreated empty, with only their columns, primary key and foreign keys
+rebuild-unselected-dependent = { $id } depends on { $dependency } but is not part of the rebuild
+rebuild-missing-definition = No definition is available for { $id }; it is dropped but not recreated
+refactor-table-recreated = { $table } is copied into a new table with only its columns, primary key, foreign keys and triggers; add back defaults, indexes and identity settings
+refactor-move-to-parent = Several rows of { $from } can share one row of { $to }; the moved values are taken from one of them
+refactor-dependent-modules = { $objects } use { $table } and may need updating
+refactor-trigger-watches-moved = { $trigger } tests for updates to { $columns }, which leave { $table }
+refactor-trigger-not-recreated = { $trigger } on { $table } has no definition in the design and is not created again
+refactor-triggers-dropped = { $triggers } are dropped along with { $table }
+rename-header = Renames { $target } to { $name } and alters { $count } objects that reference it
+rename-review = { $object } line { $line } mentions the old name; review it by hand
+rename-own-definition = The definition of { $object } still uses its old name; script it again after the rename
+rename-not-alterable = { $object } references the old name but its definition has no CREATE header to turn into ALTER
+onboarding-sample-table = Sample annotation: { $count } tables reference this one. Select it to see its relationships
+onboarding-sample-key = Primary key; foreign keys in other tables point here
+onboarding-sample-view = A view over { $count } tables; open it to see where its columns come from
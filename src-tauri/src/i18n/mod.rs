@@ -0,0 +1,129 @@
+//! Localized backend text. Messages live in Fluent-style `.ftl` catalogs
+//! (`key = text with { $placeholders }`) compiled into the binary; English is
+//! the fallback for missing languages and missing keys.
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::RwLock;
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+const CATALOG_SOURCES: &[(&str, &str)] = &[
+    ("en", include_str!("en.ftl")),
+    ("de", include_str!("de.ftl")),
+];
+
+static CATALOGS: Lazy<HashMap<&'static str, HashMap<&'static str, &'static str>>> =
+    Lazy::new(|| {
+        CATALOG_SOURCES
+            .iter()
+            .map(|(language, source)| (*language, parse_catalog(source)))
+            .collect()
+    });
+
+static LANGUAGE: Lazy<RwLock<&'static str>> = Lazy::new(|| RwLock::new(DEFAULT_LANGUAGE));
+
+/// One message per line; `#` starts a comment.
+fn parse_catalog(source: &'static str) -> HashMap<&'static str, &'static str> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, text)| (key.trim(), text.trim()))
+        .collect()
+}
+
+/// Best available catalog for a tag: exact (`de`), then primary subtag
+/// (`de-AT` → `de`), then English.
+fn resolve(language: &str) -> &'static str {
+    let language = language.trim().to_lowercase().replace('_', "-");
+    let primary = language.split('-').next().unwrap_or_default();
+    let tags = available_languages();
+    tags.iter()
+        .find(|tag| **tag == language)
+        .or_else(|| tags.iter().find(|tag| **tag == primary))
+        .copied()
+        .unwrap_or(DEFAULT_LANGUAGE)
+}
+
+pub fn available_languages() -> Vec<&'static str> {
+    CATALOG_SOURCES.iter().map(|(tag, _)| *tag).collect()
+}
+
+/// Switch the language used by `t!`; `None` restores the default.
+pub fn set_language(language: Option<&str>) {
+    let resolved = language.map(resolve).unwrap_or(DEFAULT_LANGUAGE);
+    if let Ok(mut current) = LANGUAGE.write() {
+        *current = resolved;
+    }
+}
+
+pub fn current_language() -> &'static str {
+    LANGUAGE.read().map(|l| *l).unwrap_or(DEFAULT_LANGUAGE)
+}
+
+/// Look up `key` in `language` and fill in `{ $name }` placeholders. Unknown
+/// keys come back as the key itself so a gap is visible rather than blank.
+pub fn translate(language: &str, key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let text = [language, DEFAULT_LANGUAGE]
+        .iter()
+        .find_map(|l| CATALOGS.get(l).and_then(|c| c.get(key)))
+        .copied()
+        .unwrap_or(key);
+
+    let mut message = text.to_string();
+    for (name, value) in args {
+        message = message.replace(&format!("{{ ${} }}", name), &value.to_string());
+    }
+    message
+}
+
+/// `t!("smell-wide-table", table = id, count = n)` in the current language.
+macro_rules! t {
+    ($key:expr) => {
+        $crate::i18n::translate($crate::i18n::current_language(), $key, &[])
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::translate(
+            $crate::i18n::current_language(),
+            $key,
+            &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+],
+        )
+    };
+}
+pub(crate) use t;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_fall_back_to_english() {
+        let args: &[(&str, &dyn Display)] = &[("table", &"dbo.Orders"), ("count", &120)];
+        assert_eq!(
+            translate("de", "smell-wide-table", args),
+            "dbo.Orders hat 120 Spalten"
+        );
+        assert_eq!(
+            translate(resolve("fr-CA"), "smell-wide-table", args),
+            "dbo.Orders has 120 columns"
+        );
+        assert_eq!(resolve("de_AT"), "de");
+        assert_eq!(translate("de", "no-such-key", &[]), "no-such-key");
+
+        // Every key in a translation must exist in the English catalog
+        let english = &CATALOGS[DEFAULT_LANGUAGE];
+        for (language, catalog) in CATALOGS.iter() {
+            for key in catalog.keys() {
+                assert!(
+                    english.contains_key(key),
+                    "{} has unknown key {}",
+                    language,
+                    key
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::Mutex;
+use tokio::sync::oneshot;
+
+type Waiters<T> = Vec<oneshot::Sender<Result<T, String>>>;
+
+/// Coalesces concurrent requests for the same key, such as a double-clicked
+/// schema load: the first caller does the work and everyone who asks before
+/// it finishes gets a copy of its result.
+pub struct InFlight<T> {
+    pending: Mutex<HashMap<String, Waiters<T>>>,
+}
+
+impl<T> Default for InFlight<T> {
+    fn default() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+/// Clears the key when the first caller finishes or its future is dropped,
+/// so an abandoned request never leaves later ones waiting.
+struct Leader<'a, T> {
+    pending: &'a Mutex<HashMap<String, Waiters<T>>>,
+    key: &'a str,
+}
+
+impl<T> Leader<'_, T> {
+    fn finish(self) -> Waiters<T> {
+        self.pending
+            .lock()
+            .ok()
+            .and_then(|mut pending| pending.remove(self.key))
+            .unwrap_or_default()
+    }
+}
+
+impl<T> Drop for Leader<'_, T> {
+    fn drop(&mut self) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(self.key);
+        }
+    }
+}
+
+impl<T: Clone> InFlight<T> {
+    /// Run `work` unless a request for `key` is already running, in which
+    /// case wait for that one. A joined request's error arrives as text and
+    /// is rebuilt with `joined`.
+    pub async fn run<E: Display>(
+        &self,
+        key: &str,
+        work: impl Future<Output = Result<T, E>>,
+        joined: impl FnOnce(String) -> E,
+    ) -> Result<T, E> {
+        let receiver = {
+            let Ok(mut pending) = self.pending.lock() else {
+                return work.await;
+            };
+            match pending.get_mut(key) {
+                Some(waiters) => {
+                    let (sender, receiver) = oneshot::channel();
+                    waiters.push(sender);
+                    Some(receiver)
+                }
+                None => {
+                    pending.insert(key.to_string(), Vec::new());
+                    None
+                }
+            }
+        };
+        if let Some(receiver) = receiver {
+            return match receiver.await {
+                Ok(result) => result.map_err(joined),
+                // The running request was dropped before it finished
+                Err(_) => work.await,
+            };
+        }
+
+        let leader = Leader {
+            pending: &self.pending,
+            key,
+        };
+        let result = work.await;
+        let waiters = leader.finish();
+        if !waiters.is_empty() {
+            let shared = result.as_ref().map(T::clone).map_err(E::to_string);
+            for waiter in waiters {
+                let _ = waiter.send(shared.clone());
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn concurrent_requests_share_one_run() {
+        let inflight = InFlight::<usize>::default();
+        let runs = AtomicUsize::new(0);
+        let (release, released) = oneshot::channel::<()>();
+        let work = |wait: Option<oneshot::Receiver<()>>| {
+            let runs = &runs;
+            async move {
+                if let Some(wait) = wait {
+                    let _ = wait.await;
+                }
+                Ok::<_, String>(runs.fetch_add(1, Ordering::SeqCst) + 1)
+            }
+        };
+
+        let first = inflight.run("sql01/sales", work(Some(released)), |e| e);
+        let second = inflight.run("sql01/sales", work(None), |e| e);
+        let other = inflight.run("sql01/hr", work(None), |e| e);
+        let (first, second, other, _) = tokio::join!(first, second, other, async {
+            tokio::task::yield_now().await;
+            let _ = release.send(());
+        });
+
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+        // Finished requests don't linger
+        assert_eq!(inflight.run("sql01/sales", work(None), |e| e).await, Ok(3));
+    }
+}
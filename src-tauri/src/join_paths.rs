@@ -0,0 +1,483 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::glossary::Glossary;
+use crate::naming::singular;
+use crate::search_index::{normalize_phrase, synonym_groups, words};
+use crate::types::{RelationshipEdge, SchemaGraph, TableNode};
+
+/// Tables returned as candidates.
+const MAX_CANDIDATES: usize = 8;
+/// Tables the join path connects, one per question term at most.
+const MAX_ANCHORS: usize = 4;
+
+const NAME_MATCH: u32 = 3;
+const NAME_PART_MATCH: u32 = 2;
+const COLUMN_MATCH: u32 = 1;
+
+/// Words that shape a question but never name a table.
+const STOP_WORDS: &[&str] = &[
+    "a", "all", "an", "and", "are", "by", "count", "each", "every", "for", "from", "give", "how",
+    "in", "is", "list", "many", "me", "much", "number", "of", "on", "or", "per", "show", "that",
+    "the", "their", "to", "total", "what", "which", "who", "with",
+];
+
+/// Words asking for a time filter, which points at date columns.
+const TIME_WORDS: &[&str] = &[
+    "date",
+    "day",
+    "days",
+    "last",
+    "month",
+    "months",
+    "quarter",
+    "recent",
+    "since",
+    "today",
+    "week",
+    "weeks",
+    "year",
+    "years",
+    "yesterday",
+];
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableMatch {
+    pub table_id: String,
+    pub score: u32,
+    /// Question terms the table or its columns matched.
+    pub terms: Vec<String>,
+    /// Matching columns, then date columns when the question mentions time.
+    pub columns: Vec<String>,
+}
+
+/// One join along a foreign key; `on` pairs `from` columns with `to` ones.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JoinStep {
+    pub from: String,
+    pub to: String,
+    pub on: Vec<(String, String)>,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JoinSuggestion {
+    /// Question words and phrases looked up, glossary synonyms not included.
+    pub terms: Vec<String>,
+    pub tables: Vec<TableMatch>,
+    /// Joins from the first anchor table out to the others, in order.
+    pub joins: Vec<JoinStep>,
+    /// Anchor tables no chain of foreign keys reaches.
+    pub unreachable: Vec<String>,
+    /// A `FROM ... JOIN` skeleton for the path; `None` without candidates.
+    pub sql: Option<String>,
+}
+
+/// Question words worth looking up, and each adjacent pair joined the way
+/// an identifier would write it (`purchase order` → `purchaseorder`).
+fn question_terms(question: &str) -> Vec<String> {
+    let lowered = question.to_lowercase();
+    let plain: Vec<&str> = lowered
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let mut terms = Vec::new();
+    for (i, word) in plain.iter().enumerate() {
+        if STOP_WORDS.contains(word) || TIME_WORDS.contains(word) {
+            continue;
+        }
+        terms.push(word.to_string());
+        if let Some(next) = plain.get(i + 1) {
+            if !STOP_WORDS.contains(next) && !TIME_WORDS.contains(next) {
+                terms.push(format!("{}{}", word, next));
+            }
+        }
+    }
+    let mut seen = HashSet::new();
+    terms.retain(|t| seen.insert(t.clone()));
+    terms
+}
+
+fn is_date_type(data_type: &str) -> bool {
+    let lower = data_type.to_lowercase();
+    lower.contains("date") || lower.starts_with("time")
+}
+
+/// Score of `table` for one term and its glossary synonyms (all singular),
+/// with the columns that matched.
+fn match_table(
+    table: &TableNode,
+    forms: &[String],
+    classified: &HashMap<String, Vec<(String, Option<String>)>>,
+) -> (u32, Vec<String>) {
+    let mut score = 0;
+    let mut columns = Vec::new();
+    let name_words: Vec<String> = words(&table.name).iter().map(|w| singular(w)).collect();
+    for form in forms {
+        if name_words.first() == Some(form) {
+            score = score.max(NAME_MATCH);
+        } else if name_words.iter().skip(1).any(|w| w == form) {
+            score = score.max(NAME_PART_MATCH);
+        }
+        for (object_id, column) in classified.get(form).into_iter().flatten() {
+            if object_id != &table.id {
+                continue;
+            }
+            match column {
+                None => score = score.max(NAME_MATCH),
+                Some(column) => columns.push(column.clone()),
+            }
+        }
+    }
+    for column in &table.columns {
+        let column_words: Vec<String> = words(&column.name).iter().map(|w| singular(w)).collect();
+        if forms.iter().any(|f| column_words.contains(f)) {
+            columns.push(column.name.clone());
+        }
+    }
+    columns.sort();
+    columns.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    if score == 0 && !columns.is_empty() {
+        score = COLUMN_MATCH;
+    }
+    (score, columns)
+}
+
+/// Foreign keys as an undirected adjacency list; edges without both
+/// columns can't be joined on and are left out.
+fn adjacency(graph: &SchemaGraph) -> HashMap<&str, Vec<&RelationshipEdge>> {
+    let mut adjacent: HashMap<&str, Vec<&RelationshipEdge>> = HashMap::new();
+    for r in &graph.relationships {
+        if r.from_column.is_none() || r.to_column.is_none() || r.from == r.to {
+            continue;
+        }
+        adjacent.entry(r.from.as_str()).or_default().push(r);
+        adjacent.entry(r.to.as_str()).or_default().push(r);
+    }
+    adjacent
+}
+
+/// Shortest chain of tables from any table in `joined` to `target`.
+fn shortest_path<'a>(
+    adjacent: &HashMap<&'a str, Vec<&'a RelationshipEdge>>,
+    joined: &[&'a str],
+    target: &'a str,
+) -> Option<Vec<&'a str>> {
+    let mut previous: HashMap<&str, Option<&str>> = joined.iter().map(|t| (*t, None)).collect();
+    let mut queue: VecDeque<&str> = joined.iter().copied().collect();
+    while let Some(table) = queue.pop_front() {
+        if table == target {
+            let mut path = vec![table];
+            let mut current = table;
+            while let Some(Some(prior)) = previous.get(current) {
+                path.push(prior);
+                current = prior;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for r in adjacent.get(table).into_iter().flatten() {
+            let next = if r.from == table { &r.to } else { &r.from };
+            if !previous.contains_key(next.as_str()) {
+                previous.insert(next, Some(table));
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// The join from `from` to `to` on every foreign key column pair between
+/// them, written from `from`'s side.
+fn join_step(graph: &SchemaGraph, from: &str, to: &str) -> JoinStep {
+    let mut on = Vec::new();
+    for r in &graph.relationships {
+        let (Some(from_column), Some(to_column)) = (&r.from_column, &r.to_column) else {
+            continue;
+        };
+        let pair = if r.from == from && r.to == to {
+            (from_column.clone(), to_column.clone())
+        } else if r.from == to && r.to == from {
+            (to_column.clone(), from_column.clone())
+        } else {
+            continue;
+        };
+        if !on.contains(&pair) {
+            on.push(pair);
+        }
+    }
+    JoinStep {
+        from: from.to_string(),
+        to: to.to_string(),
+        on,
+    }
+}
+
+fn join_sql(first: &str, joins: &[JoinStep]) -> String {
+    let mut sql = format!("SELECT *\nFROM {}", first);
+    for join in joins {
+        let conditions: Vec<String> = join
+            .on
+            .iter()
+            .map(|(from, to)| format!("{}.{} = {}.{}", join.from, from, join.to, to))
+            .collect();
+        sql.push_str(&format!(
+            "\nJOIN {} ON {}",
+            join.to,
+            conditions.join(" AND ")
+        ));
+    }
+    sql
+}
+
+/// Tables and columns a free-text question is likely about, matched by
+/// name and glossary terms, and the foreign key joins connecting them.
+/// Purely name based: nothing leaves the machine.
+pub fn suggest_join_path(
+    graph: &SchemaGraph,
+    glossary: &Glossary,
+    question: &str,
+) -> JoinSuggestion {
+    let terms = question_terms(question);
+    let synonyms = synonym_groups(glossary);
+    let mut classified: HashMap<String, Vec<(String, Option<String>)>> = HashMap::new();
+    for c in &graph.classifications {
+        for term in &c.terms {
+            classified
+                .entry(singular(&normalize_phrase(term)))
+                .or_default()
+                .push((c.object_id.clone(), c.column.clone()));
+        }
+    }
+    let wants_time = question
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|w| TIME_WORDS.contains(&w));
+
+    let mut matches: HashMap<&str, TableMatch> = HashMap::new();
+    let mut anchors: Vec<&str> = Vec::new();
+    for term in &terms {
+        let mut forms = vec![singular(term)];
+        for key in [term.clone(), singular(term)] {
+            for synonym in synonyms.get(&key).into_iter().flatten() {
+                forms.push(singular(synonym));
+            }
+        }
+        forms.dedup();
+        let mut best: Option<(u32, &TableNode)> = None;
+        for table in &graph.tables {
+            let (score, columns) = match_table(table, &forms, &classified);
+            if score == 0 {
+                continue;
+            }
+            let entry = matches.entry(&table.id).or_insert_with(|| TableMatch {
+                table_id: table.id.clone(),
+                score: 0,
+                terms: Vec::new(),
+                columns: Vec::new(),
+            });
+            entry.score += score;
+            entry.terms.push(term.clone());
+            for column in columns {
+                if !entry.columns.contains(&column) {
+                    entry.columns.push(column);
+                }
+            }
+            // On a tie the shorter name is the more general table
+            if score > COLUMN_MATCH
+                && best.is_none_or(|(s, current)| {
+                    score > s || (score == s && table.name.len() < current.name.len())
+                })
+            {
+                best = Some((score, table));
+            }
+        }
+        if let Some((_, table)) = best {
+            if !anchors.contains(&table.id.as_str()) && anchors.len() < MAX_ANCHORS {
+                anchors.push(&table.id);
+            }
+        }
+    }
+
+    let mut tables: Vec<TableMatch> = matches.into_values().collect();
+    tables.sort_by(|a, b| b.score.cmp(&a.score).then(a.table_id.cmp(&b.table_id)));
+    tables.truncate(MAX_CANDIDATES);
+    if wants_time {
+        for table_match in &mut tables {
+            let Some(table) = graph.tables.iter().find(|t| t.id == table_match.table_id) else {
+                continue;
+            };
+            for column in table.columns.iter().filter(|c| is_date_type(&c.data_type)) {
+                if !table_match.columns.contains(&column.name) {
+                    table_match.columns.push(column.name.clone());
+                }
+            }
+        }
+    }
+    // The best-scoring anchor leads the FROM clause; ties keep question order
+    anchors.sort_by_key(|id| {
+        std::cmp::Reverse(
+            tables
+                .iter()
+                .find(|t| t.table_id == *id)
+                .map_or(0, |t| t.score),
+        )
+    });
+
+    let adjacent = adjacency(graph);
+    let mut joined: Vec<&str> = anchors.first().copied().into_iter().collect();
+    let mut joins = Vec::new();
+    let mut unreachable = Vec::new();
+    for anchor in anchors.iter().skip(1) {
+        if joined.contains(anchor) {
+            continue;
+        }
+        match shortest_path(&adjacent, &joined, anchor) {
+            Some(path) => {
+                for pair in path.windows(2) {
+                    joins.push(join_step(graph, pair[0], pair[1]));
+                    joined.push(pair[1]);
+                }
+            }
+            None => unreachable.push(anchor.to_string()),
+        }
+    }
+
+    JoinSuggestion {
+        sql: joined.first().map(|first| join_sql(first, &joins)),
+        terms,
+        tables,
+        joins,
+        unreachable,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glossary::GlossaryTerm;
+    use crate::types::Column;
+
+    fn table(name: &str, columns: &[(&str, &str)]) -> TableNode {
+        TableNode {
+            id: format!("dbo.{}", name),
+            name: name.to_string(),
+            schema: "dbo".into(),
+            columns: columns
+                .iter()
+                .map(|(name, data_type)| Column {
+                    name: name.to_string(),
+                    data_type: (*data_type).into(),
+                    is_nullable: false,
+                    is_primary_key: *name == "Id",
+                    ordinal_position: 0,
+                    source_columns: Vec::new(),
+                    source_table: None,
+                    source_column: None,
+                    is_identity: false,
+                    is_computed: false,
+                })
+                .collect(),
+            source: None,
+            metadata: None,
+            change_capture: None,
+        }
+    }
+
+    fn foreign_key(from: &str, column: &str, to: &str) -> RelationshipEdge {
+        RelationshipEdge {
+            id: format!("FK_{}_{}", from, to),
+            from: format!("dbo.{}", from),
+            to: format!("dbo.{}", to),
+            from_column: Some(column.to_string()),
+            to_column: Some("Id".to_string()),
+            source: None,
+            is_virtual: false,
+            participation: None,
+        }
+    }
+
+    fn shop() -> SchemaGraph {
+        SchemaGraph {
+            tables: vec![
+                table("Customers", &[("Id", "int"), ("Name", "nvarchar(100)")]),
+                table(
+                    "Orders",
+                    &[("Id", "int"), ("CustomerId", "int"), ("OrderDate", "date")],
+                ),
+                table(
+                    "OrderLines",
+                    &[("Id", "int"), ("OrderId", "int"), ("ProductId", "int")],
+                ),
+                table("Products", &[("Id", "int"), ("Name", "nvarchar(100)")]),
+            ],
+            relationships: vec![
+                foreign_key("Orders", "CustomerId", "Customers"),
+                foreign_key("OrderLines", "OrderId", "Orders"),
+                foreign_key("OrderLines", "ProductId", "Products"),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn question_maps_to_tables_and_join() {
+        let suggestion = suggest_join_path(
+            &shop(),
+            &Glossary::default(),
+            "Orders per customer last month",
+        );
+
+        assert_eq!(suggestion.terms, vec!["orders", "customer"]);
+        assert_eq!(suggestion.tables[0].table_id, "dbo.Orders");
+        assert!(suggestion.tables[0]
+            .columns
+            .contains(&"OrderDate".to_string()));
+        assert_eq!(
+            suggestion.joins,
+            vec![JoinStep {
+                from: "dbo.Orders".to_string(),
+                to: "dbo.Customers".to_string(),
+                on: vec![("CustomerId".to_string(), "Id".to_string())],
+            }]
+        );
+        assert_eq!(
+            suggestion.sql.as_deref(),
+            Some("SELECT *\nFROM dbo.Orders\nJOIN dbo.Customers ON dbo.Orders.CustomerId = dbo.Customers.Id")
+        );
+    }
+
+    #[test]
+    fn glossary_synonyms_find_tables_across_several_joins() {
+        let glossary = Glossary {
+            terms: vec![GlossaryTerm {
+                name: "Client".to_string(),
+                definition: String::new(),
+                synonyms: vec!["Customer".to_string()],
+            }],
+            tags: Vec::new(),
+        };
+        let suggestion = suggest_join_path(&shop(), &glossary, "products bought by clients");
+
+        let path: Vec<(&str, &str)> = suggestion
+            .joins
+            .iter()
+            .map(|j| (j.from.as_str(), j.to.as_str()))
+            .collect();
+        assert_eq!(
+            path,
+            vec![
+                ("dbo.Products", "dbo.OrderLines"),
+                ("dbo.OrderLines", "dbo.Orders"),
+                ("dbo.Orders", "dbo.Customers"),
+            ]
+        );
+        assert_eq!(
+            suggestion.joins[0].on,
+            vec![("Id".to_string(), "ProductId".to_string())]
+        );
+        assert!(suggestion.unreachable.is_empty());
+    }
+}
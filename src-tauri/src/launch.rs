@@ -0,0 +1,158 @@
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::Url;
+
+/// Scheme of the links the app is registered for, through the deep-link
+/// plugin's `schemes` in tauri.conf.json.
+pub const URL_SCHEME: &str = "monocle";
+/// Files the app opens when they're double-clicked or passed on the
+/// command line: canvas files and diagram packages.
+const FILE_EXTENSIONS: &[&str] = &["monocle", "monoclepkg"];
+/// Event telling the frontend that `take_launch_requests_cmd` has more.
+pub const LAUNCH_EVENT: &str = "launch-requested";
+
+/// Something a launch asked the app to open.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum LaunchRequest {
+    OpenFile {
+        path: String,
+    },
+    /// A `monocle://` link, passed through for the frontend to route.
+    OpenLink {
+        url: String,
+    },
+}
+
+/// Requests from this process's own arguments and from launches forwarded
+/// by later instances, held until the frontend picks them up. Queuing
+/// rather than only emitting means a request that arrives before the
+/// window has subscribed isn't lost.
+#[derive(Default)]
+pub struct LaunchQueue {
+    pending: Mutex<Vec<LaunchRequest>>,
+}
+
+impl LaunchQueue {
+    pub fn new(requests: Vec<LaunchRequest>) -> Self {
+        Self {
+            pending: Mutex::new(requests),
+        }
+    }
+
+    pub fn push(&self, requests: Vec<LaunchRequest>) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.extend(requests);
+        }
+    }
+
+    pub fn take(&self) -> Vec<LaunchRequest> {
+        self.pending
+            .lock()
+            .map(|mut pending| std::mem::take(&mut *pending))
+            .unwrap_or_default()
+    }
+}
+
+fn is_link(arg: &str) -> bool {
+    arg.split_once("://")
+        .is_some_and(|(scheme, _)| scheme.eq_ignore_ascii_case(URL_SCHEME))
+}
+
+fn is_openable_file(arg: &str) -> bool {
+    Path::new(arg)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            FILE_EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+}
+
+/// A URL the OS handed over (a link, or on macOS a file too) as the launch
+/// argument it stands for.
+pub fn url_launch_arg(url: &Url) -> String {
+    match url.to_file_path() {
+        Ok(path) if url.scheme() == "file" => path.to_string_lossy().into_owned(),
+        _ => url.to_string(),
+    }
+}
+
+/// What a launch's arguments (without the program name) ask to open.
+/// Relative paths are resolved against `cwd`, the directory the launch
+/// came from, since a forwarded launch ran somewhere else. Flags and
+/// anything else are ignored.
+pub fn parse_launch_args(args: &[String], cwd: &Path) -> Vec<LaunchRequest> {
+    args.iter()
+        .filter(|arg| !arg.starts_with('-'))
+        .filter_map(|arg| {
+            if is_link(arg) {
+                Some(LaunchRequest::OpenLink { url: arg.clone() })
+            } else if is_openable_file(arg) {
+                let path = cwd.join(arg);
+                Some(LaunchRequest::OpenFile {
+                    path: path.to_string_lossy().into_owned(),
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn launch_args_yield_files_and_links() {
+        let cwd = Path::new("/home/dana/diagrams");
+        let args: Vec<String> = [
+            "--safe-mode",
+            "sales.monocle",
+            "/tmp/shared.MONOCLEPKG",
+            "Monocle://open?server=sql01&database=Sales",
+            "notes.txt",
+            "https://example.com",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let requests = parse_launch_args(&args, cwd);
+        assert_eq!(
+            requests,
+            vec![
+                LaunchRequest::OpenFile {
+                    path: cwd.join("sales.monocle").to_string_lossy().into_owned()
+                },
+                LaunchRequest::OpenFile {
+                    path: "/tmp/shared.MONOCLEPKG".to_string()
+                },
+                LaunchRequest::OpenLink {
+                    url: "Monocle://open?server=sql01&database=Sales".to_string()
+                },
+            ]
+        );
+
+        let queue = LaunchQueue::new(requests);
+        queue.push(Vec::new());
+        assert_eq!(queue.take().len(), 3);
+        assert!(queue.take().is_empty());
+    }
+
+    #[test]
+    fn opened_urls_become_launch_args() {
+        let link = Url::parse("monocle://open?server=sql01&database=Sales").unwrap();
+        assert_eq!(
+            url_launch_arg(&link),
+            "monocle://open?server=sql01&database=Sales"
+        );
+
+        let file = Url::parse("file:///Users/dana/sales.monocle").unwrap();
+        let arg = url_launch_arg(&file);
+        assert!(arg.ends_with("sales.monocle") && !arg.starts_with("file:"));
+    }
+}
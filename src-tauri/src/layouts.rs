@@ -0,0 +1,220 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::t;
+use crate::state::connection_file_stem;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodePosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Viewport {
+    pub x: f64,
+    pub y: f64,
+    pub zoom: f64,
+}
+
+/// A named arrangement of one database's diagram.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagramLayout {
+    pub name: String,
+    /// Node positions keyed by graph ID.
+    pub positions: BTreeMap<String, NodePosition>,
+    #[serde(default)]
+    pub collapsed_groups: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub viewport: Option<Viewport>,
+    /// Set when saved; any value sent by the caller is replaced.
+    #[serde(default)]
+    pub saved_at: String,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutSummary {
+    pub name: String,
+    pub saved_at: String,
+    pub node_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+struct LayoutFile {
+    layouts: Vec<DiagramLayout>,
+}
+
+fn layout_path(storage_path: &Path, server: &str, database: &str) -> PathBuf {
+    storage_path
+        .join("layouts")
+        .join(format!("{}.json", connection_file_stem(server, database)))
+}
+
+fn read_layout_file(path: &Path) -> LayoutFile {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_layout_file(path: &Path, file: &LayoutFile) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create layout directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(file)
+        .map_err(|e| format!("Failed to serialize layouts: {}", e))?;
+    std::fs::write(path, content).map_err(|e| format!("Failed to write layouts: {}", e))
+}
+
+fn summaries(file: &LayoutFile) -> Vec<LayoutSummary> {
+    file.layouts
+        .iter()
+        .map(|layout| LayoutSummary {
+            name: layout.name.clone(),
+            saved_at: layout.saved_at.clone(),
+            node_count: layout.positions.len(),
+        })
+        .collect()
+}
+
+/// Saved layouts for a connection, in name order.
+pub fn list_layouts(storage_path: &Path, server: &str, database: &str) -> Vec<LayoutSummary> {
+    summaries(&read_layout_file(&layout_path(
+        storage_path,
+        server,
+        database,
+    )))
+}
+
+/// Save a layout, replacing any existing layout with the same name
+/// (compared case-insensitively).
+pub fn save_layout(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+    mut layout: DiagramLayout,
+) -> Result<Vec<LayoutSummary>, String> {
+    let name = layout.name.trim().to_string();
+    if name.is_empty() {
+        return Err(t!("error-layout-name-required"));
+    }
+    layout.name = name;
+    layout.saved_at = Utc::now().to_rfc3339();
+
+    let path = layout_path(storage_path, server, database);
+    let mut file = read_layout_file(&path);
+    file.layouts
+        .retain(|l| !l.name.eq_ignore_ascii_case(&layout.name));
+    file.layouts.push(layout);
+    file.layouts.sort_by_key(|l| l.name.to_lowercase());
+    write_layout_file(&path, &file)?;
+    Ok(summaries(&file))
+}
+
+pub fn load_layout(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+    name: &str,
+) -> Result<DiagramLayout, String> {
+    read_layout_file(&layout_path(storage_path, server, database))
+        .layouts
+        .into_iter()
+        .find(|l| l.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| t!("error-layout-not-found", name = name))
+}
+
+pub fn delete_layout(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+    name: &str,
+) -> Result<Vec<LayoutSummary>, String> {
+    let path = layout_path(storage_path, server, database);
+    let mut file = read_layout_file(&path);
+    let before = file.layouts.len();
+    file.layouts.retain(|l| !l.name.eq_ignore_ascii_case(name));
+    if file.layouts.len() == before {
+        return Err(t!("error-layout-not-found", name = name));
+    }
+    write_layout_file(&path, &file)?;
+    Ok(summaries(&file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn layout(name: &str, nodes: &[&str]) -> DiagramLayout {
+        DiagramLayout {
+            name: name.to_string(),
+            positions: nodes
+                .iter()
+                .enumerate()
+                .map(|(i, id)| {
+                    (
+                        id.to_string(),
+                        NodePosition {
+                            x: i as f64 * 100.0,
+                            y: 0.0,
+                        },
+                    )
+                })
+                .collect(),
+            collapsed_groups: vec!["audit".to_string()],
+            viewport: Some(Viewport {
+                x: 0.0,
+                y: 0.0,
+                zoom: 0.8,
+            }),
+            saved_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn layouts_are_saved_per_connection_and_replaced_by_name() {
+        let dir = tempdir().expect("tempdir");
+        let root = dir.path();
+
+        save_layout(
+            root,
+            "srv",
+            "Billing",
+            layout("Full map", &["dbo.A", "dbo.B"]),
+        )
+        .unwrap();
+        save_layout(
+            root,
+            "srv",
+            "Billing",
+            layout("Billing overview", &["dbo.A"]),
+        )
+        .unwrap();
+        let listed = save_layout(root, "srv", "Billing", layout("full map", &["dbo.A"])).unwrap();
+
+        let names: Vec<(&str, usize)> = listed
+            .iter()
+            .map(|l| (l.name.as_str(), l.node_count))
+            .collect();
+        assert_eq!(names, vec![("Billing overview", 1), ("full map", 1)]);
+        assert!(list_layouts(root, "srv", "Other").is_empty());
+
+        let loaded = load_layout(root, "SRV", "billing", "Billing Overview").unwrap();
+        assert_eq!(loaded.viewport.unwrap().zoom, 0.8);
+        assert!(!loaded.saved_at.is_empty());
+
+        let remaining = delete_layout(root, "srv", "Billing", "full map").unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert!(delete_layout(root, "srv", "Billing", "full map").is_err());
+        assert!(save_layout(root, "srv", "Billing", layout("  ", &[])).is_err());
+    }
+}
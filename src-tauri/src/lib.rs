@@ -1,24 +1,122 @@
+mod analysis;
+mod annotations;
+mod bookmarks;
+mod bundle;
+mod catalog_push;
+mod changeset;
 mod commands;
+mod containers;
+mod credentials;
+mod data_dictionary;
+mod data_location;
 mod db;
+mod ddl;
+mod definition_diff;
+mod diagnostics;
+mod ef_migrations;
+mod environment;
+mod er_export;
+mod exclusions;
+mod export_annotations;
+mod export_themes;
+mod glossary;
+mod graph_cache;
+mod graph_patch;
+mod i18n;
+mod inflight;
+mod join_paths;
+mod launch;
+mod layouts;
 mod menu;
+mod naming;
+mod network;
+mod network_export;
+mod onboarding;
+mod openlineage;
+mod perf;
+mod print;
+mod profiles;
+mod query_export;
+mod sandboxes;
+mod saved_queries;
+mod schema_summary;
+mod search_index;
+mod session;
+mod snapshots;
+mod sources;
 mod state;
+mod stewardship;
+mod tasks;
 mod types;
 mod validation;
+mod virtual_relationships;
+mod watch_export;
+mod windows;
+mod workspace;
 
 use commands::{
-    bulk_scan_cmd, cancel_directory_cmd, cancel_scan_cmd, check_path_reachable,
-    content_search_cmd, get_settings, list_databases_cmd, list_directory_cmd, load_schema_cmd,
-    load_schema_mock, read_file_cmd, save_settings, set_menu_ui_state_cmd, toggle_favorite_cmd,
-    ExplorerState,
+    acquire_canvas_lock_cmd, add_virtual_relationship_cmd, advance_onboarding_cmd,
+    analyze_design_cmd, analyze_view_nesting_cmd, apply_naming_cmd, bulk_scan_cmd,
+    cancel_directory_cmd, cancel_scan_cmd, cancel_task_cmd, check_canvas_conflict_cmd,
+    check_for_updates_cmd, check_path_reachable, check_portability_cmd, classify_object_cmd,
+    clear_crash_reports_cmd, clear_performance_stats_cmd, compare_row_counts_cmd,
+    connect_profile_cmd, content_search_cmd, correlate_ef_migrations_cmd,
+    create_sample_workspace_cmd, create_sandbox_database_cmd, defer_update_cmd,
+    delete_credential_cmd, delete_layout_cmd, delete_saved_query_cmd, detect_column_domains_cmd,
+    detect_local_sql_containers_cmd, diagnose_environment_cmd, diff_definitions_cmd,
+    drop_sandbox_database_cmd, execute_procedure_cmd, export_compliance_report_cmd,
+    export_data_dictionary_cmd, export_ddl_cmd, export_diagram_bundle_cmd, export_er_diagram_cmd,
+    export_migration_kit_cmd, export_network_graph_cmd, export_openlineage_cmd,
+    export_query_result_cmd, export_rebuild_script_cmd, export_sanitized_profile_cmd,
+    get_availability_info_cmd, get_canvas_file_stamp_cmd, get_catalog_target_cmd,
+    get_change_capture_report_cmd, get_change_heatmap_cmd, get_check_rules_cmd,
+    get_complexity_report_cmd, get_crash_reports_cmd, get_credential_cmd, get_data_location_cmd,
+    get_dynamic_sql_report_cmd, get_exclusions_cmd, get_export_theme_cmd, get_export_themes_cmd,
+    get_glossary_cmd, get_graph_stats_cmd, get_languages_cmd, get_migration_history_cmd,
+    get_object_definition_cmd, get_object_history_cmd, get_onboarding_cmd,
+    get_performance_stats_cmd, get_query_limits_cmd, get_schema_display_cmd, get_settings,
+    get_startup_action_cmd, get_type_mappings_cmd, get_window_context_cmd, has_credential_cmd,
+    import_annotations_csv_cmd, import_diagram_bundle_cmd, import_profile_template_cmd,
+    import_tabular_model_cmd, install_update_cmd, is_read_only_session_cmd, is_safe_mode_cmd,
+    list_annotations_cmd, list_bookmarks_cmd, list_classifications_cmd, list_databases_cmd,
+    list_directory_cmd, list_layouts_cmd, list_sandbox_databases_cmd, list_saved_queries_cmd,
+    list_stewardship_cmd, list_tasks_cmd, list_virtual_relationships_cmd, load_dbt_manifest_cmd,
+    load_flat_files_cmd, load_layout_cmd, load_schema_binary_cmd, load_schema_cmd,
+    load_schema_mock, load_schema_mock_binary, load_workspace_cmd, merge_canvas_cmd,
+    open_new_window_cmd, plan_rename_cmd, preview_naming_cmd, print_diagram_cmd, push_catalog_cmd,
+    read_file_cmd, refactor_design_cmd, refresh_object_state_cmd, refresh_schema_cmd,
+    release_canvas_lock_cmd, remove_bookmark_cmd, remove_glossary_term_cmd, remove_tag_cmd,
+    remove_virtual_relationship_cmd, restore_session_cmd, review_changeset_cmd, run_compliance_cmd,
+    run_query_cmd, run_saved_query_cmd, save_glossary_term_cmd, save_layout_cmd, save_query_cmd,
+    save_settings, save_tag_cmd, scan_report_usage_cmd, search_schema_index_cmd,
+    set_annotation_cmd, set_bookmark_cmd, set_catalog_target_cmd, set_data_location_cmd,
+    set_exclusions_cmd, set_menu_ui_state_cmd, set_payload_measurement_cmd, set_query_limits_cmd,
+    set_read_only_session_cmd, set_startup_action_cmd, set_stewardship_cmd,
+    simulate_type_change_cmd, skip_onboarding_cmd, start_watch_export_cmd, store_credential_cmd,
+    stream_databases_cmd, suggest_join_path_cmd, summarize_schema_cmd, take_launch_requests_cmd,
+    toggle_favorite_cmd, update_schema_display_cmd, update_session_cmd, ExplorerState,
 };
+use launch::LaunchQueue;
 use state::AppState;
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Mutex;
-use tauri::Manager;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+/// How long exit waits for cancelled tasks to wind down.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        // Registered first so a second launch exits before it touches
+        // settings.json; its files and links go to this instance instead
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            forward_launch(app, argv.get(1..).unwrap_or_default(), Path::new(&cwd));
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
@@ -26,18 +124,53 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .setup(|app| {
-            let app_data_dir = app
+            let args: Vec<String> = std::env::args().collect();
+            let default_dir = app
                 .path()
                 .app_data_dir()
                 .expect("Failed to get app data directory");
-            let state = AppState::new(app_data_dir);
+            // --data-dir <path> or --portable, else the saved choice
+            let location = data_location::resolve_data_location(
+                &args,
+                data_location::exe_dir().as_deref(),
+                &default_dir,
+            );
+            let app_data_dir = location.path.clone();
+            app.manage(location);
+            diagnostics::install_panic_hook(app_data_dir.clone());
+            // --safe-mode: start with default settings and nothing restored
+            let safe_mode = args.iter().any(|arg| arg == "--safe-mode");
+            let state = match safe_mode {
+                true => AppState::new_safe_mode(app_data_dir),
+                false => AppState::new(app_data_dir),
+            };
             app.manage(state);
+            if !safe_mode {
+                state::watch_settings(app.handle().clone());
+            }
 
             let explorer_state = ExplorerState {
                 active_listings: Mutex::new(HashMap::new()),
             };
             app.manage(explorer_state);
 
+            let cwd = std::env::current_dir().unwrap_or_default();
+            let requests = launch::parse_launch_args(args.get(1..).unwrap_or_default(), &cwd);
+            app.manage(LaunchQueue::new(requests));
+
+            // Links opened while running, and on macOS files as well; later
+            // launches on Windows and Linux arrive through single-instance
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                let args: Vec<String> = event.urls().iter().map(launch::url_launch_arg).collect();
+                forward_launch(&handle, &args, Path::new(""));
+            });
+            // Installers register the scheme; this covers dev builds and AppImages
+            #[cfg(any(windows, target_os = "linux"))]
+            if let Err(e) = app.deep_link().register_all() {
+                eprintln!("Failed to register the monocle:// scheme: {}", e);
+            }
+
             // Setup native menu bar
             let menu = menu::setup_menu(app)?;
             app.set_menu(menu)?;
@@ -45,6 +178,30 @@ pub fn run() {
 
             Ok(())
         })
+        .on_window_event(|window, event| {
+            let state = window.state::<AppState>();
+            match event {
+                tauri::WindowEvent::CloseRequested { .. } => {
+                    if let Err(e) = state.save_session() {
+                        eprintln!("Failed to save session: {}", e);
+                    }
+                }
+                tauri::WindowEvent::Focused(true) => {
+                    // The menu is shared; show the newly focused window's state
+                    let menu_state = state.windows.focus(window.label());
+                    if let Err(e) = menu::set_menu_ui_state(
+                        window.app_handle(),
+                        menu_state.is_canvas_mode,
+                        menu_state.has_focus,
+                        menu_state.has_active_filters,
+                    ) {
+                        eprintln!("Failed to update menu: {}", e);
+                    }
+                }
+                tauri::WindowEvent::Destroyed => state.windows.close(window.label()),
+                _ => {}
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             load_schema_mock,
             load_schema_cmd,
@@ -60,7 +217,174 @@ pub fn run() {
             bulk_scan_cmd,
             cancel_scan_cmd,
             content_search_cmd,
+            acquire_canvas_lock_cmd,
+            release_canvas_lock_cmd,
+            get_canvas_file_stamp_cmd,
+            check_canvas_conflict_cmd,
+            merge_canvas_cmd,
+            check_for_updates_cmd,
+            defer_update_cmd,
+            install_update_cmd,
+            get_crash_reports_cmd,
+            clear_crash_reports_cmd,
+            get_performance_stats_cmd,
+            clear_performance_stats_cmd,
+            load_schema_binary_cmd,
+            load_schema_mock_binary,
+            list_tasks_cmd,
+            cancel_task_cmd,
+            get_object_definition_cmd,
+            load_workspace_cmd,
+            load_flat_files_cmd,
+            load_dbt_manifest_cmd,
+            import_tabular_model_cmd,
+            scan_report_usage_cmd,
+            refresh_object_state_cmd,
+            get_object_history_cmd,
+            export_sanitized_profile_cmd,
+            import_profile_template_cmd,
+            get_schema_display_cmd,
+            update_schema_display_cmd,
+            list_layouts_cmd,
+            save_layout_cmd,
+            load_layout_cmd,
+            delete_layout_cmd,
+            list_virtual_relationships_cmd,
+            add_virtual_relationship_cmd,
+            remove_virtual_relationship_cmd,
+            get_exclusions_cmd,
+            set_exclusions_cmd,
+            get_graph_stats_cmd,
+            analyze_design_cmd,
+            check_portability_cmd,
+            export_ddl_cmd,
+            get_type_mappings_cmd,
+            export_migration_kit_cmd,
+            compare_row_counts_cmd,
+            get_languages_cmd,
+            get_export_theme_cmd,
+            get_export_themes_cmd,
+            start_watch_export_cmd,
+            list_bookmarks_cmd,
+            remove_bookmark_cmd,
+            set_bookmark_cmd,
+            delete_saved_query_cmd,
+            list_saved_queries_cmd,
+            run_query_cmd,
+            run_saved_query_cmd,
+            save_query_cmd,
+            export_query_result_cmd,
+            execute_procedure_cmd,
+            export_rebuild_script_cmd,
+            get_change_heatmap_cmd,
+            export_er_diagram_cmd,
+            analyze_view_nesting_cmd,
+            export_diagram_bundle_cmd,
+            import_diagram_bundle_cmd,
+            set_read_only_session_cmd,
+            is_read_only_session_cmd,
+            get_change_capture_report_cmd,
+            get_availability_info_cmd,
+            stream_databases_cmd,
+            get_startup_action_cmd,
+            set_startup_action_cmd,
+            connect_profile_cmd,
+            update_session_cmd,
+            restore_session_cmd,
+            is_safe_mode_cmd,
+            open_new_window_cmd,
+            get_window_context_cmd,
+            print_diagram_cmd,
+            refresh_schema_cmd,
+            get_dynamic_sql_report_cmd,
+            export_network_graph_cmd,
+            detect_local_sql_containers_cmd,
+            create_sandbox_database_cmd,
+            drop_sandbox_database_cmd,
+            list_sandbox_databases_cmd,
+            list_annotations_cmd,
+            set_annotation_cmd,
+            import_annotations_csv_cmd,
+            get_glossary_cmd,
+            save_glossary_term_cmd,
+            remove_glossary_term_cmd,
+            save_tag_cmd,
+            remove_tag_cmd,
+            list_classifications_cmd,
+            classify_object_cmd,
+            list_stewardship_cmd,
+            set_stewardship_cmd,
+            export_data_dictionary_cmd,
+            run_compliance_cmd,
+            export_compliance_report_cmd,
+            review_changeset_cmd,
+            get_migration_history_cmd,
+            correlate_ef_migrations_cmd,
+            diff_definitions_cmd,
+            search_schema_index_cmd,
+            simulate_type_change_cmd,
+            plan_rename_cmd,
+            get_query_limits_cmd,
+            set_query_limits_cmd,
+            diagnose_environment_cmd,
+            get_onboarding_cmd,
+            advance_onboarding_cmd,
+            skip_onboarding_cmd,
+            create_sample_workspace_cmd,
+            take_launch_requests_cmd,
+            get_data_location_cmd,
+            set_data_location_cmd,
+            export_openlineage_cmd,
+            get_catalog_target_cmd,
+            set_catalog_target_cmd,
+            push_catalog_cmd,
+            detect_column_domains_cmd,
+            get_check_rules_cmd,
+            store_credential_cmd,
+            get_credential_cmd,
+            delete_credential_cmd,
+            has_credential_cmd,
+            get_complexity_report_cmd,
+            summarize_schema_cmd,
+            suggest_join_path_cmd,
+            preview_naming_cmd,
+            apply_naming_cmd,
+            refactor_design_cmd,
+            set_payload_measurement_cmd,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                shutdown(app);
+            }
+        });
+}
+
+/// Bring the main window forward and queue what a launch asked to open.
+fn forward_launch(app: &tauri::AppHandle, args: &[String], cwd: &Path) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+    let requests = launch::parse_launch_args(args, cwd);
+    if requests.is_empty() {
+        return;
+    }
+    app.state::<LaunchQueue>().push(requests);
+    let _ = app.emit(launch::LAUNCH_EVENT, ());
+}
+
+/// Stop background work before the process exits: cancel every task, give
+/// loads time to finish the cache, index and snapshot writes they are in
+/// the middle of, then persist the session.
+fn shutdown(app: &tauri::AppHandle) {
+    let state = app.state::<AppState>();
+    let cancelled = state.tasks.cancel_all();
+    if !state.tasks.drain(SHUTDOWN_GRACE) {
+        eprintln!("Exiting before {} cancelled task(s) finished", cancelled);
+    }
+    if let Err(e) = state.save_session() {
+        eprintln!("Failed to save session: {}", e);
+    }
 }
@@ -1,8 +1,10 @@
 use tauri::{
     menu::{Menu, MenuBuilder, MenuItemBuilder, PredefinedMenuItem, SubmenuBuilder},
-    App, AppHandle, Emitter, Runtime,
+    App, AppHandle, Emitter, Manager, Runtime,
 };
 
+use crate::state::AppState;
+
 const MENU_NEW_CONNECTION: &str = "new-connection";
 const MENU_DISCONNECT: &str = "disconnect";
 const MENU_EXPORT_PNG: &str = "export-png";
@@ -357,7 +359,15 @@ pub fn setup_menu_events<R: Runtime>(app: &App<R>) {
             _ => return,
         };
 
-        if let Err(e) = app_handle.emit(event_name, ()) {
+        crate::diagnostics::record_action(event_name);
+        // Send the action to the focused window only, so a second window
+        // doesn't also export or disconnect
+        let target = app_handle
+            .state::<AppState>()
+            .windows
+            .focused()
+            .unwrap_or_else(|| "main".to_string());
+        if let Err(e) = app_handle.emit_to(target.as_str(), event_name, ()) {
             eprintln!("Failed to emit menu event {}: {}", event_name, e);
         }
     });
@@ -0,0 +1,629 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::db::object_id;
+use crate::i18n::t;
+use crate::search_index::word_parts;
+use crate::types::{Column, SchemaGraph};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Casing {
+    #[default]
+    Unchanged,
+    /// `OrderLine`
+    Pascal,
+    /// `orderLine`
+    Camel,
+    /// `order_line`
+    Snake,
+    /// `ORDER_LINE`
+    UpperSnake,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Plurality {
+    #[default]
+    Unchanged,
+    Singular,
+    Plural,
+}
+
+/// Conventions to bring a design's names in line with. Prefixes are
+/// removed case-insensitively, only where a word boundary follows them.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NamingRules {
+    pub table_casing: Casing,
+    pub column_casing: Casing,
+    /// Applied to the last word of table and view names.
+    pub table_plurality: Plurality,
+    /// E.g. `tbl`, `t_`, `vw`.
+    pub table_prefixes: Vec<String>,
+    /// E.g. `col_`, `fld`.
+    pub column_prefixes: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RenamedKind {
+    Table,
+    View,
+    Column,
+}
+
+/// One rename of the preview; the same value selects it for applying.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NameChange {
+    pub kind: RenamedKind,
+    /// The table or view, by its ID before any rename.
+    pub object_id: String,
+    pub old_name: String,
+    pub new_name: String,
+}
+
+/// Several names that would become the same one, which SQL Server refuses.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NameConflict {
+    /// The schema for tables and views, the table ID for columns.
+    pub scope: String,
+    pub name: String,
+    pub old_names: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NamingPreview {
+    pub changes: Vec<NameChange>,
+    pub conflicts: Vec<NameConflict>,
+}
+
+/// `orders` and `order`, `categories` and `category`: close enough for
+/// table names, which are mostly English nouns.
+pub(crate) fn singular(word: &str) -> String {
+    if word.len() > 4 && word.ends_with("ies") {
+        format!("{}y", &word[..word.len() - 3])
+    } else if ["sses", "xes", "ches", "shes"]
+        .iter()
+        .any(|end| word.ends_with(end))
+    {
+        word[..word.len() - 2].to_string()
+    } else if word.len() > 3 && word.ends_with('s') && !word.ends_with("ss") {
+        word[..word.len() - 1].to_string()
+    } else {
+        word.to_string()
+    }
+}
+
+fn plural(word: &str) -> String {
+    // Already plural by the same rules
+    if singular(word) != word {
+        return word.to_string();
+    }
+    let consonant_y = word.len() > 1
+        && word.ends_with('y')
+        && !word[..word.len() - 1].ends_with(['a', 'e', 'i', 'o', 'u']);
+    if consonant_y {
+        format!("{}ies", &word[..word.len() - 1])
+    } else if ["s", "x", "z", "ch", "sh"]
+        .iter()
+        .any(|end| word.ends_with(end))
+    {
+        format!("{}es", word)
+    } else {
+        format!("{}s", word)
+    }
+}
+
+/// `name` with its last word inflected, keeping the case of what's there:
+/// `OrderLine` becomes `OrderLines`, `ORDER_LINE` becomes `ORDER_LINES`.
+fn inflect(name: &str, plurality: Plurality) -> String {
+    let inflected = |word: &str| match plurality {
+        Plurality::Unchanged => word.to_string(),
+        Plurality::Singular => singular(word),
+        Plurality::Plural => plural(word),
+    };
+    if plurality == Plurality::Unchanged || !name.is_ascii() {
+        return name.to_string();
+    }
+    let lower = name.to_lowercase();
+    let target = inflected(&lower);
+    let kept = lower
+        .bytes()
+        .zip(target.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let tail = &target[kept..];
+    let upper = name
+        .chars()
+        .rev()
+        .find(|c| c.is_alphabetic())
+        .is_some_and(char::is_uppercase);
+    format!(
+        "{}{}",
+        &name[..kept],
+        if upper {
+            tail.to_uppercase()
+        } else {
+            tail.to_string()
+        }
+    )
+}
+
+fn strip_prefix(name: &str, prefixes: &[String]) -> String {
+    for prefix in prefixes.iter().filter(|p| !p.is_empty()) {
+        let Some(head) = name.get(..prefix.len()) else {
+            continue;
+        };
+        let rest = &name[prefix.len()..];
+        let boundary = prefix.ends_with('_')
+            || rest.starts_with(|c: char| c == '_' || c.is_uppercase() || c.is_ascii_digit());
+        let rest = rest.trim_start_matches('_');
+        if head.eq_ignore_ascii_case(prefix) && boundary && !rest.is_empty() {
+            return rest.to_string();
+        }
+    }
+    name.to_string()
+}
+
+/// Words of an identifier, split at separators, underscores and humps.
+fn name_words(name: &str) -> Vec<String> {
+    name.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .flat_map(|segment| {
+            let chars: Vec<char> = segment.chars().collect();
+            word_parts(&chars)
+                .into_iter()
+                .map(|(s, e)| chars[s..e].iter().collect::<String>())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn apply_casing(name: &str, casing: Casing) -> String {
+    let capitalized = |word: &str| {
+        let lower = word.to_lowercase();
+        let mut chars = lower.chars();
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    };
+    let words = name_words(name);
+    if words.is_empty() {
+        return name.to_string();
+    }
+    match casing {
+        Casing::Unchanged => name.to_string(),
+        Casing::Pascal => words.iter().map(|w| capitalized(w)).collect(),
+        Casing::Camel => words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                if i == 0 {
+                    w.to_lowercase()
+                } else {
+                    capitalized(w)
+                }
+            })
+            .collect(),
+        Casing::Snake => words
+            .iter()
+            .map(|w| w.to_lowercase())
+            .collect::<Vec<_>>()
+            .join("_"),
+        Casing::UpperSnake => words
+            .iter()
+            .map(|w| w.to_uppercase())
+            .collect::<Vec<_>>()
+            .join("_"),
+    }
+}
+
+fn object_name(name: &str, rules: &NamingRules) -> String {
+    let stripped = strip_prefix(name, &rules.table_prefixes);
+    inflect(
+        &apply_casing(&stripped, rules.table_casing),
+        rules.table_plurality,
+    )
+}
+
+fn column_name(name: &str, rules: &NamingRules) -> String {
+    apply_casing(
+        &strip_prefix(name, &rules.column_prefixes),
+        rules.column_casing,
+    )
+}
+
+/// Names that collide case-insensitively within their scope once renamed;
+/// `names` holds (scope, old name, new name) for every name in the scope,
+/// renamed or not.
+fn conflicts(names: &[(String, String, String)]) -> Vec<NameConflict> {
+    let mut groups: HashMap<(String, String), Vec<&str>> = HashMap::new();
+    for (scope, old, new) in names {
+        groups
+            .entry((scope.to_lowercase(), new.to_lowercase()))
+            .or_default()
+            .push(old);
+    }
+    let mut found: Vec<NameConflict> = names
+        .iter()
+        .filter_map(|(scope, _, new)| {
+            let olds = groups.remove(&(scope.to_lowercase(), new.to_lowercase()))?;
+            (olds.len() > 1).then(|| NameConflict {
+                scope: scope.clone(),
+                name: new.clone(),
+                old_names: olds.into_iter().map(str::to_string).collect(),
+            })
+        })
+        .collect();
+    found.sort_by(|a, b| (&a.scope, &a.name).cmp(&(&b.scope, &b.name)));
+    found
+}
+
+fn objects(
+    graph: &SchemaGraph,
+) -> impl Iterator<Item = (RenamedKind, &str, &str, &str, &[Column])> {
+    let tables = graph.tables.iter().map(|t| {
+        (
+            RenamedKind::Table,
+            t.id.as_str(),
+            &*t.schema,
+            t.name.as_str(),
+            t.columns.as_slice(),
+        )
+    });
+    let views = graph.views.iter().map(|v| {
+        (
+            RenamedKind::View,
+            v.id.as_str(),
+            &*v.schema,
+            v.name.as_str(),
+            v.columns.as_slice(),
+        )
+    });
+    tables.chain(views)
+}
+
+/// Every table, view and column rename `rules` call for, and the names
+/// that would collide. Nothing in `graph` changes.
+pub fn preview_naming(graph: &SchemaGraph, rules: &NamingRules) -> NamingPreview {
+    let mut changes = Vec::new();
+    let mut object_names = Vec::new();
+    let mut column_names = Vec::new();
+    for (kind, id, schema, name, columns) in objects(graph) {
+        let new_name = object_name(name, rules);
+        object_names.push((schema.to_string(), name.to_string(), new_name.clone()));
+        if new_name != name {
+            changes.push(NameChange {
+                kind,
+                object_id: id.to_string(),
+                old_name: name.to_string(),
+                new_name,
+            });
+        }
+        for column in columns {
+            let new_name = column_name(&column.name, rules);
+            column_names.push((id.to_string(), column.name.clone(), new_name.clone()));
+            if new_name != column.name {
+                changes.push(NameChange {
+                    kind: RenamedKind::Column,
+                    object_id: id.to_string(),
+                    old_name: column.name.clone(),
+                    new_name,
+                });
+            }
+        }
+    }
+    let mut found = conflicts(&object_names);
+    found.extend(conflicts(&column_names));
+    NamingPreview {
+        changes,
+        conflicts: found,
+    }
+}
+
+fn rename_column(graph: &mut SchemaGraph, object_id: &str, old: &str, new: &str) -> bool {
+    let columns = graph
+        .tables
+        .iter_mut()
+        .find(|t| t.id == object_id)
+        .map(|t| &mut t.columns)
+        .or_else(|| {
+            graph
+                .views
+                .iter_mut()
+                .find(|v| v.id == object_id)
+                .map(|v| &mut v.columns)
+        });
+    let Some(column) = columns.and_then(|c| c.iter_mut().find(|c| c.name == old)) else {
+        return false;
+    };
+    column.name = new.to_string();
+
+    let matches = |table: &str, column: Option<&str>| {
+        table == object_id && column.is_some_and(|c| c.eq_ignore_ascii_case(old))
+    };
+    for r in &mut graph.relationships {
+        if matches(&r.from, r.from_column.as_deref()) {
+            r.from_column = Some(new.to_string());
+        }
+        if matches(&r.to, r.to_column.as_deref()) {
+            r.to_column = Some(new.to_string());
+        }
+    }
+    for link in &mut graph.links {
+        if matches(&link.from, link.from_column.as_deref()) {
+            link.from_column = Some(new.to_string());
+        }
+        if matches(&link.to, link.to_column.as_deref()) {
+            link.to_column = Some(new.to_string());
+        }
+    }
+    for c in &mut graph.classifications {
+        if matches(&c.object_id, c.column.as_deref()) {
+            c.column = Some(new.to_string());
+        }
+    }
+    for column in graph.views.iter_mut().flat_map(|v| v.columns.iter_mut()) {
+        for source in &mut column.source_columns {
+            if matches(&source.table, Some(&source.column)) {
+                source.column = new.to_string();
+            }
+        }
+        let from_renamed = column.source_table.as_deref() == Some(object_id);
+        if from_renamed && matches(object_id, column.source_column.as_deref()) {
+            column.source_column = Some(new.to_string());
+        }
+    }
+    true
+}
+
+/// Point every reference to the object `old` at `new`.
+fn rename_references(graph: &mut SchemaGraph, old: &str, new: &str) {
+    let replace = |id: &mut String| {
+        if id == old {
+            *id = new.to_string();
+        }
+    };
+    for r in &mut graph.relationships {
+        replace(&mut r.from);
+        replace(&mut r.to);
+    }
+    for link in &mut graph.links {
+        replace(&mut link.from);
+        replace(&mut link.to);
+    }
+    for trigger in &mut graph.triggers {
+        replace(&mut trigger.table_id);
+        trigger.referenced_tables.iter_mut().for_each(replace);
+        trigger.affected_tables.iter_mut().for_each(replace);
+    }
+    for procedure in &mut graph.stored_procedures {
+        procedure.referenced_tables.iter_mut().for_each(replace);
+        procedure.affected_tables.iter_mut().for_each(replace);
+    }
+    for function in &mut graph.scalar_functions {
+        function.referenced_tables.iter_mut().for_each(replace);
+        function.affected_tables.iter_mut().for_each(replace);
+    }
+    for view in &mut graph.views {
+        view.referenced_tables.iter_mut().for_each(replace);
+        for column in &mut view.columns {
+            column.source_table.iter_mut().for_each(replace);
+            for source in &mut column.source_columns {
+                replace(&mut source.table);
+            }
+        }
+    }
+    for c in &mut graph.classifications {
+        replace(&mut c.object_id);
+    }
+    for s in &mut graph.stewardship {
+        s.object_id.iter_mut().for_each(replace);
+    }
+}
+
+fn rename_object(
+    graph: &mut SchemaGraph,
+    kind: RenamedKind,
+    id: &str,
+    old: &str,
+    new: &str,
+) -> bool {
+    let found = match kind {
+        RenamedKind::Table => graph
+            .tables
+            .iter_mut()
+            .find(|t| t.id == id && t.name == old)
+            .map(|t| (&mut t.id, &mut t.name, t.schema.clone())),
+        RenamedKind::View => graph
+            .views
+            .iter_mut()
+            .find(|v| v.id == id && v.name == old)
+            .map(|v| (&mut v.id, &mut v.name, v.schema.clone())),
+        RenamedKind::Column => None,
+    };
+    let Some((object_id_field, name, schema)) = found else {
+        return false;
+    };
+    let new_id = object_id(&[&schema, new]);
+    *object_id_field = new_id.clone();
+    *name = new.to_string();
+    rename_references(graph, id, &new_id);
+    true
+}
+
+/// `graph` with the selected renames from [`preview_naming`] applied, and
+/// every relationship, reference, classification and stewardship entry
+/// following them. Fails without changing anything when a change no
+/// longer matches the design or the result would have duplicate names.
+pub fn apply_naming(graph: &SchemaGraph, changes: &[NameChange]) -> Result<SchemaGraph, String> {
+    let mut renamed = graph.clone();
+    // Columns first, while their tables still have the IDs the changes use
+    let (columns, objects): (Vec<&NameChange>, Vec<&NameChange>) =
+        changes.iter().partition(|c| c.kind == RenamedKind::Column);
+    for change in columns {
+        if !rename_column(
+            &mut renamed,
+            &change.object_id,
+            &change.old_name,
+            &change.new_name,
+        ) {
+            return Err(t!("error-naming-stale", name = change.old_name));
+        }
+    }
+    for change in objects {
+        if !rename_object(
+            &mut renamed,
+            change.kind,
+            &change.object_id,
+            &change.old_name,
+            &change.new_name,
+        ) {
+            return Err(t!("error-naming-stale", name = change.old_name));
+        }
+    }
+
+    let unchanged = NamingRules::default();
+    if let Some(conflict) = preview_naming(&renamed, &unchanged).conflicts.first() {
+        return Err(t!(
+            "error-naming-conflict",
+            name = conflict.name,
+            scope = conflict.scope
+        ));
+    }
+    Ok(renamed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{RelationshipEdge, TableNode};
+
+    fn table(name: &str, columns: &[&str]) -> TableNode {
+        TableNode {
+            id: format!("dbo.{}", name),
+            name: name.to_string(),
+            schema: "dbo".into(),
+            columns: columns
+                .iter()
+                .map(|name| Column {
+                    name: name.to_string(),
+                    data_type: "int".into(),
+                    is_nullable: false,
+                    is_primary_key: false,
+                    ordinal_position: 0,
+                    source_columns: Vec::new(),
+                    source_table: None,
+                    source_column: None,
+                    is_identity: false,
+                    is_computed: false,
+                })
+                .collect(),
+            source: None,
+            metadata: None,
+            change_capture: None,
+        }
+    }
+
+    fn legacy() -> SchemaGraph {
+        SchemaGraph {
+            tables: vec![
+                table("tblCustomer", &["CUST_ID", "cust_name"]),
+                table("tbl_order_line", &["ORDER_LINE_ID", "CUST_ID"]),
+            ],
+            relationships: vec![RelationshipEdge {
+                id: "FK_line_customer".to_string(),
+                from: "dbo.tbl_order_line".to_string(),
+                to: "dbo.tblCustomer".to_string(),
+                from_column: Some("CUST_ID".to_string()),
+                to_column: Some("CUST_ID".to_string()),
+                source: None,
+                is_virtual: false,
+                participation: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn rules() -> NamingRules {
+        NamingRules {
+            table_casing: Casing::Pascal,
+            column_casing: Casing::Pascal,
+            table_plurality: Plurality::Plural,
+            table_prefixes: vec!["tbl".to_string()],
+            column_prefixes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn rules_rename_prefixed_legacy_names() {
+        let preview = preview_naming(&legacy(), &rules());
+        let renames: Vec<(&str, &str)> = preview
+            .changes
+            .iter()
+            .map(|c| (c.old_name.as_str(), c.new_name.as_str()))
+            .collect();
+        assert_eq!(
+            renames,
+            vec![
+                ("tblCustomer", "Customers"),
+                ("CUST_ID", "CustId"),
+                ("cust_name", "CustName"),
+                ("tbl_order_line", "OrderLines"),
+                ("ORDER_LINE_ID", "OrderLineId"),
+                ("CUST_ID", "CustId"),
+            ]
+        );
+        assert!(preview.conflicts.is_empty());
+
+        assert_eq!(
+            inflect("ORDER_CATEGORY", Plurality::Plural),
+            "ORDER_CATEGORIES"
+        );
+        assert_eq!(inflect("Addresses", Plurality::Singular), "Address");
+        assert_eq!(strip_prefix("tblx", &["tbl".to_string()]), "tblx");
+    }
+
+    #[test]
+    fn selected_renames_carry_relationships_along() {
+        let graph = legacy();
+        let preview = preview_naming(&graph, &rules());
+        // Leave the customer columns as they are
+        let selected: Vec<NameChange> = preview
+            .changes
+            .into_iter()
+            .filter(|c| !(c.kind == RenamedKind::Column && c.object_id == "dbo.tblCustomer"))
+            .collect();
+
+        let renamed = apply_naming(&graph, &selected).unwrap();
+        let ids: Vec<&str> = renamed.tables.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["dbo.Customers", "dbo.OrderLines"]);
+        let fk = &renamed.relationships[0];
+        assert_eq!(
+            (fk.from.as_str(), fk.to.as_str()),
+            ("dbo.OrderLines", "dbo.Customers")
+        );
+        assert_eq!(fk.from_column.as_deref(), Some("CustId"));
+        assert_eq!(fk.to_column.as_deref(), Some("CUST_ID"));
+
+        // Applying the same preview twice finds nothing to rename
+        assert!(apply_naming(&renamed, &selected).is_err());
+    }
+
+    #[test]
+    fn colliding_names_are_reported_and_refused() {
+        let graph = SchemaGraph {
+            tables: vec![table("tblOrder", &[]), table("Orders", &[])],
+            ..Default::default()
+        };
+        let preview = preview_naming(&graph, &rules());
+        assert_eq!(preview.conflicts.len(), 1);
+        assert_eq!(preview.conflicts[0].name, "Orders");
+        assert_eq!(preview.conflicts[0].old_names, vec!["tblOrder", "Orders"]);
+        assert!(apply_naming(&graph, &preview.changes).is_err());
+    }
+}
@@ -0,0 +1,108 @@
+use crate::credentials::proxy_password;
+use crate::state::{ProxyMode, ProxySettings};
+use tauri::Url;
+
+/// Environment variables consulted for the system proxy, in priority order.
+const SYSTEM_PROXY_VARS: [&str; 6] = [
+    "HTTPS_PROXY",
+    "https_proxy",
+    "HTTP_PROXY",
+    "http_proxy",
+    "ALL_PROXY",
+    "all_proxy",
+];
+
+/// Resolve the proxy every outbound HTTP client in the backend should use.
+///
+/// `None` means connect directly. System mode reads the conventional proxy
+/// environment variables; manual mode uses the configured URL and credentials.
+pub fn resolve_proxy_url(proxy: Option<&ProxySettings>) -> Result<Option<Url>, String> {
+    let Some(proxy) = proxy else {
+        return Ok(None);
+    };
+
+    match proxy.mode {
+        ProxyMode::None => Ok(None),
+        ProxyMode::System => {
+            let configured = SYSTEM_PROXY_VARS
+                .iter()
+                .filter_map(|var| std::env::var(var).ok())
+                .find(|value| !value.trim().is_empty());
+            match configured {
+                Some(value) => parse_proxy_url(&value, proxy).map(Some),
+                None => Ok(None),
+            }
+        }
+        ProxyMode::Manual => {
+            let url = proxy
+                .url
+                .as_deref()
+                .filter(|u| !u.trim().is_empty())
+                .ok_or_else(|| "Manual proxy mode requires a proxy URL".to_string())?;
+            parse_proxy_url(url, proxy).map(Some)
+        }
+    }
+}
+
+fn parse_proxy_url(raw: &str, proxy: &ProxySettings) -> Result<Url, String> {
+    let raw = raw.trim();
+    // Proxy URLs are commonly written without a scheme ("proxy.corp:8080")
+    let with_scheme = if raw.contains("://") {
+        raw.to_string()
+    } else {
+        format!("http://{}", raw)
+    };
+
+    let mut url =
+        Url::parse(&with_scheme).map_err(|e| format!("Invalid proxy URL '{}': {}", raw, e))?;
+
+    if let Some(username) = proxy.username.as_deref().filter(|u| !u.is_empty()) {
+        url.set_username(username)
+            .map_err(|_| format!("Proxy URL '{}' cannot carry credentials", raw))?;
+        // Saved settings keep the password in the keychain
+        let password = proxy.password.clone().or_else(|| proxy_password(username));
+        url.set_password(password.as_deref())
+            .map_err(|_| format!("Proxy URL '{}' cannot carry credentials", raw))?;
+    }
+
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_proxy_settings_connects_directly() {
+        assert_eq!(resolve_proxy_url(None).unwrap(), None);
+
+        let settings = ProxySettings::default();
+        assert_eq!(resolve_proxy_url(Some(&settings)).unwrap(), None);
+    }
+
+    #[test]
+    fn manual_proxy_applies_credentials_and_default_scheme() {
+        let settings = ProxySettings {
+            mode: ProxyMode::Manual,
+            url: Some("proxy.corp.local:8080".to_string()),
+            username: Some("svc".to_string()),
+            password: Some("p@ss".to_string()),
+        };
+
+        let url = resolve_proxy_url(Some(&settings)).unwrap().unwrap();
+        assert_eq!(url.scheme(), "http");
+        assert_eq!(url.host_str(), Some("proxy.corp.local"));
+        assert_eq!(url.port(), Some(8080));
+        assert_eq!(url.username(), "svc");
+        assert_eq!(url.password(), Some("p%40ss"));
+    }
+
+    #[test]
+    fn manual_proxy_without_url_is_rejected() {
+        let settings = ProxySettings {
+            mode: ProxyMode::Manual,
+            ..Default::default()
+        };
+        assert!(resolve_proxy_url(Some(&settings)).is_err());
+    }
+}
@@ -0,0 +1,268 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::print::escape;
+use crate::types::{RelationshipEdge, SchemaGraph};
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum NetworkFormat {
+    /// GraphML, read by Gephi, yEd, NetworkX and igraph.
+    GraphMl,
+    /// Cytoscape's `elements` JSON, also read by Cytoscape.js.
+    CytoscapeJson,
+}
+
+struct Node<'a> {
+    id: &'a str,
+    label: &'a str,
+    schema: &'a str,
+    kind: &'static str,
+    column_count: usize,
+}
+
+struct Edge<'a> {
+    id: String,
+    source: &'a str,
+    target: &'a str,
+    kind: &'static str,
+    /// Constraint name for foreign keys.
+    label: &'a str,
+    columns: String,
+    is_virtual: bool,
+}
+
+/// Tables and views as nodes; foreign keys (one edge per constraint, so
+/// composite keys aren't counted twice) and view references as directed
+/// edges from the dependent object.
+fn topology(graph: &SchemaGraph) -> (Vec<Node<'_>>, Vec<Edge<'_>>) {
+    let mut nodes: Vec<Node> = graph
+        .tables
+        .iter()
+        .map(|t| Node {
+            id: &t.id,
+            label: &t.name,
+            schema: &t.schema,
+            kind: "table",
+            column_count: t.columns.len(),
+        })
+        .collect();
+    nodes.extend(graph.views.iter().map(|v| Node {
+        id: &v.id,
+        label: &v.name,
+        schema: &v.schema,
+        kind: "view",
+        column_count: v.columns.len(),
+    }));
+    let ids: HashSet<&str> = nodes.iter().map(|n| n.id).collect();
+
+    let mut keys: Vec<(&RelationshipEdge, Vec<&str>)> = Vec::new();
+    for edge in &graph.relationships {
+        if !ids.contains(edge.from.as_str()) || !ids.contains(edge.to.as_str()) {
+            continue;
+        }
+        let column = edge.from_column.as_deref();
+        match keys
+            .iter_mut()
+            .find(|(k, _)| k.id == edge.id && k.from == edge.from && k.to == edge.to)
+        {
+            Some((_, columns)) => columns.extend(column),
+            None => keys.push((edge, column.into_iter().collect())),
+        }
+    }
+    let mut edges: Vec<Edge> = keys
+        .into_iter()
+        .enumerate()
+        .map(|(i, (edge, columns))| Edge {
+            id: format!("fk{}", i),
+            source: &edge.from,
+            target: &edge.to,
+            kind: "foreignKey",
+            label: &edge.id,
+            columns: columns.join(","),
+            is_virtual: edge.is_virtual,
+        })
+        .collect();
+    for view in &graph.views {
+        for (i, referenced) in view
+            .referenced_tables
+            .iter()
+            .filter(|r| ids.contains(r.as_str()) && **r != view.id)
+            .enumerate()
+        {
+            edges.push(Edge {
+                id: format!("{}#ref{}", view.id, i),
+                source: &view.id,
+                target: referenced,
+                kind: "viewReference",
+                label: "",
+                columns: String::new(),
+                is_virtual: false,
+            });
+        }
+    }
+    (nodes, edges)
+}
+
+fn graph_ml(nodes: &[Node], edges: &[Edge]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\" \
+         xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+         xsi:schemaLocation=\"http://graphml.graphdrawing.org/xmlns \
+         http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd\">\n",
+    );
+    for (id, domain, name, kind) in [
+        ("label", "node", "label", "string"),
+        ("schema", "node", "schema", "string"),
+        ("kind", "node", "kind", "string"),
+        ("columnCount", "node", "columnCount", "int"),
+        ("edgeKind", "edge", "kind", "string"),
+        ("edgeLabel", "edge", "label", "string"),
+        ("columns", "edge", "columns", "string"),
+        ("isVirtual", "edge", "isVirtual", "boolean"),
+    ] {
+        let _ = writeln!(
+            out,
+            "  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"/>",
+            id, domain, name, kind
+        );
+    }
+    out.push_str("  <graph id=\"schema\" edgedefault=\"directed\">\n");
+    for node in nodes {
+        let _ = writeln!(out, "    <node id=\"{}\">", escape(node.id));
+        let _ = writeln!(
+            out,
+            "      <data key=\"label\">{}</data>",
+            escape(node.label)
+        );
+        let _ = writeln!(
+            out,
+            "      <data key=\"schema\">{}</data>",
+            escape(node.schema)
+        );
+        let _ = writeln!(out, "      <data key=\"kind\">{}</data>", node.kind);
+        let _ = writeln!(
+            out,
+            "      <data key=\"columnCount\">{}</data>",
+            node.column_count
+        );
+        out.push_str("    </node>\n");
+    }
+    for edge in edges {
+        let _ = writeln!(
+            out,
+            "    <edge id=\"{}\" source=\"{}\" target=\"{}\">",
+            escape(&edge.id),
+            escape(edge.source),
+            escape(edge.target)
+        );
+        let _ = writeln!(out, "      <data key=\"edgeKind\">{}</data>", edge.kind);
+        if !edge.label.is_empty() {
+            let _ = writeln!(
+                out,
+                "      <data key=\"edgeLabel\">{}</data>",
+                escape(edge.label)
+            );
+        }
+        if !edge.columns.is_empty() {
+            let _ = writeln!(
+                out,
+                "      <data key=\"columns\">{}</data>",
+                escape(&edge.columns)
+            );
+        }
+        let _ = writeln!(
+            out,
+            "      <data key=\"isVirtual\">{}</data>",
+            edge.is_virtual
+        );
+        out.push_str("    </edge>\n");
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+fn cytoscape_json(nodes: &[Node], edges: &[Edge]) -> String {
+    let nodes: Vec<Value> = nodes
+        .iter()
+        .map(|n| {
+            json!({ "data": {
+                "id": n.id,
+                "label": n.label,
+                "schema": n.schema,
+                "kind": n.kind,
+                "columnCount": n.column_count,
+            }})
+        })
+        .collect();
+    let edges: Vec<Value> = edges
+        .iter()
+        .map(|e| {
+            json!({ "data": {
+                "id": e.id,
+                "source": e.source,
+                "target": e.target,
+                "kind": e.kind,
+                "label": e.label,
+                "columns": e.columns,
+                "isVirtual": e.is_virtual,
+            }})
+        })
+        .collect();
+    let document = json!({
+        "format_version": "1.0",
+        "generated_by": "Monocle",
+        "target_cytoscapejs_version": "~3",
+        "data": { "name": "schema" },
+        "elements": { "nodes": nodes, "edges": edges },
+    });
+    serde_json::to_string_pretty(&document).unwrap_or_default()
+}
+
+/// The relationship graph of `graph` in a format network analysis tools
+/// import.
+pub fn export_network(graph: &SchemaGraph, format: NetworkFormat) -> String {
+    let (nodes, edges) = topology(graph);
+    match format {
+        NetworkFormat::GraphMl => graph_ml(&nodes, &edges),
+        NetworkFormat::CytoscapeJson => cytoscape_json(&nodes, &edges),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+
+    #[test]
+    fn both_formats_carry_every_node_and_constraint() {
+        let graph = generate_mock_schema("small");
+        let (nodes, edges) = topology(&graph);
+        assert_eq!(nodes.len(), graph.tables.len() + graph.views.len());
+
+        let xml = export_network(&graph, NetworkFormat::GraphMl);
+        let document = roxmltree::Document::parse(&xml).expect("well-formed GraphML");
+        let count = |tag: &str| {
+            document
+                .descendants()
+                .filter(|n| n.has_tag_name(tag))
+                .count()
+        };
+        assert_eq!(count("node"), nodes.len());
+        assert_eq!(count("edge"), edges.len());
+
+        let json: Value =
+            serde_json::from_str(&export_network(&graph, NetworkFormat::CytoscapeJson)).unwrap();
+        let elements = &json["elements"];
+        assert_eq!(elements["nodes"].as_array().unwrap().len(), nodes.len());
+        assert_eq!(elements["edges"].as_array().unwrap().len(), edges.len());
+        assert!(elements["edges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|e| e["data"]["kind"] == "foreignKey"));
+    }
+}
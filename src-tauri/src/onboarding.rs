@@ -0,0 +1,257 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::annotations::{set_annotation, Annotation};
+use crate::commands::mock::generate_mock_schema;
+use crate::graph_cache::store_graph;
+use crate::i18n::t;
+use crate::layouts::{load_layout, save_layout, DiagramLayout, NodePosition};
+use crate::types::SchemaGraph;
+
+/// Connection the sample workspace is stored under; no server is contacted.
+pub const SAMPLE_SERVER: &str = "monocle-sample";
+pub const SAMPLE_DATABASE: &str = "Sample";
+pub const SAMPLE_LAYOUT: &str = "Tour";
+
+/// Horizontal and vertical distance between nodes in the sample layout.
+const GRID_SPACING: (f64, f64) = (320.0, 240.0);
+/// Tables given a sample annotation, most referenced first.
+const ANNOTATED_TABLES: usize = 2;
+
+/// Stops of the guided tour, in order.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum OnboardingStep {
+    Welcome,
+    SampleWorkspace,
+    Canvas,
+    Search,
+    Connect,
+}
+
+impl OnboardingStep {
+    const ORDER: [OnboardingStep; 5] = [
+        OnboardingStep::Welcome,
+        OnboardingStep::SampleWorkspace,
+        OnboardingStep::Canvas,
+        OnboardingStep::Search,
+        OnboardingStep::Connect,
+    ];
+
+    fn next(self) -> Option<OnboardingStep> {
+        let position = Self::ORDER.iter().position(|s| *s == self)?;
+        Self::ORDER.get(position + 1).copied()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum OnboardingState {
+    InProgress { step: OnboardingStep },
+    Completed,
+    Skipped,
+}
+
+impl Default for OnboardingState {
+    fn default() -> Self {
+        OnboardingState::InProgress {
+            step: OnboardingStep::Welcome,
+        }
+    }
+}
+
+impl OnboardingState {
+    /// Move past `from`. Repeating an advance the tour has already made
+    /// changes nothing, so a double-clicked "Next" can't skip a step.
+    pub fn advance(self, from: OnboardingStep) -> OnboardingState {
+        match self {
+            OnboardingState::InProgress { step } if step == from => match step.next() {
+                Some(step) => OnboardingState::InProgress { step },
+                None => OnboardingState::Completed,
+            },
+            other => other,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStatus {
+    /// No settings existed when the app started.
+    pub first_run: bool,
+    pub state: OnboardingState,
+}
+
+/// The demo schema with a saved layout and annotations, ready to open.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SampleWorkspace {
+    pub server: String,
+    pub database: String,
+    pub graph: SchemaGraph,
+    pub layout: DiagramLayout,
+    pub annotations: Vec<Annotation>,
+}
+
+/// Tables, then views, in a square grid.
+fn grid_positions(graph: &SchemaGraph) -> BTreeMap<String, NodePosition> {
+    let ids: Vec<&String> = graph
+        .tables
+        .iter()
+        .map(|t| &t.id)
+        .chain(graph.views.iter().map(|v| &v.id))
+        .collect();
+    let columns = (ids.len() as f64).sqrt().ceil().max(1.0) as usize;
+    ids.into_iter()
+        .enumerate()
+        .map(|(i, id)| {
+            let position = NodePosition {
+                x: (i % columns) as f64 * GRID_SPACING.0,
+                y: (i / columns) as f64 * GRID_SPACING.1,
+            };
+            (id.clone(), position)
+        })
+        .collect()
+}
+
+/// Descriptions for the most referenced tables, one of their keys, and the
+/// first view, so the tour has annotations to point at.
+fn sample_annotations(graph: &SchemaGraph) -> Vec<(String, Option<String>, String)> {
+    let mut references: Vec<(usize, &str)> = graph
+        .tables
+        .iter()
+        .map(|table| {
+            let count = graph
+                .relationships
+                .iter()
+                .filter(|r| r.to == table.id && r.from != table.id)
+                .count();
+            (count, table.id.as_str())
+        })
+        .collect();
+    references.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+
+    let mut annotations = Vec::new();
+    for (count, id) in references.into_iter().take(ANNOTATED_TABLES) {
+        annotations.push((
+            id.to_string(),
+            None,
+            t!("onboarding-sample-table", count = count),
+        ));
+        let key = graph
+            .tables
+            .iter()
+            .find(|t| t.id == id)
+            .and_then(|t| t.columns.iter().find(|c| c.is_primary_key));
+        if let Some(key) = key {
+            annotations.push((
+                id.to_string(),
+                Some(key.name.clone()),
+                t!("onboarding-sample-key"),
+            ));
+        }
+    }
+    if let Some(view) = graph.views.first() {
+        annotations.push((
+            view.id.clone(),
+            None,
+            t!(
+                "onboarding-sample-view",
+                count = view.referenced_tables.len()
+            ),
+        ));
+    }
+    annotations
+}
+
+/// Store the demo schema under the sample connection with a layout and
+/// annotations. Running it again replaces them.
+pub fn create_sample_workspace(storage_path: &Path) -> Result<SampleWorkspace, String> {
+    let mut graph = generate_mock_schema("small");
+    store_graph(storage_path, SAMPLE_SERVER, SAMPLE_DATABASE, &mut graph)?;
+
+    let layout = DiagramLayout {
+        name: SAMPLE_LAYOUT.to_string(),
+        positions: grid_positions(&graph),
+        collapsed_groups: Vec::new(),
+        viewport: None,
+        saved_at: String::new(),
+    };
+    save_layout(storage_path, SAMPLE_SERVER, SAMPLE_DATABASE, layout)?;
+
+    let mut annotations = Vec::new();
+    for (object_id, column, description) in sample_annotations(&graph) {
+        annotations = set_annotation(
+            storage_path,
+            SAMPLE_SERVER,
+            SAMPLE_DATABASE,
+            &object_id,
+            column,
+            &description,
+        )?;
+    }
+
+    Ok(SampleWorkspace {
+        server: SAMPLE_SERVER.to_string(),
+        database: SAMPLE_DATABASE.to_string(),
+        layout: load_layout(storage_path, SAMPLE_SERVER, SAMPLE_DATABASE, SAMPLE_LAYOUT)?,
+        graph,
+        annotations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph_cache::read_cached_graph;
+    use tempfile::tempdir;
+
+    #[test]
+    fn advancing_is_idempotent_and_ends_completed() {
+        let mut state = OnboardingState::default();
+        state = state.advance(OnboardingStep::Welcome);
+        // A repeated click on the first step's button
+        state = state.advance(OnboardingStep::Welcome);
+        assert_eq!(
+            state,
+            OnboardingState::InProgress {
+                step: OnboardingStep::SampleWorkspace
+            }
+        );
+
+        for step in &OnboardingStep::ORDER[1..] {
+            state = state.advance(*step);
+        }
+        assert_eq!(state, OnboardingState::Completed);
+        assert_eq!(
+            OnboardingState::Skipped.advance(OnboardingStep::Welcome),
+            OnboardingState::Skipped
+        );
+    }
+
+    #[test]
+    fn sample_workspace_is_stored_with_layout_and_annotations() {
+        let dir = tempdir().expect("tempdir");
+        let sample = create_sample_workspace(dir.path()).unwrap();
+
+        let cached = read_cached_graph(dir.path(), SAMPLE_SERVER, SAMPLE_DATABASE).unwrap();
+        assert_eq!(cached.graph.tables.len(), sample.graph.tables.len());
+        assert_eq!(
+            sample.layout.positions.len(),
+            sample.graph.tables.len() + sample.graph.views.len()
+        );
+        assert!(sample
+            .annotations
+            .iter()
+            .any(|a| a.column.is_some() && a.object_id == sample.annotations[0].object_id));
+        assert!(sample
+            .annotations
+            .iter()
+            .any(|a| a.object_id == sample.graph.views[0].id));
+
+        // Provisioning again replaces rather than duplicates
+        let again = create_sample_workspace(dir.path()).unwrap();
+        assert_eq!(again.annotations.len(), sample.annotations.len());
+    }
+}
@@ -0,0 +1,343 @@
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+use crate::types::{Column, RelationshipEdge, SchemaGraph};
+
+const PRODUCER: &str = "https://github.com/elliotlayen/Monocle";
+const SPEC: &str = "https://openlineage.io/spec/2-0-2/OpenLineage.json";
+const SCHEMA_FACET: &str =
+    "https://openlineage.io/spec/facets/1-1-1/SchemaDatasetFacet.json#/$defs/SchemaDatasetFacet";
+const COLUMN_LINEAGE_FACET: &str = "https://openlineage.io/spec/facets/1-2-0/ColumnLineageDatasetFacet.json#/$defs/ColumnLineageDatasetFacet";
+const SQL_FACET: &str =
+    "https://openlineage.io/spec/facets/1-1-0/SQLJobFacet.json#/$defs/SQLJobFacet";
+const JOB_TYPE_FACET: &str =
+    "https://openlineage.io/spec/facets/2-0-3/JobTypeJobFacet.json#/$defs/JobTypeJobFacet";
+/// OpenLineage has no facet for foreign keys, so they travel as a custom
+/// one, prefixed with the producer's name as the spec asks.
+const FOREIGN_KEYS_FACET: &str = "monocle_foreignKeys";
+
+/// Dataset namespace for a SQL Server, `mssql://host:port` per the
+/// OpenLineage naming conventions. A `host,port` server name gives its
+/// port; a named instance keeps its name since its port isn't known.
+pub fn namespace_for(server: &str) -> String {
+    let server = server.trim().trim_start_matches("tcp:");
+    let authority = match server.split_once(',') {
+        Some((host, port)) => format!("{}:{}", host.trim(), port.trim()),
+        None if server.contains('\\') => server.replace('\\', "/"),
+        None => format!("{}:1433", server),
+    };
+    format!("mssql://{}", authority)
+}
+
+/// A run id that stays the same while a job's definition does, so sending
+/// an export twice updates the catalog's run instead of adding one.
+fn run_id(namespace: &str, job: &str, definition: &str) -> String {
+    // FNV-1a rather than the std hasher, whose output may change between
+    // Rust releases
+    let half = |seed: u8| {
+        let parts = [namespace.as_bytes(), job.as_bytes(), definition.as_bytes()];
+        parts
+            .iter()
+            .flat_map(|part| part.iter().chain(&[0]))
+            .fold(0xcbf2_9ce4_8422_2325_u64 ^ u64::from(seed), |hash, byte| {
+                (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+            })
+    };
+    let (high, low) = (half(0), half(1));
+    // Shaped as a version 4 UUID, which consumers validate
+    let high = (high & !0xf000) | 0x4000;
+    let low = (low & !(0b11 << 62)) | (0b10 << 62);
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        high >> 32,
+        (high >> 16) & 0xffff,
+        high & 0xffff,
+        low >> 48,
+        low & 0xffff_ffff_ffff
+    )
+}
+
+fn facet(schema_url: &str, fields: Value) -> Value {
+    let mut facet = json!({ "_producer": PRODUCER, "_schemaURL": schema_url });
+    if let (Some(facet), Value::Object(fields)) = (facet.as_object_mut(), fields) {
+        facet.extend(fields);
+    }
+    facet
+}
+
+struct Exporter<'a> {
+    graph: &'a SchemaGraph,
+    namespace: String,
+    database: &'a str,
+    event_time: &'a str,
+    /// Foreign keys by referencing table, one entry per column pair.
+    foreign_keys: BTreeMap<&'a str, Vec<&'a RelationshipEdge>>,
+}
+
+impl<'a> Exporter<'a> {
+    fn dataset_name(&self, id: &str) -> String {
+        format!("{}.{}", self.database, id)
+    }
+
+    fn columns_of(&self, id: &str) -> Option<&'a [Column]> {
+        let graph = self.graph;
+        graph
+            .tables
+            .iter()
+            .find(|t| t.id == id)
+            .map(|t| t.columns.as_slice())
+            .or_else(|| {
+                graph
+                    .views
+                    .iter()
+                    .find(|v| v.id == id)
+                    .map(|v| v.columns.as_slice())
+            })
+    }
+
+    fn foreign_keys_facet(&self, id: &str) -> Option<Value> {
+        let keys = self.foreign_keys.get(id)?;
+        let keys: Vec<Value> = keys
+            .iter()
+            .map(|edge| {
+                json!({
+                    "constraint": edge.id,
+                    "column": edge.from_column,
+                    "references": {
+                        "namespace": self.namespace,
+                        "name": self.dataset_name(&edge.to),
+                        "field": edge.to_column,
+                    },
+                    "virtual": edge.is_virtual,
+                })
+            })
+            .collect();
+        Some(facet(
+            &format!("{}#/$defs/ForeignKeysDatasetFacet", PRODUCER),
+            json!({ "foreignKeys": keys }),
+        ))
+    }
+
+    /// A dataset reference with its columns and foreign keys, when the
+    /// graph has them.
+    fn dataset(&self, id: &str) -> Value {
+        let mut facets = Map::new();
+        if let Some(columns) = self.columns_of(id) {
+            let fields: Vec<Value> = columns
+                .iter()
+                .map(|c| json!({ "name": c.name, "type": &*c.data_type }))
+                .collect();
+            facets.insert(
+                "schema".to_string(),
+                facet(SCHEMA_FACET, json!({ "fields": fields })),
+            );
+        }
+        if let Some(keys) = self.foreign_keys_facet(id) {
+            facets.insert(FOREIGN_KEYS_FACET.to_string(), keys);
+        }
+        json!({
+            "namespace": self.namespace,
+            "name": self.dataset_name(id),
+            "facets": facets,
+        })
+    }
+
+    fn column_lineage(&self, columns: &[Column]) -> Option<Value> {
+        let mut fields = Map::new();
+        for column in columns {
+            let mut sources: Vec<(&str, &str)> = column
+                .source_columns
+                .iter()
+                .map(|s| (s.table.as_str(), s.column.as_str()))
+                .collect();
+            if let (true, Some(table), Some(source)) = (
+                sources.is_empty(),
+                column.source_table.as_deref(),
+                column.source_column.as_deref(),
+            ) {
+                sources.push((table, source));
+            }
+            if sources.is_empty() {
+                continue;
+            }
+            let inputs: Vec<Value> = sources
+                .into_iter()
+                .map(|(table, field)| {
+                    json!({
+                        "namespace": self.namespace,
+                        "name": self.dataset_name(table),
+                        "field": field,
+                    })
+                })
+                .collect();
+            fields.insert(column.name.clone(), json!({ "inputFields": inputs }));
+        }
+        (!fields.is_empty()).then(|| facet(COLUMN_LINEAGE_FACET, json!({ "fields": fields })))
+    }
+
+    /// A completed run of `id`, reading `inputs` and writing `outputs`.
+    fn run_event(
+        &self,
+        id: &str,
+        job_type: &str,
+        definition: &str,
+        inputs: Vec<Value>,
+        outputs: Vec<Value>,
+    ) -> Value {
+        let job = self.dataset_name(id);
+        json!({
+            "eventType": "COMPLETE",
+            "eventTime": self.event_time,
+            "producer": PRODUCER,
+            "schemaURL": format!("{}#/$defs/RunEvent", SPEC),
+            "run": { "runId": run_id(&self.namespace, &job, definition) },
+            "job": {
+                "namespace": self.namespace,
+                "name": job,
+                "facets": {
+                    "jobType": facet(JOB_TYPE_FACET, json!({
+                        "processingType": "BATCH",
+                        "integration": "MSSQL",
+                        "jobType": job_type,
+                    })),
+                    "sql": facet(SQL_FACET, json!({ "query": definition })),
+                },
+            },
+            "inputs": inputs,
+            "outputs": outputs,
+        })
+    }
+}
+
+/// Lineage in `graph` as newline-delimited OpenLineage events, the layout
+/// of OpenLineage's file transport; each line can also be posted on its own
+/// to a Marquez or other catalog's lineage endpoint.
+///
+/// Views and procedures become runs of a job named after the object: a
+/// view reads its referenced objects and writes itself, with column lineage
+/// where sources are known; a procedure reads its referenced tables and
+/// writes the tables it changes. Each table is also sent as a dataset event
+/// carrying its columns and foreign keys.
+pub fn export_openlineage(
+    graph: &SchemaGraph,
+    server: &str,
+    database: &str,
+    event_time: &str,
+) -> String {
+    let mut foreign_keys: BTreeMap<&str, Vec<&RelationshipEdge>> = BTreeMap::new();
+    for edge in &graph.relationships {
+        foreign_keys
+            .entry(edge.from.as_str())
+            .or_default()
+            .push(edge);
+    }
+    let exporter = Exporter {
+        graph,
+        namespace: namespace_for(server),
+        database,
+        event_time,
+        foreign_keys,
+    };
+
+    let mut events = Vec::new();
+    for table in &graph.tables {
+        events.push(json!({
+            "eventTime": event_time,
+            "producer": PRODUCER,
+            "schemaURL": format!("{}#/$defs/DatasetEvent", SPEC),
+            "dataset": exporter.dataset(&table.id),
+        }));
+    }
+    for view in &graph.views {
+        let inputs = view
+            .referenced_tables
+            .iter()
+            .filter(|r| **r != view.id)
+            .map(|r| exporter.dataset(r))
+            .collect();
+        let mut output = exporter.dataset(&view.id);
+        if let (Some(lineage), Some(facets)) = (
+            exporter.column_lineage(&view.columns),
+            output["facets"].as_object_mut(),
+        ) {
+            facets.insert("columnLineage".to_string(), lineage);
+        }
+        events.push(exporter.run_event(&view.id, "VIEW", &view.definition, inputs, vec![output]));
+    }
+    for procedure in &graph.stored_procedures {
+        if procedure.referenced_tables.is_empty() && procedure.affected_tables.is_empty() {
+            continue;
+        }
+        let datasets = |ids: &[String]| ids.iter().map(|id| exporter.dataset(id)).collect();
+        events.push(exporter.run_event(
+            &procedure.id,
+            "STORED_PROCEDURE",
+            &procedure.definition,
+            datasets(&procedure.referenced_tables),
+            datasets(&procedure.affected_tables),
+        ));
+    }
+
+    let mut out = String::new();
+    for event in events {
+        out.push_str(&event.to_string());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+
+    #[test]
+    fn events_carry_lineage_and_keys() {
+        let graph = generate_mock_schema("small");
+        let out = export_openlineage(&graph, "sql01,14330", "Sales", "2026-01-01T00:00:00Z");
+        let events: Vec<Value> = out
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("one JSON event per line"))
+            .collect();
+
+        let datasets = events.iter().filter(|e| e.get("dataset").is_some()).count();
+        assert_eq!(datasets, graph.tables.len());
+
+        let view = &graph.views[0];
+        let run = events
+            .iter()
+            .find(|e| e["job"]["name"] == format!("Sales.{}", view.id))
+            .expect("a run for the view");
+        assert_eq!(run["outputs"][0]["namespace"], "mssql://sql01:14330");
+        assert_eq!(
+            run["inputs"].as_array().unwrap().len(),
+            view.referenced_tables.len()
+        );
+        let run_id = run["run"]["runId"].as_str().unwrap();
+        assert_eq!(run_id.len(), 36);
+        assert_eq!(&run_id[14..15], "4");
+
+        let edge = &graph.relationships[0];
+        let keyed = events
+            .iter()
+            .find(|e| e["dataset"]["name"] == format!("Sales.{}", edge.from))
+            .expect("the referencing table");
+        let keys = keyed["dataset"]["facets"][FOREIGN_KEYS_FACET]["foreignKeys"]
+            .as_array()
+            .unwrap();
+        assert!(keys
+            .iter()
+            .any(|k| k["references"]["name"] == format!("Sales.{}", edge.to)));
+
+        // Re-exporting keeps run ids, so a catalog updates rather than duplicates
+        let again = export_openlineage(&graph, "sql01,14330", "Sales", "2026-02-01T00:00:00Z");
+        assert!(again.contains(run_id));
+    }
+
+    #[test]
+    fn namespaces_follow_the_sql_server_convention() {
+        assert_eq!(namespace_for("sql01"), "mssql://sql01:1433");
+        assert_eq!(namespace_for("tcp:sql01, 1500"), "mssql://sql01:1500");
+        assert_eq!(namespace_for("sql01\\REPORTING"), "mssql://sql01/REPORTING");
+    }
+}
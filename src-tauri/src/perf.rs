@@ -0,0 +1,261 @@
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Number of command invocations retained for `get_performance_stats_cmd`.
+const TIMING_CAPACITY: usize = 500;
+
+static TIMINGS: Lazy<Mutex<VecDeque<CommandTiming>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(TIMING_CAPACITY)));
+
+/// Sizing a JSON response means serializing it a second time, so it is only
+/// done while payload measurement is switched on. Binary payloads are always
+/// sized, since their length is known.
+static MEASURE_PAYLOADS: AtomicBool = AtomicBool::new(false);
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandTiming {
+    pub command: String,
+    pub started_at: String,
+    pub duration_ms: f64,
+    pub response_bytes: Option<u64>,
+    pub ok: bool,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandSummary {
+    pub command: String,
+    pub count: u32,
+    pub errors: u32,
+    pub avg_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+    pub max_response_bytes: u64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceStats {
+    pub summaries: Vec<CommandSummary>,
+    pub recent: Vec<CommandTiming>,
+}
+
+/// Serializes into nothing, counting the bytes the IPC layer would send.
+struct ByteCounter(u64);
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn counted_size<T: Serialize>(value: &T) -> Option<u64> {
+    let mut counter = ByteCounter(0);
+    serde_json::to_writer(&mut counter, value).ok()?;
+    Some(counter.0)
+}
+
+fn serialized_size<T: Serialize>(value: &T) -> Option<u64> {
+    if !MEASURE_PAYLOADS.load(Ordering::Relaxed) {
+        return None;
+    }
+    counted_size(value)
+}
+
+pub fn set_payload_measurement(enabled: bool) {
+    MEASURE_PAYLOADS.store(enabled, Ordering::Relaxed);
+}
+
+fn push_timing(timing: CommandTiming) {
+    if let Ok(mut timings) = TIMINGS.lock() {
+        if timings.len() == TIMING_CAPACITY {
+            timings.pop_front();
+        }
+        timings.push_back(timing);
+    }
+}
+
+/// Times a single command invocation.
+///
+/// Call `finish` with the command's result to record duration and, while
+/// payload measurement is on, response size. A timer dropped without
+/// finishing (an early `?` return) is recorded as a failed invocation.
+pub struct CommandTimer {
+    command: &'static str,
+    started_at: String,
+    start: Instant,
+    finished: bool,
+}
+
+impl CommandTimer {
+    pub fn start(command: &'static str) -> Self {
+        Self {
+            command,
+            started_at: Utc::now().to_rfc3339(),
+            start: Instant::now(),
+            finished: false,
+        }
+    }
+
+    fn record(&mut self, ok: bool, response_bytes: Option<u64>) {
+        self.finished = true;
+        push_timing(CommandTiming {
+            command: self.command.to_string(),
+            started_at: std::mem::take(&mut self.started_at),
+            duration_ms: self.start.elapsed().as_secs_f64() * 1000.0,
+            response_bytes,
+            ok,
+        });
+    }
+
+    pub fn finish<T: Serialize, E>(mut self, result: Result<T, E>) -> Result<T, E> {
+        let bytes = result.as_ref().ok().and_then(serialized_size);
+        self.record(result.is_ok(), bytes);
+        result
+    }
+
+    /// Finish a command that returns a pre-encoded (binary) payload.
+    pub fn finish_bytes<E>(mut self, result: Result<Vec<u8>, E>) -> Result<Vec<u8>, E> {
+        let bytes = result.as_ref().ok().map(|b| b.len() as u64);
+        self.record(result.is_ok(), bytes);
+        result
+    }
+
+    pub fn finish_value<T: Serialize>(mut self, value: T) -> T {
+        let bytes = serialized_size(&value);
+        self.record(true, bytes);
+        value
+    }
+}
+
+impl Drop for CommandTimer {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.record(false, None);
+        }
+    }
+}
+
+fn summarize(timings: &[CommandTiming]) -> Vec<CommandSummary> {
+    let mut grouped: BTreeMap<&str, Vec<&CommandTiming>> = BTreeMap::new();
+    for timing in timings {
+        grouped.entry(&timing.command).or_default().push(timing);
+    }
+
+    grouped
+        .into_iter()
+        .map(|(command, entries)| {
+            let mut durations: Vec<f64> = entries.iter().map(|t| t.duration_ms).collect();
+            durations.sort_by(|a, b| a.total_cmp(b));
+            let count = durations.len();
+            let p95_index = ((count as f64 * 0.95).ceil() as usize).clamp(1, count) - 1;
+
+            CommandSummary {
+                command: command.to_string(),
+                count: count.min(u32::MAX as usize) as u32,
+                errors: entries.iter().filter(|t| !t.ok).count() as u32,
+                avg_ms: durations.iter().sum::<f64>() / count as f64,
+                p95_ms: durations[p95_index],
+                max_ms: durations[count - 1],
+                max_response_bytes: entries
+                    .iter()
+                    .filter_map(|t| t.response_bytes)
+                    .max()
+                    .unwrap_or(0),
+            }
+        })
+        .collect()
+}
+
+pub fn performance_stats() -> PerformanceStats {
+    let recent: Vec<CommandTiming> = TIMINGS
+        .lock()
+        .map(|timings| timings.iter().cloned().collect())
+        .unwrap_or_default();
+
+    PerformanceStats {
+        summaries: summarize(&recent),
+        recent,
+    }
+}
+
+pub fn clear_performance_stats() {
+    if let Ok(mut timings) = TIMINGS.lock() {
+        timings.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(command: &str, duration_ms: f64, ok: bool, bytes: Option<u64>) -> CommandTiming {
+        CommandTiming {
+            command: command.to_string(),
+            started_at: String::new(),
+            duration_ms,
+            response_bytes: bytes,
+            ok,
+        }
+    }
+
+    #[test]
+    fn serialized_size_matches_json_length() {
+        let value = vec!["alpha", "beta"];
+        let expected = serde_json::to_vec(&value).unwrap().len() as u64;
+        assert_eq!(counted_size(&value), Some(expected));
+    }
+
+    #[test]
+    fn summaries_group_by_command() {
+        let mut timings: Vec<CommandTiming> = (1..=20)
+            .map(|i| timing("load_schema_cmd", i as f64, true, Some(i * 100)))
+            .collect();
+        timings.push(timing("get_settings", 2.0, false, None));
+
+        let summaries = summarize(&timings);
+        assert_eq!(summaries.len(), 2);
+
+        let settings = &summaries[0];
+        assert_eq!(settings.command, "get_settings");
+        assert_eq!(settings.errors, 1);
+
+        let load = &summaries[1];
+        assert_eq!(load.count, 20);
+        assert_eq!(load.avg_ms, 10.5);
+        assert_eq!(load.p95_ms, 19.0);
+        assert_eq!(load.max_ms, 20.0);
+        assert_eq!(load.max_response_bytes, 2000);
+    }
+
+    #[test]
+    fn dropped_timer_records_failure() {
+        fn failing() -> Result<u32, String> {
+            let timer = CommandTimer::start("perf_test_failing_cmd");
+            let value: u32 = "nope".parse().map_err(|_| "parse failed".to_string())?;
+            timer.finish(Ok(value))
+        }
+
+        assert!(failing().is_err());
+        let stats = performance_stats();
+        let entry = stats
+            .recent
+            .iter()
+            .rev()
+            .find(|t| t.command == "perf_test_failing_cmd")
+            .expect("timing recorded");
+        assert!(!entry.ok);
+    }
+}
@@ -0,0 +1,485 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+use crate::export_annotations::ExportAnnotations;
+use crate::export_themes::ExportTheme;
+use crate::layouts::DiagramLayout;
+use crate::types::{Column, SchemaGraph};
+
+/// Canvas pixels per millimetre (96 dpi).
+const PX_PER_MM: f64 = 96.0 / 25.4;
+
+/// Node box sizes, matching the canvas defaults closely enough to paginate.
+const NODE_WIDTH: f64 = 240.0;
+const HEADER_HEIGHT: f64 = 36.0;
+const ROW_HEIGHT: f64 = 22.0;
+
+/// Space kept under the diagram for page numbers and the legend.
+const FOOTER_MM: f64 = 12.0;
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum PaperSize {
+    #[default]
+    A4,
+    A3,
+    Letter,
+    Legal,
+    Tabloid,
+}
+
+impl PaperSize {
+    /// Portrait width and height in millimetres.
+    fn dimensions_mm(self) -> (f64, f64) {
+        match self {
+            PaperSize::A4 => (210.0, 297.0),
+            PaperSize::A3 => (297.0, 420.0),
+            PaperSize::Letter => (215.9, 279.4),
+            PaperSize::Legal => (215.9, 355.6),
+            PaperSize::Tabloid => (279.4, 431.8),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Orientation {
+    Portrait,
+    #[default]
+    Landscape,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum PrintScale {
+    /// Shrink or grow the whole diagram onto one page.
+    #[default]
+    FitToPage,
+    /// Fit the width and continue down as many pages as needed.
+    FitToWidth,
+    /// Fixed scale in percent; the diagram is tiled across pages.
+    Percent { percent: f64 },
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintOptions {
+    #[serde(default)]
+    pub paper: PaperSize,
+    #[serde(default)]
+    pub orientation: Orientation,
+    #[serde(default)]
+    pub scale: PrintScale,
+    #[serde(default = "default_margin_mm")]
+    pub margin_mm: f64,
+    #[serde(default = "default_true")]
+    pub page_numbers: bool,
+    #[serde(default = "default_true")]
+    pub legend: bool,
+}
+
+fn default_margin_mm() -> f64 {
+    10.0
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// One page's window onto the diagram, in canvas pixels.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PageTile {
+    pub row: usize,
+    pub column: usize,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintPlan {
+    /// Millimetres on paper per canvas pixel.
+    pub scale: f64,
+    pub rows: usize,
+    pub columns: usize,
+    pub pages: Vec<PageTile>,
+}
+
+struct PlacedNode<'a> {
+    id: &'a str,
+    title: String,
+    columns: &'a [Column],
+    is_view: bool,
+    x: f64,
+    y: f64,
+    height: f64,
+}
+
+fn node_height(columns: &[Column]) -> f64 {
+    HEADER_HEIGHT + ROW_HEIGHT * columns.len() as f64
+}
+
+/// Tables and views that have a position in `layout`; others are not printed.
+fn placed_nodes<'a>(graph: &'a SchemaGraph, layout: &DiagramLayout) -> Vec<PlacedNode<'a>> {
+    let tables = graph
+        .tables
+        .iter()
+        .map(|t| (t.id.as_str(), &t.schema, &t.name, &t.columns, false));
+    let views = graph
+        .views
+        .iter()
+        .map(|v| (v.id.as_str(), &v.schema, &v.name, &v.columns, true));
+    tables
+        .chain(views)
+        .filter_map(|(id, schema, name, columns, is_view)| {
+            let position = layout.positions.get(id)?;
+            Some(PlacedNode {
+                id,
+                title: format!("{}.{}", schema, name),
+                columns,
+                is_view,
+                x: position.x,
+                y: position.y,
+                height: node_height(columns),
+            })
+        })
+        .collect()
+}
+
+/// Bounding box `(x, y, width, height)` of the placed nodes.
+fn bounds(nodes: &[PlacedNode]) -> (f64, f64, f64, f64) {
+    if nodes.is_empty() {
+        return (0.0, 0.0, NODE_WIDTH, HEADER_HEIGHT);
+    }
+    let min_x = nodes.iter().map(|n| n.x).fold(f64::MAX, f64::min);
+    let min_y = nodes.iter().map(|n| n.y).fold(f64::MAX, f64::min);
+    let max_x = nodes
+        .iter()
+        .map(|n| n.x + NODE_WIDTH)
+        .fold(f64::MIN, f64::max);
+    let max_y = nodes
+        .iter()
+        .map(|n| n.y + n.height)
+        .fold(f64::MIN, f64::max);
+    (min_x, min_y, max_x - min_x, max_y - min_y)
+}
+
+fn printable_area_mm(options: &PrintOptions) -> (f64, f64) {
+    let (width, height) = options.paper.dimensions_mm();
+    let (width, height) = match options.orientation {
+        Orientation::Portrait => (width, height),
+        Orientation::Landscape => (height, width),
+    };
+    let footer = if options.page_numbers || options.legend {
+        FOOTER_MM
+    } else {
+        0.0
+    };
+    (
+        (width - 2.0 * options.margin_mm).max(10.0),
+        (height - 2.0 * options.margin_mm - footer).max(10.0),
+    )
+}
+
+/// Split a diagram of `(x, y, width, height)` canvas pixels into page tiles.
+pub fn plan_pages(content: (f64, f64, f64, f64), options: &PrintOptions) -> PrintPlan {
+    let (x, y, width, height) = content;
+    let (page_width, page_height) = printable_area_mm(options);
+    let actual = 1.0 / PX_PER_MM;
+    let scale = match options.scale {
+        PrintScale::FitToPage => (page_width / width).min(page_height / height),
+        PrintScale::FitToWidth => page_width / width,
+        PrintScale::Percent { percent } => actual * (percent / 100.0).max(0.01),
+    };
+
+    // Canvas pixels one page shows
+    let (tile_width, tile_height) = (page_width / scale, page_height / scale);
+    let pages_along = |extent: f64, tile: f64| ((extent / tile) - 1e-9).ceil().max(1.0) as usize;
+    let (columns, rows) = (
+        pages_along(width, tile_width),
+        pages_along(height, tile_height),
+    );
+
+    let mut pages = Vec::with_capacity(rows * columns);
+    for row in 0..rows {
+        for column in 0..columns {
+            pages.push(PageTile {
+                row,
+                column,
+                x: x + column as f64 * tile_width,
+                y: y + row as f64 * tile_height,
+                width: tile_width,
+                height: tile_height,
+            });
+        }
+    }
+    PrintPlan {
+        scale,
+        rows,
+        columns,
+        pages,
+    }
+}
+
+/// Text escaped for XML and HTML content and attribute values.
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The diagram drawn in canvas coordinates. It is defined once in the
+/// document and each page shows part of it through its `viewBox`.
+fn diagram_svg(graph: &SchemaGraph, nodes: &[PlacedNode], theme: &ExportTheme) -> String {
+    let font = 12.0 * theme.font_scale;
+    let stroke = theme.line_width;
+    let center = |id: &str| {
+        nodes
+            .iter()
+            .find(|n| n.id == id)
+            .map(|n| (n.x + NODE_WIDTH / 2.0, n.y + n.height / 2.0))
+    };
+
+    let mut svg = String::new();
+    for edge in &graph.relationships {
+        let (Some((x1, y1)), Some((x2, y2))) = (center(&edge.from), center(&edge.to)) else {
+            continue;
+        };
+        let (color, dash) = if edge.is_virtual {
+            (&theme.virtual_edge, r#" stroke-dasharray="6 4""#)
+        } else {
+            (&theme.edge, "")
+        };
+        let _ = write!(
+            svg,
+            r#"<line x1="{x1:.1}" y1="{y1:.1}" x2="{x2:.1}" y2="{y2:.1}" stroke="{color}" stroke-width="{stroke}"{dash}/>"#
+        );
+    }
+    for node in nodes {
+        let accent = if node.is_view {
+            &theme.view_accent
+        } else {
+            &theme.table_accent
+        };
+        let (x, y) = (node.x, node.y);
+        let _ = write!(
+            svg,
+            r#"<g><rect x="{x:.1}" y="{y:.1}" width="{NODE_WIDTH}" height="{h:.1}" fill="{bg}" stroke="{accent}" stroke-width="{stroke}"/><rect x="{x:.1}" y="{y:.1}" width="{NODE_WIDTH}" height="{HEADER_HEIGHT}" fill="{accent}"/><text x="{tx:.1}" y="{ty:.1}" font-size="{font:.1}" font-weight="bold" fill="{bg}">{title}</text>"#,
+            h = node.height,
+            bg = theme.background,
+            tx = x + 8.0,
+            ty = y + HEADER_HEIGHT / 2.0 + font / 3.0,
+            title = escape(&node.title),
+        );
+        for (i, column) in node.columns.iter().enumerate() {
+            let row_y = y + HEADER_HEIGHT + ROW_HEIGHT * i as f64 + ROW_HEIGHT / 2.0 + font / 3.0;
+            let key = if column.is_primary_key { "PK " } else { "" };
+            let _ = write!(
+                svg,
+                r#"<text x="{cx:.1}" y="{row_y:.1}" font-size="{font:.1}" fill="{text}">{key}{name}</text><text x="{tx:.1}" y="{row_y:.1}" font-size="{font:.1}" fill="{muted}" text-anchor="end">{data_type}</text>"#,
+                cx = x + 8.0,
+                tx = x + NODE_WIDTH - 8.0,
+                text = theme.text,
+                muted = theme.muted_text,
+                name = escape(&column.name),
+                data_type = escape(&column.data_type),
+            );
+        }
+        svg.push_str("</g>");
+    }
+    svg
+}
+
+fn legend_html(theme: &ExportTheme) -> String {
+    format!(
+        r#"<span class="legend"><i style="background:{}"></i>Table <i style="background:{}"></i>View <i style="border-top:2px solid {}"></i>Foreign key <i style="border-top:2px dashed {}"></i>Virtual relationship</span>"#,
+        theme.table_accent, theme.view_accent, theme.edge, theme.virtual_edge
+    )
+}
+
+/// A printable HTML document with one page per tile, sized for the chosen
+/// paper through CSS `@page`. The title block goes in the first page's
+/// footer and the watermark across every page.
+pub fn render_print_document(
+    title: &str,
+    graph: &SchemaGraph,
+    layout: &DiagramLayout,
+    theme: &ExportTheme,
+    options: &PrintOptions,
+    annotations: &ExportAnnotations,
+) -> (PrintPlan, String) {
+    let nodes = placed_nodes(graph, layout);
+    let plan = plan_pages(bounds(&nodes), options);
+    let diagram = diagram_svg(graph, &nodes, theme);
+    let (paper_width, paper_height) = options.paper.dimensions_mm();
+    let (paper_width, paper_height) = match options.orientation {
+        Orientation::Portrait => (paper_width, paper_height),
+        Orientation::Landscape => (paper_height, paper_width),
+    };
+    let (area_width, area_height) = printable_area_mm(options);
+
+    let mut html = format!(
+        r#"<!DOCTYPE html><html><head><meta charset="utf-8"><title>{title}</title><style>
+@page {{ size: {paper_width}mm {paper_height}mm; margin: {margin}mm; }}
+body {{ margin: 0; font-family: sans-serif; color: {text}; }}
+.page {{ position: relative; overflow: hidden; page-break-after: always; break-after: page; }}
+.page:last-child {{ page-break-after: auto; break-after: auto; }}
+.page svg {{ display: block; width: {area_width}mm; height: {area_height}mm; background: {background}; }}
+.footer {{ display: flex; justify-content: space-between; align-items: center; height: {FOOTER_MM}mm; font-size: 9pt; }}
+.legend i {{ display: inline-block; width: 14px; height: 8px; margin: 0 4px 0 10px; vertical-align: middle; }}
+.title-block {{ display: block; color: {muted}; }}
+.watermark {{ position: absolute; top: 40%; left: 0; right: 0; text-align: center; font-size: 72pt; font-weight: bold; color: {muted}; opacity: 0.15; transform: rotate(-30deg); pointer-events: none; }}
+</style></head><body><svg xmlns="http://www.w3.org/2000/svg" width="0" height="0" style="position:absolute"><defs><g id="diagram">{diagram}</g></defs></svg>"#,
+        title = escape(title),
+        margin = options.margin_mm,
+        text = theme.text,
+        muted = theme.muted_text,
+        background = theme.background,
+    );
+    let legend = options.legend || annotations.legend;
+    let title_block = annotations
+        .title_lines()
+        .iter()
+        .map(|(label, value)| format!("{}: {}", label, escape(value)))
+        .collect::<Vec<_>>()
+        .join(" · ");
+    let watermark = annotations
+        .watermark()
+        .map(|text| format!(r#"<div class="watermark">{}</div>"#, escape(text)))
+        .unwrap_or_default();
+    let total = plan.pages.len();
+    for (index, page) in plan.pages.iter().enumerate() {
+        let _ = write!(
+            html,
+            r##"<section class="page"><svg xmlns="http://www.w3.org/2000/svg" viewBox="{:.1} {:.1} {:.1} {:.1}" preserveAspectRatio="xMinYMin meet"><use href="#diagram"/></svg>"##,
+            page.x, page.y, page.width, page.height
+        );
+        html.push_str(&watermark);
+        let first = index == 0;
+        if options.page_numbers || legend || (first && !title_block.is_empty()) {
+            let legend = if legend && first {
+                legend_html(theme)
+            } else {
+                String::new()
+            };
+            let number = if options.page_numbers {
+                format!(
+                    "{} · Page {} of {} (row {}, column {})",
+                    escape(title),
+                    index + 1,
+                    total,
+                    page.row + 1,
+                    page.column + 1
+                )
+            } else {
+                String::new()
+            };
+            let block = if first && !title_block.is_empty() {
+                format!(r#"<span class="title-block">{}</span>"#, title_block)
+            } else {
+                String::new()
+            };
+            let _ = write!(
+                html,
+                r#"<div class="footer"><span>{}{}</span>{}</div>"#,
+                number, block, legend
+            );
+        }
+        html.push_str("</section>");
+    }
+    html.push_str("</body></html>");
+    (plan, html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layouts::NodePosition;
+    use crate::types::TableNode;
+    use std::collections::BTreeMap;
+
+    fn options(scale: PrintScale) -> PrintOptions {
+        PrintOptions {
+            paper: PaperSize::A4,
+            orientation: Orientation::Landscape,
+            scale,
+            margin_mm: 10.0,
+            page_numbers: true,
+            legend: true,
+        }
+    }
+
+    #[test]
+    fn fixed_scale_tiles_and_fit_scales_to_one_page() {
+        // 2000 x 1000 px is about 529 x 265 mm at 100%
+        let content = (0.0, 0.0, 2000.0, 1000.0);
+
+        let tiled = plan_pages(content, &options(PrintScale::Percent { percent: 100.0 }));
+        assert_eq!((tiled.columns, tiled.rows), (2, 2));
+        assert_eq!(tiled.pages.len(), 4);
+        assert_eq!(tiled.pages[3].row, 1);
+        assert!(tiled.pages[1].x > 0.0);
+
+        let fit = plan_pages(content, &options(PrintScale::FitToPage));
+        assert_eq!(fit.pages.len(), 1);
+        assert!(fit.scale < tiled.scale);
+
+        let width = plan_pages((0.0, 0.0, 1000.0, 4000.0), &options(PrintScale::FitToWidth));
+        assert_eq!(width.columns, 1);
+        assert!(width.rows > 1);
+    }
+
+    #[test]
+    fn document_has_a_page_per_tile() {
+        let graph = SchemaGraph {
+            tables: vec![TableNode {
+                id: "dbo.Orders".to_string(),
+                name: "Orders & Items".to_string(),
+                schema: "dbo".into(),
+                columns: Vec::new(),
+                source: None,
+                metadata: None,
+                change_capture: None,
+            }],
+            ..Default::default()
+        };
+        let layout = DiagramLayout {
+            name: "print".to_string(),
+            positions: BTreeMap::from([(
+                "dbo.Orders".to_string(),
+                NodePosition { x: 0.0, y: 0.0 },
+            )]),
+            collapsed_groups: Vec::new(),
+            viewport: None,
+            saved_at: String::new(),
+        };
+        let theme = crate::export_themes::builtin_export_themes().remove(0);
+
+        let (plan, html) = render_print_document(
+            "Sales",
+            &graph,
+            &layout,
+            &theme,
+            &options(PrintScale::FitToPage),
+            &ExportAnnotations {
+                watermark: Some("DRAFT".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(html.matches(r#"class="page""#).count(), plan.pages.len());
+        assert!(html.contains("dbo.Orders &amp; Items"));
+        assert!(html.contains("Page 1 of 1"));
+        assert!(html.contains("Foreign key"));
+        assert_eq!(html.matches(r#"class="watermark">DRAFT<"#).count(), 1);
+        // The diagram is drawn once however many pages show it
+        assert_eq!(html.matches("Orders &amp; Items").count(), 1);
+        assert_eq!(
+            html.matches(r##"<use href="#diagram"/>"##).count(),
+            plan.pages.len()
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::db::{quote_name, QueryLimits};
+use crate::types::{AuthType, ConnectionParams, DatabaseEngine};
+
+/// Bumped when the template layout changes incompatibly.
+const PROFILE_TEMPLATE_VERSION: u32 = 1;
+
+/// What `connect_profile_cmd` does once a profile connects.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum StartupAction {
+    /// Load the full schema from the server.
+    #[default]
+    LoadSchema,
+    /// Open the last cached graph, loading live only when nothing is cached.
+    LoadCached,
+    /// Load the schema and apply a saved layout.
+    OpenLayout { name: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RequiredPermission {
+    pub permission: String,
+    pub scope: String,
+    pub reason: String,
+}
+
+/// A shareable connection profile with every credential removed. The
+/// recipient is prompted for their own login when they open it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileTemplate {
+    pub format_version: u32,
+    pub name: String,
+    pub server: String,
+    pub database: String,
+    #[serde(default)]
+    pub engine: DatabaseEngine,
+    pub auth_type: AuthType,
+    pub trust_server_certificate: bool,
+    #[serde(default)]
+    pub multi_subnet_failover: bool,
+    #[serde(default)]
+    pub read_only_intent: bool,
+    #[serde(default)]
+    pub startup_action: StartupAction,
+    /// Query caps the recipient's copy of the profile starts with.
+    #[serde(default)]
+    pub query_limits: QueryLimits,
+    pub required_permissions: Vec<RequiredPermission>,
+    /// `GRANT` statements a DBA can run for the recipient's login.
+    pub grant_script: String,
+}
+
+/// What Monocle reads: catalog views and module text (`VIEW DEFINITION`), and
+/// nothing that touches table data.
+fn required_permissions(database: &str) -> Vec<RequiredPermission> {
+    vec![
+        RequiredPermission {
+            permission: "CONNECT".to_string(),
+            scope: format!("DATABASE::{}", database),
+            reason: "Open the database".to_string(),
+        },
+        RequiredPermission {
+            permission: "VIEW DEFINITION".to_string(),
+            scope: format!("DATABASE::{}", database),
+            reason: "Read tables, keys, triggers and module definitions".to_string(),
+        },
+    ]
+}
+
+fn grant_script(database: &str) -> String {
+    let database = quote_name(database);
+    format!(
+        "USE {};\nCREATE USER [<login>] FOR LOGIN [<login>];\nGRANT CONNECT TO [<login>];\nGRANT VIEW DEFINITION TO [<login>];\n",
+        database
+    )
+}
+
+pub fn sanitize_profile(
+    name: &str,
+    params: &ConnectionParams,
+    startup_action: StartupAction,
+    query_limits: QueryLimits,
+) -> ProfileTemplate {
+    ProfileTemplate {
+        format_version: PROFILE_TEMPLATE_VERSION,
+        name: name.to_string(),
+        server: params.server.clone(),
+        database: params.database.clone(),
+        engine: params.engine,
+        auth_type: params.auth_type.clone(),
+        trust_server_certificate: params.trust_server_certificate,
+        multi_subnet_failover: params.multi_subnet_failover,
+        read_only_intent: params.read_only_intent,
+        startup_action,
+        query_limits,
+        required_permissions: required_permissions(&params.database),
+        grant_script: grant_script(&params.database),
+    }
+}
+
+pub fn write_profile_template(path: &Path, template: &ProfileTemplate) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(template)
+        .map_err(|e| format!("Failed to serialize profile: {}", e))?;
+    std::fs::write(path, content).map_err(|e| format!("Failed to write profile: {}", e))
+}
+
+pub fn read_profile_template(path: &Path) -> Result<ProfileTemplate, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read profile: {}", e))?;
+    let template: ProfileTemplate =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid profile file: {}", e))?;
+    if template.format_version > PROFILE_TEMPLATE_VERSION {
+        return Err(format!(
+            "Profile was created by a newer version of Monocle (format {})",
+            template.format_version
+        ));
+    }
+    Ok(template)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn exported_profile_has_no_credentials() {
+        let params = ConnectionParams {
+            server: "sql01,1433".to_string(),
+            database: "Sales".to_string(),
+            engine: DatabaseEngine::SqlServer,
+            auth_type: AuthType::SqlServer,
+            username: Some("dba_admin".to_string()),
+            password: Some("hunter2".to_string()),
+            trust_server_certificate: true,
+            multi_subnet_failover: true,
+            read_only_intent: false,
+        };
+        let template = sanitize_profile(
+            "Sales (prod)",
+            &params,
+            StartupAction::OpenLayout {
+                name: "Overview".to_string(),
+            },
+            QueryLimits {
+                max_preview_rows: Some(500),
+                ..Default::default()
+            },
+        );
+
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("sales.monocle-profile.json");
+        write_profile_template(&path, &template).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("dba_admin"));
+        assert!(!content.contains("hunter2"));
+        assert!(content.contains("GRANT VIEW DEFINITION"));
+
+        assert_eq!(read_profile_template(&path).unwrap(), template);
+        assert!(content.contains(r#""kind": "openLayout""#));
+        assert!(content.contains(r#""maxPreviewRows": 500"#));
+    }
+}
@@ -0,0 +1,305 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::db::RowSink;
+
+/// Worksheet rows Excel can hold, less one for the header.
+const XLSX_MAX_DATA_ROWS: usize = 1_048_575;
+
+const XLSX_CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/></Types>"#;
+
+const XLSX_ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+const XLSX_WORKBOOK: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Results" sheetId="1" r:id="rId1"/></sheets></workbook>"#;
+
+const XLSX_WORKBOOK_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum QueryExportFormat {
+    Csv,
+    /// An array with one object per row, keyed by column name.
+    Json,
+    Xlsx,
+}
+
+impl QueryExportFormat {
+    /// Most rows the format can hold, if it is limited.
+    pub fn max_rows(self) -> Option<usize> {
+        match self {
+            QueryExportFormat::Xlsx => Some(XLSX_MAX_DATA_ROWS),
+            QueryExportFormat::Csv | QueryExportFormat::Json => None,
+        }
+    }
+}
+
+/// Writes query rows to a file as they arrive, so only one row is held in
+/// memory at a time. Call `finish` to complete the file.
+pub enum QueryResultWriter {
+    Csv(csv::Writer<BufWriter<File>>),
+    Json {
+        out: BufWriter<File>,
+        columns: Vec<String>,
+        rows: usize,
+    },
+    Xlsx(ZipWriter<BufWriter<File>>),
+}
+
+fn io_error(e: impl std::fmt::Display) -> String {
+    format!("Failed to write export: {}", e)
+}
+
+/// Spreadsheet apps run text starting with these as a formula.
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// Text for a CSV cell. Text that a spreadsheet would evaluate as a formula
+/// gets a leading `'`, so opening an export can't run what a row contained.
+fn csv_text(text: &str) -> String {
+    match text.starts_with(FORMULA_PREFIXES) {
+        true => format!("'{}", text),
+        false => text.to_string(),
+    }
+}
+
+fn csv_field(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => csv_text(s),
+        other => other.to_string(),
+    }
+}
+
+/// JSON object keys for the result columns. A repeated name (`a.Id, b.Id`)
+/// gets a numeric suffix instead of overwriting the earlier column.
+fn unique_keys(columns: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    columns
+        .into_iter()
+        .map(|name| {
+            let mut key = name.clone();
+            let mut n = 1;
+            while !seen.insert(key.clone()) {
+                n += 1;
+                key = format!("{}_{}", name, n);
+            }
+            key
+        })
+        .collect()
+}
+
+/// Escape text for an XML text node, dropping control characters XML 1.0
+/// cannot represent.
+fn xml_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c < ' ' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn xlsx_cell(value: &Value) -> String {
+    match value {
+        Value::Null => "<c/>".to_string(),
+        Value::Bool(b) => format!("<c t=\"b\"><v>{}</v></c>", u8::from(*b)),
+        Value::Number(n) => format!("<c><v>{}</v></c>", n),
+        Value::String(s) => xlsx_text_cell(s),
+        other => xlsx_text_cell(&other.to_string()),
+    }
+}
+
+fn xlsx_text_cell(text: &str) -> String {
+    format!(
+        "<c t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>",
+        xml_text(text)
+    )
+}
+
+fn xlsx_row(cells: impl Iterator<Item = String>) -> String {
+    let mut row = String::from("<row>");
+    cells.for_each(|cell| row.push_str(&cell));
+    row.push_str("</row>");
+    row
+}
+
+impl QueryResultWriter {
+    pub fn create(path: &Path, format: QueryExportFormat) -> Result<Self, String> {
+        let out = BufWriter::new(File::create(path).map_err(io_error)?);
+        match format {
+            QueryExportFormat::Csv => Ok(QueryResultWriter::Csv(csv::Writer::from_writer(out))),
+            QueryExportFormat::Json => {
+                let mut out = out;
+                out.write_all(b"[").map_err(io_error)?;
+                Ok(QueryResultWriter::Json {
+                    out,
+                    columns: Vec::new(),
+                    rows: 0,
+                })
+            }
+            QueryExportFormat::Xlsx => {
+                let mut zip = ZipWriter::new(out);
+                let options = SimpleFileOptions::default();
+                for (name, content) in [
+                    ("[Content_Types].xml", XLSX_CONTENT_TYPES),
+                    ("_rels/.rels", XLSX_ROOT_RELS),
+                    ("xl/workbook.xml", XLSX_WORKBOOK),
+                    ("xl/_rels/workbook.xml.rels", XLSX_WORKBOOK_RELS),
+                ] {
+                    zip.start_file(name, options).map_err(io_error)?;
+                    zip.write_all(content.as_bytes()).map_err(io_error)?;
+                }
+                zip.start_file("xl/worksheets/sheet1.xml", options)
+                    .map_err(io_error)?;
+                zip.write_all(
+                    concat!(
+                        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                        "\n",
+                        r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#
+                    )
+                    .as_bytes(),
+                )
+                .map_err(io_error)?;
+                Ok(QueryResultWriter::Xlsx(zip))
+            }
+        }
+    }
+
+    pub fn finish(self) -> Result<(), String> {
+        match self {
+            QueryResultWriter::Csv(mut writer) => writer.flush().map_err(io_error),
+            QueryResultWriter::Json { mut out, .. } => {
+                out.write_all(b"\n]\n").map_err(io_error)?;
+                out.flush().map_err(io_error)
+            }
+            QueryResultWriter::Xlsx(mut zip) => {
+                zip.write_all(b"</sheetData></worksheet>")
+                    .map_err(io_error)?;
+                zip.finish().map_err(io_error)?.flush().map_err(io_error)
+            }
+        }
+    }
+}
+
+impl RowSink for QueryResultWriter {
+    fn columns(&mut self, columns: Vec<String>) -> Result<(), String> {
+        match self {
+            QueryResultWriter::Csv(writer) => writer
+                .write_record(columns.iter().map(|c| csv_text(c)))
+                .map_err(io_error),
+            QueryResultWriter::Json { columns: keys, .. } => {
+                *keys = unique_keys(columns);
+                Ok(())
+            }
+            QueryResultWriter::Xlsx(zip) => {
+                let header = xlsx_row(columns.iter().map(|c| xlsx_text_cell(c)));
+                zip.write_all(header.as_bytes()).map_err(io_error)
+            }
+        }
+    }
+
+    fn row(&mut self, row: Vec<Value>) -> Result<(), String> {
+        match self {
+            QueryResultWriter::Csv(writer) => writer
+                .write_record(row.iter().map(csv_field))
+                .map_err(io_error),
+            QueryResultWriter::Json { out, columns, rows } => {
+                let object: Map<String, Value> = columns.iter().cloned().zip(row).collect();
+                out.write_all(if *rows == 0 { b"\n" } else { b",\n" })
+                    .map_err(io_error)?;
+                serde_json::to_writer(&mut *out, &object).map_err(io_error)?;
+                *rows += 1;
+                Ok(())
+            }
+            QueryResultWriter::Xlsx(zip) => zip
+                .write_all(xlsx_row(row.iter().map(xlsx_cell)).as_bytes())
+                .map_err(io_error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Read;
+    use tempfile::tempdir;
+
+    fn export(path: &Path, format: QueryExportFormat) {
+        let mut writer = QueryResultWriter::create(path, format).unwrap();
+        writer
+            .columns(vec![
+                "Id".into(),
+                "Name".into(),
+                "Active".into(),
+                "Id".into(),
+            ])
+            .unwrap();
+        writer
+            .row(vec![
+                json!(1),
+                json!("Widgets, \"large\""),
+                json!(true),
+                json!(-1),
+            ])
+            .unwrap();
+        writer
+            .row(vec![
+                json!(2),
+                json!("<Gadgets & co>"),
+                Value::Null,
+                json!("=1+1"),
+            ])
+            .unwrap();
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn rows_are_written_in_each_format() {
+        let dir = tempdir().expect("tempdir");
+
+        let csv_path = dir.path().join("rows.csv");
+        export(&csv_path, QueryExportFormat::Csv);
+        assert_eq!(
+            std::fs::read_to_string(&csv_path).unwrap(),
+            "Id,Name,Active,Id\n1,\"Widgets, \"\"large\"\"\",true,-1\n2,<Gadgets & co>,,'=1+1\n"
+        );
+
+        let json_path = dir.path().join("rows.json");
+        export(&json_path, QueryExportFormat::Json);
+        let parsed: Value =
+            serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(
+            parsed[1],
+            json!({"Id": 2, "Name": "<Gadgets & co>", "Active": null, "Id_2": "=1+1"})
+        );
+
+        let xlsx_path = dir.path().join("rows.xlsx");
+        export(&xlsx_path, QueryExportFormat::Xlsx);
+        let mut archive = zip::ZipArchive::new(File::open(&xlsx_path).unwrap()).unwrap();
+        let mut sheet = String::new();
+        archive
+            .by_name("xl/worksheets/sheet1.xml")
+            .unwrap()
+            .read_to_string(&mut sheet)
+            .unwrap();
+        assert!(sheet.contains("&lt;Gadgets &amp; co&gt;"));
+        assert!(sheet.contains("<c t=\"b\"><v>1</v></c>"));
+        assert_eq!(sheet.matches("<row>").count(), 3);
+    }
+}
@@ -0,0 +1,277 @@
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::db::{create_client, create_server_client, quote_name, SchemaError};
+use crate::ddl::rebuild_script;
+use crate::types::{ConnectionParams, DatabaseEngine, SchemaGraph, ServerConnectionParams};
+
+/// Every disposable database is named with this prefix, and only databases
+/// created in this session are ever dropped.
+pub const SANDBOX_PREFIX: &str = "monocle_sandbox_";
+
+/// Statements longer than this are cut in failure reports.
+const EXCERPT_LENGTH: usize = 200;
+
+/// A batch of the design script the server rejected.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFailure {
+    /// 1-based position in the script.
+    pub batch: usize,
+    pub statement: String,
+    pub message: String,
+}
+
+/// A disposable database created from a design.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxDatabase {
+    pub database: String,
+    /// Ready to load or query the new database.
+    pub params: ConnectionParams,
+    pub created_at: String,
+    pub batches: usize,
+    /// Empty when the whole design applied.
+    pub failures: Vec<BatchFailure>,
+    /// Objects the script couldn't include, such as modules without a
+    /// definition.
+    pub warnings: Vec<String>,
+}
+
+/// Sandboxes created in this session, with the server login that created
+/// them so they can be dropped. In memory only.
+#[derive(Default)]
+pub struct SandboxRegistry {
+    databases: Mutex<HashMap<String, ServerConnectionParams>>,
+}
+
+impl SandboxRegistry {
+    fn register(&self, database: &str, params: &ServerConnectionParams) {
+        if let Ok(mut databases) = self.databases.lock() {
+            databases.insert(database.to_string(), params.clone());
+        }
+    }
+
+    fn take(&self, database: &str) -> Option<ServerConnectionParams> {
+        self.databases.lock().ok()?.remove(database)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .databases
+            .lock()
+            .map(|d| d.keys().cloned().collect())
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+}
+
+fn sandbox_name() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    format!(
+        "{}{}_{:04x}",
+        SANDBOX_PREFIX,
+        Utc::now().format("%Y%m%d_%H%M%S"),
+        nanos & 0xffff
+    )
+}
+
+/// Batches that build `graph` in an empty database: its schemas, then
+/// every table, view and module in dependency order, then foreign keys.
+pub fn design_batches(graph: &SchemaGraph) -> Result<(Vec<String>, Vec<String>), String> {
+    let ids: Vec<String> = graph
+        .tables
+        .iter()
+        .map(|t| t.id.clone())
+        .chain(graph.views.iter().map(|v| v.id.clone()))
+        .chain(graph.scalar_functions.iter().map(|f| f.id.clone()))
+        .chain(graph.stored_procedures.iter().map(|p| p.id.clone()))
+        .collect();
+    let rebuild = rebuild_script(graph, &ids)?;
+
+    let schemas: BTreeSet<&str> = graph
+        .tables
+        .iter()
+        .map(|t| &*t.schema)
+        .chain(graph.views.iter().map(|v| &*v.schema))
+        .chain(graph.scalar_functions.iter().map(|f| &*f.schema))
+        .chain(graph.stored_procedures.iter().map(|p| &*p.schema))
+        .filter(|s| !s.eq_ignore_ascii_case("dbo"))
+        .collect();
+    let mut batches: Vec<String> = schemas
+        .into_iter()
+        .map(|schema| format!("CREATE SCHEMA {};", quote_name(schema)))
+        .collect();
+    batches.extend(split_batches(&rebuild.script));
+    Ok((batches, rebuild.warnings))
+}
+
+/// Split a script on `GO` separator lines, dropping empty batches.
+pub fn split_batches(script: &str) -> Vec<String> {
+    let mut batches = Vec::new();
+    let mut current = String::new();
+    for line in script.lines() {
+        if line.trim().eq_ignore_ascii_case("go") {
+            batches.push(std::mem::take(&mut current));
+        } else {
+            current.push_str(line);
+            current.push('\n');
+        }
+    }
+    batches.push(current);
+    batches
+        .into_iter()
+        .filter(|b| {
+            b.lines()
+                .any(|l| !l.trim().is_empty() && !l.trim_start().starts_with("--"))
+        })
+        .map(|b| b.trim().to_string())
+        .collect()
+}
+
+fn excerpt(statement: &str) -> String {
+    match statement.char_indices().nth(EXCERPT_LENGTH) {
+        Some((end, _)) => format!("{}...", &statement[..end]),
+        None => statement.to_string(),
+    }
+}
+
+/// Create an empty database on `server` and apply `graph` to it. Batches
+/// the server rejects are reported rather than aborting, since that is the
+/// point of a smoke test; the database is dropped again only when it can't
+/// be connected to at all.
+pub async fn create_sandbox(
+    registry: &SandboxRegistry,
+    server: &ServerConnectionParams,
+    graph: &SchemaGraph,
+) -> Result<SandboxDatabase, SchemaError> {
+    let (batches, warnings) = design_batches(graph).map_err(SchemaError::Export)?;
+    let database = sandbox_name();
+
+    let mut master = create_server_client(server).await?;
+    master
+        .simple_query(format!("CREATE DATABASE {};", quote_name(&database)))
+        .await?
+        .into_results()
+        .await?;
+    registry.register(&database, server);
+
+    let params = ConnectionParams {
+        server: server.server.clone(),
+        database: database.clone(),
+        engine: DatabaseEngine::SqlServer,
+        auth_type: server.auth_type.clone(),
+        username: server.username.clone(),
+        password: server.password.clone(),
+        trust_server_certificate: server.trust_server_certificate,
+        multi_subnet_failover: server.multi_subnet_failover,
+        read_only_intent: false,
+    };
+    let mut client = match create_client(&params).await {
+        Ok(client) => client,
+        Err(e) => {
+            let _ = drop_sandbox(registry, &database).await;
+            return Err(e.into());
+        }
+    };
+
+    let mut failures = Vec::new();
+    for (index, batch) in batches.iter().enumerate() {
+        let result = match client.simple_query(batch.as_str()).await {
+            Ok(stream) => stream.into_results().await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            failures.push(BatchFailure {
+                batch: index + 1,
+                statement: excerpt(batch),
+                message: e.to_string(),
+            });
+        }
+    }
+
+    Ok(SandboxDatabase {
+        database,
+        params,
+        created_at: Utc::now().to_rfc3339(),
+        batches: batches.len(),
+        failures,
+        warnings,
+    })
+}
+
+/// Drop a sandbox created in this session, disconnecting anyone using it.
+/// Any other database is refused.
+pub async fn drop_sandbox(registry: &SandboxRegistry, database: &str) -> Result<(), SchemaError> {
+    if !database.starts_with(SANDBOX_PREFIX) {
+        return Err(SchemaError::InvalidQuery(format!(
+            "{} is not a sandbox database",
+            database
+        )));
+    }
+    let server = registry.take(database).ok_or_else(|| {
+        SchemaError::InvalidQuery(format!("{} was not created in this session", database))
+    })?;
+    let quoted = quote_name(database);
+    let result = async {
+        let mut master = create_server_client(&server).await?;
+        master
+            .simple_query(format!(
+                "IF DB_ID(N'{name}') IS NOT NULL BEGIN \
+                 ALTER DATABASE {db} SET SINGLE_USER WITH ROLLBACK IMMEDIATE; \
+                 DROP DATABASE {db}; END",
+                name = database.replace('\'', "''"),
+                db = quoted
+            ))
+            .await?
+            .into_results()
+            .await?;
+        Ok(())
+    }
+    .await;
+    if result.is_err() {
+        // Keep it listed so the drop can be retried
+        registry.register(database, &server);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+
+    #[test]
+    fn design_script_splits_into_batches() {
+        assert_eq!(
+            split_batches(
+                "-- header\nGO\nDROP TABLE x;\nGO\n  go  \nCREATE TABLE x (a int);\nGO\n"
+            ),
+            vec!["DROP TABLE x;", "CREATE TABLE x (a int);"]
+        );
+
+        let graph = generate_mock_schema("small");
+        let (batches, _) = design_batches(&graph).unwrap();
+        let first_table = batches
+            .iter()
+            .position(|b| b.starts_with("CREATE TABLE"))
+            .unwrap();
+        assert!(batches[..first_table]
+            .iter()
+            .all(|b| !b.starts_with("CREATE VIEW")));
+        assert!(sandbox_name().starts_with(SANDBOX_PREFIX));
+
+        let registry = SandboxRegistry::default();
+        let result = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(drop_sandbox(&registry, "Production"));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::db::QueryParameter;
+use crate::i18n::t;
+use crate::state::connection_key;
+
+/// The database a saved query was written for. Only the server and database
+/// are kept; credentials come from the connection it is run on.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedQueryConnection {
+    pub server: String,
+    pub database: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedQuery {
+    pub name: String,
+    pub sql: String,
+    #[serde(default)]
+    pub parameters: Vec<QueryParameter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection: Option<SavedQueryConnection>,
+    /// Set when saved; any value sent by the caller is replaced.
+    #[serde(default)]
+    pub saved_at: String,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SavedQueryFile {
+    queries: Vec<SavedQuery>,
+}
+
+fn saved_query_path(storage_path: &Path) -> PathBuf {
+    storage_path.join("saved-queries.json")
+}
+
+fn read_saved_query_file(path: &Path) -> SavedQueryFile {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_saved_query_file(path: &Path, file: &SavedQueryFile) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create query directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(file)
+        .map_err(|e| format!("Failed to serialize saved queries: {}", e))?;
+    std::fs::write(path, content).map_err(|e| format!("Failed to write saved queries: {}", e))
+}
+
+/// Saved queries in name order. Given a connection, only queries for that
+/// database and ones not tied to any database are listed.
+pub fn list_saved_queries(
+    storage_path: &Path,
+    connection: Option<(&str, &str)>,
+) -> Vec<SavedQuery> {
+    let queries = read_saved_query_file(&saved_query_path(storage_path)).queries;
+    match connection {
+        Some((server, database)) => {
+            let key = connection_key(server, database);
+            queries
+                .into_iter()
+                .filter(|q| {
+                    q.connection
+                        .as_ref()
+                        .is_none_or(|c| connection_key(&c.server, &c.database) == key)
+                })
+                .collect()
+        }
+        None => queries,
+    }
+}
+
+/// Save a query, replacing any existing query with the same name (compared
+/// case-insensitively).
+pub fn save_query(storage_path: &Path, mut query: SavedQuery) -> Result<Vec<SavedQuery>, String> {
+    let name = query.name.trim().to_string();
+    if name.is_empty() {
+        return Err(t!("error-saved-query-name-required"));
+    }
+    if query.sql.trim().is_empty() {
+        return Err(t!("error-saved-query-sql-required"));
+    }
+    query.name = name;
+    query.saved_at = Utc::now().to_rfc3339();
+
+    let path = saved_query_path(storage_path);
+    let mut file = read_saved_query_file(&path);
+    file.queries
+        .retain(|q| !q.name.eq_ignore_ascii_case(&query.name));
+    file.queries.push(query);
+    file.queries.sort_by_key(|q| q.name.to_lowercase());
+    write_saved_query_file(&path, &file)?;
+    Ok(file.queries)
+}
+
+pub fn load_saved_query(storage_path: &Path, name: &str) -> Result<SavedQuery, String> {
+    read_saved_query_file(&saved_query_path(storage_path))
+        .queries
+        .into_iter()
+        .find(|q| q.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| t!("error-saved-query-not-found", name = name))
+}
+
+pub fn delete_saved_query(storage_path: &Path, name: &str) -> Result<Vec<SavedQuery>, String> {
+    let path = saved_query_path(storage_path);
+    let mut file = read_saved_query_file(&path);
+    let before = file.queries.len();
+    file.queries.retain(|q| !q.name.eq_ignore_ascii_case(name));
+    if file.queries.len() == before {
+        return Err(t!("error-saved-query-not-found", name = name));
+    }
+    write_saved_query_file(&path, &file)?;
+    Ok(file.queries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn query(name: &str, connection: Option<(&str, &str)>) -> SavedQuery {
+        SavedQuery {
+            name: name.to_string(),
+            sql: "SELECT TOP 10 * FROM dbo.Orders WHERE Region = @region".to_string(),
+            parameters: vec![QueryParameter {
+                name: "region".to_string(),
+                sql_type: None,
+                default_value: Some("EU".to_string()),
+            }],
+            connection: connection.map(|(server, database)| SavedQueryConnection {
+                server: server.to_string(),
+                database: database.to_string(),
+            }),
+            saved_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn saved_queries_are_filtered_by_connection_and_replaced_by_name() {
+        let dir = tempdir().expect("tempdir");
+        let root = dir.path();
+
+        save_query(root, query("Recent orders", Some(("srv", "Sales")))).unwrap();
+        save_query(root, query("Blocking sessions", None)).unwrap();
+        save_query(root, query("Stock levels", Some(("srv", "Inventory")))).unwrap();
+        let all = save_query(root, query("recent orders", Some(("srv", "Sales")))).unwrap();
+        assert_eq!(all.len(), 3);
+
+        let names: Vec<String> = list_saved_queries(root, Some(("SRV", "sales")))
+            .into_iter()
+            .map(|q| q.name)
+            .collect();
+        assert_eq!(names, vec!["Blocking sessions", "recent orders"]);
+
+        let loaded = load_saved_query(root, "RECENT ORDERS").unwrap();
+        assert_eq!(loaded.parameters.len(), 1);
+        assert!(!loaded.saved_at.is_empty());
+
+        assert_eq!(delete_saved_query(root, "Stock levels").unwrap().len(), 2);
+        assert!(delete_saved_query(root, "Stock levels").is_err());
+        assert!(save_query(root, query(" ", None)).is_err());
+    }
+}
@@ -0,0 +1,357 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::annotations::Annotation;
+use crate::types::{Column, SchemaGraph};
+
+/// Rough size of a token in English text and SQL names; close enough for
+/// keeping chunks under a model's limit, not for billing.
+const CHARS_PER_TOKEN: usize = 4;
+pub const DEFAULT_CHUNK_TOKENS: usize = 4000;
+/// Smallest chunk budget accepted, so a chunk can hold more than its legend.
+const MIN_CHUNK_TOKENS: usize = 200;
+
+const LEGEND: &str =
+    "Columns are `name type`; `?` marks nullable, PK the primary key and `->` a foreign key.";
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum SummaryDetail {
+    /// Object names and what tables point to.
+    Names,
+    /// Columns with types, keys and foreign keys.
+    #[default]
+    Columns,
+    /// Columns plus descriptions, view sources and routine table usage.
+    Full,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SummaryOptions {
+    pub detail: SummaryDetail,
+    /// Estimated tokens per chunk; [`DEFAULT_CHUNK_TOKENS`] when unset.
+    pub max_tokens: Option<usize>,
+    /// Only these objects; every table and view when unset.
+    pub object_ids: Option<Vec<String>>,
+    /// Also list procedures and triggers with the tables they use.
+    pub include_routines: bool,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryChunk {
+    pub text: String,
+    pub estimated_tokens: usize,
+    pub object_ids: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaSummary {
+    pub chunks: Vec<SummaryChunk>,
+    pub estimated_tokens: usize,
+}
+
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+struct Context<'a> {
+    detail: SummaryDetail,
+    /// Foreign key targets by table ID and lower-cased column.
+    references: HashMap<(&'a str, String), Vec<String>>,
+    descriptions: HashMap<(&'a str, Option<String>), &'a str>,
+}
+
+impl Context<'_> {
+    fn description(&self, object_id: &str, column: Option<&str>) -> Option<&str> {
+        if self.detail != SummaryDetail::Full {
+            return None;
+        }
+        self.descriptions
+            .get(&(object_id, column.map(str::to_lowercase)))
+            .copied()
+            .filter(|d| !d.trim().is_empty())
+    }
+
+    fn columns(&self, object_id: &str, columns: &[Column]) -> String {
+        let rendered: Vec<String> = columns
+            .iter()
+            .map(|c| {
+                let mut text = format!("{} {}", c.name, c.data_type);
+                if c.is_nullable {
+                    text.push('?');
+                }
+                if c.is_primary_key {
+                    text.push_str(" PK");
+                }
+                if let Some(targets) = self.references.get(&(object_id, c.name.to_lowercase())) {
+                    for target in targets {
+                        text.push_str(" -> ");
+                        text.push_str(target);
+                    }
+                }
+                text
+            })
+            .collect();
+        rendered.join(", ")
+    }
+
+    /// Description lines under an object: its own, then its columns'.
+    fn described(&self, block: &mut String, object_id: &str, columns: &[Column]) {
+        if let Some(description) = self.description(object_id, None) {
+            block.push_str(&format!("\n  -- {}", one_line(description)));
+        }
+        for column in columns {
+            if let Some(description) = self.description(object_id, Some(&column.name)) {
+                block.push_str(&format!(
+                    "\n  -- {}: {}",
+                    column.name,
+                    one_line(description)
+                ));
+            }
+        }
+    }
+}
+
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// One text block per object, in graph order.
+fn object_blocks(
+    graph: &SchemaGraph,
+    annotations: &[Annotation],
+    options: &SummaryOptions,
+) -> Vec<(String, String)> {
+    let mut references: HashMap<(&str, String), Vec<String>> = HashMap::new();
+    for r in &graph.relationships {
+        let Some(from_column) = &r.from_column else {
+            continue;
+        };
+        let target = match &r.to_column {
+            Some(to_column) => format!("{}.{}", r.to, to_column),
+            None => r.to.clone(),
+        };
+        references
+            .entry((r.from.as_str(), from_column.to_lowercase()))
+            .or_default()
+            .push(target);
+    }
+    let context = Context {
+        detail: options.detail,
+        references,
+        descriptions: annotations
+            .iter()
+            .map(|a| {
+                (
+                    (
+                        a.object_id.as_str(),
+                        a.column.as_deref().map(str::to_lowercase),
+                    ),
+                    a.description.as_str(),
+                )
+            })
+            .collect(),
+    };
+    let wanted = |id: &str| {
+        options
+            .object_ids
+            .as_ref()
+            .is_none_or(|ids| ids.iter().any(|i| i == id))
+    };
+
+    let mut blocks = Vec::new();
+    for table in graph.tables.iter().filter(|t| wanted(&t.id)) {
+        let block = match options.detail {
+            SummaryDetail::Names => {
+                let mut targets: Vec<&str> = graph
+                    .relationships
+                    .iter()
+                    .filter(|r| r.from == table.id && r.to != table.id)
+                    .map(|r| r.to.as_str())
+                    .collect();
+                targets.sort_unstable();
+                targets.dedup();
+                if targets.is_empty() {
+                    format!("table {}", table.id)
+                } else {
+                    format!("table {} -> {}", table.id, targets.join(", "))
+                }
+            }
+            SummaryDetail::Columns | SummaryDetail::Full => {
+                let mut block = format!(
+                    "table {}({})",
+                    table.id,
+                    context.columns(&table.id, &table.columns)
+                );
+                context.described(&mut block, &table.id, &table.columns);
+                block
+            }
+        };
+        blocks.push((table.id.clone(), block));
+    }
+
+    for view in graph.views.iter().filter(|v| wanted(&v.id)) {
+        let mut block = match options.detail {
+            SummaryDetail::Names => format!("view {}", view.id),
+            _ => format!(
+                "view {}({})",
+                view.id,
+                context.columns(&view.id, &view.columns)
+            ),
+        };
+        if options.detail == SummaryDetail::Full && !view.referenced_tables.is_empty() {
+            block.push_str(&format!(" from {}", view.referenced_tables.join(", ")));
+        }
+        context.described(&mut block, &view.id, &view.columns);
+        blocks.push((view.id.clone(), block));
+    }
+
+    if options.include_routines {
+        let usage = |reads: &[String], writes: &[String]| {
+            let mut text = String::new();
+            if !reads.is_empty() {
+                text.push_str(&format!(" reads {}", reads.join(", ")));
+            }
+            if !writes.is_empty() {
+                text.push_str(&format!(" writes {}", writes.join(", ")));
+            }
+            text
+        };
+        for p in graph.stored_procedures.iter().filter(|p| wanted(&p.id)) {
+            let parameters: Vec<String> = p
+                .parameters
+                .iter()
+                .map(|param| format!("{} {}", param.name, param.data_type))
+                .collect();
+            let mut block = format!("procedure {}({})", p.id, parameters.join(", "));
+            if options.detail != SummaryDetail::Names {
+                block.push_str(&usage(&p.referenced_tables, &p.affected_tables));
+            }
+            blocks.push((p.id.clone(), block));
+        }
+        for t in graph.triggers.iter().filter(|t| wanted(&t.id)) {
+            let mut block = format!("trigger {} on {}", t.id, t.table_id);
+            if options.detail != SummaryDetail::Names {
+                block.push_str(&usage(&t.referenced_tables, &t.affected_tables));
+            }
+            blocks.push((t.id.clone(), block));
+        }
+    }
+    blocks
+}
+
+fn chunk_header(database: &str, part: usize, parts: usize) -> String {
+    format!(
+        "Schema of database {} (part {} of {}). {}\n",
+        database, part, parts, LEGEND
+    )
+}
+
+/// The graph as plain text for pasting into a language model prompt, split
+/// into chunks of at most `max_tokens` estimated tokens. Objects are never
+/// split; one larger than the budget gets a chunk of its own.
+pub fn summarize_schema(
+    graph: &SchemaGraph,
+    annotations: &[Annotation],
+    database: &str,
+    options: &SummaryOptions,
+) -> SchemaSummary {
+    let budget = options
+        .max_tokens
+        .unwrap_or(DEFAULT_CHUNK_TOKENS)
+        .max(MIN_CHUNK_TOKENS)
+        * CHARS_PER_TOKEN;
+    // Room for the header with the widest part numbers it could carry
+    let header = chunk_header(database, 9999, 9999).chars().count();
+    let room = budget.saturating_sub(header);
+
+    let mut packed: Vec<(String, Vec<String>)> = Vec::new();
+    let mut current = (String::new(), Vec::new());
+    for (id, block) in object_blocks(graph, annotations, options) {
+        let size = block.chars().count() + 1;
+        if !current.1.is_empty() && current.0.chars().count() + size > room {
+            packed.push(std::mem::take(&mut current));
+        }
+        current.0.push_str(&block);
+        current.0.push('\n');
+        current.1.push(id);
+    }
+    if !current.1.is_empty() {
+        packed.push(current);
+    }
+
+    let parts = packed.len();
+    let chunks: Vec<SummaryChunk> = packed
+        .into_iter()
+        .enumerate()
+        .map(|(i, (body, object_ids))| {
+            let text = format!("{}{}", chunk_header(database, i + 1, parts), body);
+            SummaryChunk {
+                estimated_tokens: estimate_tokens(&text),
+                text,
+                object_ids,
+            }
+        })
+        .collect();
+    SchemaSummary {
+        estimated_tokens: chunks.iter().map(|c| c.estimated_tokens).sum(),
+        chunks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+
+    #[test]
+    fn chunks_stay_within_budget_and_keep_objects_whole() {
+        let graph = generate_mock_schema("medium");
+        let options = SummaryOptions {
+            max_tokens: Some(MIN_CHUNK_TOKENS),
+            ..Default::default()
+        };
+        let summary = summarize_schema(&graph, &[], "Shop", &options);
+
+        assert!(summary.chunks.len() > 1);
+        let listed: usize = summary.chunks.iter().map(|c| c.object_ids.len()).sum();
+        assert_eq!(listed, graph.tables.len() + graph.views.len());
+        for chunk in &summary.chunks {
+            assert!(chunk.text.starts_with("Schema of database Shop (part "));
+            // Only a single oversized object may exceed the budget
+            assert!(chunk.estimated_tokens <= MIN_CHUNK_TOKENS || chunk.object_ids.len() == 1);
+        }
+    }
+
+    #[test]
+    fn detail_controls_columns_and_descriptions() {
+        let graph = generate_mock_schema("small");
+        let table = &graph.tables[0];
+        let column = &table.columns[0];
+        let annotations = vec![Annotation {
+            object_id: table.id.clone(),
+            column: Some(column.name.to_uppercase()),
+            description: "Surrogate\nkey".to_string(),
+            updated_at: String::new(),
+        }];
+        let only = |detail| SummaryOptions {
+            detail,
+            object_ids: Some(vec![table.id.clone()]),
+            ..Default::default()
+        };
+
+        let names = summarize_schema(&graph, &annotations, "db", &only(SummaryDetail::Names));
+        let text = &names.chunks[0].text;
+        assert!(text.contains(&format!("table {}", table.id)));
+        assert!(!text.contains(&format!("{} {}", column.name, column.data_type)));
+
+        let full = summarize_schema(&graph, &annotations, "db", &only(SummaryDetail::Full));
+        let text = &full.chunks[0].text;
+        assert!(text.contains(&format!("{} {}", column.name, column.data_type)));
+        assert!(text.contains(&format!("-- {}: Surrogate key", column.name)));
+        assert_eq!(full.chunks[0].object_ids, vec![table.id.clone()]);
+    }
+}
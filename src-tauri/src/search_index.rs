@@ -0,0 +1,699 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::glossary::Glossary;
+use crate::state::connection_file_stem;
+use crate::types::SchemaGraph;
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_DEFINITION_LINES: usize = 3;
+const SNIPPET_CHARS: usize = 160;
+const SNIPPET_LEAD: usize = 40;
+
+const EXACT_MATCH: u32 = 4;
+const PREFIX_MATCH: u32 = 2;
+const FUZZY_MATCH: u32 = 1;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchField {
+    Name,
+    Column,
+    Definition,
+}
+
+impl SearchField {
+    fn weight(self) -> u32 {
+        match self {
+            SearchField::Name => 20,
+            SearchField::Column => 6,
+            SearchField::Definition => 1,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct IndexedObject {
+    id: String,
+    object_type: String,
+    name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    columns: Vec<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    definition: String,
+}
+
+/// Object index, field, and how often the term occurs there.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+struct Posting(u32, SearchField, u32);
+
+/// Inverted index over the names, column names and definitions of one
+/// connection's graph. Terms are kept sorted so a query word also matches
+/// every term it is a prefix of.
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchIndex {
+    /// The graph version the index was built from.
+    version: u64,
+    objects: Vec<IndexedObject>,
+    terms: BTreeMap<String, Vec<Posting>>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHighlight {
+    pub field: SearchField,
+    /// The name, column name, or a line of the definition trimmed to a
+    /// snippet around the first match.
+    pub text: String,
+    /// Character ranges of `text` to highlight, end exclusive.
+    pub ranges: Vec<[usize; 2]>,
+    /// 1-based line of the definition the snippet comes from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub object_id: String,
+    pub object_type: String,
+    pub name: String,
+    pub score: u32,
+    pub highlights: Vec<SearchHighlight>,
+}
+
+struct Token {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+/// Words of `text` with their character ranges, lower-cased. Identifiers are
+/// also split at underscores and camel-case humps, so `OrderLineItems`
+/// yields `orderlineitems`, `order`, `line` and `items`.
+fn tokenize(text: &str) -> Vec<Token> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if !is_word_char(chars[i]) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && is_word_char(chars[i]) {
+            i += 1;
+        }
+        let word: String = chars[start..i].iter().collect();
+        let parts = word_parts(&chars[start..i]);
+        tokens.push(Token {
+            start,
+            end: i,
+            text: word.to_lowercase(),
+        });
+        if parts.len() > 1 {
+            tokens.extend(parts.into_iter().map(|(s, e)| {
+                Token {
+                    start: start + s,
+                    end: start + e,
+                    text: chars[start + s..start + e]
+                        .iter()
+                        .collect::<String>()
+                        .to_lowercase(),
+                }
+            }));
+        }
+    }
+    tokens
+}
+
+/// The lower-cased words of `text`, identifier parts included.
+pub(crate) fn words(text: &str) -> Vec<String> {
+    tokenize(text).into_iter().map(|t| t.text).collect()
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+pub(crate) fn word_parts(word: &[char]) -> Vec<(usize, usize)> {
+    let mut parts = Vec::new();
+    let mut start = None;
+    for (i, &c) in word.iter().enumerate() {
+        if c == '_' {
+            if let Some(s) = start.take() {
+                parts.push((s, i));
+            }
+            continue;
+        }
+        let boundary = start.is_some_and(|s| {
+            let prev = word[i - 1];
+            i > s
+                && ((c.is_uppercase() && prev.is_lowercase())
+                    || (c.is_uppercase()
+                        && prev.is_uppercase()
+                        && word.get(i + 1).is_some_and(|n| n.is_lowercase()))
+                    || c.is_ascii_digit() != prev.is_ascii_digit())
+        });
+        if boundary {
+            parts.push((start.unwrap_or(i), i));
+            start = Some(i);
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        parts.push((s, word.len()));
+    }
+    parts
+}
+
+impl SearchIndex {
+    pub fn build(graph: &SchemaGraph) -> Self {
+        let column_names = |columns: &[crate::types::Column]| -> Vec<String> {
+            columns.iter().map(|c| c.name.clone()).collect()
+        };
+        let object = |id: &str, object_type: &str, name: &str| IndexedObject {
+            id: id.to_string(),
+            object_type: object_type.to_string(),
+            name: name.to_string(),
+            columns: Vec::new(),
+            definition: String::new(),
+        };
+        let objects: Vec<IndexedObject> = graph
+            .tables
+            .iter()
+            .map(|t| IndexedObject {
+                columns: column_names(&t.columns),
+                ..object(&t.id, "table", &t.name)
+            })
+            .chain(graph.views.iter().map(|v| IndexedObject {
+                columns: column_names(&v.columns),
+                definition: v.definition.clone(),
+                ..object(&v.id, "view", &v.name)
+            }))
+            .chain(graph.triggers.iter().map(|t| IndexedObject {
+                definition: t.definition.clone(),
+                ..object(&t.id, "trigger", &t.name)
+            }))
+            .chain(graph.stored_procedures.iter().map(|p| IndexedObject {
+                columns: p.parameters.iter().map(|p| p.name.clone()).collect(),
+                definition: p.definition.clone(),
+                ..object(&p.id, "procedure", &p.name)
+            }))
+            .chain(graph.scalar_functions.iter().map(|f| IndexedObject {
+                columns: f.parameters.iter().map(|p| p.name.clone()).collect(),
+                definition: f.definition.clone(),
+                ..object(&f.id, "function", &f.name)
+            }))
+            .collect();
+
+        let mut terms: BTreeMap<String, Vec<Posting>> = BTreeMap::new();
+        for (doc, object) in objects.iter().enumerate() {
+            let mut counts: HashMap<(String, SearchField), u32> = HashMap::new();
+            let mut add = |text: &str, field: SearchField| {
+                for token in tokenize(text) {
+                    *counts.entry((token.text, field)).or_default() += 1;
+                }
+            };
+            add(&object.name, SearchField::Name);
+            for column in &object.columns {
+                add(column, SearchField::Column);
+            }
+            add(&object.definition, SearchField::Definition);
+            for ((term, field), count) in counts {
+                terms
+                    .entry(term)
+                    .or_default()
+                    .push(Posting(doc as u32, field, count));
+            }
+        }
+        for postings in terms.values_mut() {
+            postings.sort_by_key(|p| (p.0, p.1 as u8));
+        }
+        Self {
+            version: graph.version,
+            objects,
+            terms,
+        }
+    }
+
+    /// Objects matching every word of `query`, best first. A word matches
+    /// terms it equals or is a prefix of, terms within a typo or two of it
+    /// (one for words of four to seven letters, two for longer ones), and
+    /// the terms of its glossary synonyms. Name matches outrank column
+    /// matches, which outrank matches in definitions; exact terms outrank
+    /// prefixes, which outrank typos.
+    pub fn search(&self, query: &str, limit: Option<usize>, glossary: &Glossary) -> Vec<SearchHit> {
+        let synonyms = synonym_groups(glossary);
+        let mut words: Vec<String> = query
+            .split(|c: char| !is_word_char(c))
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        // A multi-word glossary term is searched as one identifier
+        let phrase = normalize_phrase(query);
+        if words.len() > 1 && synonyms.contains_key(&phrase) {
+            words = vec![phrase];
+        }
+        words.sort();
+        words.dedup();
+        if words.is_empty() {
+            return Vec::new();
+        }
+
+        let mut highlighted: HashMap<&str, usize> = HashMap::new();
+        let mut scores: HashMap<u32, (u32, usize)> = HashMap::new();
+        for (index, word) in words.iter().enumerate() {
+            let alternatives = synonyms.get(word).map(Vec::as_slice).unwrap_or_default();
+            let mut matched: HashMap<u32, u32> = HashMap::new();
+            for (term, (quality, length)) in self.match_word(word, alternatives) {
+                let best = highlighted.entry(term).or_default();
+                *best = (*best).max(length);
+                for Posting(doc, field, count) in &self.terms[term] {
+                    *matched.entry(*doc).or_default() += field.weight() * count * quality;
+                }
+            }
+            for (doc, score) in matched {
+                let entry = scores.entry(doc).or_default();
+                if entry.1 == index {
+                    *entry = (entry.0 + score, index + 1);
+                }
+            }
+        }
+
+        let mut hits: Vec<SearchHit> = scores
+            .into_iter()
+            .filter(|(_, (_, matched))| *matched == words.len())
+            .map(|(doc, (score, _))| {
+                let object = &self.objects[doc as usize];
+                SearchHit {
+                    object_id: object.id.clone(),
+                    object_type: object.object_type.clone(),
+                    name: object.name.clone(),
+                    score,
+                    highlights: highlights(object, &highlighted),
+                }
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.cmp(&a.score).then(a.object_id.cmp(&b.object_id)));
+        hits.truncate(limit.unwrap_or(DEFAULT_LIMIT));
+        hits
+    }
+
+    /// Index terms matched by `word` or one of its `synonyms`, with the
+    /// match quality and how many leading characters of the term to
+    /// highlight.
+    fn match_word(&self, word: &str, synonyms: &[String]) -> HashMap<&str, (u32, usize)> {
+        let mut matched: HashMap<&str, (u32, usize)> = HashMap::new();
+        for alternative in std::iter::once(word).chain(synonyms.iter().map(String::as_str)) {
+            let length = alternative.chars().count();
+            for (term, _) in self
+                .terms
+                .range(alternative.to_string()..)
+                .take_while(|(term, _)| term.starts_with(alternative))
+            {
+                let quality = match term == alternative {
+                    true => EXACT_MATCH,
+                    false => PREFIX_MATCH,
+                };
+                let entry = matched.entry(term.as_str()).or_default();
+                *entry = (*entry).max((quality, length));
+            }
+        }
+
+        let edits = max_edits(word);
+        if edits > 0 {
+            let word: Vec<char> = word.chars().collect();
+            for term in self.terms.keys() {
+                if matched.contains_key(term.as_str()) {
+                    continue;
+                }
+                let term_chars: Vec<char> = term.chars().collect();
+                if term_chars.len() + edits < word.len() {
+                    continue;
+                }
+                let (distance, length) = prefix_distance(&word, &term_chars, edits);
+                if distance <= edits {
+                    matched.insert(term.as_str(), (FUZZY_MATCH, length));
+                }
+            }
+        }
+        matched
+    }
+}
+
+/// Typos tolerated in a query word; none for short words, where a single
+/// edit already matches too much.
+fn max_edits(word: &str) -> usize {
+    match word.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Fewest edits (insertions, deletions, substitutions or adjacent
+/// transpositions) turning `word` into a prefix of `term`, with the length
+/// of that prefix. Prefixes longer than `word` plus `max_edits` can't be
+/// closer, so they aren't considered.
+fn prefix_distance(word: &[char], term: &[char], max_edits: usize) -> (usize, usize) {
+    let columns = term.len().min(word.len() + max_edits);
+    let mut rows = vec![(0..=columns).collect::<Vec<usize>>()];
+    for i in 1..=word.len() {
+        let mut row = vec![i; columns + 1];
+        for j in 1..=columns {
+            let cost = usize::from(word[i - 1] != term[j - 1]);
+            row[j] = (rows[i - 1][j] + 1)
+                .min(row[j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && word[i - 1] == term[j - 2] && word[i - 2] == term[j - 1] {
+                row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+        rows.push(row);
+    }
+    let last = &rows[word.len()];
+    let (length, distance) = last
+        .iter()
+        .enumerate()
+        .min_by_key(|(j, d)| (**d, word.len().abs_diff(*j)))
+        .map(|(j, d)| (j, *d))
+        .unwrap_or_default();
+    (distance, length)
+}
+
+/// A glossary entry as a search word: lower-cased with separators dropped,
+/// the way it would be written as an identifier, so `Purchase Order` finds
+/// `PurchaseOrders`.
+pub(crate) fn normalize_phrase(phrase: &str) -> String {
+    phrase
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Each glossary term name and synonym, mapped to the other entries of the
+/// same term.
+pub(crate) fn synonym_groups(glossary: &Glossary) -> HashMap<String, Vec<String>> {
+    let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+    for term in &glossary.terms {
+        let mut entries: Vec<String> = std::iter::once(&term.name)
+            .chain(&term.synonyms)
+            .map(|e| normalize_phrase(e))
+            .filter(|e| !e.is_empty())
+            .collect();
+        entries.sort();
+        entries.dedup();
+        for entry in &entries {
+            let others = groups.entry(entry.clone()).or_default();
+            others.extend(entries.iter().filter(|e| *e != entry).cloned());
+            others.sort();
+            others.dedup();
+        }
+    }
+    groups
+}
+
+/// Merged character ranges of `text` covering the matched prefix of each
+/// token that is a matched term.
+fn match_ranges(text: &str, highlighted: &HashMap<&str, usize>) -> Vec<[usize; 2]> {
+    let mut ranges: Vec<[usize; 2]> = tokenize(text)
+        .into_iter()
+        .filter_map(|token| {
+            let length = highlighted.get(token.text.as_str())?;
+            Some([
+                token.start,
+                token.start + length.min(&(token.end - token.start)),
+            ])
+        })
+        .filter(|range| range[1] > range[0])
+        .collect();
+    ranges.sort();
+    let mut merged: Vec<[usize; 2]> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range[0] <= last[1] => last[1] = last[1].max(range[1]),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+fn highlight(
+    field: SearchField,
+    text: &str,
+    highlighted: &HashMap<&str, usize>,
+    line: Option<usize>,
+) -> Option<SearchHighlight> {
+    let ranges = match_ranges(text, highlighted);
+    (!ranges.is_empty()).then(|| snippet(field, text, ranges, line))
+}
+
+fn highlights(object: &IndexedObject, highlighted: &HashMap<&str, usize>) -> Vec<SearchHighlight> {
+    let names = std::iter::once((SearchField::Name, &object.name))
+        .chain(object.columns.iter().map(|c| (SearchField::Column, c)))
+        .filter_map(|(field, text)| highlight(field, text, highlighted, None));
+    let lines = object
+        .definition
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| highlight(SearchField::Definition, line, highlighted, Some(i + 1)))
+        .take(MAX_DEFINITION_LINES);
+    names.chain(lines).collect()
+}
+
+/// Definition lines are trimmed, and long ones cut to a window starting a
+/// little before the first match; names and columns are kept whole.
+fn snippet(
+    field: SearchField,
+    text: &str,
+    ranges: Vec<[usize; 2]>,
+    line: Option<usize>,
+) -> SearchHighlight {
+    if field != SearchField::Definition {
+        return SearchHighlight {
+            field,
+            text: text.to_string(),
+            ranges,
+            line,
+        };
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let mut start = chars.iter().take_while(|c| c.is_whitespace()).count();
+    let mut end = chars.len() - chars.iter().rev().take_while(|c| c.is_whitespace()).count();
+    if end - start > SNIPPET_CHARS {
+        start = start.max(ranges[0][0].saturating_sub(SNIPPET_LEAD));
+        end = end.min(start + SNIPPET_CHARS);
+    }
+    SearchHighlight {
+        field,
+        text: chars[start..end].iter().collect(),
+        ranges: ranges
+            .into_iter()
+            .filter(|r| r[0] >= start && r[1] <= end)
+            .map(|r| [r[0] - start, r[1] - start])
+            .collect(),
+        line,
+    }
+}
+
+fn index_path(storage_path: &Path, server: &str, database: &str) -> PathBuf {
+    storage_path
+        .join("search-index")
+        .join(format!("{}.json", connection_file_stem(server, database)))
+}
+
+fn read_index(path: &Path) -> Option<SearchIndex> {
+    let content = std::fs::read(path).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+/// Indexes loaded this session, keyed by file, so repeated queries don't
+/// re-read them from disk.
+#[derive(Default)]
+pub struct SearchIndexes {
+    loaded: Mutex<HashMap<PathBuf, Arc<SearchIndex>>>,
+}
+
+impl SearchIndexes {
+    /// Rebuild and persist the connection's index unless it was already
+    /// built from this version of the graph.
+    pub fn update(
+        &self,
+        storage_path: &Path,
+        server: &str,
+        database: &str,
+        graph: &SchemaGraph,
+    ) -> Result<(), String> {
+        let path = index_path(storage_path, server, database);
+        if let Some(current) = self.get(&path) {
+            if graph.version != 0 && current.version == graph.version {
+                return Ok(());
+            }
+        }
+        let index = SearchIndex::build(graph);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create search index directory: {}", e))?;
+        }
+        let content = serde_json::to_vec(&index)
+            .map_err(|e| format!("Failed to serialize search index: {}", e))?;
+        std::fs::write(&path, content)
+            .map_err(|e| format!("Failed to write search index: {}", e))?;
+        if let Ok(mut loaded) = self.loaded.lock() {
+            loaded.insert(path, Arc::new(index));
+        }
+        Ok(())
+    }
+
+    /// The connection's index, from memory or disk. `None` until the schema
+    /// has been loaded once.
+    pub fn load(
+        &self,
+        storage_path: &Path,
+        server: &str,
+        database: &str,
+    ) -> Option<Arc<SearchIndex>> {
+        self.get(&index_path(storage_path, server, database))
+    }
+
+    fn get(&self, path: &Path) -> Option<Arc<SearchIndex>> {
+        if let Some(index) = self.loaded.lock().ok()?.get(path) {
+            return Some(index.clone());
+        }
+        let index = Arc::new(read_index(path)?);
+        self.loaded
+            .lock()
+            .ok()?
+            .insert(path.to_path_buf(), index.clone());
+        Some(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+    use crate::glossary::GlossaryTerm;
+    use tempfile::tempdir;
+
+    #[test]
+    fn words_split_at_humps_and_underscores() {
+        let words: Vec<String> = tokenize("OrderLineItems, tax_rate2")
+            .into_iter()
+            .map(|t| t.text)
+            .collect();
+        assert_eq!(
+            words,
+            vec![
+                "orderlineitems",
+                "order",
+                "line",
+                "items",
+                "tax_rate2",
+                "tax",
+                "rate",
+                "2"
+            ]
+        );
+    }
+
+    #[test]
+    fn search_ranks_names_first_and_highlights_matches() {
+        let dir = tempdir().expect("tempdir");
+        let mut graph = generate_mock_schema("small");
+        graph.version = 1;
+        graph.tables[0].name = "ZebraLedger".to_string();
+        graph.views[0].definition =
+            "CREATE VIEW v AS\n    SELECT * FROM dbo.ZebraLedger WHERE 1 = 1".to_string();
+
+        let indexes = SearchIndexes::default();
+        indexes.update(dir.path(), "srv", "Sales", &graph).unwrap();
+        // A fresh cache reads the persisted index back
+        let index = SearchIndexes::default()
+            .load(dir.path(), "SRV", "sales")
+            .expect("persisted");
+
+        let hits = index.search("ZEB", None, &Glossary::default());
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].object_id, graph.tables[0].id);
+        assert_eq!(hits[0].highlights[0].field, SearchField::Name);
+        assert_eq!(hits[0].highlights[0].ranges, vec![[0, 3]]);
+        assert_eq!(
+            index.search("ledger", None, &Glossary::default())[0].highlights[0].ranges,
+            vec![[5, 11]]
+        );
+
+        let view_hit = hits
+            .iter()
+            .find(|h| h.object_id == graph.views[0].id)
+            .expect("view matched by definition");
+        let line = view_hit
+            .highlights
+            .iter()
+            .find(|h| h.field == SearchField::Definition)
+            .expect("definition highlight");
+        assert_eq!(line.line, Some(2));
+        assert!(line.text.starts_with("SELECT"));
+
+        assert!(index
+            .search("zebra qqqq", None, &Glossary::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn typos_and_glossary_synonyms_match() {
+        let mut graph = generate_mock_schema("small");
+        graph.tables[0].name = "Patrons".to_string();
+        graph.tables[1].name = "ConsignmentNotes".to_string();
+        let index = SearchIndex::build(&graph);
+        let glossary = Glossary {
+            terms: vec![
+                GlossaryTerm {
+                    name: "Patron".to_string(),
+                    definition: String::new(),
+                    synonyms: vec!["Benefactor".to_string()],
+                },
+                GlossaryTerm {
+                    name: "Consignment Note".to_string(),
+                    definition: String::new(),
+                    synonyms: vec!["CN".to_string()],
+                },
+            ],
+            tags: Vec::new(),
+        };
+        let top = |query: &str| {
+            index
+                .search(query, None, &glossary)
+                .first()
+                .map(|hit| (hit.name.clone(), hit.highlights[0].ranges.clone()))
+        };
+
+        assert_eq!(
+            top("benefactor"),
+            Some(("Patrons".to_string(), vec![[0, 6]]))
+        );
+        assert_eq!(top("patrns"), Some(("Patrons".to_string(), vec![[0, 7]])));
+        assert_eq!(
+            top("cn"),
+            Some(("ConsignmentNotes".to_string(), vec![[0, 15]]))
+        );
+        assert_eq!(
+            top("consignment note"),
+            Some(("ConsignmentNotes".to_string(), vec![[0, 15]]))
+        );
+        assert_eq!(
+            prefix_distance(&['c', 'u', 's', 't'], &['c', 'u', 't', 's'], 1),
+            (1, 4)
+        );
+        assert!(index
+            .search("benefactor", None, &Glossary::default())
+            .is_empty());
+    }
+}
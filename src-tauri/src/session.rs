@@ -0,0 +1,149 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::graph_cache::read_cached_graph;
+use crate::layouts::DiagramLayout;
+use crate::types::{AuthType, DatabaseEngine, SchemaGraph};
+
+/// Canvas filters as the frontend last had them.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionFilters {
+    pub search: String,
+    pub schema: String,
+    pub focused_table_id: Option<String>,
+    pub object_types: Vec<String>,
+    pub edge_types: Vec<String>,
+    pub excluded_object_ids: Vec<String>,
+}
+
+/// One open connection. Credentials are never written; a restored session
+/// reopens from the graph cache and asks for a password only to go live.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionConnection {
+    pub server: String,
+    pub database: String,
+    #[serde(default)]
+    pub engine: DatabaseEngine,
+    #[serde(default)]
+    pub auth_type: AuthType,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub trust_server_certificate: bool,
+    #[serde(default)]
+    pub layout: Option<DiagramLayout>,
+    #[serde(default)]
+    pub filters: SessionFilters,
+}
+
+/// What was open when the app last closed, stored as `session.json`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionState {
+    #[serde(default)]
+    pub connections: Vec<SessionConnection>,
+    #[serde(default)]
+    pub saved_at: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredConnection {
+    pub connection: SessionConnection,
+    /// `None` when nothing is cached; the frontend loads live instead.
+    pub graph: Option<SchemaGraph>,
+    pub cached_at: Option<String>,
+}
+
+fn session_path(storage_path: &Path) -> PathBuf {
+    storage_path.join("session.json")
+}
+
+pub fn write_session(storage_path: &Path, session: &SessionState) -> Result<(), String> {
+    std::fs::create_dir_all(storage_path)
+        .map_err(|e| format!("Failed to create storage directory: {}", e))?;
+    let session = SessionState {
+        saved_at: Utc::now().to_rfc3339(),
+        ..session.clone()
+    };
+    let content = serde_json::to_string_pretty(&session)
+        .map_err(|e| format!("Failed to serialize session: {}", e))?;
+    std::fs::write(session_path(storage_path), content)
+        .map_err(|e| format!("Failed to write session: {}", e))
+}
+
+pub fn read_session(storage_path: &Path) -> Option<SessionState> {
+    let content = std::fs::read_to_string(session_path(storage_path)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Reopen each connection of the last session from the graph cache.
+pub fn restore_session(storage_path: &Path) -> Vec<RestoredConnection> {
+    let Some(session) = read_session(storage_path) else {
+        return Vec::new();
+    };
+    session
+        .connections
+        .into_iter()
+        .map(|connection| {
+            let cached = read_cached_graph(storage_path, &connection.server, &connection.database);
+            let (graph, cached_at) = match cached {
+                Some(cached) => (Some(cached.graph), Some(cached.cached_at)),
+                None => (None, None),
+            };
+            RestoredConnection {
+                connection,
+                graph,
+                cached_at,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+    use crate::graph_cache::write_cached_graph;
+    use tempfile::tempdir;
+
+    fn connection(database: &str) -> SessionConnection {
+        SessionConnection {
+            server: "srv".to_string(),
+            database: database.to_string(),
+            engine: DatabaseEngine::SqlServer,
+            auth_type: AuthType::SqlServer,
+            username: Some("reader".to_string()),
+            trust_server_certificate: true,
+            layout: None,
+            filters: SessionFilters {
+                schema: "sales".to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn session_restores_connections_from_cache() {
+        let dir = tempdir().expect("tempdir");
+        let root = dir.path();
+        assert!(restore_session(root).is_empty());
+
+        write_cached_graph(root, "srv", "Sales", &generate_mock_schema("small")).unwrap();
+        let session = SessionState {
+            connections: vec![connection("Sales"), connection("Billing")],
+            saved_at: String::new(),
+        };
+        write_session(root, &session).unwrap();
+        assert!(!read_session(root).unwrap().saved_at.is_empty());
+
+        let restored = restore_session(root);
+        assert_eq!(restored.len(), 2);
+        assert!(restored[0].graph.is_some());
+        assert_eq!(restored[0].connection.filters.schema, "sales");
+        assert!(restored[1].graph.is_none());
+    }
+}
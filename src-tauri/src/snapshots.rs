@@ -0,0 +1,230 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::definition_diff::{diff_sequences, Edit};
+use crate::state::connection_file_stem;
+use crate::types::SchemaGraph;
+
+/// Definition versions kept per object; the oldest are dropped first.
+const MAX_VERSIONS_PER_OBJECT: usize = 50;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DefinitionVersion {
+    pub captured_at: String,
+    pub definition: String,
+}
+
+/// Definition history of one database, stored as
+/// `snapshots/<connection>.json` in the app data directory.
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotFile {
+    objects: BTreeMap<String, Vec<DefinitionVersion>>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffOp {
+    Unchanged,
+    Added,
+    Removed,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub captured_at: String,
+    pub definition: String,
+    /// Line diff against the previous version; empty for the first version.
+    pub diff: Vec<DiffLine>,
+}
+
+fn snapshot_path(storage_path: &Path, server: &str, database: &str) -> PathBuf {
+    storage_path
+        .join("snapshots")
+        .join(format!("{}.json", connection_file_stem(server, database)))
+}
+
+fn read_snapshot_file(path: &Path) -> SnapshotFile {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Append the current definition of every module in `graph` whose text
+/// changed since the last recorded version. Returns how many were recorded.
+pub fn record_snapshot(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+    graph: &SchemaGraph,
+) -> Result<usize, String> {
+    let path = snapshot_path(storage_path, server, database);
+    let mut file = read_snapshot_file(&path);
+    let captured_at = Utc::now().to_rfc3339();
+
+    let definitions = graph
+        .views
+        .iter()
+        .map(|v| (&v.id, &v.definition))
+        .chain(graph.triggers.iter().map(|t| (&t.id, &t.definition)))
+        .chain(
+            graph
+                .stored_procedures
+                .iter()
+                .map(|p| (&p.id, &p.definition)),
+        )
+        .chain(
+            graph
+                .scalar_functions
+                .iter()
+                .map(|f| (&f.id, &f.definition)),
+        )
+        .filter(|(_, definition)| !definition.is_empty());
+
+    let mut recorded = 0;
+    for (id, definition) in definitions {
+        let versions = file.objects.entry(id.clone()).or_default();
+        if versions.last().is_some_and(|v| &v.definition == definition) {
+            continue;
+        }
+        versions.push(DefinitionVersion {
+            captured_at: captured_at.clone(),
+            definition: definition.clone(),
+        });
+        if versions.len() > MAX_VERSIONS_PER_OBJECT {
+            versions.remove(0);
+        }
+        recorded += 1;
+    }
+
+    if recorded > 0 {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create snapshot directory: {}", e))?;
+        }
+        let content = serde_json::to_string(&file)
+            .map_err(|e| format!("Failed to serialize snapshot: {}", e))?;
+        std::fs::write(&path, content).map_err(|e| format!("Failed to write snapshot: {}", e))?;
+    }
+    Ok(recorded)
+}
+
+/// Recorded versions of one object, oldest first, each diffed against the
+/// version before it.
+pub fn object_history(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+    object_id: &str,
+) -> Vec<HistoryEntry> {
+    let file = read_snapshot_file(&snapshot_path(storage_path, server, database));
+    let Some(versions) = file.objects.get(object_id) else {
+        return Vec::new();
+    };
+
+    versions
+        .iter()
+        .enumerate()
+        .map(|(i, version)| HistoryEntry {
+            captured_at: version.captured_at.clone(),
+            definition: version.definition.clone(),
+            diff: match i {
+                0 => Vec::new(),
+                _ => diff_lines(&versions[i - 1].definition, &version.definition),
+            },
+        })
+        .collect()
+}
+
+/// Line-level diff via longest common subsequence.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let line = |op, text: &str| DiffLine {
+        op,
+        text: text.to_string(),
+    };
+    diff_sequences(&old, &new)
+        .into_iter()
+        .map(|edit| match edit {
+            Edit::Unchanged(i, _) => line(DiffOp::Unchanged, old[i]),
+            Edit::Removed(i) => line(DiffOp::Removed, old[i]),
+            Edit::Added(j) => line(DiffOp::Added, new[j]),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::StoredProcedure;
+    use tempfile::tempdir;
+
+    fn graph_with_proc(definition: &str) -> SchemaGraph {
+        SchemaGraph {
+            stored_procedures: vec![StoredProcedure {
+                id: "dbo.usp_Orders".to_string(),
+                name: "usp_Orders".to_string(),
+                schema: "dbo".into(),
+                procedure_type: "SQL_STORED_PROCEDURE".into(),
+                parameters: Vec::new(),
+                definition: definition.to_string(),
+                referenced_tables: Vec::new(),
+                affected_tables: Vec::new(),
+                analysis: Default::default(),
+                source: None,
+                metadata: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn diff_marks_changed_lines() {
+        let ops: Vec<(DiffOp, &str)> = [
+            (DiffOp::Unchanged, "SELECT Id"),
+            (DiffOp::Removed, "FROM dbo.Orders"),
+            (DiffOp::Added, "FROM dbo.OrdersV2"),
+            (DiffOp::Added, "WHERE Active = 1"),
+            (DiffOp::Unchanged, "GO"),
+        ]
+        .to_vec();
+        let diff = diff_lines(
+            "SELECT Id\nFROM dbo.Orders\nGO",
+            "SELECT Id\nFROM dbo.OrdersV2\nWHERE Active = 1\nGO",
+        );
+        let actual: Vec<(DiffOp, &str)> = diff.iter().map(|l| (l.op, l.text.as_str())).collect();
+        assert_eq!(actual, ops);
+    }
+
+    #[test]
+    fn only_changed_definitions_are_recorded() {
+        let dir = tempdir().expect("tempdir");
+        let v1 = graph_with_proc("SELECT 1");
+        let v2 = graph_with_proc("SELECT 2");
+
+        assert_eq!(record_snapshot(dir.path(), "srv", "Sales", &v1).unwrap(), 1);
+        assert_eq!(record_snapshot(dir.path(), "srv", "Sales", &v1).unwrap(), 0);
+        assert_eq!(record_snapshot(dir.path(), "srv", "Sales", &v2).unwrap(), 1);
+
+        let history = object_history(dir.path(), "srv", "Sales", "dbo.usp_Orders");
+        assert_eq!(history.len(), 2);
+        assert!(history[0].diff.is_empty());
+        assert_eq!(history[1].definition, "SELECT 2");
+        assert_eq!(history[1].diff[0].op, DiffOp::Removed);
+
+        assert!(object_history(dir.path(), "srv", "Other", "dbo.usp_Orders").is_empty());
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use crate::db::object_id;
+use crate::types::{Column, Interner, RelationshipEdge, SchemaGraph, TableNode};
+
+/// Node kinds that correspond to a warehouse relation. Tests, macros and
+/// analyses also appear in the manifest but are not drawn.
+const RELATION_TYPES: [&str; 3] = ["model", "seed", "snapshot"];
+
+#[derive(Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    nodes: HashMap<String, ManifestNode>,
+    #[serde(default)]
+    sources: HashMap<String, ManifestNode>,
+}
+
+#[derive(Deserialize)]
+struct ManifestNode {
+    resource_type: String,
+    name: String,
+    schema: String,
+    #[serde(default)]
+    alias: Option<String>,
+    /// Physical table name for sources.
+    #[serde(default)]
+    identifier: Option<String>,
+    #[serde(default, deserialize_with = "ordered_columns")]
+    columns: Vec<ManifestColumn>,
+    #[serde(default)]
+    depends_on: DependsOn,
+}
+
+#[derive(Deserialize, Default)]
+struct DependsOn {
+    #[serde(default)]
+    nodes: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ManifestColumn {
+    name: String,
+    #[serde(default)]
+    data_type: Option<String>,
+}
+
+/// dbt writes `columns` as an object in YAML declaration order; keep that order
+/// rather than letting a map sort it.
+fn ordered_columns<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<ManifestColumn>, D::Error> {
+    struct ColumnsVisitor;
+
+    impl<'de> Visitor<'de> for ColumnsVisitor {
+        type Value = Vec<ManifestColumn>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a map of column name to column")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut columns = Vec::new();
+            while let Some((_, column)) = map.next_entry::<String, ManifestColumn>()? {
+                columns.push(column);
+            }
+            Ok(columns)
+        }
+    }
+
+    deserializer.deserialize_map(ColumnsVisitor)
+}
+
+impl ManifestNode {
+    /// Name of the relation dbt materializes in the warehouse.
+    fn relation_name(&self) -> &str {
+        self.identifier
+            .as_deref()
+            .or(self.alias.as_deref())
+            .unwrap_or(&self.name)
+    }
+}
+
+/// Build a graph from a parsed dbt manifest. Models, seeds, snapshots and
+/// sources become tables keyed by their warehouse `schema.relation`, so they
+/// line up with a live load of the same warehouse; every `ref()`/`source()`
+/// dependency becomes a column-less relationship from the model to its parent.
+pub fn graph_from_manifest(json: &str) -> Result<SchemaGraph, String> {
+    let manifest: Manifest =
+        serde_json::from_str(json).map_err(|e| format!("Invalid dbt manifest: {}", e))?;
+
+    let mut interner = Interner::default();
+    let relations: HashMap<&str, &ManifestNode> = manifest
+        .nodes
+        .iter()
+        .filter(|(_, node)| RELATION_TYPES.contains(&node.resource_type.as_str()))
+        .chain(manifest.sources.iter())
+        .map(|(unique_id, node)| (unique_id.as_str(), node))
+        .collect();
+    let ids: HashMap<&str, String> = relations
+        .iter()
+        .map(|(unique_id, node)| (*unique_id, object_id(&[&node.schema, node.relation_name()])))
+        .collect();
+
+    let mut graph = SchemaGraph::default();
+    let mut seen_edges = HashSet::new();
+    for (unique_id, node) in &relations {
+        let id = &ids[unique_id];
+        graph.tables.push(TableNode {
+            id: id.clone(),
+            name: node.relation_name().to_string(),
+            schema: interner.intern(&node.schema),
+            columns: node
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| Column {
+                    name: column.name.clone(),
+                    data_type: interner.intern(column.data_type.as_deref().unwrap_or("unknown")),
+                    is_nullable: true,
+                    ordinal_position: i as u32 + 1,
+                    ..Default::default()
+                })
+                .collect(),
+            source: None,
+            metadata: None,
+            change_capture: None,
+        });
+
+        for parent in &node.depends_on.nodes {
+            let Some(parent_id) = ids.get(parent.as_str()) else {
+                continue;
+            };
+            if parent_id != id && seen_edges.insert((id.clone(), parent_id.clone())) {
+                graph.relationships.push(RelationshipEdge {
+                    id: format!("dbt:{}->{}", id, parent_id),
+                    from: id.clone(),
+                    to: parent_id.clone(),
+                    from_column: None,
+                    to_column: None,
+                    source: None,
+                    is_virtual: false,
+                    participation: None,
+                });
+            }
+        }
+    }
+
+    graph
+        .tables
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    graph.relationships.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(graph)
+}
+
+/// Read `manifest.json` from a dbt project. Accepts the manifest itself, the
+/// project root or its `target/` folder.
+pub fn load_dbt_manifest(path: &Path) -> Result<SchemaGraph, String> {
+    let manifest = if path.is_dir() {
+        [
+            path.join("target").join("manifest.json"),
+            path.join("manifest.json"),
+        ]
+        .into_iter()
+        .find(|p| p.is_file())
+        .ok_or_else(|| {
+            format!(
+                "No manifest.json found in {}; run `dbt compile` first",
+                path.display()
+            )
+        })?
+    } else {
+        path.to_path_buf()
+    };
+    let json =
+        std::fs::read_to_string(&manifest).map_err(|e| format!("{}: {}", manifest.display(), e))?;
+    graph_from_manifest(&json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"{
+        "metadata": { "dbt_version": "1.7.0" },
+        "nodes": {
+            "model.shop.stg_orders": {
+                "resource_type": "model", "name": "stg_orders", "schema": "staging",
+                "columns": {
+                    "order_id": { "name": "order_id", "data_type": "int" },
+                    "customer_id": { "name": "customer_id", "data_type": null }
+                },
+                "depends_on": { "nodes": ["source.shop.raw.orders", "macro.dbt.run_query"] }
+            },
+            "model.shop.fct_orders": {
+                "resource_type": "model", "name": "fct_orders", "alias": "orders",
+                "schema": "marts", "columns": {},
+                "depends_on": { "nodes": ["model.shop.stg_orders", "model.shop.stg_orders"] }
+            },
+            "test.shop.not_null_orders": {
+                "resource_type": "test", "name": "not_null_orders", "schema": "marts",
+                "depends_on": { "nodes": ["model.shop.fct_orders"] }
+            }
+        },
+        "sources": {
+            "source.shop.raw.orders": {
+                "resource_type": "source", "name": "orders", "identifier": "Orders",
+                "schema": "dbo", "columns": {}
+            }
+        }
+    }"#;
+
+    #[test]
+    fn manifest_maps_relations_and_lineage() {
+        let graph = graph_from_manifest(MANIFEST).unwrap();
+
+        let ids: Vec<&str> = graph.tables.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["dbo.Orders", "marts.orders", "staging.stg_orders"]
+        );
+
+        let columns: Vec<(&str, &str, u32)> = graph.tables[2]
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), &*c.data_type, c.ordinal_position))
+            .collect();
+        assert_eq!(
+            columns,
+            vec![("order_id", "int", 1), ("customer_id", "unknown", 2)]
+        );
+
+        let edges: Vec<(&str, &str)> = graph
+            .relationships
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("marts.orders", "staging.stg_orders"),
+                ("staging.stg_orders", "dbo.Orders"),
+            ]
+        );
+    }
+
+    #[test]
+    fn project_folder_requires_compiled_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load_dbt_manifest(dir.path()).is_err());
+
+        std::fs::create_dir(dir.path().join("target")).unwrap();
+        std::fs::write(dir.path().join("target").join("manifest.json"), MANIFEST).unwrap();
+        assert_eq!(load_dbt_manifest(dir.path()).unwrap().tables.len(), 3);
+    }
+}
@@ -0,0 +1,366 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+
+use parquet::basic::{ConvertedType, LogicalType, Type as PhysicalType};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use walkdir::WalkDir;
+
+use crate::db::object_id;
+use crate::types::{Column, GraphWarning, GraphWarningKind, Interner, SchemaGraph, TableNode};
+
+/// Rows read from each CSV file to infer column types.
+const CSV_SAMPLE_ROWS: usize = 1000;
+
+/// Schema used for files sitting directly in the dataset folder; files in a
+/// subfolder use the subfolder name, so partitioned layouts group naturally.
+const ROOT_SCHEMA: &str = "files";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum InferredType {
+    Bit,
+    Int,
+    BigInt,
+    Float,
+    Date,
+    DateTime,
+    Text,
+}
+
+#[derive(Debug, Default)]
+struct ColumnStats {
+    inferred: Option<InferredType>,
+    max_length: usize,
+    has_empty: bool,
+}
+
+impl ColumnStats {
+    fn observe(&mut self, value: &str) {
+        let value = value.trim();
+        if value.is_empty() {
+            self.has_empty = true;
+            return;
+        }
+        self.max_length = self.max_length.max(value.chars().count());
+        let kind = infer_value(value);
+        self.inferred = Some(match self.inferred {
+            None => kind,
+            Some(current) => widen(current, kind),
+        });
+    }
+
+    fn data_type(&self) -> String {
+        match self.inferred {
+            Some(InferredType::Bit) => "bit".to_string(),
+            Some(InferredType::Int) => "int".to_string(),
+            Some(InferredType::BigInt) => "bigint".to_string(),
+            Some(InferredType::Float) => "float".to_string(),
+            Some(InferredType::Date) => "date".to_string(),
+            Some(InferredType::DateTime) => "datetime2".to_string(),
+            Some(InferredType::Text) if self.max_length <= 4000 => {
+                format!("nvarchar({})", self.max_length.max(1))
+            }
+            Some(InferredType::Text) | None => "nvarchar(max)".to_string(),
+        }
+    }
+}
+
+fn infer_value(value: &str) -> InferredType {
+    if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+        InferredType::Bit
+    } else if let Ok(n) = value.parse::<i64>() {
+        if i32::try_from(n).is_ok() {
+            InferredType::Int
+        } else {
+            InferredType::BigInt
+        }
+    } else if value.parse::<f64>().is_ok() {
+        InferredType::Float
+    } else if chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok() {
+        InferredType::Date
+    } else if value.parse::<chrono::NaiveDateTime>().is_ok()
+        || chrono::DateTime::parse_from_rfc3339(value).is_ok()
+    {
+        InferredType::DateTime
+    } else {
+        InferredType::Text
+    }
+}
+
+/// The narrowest type that holds values of both kinds.
+fn widen(a: InferredType, b: InferredType) -> InferredType {
+    use InferredType::*;
+    match (a.min(b), a.max(b)) {
+        (x, y) if x == y => x,
+        (Int, BigInt) => BigInt,
+        (Int | BigInt, Float) => Float,
+        (Date, DateTime) => DateTime,
+        _ => Text,
+    }
+}
+
+fn csv_columns(path: &Path, interner: &mut Interner) -> Result<Vec<Column>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_path(path)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("{}: {}", path.display(), e))?
+        .clone();
+
+    let mut stats: Vec<ColumnStats> = headers.iter().map(|_| ColumnStats::default()).collect();
+    for record in reader.records().take(CSV_SAMPLE_ROWS) {
+        let record = record.map_err(|e| format!("{}: {}", path.display(), e))?;
+        for (i, column) in stats.iter_mut().enumerate() {
+            column.observe(record.get(i).unwrap_or_default());
+        }
+    }
+
+    Ok(headers
+        .iter()
+        .zip(stats)
+        .enumerate()
+        .map(|(i, (name, stats))| Column {
+            name: name.trim().to_string(),
+            data_type: interner.intern(&stats.data_type()),
+            is_nullable: stats.has_empty || stats.inferred.is_none(),
+            ordinal_position: i as u32 + 1,
+            ..Default::default()
+        })
+        .collect())
+}
+
+fn parquet_type(column: &parquet::schema::types::ColumnDescriptor) -> String {
+    match (column.physical_type(), column.logical_type()) {
+        (_, Some(LogicalType::Decimal { scale, precision })) => {
+            format!("decimal({},{})", precision, scale)
+        }
+        (_, Some(LogicalType::String | LogicalType::Enum | LogicalType::Json)) => {
+            "nvarchar(max)".to_string()
+        }
+        (_, Some(LogicalType::Uuid)) => "uniqueidentifier".to_string(),
+        (_, Some(LogicalType::Date)) => "date".to_string(),
+        (_, Some(LogicalType::Time { .. })) => "time".to_string(),
+        (_, Some(LogicalType::Timestamp { .. })) | (PhysicalType::INT96, _) => {
+            "datetime2".to_string()
+        }
+        (_, Some(LogicalType::Integer { bit_width: 8, .. })) => "tinyint".to_string(),
+        (_, Some(LogicalType::Integer { bit_width: 16, .. })) => "smallint".to_string(),
+        (PhysicalType::BOOLEAN, _) => "bit".to_string(),
+        (PhysicalType::INT32, _) => "int".to_string(),
+        (PhysicalType::INT64, _) => "bigint".to_string(),
+        (PhysicalType::FLOAT, _) => "real".to_string(),
+        (PhysicalType::DOUBLE, _) => "float".to_string(),
+        (PhysicalType::FIXED_LEN_BYTE_ARRAY, _) => format!("binary({})", column.type_length()),
+        // Older writers only set the legacy converted type on strings
+        (PhysicalType::BYTE_ARRAY, _)
+            if matches!(
+                column.converted_type(),
+                ConvertedType::UTF8 | ConvertedType::ENUM | ConvertedType::JSON
+            ) =>
+        {
+            "nvarchar(max)".to_string()
+        }
+        (PhysicalType::BYTE_ARRAY, _) => "varbinary(max)".to_string(),
+    }
+}
+
+fn parquet_columns(path: &Path, interner: &mut Interner) -> Result<Vec<Column>, String> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let reader =
+        SerializedFileReader::new(file).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let schema = reader.metadata().file_metadata().schema_descr();
+
+    Ok(schema
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(i, column)| Column {
+            // Nested fields are flattened to their dotted path
+            name: column.path().string(),
+            data_type: interner.intern(&parquet_type(column)),
+            is_nullable: column.max_def_level() > 0,
+            ordinal_position: i as u32 + 1,
+            ..Default::default()
+        })
+        .collect())
+}
+
+/// Introspect a folder of CSV and Parquet files, treating each file as a table.
+/// CSV column types are inferred from a sample of rows; Parquet types come from
+/// the file footer. Other files are ignored, and files that fail to read are
+/// skipped with an `UnreadableFile` warning.
+pub fn load_flat_files(dir: &Path) -> Result<SchemaGraph, String> {
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", dir.display()));
+    }
+
+    let mut interner = Interner::default();
+    let mut tables: HashMap<String, TableNode> = HashMap::new();
+    let mut warnings = Vec::new();
+
+    for entry in WalkDir::new(dir)
+        .min_depth(1)
+        .max_depth(2)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+    {
+        let path = entry.path();
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        let columns = match extension.as_deref() {
+            Some("csv") => csv_columns(path, &mut interner),
+            Some("parquet") => parquet_columns(path, &mut interner),
+            _ => continue,
+        };
+
+        let schema = if entry.depth() == 1 {
+            ROOT_SCHEMA.to_string()
+        } else {
+            path.parent()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| ROOT_SCHEMA.to_string())
+        };
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let id = object_id(&[&schema, &name]);
+
+        let columns = match columns {
+            Ok(columns) => columns,
+            Err(message) => {
+                warnings.push(GraphWarning {
+                    kind: GraphWarningKind::UnreadableFile,
+                    object_id: id,
+                    missing: path.display().to_string(),
+                    message,
+                });
+                continue;
+            }
+        };
+
+        // `orders.csv` next to `orders.parquet` is the same dataset; first one wins
+        tables.entry(id.clone()).or_insert_with(|| TableNode {
+            id,
+            name,
+            schema: interner.intern(&schema),
+            columns,
+            source: None,
+            metadata: None,
+            change_capture: None,
+        });
+    }
+
+    let mut tables: Vec<TableNode> = tables.into_values().collect();
+    tables.sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    Ok(SchemaGraph {
+        tables,
+        warnings,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+    use std::fs;
+    use std::sync::Arc;
+
+    fn column<'a>(table: &'a TableNode, name: &str) -> &'a Column {
+        table.columns.iter().find(|c| c.name == name).unwrap()
+    }
+
+    #[test]
+    fn csv_types_are_inferred_from_sample() {
+        assert_eq!(
+            widen(InferredType::Int, InferredType::BigInt),
+            InferredType::BigInt
+        );
+        assert_eq!(
+            widen(InferredType::Int, InferredType::Float),
+            InferredType::Float
+        );
+        assert_eq!(
+            widen(InferredType::Date, InferredType::Bit),
+            InferredType::Text
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("orders.csv"),
+            "id,total,placed_on,note,active\n1,9.50,2024-01-02,,true\n3000000000,12,2024-02-03,gift,false\n",
+        )
+        .unwrap();
+        fs::create_dir(dir.path().join("raw")).unwrap();
+        fs::write(dir.path().join("raw").join("events.CSV"), "kind\nclick\n").unwrap();
+        fs::write(dir.path().join("readme.txt"), "ignored").unwrap();
+
+        fs::write(dir.path().join("broken.parquet"), "not parquet").unwrap();
+
+        let graph = load_flat_files(dir.path()).unwrap();
+        let ids: Vec<&str> = graph.tables.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["files.orders", "raw.events"]);
+        assert_eq!(graph.warnings.len(), 1);
+        assert_eq!(graph.warnings[0].kind, GraphWarningKind::UnreadableFile);
+        assert_eq!(graph.warnings[0].object_id, "files.broken");
+
+        let orders = &graph.tables[0];
+        assert_eq!(&*column(orders, "id").data_type, "bigint");
+        assert_eq!(&*column(orders, "total").data_type, "float");
+        assert_eq!(&*column(orders, "placed_on").data_type, "date");
+        assert_eq!(&*column(orders, "active").data_type, "bit");
+        let note = column(orders, "note");
+        assert_eq!(&*note.data_type, "nvarchar(4)");
+        assert!(note.is_nullable);
+        assert!(!column(orders, "id").is_nullable);
+        assert_eq!(column(orders, "note").ordinal_position, 4);
+    }
+
+    #[test]
+    fn parquet_schema_is_read_from_footer() {
+        let dir = tempfile::tempdir().unwrap();
+        let schema = Arc::new(
+            parse_message_type(
+                "message customers {
+                    REQUIRED INT64 id;
+                    OPTIONAL BYTE_ARRAY name (UTF8);
+                    OPTIONAL INT32 signup (DATE);
+                    OPTIONAL FIXED_LEN_BYTE_ARRAY(16) balance (DECIMAL(18,2));
+                }",
+            )
+            .unwrap(),
+        );
+        let file = File::create(dir.path().join("customers.parquet")).unwrap();
+        let writer =
+            SerializedFileWriter::new(file, schema, Arc::new(WriterProperties::default())).unwrap();
+        writer.close().unwrap();
+
+        let graph = load_flat_files(dir.path()).unwrap();
+        let table = &graph.tables[0];
+        assert_eq!(table.id, "files.customers");
+        let types: Vec<(&str, &str, bool)> = table
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), &*c.data_type, c.is_nullable))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ("id", "bigint", false),
+                ("name", "nvarchar(max)", true),
+                ("signup", "date", true),
+                ("balance", "decimal(18,2)", true),
+            ]
+        );
+    }
+}
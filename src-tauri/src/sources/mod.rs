@@ -0,0 +1,43 @@
+//! Loaders for schema sources other than a live SQL Server connection.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::db::{object_id, reference_pattern, split_multipart, READ_PATTERNS};
+
+pub mod dbt;
+pub mod flat_files;
+pub mod reports;
+pub mod tabular;
+
+pub use dbt::load_dbt_manifest;
+pub use flat_files::load_flat_files;
+pub use reports::scan_reports;
+pub use tabular::load_tabular_model;
+
+static EXEC_PATTERN: Lazy<Regex> = Lazy::new(|| reference_pattern(r"EXEC(?:UTE)?"));
+
+/// Graph IDs of the objects an external SQL text (report dataset, model
+/// partition) reads from or executes. Without a loaded graph to resolve
+/// against, unqualified names are assumed to live in `dbo`.
+pub(crate) fn referenced_objects(sql: &str) -> Vec<String> {
+    let mut ids = Vec::new();
+    for pattern in READ_PATTERNS.iter().chain([&*EXEC_PATTERN]) {
+        for cap in pattern.captures_iter(sql) {
+            if let Some(id) = qualified_id(&cap[1]) {
+                ids.push(id);
+            }
+        }
+    }
+    ids
+}
+
+/// `[db].[schema].[name]`, `schema.name` or `name` as a `schema.name` ID.
+pub(crate) fn qualified_id(name: &str) -> Option<String> {
+    let parts = split_multipart(name);
+    match parts.as_slice() {
+        [.., schema, object] if !schema.is_empty() => Some(object_id(&[schema, object])),
+        [.., object] if !object.is_empty() => Some(object_id(&["dbo", object])),
+        _ => None,
+    }
+}
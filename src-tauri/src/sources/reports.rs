@@ -0,0 +1,178 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use walkdir::WalkDir;
+
+use crate::sources::tabular::parse_tabular_model;
+use crate::sources::{qualified_id, referenced_objects};
+use crate::types::{ReportScan, ReportUsage};
+
+/// Datasets of an SSRS report definition as `(dataset, object IDs)`.
+/// Stored-procedure datasets name the procedure directly in `CommandText`.
+fn rdl_datasets(xml: &str) -> Result<Vec<(String, Vec<String>)>, String> {
+    let document = roxmltree::Document::parse(xml).map_err(|e| e.to_string())?;
+    let child_text = |node: roxmltree::Node, name: &str| {
+        node.children()
+            .find(|c| c.tag_name().name() == name)
+            .and_then(|c| c.text())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    Ok(document
+        .descendants()
+        .filter(|n| n.tag_name().name() == "DataSet")
+        .filter_map(|dataset| {
+            let query = dataset
+                .children()
+                .find(|c| c.tag_name().name() == "Query")?;
+            let command = child_text(query, "CommandText");
+            let objects = if child_text(query, "CommandType") == "StoredProcedure" {
+                qualified_id(&command).into_iter().collect()
+            } else {
+                referenced_objects(&command)
+            };
+            let name = dataset.attribute("Name").unwrap_or_default().to_string();
+            Some((name, objects))
+        })
+        .collect())
+}
+
+/// Datasets of a Power BI template. The embedded `DataModelSchema` is a
+/// tabular model stored as UTF-16LE JSON.
+fn pbit_datasets(path: &Path) -> Result<Vec<(String, Vec<String>)>, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let mut entry = archive
+        .by_name("DataModelSchema")
+        .map_err(|_| "template has no DataModelSchema".to_string())?;
+    let mut bytes = Vec::new();
+    entry.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+
+    let (json, _) = encoding_rs::UTF_16LE.decode_with_bom_removal(&bytes);
+    Ok(parse_tabular_model(&json)?
+        .into_iter()
+        .map(|consumer| (consumer.model_table, vec![consumer.object_id]))
+        .collect())
+}
+
+/// Scan a folder (recursively) of `.rdl` and `.pbit` files for the database
+/// objects their datasets use. Files that fail to parse are listed in
+/// `skipped` rather than failing the scan.
+pub fn scan_reports(dir: &Path) -> Result<ReportScan, String> {
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", dir.display()));
+    }
+
+    let mut scan = ReportScan::default();
+    for entry in WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+    {
+        let path = entry.path();
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        let datasets = match extension.as_deref() {
+            Some("rdl") => std::fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|xml| rdl_datasets(xml.trim_start_matches('\u{feff}'))),
+            Some("pbit") => pbit_datasets(path),
+            _ => continue,
+        };
+        let datasets = match datasets {
+            Ok(datasets) => datasets,
+            Err(e) => {
+                scan.skipped.push(format!("{}: {}", path.display(), e));
+                continue;
+            }
+        };
+
+        let report = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        for (dataset, objects) in datasets {
+            for object_id in objects {
+                let usage = ReportUsage {
+                    object_id,
+                    report: report.clone(),
+                    report_path: path.display().to_string(),
+                    dataset: dataset.clone(),
+                };
+                if !scan.usages.contains(&usage) {
+                    scan.usages.push(usage);
+                }
+            }
+        }
+    }
+
+    Ok(scan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const RDL: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<Report xmlns="http://schemas.microsoft.com/sqlserver/reporting/2016/01/reportdefinition">
+  <DataSets>
+    <DataSet Name="Orders">
+      <Query>
+        <DataSourceName>DW</DataSourceName>
+        <CommandText>SELECT o.Id FROM sales.Orders o INNER JOIN [dbo].[Customers] c ON c.Id = o.CustomerId WHERE o.Total &gt; 0</CommandText>
+      </Query>
+    </DataSet>
+    <DataSet Name="Totals">
+      <Query>
+        <DataSourceName>DW</DataSourceName>
+        <CommandType>StoredProcedure</CommandType>
+        <CommandText>reporting.usp_Totals</CommandText>
+      </Query>
+    </DataSet>
+  </DataSets>
+</Report>"#;
+
+    #[test]
+    fn rdl_and_pbit_datasets_are_scanned() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Sales.rdl"), RDL).unwrap();
+        std::fs::write(dir.path().join("Broken.rdl"), "<Report>").unwrap();
+
+        let model = r#"{ "name": "6d1f", "model": { "tables": [{
+            "name": "Products",
+            "partitions": [{ "source": { "type": "m", "expression":
+                "let S = Sql.Database(\"srv\", \"dw\"), P = S{[Schema=\"dbo\",Item=\"Products\"]}[Data] in P" } }]
+        }] } }"#;
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend(model.encode_utf16().flat_map(u16::to_le_bytes));
+        let mut zip = zip::ZipWriter::new(File::create(dir.path().join("Catalog.pbit")).unwrap());
+        zip.start_file("DataModelSchema", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(&utf16).unwrap();
+        zip.finish().unwrap();
+
+        let scan = scan_reports(dir.path()).unwrap();
+        let usages: Vec<(&str, &str, &str)> = scan
+            .usages
+            .iter()
+            .map(|u| (u.report.as_str(), u.dataset.as_str(), u.object_id.as_str()))
+            .collect();
+        assert_eq!(
+            usages,
+            vec![
+                ("Catalog", "Products", "dbo.Products"),
+                ("Sales", "Orders", "sales.Orders"),
+                ("Sales", "Orders", "dbo.Customers"),
+                ("Sales", "Totals", "reporting.usp_Totals"),
+            ]
+        );
+        assert_eq!(scan.skipped.len(), 1);
+        assert!(scan.skipped[0].contains("Broken.rdl"));
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::db::object_id;
+use crate::sources::referenced_objects;
+use crate::types::ModelConsumer;
+
+/// Power Query navigation step, e.g. `Source{[Schema="dbo",Item="FactSales"]}[Data]`.
+static M_NAVIGATION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)\[\s*Schema\s*=\s*"((?:[^"]|"")*)"\s*,\s*Item\s*=\s*"((?:[^"]|"")*)"\s*\]"#)
+        .unwrap()
+});
+
+/// Partition expressions and queries may be a string or an array of lines.
+fn text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(lines)) => lines
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Database objects a partition reads from, as graph IDs.
+fn partition_sources(partition: &Value) -> Vec<String> {
+    let source = &partition["source"];
+    let mut ids = Vec::new();
+
+    let expression = text(source.get("expression"));
+    for cap in M_NAVIGATION.captures_iter(&expression) {
+        ids.push(object_id(&[
+            &cap[1].replace("\"\"", "\""),
+            &cap[2].replace("\"\"", "\""),
+        ]));
+    }
+
+    // Query partitions, and native queries embedded in M expressions
+    let query = text(source.get("query"));
+    ids.extend(referenced_objects(&query));
+    ids.extend(referenced_objects(&expression));
+
+    ids
+}
+
+/// Find the `model` object in either a Model.bim file or a TMSL
+/// `create`/`createOrReplace` script, along with the model's display name.
+fn find_model(root: &Value) -> Option<(String, &Value)> {
+    if let Some(model) = root.get("model") {
+        let name = root["name"]
+            .as_str()
+            .or(model["name"].as_str())
+            .unwrap_or("Model");
+        return Some((name.to_string(), model));
+    }
+    ["createOrReplace", "create", "alter"]
+        .iter()
+        .find_map(|command| root.get(*command))
+        .and_then(|command| command.get("database"))
+        .and_then(find_model)
+}
+
+/// Map each table of a tabular model back to the database objects its
+/// partitions load from. Calculated tables have no database source and are
+/// skipped.
+pub fn parse_tabular_model(json: &str) -> Result<Vec<ModelConsumer>, String> {
+    let root: Value =
+        serde_json::from_str(json).map_err(|e| format!("Invalid tabular model: {}", e))?;
+    let (model_name, model) =
+        find_model(&root).ok_or("No tabular model found; expected Model.bim or TMSL")?;
+
+    let mut consumers: BTreeMap<(String, String), ModelConsumer> = BTreeMap::new();
+    for table in model["tables"].as_array().into_iter().flatten() {
+        let table_name = table["name"].as_str().unwrap_or_default();
+        let measures: Vec<String> = table["measures"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|m| m["name"].as_str().map(str::to_string))
+            .collect();
+        let columns: Vec<String> = table["columns"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|c| c["sourceColumn"].as_str().map(str::to_string))
+            .collect();
+
+        for partition in table["partitions"].as_array().into_iter().flatten() {
+            for id in partition_sources(partition) {
+                consumers
+                    .entry((id.clone(), table_name.to_string()))
+                    .or_insert_with(|| ModelConsumer {
+                        object_id: id,
+                        model: model_name.clone(),
+                        model_table: table_name.to_string(),
+                        measures: measures.clone(),
+                        columns: columns.clone(),
+                    });
+            }
+        }
+    }
+
+    Ok(consumers.into_values().collect())
+}
+
+pub fn load_tabular_model(path: &Path) -> Result<Vec<ModelConsumer>, String> {
+    let json = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    // Visual Studio saves Model.bim with a UTF-8 BOM
+    parse_tabular_model(json.trim_start_matches('\u{feff}'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn model_bim_maps_tables_to_sources() {
+        let bim = r#"{
+            "name": "SalesModel",
+            "model": {
+                "tables": [
+                    {
+                        "name": "Sales",
+                        "columns": [
+                            { "name": "Amount", "sourceColumn": "SalesAmount" },
+                            { "name": "Margin", "type": "calculated", "expression": "1" }
+                        ],
+                        "measures": [{ "name": "Total Sales", "expression": "SUM(Sales[Amount])" }],
+                        "partitions": [{
+                            "name": "Sales",
+                            "source": {
+                                "type": "m",
+                                "expression": [
+                                    "let",
+                                    "    Source = Sql.Database(\"srv\", \"dw\"),",
+                                    "    Fact = Source{[Schema=\"sales\",Item=\"FactSales\"]}[Data]",
+                                    "in Fact"
+                                ]
+                            }
+                        }]
+                    },
+                    {
+                        "name": "Customer",
+                        "partitions": [{
+                            "name": "Customer",
+                            "source": { "type": "query", "query": "SELECT * FROM [dbo].[Dim Customer] c JOIN Geography g ON 1 = 1" }
+                        }]
+                    },
+                    {
+                        "name": "Dates",
+                        "partitions": [{ "source": { "type": "calculated", "expression": "CALENDARAUTO()" } }]
+                    }
+                ]
+            }
+        }"#;
+
+        let consumers = parse_tabular_model(bim).unwrap();
+        let summary: Vec<(&str, &str)> = consumers
+            .iter()
+            .map(|c| (c.object_id.as_str(), c.model_table.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("dbo.Dim Customer", "Customer"),
+                ("dbo.Geography", "Customer"),
+                ("sales.FactSales", "Sales"),
+            ]
+        );
+        let sales = &consumers[2];
+        assert_eq!(sales.model, "SalesModel");
+        assert_eq!(sales.measures, vec!["Total Sales"]);
+        assert_eq!(sales.columns, vec!["SalesAmount"]);
+    }
+
+    #[test]
+    fn tmsl_scripts_are_unwrapped() {
+        let tmsl = r#"{ "createOrReplace": {
+            "object": { "database": "Finance" },
+            "database": { "name": "Finance", "model": { "tables": [{
+                "name": "Ledger",
+                "partitions": [{ "source": { "query": "select * from gl.Ledger" } }]
+            }] } }
+        } }"#;
+        let consumers = parse_tabular_model(tmsl).unwrap();
+        assert_eq!(consumers.len(), 1);
+        assert_eq!(consumers[0].model, "Finance");
+        assert_eq!(consumers[0].object_id, "gl.Ledger");
+
+        assert!(parse_tabular_model(r#"{ "tables": [] }"#).is_err());
+    }
+}
@@ -1,6 +1,26 @@
+use crate::credentials::store_proxy_password;
+use crate::db::QueryLimits;
+use crate::export_themes::{validate_export_theme, ExportTheme};
+use crate::graph_patch::SchemaPatch;
+use crate::i18n::{self, t};
+use crate::inflight::InFlight;
+use crate::onboarding::OnboardingState;
+use crate::profiles::StartupAction;
+use crate::sandboxes::SandboxRegistry;
+use crate::search_index::SearchIndexes;
+use crate::session::write_session;
+use crate::tasks::TaskManager;
+use crate::types::SchemaGraph;
+use crate::windows::WindowRegistry;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+/// How often settings.json is checked for edits made outside the app.
+const SETTINGS_POLL: Duration = Duration::from_secs(2);
 
 #[derive(Default, Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -14,6 +34,92 @@ pub struct FolderSource {
     pub favorites: Vec<String>,
 }
 
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProxyMode {
+    #[default]
+    None,
+    System,
+    Manual,
+}
+
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxySettings {
+    #[serde(default)]
+    pub mode: ProxyMode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Only set on its way in: saving moves it to the system keychain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+impl ProxySettings {
+    /// Moves a password typed into the settings to the keychain, so
+    /// settings.json never holds it. Left in place if the keychain fails or
+    /// there is no username to store it under.
+    fn stash_password(&mut self) -> Result<(), String> {
+        let Some(password) = self.password.as_deref() else {
+            return Ok(());
+        };
+        let username = self
+            .username
+            .as_deref()
+            .filter(|u| !u.trim().is_empty())
+            .ok_or_else(|| "Proxy password requires a username".to_string())?;
+        store_proxy_password(username, password)?;
+        self.password = None;
+        Ok(())
+    }
+}
+
+/// Diagram display preferences for one schema.
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaDisplay {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub collapsed: bool,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+/// Key for per-connection settings; server names are case-insensitive and
+/// database names are under the default collation.
+pub fn connection_key(server: &str, database: &str) -> String {
+    format!("{}/{}", server.trim(), database.trim()).to_lowercase()
+}
+
+/// `excluded_objects` key for patterns that apply to every connection.
+pub const ALL_CONNECTIONS: &str = "*";
+
+/// Recently opened databases remembered per server.
+const MAX_RECENT_DATABASES: usize = 10;
+
+/// File name stem for a data file keyed by `key` in the app data directory.
+/// Anything but ASCII letters and digits is escaped as `_xx` per UTF-8 byte,
+/// so distinct keys such as `a-b/db` and `a_b/db` never share a file.
+pub fn file_stem(key: &str) -> String {
+    let mut stem = String::new();
+    for byte in key.bytes() {
+        if byte.is_ascii_alphanumeric() {
+            stem.push(byte as char);
+        } else {
+            stem.push_str(&format!("_{:02x}", byte));
+        }
+    }
+    stem
+}
+
+/// File name stem for per-connection data files in the app data directory.
+pub fn connection_file_stem(server: &str, database: &str) -> String {
+    file_stem(&connection_key(server, database))
+}
+
 #[derive(Default, Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct AppSettings {
@@ -31,11 +137,77 @@ pub struct AppSettings {
     pub folder_sources: Vec<FolderSource>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub explorer_sidebar_width: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_channel: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_check_interval_hours: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_remind_after: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skipped_update_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_update_check_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxySettings>,
+    /// Language tag for backend-generated text; unset means English.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Selected export theme id; unset means the default theme.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export_theme: Option<String>,
+    /// Overrides the selected theme's font scale.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export_font_scale: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_export_themes: Vec<ExportTheme>,
+    /// Per-schema display preferences, keyed by `connection_key` then schema.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub schema_display: BTreeMap<String, BTreeMap<String, SchemaDisplay>>,
+    /// Object exclusion patterns keyed by `connection_key`, or
+    /// `ALL_CONNECTIONS` for patterns applied to every connection.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub excluded_objects: BTreeMap<String, Vec<String>>,
+    /// Databases opened on each server, most recent first, keyed by the
+    /// lowercased server name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub recent_databases: BTreeMap<String, Vec<String>>,
+    /// Reopen the previous session's connections from cache on launch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restore_session: Option<bool>,
+    /// Per-profile action on connect, keyed by `connection_key`; missing
+    /// entries load the schema.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub startup_actions: BTreeMap<String, StartupAction>,
+    /// Per-profile query caps, keyed by `connection_key`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub query_limits: BTreeMap<String, QueryLimits>,
+    /// Refuse everything that changes a server: committed procedure runs and
+    /// sandbox databases.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_writes: Option<bool>,
+    /// Guided tour progress. Unset for installs that predate the tour, which
+    /// count as having completed it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub onboarding: Option<OnboardingState>,
 }
 
 pub struct AppState {
     pub settings: Mutex<AppSettings>,
+    /// settings.json as last read or written here, so the watcher can tell
+    /// an outside edit from our own save. Locked after `settings`.
+    settings_on_disk: Mutex<Option<String>>,
     pub storage_path: PathBuf,
+    pub tasks: TaskManager,
+    pub windows: WindowRegistry,
+    pub sandboxes: SandboxRegistry,
+    pub search_indexes: SearchIndexes,
+    /// Schema loads running now, so a repeated request joins the first.
+    pub schema_loads: InFlight<(SchemaGraph, Option<SchemaPatch>)>,
+    /// No settings file existed at launch.
+    first_run: bool,
+    /// Launched with `--safe-mode`: default settings that are never saved, no
+    /// session restore or cached graphs, and no watchers or automatic checks.
+    safe_mode: bool,
 }
 
 #[derive(Default, Serialize, Deserialize, Clone, Debug)]
@@ -48,28 +220,114 @@ pub struct AppSettingsUpdate {
     pub show_mini_map: Option<bool>,
     pub folder_sources: Option<Vec<FolderSource>>,
     pub explorer_sidebar_width: Option<f64>,
+    pub update_channel: Option<String>,
+    pub update_check_interval_hours: Option<u32>,
+    pub update_remind_after: Option<String>,
+    pub skipped_update_version: Option<String>,
+    pub proxy: Option<ProxySettings>,
+    /// An empty string clears the language back to the default.
+    pub language: Option<String>,
+    pub export_theme: Option<String>,
+    pub export_font_scale: Option<f64>,
+    pub custom_export_themes: Option<Vec<ExportTheme>>,
+    pub restore_session: Option<bool>,
+    pub block_writes: Option<bool>,
 }
 
 impl AppState {
     pub fn new(storage_path: PathBuf) -> Self {
-        let settings = Self::read_settings(&storage_path).unwrap_or_default();
-        Self {
+        Self::build(storage_path, false)
+    }
+
+    pub fn new_safe_mode(storage_path: PathBuf) -> Self {
+        Self::build(storage_path, true)
+    }
+
+    fn build(storage_path: PathBuf, safe_mode: bool) -> Self {
+        let first_run = !safe_mode && !storage_path.join("settings.json").exists();
+        let on_disk = match safe_mode {
+            true => None,
+            false => std::fs::read_to_string(storage_path.join("settings.json")).ok(),
+        };
+        let mut settings: AppSettings = on_disk
+            .as_deref()
+            .and_then(|content| serde_json::from_str(content).ok())
+            .unwrap_or_default();
+        if first_run {
+            settings.onboarding = Some(OnboardingState::default());
+        }
+        // Settings from before the keychain was used may hold the password
+        let stashed = !safe_mode
+            && settings
+                .proxy
+                .as_mut()
+                .filter(|p| p.password.is_some())
+                .is_some_and(|p| p.stash_password().is_ok());
+        i18n::set_language(settings.language.as_deref());
+        let state = Self {
             settings: Mutex::new(settings),
+            settings_on_disk: Mutex::new(on_disk),
             storage_path,
+            tasks: TaskManager::default(),
+            windows: WindowRegistry::default(),
+            sandboxes: SandboxRegistry::default(),
+            search_indexes: SearchIndexes::default(),
+            schema_loads: InFlight::default(),
+            first_run,
+            safe_mode,
+        };
+        if stashed {
+            let _ = state.save_settings();
         }
+        state
     }
 
-    fn read_settings(storage_path: &PathBuf) -> Option<AppSettings> {
-        let settings_file = storage_path.join("settings.json");
-        if settings_file.exists() {
-            let content = std::fs::read_to_string(&settings_file).ok()?;
-            serde_json::from_str(&content).ok()
-        } else {
-            None
+    pub fn is_first_run(&self) -> bool {
+        self.first_run
+    }
+
+    pub fn is_safe_mode(&self) -> bool {
+        self.safe_mode
+    }
+
+    /// Checked by commands that start background work.
+    pub fn ensure_not_safe_mode(&self) -> Result<(), String> {
+        if self.safe_mode {
+            return Err(t!("error-safe-mode"));
         }
+        Ok(())
+    }
+
+    /// Checked first by every command that changes annotations, layouts or
+    /// other saved state. A window viewing a shared bundle or snapshot is
+    /// read-only; other windows are not affected.
+    pub fn ensure_writable(&self, window: &str) -> Result<(), String> {
+        if self.windows.is_read_only(window) {
+            return Err(t!("error-read-only-session"));
+        }
+        Ok(())
+    }
+
+    /// Checked by every command that changes a server, on top of
+    /// `ensure_writable` where saved state changes too.
+    pub fn ensure_writes_allowed(&self) -> Result<(), String> {
+        let blocked = self
+            .settings
+            .lock()
+            .map_err(|e| e.to_string())?
+            .block_writes
+            .unwrap_or(false);
+        if blocked {
+            return Err(t!("error-writes-blocked"));
+        }
+        Ok(())
     }
 
     pub fn save_settings(&self) -> Result<(), String> {
+        // Leave the settings file that may have caused the crash untouched
+        if self.safe_mode {
+            return Ok(());
+        }
         let settings = self.settings.lock().map_err(|e| e.to_string())?;
 
         // Ensure directory exists
@@ -82,12 +340,43 @@ impl AppState {
         let content = serde_json::to_string_pretty(&*settings)
             .map_err(|e| format!("Failed to serialize settings: {}", e))?;
 
-        std::fs::write(&settings_file, content)
+        // Replace the file in one step so neither the watcher nor a sync
+        // tool ever reads it half written
+        let temp_file = self.storage_path.join("settings.json.tmp");
+        std::fs::write(&temp_file, &content)
+            .and_then(|_| std::fs::rename(&temp_file, &settings_file))
             .map_err(|e| format!("Failed to write settings: {}", e))?;
+        *self.settings_on_disk.lock().map_err(|e| e.to_string())? = Some(content);
 
         Ok(())
     }
 
+    /// Pick up settings.json if something other than this app changed it.
+    /// Returns the new settings, or `None` when the file is unchanged or
+    /// missing. A file that doesn't parse leaves the current settings in
+    /// place and is reported once, not on every check.
+    pub fn reload_settings_if_changed(&self) -> Result<Option<AppSettings>, String> {
+        if self.safe_mode {
+            return Ok(None);
+        }
+        let mut settings = self.settings.lock().map_err(|e| e.to_string())?;
+        let mut on_disk = self.settings_on_disk.lock().map_err(|e| e.to_string())?;
+        let settings_file = self.storage_path.join("settings.json");
+        let Ok(content) = std::fs::read_to_string(&settings_file) else {
+            return Ok(None);
+        };
+        if on_disk.as_deref() == Some(content.as_str()) {
+            return Ok(None);
+        }
+        let reloaded = serde_json::from_str::<AppSettings>(&content);
+        *on_disk = Some(content);
+        let reloaded = reloaded.map_err(|e| format!("Failed to parse settings: {}", e))?;
+
+        i18n::set_language(reloaded.language.as_deref());
+        *settings = reloaded.clone();
+        Ok(Some(reloaded))
+    }
+
     pub fn get_settings(&self) -> Result<AppSettings, String> {
         let settings = self.settings.lock().map_err(|e| e.to_string())?;
         Ok(settings.clone())
@@ -117,6 +406,42 @@ impl AppState {
         if let Some(explorer_sidebar_width) = update.explorer_sidebar_width {
             settings.explorer_sidebar_width = Some(explorer_sidebar_width);
         }
+        if let Some(update_channel) = update.update_channel {
+            settings.update_channel = Some(update_channel);
+        }
+        if let Some(interval) = update.update_check_interval_hours {
+            settings.update_check_interval_hours = Some(interval);
+        }
+        if let Some(remind_after) = update.update_remind_after {
+            settings.update_remind_after = Some(remind_after);
+        }
+        if let Some(skipped) = update.skipped_update_version {
+            settings.skipped_update_version = Some(skipped);
+        }
+        if let Some(mut proxy) = update.proxy {
+            proxy.stash_password()?;
+            settings.proxy = Some(proxy);
+        }
+        if let Some(language) = update.language {
+            settings.language = Some(language).filter(|l| !l.trim().is_empty());
+            i18n::set_language(settings.language.as_deref());
+        }
+        if let Some(export_theme) = update.export_theme {
+            settings.export_theme = Some(export_theme);
+        }
+        if let Some(scale) = update.export_font_scale {
+            settings.export_font_scale = Some(scale);
+        }
+        if let Some(themes) = update.custom_export_themes {
+            themes.iter().try_for_each(validate_export_theme)?;
+            settings.custom_export_themes = themes;
+        }
+        if let Some(restore_session) = update.restore_session {
+            settings.restore_session = Some(restore_session);
+        }
+        if let Some(block_writes) = update.block_writes {
+            settings.block_writes = Some(block_writes);
+        }
 
         let updated = settings.clone();
         drop(settings);
@@ -141,6 +466,216 @@ impl AppState {
         Ok(updated)
     }
 
+    /// Store preferences for one schema; default preferences remove the entry.
+    pub fn set_schema_display(
+        &self,
+        connection: &str,
+        schema: &str,
+        display: SchemaDisplay,
+    ) -> Result<BTreeMap<String, SchemaDisplay>, String> {
+        let mut settings = self.settings.lock().map_err(|e| e.to_string())?;
+
+        let schemas = settings
+            .schema_display
+            .entry(connection.to_string())
+            .or_default();
+        if display == SchemaDisplay::default() {
+            schemas.remove(schema);
+        } else {
+            schemas.insert(schema.to_string(), display);
+        }
+        let updated = schemas.clone();
+        if updated.is_empty() {
+            settings.schema_display.remove(connection);
+        }
+
+        drop(settings);
+        self.save_settings()?;
+        Ok(updated)
+    }
+
+    /// Replace the exclusion patterns for a connection (or `ALL_CONNECTIONS`).
+    pub fn set_exclusions(&self, connection: &str, patterns: Vec<String>) -> Result<(), String> {
+        let mut settings = self.settings.lock().map_err(|e| e.to_string())?;
+        if patterns.is_empty() {
+            settings.excluded_objects.remove(connection);
+        } else {
+            settings
+                .excluded_objects
+                .insert(connection.to_string(), patterns);
+        }
+        drop(settings);
+        self.save_settings()
+    }
+
+    /// Patterns in effect for a connection: global ones first.
+    pub fn exclusions_for(&self, connection: &str) -> Vec<String> {
+        let Ok(settings) = self.settings.lock() else {
+            return Vec::new();
+        };
+        [ALL_CONNECTIONS, connection]
+            .iter()
+            .filter_map(|key| settings.excluded_objects.get(*key))
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    /// Move `database` to the front of the server's recently used list.
+    pub fn record_database_use(&self, server: &str, database: &str) -> Result<(), String> {
+        let mut settings = self.settings.lock().map_err(|e| e.to_string())?;
+        let recent = settings
+            .recent_databases
+            .entry(server.trim().to_lowercase())
+            .or_default();
+        recent.retain(|d| !d.eq_ignore_ascii_case(database));
+        recent.insert(0, database.to_string());
+        recent.truncate(MAX_RECENT_DATABASES);
+        drop(settings);
+        self.save_settings()
+    }
+
+    pub fn recent_databases_for(&self, server: &str) -> Vec<String> {
+        let Ok(settings) = self.settings.lock() else {
+            return Vec::new();
+        };
+        settings
+            .recent_databases
+            .get(&server.trim().to_lowercase())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Store a profile's startup action; the default removes the entry.
+    pub fn set_startup_action(
+        &self,
+        connection: &str,
+        action: StartupAction,
+    ) -> Result<(), String> {
+        let mut settings = self.settings.lock().map_err(|e| e.to_string())?;
+        if action == StartupAction::default() {
+            settings.startup_actions.remove(connection);
+        } else {
+            settings
+                .startup_actions
+                .insert(connection.to_string(), action);
+        }
+        drop(settings);
+        self.save_settings()
+    }
+
+    pub fn startup_action_for(&self, connection: &str) -> StartupAction {
+        let Ok(settings) = self.settings.lock() else {
+            return StartupAction::default();
+        };
+        settings
+            .startup_actions
+            .get(connection)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Store a profile's query limits; all-unset limits remove the entry.
+    pub fn set_query_limits(&self, connection: &str, limits: QueryLimits) -> Result<(), String> {
+        let mut settings = self.settings.lock().map_err(|e| e.to_string())?;
+        if limits == QueryLimits::default() {
+            settings.query_limits.remove(connection);
+        } else {
+            settings
+                .query_limits
+                .insert(connection.to_string(), limits);
+        }
+        drop(settings);
+        self.save_settings()
+    }
+
+    pub fn query_limits_for(&self, connection: &str) -> QueryLimits {
+        let Ok(settings) = self.settings.lock() else {
+            return QueryLimits::default();
+        };
+        settings
+            .query_limits
+            .get(connection)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn onboarding_state(&self) -> OnboardingState {
+        let Ok(settings) = self.settings.lock() else {
+            return OnboardingState::Completed;
+        };
+        settings
+            .onboarding
+            .clone()
+            .unwrap_or(OnboardingState::Completed)
+    }
+
+    /// Replace the tour state with `update` applied to the current one.
+    pub fn update_onboarding(
+        &self,
+        update: impl FnOnce(OnboardingState) -> OnboardingState,
+    ) -> Result<OnboardingState, String> {
+        let mut settings = self.settings.lock().map_err(|e| e.to_string())?;
+        let current = settings
+            .onboarding
+            .clone()
+            .unwrap_or(OnboardingState::Completed);
+        let updated = update(current);
+        settings.onboarding = Some(updated.clone());
+        drop(settings);
+        self.save_settings()?;
+        Ok(updated)
+    }
+
+    /// Persist the current session for the next launch.
+    pub fn save_session(&self) -> Result<(), String> {
+        if self.safe_mode {
+            return Ok(());
+        }
+        write_session(&self.storage_path, &self.windows.session())
+    }
+
+    pub fn record_update_check(&self, checked_at: String) -> Result<(), String> {
+        let mut settings = self.settings.lock().map_err(|e| e.to_string())?;
+        settings.last_update_check_at = Some(checked_at);
+        drop(settings);
+        self.save_settings()
+    }
+
+    pub fn defer_update(
+        &self,
+        remind_after: Option<String>,
+        skipped_version: Option<String>,
+    ) -> Result<AppSettings, String> {
+        let mut settings = self.settings.lock().map_err(|e| e.to_string())?;
+        settings.update_remind_after = remind_after;
+        if skipped_version.is_some() {
+            settings.skipped_update_version = skipped_version;
+        }
+
+        let updated = settings.clone();
+        drop(settings);
+        self.save_settings()?;
+        Ok(updated)
+    }
+}
+
+/// Check settings.json for outside edits, such as a hand edit or a dotfile
+/// sync, and emit `settings:changed` with the reloaded settings.
+pub fn watch_settings(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        loop {
+            tokio::time::sleep(SETTINGS_POLL).await;
+            match state.reload_settings_if_changed() {
+                Ok(Some(settings)) => {
+                    let _ = app.emit("settings:changed", settings);
+                }
+                Ok(None) => {}
+                Err(e) => eprintln!("Ignoring edited settings: {}", e),
+            }
+        }
+    });
 }
 
 #[cfg(test)]
@@ -162,6 +697,7 @@ mod tests {
                 show_mini_map: Some(true),
                 folder_sources: None,
                 explorer_sidebar_width: None,
+                ..Default::default()
             })
             .expect("update settings");
 
@@ -174,6 +710,65 @@ mod tests {
         assert_eq!(settings.show_mini_map, Some(true));
     }
 
+    #[test]
+    fn read_only_session_rejects_changes() {
+        let dir = tempdir().expect("tempdir");
+        let state = AppState::new(dir.path().to_path_buf());
+        assert!(state.ensure_writable("main").is_ok());
+
+        state.windows.set_read_only("main", true);
+        assert!(state.ensure_writable("main").is_err());
+        assert!(state.ensure_writable("db-1").is_ok());
+        // A fresh start is never read-only
+        assert!(!AppState::new(dir.path().to_path_buf())
+            .windows
+            .is_read_only("main"));
+
+        state.windows.set_read_only("main", false);
+        assert!(state.ensure_writable("main").is_ok());
+    }
+
+    #[test]
+    fn safe_mode_ignores_and_preserves_saved_settings() {
+        let dir = tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("settings.json"), r#"{"theme":"light"}"#).unwrap();
+
+        let state = AppState::new_safe_mode(dir.path().to_path_buf());
+        assert!(state.get_settings().unwrap().theme.is_none());
+        assert!(state.ensure_not_safe_mode().is_err());
+
+        state
+            .update_settings(AppSettingsUpdate {
+                theme: Some("dark".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        let reloaded = AppState::new(dir.path().to_path_buf());
+        assert_eq!(
+            reloaded.get_settings().unwrap().theme.as_deref(),
+            Some("light")
+        );
+    }
+
+    #[test]
+    fn proxy_password_without_username_is_rejected() {
+        let dir = tempdir().expect("tempdir");
+        let state = AppState::new(dir.path().to_path_buf());
+
+        let result = state.update_settings(AppSettingsUpdate {
+            proxy: Some(ProxySettings {
+                mode: ProxyMode::Manual,
+                url: Some("http://proxy.corp:8080".to_string()),
+                username: Some("  ".to_string()),
+                password: Some("secret".to_string()),
+            }),
+            ..Default::default()
+        });
+
+        assert!(result.is_err());
+        assert!(state.get_settings().unwrap().proxy.is_none());
+    }
+
     #[test]
     fn folder_sources_round_trip() {
         let dir = tempdir().expect("tempdir");
@@ -205,6 +800,19 @@ mod tests {
         assert_eq!(settings.folder_sources[0].favorites, vec!["ClientA"]);
     }
 
+    #[test]
+    fn file_stems_do_not_collide() {
+        assert_eq!(connection_file_stem("SQL01", "Sales"), "sql01_2fsales");
+        assert_ne!(
+            connection_file_stem("a-b", "db"),
+            connection_file_stem("a_b", "db")
+        );
+        assert_ne!(
+            connection_file_stem("a", "b_2fc"),
+            connection_file_stem("a_2fb", "c")
+        );
+    }
+
     #[test]
     fn toggle_favorite_adds_and_removes() {
         let dir = tempdir().expect("tempdir");
@@ -233,4 +841,67 @@ mod tests {
         let updated = state.toggle_favorite("src-1", "ClientX").expect("toggle off");
         assert!(!updated.folder_sources[0].favorites.contains(&"ClientX".to_string()));
     }
+
+    #[test]
+    fn schema_display_is_keyed_by_connection() {
+        let dir = tempdir().expect("tempdir");
+        let state = AppState::new(dir.path().to_path_buf());
+        let key = connection_key("SQL01", "Sales");
+        let sales = SchemaDisplay {
+            color: Some("#3b82f6".to_string()),
+            collapsed: true,
+            hidden: false,
+        };
+
+        state
+            .set_schema_display(&key, "sales", sales.clone())
+            .unwrap();
+        state
+            .set_schema_display(
+                &key,
+                "audit",
+                SchemaDisplay {
+                    hidden: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let reloaded = AppState::new(dir.path().to_path_buf());
+        let settings = reloaded.get_settings().unwrap();
+        assert_eq!(
+            settings.schema_display[&connection_key("sql01", "sales")]["sales"],
+            sales
+        );
+
+        let remaining = state
+            .set_schema_display(&key, "audit", SchemaDisplay::default())
+            .unwrap();
+        assert_eq!(remaining.keys().collect::<Vec<_>>(), vec!["sales"]);
+    }
+
+    #[test]
+    fn outside_edits_reload_but_own_saves_do_not() {
+        let dir = tempdir().expect("tempdir");
+        let state = AppState::new(dir.path().to_path_buf());
+        state
+            .update_settings(AppSettingsUpdate {
+                theme: Some("dark".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(state.reload_settings_if_changed().unwrap().is_none());
+
+        let settings_file = dir.path().join("settings.json");
+        std::fs::write(&settings_file, r#"{"theme":"light"}"#).unwrap();
+        let reloaded = state.reload_settings_if_changed().unwrap().unwrap();
+        assert_eq!(reloaded.theme.as_deref(), Some("light"));
+        assert!(state.reload_settings_if_changed().unwrap().is_none());
+
+        // A broken edit is reported once and the last good settings stay
+        std::fs::write(&settings_file, r#"{"theme":"#).unwrap();
+        assert!(state.reload_settings_if_changed().is_err());
+        assert!(state.reload_settings_if_changed().unwrap().is_none());
+        assert_eq!(state.get_settings().unwrap().theme.as_deref(), Some("light"));
+    }
 }
@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::i18n::t;
+use crate::state::connection_file_stem;
+use crate::types::{Contact, SchemaGraph, Stewardship};
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+struct StewardshipFile {
+    assignments: Vec<Stewardship>,
+}
+
+fn stewardship_path(storage_path: &Path, server: &str, database: &str) -> PathBuf {
+    storage_path
+        .join("stewardship")
+        .join(format!("{}.json", connection_file_stem(server, database)))
+}
+
+fn read_stewardship_file(path: &Path) -> StewardshipFile {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_stewardship_file(path: &Path, file: &StewardshipFile) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create stewardship directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(file)
+        .map_err(|e| format!("Failed to serialize stewardship: {}", e))?;
+    std::fs::write(path, content).map_err(|e| format!("Failed to write stewardship: {}", e))
+}
+
+fn trimmed(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Trim a contact's fields; a contact without a name is an error unless it
+/// is entirely blank, which clears it.
+fn normalize_contact(contact: Option<Contact>) -> Result<Option<Contact>, String> {
+    let Some(contact) = contact else {
+        return Ok(None);
+    };
+    let contact = Contact {
+        name: contact.name.trim().to_string(),
+        email: trimmed(contact.email),
+        team: trimmed(contact.team),
+    };
+    if contact.name.is_empty() {
+        if contact.email.is_none() && contact.team.is_none() {
+            return Ok(None);
+        }
+        return Err(t!("error-contact-name-required"));
+    }
+    Ok(Some(contact))
+}
+
+pub fn list_stewardship(storage_path: &Path, server: &str, database: &str) -> Vec<Stewardship> {
+    read_stewardship_file(&stewardship_path(storage_path, server, database)).assignments
+}
+
+/// Set the owner and steward of a schema or table, replacing the previous
+/// entry. Clearing both removes it. Returns the updated list.
+pub fn set_stewardship(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+    assignment: Stewardship,
+) -> Result<Vec<Stewardship>, String> {
+    let schema = assignment.schema.trim().to_string();
+    if schema.is_empty() {
+        return Err(t!("error-stewardship-schema-required"));
+    }
+    let assignment = Stewardship {
+        schema,
+        object_id: trimmed(assignment.object_id),
+        owner: normalize_contact(assignment.owner)?,
+        steward: normalize_contact(assignment.steward)?,
+    };
+
+    let path = stewardship_path(storage_path, server, database);
+    let mut file = read_stewardship_file(&path);
+    let position = file.assignments.iter().position(|a| {
+        a.schema.eq_ignore_ascii_case(&assignment.schema) && a.object_id == assignment.object_id
+    });
+    let empty = assignment.owner.is_none() && assignment.steward.is_none();
+    match position {
+        Some(i) if empty => {
+            file.assignments.remove(i);
+        }
+        Some(i) => file.assignments[i] = assignment,
+        None if empty => {}
+        None => file.assignments.push(assignment),
+    }
+    write_stewardship_file(&path, &file)?;
+    Ok(file.assignments)
+}
+
+/// Attach the connection's stewardship to a freshly loaded graph, so it
+/// travels with the graph into the workspace and exports.
+pub fn apply_stewardship(
+    storage_path: &Path,
+    server: &str,
+    database: &str,
+    graph: &mut SchemaGraph,
+) {
+    graph.stewardship = list_stewardship(storage_path, server, database);
+}
+
+/// The table's own entry, or else its schema's.
+pub fn effective_stewardship<'a>(
+    assignments: &'a [Stewardship],
+    schema: &str,
+    object_id: &str,
+) -> Option<&'a Stewardship> {
+    assignments
+        .iter()
+        .find(|a| a.object_id.as_deref() == Some(object_id))
+        .or_else(|| {
+            assignments
+                .iter()
+                .find(|a| a.object_id.is_none() && a.schema.eq_ignore_ascii_case(schema))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn contact(name: &str) -> Option<Contact> {
+        Some(Contact {
+            name: name.to_string(),
+            email: Some(format!(" {}@example.com ", name.to_lowercase())),
+            team: None,
+        })
+    }
+
+    fn assignment(object_id: Option<&str>, owner: Option<Contact>) -> Stewardship {
+        Stewardship {
+            schema: "sales".to_string(),
+            object_id: object_id.map(str::to_string),
+            owner,
+            steward: None,
+        }
+    }
+
+    #[test]
+    fn tables_fall_back_to_their_schema() {
+        let dir = tempdir().expect("tempdir");
+        let root = dir.path();
+
+        set_stewardship(root, "srv", "Db", assignment(None, contact("Dana"))).unwrap();
+        let saved = set_stewardship(
+            root,
+            "srv",
+            "Db",
+            assignment(Some("sales.Orders"), contact("Lee")),
+        )
+        .unwrap();
+        assert_eq!(
+            saved[0].owner.as_ref().unwrap().email.as_deref(),
+            Some("dana@example.com")
+        );
+
+        let owner = |id: &str| {
+            effective_stewardship(&saved, "SALES", id)
+                .and_then(|s| s.owner.as_ref())
+                .map(|o| o.name.clone())
+        };
+        assert_eq!(owner("sales.Orders").as_deref(), Some("Lee"));
+        assert_eq!(owner("sales.Invoices").as_deref(), Some("Dana"));
+
+        let nameless = Some(Contact {
+            name: " ".to_string(),
+            email: None,
+            team: Some("Finance".to_string()),
+        });
+        assert!(set_stewardship(root, "srv", "Db", assignment(None, nameless)).is_err());
+
+        let remaining = set_stewardship(root, "srv", "Db", assignment(None, None)).unwrap();
+        assert_eq!(remaining.len(), 1);
+        let mut graph = SchemaGraph::default();
+        apply_stewardship(root, "srv", "Db", &mut graph);
+        assert_eq!(graph.stewardship, remaining);
+    }
+}
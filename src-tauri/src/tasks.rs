@@ -0,0 +1,289 @@
+use chrono::Utc;
+use serde::Serialize;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// Finished tasks kept for the activity pane; running tasks are never pruned.
+const MAX_FINISHED_TASKS: usize = 100;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TaskStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskInfo {
+    pub id: String,
+    pub kind: String,
+    pub label: String,
+    pub status: TaskStatus,
+    /// Fraction complete in `0.0..=1.0`, when the task can report it.
+    pub progress: Option<f64>,
+    pub message: Option<String>,
+    pub error: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+struct TaskEntry {
+    info: TaskInfo,
+    token: CancellationToken,
+}
+
+/// Handle given to the code running a task, used to observe cancellation.
+pub struct TaskHandle {
+    pub id: String,
+    token: CancellationToken,
+    active: Arc<AtomicUsize>,
+}
+
+impl Drop for TaskHandle {
+    fn drop(&mut self) {
+        self.active.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl TaskHandle {
+    /// Resolves once the task has been cancelled; intended for `tokio::select!`.
+    pub async fn cancelled(&self) {
+        self.token.cancelled().await
+    }
+}
+
+/// Registry of long-running backend work (schema loads, exports, watchers).
+#[derive(Default)]
+pub struct TaskManager {
+    next_id: AtomicU64,
+    tasks: Mutex<Vec<TaskEntry>>,
+    /// Handles not yet dropped. A cancelled task stays counted until its
+    /// work actually returns, which is what shutdown waits for.
+    active: Arc<AtomicUsize>,
+}
+
+impl TaskManager {
+    pub fn start(&self, kind: &str, label: impl Into<String>) -> TaskHandle {
+        let id = format!("task-{}", self.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+        let token = CancellationToken::new();
+        let entry = TaskEntry {
+            info: TaskInfo {
+                id: id.clone(),
+                kind: kind.to_string(),
+                label: label.into(),
+                status: TaskStatus::Running,
+                progress: None,
+                message: None,
+                error: None,
+                started_at: Utc::now().to_rfc3339(),
+                finished_at: None,
+            },
+            token: token.clone(),
+        };
+
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.push(entry);
+            prune_finished(&mut tasks);
+        }
+        self.active.fetch_add(1, Ordering::SeqCst);
+        TaskHandle {
+            id,
+            token,
+            active: self.active.clone(),
+        }
+    }
+
+    pub fn set_progress(&self, id: &str, progress: Option<f64>, message: Option<String>) {
+        self.with_running(id, |info| {
+            info.progress = progress.map(|p| p.clamp(0.0, 1.0));
+            info.message = message;
+        });
+    }
+
+    /// Record the outcome of a task. A task that was cancelled stays cancelled
+    /// even if the work itself returned afterwards.
+    pub fn finish<T, E: Display>(&self, id: &str, result: &Result<T, E>) {
+        self.with_running(id, |info| {
+            match result {
+                Ok(_) => {
+                    info.status = TaskStatus::Completed;
+                    info.progress = Some(1.0);
+                }
+                Err(e) => {
+                    info.status = TaskStatus::Failed;
+                    info.error = Some(e.to_string());
+                }
+            }
+            info.finished_at = Some(Utc::now().to_rfc3339());
+        });
+    }
+
+    pub fn cancel(&self, id: &str) -> Result<TaskInfo, String> {
+        let mut tasks = self.tasks.lock().map_err(|e| e.to_string())?;
+        let entry = tasks
+            .iter_mut()
+            .find(|t| t.info.id == id)
+            .ok_or_else(|| format!("Task not found: {}", id))?;
+
+        if entry.info.status == TaskStatus::Running {
+            entry.token.cancel();
+            entry.info.status = TaskStatus::Cancelled;
+            entry.info.finished_at = Some(Utc::now().to_rfc3339());
+        }
+        Ok(entry.info.clone())
+    }
+
+    /// Cancel every running task, returning how many there were.
+    pub fn cancel_all(&self) -> usize {
+        let Ok(mut tasks) = self.tasks.lock() else {
+            return 0;
+        };
+        let finished_at = Utc::now().to_rfc3339();
+        let mut cancelled = 0;
+        for entry in tasks
+            .iter_mut()
+            .filter(|t| t.info.status == TaskStatus::Running)
+        {
+            entry.token.cancel();
+            entry.info.status = TaskStatus::Cancelled;
+            entry.info.finished_at = Some(finished_at.clone());
+            cancelled += 1;
+        }
+        cancelled
+    }
+
+    /// Wait up to `timeout` for the work behind every handle to return;
+    /// false if some is still running.
+    pub fn drain(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        while self.active.load(Ordering::SeqCst) > 0 {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        true
+    }
+
+    /// All known tasks, newest first.
+    pub fn list(&self) -> Vec<TaskInfo> {
+        self.tasks
+            .lock()
+            .map(|tasks| tasks.iter().rev().map(|t| t.info.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    fn with_running(&self, id: &str, apply: impl FnOnce(&mut TaskInfo)) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            if let Some(entry) = tasks
+                .iter_mut()
+                .find(|t| t.info.id == id && t.info.status == TaskStatus::Running)
+            {
+                apply(&mut entry.info);
+            }
+        }
+    }
+}
+
+fn prune_finished(tasks: &mut Vec<TaskEntry>) {
+    let finished = tasks
+        .iter()
+        .filter(|t| t.info.status != TaskStatus::Running)
+        .count();
+    let mut excess = finished.saturating_sub(MAX_FINISHED_TASKS);
+    tasks.retain(|t| {
+        if excess > 0 && t.info.status != TaskStatus::Running {
+            excess -= 1;
+            false
+        } else {
+            true
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn task_lifecycle_is_tracked() {
+        let manager = TaskManager::default();
+        let ok = manager.start("schema_load", "server / db");
+        let failed = manager.start("export", "diagram.png");
+
+        manager.set_progress(&ok.id, Some(0.5), Some("Loading views".to_string()));
+        assert_eq!(manager.list()[1].progress, Some(0.5));
+
+        manager.finish::<(), String>(&ok.id, &Ok(()));
+        manager.finish::<(), String>(&failed.id, &Err("disk full".to_string()));
+
+        let tasks = manager.list();
+        assert_eq!(tasks[0].id, failed.id);
+        assert_eq!(tasks[0].status, TaskStatus::Failed);
+        assert_eq!(tasks[0].error.as_deref(), Some("disk full"));
+        assert_eq!(tasks[1].status, TaskStatus::Completed);
+        assert_eq!(tasks[1].progress, Some(1.0));
+    }
+
+    #[test]
+    fn cancel_signals_handle_and_sticks() {
+        let manager = TaskManager::default();
+        let task = manager.start("schema_load", "server / db");
+
+        let info = manager.cancel(&task.id).unwrap();
+        assert_eq!(info.status, TaskStatus::Cancelled);
+        assert!(task.token.is_cancelled());
+
+        // Work finishing after cancellation does not overwrite the status
+        manager.finish::<(), String>(&task.id, &Ok(()));
+        assert_eq!(manager.list()[0].status, TaskStatus::Cancelled);
+
+        assert!(manager.cancel("task-missing").is_err());
+    }
+
+    #[test]
+    fn shutdown_cancels_and_waits_for_handles() {
+        let manager = TaskManager::default();
+        let watcher = manager.start("watch_export", "sql01 / Sales -> out");
+        let finished = manager.start("export", "diagram.png");
+        manager.finish::<(), String>(&finished.id, &Ok(()));
+        drop(finished);
+
+        assert_eq!(manager.cancel_all(), 1);
+        assert!(watcher.token.is_cancelled());
+        assert_eq!(manager.list()[1].status, TaskStatus::Cancelled);
+        assert!(!manager.drain(Duration::from_millis(30)));
+
+        let worker = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(30));
+            drop(watcher);
+        });
+        assert!(manager.drain(Duration::from_secs(5)));
+        worker.join().unwrap();
+    }
+
+    #[test]
+    fn finished_tasks_are_pruned_but_running_kept() {
+        let manager = TaskManager::default();
+        let running = manager.start("watch", "folder");
+        for i in 0..(MAX_FINISHED_TASKS + 5) {
+            let task = manager.start("export", format!("export {}", i));
+            manager.finish::<(), String>(&task.id, &Ok(()));
+        }
+        manager.start("export", "trigger prune");
+
+        let tasks = manager.list();
+        assert!(tasks.iter().any(|t| t.id == running.id));
+        let finished = tasks
+            .iter()
+            .filter(|t| t.status != TaskStatus::Running)
+            .count();
+        assert_eq!(finished, MAX_FINISHED_TASKS);
+    }
+}
@@ -0,0 +1,37 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Deduplicates repeated metadata strings (schema names, data types, object
+/// type descriptors) so a large graph shares one allocation per distinct value.
+#[derive(Default)]
+pub struct Interner {
+    strings: HashSet<Arc<str>>,
+}
+
+impl Interner {
+    pub fn intern(&mut self, value: &str) -> Arc<str> {
+        if let Some(existing) = self.strings.get(value) {
+            return existing.clone();
+        }
+        let shared: Arc<str> = Arc::from(value);
+        self.strings.insert(shared.clone());
+        shared
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_values_share_one_allocation() {
+        let mut interner = Interner::default();
+        let a = interner.intern("nvarchar(100)");
+        let b = interner.intern("nvarchar(100)");
+        let c = interner.intern("int");
+
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+        assert_eq!(&*c, "int");
+    }
+}
@@ -1,2 +1,4 @@
+pub mod intern;
 pub mod schema;
+pub use intern::*;
 pub use schema::*;
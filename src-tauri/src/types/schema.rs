@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -7,19 +8,125 @@ pub struct ColumnSource {
     pub column: String,
 }
 
+/// Facts from reading a procedure or trigger body, beyond the tables it
+/// reads and writes.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WorkingTableKind {
+    /// `#name`, private to the session.
+    TempTable,
+    /// `##name`, visible to every session.
+    GlobalTempTable,
+    /// `DECLARE @name TABLE`.
+    TableVariable,
+}
+
+impl WorkingTableKind {
+    /// Kind told by the name's prefix.
+    pub fn of(name: &str) -> Self {
+        if name.starts_with("##") {
+            WorkingTableKind::GlobalTempTable
+        } else if name.starts_with('#') {
+            WorkingTableKind::TempTable
+        } else {
+            WorkingTableKind::TableVariable
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkingColumn {
+    pub name: String,
+    /// Known when the table is declared; `SELECT ... INTO` and `INSERT`
+    /// column lists give names only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_type: Option<String>,
+}
+
+/// A temporary table or table variable inside a procedure or trigger body.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkingTable {
+    pub name: String,
+    pub kind: WorkingTableKind,
+    /// Inferred from the body; empty when it can't be told.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub columns: Vec<WorkingColumn>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DefinitionAnalysis {
+    /// The body went through the T-SQL parser (`deep-parse` feature) rather
+    /// than pattern matching alone.
+    #[serde(default)]
+    pub parsed: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub referenced_columns: Vec<ColumnSource>,
+    /// `#temp` tables and `@table` variables the body works with.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub working_tables: Vec<WorkingTable>,
+    /// Runs SQL built at run time through `EXEC(...)` or `sp_executesql`.
+    #[serde(default)]
+    pub dynamic_sql: bool,
+    /// Variables concatenated into dynamic SQL without `QUOTENAME`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub concatenated_inputs: Vec<String>,
+    /// Set when `concatenated_inputs` is non-empty: the executed text may
+    /// carry caller input, a possible SQL injection.
+    #[serde(default)]
+    pub injection_risk: bool,
+}
+
+impl DefinitionAnalysis {
+    pub fn is_empty(&self) -> bool {
+        !self.parsed
+            && self.referenced_columns.is_empty()
+            && self.working_tables.is_empty()
+            && !self.dynamic_sql
+            && self.concatenated_inputs.is_empty()
+            && !self.injection_risk
+    }
+}
+
+/// Ownership and change dates from `sys.objects`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectMetadata {
+    /// Explicit owner, or the owner of the object's schema.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Server-local time, matching the timestamps migration tools record.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    /// UTC, so change ages don't depend on the server's time zone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified_at: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Column {
     pub name: String,
-    pub data_type: String,
+    pub data_type: Arc<str>,
     pub is_nullable: bool,
     pub is_primary_key: bool,
+    /// `sys.columns.column_id`; may have gaps where columns were dropped.
+    #[serde(default)]
+    pub ordinal_position: u32,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub source_columns: Vec<ColumnSource>,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub source_table: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub source_column: Option<String>,
+    /// Values come from `IDENTITY` (or `AUTO_INCREMENT`) unless inserted explicitly.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_identity: bool,
+    /// Computed or generated; never written directly.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_computed: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,21 +134,61 @@ pub struct Column {
 pub struct TableNode {
     pub id: String,
     pub name: String,
-    pub schema: String,
+    pub schema: Arc<str>,
     pub columns: Vec<Column>,
+    /// Workspace source this object was loaded from; unset for single-source graphs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ObjectMetadata>,
+    /// Set when the table is tracked by CDC or published for replication.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change_capture: Option<ChangeCapture>,
 }
 
+/// How changes to a table are captured. Both constrain schema changes: CDC
+/// capture instances do not follow new or altered columns, and published
+/// tables cannot be dropped.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeCapture {
+    /// `sys.tables.is_tracked_by_cdc`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cdc: bool,
+    /// Published in a transactional or snapshot publication.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub replicated: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub merge_published: bool,
+}
+
+impl ChangeCapture {
+    /// `None` when no flag is set, so uncaptured tables serialize as before.
+    pub fn from_flags(cdc: bool, replicated: bool, merge_published: bool) -> Option<Self> {
+        (cdc || replicated || merge_published).then_some(ChangeCapture {
+            cdc,
+            replicated,
+            merge_published,
+        })
+    }
+}
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ViewNode {
     pub id: String,
     pub name: String,
-    pub schema: String,
+    pub schema: Arc<str>,
     pub columns: Vec<Column>,
     pub definition: String,
     pub referenced_tables: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ObjectMetadata>,
+    /// Has a clustered index, so SQL Server stores its rows (a materialized view).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_indexed: bool,
 }
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RelationshipEdge {
@@ -52,13 +199,41 @@ pub struct RelationshipEdge {
     pub from_column: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub to_column: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// User-defined edge documenting an implicit join; not a real foreign key.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_virtual: bool,
+    /// Crow's-foot details derived from the key columns; unset when a column
+    /// is unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub participation: Option<Participation>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Optionality {
+    Mandatory,
+    Optional,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Participation {
+    /// Whether every child row needs a parent: mandatory when all foreign
+    /// key columns are `NOT NULL`.
+    pub parent: Optionality,
+    /// The foreign key columns are the child's whole primary key, so each
+    /// parent has at most one child.
+    pub one_to_one: bool,
+    /// The foreign key columns are part of the child's primary key.
+    pub identifying: bool,
+}
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProcedureParameter {
     pub name: String,
-    pub data_type: String,
+    pub data_type: Arc<str>,
     pub is_output: bool,
 }
 
@@ -67,9 +242,9 @@ pub struct ProcedureParameter {
 pub struct Trigger {
     pub id: String,
     pub name: String,
-    pub schema: String,
+    pub schema: Arc<str>,
     pub table_id: String,
-    pub trigger_type: String,
+    pub trigger_type: Arc<str>,
     pub is_disabled: bool,
     pub fires_on_insert: bool,
     pub fires_on_update: bool,
@@ -77,37 +252,253 @@ pub struct Trigger {
     pub definition: String,
     pub referenced_tables: Vec<String>,
     pub affected_tables: Vec<String>,
+    /// Columns the trigger tests with `UPDATE(col)` or `COLUMNS_UPDATED()`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub watched_columns: Vec<String>,
+    #[serde(default, skip_serializing_if = "DefinitionAnalysis::is_empty")]
+    pub analysis: DefinitionAnalysis,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ObjectMetadata>,
 }
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StoredProcedure {
     pub id: String,
     pub name: String,
-    pub schema: String,
-    pub procedure_type: String,
+    pub schema: Arc<str>,
+    pub procedure_type: Arc<str>,
     pub parameters: Vec<ProcedureParameter>,
     pub definition: String,
     pub referenced_tables: Vec<String>,
     pub affected_tables: Vec<String>,
+    #[serde(default, skip_serializing_if = "DefinitionAnalysis::is_empty")]
+    pub analysis: DefinitionAnalysis,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ObjectMetadata>,
 }
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ScalarFunction {
     pub id: String,
     pub name: String,
-    pub schema: String,
-    pub function_type: String,
+    pub schema: Arc<str>,
+    pub function_type: Arc<str>,
     pub parameters: Vec<ProcedureParameter>,
-    pub return_type: String,
+    pub return_type: Arc<str>,
     pub definition: String,
     pub referenced_tables: Vec<String>,
     pub affected_tables: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ObjectMetadata>,
+}
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum LoadPhaseStatus {
+    Ok,
+    Failed,
+    TimedOut,
 }
 
+/// Timing for one metadata query phase of a schema load.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct LoadPhase {
+    pub phase: String,
+    pub status: LoadPhaseStatus,
+    pub attempts: u32,
+    pub duration_ms: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum GraphWarningKind {
+    DanglingRelationship,
+    MissingRelationshipColumn,
+    OrphanTrigger,
+    DanglingReference,
+    DanglingLink,
+    /// A file in a folder source that could not be read; the rest still load.
+    UnreadableFile,
+}
+
+/// An inconsistency found (and removed) by the post-load integrity pass.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphWarning {
+    pub kind: GraphWarningKind,
+    pub object_id: String,
+    pub missing: String,
+    pub message: String,
+}
+
+/// A user-drawn edge documenting an integration between objects, typically
+/// across workspace sources (e.g. an ETL feed from one database into another).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ManualLink {
+    pub id: String,
+    pub from: String,
+    pub to: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_column: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_column: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// Glossary terms and taxonomy tags assigned to an object, or to one of its
+/// columns when `column` is set.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Classification {
+    pub object_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub terms: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Contact {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+}
+
+/// Who owns and who looks after a schema, or a table when `object_id` is
+/// set. A table without its own entry falls back to its schema's.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Stewardship {
+    pub schema: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub object_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Contact>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steward: Option<Contact>,
+}
+
+/// A table in a tabular (SSAS / Power BI) model that is fed by a database
+/// object, i.e. a "consumed by model X" annotation on that object.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelConsumer {
+    /// Graph ID of the source table or view (`schema.name`).
+    pub object_id: String,
+    pub model: String,
+    pub model_table: String,
+    pub measures: Vec<String>,
+    /// Source columns the model table imports.
+    pub columns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ObjectStateKind {
+    Trigger,
+    ForeignKey,
+    CheckConstraint,
+    Index,
+}
+
+/// Current enable/trust flags of one object, from `refresh_object_state_cmd`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectState {
+    pub kind: ObjectStateKind,
+    /// Matches `Trigger.id` for triggers and `RelationshipEdge.id` for foreign keys.
+    pub id: String,
+    pub table_id: String,
+    pub name: String,
+    pub is_disabled: bool,
+    /// Constraint was enabled `WITH NOCHECK`, so existing rows are unverified.
+    pub is_not_trusted: bool,
+}
+
+/// A report dataset that reads from or executes a database object.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportUsage {
+    pub object_id: String,
+    /// Report file name without extension.
+    pub report: String,
+    pub report_path: String,
+    pub dataset: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportScan {
+    pub usages: Vec<ReportUsage>,
+    /// Report files that could not be parsed, with the reason.
+    pub skipped: Vec<String>,
+}
+
+/// One step of a SQL Server Agent job. References are only resolved for
+/// T-SQL steps that run in the loaded database.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentJobStep {
+    pub step_id: i32,
+    pub name: String,
+    /// `TSQL`, `CmdExec`, `SSIS`, `PowerShell`, ...
+    pub subsystem: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub database: Option<String>,
+    pub command: String,
+    pub referenced_tables: Vec<String>,
+    pub affected_tables: Vec<String>,
+    pub executed_procedures: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentSchedule {
+    pub name: String,
+    pub enabled: bool,
+    /// Readable summary such as "Daily at 02:00".
+    pub description: String,
+}
+
+/// A SQL Server Agent job with at least one step touching the loaded
+/// database. The reference lists are the union over its steps.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentJob {
+    /// `job:<name>`, so it cannot collide with a `schema.object` ID.
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    pub schedules: Vec<AgentSchedule>,
+    pub steps: Vec<AgentJobStep>,
+    pub referenced_tables: Vec<String>,
+    pub affected_tables: Vec<String>,
+    pub executed_procedures: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct SchemaGraph {
     pub tables: Vec<TableNode>,
     pub views: Vec<ViewNode>,
@@ -115,6 +506,25 @@ pub struct SchemaGraph {
     pub triggers: Vec<Trigger>,
     pub stored_procedures: Vec<StoredProcedure>,
     pub scalar_functions: Vec<ScalarFunction>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub load_phases: Vec<LoadPhase>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<GraphWarning>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<ManualLink>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agent_jobs: Vec<AgentJob>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classifications: Vec<Classification>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stewardship: Vec<Stewardship>,
+    /// Default schema of the connecting user, used to resolve unqualified names.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_schema: Option<String>,
+    /// Position in the connection's `SchemaPatch` sequence; 0 for graphs that
+    /// are not tracked, such as mock or imported ones.
+    #[serde(default)]
+    pub version: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
@@ -125,12 +535,25 @@ pub enum AuthType {
     Windows,
 }
 
+/// The kind of server a connection talks to.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DatabaseEngine {
+    #[default]
+    SqlServer,
+    /// MySQL 5.7+ or MariaDB 10.2+. Schema loading only; the query,
+    /// procedure and monitoring features need SQL Server.
+    MySql,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ConnectionParams {
     pub server: String,
     pub database: String,
     #[serde(default)]
+    pub engine: DatabaseEngine,
+    #[serde(default)]
     pub auth_type: AuthType,
     #[serde(default)]
     pub username: Option<String>,
@@ -138,6 +561,29 @@ pub struct ConnectionParams {
     pub password: Option<String>,
     #[serde(default)]
     pub trust_server_certificate: bool,
+    /// Try every address of an availability group listener at once, as
+    /// `MultiSubnetFailover=True` does.
+    #[serde(default)]
+    pub multi_subnet_failover: bool,
+    /// `ApplicationIntent=ReadOnly`: the listener routes the connection to a
+    /// readable secondary.
+    #[serde(default)]
+    pub read_only_intent: bool,
+}
+
+impl ConnectionParams {
+    /// Username and password of a SQL login. `None` with integrated
+    /// authentication, which logs in as the user running the app through
+    /// SSPI; credentials left over from a SQL login are ignored then.
+    pub fn sql_login(&self) -> Option<(&str, &str)> {
+        match self.auth_type {
+            AuthType::SqlServer => Some((
+                self.username.as_deref().unwrap_or_default(),
+                self.password.as_deref().unwrap_or_default(),
+            )),
+            AuthType::Windows => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -152,4 +598,19 @@ pub struct ServerConnectionParams {
     pub password: Option<String>,
     #[serde(default)]
     pub trust_server_certificate: bool,
+    #[serde(default)]
+    pub multi_subnet_failover: bool,
+}
+
+impl ServerConnectionParams {
+    /// See [`ConnectionParams::sql_login`].
+    pub fn sql_login(&self) -> Option<(&str, &str)> {
+        match self.auth_type {
+            AuthType::SqlServer => Some((
+                self.username.as_deref().unwrap_or_default(),
+                self.password.as_deref().unwrap_or_default(),
+            )),
+            AuthType::Windows => None,
+        }
+    }
 }
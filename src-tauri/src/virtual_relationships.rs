@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::db::validate_graph;
+use crate::i18n::t;
+use crate::state::file_stem;
+use crate::types::{RelationshipEdge, SchemaGraph};
+
+/// A relationship the user wants drawn between two tables or views.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualRelationshipInput {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub from_column: Option<String>,
+    #[serde(default)]
+    pub to_column: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+struct VirtualRelationshipFile {
+    relationships: Vec<RelationshipEdge>,
+}
+
+/// Relationships belong to a workspace: its ID for multi-source workspaces,
+/// or the `connection_key` when a single database is open on its own.
+fn store_path(storage_path: &Path, workspace: &str) -> PathBuf {
+    storage_path
+        .join("virtual-relationships")
+        .join(format!("{}.json", file_stem(workspace)))
+}
+
+fn read_store(path: &Path) -> VirtualRelationshipFile {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_store(path: &Path, file: &VirtualRelationshipFile) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create relationship directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(file)
+        .map_err(|e| format!("Failed to serialize relationships: {}", e))?;
+    std::fs::write(path, content).map_err(|e| format!("Failed to write relationships: {}", e))
+}
+
+/// Deterministic ID, so adding the same relationship twice replaces it.
+fn virtual_id(input: &VirtualRelationshipInput) -> String {
+    let end = |id: &str, column: &Option<String>| match column {
+        Some(column) => format!("{}({})", id, column),
+        None => id.to_string(),
+    };
+    format!(
+        "virtual:{}->{}",
+        end(&input.from, &input.from_column),
+        end(&input.to, &input.to_column)
+    )
+}
+
+pub fn list_virtual_relationships(storage_path: &Path, workspace: &str) -> Vec<RelationshipEdge> {
+    read_store(&store_path(storage_path, workspace)).relationships
+}
+
+pub fn add_virtual_relationship(
+    storage_path: &Path,
+    workspace: &str,
+    input: VirtualRelationshipInput,
+) -> Result<RelationshipEdge, String> {
+    if input.from.trim().is_empty() || input.to.trim().is_empty() {
+        return Err(t!("error-relationship-ends-required"));
+    }
+
+    let edge = RelationshipEdge {
+        id: virtual_id(&input),
+        from: input.from,
+        to: input.to,
+        from_column: input.from_column,
+        to_column: input.to_column,
+        source: None,
+        is_virtual: true,
+        participation: None,
+    };
+
+    let path = store_path(storage_path, workspace);
+    let mut file = read_store(&path);
+    file.relationships.retain(|r| r.id != edge.id);
+    file.relationships.push(edge.clone());
+    write_store(&path, &file)?;
+    Ok(edge)
+}
+
+pub fn remove_virtual_relationship(
+    storage_path: &Path,
+    workspace: &str,
+    id: &str,
+) -> Result<(), String> {
+    let path = store_path(storage_path, workspace);
+    let mut file = read_store(&path);
+    let before = file.relationships.len();
+    file.relationships.retain(|r| r.id != id);
+    if file.relationships.len() == before {
+        return Err(t!("error-relationship-not-found", id = id));
+    }
+    write_store(&path, &file)
+}
+
+/// Add the workspace's saved virtual relationships to a freshly loaded graph.
+/// Ones whose tables or columns no longer exist are dropped with a warning by
+/// the integrity pass, like any other dangling edge.
+pub fn apply_virtual_relationships(storage_path: &Path, workspace: &str, graph: &mut SchemaGraph) {
+    let relationships = list_virtual_relationships(storage_path, workspace);
+    if relationships.is_empty() {
+        return;
+    }
+    graph.relationships.extend(relationships);
+    validate_graph(graph);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{GraphWarningKind, TableNode};
+    use tempfile::tempdir;
+
+    fn input(from: &str, to: &str) -> VirtualRelationshipInput {
+        VirtualRelationshipInput {
+            from: from.to_string(),
+            to: to.to_string(),
+            from_column: None,
+            to_column: None,
+        }
+    }
+
+    #[test]
+    fn virtual_relationships_persist_and_merge_into_graph() {
+        let dir = tempdir().expect("tempdir");
+        let root = dir.path();
+        let table = |name: &str| TableNode {
+            id: format!("dbo.{}", name),
+            name: name.to_string(),
+            schema: "dbo".into(),
+            columns: Vec::new(),
+            source: None,
+            metadata: None,
+            change_capture: None,
+        };
+        let mut graph = SchemaGraph {
+            tables: vec![table("Orders"), table("Invoices")],
+            ..Default::default()
+        };
+        let (a, b) = ("dbo.Orders", "dbo.Invoices");
+
+        let edge = add_virtual_relationship(root, "srv/db", input(a, b)).unwrap();
+        assert!(edge.is_virtual);
+        // Same ends again replaces rather than duplicates
+        add_virtual_relationship(root, "srv/db", input(a, b)).unwrap();
+        add_virtual_relationship(root, "srv/db", input(a, "dbo.Gone")).unwrap();
+        assert_eq!(list_virtual_relationships(root, "srv/db").len(), 2);
+
+        apply_virtual_relationships(root, "srv/db", &mut graph);
+        assert_eq!(graph.relationships.len(), 1);
+        assert_eq!(graph.relationships[0].id, edge.id);
+        assert_eq!(graph.warnings.len(), 1);
+        assert_eq!(
+            graph.warnings[0].kind,
+            GraphWarningKind::DanglingRelationship
+        );
+
+        remove_virtual_relationship(root, "srv/db", &edge.id).unwrap();
+        assert!(remove_virtual_relationship(root, "srv/db", &edge.id).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::analysis::TargetDialect;
+use crate::ddl::{generate_ddl, migration_kit, MigrationFormat};
+use crate::i18n::t;
+use crate::state::file_stem;
+use crate::types::SchemaGraph;
+
+/// Poll interval when the caller does not choose one.
+pub const DEFAULT_WATCH_INTERVAL_SECS: u64 = 60;
+
+/// Lower bound, so a watch cannot hammer a production server.
+pub const MIN_WATCH_INTERVAL_SECS: u64 = 10;
+
+/// One file kept up to date by watch mode.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum WatchExport {
+    /// The schema graph as JSON.
+    Json,
+    Ddl {
+        dialect: TargetDialect,
+    },
+    MigrationKit {
+        format: MigrationFormat,
+    },
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchExportConfig {
+    pub exports: Vec<WatchExport>,
+    #[serde(default)]
+    pub interval_seconds: Option<u64>,
+}
+
+impl WatchExportConfig {
+    pub fn interval_secs(&self) -> u64 {
+        self.interval_seconds
+            .unwrap_or(DEFAULT_WATCH_INTERVAL_SECS)
+            .max(MIN_WATCH_INTERVAL_SECS)
+    }
+}
+
+/// Payload of the `watch-export` event, emitted after every regeneration
+/// attempt.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchExportEvent {
+    pub task_id: String,
+    pub fingerprint: String,
+    /// Paths written; empty when the regeneration failed.
+    pub files: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub generated_at: String,
+}
+
+fn file_base(database: &str) -> String {
+    match database.trim().is_empty() {
+        true => "schema".to_string(),
+        false => file_stem(database.trim()),
+    }
+}
+
+/// File names and contents for every configured export.
+pub fn render_exports(
+    graph: &SchemaGraph,
+    database: &str,
+    exports: &[WatchExport],
+) -> Result<Vec<(String, String)>, String> {
+    let base = file_base(database);
+    exports
+        .iter()
+        .map(|export| match export {
+            WatchExport::Json => serde_json::to_string_pretty(graph)
+                .map(|json| (format!("{}.schema.json", base), json))
+                .map_err(|e| t!("error-export-serialize", reason = e)),
+            WatchExport::Ddl { dialect } => {
+                let suffix = match dialect {
+                    TargetDialect::Postgres => "postgres",
+                    TargetDialect::MySql => "mysql",
+                };
+                let script = generate_ddl(graph, *dialect, &[]).script;
+                Ok((format!("{}.{}.sql", base, suffix), script))
+            }
+            WatchExport::MigrationKit { format } => {
+                let name = match format {
+                    MigrationFormat::InsertSelect => format!("{}.migration.sql", base),
+                    MigrationFormat::Bcp => format!("{}.migration.cmd", base),
+                };
+                Ok((name, migration_kit(graph, *format).script))
+            }
+        })
+        .collect()
+}
+
+/// Write each file next to its final name and rename it into place, so a
+/// reader never sees a half-written export.
+pub fn write_exports(output_dir: &Path, files: &[(String, String)]) -> Result<Vec<String>, String> {
+    std::fs::create_dir_all(output_dir).map_err(|e| {
+        t!(
+            "error-export-folder",
+            path = output_dir.display(),
+            reason = e
+        )
+    })?;
+    files
+        .iter()
+        .map(|(name, content)| {
+            let path = output_dir.join(name);
+            let partial = output_dir.join(format!(".{}.partial", name));
+            std::fs::write(&partial, content)
+                .and_then(|_| std::fs::rename(&partial, &path))
+                .map_err(|e| t!("error-export-write", path = path.display(), reason = e))?;
+            Ok(path.to_string_lossy().to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+    use tempfile::tempdir;
+
+    #[test]
+    fn configured_exports_are_written_to_the_folder() {
+        let config: WatchExportConfig = serde_json::from_str(
+            r#"{"exports":[{"kind":"json"},{"kind":"ddl","dialect":"postgres"},{"kind":"migrationKit","format":"bcp"}],"intervalSeconds":1}"#,
+        )
+        .unwrap();
+        assert_eq!(config.interval_secs(), MIN_WATCH_INTERVAL_SECS);
+
+        let graph = generate_mock_schema("small");
+        let files = render_exports(&graph, "Sales DB", &config.exports).unwrap();
+        let names: Vec<&str> = files.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Sales_20DB.schema.json",
+                "Sales_20DB.postgres.sql",
+                "Sales_20DB.migration.cmd"
+            ]
+        );
+
+        let dir = tempdir().expect("tempdir");
+        let written = write_exports(&dir.path().join("exports"), &files).unwrap();
+        assert_eq!(written.len(), 3);
+        let entries = std::fs::read_dir(dir.path().join("exports"))
+            .unwrap()
+            .count();
+        assert_eq!(entries, 3);
+    }
+}
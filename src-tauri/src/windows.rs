@@ -0,0 +1,245 @@
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::session::SessionState;
+use crate::types::ConnectionParams;
+
+/// Label prefix of windows opened with `open_new_window_cmd`; the default
+/// capability grants them the same permissions as the main window.
+pub const DATABASE_WINDOW_PREFIX: &str = "db-";
+
+const MAIN_WINDOW: &str = "main";
+
+/// Which menu items a window's current view can use.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MenuUiState {
+    pub is_canvas_mode: bool,
+    pub has_focus: bool,
+    pub has_active_filters: bool,
+}
+
+/// Per-window state, keyed by window label: the connection each additional
+/// window was opened for, the menu state and session each window last
+/// reported, and which windows are viewing a bundle or snapshot read-only.
+/// Held in memory only and dropped when the window is destroyed.
+#[derive(Default)]
+pub struct WindowRegistry {
+    next_id: AtomicU64,
+    contexts: Mutex<HashMap<String, ConnectionParams>>,
+    menu_states: Mutex<HashMap<String, MenuUiState>>,
+    focused: Mutex<Option<String>>,
+    read_only: Mutex<HashSet<String>>,
+    sessions: Mutex<HashMap<String, SessionState>>,
+}
+
+impl WindowRegistry {
+    /// Reserve a label for a new window, binding it to `params` if given.
+    pub fn open(&self, params: Option<ConnectionParams>) -> String {
+        let label = format!(
+            "{}{}",
+            DATABASE_WINDOW_PREFIX,
+            self.next_id.fetch_add(1, Ordering::Relaxed) + 1
+        );
+        if let (Some(params), Ok(mut contexts)) = (params, self.contexts.lock()) {
+            contexts.insert(label.clone(), params);
+        }
+        label
+    }
+
+    pub fn context(&self, label: &str) -> Option<ConnectionParams> {
+        self.contexts.lock().ok()?.get(label).cloned()
+    }
+
+    pub fn close(&self, label: &str) {
+        if let Ok(mut contexts) = self.contexts.lock() {
+            contexts.remove(label);
+        }
+        if let Ok(mut states) = self.menu_states.lock() {
+            states.remove(label);
+        }
+        if let Ok(mut focused) = self.focused.lock() {
+            if focused.as_deref() == Some(label) {
+                *focused = None;
+            }
+        }
+        if let Ok(mut read_only) = self.read_only.lock() {
+            read_only.remove(label);
+        }
+        // The last window's session is kept for the save on exit
+        if let Ok(mut sessions) = self.sessions.lock() {
+            if sessions.keys().any(|other| other != label) {
+                sessions.remove(label);
+            }
+        }
+    }
+
+    pub fn set_read_only(&self, label: &str, read_only: bool) {
+        if let Ok(mut labels) = self.read_only.lock() {
+            if read_only {
+                labels.insert(label.to_string());
+            } else {
+                labels.remove(label);
+            }
+        }
+    }
+
+    pub fn is_read_only(&self, label: &str) -> bool {
+        self.read_only
+            .lock()
+            .is_ok_and(|labels| labels.contains(label))
+    }
+
+    /// Replace what a window last reported as open.
+    pub fn set_session(&self, label: &str, session: SessionState) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions.insert(label.to_string(), session);
+        }
+    }
+
+    /// Every window's connections, the main window's first, as one session.
+    pub fn session(&self) -> SessionState {
+        let Ok(sessions) = self.sessions.lock() else {
+            return SessionState::default();
+        };
+        let mut labels: Vec<&String> = sessions.keys().collect();
+        labels.sort_by_key(|label| (label.as_str() != MAIN_WINDOW, label.as_str()));
+        SessionState {
+            connections: labels
+                .into_iter()
+                .flat_map(|label| sessions[label].connections.iter().cloned())
+                .collect(),
+            saved_at: String::new(),
+        }
+    }
+
+    /// Remember a window's menu state. Returns whether the shared menu should
+    /// show it now: only the focused window's state is applied, so a window
+    /// in the background can't change what the active one sees.
+    pub fn set_menu_state(&self, label: &str, state: MenuUiState) -> bool {
+        if let Ok(mut states) = self.menu_states.lock() {
+            states.insert(label.to_string(), state);
+        }
+        match self.focused() {
+            Some(focused) => focused == label,
+            None => true,
+        }
+    }
+
+    /// Mark `label` as the focused window and return the menu state to show.
+    pub fn focus(&self, label: &str) -> MenuUiState {
+        if let Ok(mut focused) = self.focused.lock() {
+            *focused = Some(label.to_string());
+        }
+        self.menu_states
+            .lock()
+            .ok()
+            .and_then(|states| states.get(label).copied())
+            .unwrap_or_default()
+    }
+
+    /// Label of the window menu actions go to.
+    pub fn focused(&self) -> Option<String> {
+        self.focused.lock().ok()?.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::SessionConnection;
+    use crate::types::{AuthType, DatabaseEngine};
+
+    fn params(database: &str) -> ConnectionParams {
+        ConnectionParams {
+            server: "srv".to_string(),
+            database: database.to_string(),
+            engine: DatabaseEngine::SqlServer,
+            auth_type: AuthType::SqlServer,
+            username: None,
+            password: None,
+            trust_server_certificate: false,
+            multi_subnet_failover: false,
+            read_only_intent: false,
+        }
+    }
+
+    #[test]
+    fn each_window_keeps_its_own_connection() {
+        let registry = WindowRegistry::default();
+        let sales = registry.open(Some(params("Sales")));
+        let billing = registry.open(Some(params("Billing")));
+        let empty = registry.open(None);
+
+        assert_ne!(sales, billing);
+        assert!(sales.starts_with(DATABASE_WINDOW_PREFIX));
+        assert_eq!(registry.context(&billing).unwrap().database, "Billing");
+        assert!(registry.context(&empty).is_none());
+
+        registry.close(&sales);
+        assert!(registry.context(&sales).is_none());
+        assert_eq!(registry.context(&billing).unwrap().database, "Billing");
+    }
+
+    #[test]
+    fn only_the_focused_window_drives_the_menu() {
+        let registry = WindowRegistry::default();
+        let canvas = MenuUiState {
+            is_canvas_mode: true,
+            ..Default::default()
+        };
+
+        // Before any focus event the reporting window is applied
+        assert!(registry.set_menu_state("main", canvas));
+        assert_eq!(registry.focus("db-1"), MenuUiState::default());
+        assert!(!registry.set_menu_state("main", MenuUiState::default()));
+        assert!(registry.set_menu_state("db-1", canvas));
+
+        assert_eq!(registry.focus("main"), MenuUiState::default());
+        assert_eq!(registry.focus("db-1"), canvas);
+        registry.close("db-1");
+        assert_eq!(registry.focused(), None);
+    }
+
+    #[test]
+    fn read_only_and_sessions_are_per_window() {
+        let registry = WindowRegistry::default();
+        registry.set_read_only("db-1", true);
+        assert!(registry.is_read_only("db-1"));
+        assert!(!registry.is_read_only("main"));
+
+        let session = |database: &str| SessionState {
+            connections: vec![SessionConnection {
+                server: "srv".to_string(),
+                database: database.to_string(),
+                engine: DatabaseEngine::SqlServer,
+                auth_type: AuthType::SqlServer,
+                username: None,
+                trust_server_certificate: false,
+                layout: None,
+                filters: Default::default(),
+            }],
+            saved_at: String::new(),
+        };
+        registry.set_session("db-1", session("Billing"));
+        registry.set_session("main", session("Sales"));
+        let databases = |registry: &WindowRegistry| -> Vec<String> {
+            registry
+                .session()
+                .connections
+                .into_iter()
+                .map(|c| c.database)
+                .collect()
+        };
+        assert_eq!(databases(&registry), vec!["Sales", "Billing"]);
+
+        // Closing a window forgets it, unless it is the last one
+        registry.close("db-1");
+        assert!(!registry.is_read_only("db-1"));
+        assert_eq!(databases(&registry), vec!["Sales"]);
+        registry.close("main");
+        assert_eq!(databases(&registry), vec!["Sales"]);
+    }
+}
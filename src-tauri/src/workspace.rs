@@ -0,0 +1,245 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+
+use crate::types::{ConnectionParams, GraphWarning, GraphWarningKind, ManualLink, SchemaGraph};
+
+/// How to load one workspace source. Tagged by `engine` so each loader can
+/// add its own variant without changing the workspace format.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "engine", rename_all = "camelCase")]
+pub enum SourceConnection {
+    SqlServer { params: ConnectionParams },
+    Mock { size: String },
+    FlatFiles { path: String },
+    Dbt { path: String },
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceSource {
+    pub id: String,
+    pub label: String,
+    pub connection: SourceConnection,
+}
+
+fn scoped(source_id: &str, id: &str) -> String {
+    format!("{}:{}", source_id, id)
+}
+
+fn scope_all(source_id: &str, ids: &mut [String]) {
+    for id in ids.iter_mut() {
+        *id = scoped(source_id, id);
+    }
+}
+
+/// Namespace every ID in `graph` with `source_id` (`crm:dbo.Orders`) and tag
+/// each node, so graphs from different engines can share one canvas without
+/// colliding on common names like `dbo.Users`.
+pub fn tag_source(graph: &mut SchemaGraph, source_id: &str) {
+    let source = Some(source_id.to_string());
+
+    for table in &mut graph.tables {
+        table.id = scoped(source_id, &table.id);
+        table.source = source.clone();
+    }
+    for view in &mut graph.views {
+        view.id = scoped(source_id, &view.id);
+        view.source = source.clone();
+        scope_all(source_id, &mut view.referenced_tables);
+        for column in &mut view.columns {
+            for column_source in &mut column.source_columns {
+                column_source.table = scoped(source_id, &column_source.table);
+            }
+            if let Some(table) = column.source_table.as_mut() {
+                *table = scoped(source_id, table);
+            }
+        }
+    }
+    for edge in &mut graph.relationships {
+        edge.id = scoped(source_id, &edge.id);
+        edge.from = scoped(source_id, &edge.from);
+        edge.to = scoped(source_id, &edge.to);
+        edge.source = source.clone();
+    }
+    for trigger in &mut graph.triggers {
+        trigger.id = scoped(source_id, &trigger.id);
+        trigger.table_id = scoped(source_id, &trigger.table_id);
+        trigger.source = source.clone();
+        scope_all(source_id, &mut trigger.referenced_tables);
+        scope_all(source_id, &mut trigger.affected_tables);
+    }
+    for procedure in &mut graph.stored_procedures {
+        procedure.id = scoped(source_id, &procedure.id);
+        procedure.source = source.clone();
+        scope_all(source_id, &mut procedure.referenced_tables);
+        scope_all(source_id, &mut procedure.affected_tables);
+    }
+    for function in &mut graph.scalar_functions {
+        function.id = scoped(source_id, &function.id);
+        function.source = source.clone();
+        scope_all(source_id, &mut function.referenced_tables);
+        scope_all(source_id, &mut function.affected_tables);
+    }
+    for job in &mut graph.agent_jobs {
+        job.id = scoped(source_id, &job.id);
+        job.source = source.clone();
+        scope_all(source_id, &mut job.referenced_tables);
+        scope_all(source_id, &mut job.affected_tables);
+        scope_all(source_id, &mut job.executed_procedures);
+        for step in &mut job.steps {
+            scope_all(source_id, &mut step.referenced_tables);
+            scope_all(source_id, &mut step.affected_tables);
+            scope_all(source_id, &mut step.executed_procedures);
+        }
+    }
+    for phase in &mut graph.load_phases {
+        phase.phase = scoped(source_id, &phase.phase);
+    }
+    for warning in &mut graph.warnings {
+        warning.object_id = scoped(source_id, &warning.object_id);
+    }
+    for classification in &mut graph.classifications {
+        classification.object_id = scoped(source_id, &classification.object_id);
+    }
+    for assignment in &mut graph.stewardship {
+        if let Some(id) = assignment.object_id.as_mut() {
+            *id = scoped(source_id, id);
+        }
+    }
+}
+
+/// Combine per-source graphs into one workspace graph. Manual links must use
+/// source-scoped IDs; links whose endpoints are missing are dropped with a warning.
+pub fn merge_sources(
+    sources: Vec<(String, SchemaGraph)>,
+    links: Vec<ManualLink>,
+) -> Result<SchemaGraph, String> {
+    let mut seen = HashSet::new();
+    let mut merged = SchemaGraph::default();
+
+    for (source_id, mut graph) in sources {
+        if source_id.is_empty() || source_id.contains(':') {
+            return Err(format!("Invalid workspace source id '{}'", source_id));
+        }
+        if !seen.insert(source_id.clone()) {
+            return Err(format!("Duplicate workspace source id '{}'", source_id));
+        }
+
+        tag_source(&mut graph, &source_id);
+        merged.tables.extend(graph.tables);
+        merged.views.extend(graph.views);
+        merged.relationships.extend(graph.relationships);
+        merged.triggers.extend(graph.triggers);
+        merged.stored_procedures.extend(graph.stored_procedures);
+        merged.scalar_functions.extend(graph.scalar_functions);
+        merged.agent_jobs.extend(graph.agent_jobs);
+        merged.load_phases.extend(graph.load_phases);
+        merged.warnings.extend(graph.warnings);
+        merged.classifications.extend(graph.classifications);
+        merged.stewardship.extend(graph.stewardship);
+    }
+
+    let nodes: HashSet<&str> = merged
+        .tables
+        .iter()
+        .map(|t| t.id.as_str())
+        .chain(merged.views.iter().map(|v| v.id.as_str()))
+        .collect();
+    let mut warnings = Vec::new();
+    let links = links
+        .into_iter()
+        .filter(|link| {
+            let missing = [&link.from, &link.to]
+                .into_iter()
+                .find(|end| !nodes.contains(end.as_str()));
+            if let Some(end) = missing {
+                warnings.push(GraphWarning {
+                    kind: GraphWarningKind::DanglingLink,
+                    object_id: link.id.clone(),
+                    missing: end.clone(),
+                    message: format!("Link {} points at missing object {}", link.id, end),
+                });
+            }
+            missing.is_none()
+        })
+        .collect();
+
+    merged.links = links;
+    merged.warnings.extend(warnings);
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock::generate_mock_schema;
+
+    fn link(id: &str, from: &str, to: &str) -> ManualLink {
+        ManualLink {
+            id: id.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+            from_column: None,
+            to_column: None,
+            label: Some("nightly ETL".to_string()),
+        }
+    }
+
+    #[test]
+    fn sources_are_scoped_and_tagged() {
+        let crm = generate_mock_schema("small");
+        let first_table = crm.tables[0].id.clone();
+        let first_edge = crm.relationships[0].clone();
+
+        let merged = merge_sources(
+            vec![
+                ("crm".to_string(), crm),
+                ("warehouse".to_string(), generate_mock_schema("small")),
+            ],
+            vec![
+                link(
+                    "crm-to-dw",
+                    &format!("crm:{}", first_table),
+                    &format!("warehouse:{}", first_table),
+                ),
+                link(
+                    "broken",
+                    "crm:dbo.Nope",
+                    &format!("warehouse:{}", first_table),
+                ),
+            ],
+        )
+        .unwrap();
+
+        let table = merged
+            .tables
+            .iter()
+            .find(|t| t.id == format!("warehouse:{}", first_table))
+            .expect("scoped table");
+        assert_eq!(table.source.as_deref(), Some("warehouse"));
+
+        let edge = &merged.relationships[0];
+        assert_eq!(edge.from, format!("crm:{}", first_edge.from));
+        assert_eq!(edge.source.as_deref(), Some("crm"));
+
+        assert_eq!(merged.links.len(), 1);
+        assert_eq!(merged.links[0].id, "crm-to-dw");
+        assert_eq!(merged.warnings.len(), 1);
+        assert_eq!(merged.warnings[0].kind, GraphWarningKind::DanglingLink);
+    }
+
+    #[test]
+    fn duplicate_or_invalid_source_ids_are_rejected() {
+        let graph = SchemaGraph::default();
+        assert!(merge_sources(
+            vec![
+                ("a".to_string(), graph.clone()),
+                ("a".to_string(), graph.clone())
+            ],
+            Vec::new()
+        )
+        .is_err());
+        assert!(merge_sources(vec![("a:b".to_string(), graph)], Vec::new()).is_err());
+    }
+}
@@ -1,8 +1,12 @@
 import { useEffect, useRef } from "react";
-import { check, Update } from "@tauri-apps/plugin-updater";
 import { relaunch } from "@tauri-apps/plugin-process";
 import { useToastStore } from "@/features/notifications/store";
 import { useAppVersion } from "@/hooks/useAppVersion";
+import { tauri } from "@/services/tauri";
+import { updateProgressHub } from "@/services/events";
+
+// "Later" postpones automatic prompts by this long
+const REMIND_LATER_HOURS = 24;
 
 interface UpdateCheckerProps {
   checkRequested?: boolean;
@@ -13,7 +17,7 @@ export function UpdateChecker({
   checkRequested,
   onCheckComplete,
 }: UpdateCheckerProps) {
-  const updateRef = useRef<Update | null>(null);
+  const versionRef = useRef<string | null>(null);
   const toastIdRef = useRef<string | null>(null);
   const currentVersion = useAppVersion();
   const { addToast, updateToast, removeToast } = useToastStore();
@@ -32,10 +36,11 @@ export function UpdateChecker({
 
   async function checkForUpdates(isManual = false) {
     try {
-      const updateResult = await check();
-      if (updateResult) {
-        updateRef.current = updateResult;
-        showUpdateAvailableToast(updateResult.version);
+      // The backend applies the channel, check interval, deferrals and proxy
+      const result = await tauri.checkForUpdates(isManual);
+      if (result.available && result.version) {
+        versionRef.current = result.version;
+        showUpdateAvailableToast(result.version);
       } else if (isManual) {
         // Only show "up to date" message for manual checks
         addToast({
@@ -73,12 +78,11 @@ export function UpdateChecker({
       actions: [
         {
           label: "Later",
-          onClick: () => {
-            if (toastIdRef.current) {
-              removeToast(toastIdRef.current);
-              toastIdRef.current = null;
-            }
-          },
+          onClick: () => deferUpdate(REMIND_LATER_HOURS),
+        },
+        {
+          label: "Skip This Version",
+          onClick: () => deferUpdate(undefined, newVersion),
         },
         {
           label: "Update",
@@ -88,9 +92,18 @@ export function UpdateChecker({
     });
   }
 
+  function deferUpdate(hours?: number, skipVersion?: string) {
+    if (toastIdRef.current) {
+      removeToast(toastIdRef.current);
+      toastIdRef.current = null;
+    }
+    tauri.deferUpdate(hours, skipVersion).catch((err) => {
+      console.error("Failed to defer update:", err);
+    });
+  }
+
   async function installUpdate() {
-    const update = updateRef.current;
-    if (!update || !toastIdRef.current) return;
+    if (!versionRef.current || !toastIdRef.current) return;
 
     // Update toast to show downloading state
     updateToast(toastIdRef.current, {
@@ -100,37 +113,37 @@ export function UpdateChecker({
       actions: [], // Remove actions during download
     });
 
-    try {
-      let downloaded = 0;
-      let contentLength = 0;
-
-      await update.downloadAndInstall((event) => {
-        if (event.event === "Started") {
-          contentLength = event.data.contentLength ?? 0;
-        } else if (event.event === "Progress") {
-          downloaded += event.data.chunkLength;
-          if (contentLength > 0 && toastIdRef.current) {
-            const percent = Math.round((downloaded / contentLength) * 100);
-            updateToast(toastIdRef.current, {
-              message: `Downloading... ${percent}%`,
-              progress: { current: percent, total: 100 },
-            });
-          }
-        } else if (event.event === "Finished") {
-          if (toastIdRef.current) {
-            updateToast(toastIdRef.current, {
-              title: "Update Ready",
-              message: "Restarting...",
-              progress: { current: 100, total: 100 },
-            });
-          }
+    const unsubscribe = updateProgressHub.subscribe(
+      ({ downloaded, contentLength }) => {
+        if (contentLength && toastIdRef.current) {
+          const percent = Math.round((downloaded / contentLength) * 100);
+          updateToast(toastIdRef.current, {
+            message: `Downloading... ${percent}%`,
+            progress: { current: percent, total: 100 },
+          });
         }
-      });
+      }
+    );
+
+    try {
+      await tauri.installUpdate();
+      if (toastIdRef.current) {
+        updateToast(toastIdRef.current, {
+          title: "Update Ready",
+          message: "Restarting...",
+          progress: { current: 100, total: 100 },
+        });
+      }
 
       await relaunch();
     } catch (err) {
+      // Backend commands reject with the error text
       const errorMessage =
-        err instanceof Error ? err.message : "Failed to install update";
+        typeof err === "string"
+          ? err
+          : err instanceof Error
+            ? err.message
+            : "Failed to install update";
       if (toastIdRef.current) {
         updateToast(toastIdRef.current, {
           type: "error",
@@ -154,6 +167,8 @@ export function UpdateChecker({
           ],
         });
       }
+    } finally {
+      unsubscribe();
     }
   }
 
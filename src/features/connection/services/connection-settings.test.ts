@@ -86,4 +86,26 @@ describe("connection settings persistence", () => {
       username: "sa",
     });
   });
+
+  it("keeps the stored credential flag for sql server auth only", () => {
+    saveConnectionSettings({
+      server: "db-host",
+      authType: "windows",
+      hasStoredCredential: true,
+    });
+    expect(loadConnectionSettings()?.hasStoredCredential).toBeUndefined();
+
+    saveConnectionSettings({
+      server: "db-host",
+      authType: "sqlServer",
+      username: "sa",
+      hasStoredCredential: true,
+    });
+    expect(loadConnectionSettings()).toEqual({
+      server: "db-host",
+      authType: "sqlServer",
+      username: "sa",
+      hasStoredCredential: true,
+    });
+  });
 });
@@ -6,6 +6,8 @@ export interface SavedConnectionSettings {
   server: string;
   authType: AuthType;
   username?: string;
+  /** The login's password is in the system keychain */
+  hasStoredCredential?: boolean;
 }
 
 export function loadConnectionSettings(): SavedConnectionSettings | null {
@@ -29,6 +31,9 @@ export function saveConnectionSettings(settings: SavedConnectionSettings) {
 
     if (settings.authType === "sqlServer" && settings.username) {
       toSave.username = settings.username;
+      if (settings.hasStoredCredential) {
+        toSave.hasStoredCredential = true;
+      }
     }
 
     localStorage.setItem(
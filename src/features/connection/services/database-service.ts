@@ -1,7 +1,34 @@
 import { tauri } from "@/services/tauri";
-import type { ServerConnectionParams } from "@/features/schema-graph/types";
+import { databaseBatchHub } from "@/services/events";
+import type {
+  DatabaseCounts,
+  DatabaseEntry,
+  ServerConnectionParams,
+} from "@/features/schema-graph/types";
 
 export const databaseService = {
-  listDatabases: (params: ServerConnectionParams): Promise<string[]> =>
-    tauri.listDatabases(params),
+  // Recently used databases come first
+  listDatabases: (
+    params: ServerConnectionParams,
+    filter?: string
+  ): Promise<string[]> => tauri.listDatabases(params, filter),
+
+  // Streams the list in batches; batches from other operations are ignored
+  streamDatabases: async (
+    params: ServerConnectionParams,
+    operationId: string,
+    onBatch: (databases: DatabaseEntry[]) => void,
+    filter?: string
+  ): Promise<DatabaseCounts> => {
+    const unsubscribe = databaseBatchHub.subscribe((payload) => {
+      if (payload.operationId === operationId) {
+        onBatch(payload.databases);
+      }
+    });
+    try {
+      return await tauri.streamDatabases(params, operationId, filter);
+    } finally {
+      unsubscribe();
+    }
+  },
 };
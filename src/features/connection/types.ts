@@ -0,0 +1,133 @@
+import type { DiagramLayout } from "@/features/export/types";
+import type {
+  Annotation,
+  AuthType,
+  ConnectionParams,
+  SchemaGraph,
+} from "@/features/schema-graph/types";
+
+// What happens when a profile connects
+export type StartupAction =
+  | { kind: "loadSchema" }
+  | { kind: "loadCached" }
+  | { kind: "openLayout"; name: string };
+
+// Per-profile caps enforced by the backend query runner
+export interface QueryLimits {
+  maxPreviewRows?: number;
+  timeoutSeconds?: number;
+  maxStatements?: number;
+}
+
+export type ConnectStep =
+  | "readingCache"
+  | "loadingSchema"
+  | "applyingLayout"
+  | "done";
+
+export interface ConnectProgress {
+  server: string;
+  database: string;
+  step: ConnectStep;
+}
+
+export interface ConnectResult {
+  action: StartupAction;
+  graph: SchemaGraph;
+  /** Set when the graph came from the local cache */
+  cachedAt: string | null;
+  layout: DiagramLayout | null;
+}
+
+// Canvas filters saved with the session
+export interface SessionFilters {
+  search: string;
+  schema: string;
+  focusedTableId: string | null;
+  objectTypes: string[];
+  edgeTypes: string[];
+  excludedObjectIds: string[];
+}
+
+// An open connection, without credentials
+export interface SessionConnection {
+  server: string;
+  database: string;
+  authType: AuthType;
+  username?: string;
+  trustServerCertificate: boolean;
+  layout: DiagramLayout | null;
+  filters: SessionFilters;
+}
+
+export interface SessionState {
+  connections: SessionConnection[];
+  savedAt?: string;
+}
+
+export interface RestoredConnection {
+  connection: SessionConnection;
+  /** Null when nothing was cached; load live instead */
+  graph: SchemaGraph | null;
+  cachedAt: string | null;
+}
+
+// A running SQL Server container on the local Docker daemon
+export interface SqlContainer {
+  id: string;
+  name: string;
+  image: string;
+  status: string;
+  hostPort: number | null; // Null when 1433 isn't published to the host
+  /** Prefill for the connection form; the password is left empty */
+  params: ConnectionParams | null;
+}
+
+// A design batch the server rejected while building a sandbox
+export interface BatchFailure {
+  batch: number; // 1-based
+  statement: string;
+  message: string;
+}
+
+// A disposable database built from the canvas design
+export interface SandboxDatabase {
+  database: string;
+  params: ConnectionParams;
+  createdAt: string;
+  batches: number;
+  failures: BatchFailure[];
+  warnings: string[];
+}
+
+// Stops of the first-run tour, in order
+export type OnboardingStep =
+  | "welcome"
+  | "sampleWorkspace"
+  | "canvas"
+  | "search"
+  | "connect";
+
+export type OnboardingState =
+  | { status: "inProgress"; step: OnboardingStep }
+  | { status: "completed" }
+  | { status: "skipped" };
+
+export interface OnboardingStatus {
+  firstRun: boolean; // No settings existed when the app started
+  state: OnboardingState;
+}
+
+// The demo schema stored under a pseudo-connection for the tour
+export interface SampleWorkspace {
+  server: string;
+  database: string;
+  graph: SchemaGraph;
+  layout: DiagramLayout;
+  annotations: Annotation[];
+}
+
+// A file or monocle:// link a launch asked the running instance to open
+export type LaunchRequest =
+  | { kind: "openFile"; path: string }
+  | { kind: "openLink"; url: string };
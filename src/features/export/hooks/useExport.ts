@@ -6,6 +6,12 @@ import { exportService } from "../services/export-service";
 import { exportToPng } from "../utils/png-export";
 import { exportToPdf } from "../utils/pdf-export";
 import { exportToJson } from "../utils/json-export";
+import { tauri } from "@/services/tauri";
+import type { ExportTheme } from "../types";
+
+// Exports still work with built-in styling if the theme cannot be read
+const loadExportTheme = (): Promise<ExportTheme | undefined> =>
+  tauri.getExportTheme().catch(() => undefined);
 
 export function useExport() {
   const [isExporting, setIsExporting] = useState(false);
@@ -27,7 +33,10 @@ export function useExport() {
 
     try {
       const nodes = getNodes();
-      const pngData = await exportToPng(nodes);
+      const theme = await loadExportTheme();
+      const pngData = await exportToPng(nodes, {
+        backgroundColor: theme?.background,
+      });
       const dbName = connectionInfo?.database ?? "schema";
       const filename = `${dbName}-diagram.png`;
 
@@ -54,10 +63,13 @@ export function useExport() {
 
       try {
         let imageData: Uint8Array | undefined;
+        const theme = await loadExportTheme();
 
         if (includeImage) {
           const nodes = getNodes();
-          imageData = await exportToPng(nodes);
+          imageData = await exportToPng(nodes, {
+            backgroundColor: theme?.background,
+          });
         }
 
         const pdfData = await exportToPdf(schema, {
@@ -65,6 +77,7 @@ export function useExport() {
           connectionInfo: connectionInfo ?? undefined,
           includeImage,
           imageData,
+          theme,
         });
 
         const dbName = connectionInfo?.database ?? "schema";
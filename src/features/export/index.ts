@@ -3,3 +3,4 @@ export { exportToPng } from "./utils/png-export";
 export { exportToPdf } from "./utils/pdf-export";
 export { exportToJson } from "./utils/json-export";
 export { useExport } from "./hooks/useExport";
+export type { ExportTheme } from "./types";
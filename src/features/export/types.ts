@@ -0,0 +1,84 @@
+import type {
+  Annotation,
+  Bookmark,
+  SchemaGraph,
+} from "@/features/schema-graph/types";
+
+/** Colours and sizing for exported diagrams and reports (`get_export_theme_cmd`). */
+export interface ExportTheme {
+  id: string;
+  name: string;
+  background: string;
+  text: string;
+  mutedText: string;
+  tableAccent: string;
+  viewAccent: string;
+  edge: string;
+  virtualEdge: string;
+  lineWidth: number;
+  fontScale: number;
+}
+
+/** A saved arrangement of one database's diagram (`save_layout_cmd`). */
+export interface DiagramLayout {
+  name: string;
+  positions: Record<string, { x: number; y: number }>;
+  collapsedGroups: string[];
+  viewport?: { x: number; y: number; zoom: number };
+  savedAt: string;
+}
+
+/** Contents of a `.monoclepkg` bundle; no connection details are included. */
+export interface DiagramBundle {
+  manifest: { formatVersion: number; title: string; createdAt: string };
+  graph: SchemaGraph;
+  layout?: DiagramLayout;
+  annotations: Bookmark[];
+  descriptions: Annotation[];
+  theme?: ExportTheme;
+}
+
+export type PaperSize = "a4" | "a3" | "letter" | "legal" | "tabloid";
+
+export type PrintScale =
+  | { mode: "fitToPage" }
+  | { mode: "fitToWidth" }
+  | { mode: "percent"; percent: number };
+
+/** Pagination options for `print_diagram_cmd`. */
+export interface PrintOptions {
+  paper?: PaperSize;
+  orientation?: "portrait" | "landscape";
+  scale?: PrintScale;
+  marginMm?: number;
+  pageNumbers?: boolean;
+  legend?: boolean;
+}
+
+/** Legend, title block and watermark added by every exporter. */
+export interface ExportAnnotations {
+  legend?: boolean;
+  titleBlock?: {
+    database?: string;
+    server?: string;
+    /** Defaults to the export time. */
+    generatedAt?: string;
+    filterSummary?: string;
+  };
+  watermark?: string;
+}
+
+/** How the diagram was split into pages; tiles are in canvas pixels. */
+export interface PrintPlan {
+  scale: number;
+  rows: number;
+  columns: number;
+  pages: {
+    row: number;
+    column: number;
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+  }[];
+}
@@ -1,11 +1,21 @@
 import jsPDF from "jspdf";
 import type { SchemaGraph } from "@/features/schema-graph/types";
+import type { ExportTheme } from "../types";
 
 export interface PdfExportOptions {
   title?: string;
   includeImage?: boolean;
   imageData?: Uint8Array;
   connectionInfo?: { server: string; database?: string };
+  theme?: ExportTheme;
+}
+
+function isLightColor(hex: string): boolean {
+  const value = parseInt(hex.replace("#", ""), 16);
+  const r = (value >> 16) & 0xff;
+  const g = (value >> 8) & 0xff;
+  const b = value & 0xff;
+  return 0.299 * r + 0.587 * g + 0.114 * b > 128;
 }
 
 export async function exportToPdf(
@@ -17,7 +27,14 @@ export async function exportToPdf(
     connectionInfo,
     includeImage,
     imageData,
+    theme,
   } = options;
+  // Reports print on white paper, so a dark theme's light text colours
+  // would be unreadable; only light themes contribute their colours
+  const scale = theme?.fontScale ?? 1;
+  const useThemeColors = theme ? isLightColor(theme.background) : false;
+  const textColor = useThemeColors && theme ? theme.text : "#000000";
+  const mutedColor = useThemeColors && theme ? theme.mutedText : "#646464";
 
   const doc = new jsPDF({
     orientation: "portrait",
@@ -38,56 +55,62 @@ export async function exportToPdf(
   };
 
   // Title
-  doc.setFontSize(24);
+  doc.setTextColor(textColor);
+  doc.setFontSize(24 * scale);
   doc.setFont("helvetica", "bold");
   doc.text(title, margin, yPos);
-  yPos += 10;
+  yPos += 10 * scale;
 
   // Connection info
   if (connectionInfo) {
-    doc.setFontSize(10);
+    doc.setFontSize(10 * scale);
     doc.setFont("helvetica", "normal");
-    doc.setTextColor(100);
+    doc.setTextColor(mutedColor);
     doc.text(`Server: ${connectionInfo.server}`, margin, yPos);
-    yPos += 5;
+    yPos += 5 * scale;
     if (connectionInfo.database) {
       doc.text(`Database: ${connectionInfo.database}`, margin, yPos);
-      yPos += 5;
+      yPos += 5 * scale;
     }
     doc.text(`Generated: ${new Date().toLocaleString()}`, margin, yPos);
-    yPos += 10;
+    yPos += 10 * scale;
   }
 
   // Summary section
-  doc.setTextColor(0);
-  doc.setFontSize(14);
+  doc.setTextColor(textColor);
+  doc.setFontSize(14 * scale);
   doc.setFont("helvetica", "bold");
   doc.text("Summary", margin, yPos);
-  yPos += 7;
+  yPos += 7 * scale;
 
-  doc.setFontSize(10);
+  doc.setFontSize(10 * scale);
   doc.setFont("helvetica", "normal");
+  const virtualRelationships = schema.relationships.filter(
+    (rel) => rel.isVirtual
+  ).length;
   const summaryItems = [
     `Tables: ${schema.tables.length}`,
     `Views: ${schema.views.length}`,
-    `Relationships: ${schema.relationships.length}`,
+    `Relationships: ${schema.relationships.length}${
+      virtualRelationships > 0 ? ` (${virtualRelationships} virtual)` : ""
+    }`,
     `Triggers: ${schema.triggers.length}`,
     `Stored Procedures: ${schema.storedProcedures.length}`,
     `Scalar Functions: ${schema.scalarFunctions.length}`,
   ];
   summaryItems.forEach((item) => {
     doc.text(item, margin + 5, yPos);
-    yPos += 5;
+    yPos += 5 * scale;
   });
-  yPos += 5;
+  yPos += 5 * scale;
 
   // Include graph image if provided
   if (includeImage && imageData) {
     checkPageBreak(100);
-    doc.setFontSize(14);
+    doc.setFontSize(14 * scale);
     doc.setFont("helvetica", "bold");
     doc.text("Schema Diagram", margin, yPos);
-    yPos += 7;
+    yPos += 7 * scale;
 
     const blob = new Blob([imageData], { type: "image/png" });
     const imageUrl = URL.createObjectURL(blob);
@@ -105,22 +128,26 @@ export async function exportToPdf(
   // Tables section
   if (schema.tables.length > 0) {
     checkPageBreak(20);
-    doc.setFontSize(14);
+    doc.setFontSize(14 * scale);
     doc.setFont("helvetica", "bold");
     doc.text("Tables", margin, yPos);
-    yPos += 7;
+    yPos += 7 * scale;
 
     schema.tables.forEach((table) => {
-      checkPageBreak(15 + table.columns.length * 4);
+      checkPageBreak((15 + table.columns.length * 4) * scale);
 
-      doc.setFontSize(11);
+      doc.setFontSize(11 * scale);
       doc.setFont("helvetica", "bold");
       doc.text(`${table.schema}.${table.name}`, margin + 5, yPos);
-      yPos += 5;
+      yPos += 5 * scale;
 
-      doc.setFontSize(9);
+      doc.setFontSize(9 * scale);
       doc.setFont("helvetica", "normal");
-      table.columns.forEach((col) => {
+      // Stable sort: columns without an ordinal keep their existing order
+      const columns = [...table.columns].sort(
+        (a, b) => (a.ordinalPosition ?? 0) - (b.ordinalPosition ?? 0)
+      );
+      columns.forEach((col) => {
         const pkIndicator = col.isPrimaryKey ? " (PK)" : "";
         const nullable = col.isNullable ? "NULL" : "NOT NULL";
         doc.text(
@@ -128,70 +155,70 @@ export async function exportToPdf(
           margin + 10,
           yPos
         );
-        yPos += 4;
+        yPos += 4 * scale;
       });
-      yPos += 3;
+      yPos += 3 * scale;
     });
   }
 
   // Views section
   if (schema.views.length > 0) {
     checkPageBreak(20);
-    doc.setFontSize(14);
+    doc.setFontSize(14 * scale);
     doc.setFont("helvetica", "bold");
     doc.text("Views", margin, yPos);
-    yPos += 7;
+    yPos += 7 * scale;
 
     schema.views.forEach((view) => {
       checkPageBreak(10);
-      doc.setFontSize(10);
+      doc.setFontSize(10 * scale);
       doc.setFont("helvetica", "normal");
       doc.text(
         `${view.schema}.${view.name} (${view.columns.length} columns)`,
         margin + 5,
         yPos
       );
-      yPos += 5;
+      yPos += 5 * scale;
     });
-    yPos += 5;
+    yPos += 5 * scale;
   }
 
   // Stored Procedures section
   if (schema.storedProcedures.length > 0) {
     checkPageBreak(20);
-    doc.setFontSize(14);
+    doc.setFontSize(14 * scale);
     doc.setFont("helvetica", "bold");
     doc.text("Stored Procedures", margin, yPos);
-    yPos += 7;
+    yPos += 7 * scale;
 
     schema.storedProcedures.forEach((proc) => {
       checkPageBreak(10);
-      doc.setFontSize(10);
+      doc.setFontSize(10 * scale);
       doc.setFont("helvetica", "normal");
       doc.text(
         `${proc.schema}.${proc.name} (${proc.parameters.length} params)`,
         margin + 5,
         yPos
       );
-      yPos += 5;
+      yPos += 5 * scale;
     });
-    yPos += 5;
+    yPos += 5 * scale;
   }
 
   // Scalar Functions section
   if (schema.scalarFunctions.length > 0) {
     checkPageBreak(20);
-    doc.setFontSize(14);
+    doc.setFontSize(14 * scale);
     doc.setFont("helvetica", "bold");
     doc.text("Scalar Functions", margin, yPos);
-    yPos += 7;
+    yPos += 7 * scale;
 
     schema.scalarFunctions.forEach((fn) => {
       checkPageBreak(10);
-      doc.setFontSize(10);
+      doc.setFontSize(10 * scale);
       doc.setFont("helvetica", "normal");
       doc.text(`${fn.schema}.${fn.name} -> ${fn.returnType}`, margin + 5, yPos);
-      yPos += 5;
+      yPos += 5 * scale;
     });
   }
 
@@ -7,7 +7,10 @@ import {
   StoredProcedure,
   ScalarFunction,
   Column,
+  ObjectMetadata,
   ProcedureParameter,
+  DefinitionAnalysis,
+  WorkingTable,
 } from "../types";
 import { cn } from "@/lib/utils";
 import { SqlCodeBlock } from "./sql-code-block";
@@ -215,6 +218,61 @@ export function ViewDetail({ view }: { view: ViewNode }) {
   );
 }
 
+function DynamicSqlBadges({ analysis }: { analysis?: DefinitionAnalysis }) {
+  if (!analysis?.dynamicSql) return null;
+  return (
+    <div className="flex items-center gap-2 flex-wrap">
+      <span className="bg-sky-100 text-sky-800 dark:bg-sky-900/30 dark:text-sky-400 text-xs px-2 py-1 rounded">
+        Dynamic SQL
+      </span>
+      {analysis.injectionRisk && (
+        <span
+          className="bg-red-100 text-red-700 dark:bg-red-900/30 dark:text-red-400 text-xs px-2 py-1 rounded"
+          title={`Concatenated into dynamic SQL: ${(analysis.concatenatedInputs ?? []).join(", ")}`}
+        >
+          Possible SQL injection
+        </span>
+      )}
+    </div>
+  );
+}
+
+const WORKING_TABLE_KIND_LABELS: Record<WorkingTable["kind"], string> = {
+  tempTable: "Temp table",
+  globalTempTable: "Global temp table",
+  tableVariable: "Table variable",
+};
+
+function WorkingTables({ tables }: { tables?: WorkingTable[] }) {
+  if (!tables || tables.length === 0) return null;
+  return (
+    <div>
+      <h4 className="text-sm font-medium mb-2">Working Tables</h4>
+      <div className="space-y-2">
+        {tables.map((table) => (
+          <div key={table.name} className="border rounded-lg px-3 py-2">
+            <div className="flex items-center gap-2">
+              <span className="font-mono text-sm text-foreground">
+                {table.name}
+              </span>
+              <span className="bg-muted text-muted-foreground text-xs px-2 py-0.5 rounded">
+                {WORKING_TABLE_KIND_LABELS[table.kind]}
+              </span>
+            </div>
+            {table.columns && table.columns.length > 0 && (
+              <div className="mt-1 text-xs font-mono text-muted-foreground">
+                {table.columns
+                  .map((c) => (c.dataType ? `${c.name} ${c.dataType}` : c.name))
+                  .join(", ")}
+              </div>
+            )}
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
+
 export function TriggerDetail({ trigger }: { trigger: Trigger }) {
   const events = [
     trigger.firesOnInsert && "INSERT",
@@ -240,6 +298,24 @@ export function TriggerDetail({ trigger }: { trigger: Trigger }) {
         )}
       </div>
 
+      <DynamicSqlBadges analysis={trigger.analysis} />
+
+      {trigger.watchedColumns && trigger.watchedColumns.length > 0 && (
+        <div>
+          <h4 className="text-sm font-medium mb-2">Watched Columns</h4>
+          <div className="flex items-center gap-1 flex-wrap">
+            {trigger.watchedColumns.map((column) => (
+              <span
+                key={column}
+                className="bg-muted text-muted-foreground font-mono text-xs px-2 py-1 rounded"
+              >
+                {column}
+              </span>
+            ))}
+          </div>
+        </div>
+      )}
+
       <div>
         <h4 className="text-sm font-medium mb-2">Definition</h4>
         <SqlCodeBlock code={trigger.definition} maxHeight="300px" />
@@ -258,6 +334,8 @@ export function StoredProcedureDetail({
 
   return (
     <div className="space-y-4">
+      <DynamicSqlBadges analysis={procedure.analysis} />
+
       {procedure.parameters.length > 0 && (
         <div>
           <h4 className="text-sm font-medium mb-2">Parameters</h4>
@@ -309,6 +387,8 @@ export function StoredProcedureDetail({
         </div>
       )}
 
+      <WorkingTables tables={procedure.analysis?.workingTables} />
+
       <div>
         <h4 className="text-sm font-medium mb-2">Definition</h4>
         <SqlCodeBlock code={procedure.definition} maxHeight="300px" />
@@ -365,7 +445,30 @@ export function ScalarFunctionDetail({ fn }: { fn: ScalarFunction }) {
   );
 }
 
-export function DetailContent({ data }: { data: DetailSidebarData }) {
+const formatMetadataDate = (value: string) =>
+  value.replace("T", " ").slice(0, 19);
+
+export function MetadataDetail({ metadata }: { metadata: ObjectMetadata }) {
+  const rows: [string, string | undefined][] = [
+    ["Owner", metadata.owner],
+    ["Created", metadata.createdAt && formatMetadataDate(metadata.createdAt)],
+    ["Modified", metadata.modifiedAt && formatMetadataDate(metadata.modifiedAt)],
+  ];
+  return (
+    <dl className="grid grid-cols-[auto_1fr] gap-x-4 gap-y-1 text-sm">
+      {rows
+        .filter(([, value]) => value)
+        .map(([label, value]) => (
+          <div key={label} className="contents">
+            <dt className="text-muted-foreground">{label}</dt>
+            <dd className="font-mono text-foreground">{value}</dd>
+          </div>
+        ))}
+    </dl>
+  );
+}
+
+function DetailBody({ data }: { data: DetailSidebarData }) {
   switch (data.type) {
     case "table":
       return <TableDetail table={data.data} />;
@@ -379,3 +482,16 @@ export function DetailContent({ data }: { data: DetailSidebarData }) {
       return <ScalarFunctionDetail fn={data.data} />;
   }
 }
+
+export function DetailContent({ data }: { data: DetailSidebarData }) {
+  const metadata = data.data.metadata;
+  if (!metadata) {
+    return <DetailBody data={data} />;
+  }
+  return (
+    <div className="space-y-4">
+      <MetadataDetail metadata={metadata} />
+      <DetailBody data={data} />
+    </div>
+  );
+}
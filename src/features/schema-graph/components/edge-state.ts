@@ -12,6 +12,7 @@ export interface EdgeMeta {
   label?: string;
   sourceColumn?: string;
   targetColumn?: string;
+  dashed?: boolean; // User-defined (virtual) relationships
 }
 
 const EDGE_STYLE: Record<
@@ -240,6 +241,7 @@ export function deriveEdgeState({
         strokeWidth,
         opacity: isDimmed ? 0.4 : 1,
         cursor: isFocusActive ? "default" : "pointer",
+        strokeDasharray: edge.dashed ? "6 4" : undefined,
       },
       markerEnd: {
         type: MarkerType.ArrowClosed,
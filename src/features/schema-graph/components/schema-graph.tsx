@@ -825,8 +825,11 @@ function buildBaseEdges(
       targetColumn: rel.toColumn,
       label:
         rel.fromColumn && rel.toColumn
-          ? `${rel.fromColumn} → ${rel.toColumn}`
-          : undefined,
+          ? `${rel.fromColumn} → ${rel.toColumn}${rel.isVirtual ? " (virtual)" : ""}`
+          : rel.isVirtual
+            ? "virtual"
+            : undefined,
+      dashed: rel.isVirtual,
     });
   });
 
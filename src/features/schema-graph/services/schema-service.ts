@@ -4,4 +4,7 @@ import type { ConnectionParams } from "../types";
 export const schemaService = {
   loadSchema: (params: ConnectionParams) => tauri.loadSchema(params),
   loadMockSchema: (size: string) => tauri.loadMockSchema(size),
+  loadSchemaBinary: (params: ConnectionParams) =>
+    tauri.loadSchemaBinary(params),
+  refreshSchema: (params: ConnectionParams) => tauri.refreshSchema(params),
 };
@@ -789,6 +789,8 @@ export const useSchemaStore = create<SchemaStore>((set, get) => ({
         username: serverConnection.username,
         password: serverConnection.password,
         trustServerCertificate: serverConnection.trustServerCertificate,
+        multiSubnetFailover: serverConnection.multiSubnetFailover,
+        readOnlyIntent: serverConnection.readOnlyIntent,
       };
 
       const loadedSchema = await schemaService.loadSchema(params);
@@ -845,6 +847,8 @@ export const useSchemaStore = create<SchemaStore>((set, get) => ({
         username: serverConnection.username,
         password: serverConnection.password,
         trustServerCertificate: serverConnection.trustServerCertificate,
+        multiSubnetFailover: serverConnection.multiSubnetFailover,
+        readOnlyIntent: serverConnection.readOnlyIntent,
       };
 
       const loadedSchema = await schemaService.loadSchema(params);
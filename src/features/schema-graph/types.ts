@@ -9,9 +9,12 @@ export interface Column {
   dataType: string;
   isNullable: boolean;
   isPrimaryKey: boolean;
+  ordinalPosition?: number; // sys.columns.column_id (may have gaps)
   sourceColumns?: ColumnSource[]; // Source columns (for view columns)
   sourceTable?: string; // Source table name (legacy view columns)
   sourceColumn?: string; // Source column name (legacy view columns)
+  isIdentity?: boolean; // IDENTITY / AUTO_INCREMENT
+  isComputed?: boolean; // Computed or generated; not writable
 }
 
 // Table node representation
@@ -20,6 +23,23 @@ export interface TableNode {
   name: string; // Table name only
   schema: string; // Schema name (e.g., "dbo")
   columns: Column[];
+  source?: string; // Workspace source ID (multi-source workspaces only)
+  metadata?: ObjectMetadata;
+  changeCapture?: ChangeCapture; // Set when tracked by CDC or replicated
+}
+
+// CDC and replication flags; each constrains schema changes
+export interface ChangeCapture {
+  cdc?: boolean;
+  replicated?: boolean; // Transactional or snapshot publication
+  mergePublished?: boolean;
+}
+
+// Ownership and change dates from sys.objects
+export interface ObjectMetadata {
+  owner?: string;
+  createdAt?: string; // ISO 8601, server local time
+  modifiedAt?: string; // ISO 8601, server local time
 }
 
 // View node representation
@@ -30,6 +50,9 @@ export interface ViewNode {
   columns: Column[];
   definition: string; // SQL definition
   referencedTables: string[]; // List of table/view IDs referenced in the view
+  source?: string; // Workspace source ID (multi-source workspaces only)
+  metadata?: ObjectMetadata;
+  isIndexed?: boolean; // Has a clustered index (materialized view)
 }
 
 // Foreign key relationship
@@ -39,6 +62,48 @@ export interface RelationshipEdge {
   to: string; // Target table ID ("schema.table")
   fromColumn?: string; // FK column in source (optional for column-less edges)
   toColumn?: string; // Referenced column in target (optional for column-less edges)
+  source?: string; // Workspace source ID (multi-source workspaces only)
+  isVirtual?: boolean; // User-defined relationship, not a real foreign key
+  participation?: Participation; // Crow's-foot details, when columns are known
+}
+
+export type Optionality = "mandatory" | "optional";
+
+export interface Participation {
+  parent: Optionality; // Mandatory when every FK column is NOT NULL
+  oneToOne: boolean; // FK columns are exactly the child's primary key
+  identifying: boolean; // FK columns are part of the child's primary key
+}
+
+export type ErFormat = "mermaid" | "dbml" | "plantUml";
+
+// User-drawn edge documenting an integration, usually across workspace sources
+export interface ManualLink {
+  id: string;
+  from: string; // Source-scoped node ID ("source:schema.table")
+  to: string; // Source-scoped node ID ("source:schema.table")
+  fromColumn?: string;
+  toColumn?: string;
+  label?: string;
+}
+
+export type WorkingTableKind = "tempTable" | "globalTempTable" | "tableVariable";
+
+// A #temp table or @table variable used inside a procedure or trigger body
+export interface WorkingTable {
+  name: string;
+  kind: WorkingTableKind;
+  columns?: { name: string; dataType?: string }[]; // Inferred; types only when declared
+}
+
+// What analysis found in a trigger or procedure body; parsed is set only in deep-parse builds
+export interface DefinitionAnalysis {
+  parsed: boolean;
+  referencedColumns?: ColumnSource[];
+  workingTables?: WorkingTable[];
+  dynamicSql: boolean; // Runs EXEC (@sql) or sp_executesql
+  concatenatedInputs?: string[]; // Variables concatenated into dynamic SQL without QUOTENAME
+  injectionRisk: boolean;
 }
 
 // Trigger definition
@@ -55,6 +120,9 @@ export interface Trigger {
   definition: string; // SQL definition
   referencedTables: string[]; // List of table/view IDs referenced in the trigger (reads)
   affectedTables: string[]; // List of table/view IDs modified by the trigger (writes)
+  watchedColumns?: string[]; // Columns tested with UPDATE(col) or COLUMNS_UPDATED()
+  analysis?: DefinitionAnalysis;
+  metadata?: ObjectMetadata;
 }
 
 // Stored procedure parameter
@@ -74,6 +142,8 @@ export interface StoredProcedure {
   definition: string; // SQL definition
   referencedTables: string[]; // List of table/view IDs referenced in the procedure (reads)
   affectedTables: string[]; // List of table/view IDs modified by the procedure (writes)
+  analysis?: DefinitionAnalysis;
+  metadata?: ObjectMetadata;
 }
 
 // Scalar function definition
@@ -87,6 +157,7 @@ export interface ScalarFunction {
   definition: string; // SQL definition
   referencedTables: string[]; // List of table/view IDs referenced in the function (reads)
   affectedTables: string[]; // Usually empty for functions (read-only)
+  metadata?: ObjectMetadata;
 }
 
 // Complete schema graph
@@ -97,19 +168,161 @@ export interface SchemaGraph {
   triggers: Trigger[];
   storedProcedures: StoredProcedure[];
   scalarFunctions: ScalarFunction[];
+  links?: ManualLink[]; // Cross-source links (multi-source workspaces only)
+  agentJobs?: AgentJob[]; // Present when msdb could be read
+  warnings?: GraphWarning[];
+  classifications?: Classification[]; // Glossary terms and tags assigned locally
+  stewardship?: Stewardship[];
+  version?: number; // Position in the connection's patch sequence; 0 if untracked
+}
+
+export interface GraphWarning {
+  kind: string;
+  objectId: string;
+  missing: string;
+  message: string;
+}
+
+export type PatchObject =
+  | { kind: "table"; object: TableNode }
+  | { kind: "view"; object: ViewNode }
+  | { kind: "relationship"; object: RelationshipEdge }
+  | { kind: "trigger"; object: Trigger }
+  | { kind: "storedProcedure"; object: StoredProcedure }
+  | { kind: "scalarFunction"; object: ScalarFunction }
+  | { kind: "agentJob"; object: AgentJob };
+
+// Changes between two graph versions (`schema-patch` event, `refresh_schema_cmd`)
+export interface SchemaPatch {
+  baseVersion: number;
+  version: number;
+  added: PatchObject[];
+  updated: PatchObject[];
+  removed: { kind: PatchObject["kind"]; id: string }[];
+  warnings: GraphWarning[];
+}
+
+// What diff_definitions_cmd ignores; every option defaults to true
+export interface DiffOptions {
+  ignoreWhitespace?: boolean;
+  ignoreBrackets?: boolean; // [Orders] and "Orders" equal Orders
+  ignoreCase?: boolean; // string literals always compare exactly
+}
+
+export type DiffOp = "unchanged" | "added" | "removed";
+
+export interface DefinitionDiffLine {
+  op: DiffOp;
+  oldLine?: number; // 1-based
+  newLine?: number;
+  text: string;
+  tokens?: { op: DiffOp; text: string }[]; // inline diff against the replaced line
+}
+
+export interface DefinitionDiff {
+  identical: boolean;
+  added: number;
+  removed: number;
+  lines: DefinitionDiffLine[];
+}
+
+// Pending migration scripts projected onto the loaded graph (`review_changeset_cmd`)
+export interface ScriptSummary {
+  file: string;
+  statements: number;
+  recognized: number;
+}
+
+export interface ImpactedObject {
+  objectId: string;
+  dependsOn: string[];
+  broken: boolean; // depends on an object the scripts remove
+}
+
+export interface ChangesetReview {
+  scripts: ScriptSummary[];
+  patch: SchemaPatch;
+  projected: SchemaGraph;
+  impacted: ImpactedObject[];
+  unrecognized: { file: string; statement: string }[];
+}
+
+// Persisted full-text index over names, columns and definitions (`search_schema_index_cmd`)
+export type SearchField = "name" | "column" | "definition";
+
+export interface SearchHighlight {
+  field: SearchField;
+  text: string; // definition lines are trimmed to a snippet
+  ranges: [number, number][]; // character offsets into text, end exclusive
+  line?: number; // 1-based definition line
+}
+
+export interface SearchHit {
+  objectId: string;
+  objectType: "table" | "view" | "trigger" | "procedure" | "function";
+  name: string;
+  score: number;
+  highlights: SearchHighlight[];
+}
+
+export interface SchemaPatchEvent {
+  server: string;
+  database: string;
+  patch: SchemaPatch;
+}
+
+// SQL Server Agent job step; references are resolved for T-SQL steps only
+export interface AgentJobStep {
+  stepId: number;
+  name: string;
+  subsystem: string; // e.g., "TSQL", "CmdExec", "SSIS"
+  database?: string;
+  command: string;
+  referencedTables: string[];
+  affectedTables: string[];
+  executedProcedures: string[];
+}
+
+export interface AgentSchedule {
+  name: string;
+  enabled: boolean;
+  description: string; // e.g., "Daily at 02:00"
+}
+
+// SQL Server Agent job touching the loaded database
+export interface AgentJob {
+  id: string; // Format: "job:<name>"
+  name: string;
+  enabled: boolean;
+  description?: string;
+  owner?: string;
+  schedules: AgentSchedule[];
+  steps: AgentJobStep[];
+  referencedTables: string[]; // Union over steps (reads)
+  affectedTables: string[]; // Union over steps (writes)
+  executedProcedures: string[];
+  source?: string; // Workspace source ID (multi-source workspaces only)
 }
 
 // Authentication type
 export type AuthType = "sqlServer" | "windows";
 
+// Database engine (omitted means SQL Server)
+export type DatabaseEngine = "sqlServer" | "mySql";
+
 // Connection parameters
 export interface ConnectionParams {
   server: string;
   database: string;
+  engine?: DatabaseEngine;
   authType: AuthType;
   username?: string;
   password?: string;
   trustServerCertificate?: boolean;
+  /** Try every availability group listener address in parallel */
+  multiSubnetFailover?: boolean;
+  /** Request routing to a readable secondary (ApplicationIntent=ReadOnly) */
+  readOnlyIntent?: boolean;
 }
 
 // Server connection parameters (without database)
@@ -119,4 +332,332 @@ export interface ServerConnectionParams {
   username?: string;
   password?: string;
   trustServerCertificate?: boolean;
+  multiSubnetFailover?: boolean;
+  /** Applied to database connections opened from this server */
+  readOnlyIntent?: boolean;
+}
+
+// One database in the streamed server database list
+export interface DatabaseEntry {
+  name: string;
+  state: string;
+  accessible: boolean;
+  sizeMb: number | null;
+}
+
+export interface DatabaseBatchPayload {
+  operationId: string;
+  taskId: string;
+  databases: DatabaseEntry[];
+}
+
+export interface DatabaseCounts {
+  total: number;
+  inaccessible: number;
+}
+
+export interface AvailabilityReplica {
+  server: string;
+  role: string | null;
+  isLocal: boolean;
+  synchronizationHealth: string | null;
+  availabilityMode: string;
+  secondaryAllowConnections: string;
+}
+
+// Availability group the connected database belongs to
+export interface AvailabilityInfo {
+  group: string;
+  connectedServer: string;
+  localRole: string | null;
+  databaseUpdateable: boolean;
+  replicas: AvailabilityReplica[];
+}
+
+// Row of a Flyway, Liquibase or EF Core history table; Liquibase changesets
+// map ID to version, changelog file to script and author to installedBy, and
+// EF Core migrations their MigrationId to version and script
+export interface AppliedMigration {
+  rank: number;
+  version?: string;
+  description: string;
+  kind: string; // Flyway type, Liquibase EXECTYPE or EF Core ProductVersion
+  script: string;
+  installedBy: string;
+  installedOn: string; // ISO 8601, server local time; empty for EF Core
+  executionMs?: number;
+  success: boolean;
+}
+
+export interface MigrationHistory {
+  tool: "flyway" | "liquibase" | "efCore";
+  table: string;
+  migrations: AppliedMigration[];
+  objects: { objectId: string; rank: number }[]; // migration that created each object
+}
+
+// Table or column operation from an EF Core migration's Up method
+export type EfOperation =
+  | { kind: "createTable"; tableId: string; columns: string[] }
+  | { kind: "dropTable"; tableId: string }
+  | { kind: "renameTable"; tableId: string; newId: string }
+  | { kind: "addColumn"; tableId: string; column: string }
+  | { kind: "dropColumn"; tableId: string; column: string }
+  | { kind: "renameColumn"; tableId: string; column: string; newName: string };
+
+export interface EfMigration {
+  id: string;
+  file: string;
+  applied: boolean;
+  operations: EfOperation[];
+}
+
+export type EfDriftKind =
+  | "pendingMigration"
+  | "missingMigrationFile"
+  | "missingTable"
+  | "missingColumn"
+  | "unmanagedTable"
+  | "unmanagedColumn";
+
+// EF Core Migrations folder compared with __EFMigrationsHistory and the database
+export interface EfMigrationReport {
+  migrations: EfMigration[];
+  objects: { objectId: string; column?: string; migrationId: string }[];
+  drift: { kind: EfDriftKind; objectId: string; column?: string }[];
+}
+
+// Bookmarked object for quick navigation
+export interface Bookmark {
+  objectId: string;
+  label?: string;
+  createdAt: string;
+}
+
+// Business term from the workspace glossary
+export interface GlossaryTerm {
+  name: string;
+  definition: string;
+  synonyms?: string[];
+}
+
+// Node of the tag taxonomy
+export interface Tag {
+  name: string;
+  parent?: string;
+  description?: string;
+}
+
+export interface Glossary {
+  terms: GlossaryTerm[];
+  tags: Tag[];
+}
+
+// Terms and tags assigned to an object, or one of its columns
+export interface Classification {
+  objectId: string;
+  column?: string;
+  terms?: string[];
+  tags?: string[];
+}
+
+// Purview or DataHub instance a connection's schema is pushed to
+export interface CatalogTarget {
+  kind: "purview" | "dataHub";
+  endpoint: string;
+  token?: string; // Sent as a bearer token; only on save, kept in the keychain
+  hasToken?: boolean; // Whether a token is stored
+  environment?: string; // DataHub dataset environment, PROD when unset
+}
+
+export interface CatalogPushResult {
+  datasets: number;
+  requests: number;
+}
+
+export type SummaryDetail = "names" | "columns" | "full";
+
+// Schema text for language model prompts, chunked to a token budget
+export interface SummaryOptions {
+  detail?: SummaryDetail;
+  maxTokens?: number; // Estimated tokens per chunk, 4000 by default
+  objectIds?: string[];
+  includeRoutines?: boolean;
+}
+
+export interface SummaryChunk {
+  text: string;
+  estimatedTokens: number;
+  objectIds: string[];
+}
+
+export interface SchemaSummary {
+  chunks: SummaryChunk[];
+  estimatedTokens: number;
+}
+
+// Tables a free-text question is likely about, matched by name and glossary
+export interface TableMatch {
+  tableId: string;
+  score: number;
+  terms: string[];
+  columns: string[];
+}
+
+export interface JoinStep {
+  from: string;
+  to: string;
+  on: [string, string][]; // [from column, to column]
+}
+
+export interface JoinSuggestion {
+  terms: string[];
+  tables: TableMatch[];
+  joins: JoinStep[];
+  unreachable: string[];
+  sql: string | null;
+}
+
+export type NameCasing =
+  | "unchanged"
+  | "pascal"
+  | "camel"
+  | "snake"
+  | "upperSnake";
+export type NamePlurality = "unchanged" | "singular" | "plural";
+
+// Naming conventions applied to a whole canvas design
+export interface NamingRules {
+  tableCasing?: NameCasing;
+  columnCasing?: NameCasing;
+  tablePlurality?: NamePlurality;
+  tablePrefixes?: string[];
+  columnPrefixes?: string[];
+}
+
+export interface NameChange {
+  kind: "table" | "view" | "column";
+  objectId: string; // The table or view before any rename
+  oldName: string;
+  newName: string;
+}
+
+export interface NameConflict {
+  scope: string; // Schema for tables and views, table ID for columns
+  name: string;
+  oldNames: string[];
+}
+
+export interface NamingPreview {
+  changes: NameChange[];
+  conflicts: NameConflict[];
+}
+
+export type CanvasRefactoring =
+  | { kind: "reorderColumns"; tableId: string; columns: string[] }
+  | {
+      kind: "splitTable";
+      tableId: string;
+      newTable: string; // Name only; created in the same schema
+      columns: string[];
+    }
+  | { kind: "moveColumns"; from: string; to: string; columns: string[] }
+  | { kind: "mergeTables"; into: string; from: string };
+
+export interface RefactorResult {
+  graph: SchemaGraph;
+  script: string; // T-SQL migration that keeps existing data
+  warnings: string[];
+}
+
+export interface Contact {
+  name: string;
+  email?: string;
+  team?: string;
+}
+
+// Owner and steward of a schema, or of a table when objectId is set
+export interface Stewardship {
+  schema: string;
+  objectId?: string;
+  owner?: Contact;
+  steward?: Contact;
+}
+
+// Local description of an object or one of its columns
+export interface Annotation {
+  objectId: string;
+  column?: string;
+  description: string;
+  updatedAt: string;
+}
+
+// Header names of a data dictionary CSV
+export interface AnnotationCsvMapping {
+  object: string; // Qualified name, or bare with `schema`
+  schema?: string;
+  column?: string; // Blank cells annotate the object itself
+  description: string;
+  dryRun?: boolean;
+  extendedProperties?: boolean; // Also return an MS_Description script
+}
+
+export type UnmatchedReason =
+  | "missingObject"
+  | "missingDescription"
+  | "unknownObject"
+  | "ambiguousObject"
+  | "unknownColumn";
+
+export interface UnmatchedRow {
+  line: number;
+  object: string;
+  column?: string;
+  reason: UnmatchedReason;
+}
+
+export interface AnnotationImportReport {
+  rows: number;
+  matched: Annotation[];
+  unmatched: UnmatchedRow[];
+  saved: boolean; // False for a dry run
+  extendedPropertyScript?: string;
+}
+
+// Saved (pinned) query and its parameters
+export interface QueryParameter {
+  name: string;
+  sqlType?: string;
+  defaultValue?: string;
+}
+
+export interface SavedQuery {
+  name: string;
+  sql: string;
+  parameters: QueryParameter[];
+  connection?: { server: string; database: string };
+  savedAt: string;
+}
+
+// First result set of a read-only query
+export interface QueryResult {
+  columns: string[];
+  rows: unknown[][];
+  truncated: boolean;
+}
+
+export type QuerySource =
+  | { kind: "sql"; sql: string }
+  | { kind: "savedQuery"; name: string; values?: Record<string, string> };
+
+export type QueryExportFormat = "csv" | "json" | "xlsx";
+
+// Outcome of a stored procedure test run
+export interface ProcedureRun {
+  resultSets: QueryResult[];
+  outputParameters: Record<string, unknown>;
+  returnValue: unknown;
+  committed: boolean;
+  // False when committed, or when the procedure ended the transaction itself
+  rolledBack: boolean;
 }
@@ -0,0 +1,54 @@
+import { describe, expect, it } from "vitest";
+import { applySchemaPatch } from "./schema-patch";
+import type { SchemaGraph, SchemaPatch, TableNode } from "../types";
+
+const table = (id: string): TableNode => ({
+  id,
+  name: id.split(".")[1],
+  schema: "dbo",
+  columns: [],
+});
+
+const graph: SchemaGraph = {
+  tables: [table("dbo.orders"), table("dbo.customers")],
+  views: [],
+  relationships: [],
+  triggers: [],
+  storedProcedures: [],
+  scalarFunctions: [],
+  version: 3,
+};
+
+const patch: SchemaPatch = {
+  baseVersion: 3,
+  version: 4,
+  added: [{ kind: "table", object: table("dbo.invoices") }],
+  updated: [
+    {
+      kind: "table",
+      object: { ...table("dbo.orders"), changeCapture: { cdc: true } },
+    },
+  ],
+  removed: [{ kind: "table", id: "dbo.customers" }],
+  warnings: [],
+};
+
+describe("applySchemaPatch", () => {
+  it("applies adds, updates and removes on the base version", () => {
+    const next = applySchemaPatch(graph, patch);
+    if (typeof next === "string") throw new Error(next);
+
+    expect(next.version).toBe(4);
+    expect(next.tables.map((t) => t.id)).toEqual([
+      "dbo.orders",
+      "dbo.invoices",
+    ]);
+    expect(next.tables[0].changeCapture?.cdc).toBe(true);
+    expect(graph.tables).toHaveLength(2);
+  });
+
+  it("reports current or stale graphs instead of applying", () => {
+    expect(applySchemaPatch({ ...graph, version: 4 }, patch)).toBe("current");
+    expect(applySchemaPatch({ ...graph, version: 1 }, patch)).toBe("stale");
+  });
+});
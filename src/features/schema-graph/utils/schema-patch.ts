@@ -0,0 +1,69 @@
+import type {
+  PatchObject,
+  SchemaGraph,
+  SchemaPatch,
+} from "@/features/schema-graph/types";
+
+type Collection =
+  | "tables"
+  | "views"
+  | "relationships"
+  | "triggers"
+  | "storedProcedures"
+  | "scalarFunctions"
+  | "agentJobs";
+
+const COLLECTIONS: Record<PatchObject["kind"], Collection> = {
+  table: "tables",
+  view: "views",
+  relationship: "relationships",
+  trigger: "triggers",
+  storedProcedure: "storedProcedures",
+  scalarFunction: "scalarFunctions",
+  agentJob: "agentJobs",
+};
+
+/**
+ * Apply a patch to a graph held in the store, returning a new graph.
+ * Returns `"current"` when the graph already has this version or a later
+ * one, and `"stale"` when it is older than the patch's base version and
+ * has to be reloaded instead.
+ */
+export function applySchemaPatch(
+  graph: SchemaGraph,
+  patch: SchemaPatch
+): SchemaGraph | "current" | "stale" {
+  const version = graph.version ?? 0;
+  if (version >= patch.version) return "current";
+  if (version !== patch.baseVersion) return "stale";
+
+  const next: SchemaGraph = { ...graph };
+  const lists = new Map<Collection, { id: string }[]>();
+  const list = (collection: Collection) => {
+    let items = lists.get(collection);
+    if (!items) {
+      items = [...((graph[collection] ?? []) as { id: string }[])];
+      lists.set(collection, items);
+    }
+    return items;
+  };
+
+  for (const { kind, id } of patch.removed) {
+    const items = list(COLLECTIONS[kind]);
+    const index = items.findIndex((item) => item.id === id);
+    if (index !== -1) items.splice(index, 1);
+  }
+  for (const { kind, object } of [...patch.added, ...patch.updated]) {
+    const items = list(COLLECTIONS[kind]);
+    const index = items.findIndex((item) => item.id === object.id);
+    if (index === -1) items.push(object);
+    else items[index] = object;
+  }
+
+  lists.forEach((items, collection) => {
+    (next as unknown as Record<Collection, unknown>)[collection] = items;
+  });
+  next.warnings = patch.warnings;
+  next.version = patch.version;
+  return next;
+}
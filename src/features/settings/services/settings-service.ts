@@ -12,6 +12,10 @@ export interface AppSettings {
   showMiniMap?: boolean;
   folderSources?: FolderSource[];
   explorerSidebarWidth?: number;
+  restoreSession?: boolean;
+  blockWrites?: boolean;
+  updateChannel?: UpdateChannel;
+  updateCheckIntervalHours?: number;
 }
 
 export interface SettingsUpdate {
@@ -22,6 +26,42 @@ export interface SettingsUpdate {
   showMiniMap?: boolean;
   folderSources?: FolderSource[];
   explorerSidebarWidth?: number;
+  restoreSession?: boolean;
+  blockWrites?: boolean;
+  updateChannel?: UpdateChannel;
+  updateCheckIntervalHours?: number;
+}
+
+// Where settings, caches and snapshots live; changes apply on next launch
+export type DataLocationChoice =
+  | { mode: "default" }
+  | { mode: "portable" } // monocle-data next to the executable
+  | { mode: "custom"; path: string };
+
+export interface DataLocation {
+  path: string;
+  portable: boolean;
+  source: "default" | "launchFlag" | "saved" | "portableFolder";
+  defaultPath: string;
+}
+
+export type UpdateChannel = "stable" | "beta";
+
+export interface UpdateCheckResult {
+  channel: UpdateChannel;
+  checked: boolean;
+  // Skipped because of the check interval, "remind me later" or safe mode
+  deferred: boolean;
+  available: boolean;
+  currentVersion: string;
+  version: string | null;
+  releaseNotes: string | null;
+  releaseDate: string | null;
+}
+
+export interface UpdateProgress {
+  downloaded: number;
+  contentLength: number | null;
 }
 
 export const settingsService = {
@@ -0,0 +1,28 @@
+import { describe, expect, it } from "vitest";
+import { decodeMsgpack } from "./msgpack";
+
+describe("decodeMsgpack", () => {
+  it("decodes maps, arrays and scalars", () => {
+    // {"a": [1, -1, "x", null, true, 1.5, 300]}
+    const bytes = new Uint8Array([
+      0x81, 0xa1, 0x61, 0x97, 0x01, 0xff, 0xa1, 0x78, 0xc0, 0xc3, 0xcb, 0x3f,
+      0xf8, 0, 0, 0, 0, 0, 0, 0xcd, 0x01, 0x2c,
+    ]);
+    expect(decodeMsgpack(bytes)).toEqual({
+      a: [1, -1, "x", null, true, 1.5, 300],
+    });
+  });
+
+  it("decodes longer strings and rejects extension types", () => {
+    const word = "tables".repeat(6);
+    const bytes = new Uint8Array([
+      0xd9,
+      word.length,
+      ...new TextEncoder().encode(word),
+    ]);
+    expect(decodeMsgpack(bytes.buffer)).toBe(word);
+    expect(() => decodeMsgpack(new Uint8Array([0xd4, 0, 0]))).toThrow(
+      "Unsupported MessagePack type 0xd4"
+    );
+  });
+});
@@ -0,0 +1,101 @@
+/**
+ * Decodes MessagePack as written by rmp-serde's named encoding, which the
+ * binary schema commands use. Extension types never appear there and are
+ * rejected.
+ */
+export function decodeMsgpack(data: ArrayBuffer | Uint8Array): unknown {
+  const bytes = data instanceof Uint8Array ? data : new Uint8Array(data);
+  const view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+  const text = new TextDecoder();
+  let offset = 0;
+
+  const advance = <T>(size: number, value: T) => {
+    offset += size;
+    return value;
+  };
+  const u8 = () => advance(1, view.getUint8(offset));
+  const u16 = () => advance(2, view.getUint16(offset));
+  const u32 = () => advance(4, view.getUint32(offset));
+
+  const str = (length: number) =>
+    advance(length, text.decode(bytes.subarray(offset, offset + length)));
+  const bin = (length: number) =>
+    advance(length, bytes.slice(offset, offset + length));
+  const array = (length: number) => {
+    const items: unknown[] = new Array(length);
+    for (let i = 0; i < length; i++) items[i] = read();
+    return items;
+  };
+  const map = (length: number) => {
+    const entries: Record<string, unknown> = {};
+    for (let i = 0; i < length; i++) {
+      const key = String(read());
+      entries[key] = read();
+    }
+    return entries;
+  };
+
+  function read(): unknown {
+    const byte = u8();
+    if (byte <= 0x7f) return byte;
+    if (byte >= 0xe0) return byte - 0x100;
+    if ((byte & 0xf0) === 0x80) return map(byte & 0x0f);
+    if ((byte & 0xf0) === 0x90) return array(byte & 0x0f);
+    if ((byte & 0xe0) === 0xa0) return str(byte & 0x1f);
+
+    switch (byte) {
+      case 0xc0:
+        return null;
+      case 0xc2:
+        return false;
+      case 0xc3:
+        return true;
+      case 0xc4:
+        return bin(u8());
+      case 0xc5:
+        return bin(u16());
+      case 0xc6:
+        return bin(u32());
+      case 0xca:
+        return advance(4, view.getFloat32(offset));
+      case 0xcb:
+        return advance(8, view.getFloat64(offset));
+      case 0xcc:
+        return u8();
+      case 0xcd:
+        return u16();
+      case 0xce:
+        return u32();
+      case 0xcf:
+        return advance(8, Number(view.getBigUint64(offset)));
+      case 0xd0:
+        return advance(1, view.getInt8(offset));
+      case 0xd1:
+        return advance(2, view.getInt16(offset));
+      case 0xd2:
+        return advance(4, view.getInt32(offset));
+      case 0xd3:
+        return advance(8, Number(view.getBigInt64(offset)));
+      case 0xd9:
+        return str(u8());
+      case 0xda:
+        return str(u16());
+      case 0xdb:
+        return str(u32());
+      case 0xdc:
+        return array(u16());
+      case 0xdd:
+        return array(u32());
+      case 0xde:
+        return map(u16());
+      case 0xdf:
+        return map(u32());
+      default:
+        throw new Error(
+          `Unsupported MessagePack type 0x${byte.toString(16)} at ${offset - 1}`
+        );
+    }
+  }
+
+  return read();
+}
@@ -27,6 +27,30 @@ function sortByScore<T extends { score: number }>(items: T[]): T[] {
   return items.sort((a, b) => b.score - a.score);
 }
 
+function classificationKey(objectId: string, column?: string): string {
+  return column ? `${objectId}#${column.toLowerCase()}` : objectId;
+}
+
+// Glossary terms and tags by object (and column), for matching business
+// vocabulary. They rank below name matches.
+function buildClassificationScorer(
+  schema: SchemaGraph,
+  query: string
+): (objectId: string, column?: string) => number {
+  const labels = new Map<string, string[]>();
+  for (const c of schema.classifications || []) {
+    labels.set(classificationKey(c.objectId, c.column), [
+      ...(c.terms || []),
+      ...(c.tags || []),
+    ]);
+  }
+  return (objectId, column) => {
+    const assigned = labels.get(classificationKey(objectId, column)) || [];
+    const best = Math.max(0, ...assigned.map((l) => getMatchScore(l, query)));
+    return Math.min(best, 50);
+  };
+}
+
 export function searchSchema(
   schema: SchemaGraph,
   query: string,
@@ -45,13 +69,20 @@ export function searchSchema(
     };
   }
 
+  const classificationScore = buildClassificationScorer(schema, trimmedQuery);
+
   // Search tables
   const tableResults: (TableSearchResult & { score: number })[] = [];
   for (const table of schema.tables) {
     const nameScore = getMatchScore(table.name, trimmedQuery);
     const schemaScore = getMatchScore(table.schema, trimmedQuery);
     const idScore = getMatchScore(table.id, trimmedQuery);
-    const score = Math.max(nameScore, schemaScore, idScore);
+    const score = Math.max(
+      nameScore,
+      schemaScore,
+      idScore,
+      classificationScore(table.id)
+    );
 
     if (score > 0) {
       tableResults.push({
@@ -72,7 +103,12 @@ export function searchSchema(
     const nameScore = getMatchScore(view.name, trimmedQuery);
     const schemaScore = getMatchScore(view.schema, trimmedQuery);
     const idScore = getMatchScore(view.id, trimmedQuery);
-    const score = Math.max(nameScore, schemaScore, idScore);
+    const score = Math.max(
+      nameScore,
+      schemaScore,
+      idScore,
+      classificationScore(view.id)
+    );
 
     if (score > 0) {
       viewResults.push({
@@ -92,7 +128,10 @@ export function searchSchema(
 
   for (const table of schema.tables) {
     for (const column of table.columns) {
-      const score = getMatchScore(column.name, trimmedQuery);
+      const score = Math.max(
+        getMatchScore(column.name, trimmedQuery),
+        classificationScore(table.id, column.name)
+      );
       if (score > 0) {
         columnResults.push({
           id: `column-${table.id}-${column.name}`,
@@ -112,7 +151,10 @@ export function searchSchema(
 
   for (const view of schema.views || []) {
     for (const column of view.columns) {
-      const score = getMatchScore(column.name, trimmedQuery);
+      const score = Math.max(
+        getMatchScore(column.name, trimmedQuery),
+        classificationScore(view.id, column.name)
+      );
       if (score > 0) {
         columnResults.push({
           id: `column-${view.id}-${column.name}`,
@@ -156,7 +198,11 @@ export function searchSchema(
   for (const procedure of schema.storedProcedures || []) {
     const nameScore = getMatchScore(procedure.name, trimmedQuery);
     const schemaScore = getMatchScore(procedure.schema, trimmedQuery);
-    const score = Math.max(nameScore, schemaScore);
+    const score = Math.max(
+      nameScore,
+      schemaScore,
+      classificationScore(procedure.id)
+    );
 
     if (score > 0) {
       procedureResults.push({
@@ -176,7 +222,11 @@ export function searchSchema(
   for (const fn of schema.scalarFunctions || []) {
     const nameScore = getMatchScore(fn.name, trimmedQuery);
     const schemaScore = getMatchScore(fn.schema, trimmedQuery);
-    const score = Math.max(nameScore, schemaScore);
+    const score = Math.max(
+      nameScore,
+      schemaScore,
+      classificationScore(fn.id)
+    );
 
     if (score > 0) {
       functionResults.push({
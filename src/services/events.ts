@@ -63,6 +63,33 @@ export const searchResultHub =
 export const searchProgressHub =
   createEventHub<SearchProgressPayload>("search-progress");
 
+// Database list streaming hub
+import type { DatabaseBatchPayload } from "@/features/schema-graph/types";
+export const databaseBatchHub =
+  createEventHub<DatabaseBatchPayload>("database-batch");
+
+// Live schema patch hub
+import type { SchemaPatchEvent } from "@/features/schema-graph/types";
+export const schemaPatchHub = createEventHub<SchemaPatchEvent>("schema-patch");
+
+// Profile connect progress hub
+import type { ConnectProgress } from "@/features/connection/types";
+export const connectProgressHub =
+  createEventHub<ConnectProgress>("connect-progress");
+
+// Settings reloaded after settings.json was edited outside the app
+import type { AppSettings } from "@/features/settings/services/settings-service";
+export const settingsChangedHub =
+  createEventHub<AppSettings>("settings:changed");
+
+// Update download progress from install_update_cmd
+import type { UpdateProgress } from "@/features/settings/services/settings-service";
+export const updateProgressHub =
+  createEventHub<UpdateProgress>("update-progress");
+
+// Forwarded launch hub; drain with tauriService.takeLaunchRequests()
+export const launchRequestedHub = createEventHub<void>("launch-requested");
+
 // Menu event hubs for cross-component communication
 export const menuToggleSidebarHub = createEventHub<void>("menu:toggle-sidebar");
 export const menuFitViewHub = createEventHub<void>("menu:fit-view");
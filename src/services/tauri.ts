@@ -1,12 +1,51 @@
 import { invoke } from "@tauri-apps/api/core";
+import { decodeMsgpack } from "@/lib/msgpack";
 import type {
+  Annotation,
+  AnnotationCsvMapping,
+  AnnotationImportReport,
+  AvailabilityInfo,
+  Bookmark,
+  CanvasRefactoring,
+  CatalogPushResult,
+  CatalogTarget,
+  ChangesetReview,
+  Classification,
   ConnectionParams,
+  DatabaseCounts,
+  DatabaseEngine,
+  DefinitionDiff,
+  DiffOptions,
+  EfMigrationReport,
+  Glossary,
+  GlossaryTerm,
+  JoinSuggestion,
+  MigrationHistory,
+  NameChange,
+  NamingPreview,
+  NamingRules,
+  ProcedureRun,
+  QueryExportFormat,
+  QueryResult,
+  QuerySource,
+  RefactorResult,
+  SavedQuery,
   ServerConnectionParams,
   SchemaGraph,
+  SchemaPatch,
+  SchemaSummary,
+  SearchHit,
+  Stewardship,
+  StoredProcedure,
+  SummaryOptions,
+  Tag,
 } from "@/features/schema-graph/types";
 import type {
   AppSettings,
+  DataLocation,
+  DataLocationChoice,
   SettingsUpdate,
+  UpdateCheckResult,
 } from "@/features/settings/services/settings-service";
 import type {
   DirEntry,
@@ -14,6 +53,28 @@ import type {
   ScanSummary,
   SearchSummary,
 } from "@/features/explorer/types";
+import type {
+  ConnectResult,
+  LaunchRequest,
+  OnboardingState,
+  OnboardingStatus,
+  OnboardingStep,
+  RestoredConnection,
+  SampleWorkspace,
+  SandboxDatabase,
+  SessionState,
+  QueryLimits,
+  SqlContainer,
+  StartupAction,
+} from "@/features/connection/types";
+import type {
+  DiagramBundle,
+  DiagramLayout,
+  ExportAnnotations,
+  ExportTheme,
+  PrintOptions,
+  PrintPlan,
+} from "@/features/export/types";
 
 // Centralized error handling wrapper
 async function invokeCommand<T>(
@@ -36,15 +97,408 @@ export const tauri = {
     invokeCommand<SchemaGraph>("load_schema_cmd", { params }),
   loadMockSchema: (size: string) =>
     invokeCommand<SchemaGraph>("load_schema_mock", { size }),
+  // MessagePack responses skip JSON parsing for very large schemas
+  loadSchemaBinary: async (params: ConnectionParams) =>
+    decodeMsgpack(
+      await invokeCommand<ArrayBuffer>("load_schema_binary_cmd", { params })
+    ) as SchemaGraph,
+  loadMockSchemaBinary: async (size: string) =>
+    decodeMsgpack(
+      await invokeCommand<ArrayBuffer>("load_schema_mock_binary", { size })
+    ) as SchemaGraph,
+  refreshSchema: (params: ConnectionParams) =>
+    invokeCommand<SchemaPatch | null>("refresh_schema_cmd", { params }),
+  searchSchemaIndex: (
+    params: ConnectionParams,
+    query: string,
+    limit?: number
+  ) =>
+    invokeCommand<SearchHit[]>("search_schema_index_cmd", {
+      params,
+      query,
+      limit,
+    }),
 
   // Database commands
-  listDatabases: (params: ServerConnectionParams) =>
-    invokeCommand<string[]>("list_databases_cmd", { params }),
+  listDatabases: (params: ServerConnectionParams, filter?: string) =>
+    invokeCommand<string[]>("list_databases_cmd", { params, filter }),
+  streamDatabases: (
+    params: ServerConnectionParams,
+    operationId: string,
+    filter?: string
+  ) =>
+    invokeCommand<DatabaseCounts>("stream_databases_cmd", {
+      params,
+      operationId,
+      filter,
+    }),
+  getAvailabilityInfo: (params: ConnectionParams) =>
+    invokeCommand<AvailabilityInfo | null>("get_availability_info_cmd", {
+      params,
+    }),
+  getMigrationHistory: (params: ConnectionParams) =>
+    invokeCommand<MigrationHistory | null>("get_migration_history_cmd", {
+      params,
+    }),
+  correlateEfMigrations: (
+    params: ConnectionParams,
+    graph: SchemaGraph,
+    folder: string
+  ) =>
+    invokeCommand<EfMigrationReport>("correlate_ef_migrations_cmd", {
+      params,
+      graph,
+      folder,
+    }),
+  detectLocalSqlContainers: () =>
+    invokeCommand<SqlContainer[]>("detect_local_sql_containers_cmd"),
+  createSandboxDatabase: (params: ServerConnectionParams, graph: SchemaGraph) =>
+    invokeCommand<SandboxDatabase>("create_sandbox_database_cmd", {
+      params,
+      graph,
+    }),
+  dropSandboxDatabase: (database: string) =>
+    invokeCommand<void>("drop_sandbox_database_cmd", { database }),
+  listSandboxDatabases: () =>
+    invokeCommand<string[]>("list_sandbox_databases_cmd"),
+  previewNaming: (graph: SchemaGraph, rules: NamingRules) =>
+    invokeCommand<NamingPreview>("preview_naming_cmd", { graph, rules }),
+  applyNaming: (graph: SchemaGraph, changes: NameChange[]) =>
+    invokeCommand<SchemaGraph>("apply_naming_cmd", { graph, changes }),
+  refactorDesign: (graph: SchemaGraph, refactoring: CanvasRefactoring) =>
+    invokeCommand<RefactorResult>("refactor_design_cmd", {
+      graph,
+      refactoring,
+    }),
+
+  // Saved query commands
+  listSavedQueries: (server?: string, database?: string) =>
+    invokeCommand<SavedQuery[]>("list_saved_queries_cmd", { server, database }),
+  saveQuery: (query: SavedQuery) =>
+    invokeCommand<SavedQuery[]>("save_query_cmd", { query }),
+  deleteSavedQuery: (name: string) =>
+    invokeCommand<SavedQuery[]>("delete_saved_query_cmd", { name }),
+  runSavedQuery: (
+    params: ConnectionParams,
+    name: string,
+    values?: Record<string, string>
+  ) =>
+    invokeCommand<QueryResult>("run_saved_query_cmd", { params, name, values }),
+  runQuery: (params: ConnectionParams, sql: string) =>
+    invokeCommand<QueryResult>("run_query_cmd", { params, sql }),
+  exportQueryResult: (
+    params: ConnectionParams,
+    source: QuerySource,
+    format: QueryExportFormat,
+    path: string
+  ) =>
+    invokeCommand<{ rows: number; truncated: boolean }>(
+      "export_query_result_cmd",
+      { params, source, format, path }
+    ),
+
+  // Procedure commands
+  executeProcedure: (
+    params: ConnectionParams,
+    procedure: StoredProcedure,
+    values?: Record<string, string | null>,
+    commit?: boolean
+  ) =>
+    invokeCommand<ProcedureRun>("execute_procedure_cmd", {
+      params,
+      procedure,
+      values,
+      commit,
+    }),
+
+  // Bookmark commands
+  listBookmarks: (server: string, database: string) =>
+    invokeCommand<Bookmark[]>("list_bookmarks_cmd", { server, database }),
+  setBookmark: (
+    server: string,
+    database: string,
+    objectId: string,
+    label?: string
+  ) =>
+    invokeCommand<Bookmark[]>("set_bookmark_cmd", {
+      server,
+      database,
+      objectId,
+      label,
+    }),
+  removeBookmark: (server: string, database: string, objectId: string) =>
+    invokeCommand<Bookmark[]>("remove_bookmark_cmd", {
+      server,
+      database,
+      objectId,
+    }),
+
+  // Annotation commands
+  listAnnotations: (server: string, database: string) =>
+    invokeCommand<Annotation[]>("list_annotations_cmd", { server, database }),
+  setAnnotation: (
+    server: string,
+    database: string,
+    objectId: string,
+    description: string,
+    column?: string
+  ) =>
+    invokeCommand<Annotation[]>("set_annotation_cmd", {
+      server,
+      database,
+      objectId,
+      column,
+      description,
+    }),
+  importAnnotationsCsv: (
+    server: string,
+    database: string,
+    graph: SchemaGraph,
+    path: string,
+    mapping: AnnotationCsvMapping
+  ) =>
+    invokeCommand<AnnotationImportReport>("import_annotations_csv_cmd", {
+      server,
+      database,
+      graph,
+      path,
+      mapping,
+    }),
+
+  // Glossary commands
+  getGlossary: () => invokeCommand<Glossary>("get_glossary_cmd"),
+  saveGlossaryTerm: (term: GlossaryTerm) =>
+    invokeCommand<Glossary>("save_glossary_term_cmd", { term }),
+  removeGlossaryTerm: (name: string) =>
+    invokeCommand<Glossary>("remove_glossary_term_cmd", { name }),
+  saveTag: (tag: Tag) => invokeCommand<Glossary>("save_tag_cmd", { tag }),
+  removeTag: (name: string) =>
+    invokeCommand<Glossary>("remove_tag_cmd", { name }),
+  listClassifications: (server: string, database: string) =>
+    invokeCommand<Classification[]>("list_classifications_cmd", {
+      server,
+      database,
+    }),
+  classifyObject: (
+    server: string,
+    database: string,
+    objectId: string,
+    terms: string[],
+    tags: string[],
+    column?: string
+  ) =>
+    invokeCommand<Classification[]>("classify_object_cmd", {
+      server,
+      database,
+      objectId,
+      column,
+      terms,
+      tags,
+    }),
+
+  // Stewardship commands
+  listStewardship: (server: string, database: string) =>
+    invokeCommand<Stewardship[]>("list_stewardship_cmd", { server, database }),
+  setStewardship: (server: string, database: string, assignment: Stewardship) =>
+    invokeCommand<Stewardship[]>("set_stewardship_cmd", {
+      server,
+      database,
+      assignment,
+    }),
+  exportDataDictionary: (server: string, database: string, graph: SchemaGraph) =>
+    invokeCommand<string>("export_data_dictionary_cmd", {
+      server,
+      database,
+      graph,
+    }),
+  // Newline-delimited OpenLineage events for data catalogs
+  exportOpenLineage: (server: string, database: string, graph: SchemaGraph) =>
+    invokeCommand<string>("export_openlineage_cmd", {
+      server,
+      database,
+      graph,
+    }),
+  summarizeSchema: (
+    server: string,
+    database: string,
+    graph: SchemaGraph,
+    options?: SummaryOptions
+  ) =>
+    invokeCommand<SchemaSummary>("summarize_schema_cmd", {
+      server,
+      database,
+      graph,
+      options,
+    }),
+  suggestJoinPath: (graph: SchemaGraph, question: string) =>
+    invokeCommand<JoinSuggestion>("suggest_join_path_cmd", { graph, question }),
+  getCatalogTarget: (server: string, database: string) =>
+    invokeCommand<CatalogTarget | null>("get_catalog_target_cmd", {
+      server,
+      database,
+    }),
+  setCatalogTarget: (
+    server: string,
+    database: string,
+    target: CatalogTarget | null
+  ) =>
+    invokeCommand<CatalogTarget | null>("set_catalog_target_cmd", {
+      server,
+      database,
+      target,
+    }),
+  pushCatalog: (
+    server: string,
+    database: string,
+    graph: SchemaGraph,
+    engine?: DatabaseEngine
+  ) =>
+    invokeCommand<CatalogPushResult>("push_catalog_cmd", {
+      server,
+      database,
+      engine,
+      graph,
+    }),
+  diffDefinitions: (textA: string, textB: string, options?: DiffOptions) =>
+    invokeCommand<DefinitionDiff>("diff_definitions_cmd", {
+      textA,
+      textB,
+      options,
+    }),
+  reviewChangeset: (graph: SchemaGraph, folder: string) =>
+    invokeCommand<ChangesetReview>("review_changeset_cmd", { graph, folder }),
+
+  // SQL login passwords kept in the system keychain
+  storeCredential: (
+    server: string,
+    database: string,
+    username: string,
+    password: string
+  ) =>
+    invokeCommand<void>("store_credential_cmd", {
+      server,
+      database,
+      username,
+      password,
+    }),
+  getCredential: (server: string, database: string, username: string) =>
+    invokeCommand<string | null>("get_credential_cmd", {
+      server,
+      database,
+      username,
+    }),
+  deleteCredential: (server: string, database: string, username: string) =>
+    invokeCommand<void>("delete_credential_cmd", {
+      server,
+      database,
+      username,
+    }),
+  hasCredential: (server: string, database: string, username: string) =>
+    invokeCommand<boolean>("has_credential_cmd", {
+      server,
+      database,
+      username,
+    }),
 
   // Settings commands
   getSettings: () => invokeCommand<AppSettings>("get_settings"),
   saveSettings: (settings: SettingsUpdate) =>
     invokeCommand<AppSettings>("save_settings", { settings }),
+  getDataLocation: () => invokeCommand<DataLocation>("get_data_location_cmd"),
+  setDataLocation: (choice: DataLocationChoice) =>
+    invokeCommand<string>("set_data_location_cmd", { choice }),
+  getExportTheme: () => invokeCommand<ExportTheme>("get_export_theme_cmd"),
+
+  // Update commands
+  checkForUpdates: (force: boolean) =>
+    invokeCommand<UpdateCheckResult>("check_for_updates_cmd", { force }),
+  deferUpdate: (hours?: number, skipVersion?: string) =>
+    invokeCommand<AppSettings>("defer_update_cmd", { hours, skipVersion }),
+  installUpdate: () => invokeCommand<void>("install_update_cmd"),
+
+  // Diagram bundle commands
+  exportDiagramBundle: (
+    path: string,
+    title: string,
+    graph: SchemaGraph,
+    layout?: DiagramLayout,
+    bookmarks?: Bookmark[],
+    annotations?: Annotation[]
+  ) =>
+    invokeCommand<void>("export_diagram_bundle_cmd", {
+      path,
+      title,
+      graph,
+      layout,
+      bookmarks,
+      annotations,
+    }),
+  importDiagramBundle: (path: string) =>
+    invokeCommand<DiagramBundle>("import_diagram_bundle_cmd", { path }),
+
+  // Profile commands
+  connectProfile: (params: ConnectionParams) =>
+    invokeCommand<ConnectResult>("connect_profile_cmd", { params }),
+  getStartupAction: (server: string, database: string) =>
+    invokeCommand<StartupAction>("get_startup_action_cmd", {
+      server,
+      database,
+    }),
+  setStartupAction: (
+    server: string,
+    database: string,
+    action: StartupAction
+  ) =>
+    invokeCommand<void>("set_startup_action_cmd", {
+      server,
+      database,
+      action,
+    }),
+  getQueryLimits: (server: string, database: string) =>
+    invokeCommand<QueryLimits>("get_query_limits_cmd", { server, database }),
+  setQueryLimits: (server: string, database: string, limits: QueryLimits) =>
+    invokeCommand<void>("set_query_limits_cmd", { server, database, limits }),
+
+  // Onboarding commands
+  getOnboarding: () => invokeCommand<OnboardingStatus>("get_onboarding_cmd"),
+  advanceOnboarding: (from: OnboardingStep) =>
+    invokeCommand<OnboardingState>("advance_onboarding_cmd", { from }),
+  skipOnboarding: () => invokeCommand<OnboardingState>("skip_onboarding_cmd"),
+  createSampleWorkspace: () =>
+    invokeCommand<SampleWorkspace>("create_sample_workspace_cmd"),
+
+  // Window commands
+  openNewWindow: (params?: ConnectionParams) =>
+    invokeCommand<string>("open_new_window_cmd", { params }),
+  getWindowContext: () =>
+    invokeCommand<ConnectionParams | null>("get_window_context_cmd"),
+  takeLaunchRequests: () =>
+    invokeCommand<LaunchRequest[]>("take_launch_requests_cmd"),
+
+  // Print commands
+  printDiagram: (
+    title: string,
+    graph: SchemaGraph,
+    layout: DiagramLayout,
+    options: PrintOptions,
+    annotations?: ExportAnnotations
+  ) =>
+    invokeCommand<PrintPlan>("print_diagram_cmd", {
+      title,
+      graph,
+      layout,
+      options,
+      annotations,
+    }),
+
+  // Session commands
+  setReadOnlySession: () => invokeCommand<boolean>("set_read_only_session_cmd"),
+  isReadOnlySession: () => invokeCommand<boolean>("is_read_only_session_cmd"),
+  isSafeMode: () => invokeCommand<boolean>("is_safe_mode_cmd"),
+  updateSession: (session: SessionState) =>
+    invokeCommand<void>("update_session_cmd", { session }),
+  restoreSession: () =>
+    invokeCommand<RestoredConnection[]>("restore_session_cmd"),
 
   // Menu commands
   setMenuUiState: (state: {